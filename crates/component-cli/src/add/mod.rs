@@ -0,0 +1,158 @@
+#![allow(clippy::print_stdout, clippy::print_stderr)]
+
+use component_manifest::{Dependency, ManifestDocument, PackageType};
+use component_package_manager::Reference;
+use component_package_manager::manager::install::{re_vendor_wit_files, resolve_install_inputs};
+use component_package_manager::manager::{Manager, SyncPolicy};
+use miette::{IntoDiagnostic, WrapErr};
+
+use crate::install::{InstallError, dependency_name, process_top_level_result};
+use crate::util::{into_miette, read_lock_file, write_lock_file};
+
+/// Options for the `add` command.
+#[derive(clap::Parser)]
+pub(crate) struct Opts {
+    /// The package to add. Accepts OCI references
+    /// (e.g., ghcr.io/webassembly/wasi-logging:1.0.0) or WIT-style names
+    /// (e.g., wasi:logging@1.0.0).
+    #[arg(value_name = "REFERENCE")]
+    reference: String,
+}
+
+impl Opts {
+    pub(crate) async fn run(self, offline: bool) -> miette::Result<()> {
+        let manifest_path = std::path::PathBuf::from("wasm.toml");
+        let lockfile_path = std::path::PathBuf::from("wasm.lock.toml");
+        let wasm_vendor_dir = std::path::PathBuf::from("vendor/wasm");
+        let wit_vendor_dir = std::path::PathBuf::from("vendor/wit");
+
+        if !manifest_path.exists() {
+            return Err(InstallError::NoManifest.into());
+        }
+        let source = tokio::fs::read_to_string(&manifest_path)
+            .await
+            .into_diagnostic()
+            .wrap_err_with(|| format!("could not read '{}'", manifest_path.display()))?;
+        // The parsed manifest answers questions; the document is what gets
+        // edited and written back, comments and all.
+        let mut manifest: component_manifest::Manifest =
+            toml::from_str(&source).into_diagnostic()?;
+        let mut document: ManifestDocument = source.parse()?;
+        let mut lockfile = read_lock_file(&lockfile_path).await.map_err(into_miette)?;
+
+        let manager = if offline {
+            Manager::open_offline().await.map_err(into_miette)?
+        } else {
+            Manager::open().await.map_err(into_miette)?
+        };
+
+        // WIT-style names are looked up in the package index, so bring it
+        // up to date first.
+        if !offline
+            && let Err(e) = manager
+                .sync_from_meta_registry(
+                    Manager::DEFAULT_REGISTRY_URL,
+                    Manager::DEFAULT_SYNC_INTERVAL,
+                    SyncPolicy::IfStale,
+                )
+                .await
+        {
+            tracing::warn!("{e}");
+        }
+
+        let (reference, _, explicit_name) =
+            resolve_install_inputs(std::slice::from_ref(&self.reference), &manifest, &manager)?
+                .into_iter()
+                .next()
+                .ok_or_else(|| miette::miette!("could not resolve '{}'", self.reference))?;
+
+        let result = manager
+            .install(reference.clone(), &wasm_vendor_dir)
+            .await
+            .map_err(into_miette)?;
+        re_vendor_wit_files(&result, &wit_vendor_dir)
+            .await
+            .map_err(into_miette)?;
+
+        let name = dependency_name(&result, explicit_name.clone(), &manifest);
+        let version = result.tag.clone().unwrap_or_default();
+        let kind = if result.is_component {
+            PackageType::Component
+        } else {
+            PackageType::Interface
+        };
+        let dependency = manifest_dependency(&reference, &version, explicit_name.is_some());
+        document.insert_dependency(&kind, &name, &dependency)?;
+
+        // The manifest is already edited; this only records the lock entry.
+        process_top_level_result(
+            result,
+            false,
+            Some(name.clone()),
+            &mut manifest,
+            &mut lockfile,
+        );
+        lockfile.resolve_dependency_details();
+
+        tokio::fs::write(&manifest_path, document.to_string())
+            .await
+            .into_diagnostic()
+            .wrap_err_with(|| format!("could not write '{}'", manifest_path.display()))?;
+        write_lock_file(&lockfile_path, &lockfile)
+            .await
+            .into_diagnostic()?;
+
+        let section = match kind {
+            PackageType::Component => "components",
+            PackageType::Interface => "interfaces",
+        };
+        println!(
+            "{} Added {name} {version} to [dependencies.{section}]",
+            console::style("✓").green().bold(),
+        );
+        Ok(())
+    }
+}
+
+/// The manifest entry for a package added as `reference` at `version`.
+///
+/// Packages added by WIT-style name are found through the package index
+/// again, so their version alone is enough. Packages added by OCI reference
+/// aren't necessarily in the index, so their entry spells out where they
+/// live.
+fn manifest_dependency(reference: &Reference, version: &str, by_wit_name: bool) -> Dependency {
+    match reference.repository().rsplit_once('/') {
+        Some((namespace, package)) if !by_wit_name => Dependency::Explicit {
+            registry: reference.registry().to_string(),
+            namespace: namespace.to_string(),
+            package: package.to_string(),
+            version: version.to_string(),
+            permissions: None,
+        },
+        _ => Dependency::Compact(version.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn oci_references_are_spelled_out() {
+        let reference: Reference = "ghcr.io/webassembly/wasi/logging:1.0.0".parse().unwrap();
+        assert_eq!(
+            manifest_dependency(&reference, "1.0.0", false),
+            Dependency::Explicit {
+                registry: "ghcr.io".to_string(),
+                namespace: "webassembly/wasi".to_string(),
+                package: "logging".to_string(),
+                version: "1.0.0".to_string(),
+                permissions: None,
+            }
+        );
+        assert_eq!(
+            manifest_dependency(&reference, "1.0.0", true),
+            Dependency::Compact("1.0.0".to_string())
+        );
+    }
+}
@@ -0,0 +1,66 @@
+//! Error types for the `component audit` CLI command.
+
+use miette::Diagnostic;
+
+use super::Severity;
+
+/// CLI-specific error type for `component audit` command failures.
+///
+/// Each variant carries a stable [diagnostic error code][miette::Diagnostic::code]
+/// that uniquely identifies the failure.
+#[derive(Debug, Clone, PartialEq, Eq, Diagnostic)]
+#[must_use]
+pub(crate) enum AuditError {
+    /// The audit found problems at or above the `--fail-on` severity.
+    #[diagnostic(
+        code(component::audit::failed),
+        help("address the findings above, or pass a higher `--fail-on` to accept them")
+    )]
+    Failed {
+        /// How many findings were at or above `severity`.
+        count: usize,
+        /// The `--fail-on` severity.
+        severity: Severity,
+    },
+}
+
+impl std::fmt::Display for AuditError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AuditError::Failed { count: 1, severity } => {
+                write!(f, "audit found 1 problem of {severity} severity or worse")
+            }
+            AuditError::Failed { count, severity } => {
+                write!(
+                    f,
+                    "audit found {count} problems of {severity} severity or worse"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for AuditError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_all_variants_have_error_codes() {
+        use miette::Diagnostic;
+
+        let failed = AuditError::Failed {
+            count: 2,
+            severity: Severity::High,
+        };
+        assert_eq!(
+            failed
+                .code()
+                .expect("Failed must have a diagnostic code")
+                .to_string(),
+            "component::audit::failed",
+        );
+        assert!(failed.help().is_some(), "Failed must have a help message");
+    }
+}
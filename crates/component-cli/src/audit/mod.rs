@@ -0,0 +1,364 @@
+#![allow(clippy::print_stdout)]
+
+mod errors;
+
+use component_manifest::LockedPackage;
+use component_package_manager::manager::Manager;
+use component_package_manager::oci::{ImageEntry, SortBy, SortDirection};
+use component_package_manager::policy::Denylist;
+
+use crate::tree::package_label;
+use crate::util::{into_miette, read_lock_file};
+use errors::AuditError;
+
+/// Options for the `audit` command.
+#[derive(clap::Parser)]
+pub(crate) struct Opts {
+    /// Also audit every package in the local store, not only those in
+    /// `wasm.lock.toml`
+    #[arg(long)]
+    store: bool,
+    /// Also check digests against this denylist, on top of the `denylist`
+    /// feeds in policy.toml: a URL, or a file with one digest per line
+    #[arg(long, value_name = "URL|PATH")]
+    denylist: Vec<String>,
+    /// Exit non-zero if anything at least this severe is found
+    #[arg(long, value_enum, value_name = "SEVERITY", default_value_t = Severity::High)]
+    fail_on: Severity,
+}
+
+/// How much a finding matters, from least to most severe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, clap::ValueEnum)]
+pub(crate) enum Severity {
+    /// Worth knowing, such as a package pulled by a mutable tag
+    Low,
+    /// A gap in the supply chain, such as a missing signature
+    Medium,
+    /// Something that can't be trusted, such as a failed verification
+    High,
+    /// A digest that is known to be bad
+    Critical,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.pad(match self {
+            Severity::Low => "low",
+            Severity::Medium => "medium",
+            Severity::High => "high",
+            Severity::Critical => "critical",
+        })
+    }
+}
+
+/// A problem found with one package.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Finding {
+    severity: Severity,
+    package: String,
+    message: String,
+}
+
+/// A package to audit, from the lockfile or the local store.
+#[derive(Debug, Clone)]
+struct Target {
+    /// How the package is shown in findings.
+    label: String,
+    /// The reference to verify signatures against.
+    reference: String,
+    /// The manifest digest, when it is known without asking the registry.
+    digest: Option<String>,
+    /// The tag the package was locked or pulled by.
+    tag: Option<String>,
+    /// Whether the package comes from `wasm.lock.toml`.
+    locked: bool,
+}
+
+impl Target {
+    fn locked(package: &LockedPackage) -> Self {
+        let digest = Some(package.digest.clone()).filter(|d| !d.is_empty());
+        let reference = match &digest {
+            Some(digest) => format!("{}@{digest}", package.registry),
+            None => format!("{}:{}", package.registry, package.version),
+        };
+        Self {
+            label: package_label(package),
+            reference,
+            digest,
+            tag: Some(package.version.clone()),
+            locked: true,
+        }
+    }
+
+    fn stored(entry: &ImageEntry) -> Self {
+        Self {
+            label: entry.reference(),
+            reference: entry.reference(),
+            digest: entry.ref_digest.clone(),
+            tag: entry.ref_tag.clone(),
+            locked: false,
+        }
+    }
+}
+
+impl Opts {
+    pub(crate) async fn run(self, offline: bool) -> miette::Result<()> {
+        let manager = if offline {
+            Manager::open_offline().await.map_err(into_miette)?
+        } else {
+            Manager::open().await.map_err(into_miette)?
+        };
+
+        let lockfile = read_lock_file("wasm.lock.toml")
+            .await
+            .map_err(into_miette)?;
+        let mut targets: Vec<Target> = lockfile
+            .all_packages()
+            .map(|(package, _)| Target::locked(package))
+            .collect();
+        if self.store {
+            let images = manager
+                .list_all(SortBy::Name, SortDirection::Ascending)
+                .map_err(into_miette)?;
+            targets.extend(images.iter().map(Target::stored));
+        }
+        if targets.is_empty() {
+            println!("Nothing to audit: no `wasm.lock.toml`, and `--store` wasn't passed");
+            return Ok(());
+        }
+
+        let mut feeds = manager.policy().denylist.clone();
+        feeds.extend(self.denylist);
+        let denylist = manager.load_denylist(&feeds).await.map_err(into_miette)?;
+
+        let mut findings = Vec::new();
+        for target in &targets {
+            findings.extend(local_findings(target, &denylist));
+            if !offline {
+                findings.extend(registry_findings(&manager, target, &denylist).await);
+            }
+        }
+        // Most severe first; the sort is stable, so packages keep their order.
+        findings.sort_by_key(|finding| std::cmp::Reverse(finding.severity));
+
+        for finding in &findings {
+            let label = console::style(format!("{:>8}", finding.severity)).bold();
+            let label = match finding.severity {
+                Severity::Critical | Severity::High => label.red(),
+                Severity::Medium => label.yellow(),
+                Severity::Low => label.dim(),
+            };
+            println!("{label} {}: {}", finding.package, finding.message);
+        }
+        if !findings.is_empty() {
+            println!();
+        }
+        if offline {
+            println!("Signatures weren't checked while offline");
+        }
+        for feed in denylist.skipped_feeds() {
+            println!("Skipped denylist {feed} while offline");
+        }
+        println!("{}", summary(targets.len(), &findings));
+
+        let failing = findings
+            .iter()
+            .filter(|finding| finding.severity >= self.fail_on)
+            .count();
+        if failing > 0 {
+            return Err(AuditError::Failed {
+                count: failing,
+                severity: self.fail_on,
+            }
+            .into());
+        }
+        Ok(())
+    }
+}
+
+/// Findings that need no network: denied digests and unpinned tags.
+fn local_findings(target: &Target, denylist: &Denylist) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let finding = |severity, message: String| Finding {
+        severity,
+        package: target.label.clone(),
+        message,
+    };
+    if let Some(digest) = &target.digest {
+        findings
+            .extend(denied(digest, denylist).map(|message| finding(Severity::Critical, message)));
+    }
+    match (&target.digest, &target.tag) {
+        (None, Some(tag)) if target.locked => findings.push(finding(
+            Severity::High,
+            format!("locked by the tag '{tag}' without a digest, so the registry can change it"),
+        )),
+        (None, Some(tag)) if !is_version_tag(tag) => findings.push(finding(
+            Severity::Low,
+            format!("pulled by the mutable tag '{tag}'"),
+        )),
+        _ => {}
+    }
+    findings
+}
+
+/// Findings from the registry: missing or failed signatures, and denied
+/// digests that were only known once the registry resolved the tag.
+async fn registry_findings(
+    manager: &Manager,
+    target: &Target,
+    denylist: &Denylist,
+) -> Vec<Finding> {
+    let finding = |severity, message: String| Finding {
+        severity,
+        package: target.label.clone(),
+        message,
+    };
+    let report = match manager.resolve_reference(&target.reference) {
+        Ok(reference) => manager.verify(&reference, &[]).await,
+        Err(err) => Err(err),
+    };
+    let report = match report {
+        Ok(report) => report,
+        Err(err) => {
+            return vec![finding(
+                Severity::Medium,
+                format!("could not be verified: {}", into_miette(err)),
+            )];
+        }
+    };
+
+    let mut findings = Vec::new();
+    if target.digest.is_none() {
+        findings.extend(
+            denied(&report.digest, denylist).map(|message| finding(Severity::Critical, message)),
+        );
+    }
+    if report.signatures.is_empty() {
+        findings.push(finding(Severity::Medium, "has no signature".to_string()));
+    } else {
+        findings.extend(
+            report
+                .failures
+                .iter()
+                .map(|failure| finding(Severity::High, format!("failed verification: {failure}"))),
+        );
+    }
+    findings
+}
+
+/// The finding message for `digest`, if it is on the denylist.
+fn denied(digest: &str, denylist: &Denylist) -> Option<String> {
+    denylist.reason(digest).map(|reason| match reason {
+        Some(reason) => format!("digest {digest} is denied: {reason}"),
+        None => format!("digest {digest} is denied"),
+    })
+}
+
+/// Whether `tag` names a release, like `1.2.3` or `v1.2.3-rc.1`, rather than
+/// something that moves, like `latest` or `1.2`.
+fn is_version_tag(tag: &str) -> bool {
+    let tag = tag.strip_prefix('v').unwrap_or(tag);
+    let core = tag.split(['-', '+']).next().unwrap_or_default();
+    let parts: Vec<&str> = core.split('.').collect();
+    parts.len() == 3
+        && parts
+            .iter()
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// A one-line count of `findings` by severity.
+fn summary(packages: usize, findings: &[Finding]) -> String {
+    let noun = if packages == 1 { "package" } else { "packages" };
+    if findings.is_empty() {
+        return format!("Audited {packages} {noun}: no problems found");
+    }
+    let counts: Vec<String> = [
+        Severity::Critical,
+        Severity::High,
+        Severity::Medium,
+        Severity::Low,
+    ]
+    .into_iter()
+    .map(|severity| {
+        let count = findings.iter().filter(|f| f.severity == severity).count();
+        format!("{count} {severity}")
+    })
+    .collect();
+    format!("Audited {packages} {noun}: {}", counts.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn target(digest: Option<&str>, tag: &str, locked: bool) -> Target {
+        Target {
+            label: "example:hello@1.0.0".to_string(),
+            reference: "ghcr.io/example/hello:1.0.0".to_string(),
+            digest: digest.map(str::to_string),
+            tag: Some(tag.to_string()),
+            locked,
+        }
+    }
+
+    #[test]
+    fn local_findings_flag_denied_digests_and_unpinned_tags() {
+        let mut denylist = Denylist::default();
+        denylist
+            .extend_from_feed("sha256:bad leaked signing key\n")
+            .unwrap();
+
+        let findings = local_findings(&target(Some("sha256:bad"), "1.0.0", true), &denylist);
+        assert_eq!(
+            findings,
+            [Finding {
+                severity: Severity::Critical,
+                package: "example:hello@1.0.0".to_string(),
+                message: "digest sha256:bad is denied: leaked signing key".to_string(),
+            }]
+        );
+
+        let findings = local_findings(&target(None, "1.0.0", true), &denylist);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings.first().map(|f| f.severity), Some(Severity::High));
+
+        let findings = local_findings(&target(None, "latest", false), &denylist);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings.first().map(|f| f.severity), Some(Severity::Low));
+
+        assert!(local_findings(&target(None, "1.0.0", false), &denylist).is_empty());
+        assert!(local_findings(&target(Some("sha256:ok"), "1.0.0", true), &denylist).is_empty());
+    }
+
+    #[test]
+    fn version_tags_are_full_releases() {
+        for tag in ["1.2.3", "v0.2.0", "1.0.0-rc.1", "1.0.0+build.5"] {
+            assert!(is_version_tag(tag), "{tag}");
+        }
+        for tag in ["latest", "main", "1", "1.2", "1.2.x", ""] {
+            assert!(!is_version_tag(tag), "{tag}");
+        }
+    }
+
+    #[test]
+    fn summary_counts_by_severity() {
+        let finding = |severity| Finding {
+            severity,
+            package: "a".to_string(),
+            message: "b".to_string(),
+        };
+        assert_eq!(
+            summary(
+                3,
+                &[
+                    finding(Severity::Critical),
+                    finding(Severity::Medium),
+                    finding(Severity::Medium)
+                ]
+            ),
+            "Audited 3 packages: 1 critical, 0 high, 2 medium, 0 low"
+        );
+        assert_eq!(summary(1, &[]), "Audited 1 package: no problems found");
+    }
+}
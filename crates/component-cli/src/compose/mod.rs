@@ -2,7 +2,7 @@
 
 use std::path::PathBuf;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use component_package_manager::compose;
 
 /// How to link dependencies in the composed component.
@@ -15,15 +15,47 @@ pub(crate) enum LinkerMode {
     Dynamic,
 }
 
-/// Compose Wasm components from WAC scripts
+/// Compose Wasm components from WAC scripts or by plugging them together
 #[derive(clap::Args)]
+#[command(args_conflicts_with_subcommands = true)]
 pub(crate) struct Opts {
+    #[command(subcommand)]
+    command: Option<Subcommand>,
+
     /// Name of a `.wac` file in `seams/` to compose. For example, `component compose
-    /// foo` resolves to `seams/foo.wac`. If omitted, all `.wac` files in
-    /// `seams/` are composed.
+    /// foo` resolves to `seams/foo.wac`. A path ending in `.wac` is composed
+    /// as-is. If omitted, all `.wac` files in `seams/` are composed.
     #[arg()]
     name: Option<String>,
 
+    #[command(flatten)]
+    output: OutputOpts,
+}
+
+#[derive(clap::Subcommand)]
+enum Subcommand {
+    /// Plug the exports of components into the matching imports of another,
+    /// without a WAC script
+    Plug(PlugOpts),
+}
+
+#[derive(clap::Args)]
+struct PlugOpts {
+    /// The component to plug into: a local path or an OCI reference
+    socket: String,
+
+    /// Components whose exports satisfy imports of the socket: local paths or
+    /// OCI references. Exports are matched to imports by interface name and
+    /// compatible version.
+    #[arg(required = true)]
+    plugs: Vec<String>,
+
+    #[command(flatten)]
+    output: OutputOpts,
+}
+
+#[derive(clap::Args)]
+struct OutputOpts {
     /// How to link dependencies.
     #[arg(long, value_enum, default_value_t = LinkerMode::Static)]
     linker: LinkerMode,
@@ -33,19 +65,93 @@ pub(crate) struct Opts {
     output: PathBuf,
 }
 
-impl Opts {
-    pub(crate) fn run(self) -> Result<()> {
-        let linker = match self.linker {
+impl OutputOpts {
+    fn linker(&self) -> compose::LinkerMode {
+        match self.linker {
             LinkerMode::Static => compose::LinkerMode::Static,
             LinkerMode::Dynamic => compose::LinkerMode::Dynamic,
-        };
+        }
+    }
+}
 
-        let results = compose::compose(self.name.as_deref(), &linker, &self.output)?;
+impl Opts {
+    pub(crate) async fn run(self, offline: bool) -> Result<()> {
+        if let Some(Subcommand::Plug(opts)) = self.command {
+            return opts.run(offline).await;
+        }
+
+        let results = compose::compose(
+            self.name.as_deref(),
+            &self.output.linker(),
+            &self.output.output,
+        )?;
 
         for out_path in &results {
             println!("Composed component written to {}", out_path.display());
+            report_unsatisfied_imports(&std::fs::read(out_path)?)?;
         }
 
         Ok(())
     }
 }
+
+impl PlugOpts {
+    async fn run(self, offline: bool) -> Result<()> {
+        let socket = read_input(&self.socket, offline).await?;
+        let mut plugs = Vec::with_capacity(self.plugs.len());
+        for plug in &self.plugs {
+            plugs.push(read_input(plug, offline).await?);
+        }
+        let plugged = compose::plug(&socket, &plugs, &self.output.linker())?;
+
+        let out_dir = &self.output.output;
+        std::fs::create_dir_all(out_dir).with_context(|| {
+            format!("could not create output directory '{}'", out_dir.display())
+        })?;
+        let out_path = out_dir.join(format!("{}.wasm", output_stem(&socket.name)));
+        std::fs::write(&out_path, &plugged.bytes)
+            .with_context(|| format!("could not write '{}'", out_path.display()))?;
+
+        for connection in &plugged.connections {
+            if connection.export == connection.import {
+                println!("Plugged {} from {}", connection.import, connection.plug);
+            } else {
+                println!(
+                    "Plugged {} from {} (as {})",
+                    connection.import, connection.plug, connection.export
+                );
+            }
+        }
+        println!("Composed component written to {}", out_path.display());
+        report_unsatisfied_imports(&plugged.bytes)
+    }
+}
+
+/// Read a component to compose from a local path or the local store.
+async fn read_input(input: &str, offline: bool) -> Result<compose::ComposeInput> {
+    Ok(compose::ComposeInput {
+        name: input.to_string(),
+        bytes: crate::util::read_wasm(input, offline).await?,
+    })
+}
+
+/// The file stem to name a composition after: the file name of a path, or
+/// the last repository segment of an OCI reference.
+fn output_stem(input: &str) -> &str {
+    let name = input.rsplit(['/', '\\']).next().unwrap_or(input);
+    let name = name.split(['@', ':']).next().unwrap_or(name);
+    name.strip_suffix(".wasm").unwrap_or(name)
+}
+
+/// Print the imports of a composed component that the host has to satisfy.
+fn report_unsatisfied_imports(bytes: &[u8]) -> Result<()> {
+    let imports = compose::unsatisfied_imports(bytes)?;
+    if imports.is_empty() {
+        return Ok(());
+    }
+    println!("Unsatisfied imports, to be provided by the host:");
+    for import in imports {
+        println!("  {import}");
+    }
+    Ok(())
+}
@@ -0,0 +1,177 @@
+#![allow(clippy::print_stdout)]
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use component_package_manager::types;
+
+use crate::util::{is_stdio, read_input};
+
+/// Convert between the Wasm binary and text formats
+#[derive(clap::Parser)]
+pub(crate) enum Opts {
+    /// Print a Wasm binary in the WebAssembly text format
+    Wat(ConvertOpts),
+    /// Assemble a WebAssembly text file into a Wasm binary
+    Wasm(ConvertOpts),
+    /// Wrap a core Wasm module into a component
+    Component(ComponentOpts),
+    /// Extract the WIT definitions of a component or WIT package
+    Wit(WitOpts),
+}
+
+/// Options shared by the `convert` commands.
+#[derive(clap::Args)]
+pub(crate) struct ConvertOpts {
+    /// The file to convert, or `-` for stdin
+    input: PathBuf,
+
+    /// Where to write the result (defaults to stdout, as does `-`)
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+/// Options for the `convert component` command.
+#[derive(clap::Args)]
+pub(crate) struct ComponentOpts {
+    /// The core Wasm module to wrap, or `-` for stdin
+    input: PathBuf,
+
+    /// Where to write the component (defaults to stdout, as does `-`)
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// An adapter module that implements the module's imports, such as
+    /// `wasi_snapshot_preview1.wasm`. Takes `[NAME=]PATH`, where `NAME` is the
+    /// import module it adapts and defaults to the file's stem. May be given
+    /// more than once.
+    #[arg(long, value_name = "[NAME=]PATH")]
+    adapt: Vec<String>,
+}
+
+/// Options for the `convert wit` command.
+#[derive(clap::Args)]
+pub(crate) struct WitOpts {
+    /// The component or binary WIT package to read, or `-` for stdin
+    input: PathBuf,
+
+    /// A directory to write the WIT package to, with its dependencies in
+    /// `deps/` (defaults to printing all packages to stdout, as does `-`)
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+impl Opts {
+    pub(crate) fn run(self) -> Result<()> {
+        match self {
+            Opts::Wat(opts) => {
+                let bytes = read_input(&opts.input)?;
+                let text = wasmprinter::print_bytes(&bytes)
+                    .with_context(|| format!("failed to print {} as text", opts.input.display()))?;
+                write_output(opts.output.as_deref(), text.as_bytes())
+            }
+            Opts::Wasm(opts) => {
+                let bytes = parse_wat(&opts.input)?;
+                write_output(opts.output.as_deref(), &bytes)
+            }
+            Opts::Component(opts) => opts.run(),
+            Opts::Wit(opts) => opts.run(),
+        }
+    }
+}
+
+impl ComponentOpts {
+    fn run(self) -> Result<()> {
+        let module = parse_wat(&self.input)?;
+        if !wasmparser::Parser::is_core_wasm(&module) {
+            anyhow::bail!(
+                "{} is not a core Wasm module; only core modules can be wrapped into components",
+                self.input.display()
+            );
+        }
+
+        let mut encoder = wit_component::ComponentEncoder::default()
+            .validate(true)
+            .module(&module)
+            .with_context(|| format!("failed to read {}", self.input.display()))?;
+        for adapter in &self.adapt {
+            let (name, path) = parse_adapter(adapter)?;
+            let bytes = wat::parse_file(&path)?;
+            encoder = encoder
+                .adapter(&name, &bytes)
+                .with_context(|| format!("failed to use {} as an adapter", path.display()))?;
+        }
+        let component = encoder
+            .encode()
+            .with_context(|| format!("failed to wrap {} into a component", self.input.display()))?;
+        write_output(self.output.as_deref(), &component)
+    }
+}
+
+impl WitOpts {
+    fn run(self) -> Result<()> {
+        let bytes = read_input(&self.input)?;
+        let not_wit = || {
+            anyhow::anyhow!(
+                "{} is not a component or binary WIT package",
+                self.input.display()
+            )
+        };
+
+        let Some(dir) = self.output.as_ref().filter(|dir| !is_stdio(dir)) else {
+            let text = types::extract_wit_text(&bytes).ok_or_else(not_wit)?;
+            return write_output(None, text.as_bytes());
+        };
+        let files = types::extract_wit_files(&bytes).ok_or_else(not_wit)?;
+        for file in files {
+            let path = dir.join(&file.path);
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)
+                    .with_context(|| format!("failed to create {}", parent.display()))?;
+            }
+            write_output(Some(&path), file.contents.as_bytes())?;
+            println!("Wrote {}", path.display());
+        }
+        Ok(())
+    }
+}
+
+/// Split an `--adapt` value into the import module it adapts and its path.
+fn parse_adapter(value: &str) -> Result<(String, PathBuf)> {
+    if let Some((name, path)) = value.split_once('=') {
+        return Ok((name.to_string(), PathBuf::from(path)));
+    }
+    let path = PathBuf::from(value);
+    let name = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .with_context(|| format!("cannot name the adapter {value}; pass it as NAME=PATH"))?
+        .to_string();
+    Ok((name, path))
+}
+
+/// Read a Wasm binary or text file, or stdin for `-`, as a Wasm binary.
+fn parse_wat(path: &Path) -> Result<Vec<u8>> {
+    if !is_stdio(path) {
+        return Ok(wat::parse_file(path)?);
+    }
+    let bytes = read_input(path)?;
+    let binary = wat::parse_bytes(&bytes).map_err(|mut e| {
+        e.set_path("<stdin>");
+        e
+    })?;
+    Ok(binary.into_owned())
+}
+
+/// Write `data` to `output`, or to stdout if no path or `-` is given.
+fn write_output(output: Option<&Path>, data: &[u8]) -> Result<()> {
+    if let Some(path) = output.filter(|path| !is_stdio(path)) {
+        return std::fs::write(path, data)
+            .with_context(|| format!("failed to write {}", path.display()));
+    }
+    let mut stdout = std::io::stdout().lock();
+    stdout.write_all(data)?;
+    stdout.flush()?;
+    Ok(())
+}
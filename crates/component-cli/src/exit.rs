@@ -0,0 +1,225 @@
+//! Exit codes, and reporting the error that ended a command in the format
+//! picked with the global `--error-format` flag.
+
+#![allow(clippy::print_stderr)]
+
+use std::any::Any;
+use std::process::ExitCode;
+
+use component_package_manager::PackageManagerError;
+
+use crate::local::LocalError;
+use crate::registry::{PullError, VerifyError};
+
+/// How the error that ended a command is reported on stderr.
+// The variants are described with plain comments: clap would otherwise show
+// every command's help in its long form to list them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub(crate) enum ErrorFormat {
+    // A rendered diagnostic for people to read.
+    #[default]
+    Human,
+    // One line of JSON with the message, kind, and exit code.
+    Json,
+}
+
+/// Why a command failed, as its exit code.
+///
+/// Scripts can branch on these instead of parsing error messages. Exit code
+/// 2 is left to clap, which uses it for usage errors such as unknown flags.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ExitStatus {
+    /// Any failure without a more specific code.
+    Failure = 1,
+    /// A package, tag, or file does not exist.
+    NotFound = 3,
+    /// The registry rejected the credentials, or there were none.
+    AuthFailed = 4,
+    /// The command needs the network, but `--offline` was passed.
+    Offline = 5,
+    /// A signature, digest, policy, audit, or lockfile check failed.
+    VerificationFailed = 6,
+    /// Some items of a batch failed while others succeeded.
+    PartialSuccess = 7,
+}
+
+impl ExitStatus {
+    /// The status for an error that ended a command.
+    pub(crate) fn of(report: &miette::Report) -> Self {
+        if let Some(failure) = report.downcast_ref::<Failure>() {
+            return failure.status;
+        }
+        // CLI diagnostics are matched by their stable codes.
+        match report.code().map(|code| code.to_string()).as_deref() {
+            Some("component::audit::failed" | "component::lock::verify_failed") => {
+                Self::VerificationFailed
+            }
+            _ => Self::Failure,
+        }
+    }
+
+    /// The status for an error from a subsystem, found by looking through
+    /// its cause chain for a known kind of error.
+    fn of_error(err: &dyn Any) -> Self {
+        if let Some(err) = err.downcast_ref::<anyhow::Error>() {
+            return err
+                .chain()
+                .find_map(Self::of_cause)
+                .unwrap_or(Self::Failure);
+        }
+        err.downcast_ref::<PackageManagerError>()
+            .and_then(|err| Self::of_cause(err))
+            .unwrap_or(Self::Failure)
+    }
+
+    fn of_cause(cause: &(dyn std::error::Error + 'static)) -> Option<Self> {
+        if let Some(err) = cause.downcast_ref::<PackageManagerError>() {
+            return match err {
+                PackageManagerError::NotFound(_) => Some(Self::NotFound),
+                PackageManagerError::AuthFailed(_) => Some(Self::AuthFailed),
+                PackageManagerError::Offline(_) => Some(Self::Offline),
+                PackageManagerError::Corrupt(_) | PackageManagerError::PolicyViolation(_) => {
+                    Some(Self::VerificationFailed)
+                }
+                _ => None,
+            };
+        }
+        if let Some(err) = cause.downcast_ref::<LocalError>() {
+            return match err {
+                LocalError::NoSuchIndex { .. }
+                | LocalError::NoMatch { .. }
+                | LocalError::NoBuildOutput { .. } => Some(Self::NotFound),
+                LocalError::Ambiguous { .. } => None,
+            };
+        }
+        if cause.is::<VerifyError>() {
+            return Some(Self::VerificationFailed);
+        }
+        match cause.downcast_ref::<PullError>() {
+            Some(PullError::Failed { failed, total }) if failed < total => {
+                Some(Self::PartialSuccess)
+            }
+            _ => None,
+        }
+    }
+
+    /// The kind of failure, as reported in JSON.
+    fn kind(self) -> &'static str {
+        match self {
+            Self::Failure => "failure",
+            Self::NotFound => "not_found",
+            Self::AuthFailed => "auth_failed",
+            Self::Offline => "offline",
+            Self::VerificationFailed => "verification_failed",
+            Self::PartialSuccess => "partial_success",
+        }
+    }
+}
+
+impl From<ExitStatus> for ExitCode {
+    fn from(status: ExitStatus) -> Self {
+        ExitCode::from(status as u8)
+    }
+}
+
+/// An error from a subsystem, flattened into its message, along with the
+/// exit status its cause maps to. See [`crate::util::into_miette`].
+#[derive(Debug)]
+pub(crate) struct Failure {
+    message: String,
+    status: ExitStatus,
+}
+
+impl Failure {
+    /// Flatten `err`, rendering its full cause chain as
+    /// "outer: inner: root cause".
+    pub(crate) fn new<E: std::fmt::Display + 'static>(err: &E) -> Self {
+        Self {
+            message: format!("{err:#}"),
+            status: ExitStatus::of_error(err),
+        }
+    }
+}
+
+impl std::fmt::Display for Failure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for Failure {}
+
+impl miette::Diagnostic for Failure {}
+
+/// Print the error that ended a command to stderr in `format`, and return
+/// the exit code for it.
+pub(crate) fn report(report: &miette::Report, format: ErrorFormat) -> ExitCode {
+    let status = ExitStatus::of(report);
+    match format {
+        // The same output as returning the report from `main`.
+        ErrorFormat::Human => eprintln!("Error: {report:?}"),
+        ErrorFormat::Json => eprintln!("{}", error_json(report, status)),
+    }
+    status.into()
+}
+
+/// The error as a JSON object, for CI log parsers.
+fn error_json(report: &miette::Report, status: ExitStatus) -> serde_json::Value {
+    let message = report
+        .chain()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(": ");
+    serde_json::json!({
+        "message": message,
+        "kind": status.kind(),
+        "exit_code": status as u8,
+        "code": report.code().map(|code| code.to_string()),
+        "help": report.help().map(|help| help.to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use component_package_manager::manager::ManagerError;
+
+    #[test]
+    fn statuses_follow_the_cause_chain() {
+        let offline = anyhow::Error::from(PackageManagerError::from(ManagerError::OfflinePull))
+            .context("while pulling");
+        assert_eq!(ExitStatus::of_error(&offline), ExitStatus::Offline);
+
+        let partial = anyhow::Error::from(PullError::Failed {
+            failed: 1,
+            total: 2,
+        });
+        assert_eq!(ExitStatus::of_error(&partial), ExitStatus::PartialSuccess);
+
+        let all_failed = anyhow::Error::from(PullError::Failed {
+            failed: 2,
+            total: 2,
+        });
+        assert_eq!(ExitStatus::of_error(&all_failed), ExitStatus::Failure);
+
+        let other = anyhow::anyhow!("something else");
+        assert_eq!(ExitStatus::of_error(&other), ExitStatus::Failure);
+    }
+
+    #[test]
+    fn json_errors_carry_the_kind_and_exit_code() {
+        let report = miette::Report::new(Failure::new(&anyhow::Error::from(
+            PackageManagerError::from(ManagerError::OfflinePull),
+        )));
+        let json = error_json(&report, ExitStatus::of(&report));
+        assert_eq!(json.get("kind").and_then(|v| v.as_str()), Some("offline"));
+        assert_eq!(
+            json.get("exit_code").and_then(serde_json::Value::as_u64),
+            Some(5)
+        );
+        assert_eq!(
+            json.get("message").and_then(|v| v.as_str()),
+            Some("cannot pull packages in offline mode")
+        );
+    }
+}
@@ -0,0 +1,85 @@
+//! Error types for the `component init` and `component new` CLI commands.
+
+use miette::Diagnostic;
+
+/// CLI-specific error type for `component init` and `component new` failures.
+///
+/// Each variant carries a stable [diagnostic error code][miette::Diagnostic::code]
+/// that uniquely identifies the failure.
+#[derive(Debug, Clone, PartialEq, Eq, Diagnostic)]
+#[must_use]
+pub(crate) enum InitError {
+    /// The package name isn't in `namespace:name` form.
+    #[diagnostic(
+        code(component::init::invalid_name),
+        help("package names look like `my-org:hello`")
+    )]
+    InvalidName {
+        /// The name that was given.
+        name: String,
+    },
+    /// `component new` was pointed at a path that already exists.
+    #[diagnostic(
+        code(component::init::already_exists),
+        help("call `component init` to set up an existing directory")
+    )]
+    AlreadyExists {
+        /// The path that exists.
+        path: String,
+    },
+}
+
+impl std::fmt::Display for InitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InitError::InvalidName { name } => {
+                write!(f, "'{name}' is not a valid package name")
+            }
+            InitError::AlreadyExists { path } => {
+                write!(f, "destination `{path}` already exists")
+            }
+        }
+    }
+}
+
+impl std::error::Error for InitError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_all_variants_have_error_codes() {
+        use miette::Diagnostic;
+
+        let invalid_name = InitError::InvalidName {
+            name: "hello".to_string(),
+        };
+        assert_eq!(
+            invalid_name
+                .code()
+                .expect("InvalidName must have a diagnostic code")
+                .to_string(),
+            "component::init::invalid_name",
+        );
+        assert!(
+            invalid_name.help().is_some(),
+            "InvalidName must have a help message"
+        );
+
+        let already_exists = InitError::AlreadyExists {
+            path: "hello".to_string(),
+        };
+        assert_eq!(
+            already_exists
+                .code()
+                .expect("AlreadyExists must have a diagnostic code")
+                .to_string(),
+            "component::init::already_exists",
+        );
+        assert!(
+            already_exists.help().is_some(),
+            "AlreadyExists must have a help message"
+        );
+    }
+}
@@ -1,10 +1,14 @@
 #![allow(clippy::print_stdout)]
 
-use std::path::PathBuf;
+mod errors;
 
+use std::path::{Path, PathBuf};
+
+use component_manifest::{Dependency, Manifest, Package, PackageKind};
 use miette::{IntoDiagnostic, WrapErr};
 
-use crate::util::write_lock_file;
+use crate::util::{into_miette, prompt, write_lock_file};
+use errors::InitError;
 
 /// Options for the `init` command.
 #[derive(clap::Parser)]
@@ -13,54 +17,327 @@ pub(crate) struct Opts {
     /// current directory.
     #[arg(default_value = ".")]
     path: PathBuf,
+    #[command(flatten)]
+    project: ProjectOpts,
+}
+
+/// Options for the `new` command.
+#[derive(clap::Parser)]
+pub(crate) struct NewOpts {
+    /// The directory to create the wasm package in. Must not exist yet.
+    path: PathBuf,
+    #[command(flatten)]
+    project: ProjectOpts,
+}
+
+/// What to put in a new project. Values that aren't passed are asked for
+/// when running in a terminal.
+#[derive(clap::Args)]
+struct ProjectOpts {
+    /// The package name, in `namespace:name` form (e.g., my-org:hello)
+    #[arg(long)]
+    name: Option<String>,
+    /// The WIT world the component targets (e.g., wasi:http/proxy)
+    #[arg(long)]
+    world: Option<String>,
+    /// The registry to publish to, without the package name
+    /// (e.g., ghcr.io/my-org)
+    #[arg(long)]
+    registry: Option<String>,
+    /// Start from a component template
+    #[arg(long, value_enum)]
+    template: Option<Template>,
+    /// Add the generated `build/` directory to `.gitignore`
+    #[arg(long)]
+    gitignore: bool,
 }
 
+/// A starting point for a new component.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum Template {
+    /// An HTTP handler targeting `wasi:http/proxy`
+    HttpProxy,
+    /// A command-line program targeting `wasi:cli/command`
+    CliCommand,
+}
+
+impl Template {
+    /// The world a component made from this template targets.
+    fn world(self) -> &'static str {
+        match self {
+            Template::HttpProxy => "wasi:http/proxy",
+            Template::CliCommand => "wasi:cli/command",
+        }
+    }
+
+    /// The interface package that world comes from, and its version.
+    fn dependency(self) -> (&'static str, &'static str) {
+        match self {
+            Template::HttpProxy => ("wasi:http", "0.2.3"),
+            Template::CliCommand => ("wasi:cli", "0.2.3"),
+        }
+    }
+}
+
+/// Entries `--gitignore` adds to `.gitignore`.
+const GITIGNORE_ENTRIES: &[&str] = &["/build/"];
+
 impl Opts {
     pub(crate) async fn run(self) -> miette::Result<()> {
-        let base = &self.path;
-
-        tokio::fs::create_dir_all(base.join("vendor/wit"))
-            .await
-            .into_diagnostic()
-            .wrap_err("failed to create vendor/wit directory")?;
-        tokio::fs::create_dir_all(base.join("vendor/wasm"))
-            .await
-            .into_diagnostic()
-            .wrap_err("failed to create vendor/wasm directory")?;
-
-        // Create composition workspace directories
-        tokio::fs::create_dir_all(base.join("types"))
-            .await
-            .into_diagnostic()
-            .wrap_err("failed to create types directory")?;
-        tokio::fs::create_dir_all(base.join("seams"))
-            .await
-            .into_diagnostic()
-            .wrap_err("failed to create seams directory")?;
-        tokio::fs::create_dir_all(base.join("build"))
-            .await
-            .into_diagnostic()
-            .wrap_err("failed to create build directory")?;
-
-        let manifest = component_manifest::Manifest::default();
-        let manifest = toml::to_string_pretty(&manifest).into_diagnostic()?;
-        tokio::fs::write(base.join("wasm.toml"), manifest.as_bytes())
-            .await
-            .into_diagnostic()
-            .wrap_err("failed to write wasm.toml")?;
-
-        let lockfile = component_manifest::Lockfile::default();
-        write_lock_file(base.join("wasm.lock.toml"), &lockfile)
-            .await
-            .into_diagnostic()
-            .wrap_err("failed to write wasm.lock.toml")?;
-
-        println!(
-            "{:>12} wasm project at `{}`",
-            console::style("Created").green().bold(),
-            base.display()
+        scaffold(&self.path, &self.project).await
+    }
+}
+
+impl NewOpts {
+    pub(crate) async fn run(self) -> miette::Result<()> {
+        if self.path.exists() {
+            return Err(InitError::AlreadyExists {
+                path: self.path.display().to_string(),
+            }
+            .into());
+        }
+        scaffold(&self.path, &self.project).await
+    }
+}
+
+/// Create the project files and directories in `base`.
+async fn scaffold(base: &Path, project: &ProjectOpts) -> miette::Result<()> {
+    tokio::fs::create_dir_all(base.join("vendor/wit"))
+        .await
+        .into_diagnostic()
+        .wrap_err("failed to create vendor/wit directory")?;
+    tokio::fs::create_dir_all(base.join("vendor/wasm"))
+        .await
+        .into_diagnostic()
+        .wrap_err("failed to create vendor/wasm directory")?;
+
+    // Create composition workspace directories
+    tokio::fs::create_dir_all(base.join("types"))
+        .await
+        .into_diagnostic()
+        .wrap_err("failed to create types directory")?;
+    tokio::fs::create_dir_all(base.join("seams"))
+        .await
+        .into_diagnostic()
+        .wrap_err("failed to create seams directory")?;
+    tokio::fs::create_dir_all(base.join("build"))
+        .await
+        .into_diagnostic()
+        .wrap_err("failed to create build directory")?;
+
+    let manifest = project_manifest(base, project)?;
+    if let (Some(package), Some(template)) = (&manifest.package, project.template) {
+        write_world(base, package, template).await?;
+    }
+    let manifest = toml::to_string_pretty(&manifest).into_diagnostic()?;
+    tokio::fs::write(base.join("wasm.toml"), manifest.as_bytes())
+        .await
+        .into_diagnostic()
+        .wrap_err("failed to write wasm.toml")?;
+
+    let lockfile = component_manifest::Lockfile::default();
+    write_lock_file(base.join("wasm.lock.toml"), &lockfile)
+        .await
+        .into_diagnostic()
+        .wrap_err("failed to write wasm.lock.toml")?;
+
+    if project.gitignore {
+        update_gitignore(&base.join(".gitignore")).await?;
+    }
+
+    println!(
+        "{:>12} wasm project at `{}`",
+        console::style("Created").green().bold(),
+        base.display()
+    );
+
+    Ok(())
+}
+
+/// The manifest for a new project. It only gets a `[package]` section once
+/// there is a name, which templates always have.
+fn project_manifest(base: &Path, project: &ProjectOpts) -> miette::Result<Manifest> {
+    let mut manifest = Manifest::default();
+    if let Some(template) = project.template {
+        let (name, version) = template.dependency();
+        manifest
+            .dependencies
+            .interfaces
+            .insert(name.to_string(), Dependency::Compact(version.to_string()));
+    }
+
+    let default_name = project.template.map(|_| default_name(base));
+    let Some(name) = ask(
+        project.name.as_deref(),
+        "Package name (namespace:name)",
+        default_name.as_deref(),
+    )?
+    else {
+        return Ok(manifest);
+    };
+    let Some((namespace, short_name)) = name
+        .split_once(':')
+        .filter(|(namespace, short_name)| !namespace.is_empty() && !short_name.is_empty())
+    else {
+        return Err(InitError::InvalidName { name }.into());
+    };
+
+    let world = ask(
+        project.world.as_deref(),
+        "WIT world",
+        project.template.map(Template::world),
+    )?;
+    let default_registry = format!("ghcr.io/{namespace}");
+    let registry = ask(
+        project.registry.as_deref(),
+        "Registry",
+        Some(&default_registry),
+    )?
+    .unwrap_or(default_registry);
+
+    let package = Package {
+        registry_ref: format!("{}/{short_name}", registry.trim_end_matches('/')),
+        name,
+        version: "0.1.0".to_string(),
+        kind: PackageKind::Component,
+        file: None,
+        wit: None,
+        world,
+        description: None,
+        source: None,
+        homepage: None,
+        documentation: None,
+        license: None,
+        authors: Vec::new(),
+    };
+    package.validate().map_err(into_miette)?;
+    manifest.package = Some(package);
+    Ok(manifest)
+}
+
+/// The value passed on the command line, or else the answer to `question`.
+fn ask(
+    given: Option<&str>,
+    question: &str,
+    default: Option<&str>,
+) -> miette::Result<Option<String>> {
+    if let Some(value) = given {
+        return Ok(Some(value.to_string()));
+    }
+    prompt(question, default).map_err(into_miette)
+}
+
+/// A package name for `base`, for when a template needs one and none was
+/// given.
+fn default_name(base: &Path) -> String {
+    let dir = std::path::absolute(base)
+        .ok()
+        .and_then(|path| {
+            path.file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+        })
+        .unwrap_or_default();
+    let short_name: String = dir
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let short_name = short_name.trim_matches('-');
+    if short_name.is_empty() {
+        "local:component".to_string()
+    } else {
+        format!("local:{short_name}")
+    }
+}
+
+/// Write `wit/world.wit` for `template`, unless the project already has one.
+async fn write_world(base: &Path, package: &Package, template: Template) -> miette::Result<()> {
+    let path = base.join("wit/world.wit");
+    if path.exists() {
+        return Ok(());
+    }
+    let short_name = package
+        .name
+        .rsplit_once(':')
+        .map_or(&*package.name, |(_, n)| n);
+    let (_, version) = template.dependency();
+    let world = format!(
+        "package {};\n\nworld {short_name} {{\n    include {}@{version};\n}}\n",
+        package.name,
+        template.world()
+    );
+    tokio::fs::create_dir_all(base.join("wit"))
+        .await
+        .into_diagnostic()
+        .wrap_err("failed to create wit directory")?;
+    tokio::fs::write(&path, world)
+        .await
+        .into_diagnostic()
+        .wrap_err("failed to write wit/world.wit")
+}
+
+/// Append the [`GITIGNORE_ENTRIES`] that `.gitignore` doesn't have yet.
+async fn update_gitignore(path: &Path) -> miette::Result<()> {
+    let mut contents = match tokio::fs::read_to_string(path).await {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => {
+            return Err(e)
+                .into_diagnostic()
+                .wrap_err("failed to read .gitignore");
+        }
+    };
+    let missing: Vec<&str> = GITIGNORE_ENTRIES
+        .iter()
+        .copied()
+        .filter(|entry| !contents.lines().any(|line| line.trim() == *entry))
+        .collect();
+    if missing.is_empty() {
+        return Ok(());
+    }
+    if !contents.is_empty() && !contents.ends_with('\n') {
+        contents.push('\n');
+    }
+    for entry in missing {
+        contents.push_str(entry);
+        contents.push('\n');
+    }
+    tokio::fs::write(path, contents)
+        .await
+        .into_diagnostic()
+        .wrap_err("failed to write .gitignore")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_name_is_derived_from_the_directory() {
+        assert_eq!(
+            default_name(Path::new("/tmp/My Project")),
+            "local:my-project"
         );
+        assert_eq!(default_name(Path::new("/")), "local:component");
+    }
 
-        Ok(())
+    #[tokio::test]
+    async fn gitignore_entries_are_only_added_once() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join(".gitignore");
+        std::fs::write(&path, "/target").unwrap();
+
+        update_gitignore(&path).await.unwrap();
+        update_gitignore(&path).await.unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "/target\n/build/\n"
+        );
     }
 }
@@ -0,0 +1,87 @@
+//! The imports and exports of a core Wasm module, which has no WIT world.
+
+use anyhow::Result;
+use wasmparser::{ExternalKind, Parser, Payload, TypeRef};
+
+/// What a core module imports and exports.
+#[derive(serde::Serialize)]
+pub(super) struct CoreSurface {
+    pub(super) imports: Vec<CoreImport>,
+    pub(super) exports: Vec<CoreExport>,
+}
+
+/// An item a core module imports.
+#[derive(serde::Serialize)]
+pub(super) struct CoreImport {
+    /// The module the item is imported from, e.g. `env`.
+    pub(super) module: String,
+    pub(super) name: String,
+    /// `func`, `table`, `memory`, `global`, or `tag`.
+    pub(super) kind: &'static str,
+}
+
+/// An item a core module exports.
+#[derive(serde::Serialize)]
+pub(super) struct CoreExport {
+    pub(super) name: String,
+    /// `func`, `table`, `memory`, `global`, or `tag`.
+    pub(super) kind: &'static str,
+}
+
+impl CoreImport {
+    /// The import as `module.name`.
+    pub(super) fn path(&self) -> String {
+        format!("{}.{}", self.module, self.name)
+    }
+}
+
+/// Read the imports and exports of the core module `bytes`.
+pub(super) fn surface(bytes: &[u8]) -> Result<CoreSurface> {
+    let mut imports = Vec::new();
+    let mut exports = Vec::new();
+    for payload in Parser::new(0).parse_all(bytes) {
+        match payload? {
+            Payload::ImportSection(reader) => {
+                for import in reader.into_imports() {
+                    let import = import?;
+                    imports.push(CoreImport {
+                        module: import.module.to_string(),
+                        name: import.name.to_string(),
+                        kind: import_kind(import.ty),
+                    });
+                }
+            }
+            Payload::ExportSection(reader) => {
+                for export in reader {
+                    let export = export?;
+                    exports.push(CoreExport {
+                        name: export.name.to_string(),
+                        kind: export_kind(export.kind),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(CoreSurface { imports, exports })
+}
+
+fn import_kind(ty: TypeRef) -> &'static str {
+    match ty {
+        TypeRef::Func(_) | TypeRef::FuncExact(_) => "func",
+        TypeRef::Table(_) => "table",
+        TypeRef::Memory(_) => "memory",
+        TypeRef::Global(_) => "global",
+        TypeRef::Tag(_) => "tag",
+    }
+}
+
+fn export_kind(kind: ExternalKind) -> &'static str {
+    match kind {
+        ExternalKind::Func | ExternalKind::FuncExact => "func",
+        ExternalKind::Table => "table",
+        ExternalKind::Memory => "memory",
+        ExternalKind::Global => "global",
+        ExternalKind::Tag => "tag",
+    }
+}
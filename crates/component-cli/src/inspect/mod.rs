@@ -0,0 +1,361 @@
+#![allow(clippy::print_stdout, clippy::print_stderr)]
+
+use anyhow::{Context, Result};
+use comfy_table::{CellAlignment, ContentArrangement, Table};
+use component_package_manager::format_size;
+use component_package_manager::types::{self, ImportExportItem, SurfaceItem};
+use wasm_metadata::Payload;
+
+use crate::output::{Fields, OutputFormat, print_json};
+
+mod core;
+mod size;
+
+/// Show the WIT worlds, imports, and exports of a Wasm file or OCI reference
+#[derive(clap::Args)]
+pub(crate) struct Opts {
+    /// A local file or OCI reference to inspect: a component, core module, or
+    /// binary WIT package. References are pulled into the local store first,
+    /// or read from it when offline. Use `-` to read stdin.
+    input: String,
+
+    #[command(flatten)]
+    view: View,
+}
+
+/// What `inspect` shows about a file, shared with `local inspect`.
+#[derive(clap::Args)]
+#[allow(clippy::struct_excessive_bools)]
+pub(crate) struct View {
+    /// Only show the interfaces the file imports, with their functions
+    #[arg(long, conflicts_with = "metadata")]
+    imports: bool,
+
+    /// Only show the interfaces the file exports, with their functions
+    #[arg(long, conflicts_with = "metadata")]
+    exports: bool,
+
+    /// Show the embedded metadata instead: the producers section, component
+    /// name, build information, and every custom section
+    #[arg(long)]
+    metadata: bool,
+
+    /// Show a size profile instead: the size of each kind of section and of
+    /// each module, and the modules imported from most
+    #[arg(long, conflicts_with_all = ["metadata", "imports", "exports"])]
+    size: bool,
+}
+
+impl Opts {
+    pub(crate) async fn run(self, offline: bool, format: OutputFormat) -> Result<()> {
+        let bytes = crate::util::read_wasm(&self.input, offline).await?;
+        let input = if self.input == crate::util::STDIO {
+            "stdin"
+        } else {
+            &self.input
+        };
+        self.view.show(input, &bytes, format)
+    }
+}
+
+impl View {
+    /// Show what was asked for about `bytes`, read from the file or
+    /// reference `input`.
+    pub(crate) fn show(&self, input: &str, bytes: &[u8], format: OutputFormat) -> Result<()> {
+        if self.metadata {
+            return Self::run_metadata(bytes, format);
+        }
+        if self.size {
+            return Self::run_size(bytes, format);
+        }
+        if self.imports || self.exports {
+            return self.run_surface(input, bytes, format);
+        }
+        if wasmparser::Parser::is_core_wasm(bytes) {
+            return Self::run_core(bytes, format);
+        }
+        let metadata = types::extract_wit_metadata(bytes)
+            .with_context(|| format!("{input} is not a component or binary WIT package"))?;
+
+        let mut fields = Fields::new();
+        if let Some(package) = &metadata.package_name {
+            fields.push("Package", package.as_str());
+        }
+        let kind = if metadata.is_component {
+            "component"
+        } else {
+            "WIT package"
+        };
+        fields.push("Kind", kind);
+        for world in &metadata.worlds {
+            fields.push("World", world.name.as_str());
+            for import in &world.imports {
+                fields.push("Import", item_name(import));
+            }
+            for export in &world.exports {
+                fields.push("Export", item_name(export));
+            }
+        }
+        for dependency in &metadata.dependencies {
+            let name = match &dependency.version {
+                Some(version) => format!("{}@{version}", dependency.package),
+                None => dependency.package.clone(),
+            };
+            fields.push("Dependency", name);
+        }
+        fields.print(format, || serde_json::json!(metadata))
+    }
+
+    /// Print what a core module imports and exports; it has no WIT world.
+    fn run_core(bytes: &[u8], format: OutputFormat) -> Result<()> {
+        let surface = core::surface(bytes)?;
+
+        let mut fields = Fields::new();
+        fields.push("Kind", "core module");
+        for import in &surface.imports {
+            fields.push("Import", format!("{} ({})", import.path(), import.kind));
+        }
+        for export in &surface.exports {
+            fields.push("Export", format!("{} ({})", export.name, export.kind));
+        }
+        fields.print(format, || {
+            serde_json::json!({
+                "kind": "core module",
+                "imports": surface.imports,
+                "exports": surface.exports,
+            })
+        })
+    }
+
+    /// Print the embedded metadata and custom sections.
+    fn run_metadata(bytes: &[u8], format: OutputFormat) -> Result<()> {
+        let payload = Payload::from_binary(bytes)?;
+        let sections = custom_sections(bytes)?;
+
+        if format.is_json() {
+            let sections: Vec<serde_json::Value> = sections
+                .iter()
+                .map(|(name, size)| serde_json::json!({ "name": name, "size": size }))
+                .collect();
+            let value = serde_json::json!({
+                "metadata": payload,
+                "custom_sections": sections,
+            });
+            print_json(&value)?;
+            return Ok(());
+        }
+
+        crate::registry::write_details_table(&payload, &mut std::io::stdout())?;
+        let mut table = Table::new();
+        table
+            .load_preset(comfy_table::presets::NOTHING)
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_header(vec!["Custom section", "Size"]);
+        table
+            .column_mut(1)
+            .expect("This should be the Size column")
+            .set_cell_alignment(CellAlignment::Right);
+        for (name, size) in &sections {
+            table.add_row(vec![name.clone(), format_size(*size)]);
+        }
+        println!("{table}");
+        Ok(())
+    }
+
+    /// Print where the bytes of the binary go, and what it imports most.
+    fn run_size(bytes: &[u8], format: OutputFormat) -> Result<()> {
+        let profile = size::profile(bytes)?;
+        let payload = Payload::from_binary(bytes)?;
+
+        if format.is_json() {
+            let mut modules = Vec::new();
+            module_sizes(&payload, None, &mut modules);
+            let sections: Vec<serde_json::Value> = profile
+                .sections
+                .iter()
+                .map(|(name, size)| serde_json::json!({ "name": name, "size": size }))
+                .collect();
+            let imports: Vec<serde_json::Value> = profile
+                .imports
+                .iter()
+                .map(|(module, count)| serde_json::json!({ "module": module, "count": count }))
+                .collect();
+            let value = serde_json::json!({
+                "size": profile.total,
+                "sections": sections,
+                "modules": modules,
+                "imports": imports,
+            });
+            print_json(&value)?;
+            return Ok(());
+        }
+
+        let mut table = Table::new();
+        table
+            .load_preset(comfy_table::presets::NOTHING)
+            .set_content_arrangement(ContentArrangement::Dynamic)
+            .set_header(vec!["Section", "Size", "Share"]);
+        for column in [1, 2] {
+            table
+                .column_mut(column)
+                .expect("This should be a size column")
+                .set_cell_alignment(CellAlignment::Right);
+        }
+        for (name, size) in &profile.sections {
+            let percent = size
+                .saturating_mul(100)
+                .checked_div(profile.total)
+                .unwrap_or(0);
+            table.add_row(vec![
+                name.clone(),
+                format_size(*size),
+                format!("{percent}%"),
+            ]);
+        }
+        println!("{table}");
+
+        crate::registry::write_summary_table(&payload, &mut std::io::stdout())?;
+
+        if !profile.imports.is_empty() {
+            let mut table = Table::new();
+            table
+                .load_preset(comfy_table::presets::NOTHING)
+                .set_content_arrangement(ContentArrangement::Dynamic)
+                .set_header(vec!["Imported from", "Imports"]);
+            table
+                .column_mut(1)
+                .expect("This should be the Imports column")
+                .set_cell_alignment(CellAlignment::Right);
+            for (module, count) in &profile.imports {
+                table.add_row(vec![module.clone(), count.to_string()]);
+            }
+            println!("{table}");
+        }
+        Ok(())
+    }
+
+    /// Print the imports and/or exports of each world, grouped by interface.
+    fn run_surface(&self, input: &str, bytes: &[u8], format: OutputFormat) -> Result<()> {
+        if wasmparser::Parser::is_core_wasm(bytes) {
+            return self.run_core_surface(bytes, format);
+        }
+        let worlds = types::extract_world_surface(bytes)
+            .with_context(|| format!("{input} is not a component or binary WIT package"))?;
+
+        if format.is_json() {
+            let worlds: Vec<serde_json::Value> = worlds
+                .iter()
+                .map(|world| {
+                    let mut value = serde_json::Map::new();
+                    value.insert("world".into(), serde_json::json!(world.world));
+                    if self.imports {
+                        value.insert("imports".into(), serde_json::json!(world.imports));
+                    }
+                    if self.exports {
+                        value.insert("exports".into(), serde_json::json!(world.exports));
+                    }
+                    serde_json::Value::Object(value)
+                })
+                .collect();
+            print_json(&worlds)?;
+            return Ok(());
+        }
+
+        for world in &worlds {
+            println!("world {}", world.world);
+            if self.imports {
+                print_items("import", &world.imports);
+            }
+            if self.exports {
+                print_items("export", &world.exports);
+            }
+        }
+        Ok(())
+    }
+
+    /// Print the imports or exports of a core module, which has no world.
+    fn run_core_surface(&self, bytes: &[u8], format: OutputFormat) -> Result<()> {
+        let surface = core::surface(bytes)?;
+
+        if format.is_json() {
+            let mut value = serde_json::Map::new();
+            value.insert("kind".into(), serde_json::json!("core module"));
+            if self.imports {
+                value.insert("imports".into(), serde_json::json!(surface.imports));
+            }
+            if self.exports {
+                value.insert("exports".into(), serde_json::json!(surface.exports));
+            }
+            print_json(&serde_json::Value::Object(value))?;
+            return Ok(());
+        }
+
+        println!("core module");
+        if self.imports {
+            for import in &surface.imports {
+                println!("  import {}: {}", import.path(), import.kind);
+            }
+        }
+        if self.exports {
+            for export in &surface.exports {
+                println!("  export {}: {}", export.name, export.kind);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Print imports or exports as a tree of interfaces and their functions.
+fn print_items(kind: &str, items: &[SurfaceItem]) {
+    for item in items {
+        println!("  {kind} {}", item.name);
+        for function in &item.functions {
+            println!("    {}: {}", function.name, function.signature);
+        }
+    }
+}
+
+/// Collect the kind, name, size, and parent of `payload` and every module and
+/// component nested in it.
+fn module_sizes(payload: &Payload, parent: Option<&str>, out: &mut Vec<serde_json::Value>) {
+    let metadata = payload.metadata();
+    let kind = match payload {
+        Payload::Component { .. } => "component",
+        Payload::Module(_) => "module",
+    };
+    out.push(serde_json::json!({
+        "kind": kind,
+        "name": metadata.name,
+        "size": metadata.range.len(),
+        "parent": parent,
+    }));
+    if let Payload::Component { children, .. } = payload {
+        for child in children {
+            module_sizes(child, metadata.name.as_deref(), out);
+        }
+    }
+}
+
+/// The name and size in bytes of every custom section, including those of
+/// nested modules and components.
+fn custom_sections(bytes: &[u8]) -> Result<Vec<(String, u64)>> {
+    let mut sections = Vec::new();
+    for payload in wasmparser::Parser::new(0).parse_all(bytes) {
+        if let wasmparser::Payload::CustomSection(reader) = payload? {
+            sections.push((reader.name().to_string(), reader.data().len() as u64));
+        }
+    }
+    Ok(sections)
+}
+
+/// Format an import or export as `namespace:package/interface@version`.
+fn item_name(item: &ImportExportItem) -> String {
+    let mut name = item.package.clone();
+    if let Some(interface) = &item.interface {
+        name = format!("{name}/{interface}");
+    }
+    if let Some(version) = &item.version {
+        name = format!("{name}@{version}");
+    }
+    name
+}
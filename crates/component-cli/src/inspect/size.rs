@@ -0,0 +1,91 @@
+//! A size profile of a Wasm binary: where its bytes go, and what it imports.
+
+use std::collections::HashMap;
+
+use anyhow::Result;
+use wasmparser::{Parser, Payload};
+
+/// How many import modules the profile lists.
+const TOP_IMPORTS: usize = 10;
+
+/// The size profile of a Wasm binary.
+pub(super) struct SizeProfile {
+    /// The binary's size, in bytes.
+    pub(super) total: u64,
+    /// The size of each kind of section, largest first. Sections of nested
+    /// modules and components are added to the totals of their kind.
+    pub(super) sections: Vec<(String, u64)>,
+    /// The core modules imported from most often, with how many items each
+    /// provides, most first.
+    pub(super) imports: Vec<(String, u64)>,
+}
+
+/// Profile the sections and imports of `bytes`.
+pub(super) fn profile(bytes: &[u8]) -> Result<SizeProfile> {
+    let mut sections: HashMap<String, u64> = HashMap::new();
+    let mut imports: HashMap<String, u64> = HashMap::new();
+    for payload in Parser::new(0).parse_all(bytes) {
+        let payload = payload?;
+        if let Payload::ImportSection(reader) = &payload {
+            for import in reader.clone().into_imports() {
+                *imports.entry(import?.module.to_string()).or_default() += 1;
+            }
+        }
+        let Some(name) = section_name(&payload) else {
+            continue;
+        };
+        if let Some((_, range)) = payload.as_section() {
+            *sections.entry(name).or_default() += range.len() as u64;
+        }
+    }
+
+    let mut imports = sorted(imports);
+    imports.truncate(TOP_IMPORTS);
+    Ok(SizeProfile {
+        total: bytes.len() as u64,
+        sections: sorted(sections),
+        imports,
+    })
+}
+
+/// The entries of `counts`, largest first and then by name.
+fn sorted(counts: HashMap<String, u64>) -> Vec<(String, u64)> {
+    let mut counts: Vec<_> = counts.into_iter().collect();
+    counts.sort_by(|(a_name, a), (b_name, b)| b.cmp(a).then_with(|| a_name.cmp(b_name)));
+    counts
+}
+
+/// The name a section is profiled under.
+///
+/// Nested modules and components have no name: their own sections are
+/// profiled instead, so their bytes aren't counted twice.
+fn section_name(payload: &Payload<'_>) -> Option<String> {
+    let name = match payload {
+        Payload::TypeSection(_) => "type",
+        Payload::ImportSection(_) => "import",
+        Payload::FunctionSection(_) => "function",
+        Payload::TableSection(_) => "table",
+        Payload::MemorySection(_) => "memory",
+        Payload::TagSection(_) => "tag",
+        Payload::GlobalSection(_) => "global",
+        Payload::ExportSection(_) => "export",
+        Payload::StartSection { .. } => "start",
+        Payload::ElementSection(_) => "element",
+        Payload::DataCountSection { .. } => "data count",
+        Payload::DataSection(_) => "data",
+        Payload::CodeSectionStart { .. } => "code",
+        Payload::InstanceSection(_) => "core instance",
+        Payload::CoreTypeSection(_) => "core type",
+        Payload::ComponentInstanceSection(_) => "component instance",
+        Payload::ComponentAliasSection(_) => "component alias",
+        Payload::ComponentTypeSection(_) => "component type",
+        Payload::ComponentCanonicalSection(_) => "component canonical",
+        Payload::ComponentStartSection { .. } => "component start",
+        Payload::ComponentImportSection(_) => "component import",
+        Payload::ComponentExportSection(_) => "component export",
+        Payload::CustomSection(reader) => return Some(format!("custom \"{}\"", reader.name())),
+        Payload::UnknownSection { id, .. } => return Some(format!("unknown ({id})")),
+        _ => return None,
+    };
+    Some(name.to_string())
+}
@@ -33,6 +33,13 @@ pub(crate) enum InstallError {
         )
     )]
     DependencyConflict(String),
+
+    /// `--locked` was passed, but installing would change the lockfile.
+    #[diagnostic(
+        code(component::install::lockfile_needs_update),
+        help("run `component install` without `--locked` to update `wasm.lock.toml`")
+    )]
+    LockfileNeedsUpdate(String),
 }
 
 impl std::fmt::Display for InstallError {
@@ -44,6 +51,12 @@ impl std::fmt::Display for InstallError {
             InstallError::DependencyConflict(reason) => {
                 write!(f, "dependency conflict: {reason}")
             }
+            InstallError::LockfileNeedsUpdate(packages) => {
+                write!(
+                    f,
+                    "`wasm.lock.toml` needs to be updated for {packages}, but `--locked` was passed"
+                )
+            }
         }
     }
 }
@@ -83,5 +96,18 @@ mod tests {
             dep_conflict.help().is_some(),
             "DependencyConflict must have a help message"
         );
+
+        let needs_update = InstallError::LockfileNeedsUpdate("wasi:logging".to_string());
+        assert_eq!(
+            needs_update
+                .code()
+                .expect("LockfileNeedsUpdate must have a diagnostic code")
+                .to_string(),
+            "component::install::lockfile_needs_update",
+        );
+        assert!(
+            needs_update.help().is_some(),
+            "LockfileNeedsUpdate must have a help message"
+        );
     }
 }
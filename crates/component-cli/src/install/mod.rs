@@ -4,6 +4,7 @@ mod errors;
 mod progress_bar;
 
 use std::collections::{HashMap, HashSet};
+use std::io::IsTerminal;
 
 use component_package_manager::manager::{
     InstallResult, Manager, SyncPolicy, SyncResult, derive_component_name,
@@ -19,8 +20,8 @@ use futures_concurrency::prelude::*;
 use indicatif::MultiProgress;
 use miette::{IntoDiagnostic, WrapErr};
 
-use crate::util::write_lock_file;
-use errors::InstallError;
+use crate::util::{read_lock_file, write_lock_file};
+pub(crate) use errors::InstallError;
 use progress_bar::{
     InstallDisplay, oci_repo_display_name, package_display_parts, run_progress_bars,
 };
@@ -40,6 +41,9 @@ pub(crate) struct Opts {
     /// If no arguments are provided, installs all packages listed in the manifest.
     #[arg(value_name = "COMPONENT", num_args = 0..)]
     inputs: Vec<String>,
+    /// Fail instead of changing `wasm.lock.toml`, such as in CI
+    #[arg(long)]
+    locked: bool,
 }
 
 impl Opts {
@@ -48,7 +52,10 @@ impl Opts {
     /// Used by other commands (e.g., `component run`) that need to invoke the
     /// install logic without going through clap-based argument parsing.
     pub(crate) fn with_inputs(inputs: Vec<String>) -> Self {
-        Self { inputs }
+        Self {
+            inputs,
+            locked: false,
+        }
     }
 
     pub(crate) async fn run(self, offline: bool) -> miette::Result<()> {
@@ -71,18 +78,23 @@ impl Opts {
             toml::from_str(&manifest_str).into_diagnostic()?;
 
         // Read existing lockfile — create a fresh one when none exists yet.
-        let mut lockfile = match tokio::fs::read_to_string(&lockfile_path).await {
-            Ok(s) => toml::from_str::<component_manifest::Lockfile>(&s).into_diagnostic()?,
-            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
-                component_manifest::Lockfile::default()
-            }
-            Err(e) => {
-                return Err(miette::miette!(
-                    "could not read '{}': {e}",
-                    lockfile_path.display()
-                ));
+        let mut lockfile = read_lock_file(&lockfile_path)
+            .await
+            .map_err(crate::util::into_miette)?;
+        let locked_lockfile = self.locked.then(|| lockfile.clone());
+
+        // A dependency that isn't locked yet would have to be added, so
+        // `--locked` can reject it before anything is pulled.
+        if self.locked {
+            let unlocked: Vec<&str> = manifest
+                .all_dependencies()
+                .map(|(name, _, _)| name.as_str())
+                .filter(|name| !lockfile.all_packages().any(|(p, _)| p.name == *name))
+                .collect();
+            if !unlocked.is_empty() {
+                return Err(InstallError::LockfileNeedsUpdate(unlocked.join(", ")).into());
             }
-        };
+        }
 
         // Open manager
         let manager = if offline {
@@ -93,14 +105,19 @@ impl Opts {
             Manager::open().await.map_err(crate::util::into_miette)?
         };
 
-        // Shared progress display for all concurrent installs.
+        // Shared progress display for all concurrent installs. Progress bars
+        // only make sense on a terminal; elsewhere, such as in CI logs, each
+        // package gets a plain line instead, as it does offline.
+        let show_progress = !offline && std::io::stderr().is_terminal();
         let multi = MultiProgress::new();
         let display = std::sync::Arc::new(tokio::sync::Mutex::new(InstallDisplay::new(multi)));
 
         // Sync the local package index from the meta-registry so WIT-style
         // names and search-based lookups can be resolved.
         if !offline {
-            display.lock().await.start_sync();
+            if show_progress {
+                display.lock().await.start_sync();
+            }
             let sync_result = manager
                 .sync_from_meta_registry(REGISTRY_URL, SYNC_INTERVAL, SyncPolicy::IfStale)
                 .await;
@@ -121,7 +138,7 @@ impl Opts {
         let start_time = std::time::Instant::now();
 
         // Start the planning phase spinner.
-        if !offline {
+        if show_progress {
             display.lock().await.start_planning();
         }
 
@@ -255,9 +272,27 @@ impl Opts {
             .chain(transitive_installs)
             .collect();
 
+        // With `--locked`, find anything that would change the lockfile
+        // before a single package is pulled.
+        if self.locked {
+            let changed = locked_changes(&manager, &lockfile, &all_installs).await?;
+            if !changed.is_empty() {
+                return Err(InstallError::LockfileNeedsUpdate(changed.join(", ")).into());
+            }
+        }
+
+        // Packages are installed into a staging directory, and only copied
+        // into `vendor/` once the manifest and lockfile are written, so a
+        // failed install leaves the project as it was.
+        let staging = tempfile::tempdir()
+            .into_diagnostic()
+            .wrap_err("failed to create a staging directory")?;
+        let staged_wasm_dir = staging.path().join("wasm");
+        let staged_wit_dir = staging.path().join("wit");
+
         // Display the resolved plan and transition to the installing phase.
         // r[impl cli.progress-bar.plan-timing]
-        if !offline {
+        if show_progress {
             let plan_entries: Vec<(String, Option<String>)> = all_installs
                 .iter()
                 .map(PlannedInstall::display_info)
@@ -280,14 +315,14 @@ impl Opts {
             .into_co_stream()
             .map(|entry| {
                 let display = SharedDisplay::clone(&display);
-                let vendor_dir = wasm_vendor_dir.clone();
-                let wit_vendor_dir = wit_vendor_dir.clone();
+                let vendor_dir = staged_wasm_dir.clone();
+                let wit_vendor_dir = staged_wit_dir.clone();
                 async move {
                     let (display_name, version) = entry.display_info();
                     let install_result = install_one(
                         manager_ref,
                         &display,
-                        offline,
+                        show_progress,
                         entry.reference(),
                         &vendor_dir,
                         &display_name,
@@ -351,43 +386,142 @@ impl Opts {
             }
         }
 
-        // Write updated manifest
-        let manifest_str = toml::to_string_pretty(&manifest).into_diagnostic()?;
-        tokio::fs::write(&manifest_path, manifest_str.as_bytes())
-            .await
-            .into_diagnostic()?;
-
         // Resolve registry and digest for all dependency entries from their
         // matching top-level package entries. Dependency entries whose
         // packages are not in the lockfile (e.g. offline / skipped) are
         // silently removed.
         lockfile.resolve_dependency_details();
 
+        // With `--locked`, catch whatever else changed, such as a package's
+        // dependencies, while everything is still staged.
+        if let Some(before) = &locked_lockfile {
+            let changed = changed_packages(before, &lockfile);
+            if !changed.is_empty() {
+                return Err(InstallError::LockfileNeedsUpdate(changed.join(", ")).into());
+            }
+        }
+
+        // Write updated manifest
+        let manifest_str = toml::to_string_pretty(&manifest).into_diagnostic()?;
+        tokio::fs::write(&manifest_path, manifest_str.as_bytes())
+            .await
+            .into_diagnostic()?;
+
         // Write updated lockfile
         write_lock_file(&lockfile_path, &lockfile)
             .await
             .into_diagnostic()?;
 
+        copy_staged(&staged_wasm_dir, &wasm_vendor_dir).await?;
+        copy_staged(&staged_wit_dir, &wit_vendor_dir).await?;
+
         // Display the final completion summary.
         let elapsed = start_time.elapsed();
-        if offline {
-            // Offline mode never starts the phased display — print a plain
-            // summary line so the user still sees the result.
+        if show_progress {
+            let mut d = display.lock().await;
+            let completed_count = d.completed_count();
+            d.finish_all(completed_count, elapsed);
+        } else {
+            // Without progress bars the phased display never starts — print
+            // a plain summary line so the user still sees the result.
             println!(
                 "{} Installed in {:.1}s",
                 console::style("✓").green().bold(),
                 elapsed.as_secs_f64()
             );
-        } else {
-            let mut d = display.lock().await;
-            let completed_count = d.completed_count();
-            d.finish_all(completed_count, elapsed);
         }
 
         Ok(())
     }
 }
 
+/// The packages among `installs` that would change `lockfile`: those that
+/// aren't locked yet, and those whose reference now resolves to another
+/// digest than the locked one. Only manifests are fetched, nothing is pulled.
+async fn locked_changes(
+    manager: &Manager,
+    lockfile: &component_manifest::Lockfile,
+    installs: &[PlannedInstall],
+) -> miette::Result<Vec<String>> {
+    let mut changed = Vec::new();
+    for install in installs {
+        let (reference, name) = match install {
+            PlannedInstall::TopLevel {
+                reference,
+                explicit_name,
+                ..
+            } => (reference, explicit_name.as_deref()),
+            // Transitive dependencies are only planned when they aren't
+            // locked yet.
+            PlannedInstall::Transitive { package_name, .. } => {
+                changed.push(package_name.clone());
+                continue;
+            }
+        };
+        let repository = format!("{}/{}", reference.registry(), reference.repository());
+        let locked = lockfile
+            .all_packages()
+            .map(|(p, _)| p)
+            .find(|p| name.map_or(p.registry == repository, |name| p.name == name));
+        let Some(locked) = locked else {
+            changed.push(name.map_or_else(|| reference.whole(), str::to_string));
+            continue;
+        };
+        let digest = manager
+            .resolve_digest(reference)
+            .await
+            .map_err(crate::util::into_miette)?;
+        if digest != locked.digest {
+            changed.push(locked.name.clone());
+        }
+    }
+    changed.sort();
+    changed.dedup();
+    Ok(changed)
+}
+
+/// Copy the files installed into `staged` to `dir`.
+async fn copy_staged(staged: &std::path::Path, dir: &std::path::Path) -> miette::Result<()> {
+    let mut entries = match tokio::fs::read_dir(staged).await {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e).into_diagnostic(),
+    };
+    tokio::fs::create_dir_all(dir)
+        .await
+        .into_diagnostic()
+        .wrap_err_with(|| format!("failed to create {}", dir.display()))?;
+    while let Some(entry) = entries.next_entry().await.into_diagnostic()? {
+        let dest = dir.join(entry.file_name());
+        tokio::fs::copy(entry.path(), &dest)
+            .await
+            .into_diagnostic()
+            .wrap_err_with(|| format!("failed to write {}", dest.display()))?;
+    }
+    Ok(())
+}
+
+/// The names of packages that differ between two lockfiles: added,
+/// removed, or locked to something else. Sorted and deduplicated.
+fn changed_packages(
+    before: &component_manifest::Lockfile,
+    after: &component_manifest::Lockfile,
+) -> Vec<String> {
+    let in_both = |package: &component_manifest::LockedPackage| {
+        before.all_packages().any(|(p, _)| p == package)
+            && after.all_packages().any(|(p, _)| p == package)
+    };
+    let mut changed: Vec<String> = before
+        .all_packages()
+        .chain(after.all_packages())
+        .filter(|(package, _)| !in_both(package))
+        .map(|(package, _)| package.name.clone())
+        .collect();
+    changed.sort();
+    changed.dedup();
+    changed
+}
+
 /// Shared handle to an [`InstallDisplay`] for use across concurrent tasks.
 type SharedDisplay = std::sync::Arc<tokio::sync::Mutex<InstallDisplay>>;
 
@@ -457,23 +591,23 @@ impl PlannedInstall {
 
 /// Install a single package and report progress.
 ///
-/// In offline mode a plain status line is printed. In online mode a
-/// progress bar is created for the package showing aggregated download
-/// progress across all layers.
+/// Without `show_progress` (offline, or when stderr isn't a terminal) a
+/// plain status line is printed. Otherwise a progress bar is created for the
+/// package showing aggregated download progress across all layers.
 async fn install_one(
     manager: &Manager,
     display: &SharedDisplay,
-    offline: bool,
+    show_progress: bool,
     reference: &Reference,
     vendor_dir: &std::path::Path,
     display_name: &str,
     display_version: Option<&str>,
 ) -> anyhow::Result<InstallResult> {
-    if offline {
-        // No progress bars in offline mode — print a simple status line.
+    if !show_progress {
+        // No progress bars — print a simple status line.
         let version_str = display_version.map(|v| format!(" {v}")).unwrap_or_default();
         println!("{display_name}{version_str}");
-        return manager.install(reference.clone(), vendor_dir).await;
+        return Ok(manager.install(reference.clone(), vendor_dir).await?);
     }
 
     let (progress_tx, progress_rx) = tokio::sync::mpsc::channel::<ProgressEvent>(64);
@@ -498,27 +632,23 @@ async fn install_one(
         display.lock().await.finish_bar(bar_id);
     }
 
-    result
+    Ok(result?)
 }
 
-/// Process a top-level install result: update the manifest (if requested)
-/// and upsert the lockfile entry.  Returns the result's dependency list
-/// so the caller can handle any unplanned transitive deps.
-fn process_top_level_result(
-    result: InstallResult,
-    update_manifest: bool,
+/// The name an installed package goes by in the manifest.
+///
+/// When the user provided an explicit WIT-style name (e.g.
+/// `ba:sample-wasi-http-rust`), use that directly — the embedded
+/// WIT metadata may contain a placeholder like `root:component`.
+/// Otherwise, for components use `derive_component_name` which
+/// tries WIT metadata, OCI title, last repository segment, then
+/// full path.  For interfaces, use the WIT package name.
+pub(crate) fn dependency_name(
+    result: &InstallResult,
     explicit_name: Option<String>,
-    manifest: &mut component_manifest::Manifest,
-    lockfile: &mut component_manifest::Lockfile,
-) -> Vec<DependencyItem> {
-    // Derive the dependency name.
-    // When the user provided an explicit WIT-style name (e.g.
-    // `ba:sample-wasi-http-rust`), use that directly — the embedded
-    // WIT metadata may contain a placeholder like `root:component`.
-    // Otherwise, for components use `derive_component_name` which
-    // tries WIT metadata, OCI title, last repository segment, then
-    // full path.  For interfaces, use the WIT package name.
-    let dep_name = if let Some(name) = explicit_name {
+    manifest: &component_manifest::Manifest,
+) -> String {
+    if let Some(name) = explicit_name {
         name
     } else if result.is_component {
         let existing_names: HashSet<String> = manifest
@@ -539,7 +669,20 @@ fn process_top_level_result(
             || format!("{}/{}", result.registry, result.repository),
             |name| name.split('@').next().unwrap_or(name).to_string(),
         )
-    };
+    }
+}
+
+/// Process a top-level install result: update the manifest (if requested)
+/// and upsert the lockfile entry.  Returns the result's dependency list
+/// so the caller can handle any unplanned transitive deps.
+pub(crate) fn process_top_level_result(
+    result: InstallResult,
+    update_manifest: bool,
+    explicit_name: Option<String>,
+    manifest: &mut component_manifest::Manifest,
+    lockfile: &mut component_manifest::Lockfile,
+) -> Vec<DependencyItem> {
+    let dep_name = dependency_name(&result, explicit_name, manifest);
 
     // Determine the version from the tag
     let version = result.tag.clone().unwrap_or_default();
@@ -788,4 +931,37 @@ mod tests {
         // Original .wasm should still be present (not moved).
         assert!(wasm_path.exists(), "component .wasm should be untouched");
     }
+
+    #[test]
+    fn changed_packages_lists_added_removed_and_relocked() {
+        let locked = |name: &str, digest: &str| component_manifest::LockedPackage {
+            name: name.to_string(),
+            version: "1.0.0".to_string(),
+            registry: format!("ghcr.io/example/{name}"),
+            digest: digest.to_string(),
+            dependencies: Vec::new(),
+        };
+        let before = component_manifest::Lockfile {
+            interfaces: vec![
+                locked("wasi:clocks", "sha256:a"),
+                locked("wasi:io", "sha256:b"),
+                locked("wasi:logging", "sha256:c"),
+            ],
+            ..Default::default()
+        };
+        let after = component_manifest::Lockfile {
+            interfaces: vec![
+                locked("wasi:clocks", "sha256:a"),
+                locked("wasi:logging", "sha256:d"),
+                locked("wasi:http", "sha256:e"),
+            ],
+            ..Default::default()
+        };
+
+        assert!(super::changed_packages(&before, &before).is_empty());
+        assert_eq!(
+            super::changed_packages(&before, &after),
+            ["wasi:http", "wasi:io", "wasi:logging"]
+        );
+    }
 }
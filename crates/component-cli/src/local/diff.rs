@@ -0,0 +1,283 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use comfy_table::{modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL};
+use component_package_manager::format_size;
+use component_package_manager::types;
+
+use crate::output::{Listing, OutputFormat};
+
+/// Compare the imports, exports, and sizes of two Wasm files
+#[derive(clap::Args)]
+pub(crate) struct DiffOpts {
+    /// The earlier build
+    old: PathBuf,
+
+    /// The later build
+    new: PathBuf,
+}
+
+/// How an import, export, or function changed between the two files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Change {
+    Added,
+    Removed,
+    Changed,
+}
+
+impl Change {
+    fn as_str(self) -> &'static str {
+        match self {
+            Change::Added => "added",
+            Change::Removed => "removed",
+            Change::Changed => "changed",
+        }
+    }
+}
+
+/// One difference between the surfaces of the two files.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+struct SurfaceChange {
+    change: Change,
+    /// `import` or `export`.
+    direction: &'static str,
+    /// The interface or freestanding function.
+    item: String,
+    /// The function within the interface, when the change is to a single
+    /// function rather than the whole item.
+    function: Option<String>,
+    /// The signature in the old file, for functions.
+    old: Option<String>,
+    /// The signature in the new file, for functions.
+    new: Option<String>,
+}
+
+/// The functions of every import or export, by direction and item name.
+type Surface = BTreeMap<(&'static str, String), BTreeMap<String, String>>;
+
+impl DiffOpts {
+    #[allow(clippy::print_stdout)]
+    pub(crate) fn run(&self, format: OutputFormat) -> Result<()> {
+        let old_bytes = std::fs::read(&self.old)
+            .with_context(|| format!("failed to read {}", self.old.display()))?;
+        let new_bytes = std::fs::read(&self.new)
+            .with_context(|| format!("failed to read {}", self.new.display()))?;
+        let old = surface(&old_bytes)
+            .with_context(|| format!("{} is not a component", self.old.display()))?;
+        let new = surface(&new_bytes)
+            .with_context(|| format!("{} is not a component", self.new.display()))?;
+        let changes = diff(&old, &new);
+
+        let old_size = old_bytes.len() as u64;
+        let new_size = new_bytes.len() as u64;
+        let mut listing = Listing::new(["CHANGE", "KIND", "NAME", "FUNCTION", "OLD", "NEW"]);
+        for change in &changes {
+            listing.push([
+                change.change.as_str().to_string(),
+                change.direction.to_string(),
+                change.item.clone(),
+                change.function.clone().unwrap_or_default(),
+                change.old.clone().unwrap_or_default(),
+                change.new.clone().unwrap_or_default(),
+            ]);
+        }
+
+        if format == OutputFormat::Table {
+            println!(
+                "Size: {} → {} ({})",
+                format_size(old_size),
+                format_size(new_size),
+                size_delta(old_size, new_size)
+            );
+            if listing.is_empty() {
+                println!("No changes to imports or exports");
+            } else {
+                let mut table = listing.table();
+                table
+                    .load_preset(UTF8_FULL)
+                    .apply_modifier(UTF8_ROUND_CORNERS);
+                println!("{table}");
+            }
+            return Ok(());
+        }
+        listing.print(format, "", || {
+            serde_json::json!({
+                "size": { "old": old_size, "new": new_size },
+                "changes": changes,
+            })
+        })
+    }
+}
+
+/// The import and export surface of a component or WIT package, with the
+/// worlds merged, as builds of the same component may name theirs
+/// differently.
+fn surface(bytes: &[u8]) -> Option<Surface> {
+    let mut surface = Surface::new();
+    for world in types::extract_world_surface(bytes)? {
+        for (direction, items) in [("import", world.imports), ("export", world.exports)] {
+            for item in items {
+                let functions = item
+                    .functions
+                    .into_iter()
+                    .map(|function| (function.name, function.signature));
+                surface
+                    .entry((direction, item.name))
+                    .or_default()
+                    .extend(functions);
+            }
+        }
+    }
+    Some(surface)
+}
+
+/// The imports, exports, and functions added, removed, or changed between
+/// two surfaces, imports first, then by name.
+fn diff(old: &Surface, new: &Surface) -> Vec<SurfaceChange> {
+    let item_change = |change, (direction, item): &(&'static str, String)| SurfaceChange {
+        change,
+        direction,
+        item: item.clone(),
+        function: None,
+        old: None,
+        new: None,
+    };
+    let mut changes = Vec::new();
+    for (key, new_functions) in new {
+        let Some(old_functions) = old.get(key) else {
+            changes.push(item_change(Change::Added, key));
+            continue;
+        };
+        let names: BTreeSet<&String> = old_functions.keys().chain(new_functions.keys()).collect();
+        for name in names {
+            let (before, after) = (old_functions.get(name), new_functions.get(name));
+            let change = match (before, after) {
+                (None, Some(_)) => Change::Added,
+                (Some(_), None) => Change::Removed,
+                (Some(before), Some(after)) if before != after => Change::Changed,
+                _ => continue,
+            };
+            changes.push(SurfaceChange {
+                function: Some(name.clone()),
+                old: before.cloned(),
+                new: after.cloned(),
+                ..item_change(change, key)
+            });
+        }
+    }
+    for key in old.keys().filter(|key| !new.contains_key(*key)) {
+        changes.push(item_change(Change::Removed, key));
+    }
+    changes.sort_by(|a, b| {
+        (a.direction != "import", &a.item, &a.function).cmp(&(
+            b.direction != "import",
+            &b.item,
+            &b.function,
+        ))
+    });
+    changes
+}
+
+/// The difference between two sizes, e.g. `+1.2 KB, +15%`.
+fn size_delta(old: u64, new: u64) -> String {
+    let (sign, delta) = if new >= old {
+        ("+", new - old)
+    } else {
+        ("-", old - new)
+    };
+    match delta.saturating_mul(100).checked_div(old) {
+        Some(percent) => format!("{sign}{}, {sign}{percent}%", format_size(delta)),
+        None => format!("{sign}{}", format_size(delta)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A direction, an item name and its function signatures.
+    type Item<'a> = (&'static str, &'a str, &'a [(&'a str, &'a str)]);
+
+    fn surface(items: &[Item<'_>]) -> Surface {
+        items
+            .iter()
+            .map(|(direction, item, functions)| {
+                let functions = functions
+                    .iter()
+                    .map(|(name, signature)| (name.to_string(), signature.to_string()))
+                    .collect();
+                ((*direction, item.to_string()), functions)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn diff_reports_items_and_function_signatures() {
+        let old = surface(&[
+            (
+                "import",
+                "wasi:cli/stdout@0.2.0",
+                &[("get-stdout", "func()")],
+            ),
+            ("export", "run", &[("run", "func()")]),
+            (
+                "export",
+                "example:app/api",
+                &[("greet", "func() -> string"), ("old", "func()")],
+            ),
+        ]);
+        let new = surface(&[
+            ("import", "wasi:http/types@0.2.0", &[]),
+            ("export", "run", &[("run", "func()")]),
+            (
+                "export",
+                "example:app/api",
+                &[("greet", "func(name: string) -> string"), ("new", "func()")],
+            ),
+        ]);
+
+        let changes: Vec<_> = diff(&old, &new)
+            .into_iter()
+            .map(|c| (c.change, c.direction, c.item, c.function))
+            .collect();
+        assert_eq!(
+            changes,
+            [
+                (
+                    Change::Removed,
+                    "import",
+                    "wasi:cli/stdout@0.2.0".into(),
+                    None
+                ),
+                (
+                    Change::Added,
+                    "import",
+                    "wasi:http/types@0.2.0".into(),
+                    None
+                ),
+                (
+                    Change::Changed,
+                    "export",
+                    "example:app/api".into(),
+                    Some("greet".into())
+                ),
+                (
+                    Change::Added,
+                    "export",
+                    "example:app/api".into(),
+                    Some("new".into())
+                ),
+                (
+                    Change::Removed,
+                    "export",
+                    "example:app/api".into(),
+                    Some("old".into())
+                ),
+            ]
+        );
+        assert!(diff(&new, &new).is_empty());
+        assert_eq!(size_delta(100, 150), format!("+{}, +50%", format_size(50)));
+    }
+}
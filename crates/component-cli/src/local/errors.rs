@@ -0,0 +1,151 @@
+//! Error types for `component local` subcommands.
+
+use miette::Diagnostic;
+
+/// Error type for picking a detected file in `component local` subcommands.
+///
+/// Each variant carries a stable [diagnostic error code][miette::Diagnostic::code]
+/// that uniquely identifies the failure.
+#[derive(Debug, Clone, PartialEq, Eq, Diagnostic)]
+#[must_use]
+pub(crate) enum LocalError {
+    /// The number is outside the listing.
+    #[diagnostic(
+        code(component::local::no_such_index),
+        help("run `component local list` to see the numbered files")
+    )]
+    NoSuchIndex {
+        /// The number that was passed.
+        index: usize,
+        /// How many files were found.
+        count: usize,
+    },
+
+    /// No detected file has this name or path.
+    #[diagnostic(
+        code(component::local::no_match),
+        help("run `component local list` to see the detected files")
+    )]
+    NoMatch {
+        /// The name that was passed.
+        name: String,
+    },
+
+    /// More than one detected file has this name.
+    #[diagnostic(
+        code(component::local::ambiguous),
+        help("pass the file's number or more of its path: {candidates}")
+    )]
+    Ambiguous {
+        /// The name that was passed.
+        name: String,
+        /// The paths of the matching files, comma-separated.
+        candidates: String,
+    },
+
+    /// No component was found in the build output directories.
+    #[diagnostic(
+        code(component::local::no_build_output),
+        help("build the component first, e.g. `cargo build --target wasm32-wasip2 --release`")
+    )]
+    NoBuildOutput {
+        /// The directory that was searched.
+        path: String,
+    },
+}
+
+impl std::fmt::Display for LocalError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LocalError::NoSuchIndex { index, count: 1 } => {
+                write!(f, "there is no file #{index}; only 1 Wasm file was found")
+            }
+            LocalError::NoSuchIndex { index, count } => {
+                write!(
+                    f,
+                    "there is no file #{index}; {count} Wasm files were found"
+                )
+            }
+            LocalError::NoMatch { name } => {
+                write!(f, "no detected Wasm file matches '{name}'")
+            }
+            LocalError::Ambiguous { name, .. } => {
+                write!(f, "more than one detected Wasm file matches '{name}'")
+            }
+            LocalError::NoBuildOutput { path } => {
+                write!(f, "no built component was found under {path}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for LocalError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_all_variants_have_error_codes() {
+        use miette::Diagnostic;
+
+        let no_such_index = LocalError::NoSuchIndex { index: 3, count: 2 };
+        assert_eq!(
+            no_such_index
+                .code()
+                .expect("NoSuchIndex must have a diagnostic code")
+                .to_string(),
+            "component::local::no_such_index",
+        );
+        assert!(
+            no_such_index.help().is_some(),
+            "NoSuchIndex must have a help message"
+        );
+
+        let no_match = LocalError::NoMatch {
+            name: "app.wasm".to_string(),
+        };
+        assert_eq!(
+            no_match
+                .code()
+                .expect("NoMatch must have a diagnostic code")
+                .to_string(),
+            "component::local::no_match",
+        );
+        assert!(
+            no_match.help().is_some(),
+            "NoMatch must have a help message"
+        );
+
+        let ambiguous = LocalError::Ambiguous {
+            name: "app.wasm".to_string(),
+            candidates: "a/app.wasm, b/app.wasm".to_string(),
+        };
+        assert_eq!(
+            ambiguous
+                .code()
+                .expect("Ambiguous must have a diagnostic code")
+                .to_string(),
+            "component::local::ambiguous",
+        );
+        assert!(
+            ambiguous.help().is_some(),
+            "Ambiguous must have a help message"
+        );
+
+        let no_build_output = LocalError::NoBuildOutput {
+            path: ".".to_string(),
+        };
+        assert_eq!(
+            no_build_output
+                .code()
+                .expect("NoBuildOutput must have a diagnostic code")
+                .to_string(),
+            "component::local::no_build_output",
+        );
+        assert!(
+            no_build_output.help().is_some(),
+            "NoBuildOutput must have a help message"
+        );
+    }
+}
@@ -0,0 +1,104 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use component_detector::WasmEntry;
+
+use super::errors::LocalError;
+use crate::output::OutputFormat;
+
+/// Inspect a file from `local list` by its number or name.
+#[derive(clap::Args)]
+pub(crate) struct InspectOpts {
+    /// The file's number in `component local list`, or its file name or the
+    /// end of its path (e.g. `2`, `app.wasm`, or `release/app.wasm`)
+    file: String,
+
+    /// Directory the listing was made in (defaults to current directory)
+    #[arg(long, default_value = ".")]
+    path: PathBuf,
+
+    /// Include hidden files and directories, as with `local list --hidden`
+    #[arg(long)]
+    hidden: bool,
+
+    /// Follow symbolic links, as with `local list --follow-links`
+    #[arg(long)]
+    follow_links: bool,
+
+    #[command(flatten)]
+    view: crate::inspect::View,
+}
+
+impl InspectOpts {
+    #[allow(clippy::print_stderr)]
+    pub(crate) fn run(&self, format: OutputFormat) -> Result<()> {
+        let files = super::detect(&self.path, self.hidden, self.follow_links);
+        let entry = select(&files, &self.file)?;
+        let input = entry.path().display().to_string();
+        // On stderr, so JSON on stdout stays parseable.
+        eprintln!("Inspecting {input}");
+
+        let bytes =
+            std::fs::read(entry.path()).with_context(|| format!("could not read '{input}'"))?;
+        self.view.show(&input, &bytes, format)
+    }
+}
+
+/// Pick a file by its 1-based number in the listing, or by its name or the
+/// end of its path.
+fn select<'a>(files: &'a [WasmEntry], file: &str) -> Result<&'a WasmEntry, LocalError> {
+    if let Ok(index) = file.parse::<usize>() {
+        return index.checked_sub(1).and_then(|idx| files.get(idx)).ok_or(
+            LocalError::NoSuchIndex {
+                index,
+                count: files.len(),
+            },
+        );
+    }
+    let matches: Vec<&WasmEntry> = files
+        .iter()
+        .filter(|entry| entry.path().ends_with(file))
+        .collect();
+    match matches.as_slice() {
+        [entry] => Ok(entry),
+        [] => Err(LocalError::NoMatch {
+            name: file.to_string(),
+        }),
+        entries => Err(LocalError::Ambiguous {
+            name: file.to_string(),
+            candidates: entries
+                .iter()
+                .map(|entry| entry.path().display().to_string())
+                .collect::<Vec<_>>()
+                .join(", "),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn files_are_picked_by_number_or_path_suffix() {
+        let files: Vec<WasmEntry> = ["dist/app.wasm", "target/a/app.wasm", "target/b/lib.wasm"]
+            .into_iter()
+            .map(|path| WasmEntry::new(PathBuf::from(path)))
+            .collect();
+        let picked = |file: &str| select(&files, file).map(|entry| entry.path().to_path_buf());
+
+        assert_eq!(picked("2"), Ok(PathBuf::from("target/a/app.wasm")));
+        assert_eq!(picked("lib.wasm"), Ok(PathBuf::from("target/b/lib.wasm")));
+        assert_eq!(picked("a/app.wasm"), Ok(PathBuf::from("target/a/app.wasm")));
+        assert!(matches!(
+            picked("0"),
+            Err(LocalError::NoSuchIndex { index: 0, count: 3 })
+        ));
+        assert!(matches!(picked("4"), Err(LocalError::NoSuchIndex { .. })));
+        assert!(matches!(
+            picked("app.wasm"),
+            Err(LocalError::Ambiguous { .. })
+        ));
+        assert!(matches!(picked("pp.wasm"), Err(LocalError::NoMatch { .. })));
+    }
+}
@@ -1,16 +1,38 @@
 #![allow(clippy::print_stdout, clippy::print_stderr)]
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use comfy_table::{Table, modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL};
+use chrono::{DateTime, Local, SecondsFormat, Utc};
+use comfy_table::{modifiers::UTF8_ROUND_CORNERS, presets::UTF8_FULL};
+use component_detector::WasmEntry;
+use component_package_manager::format_size;
 use component_package_manager::manager::Manager;
 
+use crate::output::{Listing, OutputFormat};
+
+mod diff;
+mod errors;
+mod inspect;
+mod publish;
+mod watch;
+
+pub(crate) use errors::LocalError;
+
 /// Detect and manage local WASM files
 #[derive(clap::Parser)]
 pub(crate) enum Opts {
     /// List local WASM files in the current directory
     List(ListOpts),
-    /// Remove the lockfile and vendored dependencies
+    /// Inspect a file from `local list` by its number or name
+    Inspect(inspect::InspectOpts),
+    /// Compare the imports, exports, and sizes of two Wasm files
+    Diff(diff::DiffOpts),
+    /// Watch for Wasm files being added, removed, or rebuilt
+    Watch(watch::WatchOpts),
+    /// Publish the newest built component with the metadata in `wasm.toml`
+    Publish(publish::PublishOpts),
+    /// Remove the lockfile and vendored dependencies, and optionally build
+    /// outputs
     Clean(CleanOpts),
 }
 
@@ -35,74 +57,206 @@ pub(crate) struct CleanOpts {
     /// Directory to clean (defaults to current directory)
     #[arg(default_value = ".")]
     path: PathBuf,
+
+    /// Also delete the `.wasm` files in build output directories:
+    /// `target/wasm32-*/`, `pkg/`, and `dist/`
+    #[arg(long)]
+    build: bool,
+
+    /// Delete build outputs without asking for confirmation
+    #[arg(short, long)]
+    yes: bool,
+
+    /// Only list what would be removed
+    #[arg(long, conflicts_with = "yes")]
+    dry_run: bool,
 }
 
 impl Opts {
-    pub(crate) fn run(self) {
+    pub(crate) async fn run(self, offline: bool, format: OutputFormat) -> anyhow::Result<()> {
         match self {
-            Opts::List(opts) => opts.run(),
+            Opts::List(opts) => opts.run(format),
+            Opts::Inspect(opts) => opts.run(format),
+            Opts::Diff(opts) => opts.run(format),
+            Opts::Watch(opts) => opts.run(format),
+            Opts::Publish(opts) => opts.run(offline).await,
             Opts::Clean(opts) => opts.run(),
         }
     }
 }
 
 impl ListOpts {
-    fn run(&self) {
-        let mut wasm_files = Manager::detect_local_wasm(&self.path, self.hidden, self.follow_links);
+    fn run(&self, format: OutputFormat) -> anyhow::Result<()> {
+        let wasm_files = detect(&self.path, self.hidden, self.follow_links);
+        let files: Vec<LocalFile> = wasm_files.iter().map(LocalFile::new).collect();
 
-        if wasm_files.is_empty() {
-            println!("No WASM files found in {}", self.path.display());
-            return;
+        // Tables are for people; every other format is for scripts, which
+        // get sizes in bytes and times in RFC 3339.
+        let exact = format != OutputFormat::Table;
+        let mut listing = Listing::new(["#", "File Path", "Kind", "Size", "Modified"]);
+        for (idx, file) in files.iter().enumerate() {
+            let (size, modified) = if exact {
+                (
+                    file.size.to_string(),
+                    file.modified
+                        .map(|time| time.to_rfc3339_opts(SecondsFormat::Secs, true)),
+                )
+            } else {
+                (
+                    format_size(file.size),
+                    file.modified.map(|time| {
+                        time.with_timezone(&Local)
+                            .format("%Y-%m-%d %H:%M")
+                            .to_string()
+                    }),
+                )
+            };
+            listing.push([
+                format!("{}", idx + 1),
+                file.path.display().to_string(),
+                file.kind.clone().unwrap_or_else(|| "-".to_string()),
+                size,
+                modified.unwrap_or_else(|| "-".to_string()),
+            ]);
         }
 
-        // Sort by path for consistent output
-        wasm_files.sort_by(|a, b| a.path().cmp(b.path()));
+        match format {
+            OutputFormat::Table if !listing.is_empty() => {
+                let mut table = listing.table();
+                table
+                    .load_preset(UTF8_FULL)
+                    .apply_modifier(UTF8_ROUND_CORNERS);
+                println!("{table}");
+                println!("\nFound {} WASM file(s)", wasm_files.len());
+                Ok(())
+            }
+            _ => listing.print(
+                format,
+                &format!("No WASM files found in {}", self.path.display()),
+                || serde_json::json!(files),
+            ),
+        }
+    }
+}
 
-        // Create a table for nice output
-        let mut table = Table::new();
-        table
-            .load_preset(UTF8_FULL)
-            .apply_modifier(UTF8_ROUND_CORNERS)
-            .set_header(vec!["#", "File Path"]);
+/// Detect the Wasm files under `path`, sorted by path so that `local list`
+/// numbers them the same way every time.
+fn detect(path: &Path, hidden: bool, follow_links: bool) -> Vec<WasmEntry> {
+    let mut wasm_files = Manager::detect_local_wasm(path, hidden, follow_links);
+    wasm_files.sort_by(|a, b| a.path().cmp(b.path()));
+    wasm_files
+}
 
-        for (idx, entry) in wasm_files.iter().enumerate() {
-            table.add_row(vec![
-                format!("{}", idx + 1),
-                entry.path().display().to_string(),
-            ]);
-        }
+/// A detected Wasm file, with what `local list` shows about it.
+#[derive(serde::Serialize)]
+struct LocalFile {
+    path: PathBuf,
+    /// Whether the file is a component or a core module, if it's a Wasm
+    /// binary at all.
+    kind: Option<String>,
+    /// The size in bytes.
+    size: u64,
+    modified: Option<DateTime<Utc>>,
+}
 
-        println!("{table}");
-        println!("\nFound {} WASM file(s)", wasm_files.len());
+impl LocalFile {
+    /// Read what's known about `entry`. Files that can't be read are still
+    /// listed, without a kind, size, or modification time.
+    fn new(entry: &WasmEntry) -> Self {
+        let metadata = std::fs::metadata(entry.path()).ok();
+        Self {
+            path: entry.path().to_path_buf(),
+            kind: entry.kind().ok().flatten().map(|kind| kind.to_string()),
+            size: metadata.as_ref().map_or(0, std::fs::Metadata::len),
+            modified: metadata
+                .and_then(|metadata| metadata.modified().ok())
+                .map(DateTime::<Utc>::from),
+        }
     }
 }
 
 impl CleanOpts {
-    fn run(&self) {
+    fn run(&self) -> anyhow::Result<()> {
+        let mut paths = Vec::new();
         let lockfile = self.path.join("wasm.lock.toml");
-        let vendor_wasm = self.path.join("vendor/wasm");
-        let vendor_wit = self.path.join("vendor/wit");
+        if lockfile.exists() {
+            paths.push(lockfile);
+        }
+        paths.extend(dir_contents(&self.path.join("vendor/wasm")));
+        paths.extend(dir_contents(&self.path.join("vendor/wit")));
+        let build_outputs = if self.build {
+            build_outputs(&self.path)
+                .into_iter()
+                .map(WasmEntry::into_path)
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let build_size: u64 = build_outputs
+            .iter()
+            .filter_map(|path| std::fs::metadata(path).ok())
+            .map(|metadata| metadata.len())
+            .sum();
 
-        remove_file(&lockfile);
-        remove_dir_contents(&vendor_wasm);
-        remove_dir_contents(&vendor_wit);
+        if self.dry_run {
+            for path in paths.iter().chain(&build_outputs) {
+                println!(
+                    "{:>12} {}",
+                    console::style("Would remove").yellow().bold(),
+                    path.display()
+                );
+            }
+            if !build_outputs.is_empty() {
+                println!("\nWould free {} of build outputs", format_size(build_size));
+            }
+            return Ok(());
+        }
+        if !build_outputs.is_empty() && !self.yes {
+            let prompt = format!(
+                "Delete {} build output(s), {}?",
+                build_outputs.len(),
+                format_size(build_size)
+            );
+            if !crate::util::confirm(&prompt)? {
+                println!("Nothing removed");
+                return Ok(());
+            }
+        }
 
+        for path in paths.iter().chain(&build_outputs) {
+            remove(path);
+        }
         println!(
             "{:>12} local build artifacts",
             console::style("Cleaned").green().bold(),
         );
+        Ok(())
     }
 }
 
-/// Remove a file if it exists, printing what was removed.
-fn remove_file(path: &std::path::Path) {
-    match std::fs::remove_file(path) {
+/// The detected `.wasm` files in the well-known build output directories
+/// under `root`.
+fn build_outputs(root: &Path) -> Vec<WasmEntry> {
+    let dirs = component_detector::WasmDetector::new(root).well_known_dirs();
+    detect(root, false, false)
+        .into_iter()
+        .filter(|entry| dirs.iter().any(|dir| entry.path().starts_with(dir)))
+        .collect()
+}
+
+/// Remove a file or directory, printing what was removed.
+fn remove(path: &Path) {
+    let result = if path.is_dir() {
+        std::fs::remove_dir_all(path)
+    } else {
+        std::fs::remove_file(path)
+    };
+    match result {
         Ok(()) => println!(
             "{:>12} {}",
             console::style("Removed").red().bold(),
             path.display()
         ),
-        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
         Err(e) => eprintln!(
             "{:>12} failed to remove {}: {e}",
             console::style("Warning").yellow().bold(),
@@ -111,11 +265,11 @@ fn remove_file(path: &std::path::Path) {
     }
 }
 
-/// Remove all contents of a directory (but keep the directory itself).
+/// The contents of a directory, to remove while keeping the directory itself.
 ///
-/// If `dir` is a symlink the function warns and returns without
+/// If `dir` is a symlink the function warns and returns nothing without
 /// traversing, preventing accidental deletion outside the project tree.
-fn remove_dir_contents(dir: &std::path::Path) {
+fn dir_contents(dir: &Path) -> Vec<PathBuf> {
     // Guard: refuse to traverse symlinks so we never delete outside the project.
     match std::fs::symlink_metadata(dir) {
         Ok(meta) if meta.is_symlink() => {
@@ -124,7 +278,7 @@ fn remove_dir_contents(dir: &std::path::Path) {
                 console::style("Warning").yellow().bold(),
                 dir.display()
             );
-            return;
+            return Vec::new();
         }
         // Not a symlink — proceed. If the path doesn't exist, fall through
         // to read_dir which handles the NotFound case.
@@ -133,46 +287,28 @@ fn remove_dir_contents(dir: &std::path::Path) {
 
     let entries = match std::fs::read_dir(dir) {
         Ok(entries) => entries,
-        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Vec::new(),
         Err(e) => {
             eprintln!(
                 "{:>12} failed to read {}: {e}",
                 console::style("Warning").yellow().bold(),
                 dir.display()
             );
-            return;
+            return Vec::new();
         }
     };
 
+    let mut paths = Vec::new();
     for entry in entries {
-        let entry = match entry {
-            Ok(entry) => entry,
-            Err(e) => {
-                eprintln!(
-                    "{:>12} failed to read entry in {}: {e}",
-                    console::style("Warning").yellow().bold(),
-                    dir.display()
-                );
-                continue;
-            }
-        };
-        let path = entry.path();
-        let result = if path.is_dir() {
-            std::fs::remove_dir_all(&path)
-        } else {
-            std::fs::remove_file(&path)
-        };
-        match result {
-            Ok(()) => println!(
-                "{:>12} {}",
-                console::style("Removed").red().bold(),
-                path.display()
-            ),
+        match entry {
+            Ok(entry) => paths.push(entry.path()),
             Err(e) => eprintln!(
-                "{:>12} failed to remove {}: {e}",
+                "{:>12} failed to read entry in {}: {e}",
                 console::style("Warning").yellow().bold(),
-                path.display()
+                dir.display()
             ),
         }
     }
+    paths.sort();
+    paths
 }
@@ -0,0 +1,157 @@
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, Local};
+use component_detector::WasmKind;
+use component_manifest::PackageKind;
+use component_package_manager::manager::{Manager, ManagerError};
+use component_package_manager::{PackageManagerError, Reference, format_size};
+
+use super::errors::LocalError;
+
+/// Publish the newest built component with the metadata in `wasm.toml`
+#[derive(clap::Args)]
+pub(crate) struct PublishOpts {
+    /// Where to push the component, e.g. `ghcr.io/user/app:0.1.0`. Without a
+    /// tag, the version in `wasm.toml` is used
+    reference: String,
+
+    /// Path to the project directory containing `wasm.toml` and the build
+    /// outputs. Defaults to the current directory.
+    #[arg(long, default_value = ".")]
+    manifest_path: PathBuf,
+
+    /// Publish without asking for confirmation
+    #[arg(short, long)]
+    yes: bool,
+
+    /// Print the publish plan without contacting the registry
+    #[arg(long, conflicts_with = "yes")]
+    dry_run: bool,
+}
+
+impl PublishOpts {
+    #[allow(clippy::print_stdout)]
+    pub(crate) async fn run(&self, offline: bool) -> Result<()> {
+        let mut manifest = crate::publish::read_manifest(&self.manifest_path).await?;
+        let pkg = manifest
+            .package
+            .as_mut()
+            .context("`local publish` requires the manifest to have a `[package]` section")?;
+        if pkg.kind == PackageKind::Interface {
+            bail!(
+                "`local publish` pushes built components, but `{}` is a WIT interface; \
+                 use `component publish` instead",
+                pkg.name
+            );
+        }
+        let reference = target_reference(&self.reference, &pkg.version)?;
+
+        let (path, modified) =
+            freshest_component(&self.manifest_path).ok_or_else(|| LocalError::NoBuildOutput {
+                path: self.manifest_path.display().to_string(),
+            })?;
+        // `[package].file` is relative to the manifest directory.
+        let file = path
+            .strip_prefix(&self.manifest_path)
+            .unwrap_or(&path)
+            .to_path_buf();
+        pkg.file = Some(file);
+
+        if self.dry_run {
+            let plan = component_package_manager::publish::plan_for(
+                &manifest,
+                &self.manifest_path,
+                reference,
+            )
+            .await?;
+            println!("{}", plan.render());
+            return Ok(());
+        }
+        if offline {
+            return Err(PackageManagerError::from(ManagerError::OfflinePublish).into());
+        }
+        if !self.yes {
+            let size = std::fs::metadata(&path).map_or(0, |metadata| metadata.len());
+            let built = DateTime::<Local>::from(modified).format("%Y-%m-%d %H:%M");
+            let prompt = format!(
+                "Publish {} ({}, built {built}) to {reference}?",
+                path.display(),
+                format_size(size)
+            );
+            if !crate::util::confirm(&prompt)? {
+                println!("Nothing published");
+                return Ok(());
+            }
+        }
+
+        let manager = Manager::open().await?;
+        let plan = manager
+            .publish_to(&manifest, &self.manifest_path, reference)
+            .await?;
+        println!(
+            "{:>12} {} ({} bytes)",
+            console::style("Published").green().bold(),
+            plan.reference,
+            plan.size_bytes,
+        );
+        Ok(())
+    }
+}
+
+/// The reference to push to: `reference` as given, or tagged with `version`
+/// when it has no tag of its own.
+fn target_reference(reference: &str, version: &str) -> Result<Reference> {
+    let parsed = crate::util::parse_reference(reference).map_err(anyhow::Error::msg)?;
+    if parsed.digest().is_some() {
+        bail!("cannot publish to a digest reference: {reference}");
+    }
+    // The last path segment carries the tag; a `:` before it is a port.
+    let tagged = reference
+        .rsplit('/')
+        .next()
+        .is_some_and(|name| name.contains(':'));
+    if tagged {
+        return Ok(parsed);
+    }
+    Ok(Reference::with_tag(
+        parsed.registry().to_string(),
+        parsed.repository().to_string(),
+        version.to_string(),
+    ))
+}
+
+/// The most recently modified component in the build output directories
+/// under `root`, with its modification time.
+fn freshest_component(root: &Path) -> Option<(PathBuf, SystemTime)> {
+    super::build_outputs(root)
+        .into_iter()
+        .filter(|entry| matches!(entry.kind(), Ok(Some(WasmKind::Component))))
+        .filter_map(|entry| {
+            let modified = std::fs::metadata(entry.path())
+                .and_then(|metadata| metadata.modified())
+                .ok()?;
+            Some((entry.into_path(), modified))
+        })
+        .max_by_key(|(_, modified)| *modified)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn untagged_references_take_the_manifest_version() {
+        let reference = target_reference("ghcr.io/user/app", "0.2.0").unwrap();
+        assert_eq!(reference.whole(), "ghcr.io/user/app:0.2.0");
+
+        let reference = target_reference("localhost:5000/app:dev", "0.2.0").unwrap();
+        assert_eq!(reference.whole(), "localhost:5000/app:dev");
+
+        let reference = target_reference("localhost:5000/app", "0.2.0").unwrap();
+        assert_eq!(reference.whole(), "localhost:5000/app:0.2.0");
+
+        assert!(target_reference("ghcr.io/user/app@sha256:abc", "0.2.0").is_err());
+    }
+}
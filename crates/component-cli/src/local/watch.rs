@@ -0,0 +1,231 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::{Duration, SystemTime};
+
+use anyhow::{Context, Result};
+use notify::{RecursiveMode, Watcher};
+
+use crate::output::OutputFormat;
+
+/// How long to wait for a burst of changes, such as a build writing its
+/// outputs, to settle before detecting again.
+const DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// Watch for Wasm files being added, removed, or rebuilt
+#[derive(clap::Args)]
+pub(crate) struct WatchOpts {
+    /// Directory to watch (defaults to current directory)
+    #[arg(default_value = ".")]
+    path: PathBuf,
+
+    /// Include hidden files and directories
+    #[arg(long)]
+    hidden: bool,
+
+    /// Follow symbolic links
+    #[arg(long)]
+    follow_links: bool,
+
+    /// Run this shell command after each batch of changes, e.g.
+    /// `--exec "wasm-tools validate dist/app.wasm"`
+    #[arg(long, value_name = "COMMAND")]
+    exec: Option<String>,
+}
+
+/// What is known about a detected file, to tell when it was rebuilt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Stamp {
+    size: u64,
+    modified: Option<SystemTime>,
+}
+
+/// The detected files, by path.
+type Snapshot = BTreeMap<PathBuf, Stamp>;
+
+/// How a detected file changed between two detections.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Change {
+    Added,
+    Removed,
+    Updated,
+}
+
+impl Change {
+    fn as_str(self) -> &'static str {
+        match self {
+            Change::Added => "added",
+            Change::Removed => "removed",
+            Change::Updated => "updated",
+        }
+    }
+}
+
+impl WatchOpts {
+    #[allow(clippy::print_stdout, clippy::print_stderr)]
+    pub(crate) fn run(&self, format: OutputFormat) -> Result<()> {
+        let (tx, rx) = mpsc::channel();
+        let mut watcher =
+            notify::recommended_watcher(tx).context("failed to create file watcher")?;
+        watcher
+            .watch(&self.path, RecursiveMode::Recursive)
+            .with_context(|| format!("failed to watch {}", self.path.display()))?;
+
+        let mut snapshot = self.snapshot();
+        eprintln!(
+            "Watching {} ({} Wasm file(s) found); press Ctrl-C to stop",
+            self.path.display(),
+            snapshot.len()
+        );
+
+        while let Ok(event) = rx.recv() {
+            if !is_relevant(&event) {
+                continue;
+            }
+            // Let the rest of the burst arrive before detecting again.
+            while rx.recv_timeout(DEBOUNCE).is_ok() {}
+
+            let next = self.snapshot();
+            let changes = diff(&snapshot, &next);
+            snapshot = next;
+            if changes.is_empty() {
+                continue;
+            }
+            for (change, path) in &changes {
+                print_change(*change, path, format);
+            }
+            if let Some(command) = &self.exec {
+                run_command(command);
+            }
+        }
+        Ok(())
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        super::detect(&self.path, self.hidden, self.follow_links)
+            .into_iter()
+            .map(|entry| {
+                let metadata = std::fs::metadata(entry.path()).ok();
+                let stamp = Stamp {
+                    size: metadata.as_ref().map_or(0, std::fs::Metadata::len),
+                    modified: metadata.and_then(|metadata| metadata.modified().ok()),
+                };
+                (entry.into_path(), stamp)
+            })
+            .collect()
+    }
+}
+
+/// Whether an event could change what's detected: anything touching a
+/// `.wasm` file, or a removal, which may take a whole directory with it.
+fn is_relevant(event: &notify::Result<notify::Event>) -> bool {
+    let Ok(event) = event else {
+        return false;
+    };
+    event.kind.is_remove()
+        || event
+            .paths
+            .iter()
+            .any(|path| path.extension().is_some_and(|ext| ext == "wasm"))
+}
+
+/// The files added, removed, or updated between two detections, by path.
+fn diff(before: &Snapshot, after: &Snapshot) -> Vec<(Change, PathBuf)> {
+    let mut changes = Vec::new();
+    for (path, stamp) in after {
+        match before.get(path) {
+            None => changes.push((Change::Added, path.clone())),
+            Some(old) if old != stamp => changes.push((Change::Updated, path.clone())),
+            Some(_) => {}
+        }
+    }
+    for path in before.keys() {
+        if !after.contains_key(path) {
+            changes.push((Change::Removed, path.clone()));
+        }
+    }
+    changes.sort_by(|a, b| a.1.cmp(&b.1));
+    changes
+}
+
+/// Print a change as it happens: one JSON object per line with `--json`.
+#[allow(clippy::print_stdout)]
+fn print_change(change: Change, path: &Path, format: OutputFormat) {
+    match format {
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::json!({ "change": change.as_str(), "path": path })
+        ),
+        OutputFormat::Plain | OutputFormat::Csv => {
+            println!("{}\t{}", change.as_str(), path.display());
+        }
+        OutputFormat::Table => {
+            let label = match change {
+                Change::Added => console::style("Added").green().bold(),
+                Change::Removed => console::style("Removed").red().bold(),
+                Change::Updated => console::style("Updated").yellow().bold(),
+            };
+            println!("{label:>12} {}", path.display());
+        }
+    }
+}
+
+/// Run the `--exec` command through the shell, reporting it if it fails.
+#[allow(clippy::print_stderr)]
+fn run_command(command: &str) {
+    let mut shell = if cfg!(windows) {
+        let mut shell = std::process::Command::new("cmd");
+        shell.arg("/C");
+        shell
+    } else {
+        let mut shell = std::process::Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+    match shell.arg(command).status() {
+        Ok(status) if status.success() => {}
+        Ok(status) => eprintln!(
+            "{:>12} `{command}` exited with {status}",
+            console::style("Warning").yellow().bold()
+        ),
+        Err(e) => eprintln!(
+            "{:>12} failed to run `{command}`: {e}",
+            console::style("Warning").yellow().bold()
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diff_reports_added_removed_and_updated_files() {
+        let stamp = |size| Stamp {
+            size,
+            modified: None,
+        };
+        let before: Snapshot = [
+            (PathBuf::from("a.wasm"), stamp(1)),
+            (PathBuf::from("b.wasm"), stamp(2)),
+            (PathBuf::from("c.wasm"), stamp(3)),
+        ]
+        .into();
+        let after: Snapshot = [
+            (PathBuf::from("a.wasm"), stamp(1)),
+            (PathBuf::from("c.wasm"), stamp(4)),
+            (PathBuf::from("d.wasm"), stamp(5)),
+        ]
+        .into();
+
+        assert_eq!(
+            diff(&before, &after),
+            [
+                (Change::Removed, PathBuf::from("b.wasm")),
+                (Change::Updated, PathBuf::from("c.wasm")),
+                (Change::Added, PathBuf::from("d.wasm")),
+            ]
+        );
+        assert!(diff(&after, &after).is_empty());
+    }
+}
@@ -0,0 +1,82 @@
+//! Error types for the `component lock` CLI commands.
+
+use miette::Diagnostic;
+
+/// CLI-specific error type for `component lock` command failures.
+///
+/// Each variant carries a stable [diagnostic error code][miette::Diagnostic::code]
+/// that uniquely identifies the failure.
+#[derive(Debug, Clone, PartialEq, Eq, Diagnostic)]
+#[must_use]
+pub(crate) enum LockError {
+    /// There is no `wasm.lock.toml` to verify.
+    #[diagnostic(
+        code(component::lock::no_lockfile),
+        help("run `component install` to create `wasm.lock.toml`")
+    )]
+    NoLockfile,
+    /// Verification found problems, which were printed before this error.
+    #[diagnostic(
+        code(component::lock::verify_failed),
+        help("run `component install` to bring `wasm.lock.toml` up to date")
+    )]
+    VerifyFailed {
+        /// How many problems were found.
+        problems: usize,
+    },
+}
+
+impl std::fmt::Display for LockError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LockError::NoLockfile => write!(f, "no `wasm.lock.toml` found"),
+            LockError::VerifyFailed { problems: 1 } => {
+                write!(f, "`wasm.lock.toml` failed verification with 1 problem")
+            }
+            LockError::VerifyFailed { problems } => {
+                write!(
+                    f,
+                    "`wasm.lock.toml` failed verification with {problems} problems"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for LockError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_all_variants_have_error_codes() {
+        use miette::Diagnostic;
+
+        let no_lockfile = LockError::NoLockfile;
+        assert_eq!(
+            no_lockfile
+                .code()
+                .expect("NoLockfile must have a diagnostic code")
+                .to_string(),
+            "component::lock::no_lockfile",
+        );
+        assert!(
+            no_lockfile.help().is_some(),
+            "NoLockfile must have a help message"
+        );
+
+        let failed = LockError::VerifyFailed { problems: 2 };
+        assert_eq!(
+            failed
+                .code()
+                .expect("VerifyFailed must have a diagnostic code")
+                .to_string(),
+            "component::lock::verify_failed",
+        );
+        assert!(
+            failed.help().is_some(),
+            "VerifyFailed must have a help message"
+        );
+    }
+}
@@ -0,0 +1,130 @@
+#![allow(clippy::print_stdout)]
+
+mod errors;
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use component_manifest::{Lockfile, Manifest, ValidationError, validate, validate_digests};
+use component_package_manager::manager::Manager;
+use miette::{IntoDiagnostic, WrapErr};
+
+use crate::install::InstallError;
+use crate::tree::package_label;
+use crate::util::{into_miette, read_lock_file};
+use errors::LockError;
+
+/// Check and maintain `wasm.lock.toml`
+#[derive(clap::Parser)]
+pub(crate) enum Opts {
+    /// Check that `wasm.lock.toml` matches `wasm.toml` and the registry.
+    /// Exits non-zero if it doesn't, for use in CI
+    Verify(VerifyOpts),
+}
+
+/// Options for the `lock verify` command.
+#[derive(clap::Args)]
+pub(crate) struct VerifyOpts {}
+
+impl Opts {
+    pub(crate) async fn run(self, offline: bool) -> miette::Result<()> {
+        match self {
+            Opts::Verify(opts) => opts.run(offline).await,
+        }
+    }
+}
+
+impl VerifyOpts {
+    async fn run(self, offline: bool) -> miette::Result<()> {
+        let manifest_path = PathBuf::from("wasm.toml");
+        let lockfile_path = PathBuf::from("wasm.lock.toml");
+
+        if !manifest_path.exists() {
+            return Err(InstallError::NoManifest.into());
+        }
+        if !lockfile_path.exists() {
+            return Err(LockError::NoLockfile.into());
+        }
+        let source = tokio::fs::read_to_string(&manifest_path)
+            .await
+            .into_diagnostic()
+            .wrap_err_with(|| format!("could not read '{}'", manifest_path.display()))?;
+        let manifest: Manifest = toml::from_str(&source).into_diagnostic()?;
+        let lockfile = read_lock_file(&lockfile_path).await.map_err(into_miette)?;
+
+        // Checks that need no network: the lockfile agreeing with the
+        // manifest, and well-formed digests.
+        let consistency = validate(&manifest, &lockfile).err().unwrap_or_default();
+        let digests = validate_digests(&lockfile).err().unwrap_or_default();
+        let malformed: HashSet<&str> = digests
+            .iter()
+            .filter_map(|error| {
+                if let ValidationError::InvalidDigest { name, .. } = error {
+                    Some(name.as_str())
+                } else {
+                    None
+                }
+            })
+            .collect();
+        let mut problems: Vec<String> = consistency
+            .iter()
+            .chain(&digests)
+            .map(ToString::to_string)
+            .collect();
+        if !offline {
+            problems.extend(registry_problems(&lockfile, &malformed).await?);
+        }
+
+        if !problems.is_empty() {
+            for problem in &problems {
+                println!("{} {problem}", console::style("✗").red().bold());
+            }
+            return Err(LockError::VerifyFailed {
+                problems: problems.len(),
+            }
+            .into());
+        }
+
+        let count = lockfile.all_packages().count();
+        let packages = if count == 1 { "package" } else { "packages" };
+        let checked = if offline {
+            "registry not checked while offline"
+        } else {
+            "all digests exist in their registries"
+        };
+        println!(
+            "{} Verified {count} locked {packages} ({checked})",
+            console::style("✓").green().bold(),
+        );
+        Ok(())
+    }
+}
+
+/// Locked packages whose digest the registry no longer serves. Packages in
+/// `malformed` are skipped, since their digest can't be looked up.
+async fn registry_problems(
+    lockfile: &Lockfile,
+    malformed: &HashSet<&str>,
+) -> miette::Result<Vec<String>> {
+    let manager = Manager::open().await.map_err(into_miette)?;
+    let mut problems = Vec::new();
+    for (package, _) in lockfile.all_packages() {
+        if malformed.contains(package.name.as_str()) {
+            continue;
+        }
+        let exists = manager
+            .locked_package_exists(package)
+            .await
+            .map_err(into_miette)
+            .wrap_err_with(|| format!("could not check {}", package.registry))?;
+        if !exists {
+            problems.push(format!(
+                "{} is locked to '{}', which {} no longer has",
+                package_label(package),
+                package.digest,
+                package.registry
+            ));
+        }
+    }
+    Ok(problems)
+}
@@ -1,19 +1,32 @@
 //! Component CLI command
 //!
 
+mod add;
+mod audit;
 mod compose;
+mod convert;
+mod exit;
 mod init;
+mod inspect;
 mod install;
 mod local;
+mod lock;
+mod output;
 mod publish;
 mod registry;
+mod remove;
 mod run;
 mod self_;
+mod stats;
+mod tree;
 mod util;
+mod why;
 
 use clap::{ColorChoice, CommandFactory, Parser};
 use clap_verbosity_flag::Verbosity;
+use exit::ErrorFormat;
 use miette::{Context, IntoDiagnostic};
+use output::OutputFormat;
 use util::into_miette;
 
 #[derive(Parser)]
@@ -34,6 +47,46 @@ pub(crate) struct Cli {
     #[arg(long, global = true, help_heading = "Global Options")]
     offline: bool,
 
+    /// Use this config file in place of the global one.
+    #[arg(
+        long,
+        value_name = "PATH",
+        global = true,
+        help_heading = "Global Options"
+    )]
+    config: Option<std::path::PathBuf>,
+
+    /// How to print results: tables, JSON, CSV, or plain tab-separated lines.
+    #[arg(
+        long,
+        value_enum,
+        value_name = "FORMAT",
+        default_value_t = OutputFormat::Table,
+        global = true,
+        help_heading = "Global Options"
+    )]
+    format: OutputFormat,
+
+    /// Print results as JSON; short for `--format json`.
+    #[arg(
+        long,
+        global = true,
+        conflicts_with = "format",
+        help_heading = "Global Options"
+    )]
+    json: bool,
+
+    /// How to report errors: a rendered diagnostic, or one line of JSON.
+    #[arg(
+        long,
+        value_enum,
+        value_name = "FORMAT",
+        default_value_t = ErrorFormat::Human,
+        global = true,
+        help_heading = "Global Options"
+    )]
+    error_format: ErrorFormat,
+
     /// Controls logging verbosity via `-v`/`--verbose` and `-q`/`--quiet`
     /// flags.
     #[command(flatten, next_help_heading = "Global Options")]
@@ -44,18 +97,42 @@ pub(crate) struct Cli {
 }
 
 impl Cli {
+    /// The output format, with `--json` taking the place of `--format json`.
+    fn format(&self) -> OutputFormat {
+        if self.json {
+            OutputFormat::Json
+        } else {
+            self.format
+        }
+    }
+
     async fn run(self) -> miette::Result<()> {
+        let format = self.format();
         match self.command {
             Some(Command::Run(opts)) => opts.run(self.offline).await?,
             Some(Command::Local(opts)) => {
-                opts.run();
+                opts.run(self.offline, format).await.map_err(into_miette)?;
+            }
+            Some(Command::Registry(opts)) => {
+                opts.run(self.offline, format).await.map_err(into_miette)?;
             }
-            Some(Command::Registry(opts)) => opts.run(self.offline).await.map_err(into_miette)?,
-            Some(Command::Compose(opts)) => opts.run().map_err(into_miette)?,
+            Some(Command::Compose(opts)) => opts.run(self.offline).await.map_err(into_miette)?,
+            Some(Command::Convert(opts)) => opts.run().map_err(into_miette)?,
             Some(Command::Init(opts)) => opts.run().await?,
+            Some(Command::New(opts)) => opts.run().await?,
+            Some(Command::Inspect(opts)) => {
+                opts.run(self.offline, format).await.map_err(into_miette)?;
+            }
             Some(Command::Install(opts)) => opts.run(self.offline).await?,
+            Some(Command::Add(opts)) => opts.run(self.offline).await?,
+            Some(Command::Remove(opts)) => opts.run().await?,
+            Some(Command::Tree(opts)) => opts.run().await?,
+            Some(Command::Why(opts)) => opts.run().await?,
+            Some(Command::Lock(opts)) => opts.run(self.offline).await?,
+            Some(Command::Audit(opts)) => opts.run(self.offline).await?,
             Some(Command::Publish(opts)) => opts.run(self.offline).await.map_err(into_miette)?,
-            Some(Command::Self_(opts)) => opts.run().await.map_err(into_miette)?,
+            Some(Command::Stats(opts)) => opts.run(format).await.map_err(into_miette)?,
+            Some(Command::Self_(opts)) => opts.run(format).await.map_err(into_miette)?,
             None => {
                 // Apply the parsed color choice when printing help
                 Cli::command()
@@ -74,18 +151,42 @@ enum Command {
     Run(run::Opts),
     /// Create a new wasm component in an existing directory
     Init(init::Opts),
+    /// Create a new wasm component in a new directory
+    New(init::NewOpts),
     /// Install a dependency from an OCI registry
     Install(install::Opts),
+    /// Add a dependency to `wasm.toml`, and install it
+    Add(add::Opts),
+    /// Remove a dependency from `wasm.toml`, and prune the lockfile
+    Remove(remove::Opts),
+    /// Show the project's locked dependencies as a tree
+    Tree(tree::Opts),
+    /// Show why a package is in the lockfile
+    Why(why::Opts),
+    /// Check and maintain `wasm.lock.toml`
+    #[command(subcommand)]
+    Lock(lock::Opts),
+    /// Check dependencies for missing signatures, denied digests, and
+    /// unpinned tags
+    Audit(audit::Opts),
     /// Publish a component or WIT interface to an OCI registry
     Publish(publish::Opts),
-    /// Compose Wasm components from WAC scripts
+    /// Compose Wasm components from WAC scripts or by plugging them together
     Compose(compose::Opts),
+    /// Show the WIT worlds, imports, and exports of a Wasm file or OCI reference
+    Inspect(inspect::Opts),
+    /// Convert between the Wasm binary and text formats
+    #[command(subcommand)]
+    Convert(convert::Opts),
     /// Detect and manage local WASM files
     #[command(subcommand)]
     Local(local::Opts),
     /// Manage Wasm Components and WIT interfaces in OCI registries
     #[command(subcommand)]
     Registry(registry::Opts),
+    /// Show how many packages and interfaces the store holds, and how much
+    /// space they take
+    Stats(stats::Opts),
     /// Configure the `component(1)` tool, generate completions, & manage state
     #[clap(name = "self")]
     #[command(subcommand)]
@@ -105,6 +206,8 @@ pub(crate) fn log_dir() -> std::path::PathBuf {
 ///
 /// The `level` parameter controls the verbosity of both the file and stderr
 /// log layers, and is typically derived from `--verbose` / `--quiet` CLI flags.
+/// `RUST_LOG` directives refine it, e.g. `RUST_LOG=oci_client=trace` to see
+/// every registry request; see [`log_filter`].
 ///
 /// The returned `WorkerGuard` must be kept alive for the duration of the
 /// program to ensure all buffered log records are flushed.
@@ -112,6 +215,7 @@ fn init_tracing(
     level: tracing::level_filters::LevelFilter,
 ) -> miette::Result<tracing_appender::non_blocking::WorkerGuard> {
     use tracing_subscriber::Layer;
+    use tracing_subscriber::fmt::format::FmtSpan;
     use tracing_subscriber::layer::SubscriberExt;
     use tracing_subscriber::util::SubscriberInitExt;
 
@@ -120,17 +224,33 @@ fn init_tracing(
         .into_diagnostic()
         .wrap_err("failed to create log directory")?;
 
+    let directives = std::env::var("RUST_LOG").ok();
+    let filter = || log_filter(level, directives.as_deref());
+
+    // At debug verbosity and above, report when each span closes so the time
+    // spent in pull phases, registry calls, and store operations is visible.
+    let span_events = if filter()
+        .max_level_hint()
+        .is_some_and(|max| max >= tracing::level_filters::LevelFilter::DEBUG)
+    {
+        FmtSpan::CLOSE
+    } else {
+        FmtSpan::NONE
+    };
+
     let file_appender = tracing_appender::rolling::never(&log_dir, "component.log");
     let (non_blocking, guard) = tracing_appender::non_blocking(file_appender);
 
     let file_layer = tracing_subscriber::fmt::layer()
         .with_writer(non_blocking)
         .with_ansi(false)
-        .with_filter(level);
+        .with_span_events(span_events.clone())
+        .with_filter(filter());
 
     let stderr_layer = tracing_subscriber::fmt::layer()
         .with_writer(std::io::stderr)
-        .with_filter(level);
+        .with_span_events(span_events)
+        .with_filter(filter());
 
     tracing_subscriber::registry()
         .with(file_layer)
@@ -140,8 +260,22 @@ fn init_tracing(
     Ok(guard)
 }
 
+/// The log filter for `level`, refined by `RUST_LOG`-style `directives`.
+///
+/// Directives for a target, like `component_package_manager=debug`, add to
+/// the level; a bare level, like `RUST_LOG=trace`, replaces it. Directives
+/// that don't parse are skipped rather than failing the command.
+fn log_filter(
+    level: tracing::level_filters::LevelFilter,
+    directives: Option<&str>,
+) -> tracing_subscriber::EnvFilter {
+    tracing_subscriber::EnvFilter::builder()
+        .with_default_directive(level.into())
+        .parse_lossy(directives.unwrap_or_default())
+}
+
 #[tokio::main]
-async fn main() -> miette::Result<()> {
+async fn main() -> std::process::ExitCode {
     // Load .env file if present; variables already set in the environment
     // take precedence (system environment is not overridden).
     dotenvy::dotenv().ok();
@@ -155,10 +289,27 @@ async fn main() -> miette::Result<()> {
     // r[impl run.library-help.dynamic]
     // r[impl run.host-flags-before-input]
     let argv = quarantine_run_trailing_args(std::env::args().collect());
-    let cli = Cli::parse_from(argv);
+    let mut cli = Cli::parse_from(argv);
+    // `WASM_OFFLINE` can only turn offline mode on, so `--offline` always wins.
+    cli.offline |= component_package_manager::Config::offline_from_env();
+    let error_format = cli.error_format;
+    match run(cli).await {
+        Ok(()) => std::process::ExitCode::SUCCESS,
+        Err(report) => exit::report(&report, error_format),
+    }
+}
+
+/// Set up logging and the config file, and run the command.
+async fn run(cli: Cli) -> miette::Result<()> {
     let _tracing_guard = init_tracing(cli.verbosity.tracing_level_filter())?;
-    cli.run().await?;
-    Ok(())
+    if let Some(path) = &cli.config {
+        if !path.is_file() {
+            miette::bail!("config file {} does not exist", path.display());
+        }
+        component_package_manager::Config::use_path(path.clone()).map_err(into_miette)?;
+    }
+    // The command futures are large; keep them off the main stack.
+    Box::pin(cli.run()).await
 }
 
 /// Insert `--` after the first positional argument of the `run`
@@ -166,8 +317,9 @@ async fn main() -> miette::Result<()> {
 /// by clap (`trailing_var_arg = true`).
 ///
 /// Identifies host flags as `--inherit-env`, `--inherit-network`,
-/// `--no-stdio`, `--global`/`-g`, plus value-taking flags `--env`,
-/// `--dir`, `--listen` (each followed by its value). A leading
+/// `--no-stdio`, `--global`/`-g`, `--serve` and `--serve=PORT`, plus
+/// value-taking flags `--env`, `--dir`, `--listen` (each followed by its
+/// value). A leading
 /// `-h`/`--help` before any positional triggers host help via clap
 /// in the usual way.
 fn quarantine_run_trailing_args(args: Vec<String>) -> Vec<String> {
@@ -176,6 +328,7 @@ fn quarantine_run_trailing_args(args: Vec<String>) -> Vec<String> {
         "--inherit-env",
         "--inherit-network",
         "--no-stdio",
+        "--serve",
         "--global",
         "-g",
         "-h",
@@ -193,7 +346,8 @@ fn quarantine_run_trailing_args(args: Vec<String>) -> Vec<String> {
 
     let mut i = run_idx + 1;
     while let Some(token) = args.get(i) {
-        if VALUELESS.iter().any(|f| f == token) {
+        // `--serve` only takes a value with `=`, as `--serve=PORT`.
+        if VALUELESS.iter().any(|f| f == token) || token.starts_with("--serve=") {
             out.push(token.clone());
             i += 1;
             continue;
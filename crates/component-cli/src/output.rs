@@ -0,0 +1,246 @@
+//! Rendering command results in the format picked with the global
+//! `--format` flag.
+
+#![allow(clippy::print_stdout)]
+
+use comfy_table::{ContentArrangement, Table};
+
+/// How commands print their results.
+// The variants are described with plain comments: clap would otherwise show
+// every command's help in its long form to list them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub(crate) enum OutputFormat {
+    // Tables and summaries for people to read.
+    #[default]
+    Table,
+    // JSON, with sizes in bytes and digests in full.
+    Json,
+    // One tab-separated record per line, without headers or decoration.
+    Plain,
+    // Comma-separated values with a header row.
+    Csv,
+}
+
+impl OutputFormat {
+    /// Whether results should be printed as JSON.
+    #[must_use]
+    pub(crate) fn is_json(self) -> bool {
+        self == OutputFormat::Json
+    }
+}
+
+/// Rows of a listing, printed as a table, as plain text, or, together with
+/// a richer JSON value, as JSON.
+///
+/// The cells are what people read, such as human-readable sizes and
+/// shortened digests; the JSON value passed to [`Listing::print`] carries
+/// the exact data for scripts.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Listing {
+    header: Vec<&'static str>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Listing {
+    /// An empty listing with these column names.
+    #[must_use]
+    pub(crate) fn new(header: impl Into<Vec<&'static str>>) -> Self {
+        Self {
+            header: header.into(),
+            rows: Vec::new(),
+        }
+    }
+
+    /// Add a row, one cell per column.
+    pub(crate) fn push(&mut self, row: impl IntoIterator<Item = impl Into<String>>) {
+        self.rows.push(row.into_iter().map(Into::into).collect());
+    }
+
+    /// Whether the listing has no rows.
+    #[must_use]
+    pub(crate) fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// The listing as a table that wraps to the terminal width. Callers may
+    /// restyle it before printing.
+    #[must_use]
+    pub(crate) fn table(&self) -> Table {
+        let mut table = Table::new();
+        table.set_content_arrangement(ContentArrangement::Dynamic);
+        table.set_header(self.header.clone());
+        for row in &self.rows {
+            table.add_row(row.clone());
+        }
+        table
+    }
+
+    /// The rows as tab-separated lines, without the header.
+    #[must_use]
+    pub(crate) fn plain(&self) -> String {
+        self.rows
+            .iter()
+            .map(|row| row.join("\t"))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// The listing as comma-separated values, with the header as the first
+    /// row. Cells with commas, quotes, or line breaks are quoted.
+    #[must_use]
+    pub(crate) fn csv(&self) -> String {
+        let header: Vec<String> = self.header.iter().map(ToString::to_string).collect();
+        std::iter::once(&header)
+            .chain(&self.rows)
+            .map(|row| {
+                row.iter()
+                    .map(|cell| csv_cell(cell))
+                    .collect::<Vec<_>>()
+                    .join(",")
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Print the listing in `format`, building the JSON value only when it
+    /// is needed.
+    ///
+    /// Empty listings print `empty` as tables, nothing as plain text, and
+    /// just the header as CSV.
+    pub(crate) fn print(
+        &self,
+        format: OutputFormat,
+        empty: &str,
+        json: impl FnOnce() -> serde_json::Value,
+    ) -> anyhow::Result<()> {
+        match format {
+            OutputFormat::Json => print_json(&json())?,
+            OutputFormat::Plain if self.is_empty() => {}
+            OutputFormat::Plain => println!("{}", self.plain()),
+            OutputFormat::Csv => println!("{}", self.csv()),
+            OutputFormat::Table if self.is_empty() => println!("{empty}"),
+            OutputFormat::Table => println!("{}", self.table()),
+        }
+        Ok(())
+    }
+}
+
+/// Named values describing one thing, such as the store or a file, printed
+/// as two aligned columns, or as plain text with one `name\tvalue` record
+/// per line.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Fields {
+    rows: Vec<(&'static str, String)>,
+}
+
+impl Fields {
+    /// No fields yet.
+    #[must_use]
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a field. Names may repeat, such as one `Import` per import.
+    pub(crate) fn push(&mut self, name: &'static str, value: impl Into<String>) {
+        self.rows.push((name, value.into()));
+    }
+
+    /// The fields as a borderless table without a header.
+    #[must_use]
+    pub(crate) fn table(&self) -> Table {
+        let mut table = Table::new();
+        table
+            .load_preset(comfy_table::presets::NOTHING)
+            .set_content_arrangement(ContentArrangement::Dynamic);
+        for (name, value) in &self.rows {
+            table.add_row(vec![*name, value.as_str()]);
+        }
+        table
+    }
+
+    /// The fields as tab-separated `name\tvalue` lines.
+    #[must_use]
+    pub(crate) fn plain(&self) -> String {
+        self.rows
+            .iter()
+            .map(|(name, value)| format!("{name}\t{value}"))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Print the fields in `format`, building the JSON value only when it
+    /// is needed. CSV has a `FIELD,VALUE` header row.
+    pub(crate) fn print(
+        &self,
+        format: OutputFormat,
+        json: impl FnOnce() -> serde_json::Value,
+    ) -> anyhow::Result<()> {
+        match format {
+            OutputFormat::Json => print_json(&json())?,
+            OutputFormat::Plain => println!("{}", self.plain()),
+            OutputFormat::Csv => {
+                let mut listing = Listing::new(["FIELD", "VALUE"]);
+                for (name, value) in &self.rows {
+                    listing.push([*name, value.as_str()]);
+                }
+                println!("{}", listing.csv());
+            }
+            OutputFormat::Table => println!("{}", self.table()),
+        }
+        Ok(())
+    }
+}
+
+/// A CSV cell, quoted if it needs to be.
+fn csv_cell(cell: &str) -> std::borrow::Cow<'_, str> {
+    if cell.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", cell.replace('"', "\"\"")).into()
+    } else {
+        cell.into()
+    }
+}
+
+/// Print `value` as pretty-printed JSON.
+pub(crate) fn print_json(value: &impl serde::Serialize) -> anyhow::Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn listing_renders_tables_and_plain_text() {
+        let mut listing = Listing::new(["NAME", "SIZE"]);
+        assert!(listing.is_empty());
+        listing.push(["hello", "1.0 KB"]);
+        listing.push(["world", "2.0 KB"]);
+
+        assert_eq!(listing.plain(), "hello\t1.0 KB\nworld\t2.0 KB");
+        listing.push(["a, \"b\"", "3"]);
+        assert_eq!(
+            listing.csv(),
+            "NAME,SIZE\nhello,1.0 KB\nworld,2.0 KB\n\"a, \"\"b\"\"\",3"
+        );
+        let table = listing.table().to_string();
+        assert!(table.contains("NAME"), "{table}");
+        assert!(table.contains("world"), "{table}");
+    }
+
+    #[test]
+    fn fields_render_as_name_value_records() {
+        let mut fields = Fields::new();
+        fields.push("Kind", "component");
+        fields.push("Import", "wasi:cli/stdout");
+        fields.push("Import", "wasi:io/streams");
+
+        assert_eq!(
+            fields.plain(),
+            "Kind\tcomponent\nImport\twasi:cli/stdout\nImport\twasi:io/streams"
+        );
+        let table = fields.table().to_string();
+        assert!(table.contains("Kind"), "{table}");
+        assert!(!table.contains('\t'), "{table}");
+    }
+}
@@ -3,7 +3,7 @@
 
 #![allow(clippy::print_stdout)]
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result, bail};
 use component_manifest::{Manifest, PackageKind};
@@ -33,12 +33,7 @@ pub(crate) struct Opts {
 impl Opts {
     pub(crate) async fn run(self, offline: bool) -> Result<()> {
         let manifest_dir = self.manifest_path.clone();
-        let manifest_file = manifest_dir.join("wasm.toml");
-        let manifest_text = tokio::fs::read_to_string(&manifest_file)
-            .await
-            .with_context(|| format!("failed to read `{}`", manifest_file.display()))?;
-        let mut manifest: Manifest = toml::from_str(&manifest_text)
-            .with_context(|| format!("failed to parse `{}`", manifest_file.display()))?;
+        let mut manifest = read_manifest(&manifest_dir).await?;
 
         // Apply --file override before anything else looks at the
         // manifest's [package] section.
@@ -79,3 +74,13 @@ impl Opts {
         Ok(())
     }
 }
+
+/// Read and parse the `wasm.toml` in `manifest_dir`.
+pub(crate) async fn read_manifest(manifest_dir: &Path) -> Result<Manifest> {
+    let manifest_file = manifest_dir.join("wasm.toml");
+    let manifest_text = tokio::fs::read_to_string(&manifest_file)
+        .await
+        .with_context(|| format!("failed to read `{}`", manifest_file.display()))?;
+    toml::from_str(&manifest_text)
+        .with_context(|| format!("failed to parse `{}`", manifest_file.display()))
+}
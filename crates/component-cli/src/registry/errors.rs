@@ -18,16 +18,6 @@ pub(crate) enum InspectError {
         /// The OCI reference that was inspected.
         reference: String,
     },
-
-    /// The OCI manifest contains no `application/wasm` layer.
-    #[diagnostic(
-        code(component::registry::inspect_no_wasm_layer),
-        help("'{reference}' does not contain an `application/wasm` layer")
-    )]
-    NoWasmLayer {
-        /// The OCI reference that was inspected.
-        reference: String,
-    },
 }
 
 impl std::fmt::Display for InspectError {
@@ -36,9 +26,6 @@ impl std::fmt::Display for InspectError {
             InspectError::NoManifest { reference } => {
                 write!(f, "no manifest found for '{reference}'")
             }
-            InspectError::NoWasmLayer { reference } => {
-                write!(f, "no wasm layers found for '{reference}'")
-            }
         }
     }
 }
@@ -75,6 +62,68 @@ impl std::fmt::Display for SyncError {
 
 impl std::error::Error for SyncError {}
 
+/// Error type for `component registry pull` command failures.
+///
+/// Each variant carries a stable [diagnostic error code][miette::Diagnostic::code]
+/// that uniquely identifies the failure.
+#[derive(Debug, Clone, PartialEq, Eq, Diagnostic)]
+#[must_use]
+pub(crate) enum PullError {
+    /// Some of the packages in a batch pull failed.
+    #[diagnostic(
+        code(component::registry::pull_failed),
+        help("see the table above for why each pull failed")
+    )]
+    Failed {
+        /// How many pulls failed.
+        failed: usize,
+        /// How many packages were pulled in total.
+        total: usize,
+    },
+}
+
+impl std::fmt::Display for PullError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PullError::Failed { failed, total } => {
+                write!(f, "{failed} of {total} pulls failed")
+            }
+        }
+    }
+}
+
+impl std::error::Error for PullError {}
+
+/// Error type for `component registry verify` command failures.
+///
+/// Each variant carries a stable [diagnostic error code][miette::Diagnostic::code]
+/// that uniquely identifies the failure.
+#[derive(Debug, Clone, PartialEq, Eq, Diagnostic)]
+#[must_use]
+pub(crate) enum VerifyError {
+    /// The package has no valid signature from a trusted key.
+    #[diagnostic(
+        code(component::registry::verify_failed),
+        help("trust the signer's public key with `--key`, or in policy.toml")
+    )]
+    Failed {
+        /// The reference that was verified.
+        reference: String,
+    },
+}
+
+impl std::fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VerifyError::Failed { reference } => {
+                write!(f, "verification of '{reference}' failed")
+            }
+        }
+    }
+}
+
+impl std::error::Error for VerifyError {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -98,34 +147,50 @@ mod tests {
             "NoManifest must have a help message"
         );
 
-        let no_layer = InspectError::NoWasmLayer {
-            reference: "ghcr.io/example/comp:1.0".to_string(),
+        let degraded = SyncError::Degraded {
+            reason: "connection refused".to_string(),
+        };
+        assert_eq!(
+            degraded
+                .code()
+                .expect("Degraded must have a diagnostic code")
+                .to_string(),
+            "component::registry::sync_failed",
+        );
+        assert!(
+            degraded.help().is_some(),
+            "Degraded must have a help message"
+        );
+
+        let failed_pulls = PullError::Failed {
+            failed: 1,
+            total: 2,
         };
         assert_eq!(
-            no_layer
+            failed_pulls
                 .code()
-                .expect("NoWasmLayer must have a diagnostic code")
+                .expect("PullError::Failed must have a diagnostic code")
                 .to_string(),
-            "component::registry::inspect_no_wasm_layer",
+            "component::registry::pull_failed",
         );
         assert!(
-            no_layer.help().is_some(),
-            "NoWasmLayer must have a help message"
+            failed_pulls.help().is_some(),
+            "PullError::Failed must have a help message"
         );
 
-        let degraded = SyncError::Degraded {
-            reason: "connection refused".to_string(),
+        let failed_verify = VerifyError::Failed {
+            reference: "ghcr.io/example/comp:1.0".to_string(),
         };
         assert_eq!(
-            degraded
+            failed_verify
                 .code()
-                .expect("Degraded must have a diagnostic code")
+                .expect("VerifyError::Failed must have a diagnostic code")
                 .to_string(),
-            "component::registry::sync_failed",
+            "component::registry::verify_failed",
         );
         assert!(
-            degraded.help().is_some(),
-            "Degraded must have a help message"
+            failed_verify.help().is_some(),
+            "VerifyError::Failed must have a help message"
         );
     }
 }
@@ -0,0 +1,113 @@
+//! `component registry import` and `component registry export` subcommands.
+
+use std::path::PathBuf;
+
+use anyhow::Result;
+use component_package_manager::import::ContainerEngine;
+use component_package_manager::manager::Manager;
+use component_package_manager::oci::InsertResult;
+use component_package_manager::{Reference, format_size};
+
+/// Import wasm images from a local Docker or Podman daemon, or a bundle.
+#[derive(clap::Args)]
+pub(crate) struct ImportOpts {
+    /// The images to import, as the engine names them; lists importable images when omitted
+    images: Vec<String>,
+    /// The container engine to import from
+    #[arg(long, value_enum, default_value_t = Engine::Docker)]
+    engine: Engine,
+    /// Import every wasm image the engine has
+    #[arg(long, conflicts_with = "images")]
+    all: bool,
+    /// Import every package in a bundle written by `registry export`
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["images", "engine", "all"])]
+    bundle: Option<PathBuf>,
+}
+
+/// Write stored packages to a bundle, for `registry import --bundle`.
+#[derive(clap::Args)]
+pub(crate) struct ExportOpts {
+    /// The stored references to export (e.g., ghcr.io/example/component:tag)
+    #[arg(required = true, value_parser = crate::util::parse_reference)]
+    references: Vec<Reference>,
+    /// Where to write the bundle (e.g., bundle.tar.zst)
+    #[arg(short, long, value_name = "PATH")]
+    output: PathBuf,
+}
+
+/// Container engine for `registry import`.
+#[derive(Clone, Copy, Debug, clap::ValueEnum)]
+pub(crate) enum Engine {
+    /// Docker (default).
+    Docker,
+    /// Podman.
+    Podman,
+}
+
+impl From<Engine> for ContainerEngine {
+    fn from(engine: Engine) -> Self {
+        match engine {
+            Engine::Docker => ContainerEngine::Docker,
+            Engine::Podman => ContainerEngine::Podman,
+        }
+    }
+}
+
+impl ImportOpts {
+    pub(crate) async fn run(self, store: &Manager) -> Result<()> {
+        if let Some(bundle) = &self.bundle {
+            for (reference, result) in store.import_bundle(bundle).await? {
+                print_imported(&reference.whole(), result.insert_result);
+            }
+            return Ok(());
+        }
+
+        let engine = ContainerEngine::from(self.engine);
+        let images = if self.all || self.images.is_empty() {
+            let available = store.daemon_images(engine).await?;
+            if !self.all {
+                if available.is_empty() {
+                    println!("No wasm images found in {}", engine.command());
+                }
+                for image in &available {
+                    println!(
+                        "{} ({}, {})",
+                        image.reference,
+                        image.os,
+                        format_size(image.size)
+                    );
+                }
+                return Ok(());
+            }
+            available.into_iter().map(|image| image.reference).collect()
+        } else {
+            self.images
+        };
+
+        for image in &images {
+            let result = store.import_image(engine, image).await?;
+            print_imported(image, result.insert_result);
+        }
+        Ok(())
+    }
+}
+
+impl ExportOpts {
+    pub(crate) async fn run(self, store: &Manager) -> Result<()> {
+        let size = store.export_bundle(&self.references, &self.output).await?;
+        println!(
+            "Exported {} package(s) to {} ({})",
+            self.references.len(),
+            self.output.display(),
+            format_size(size)
+        );
+        Ok(())
+    }
+}
+
+fn print_imported(image: &str, result: InsertResult) {
+    match result {
+        InsertResult::Inserted => println!("Imported {image}"),
+        InsertResult::AlreadyExists => println!("Already in the store: {image}"),
+    }
+}
@@ -9,45 +9,35 @@ use comfy_table::presets::UTF8_FULL;
 use comfy_table::{CellAlignment, ContentArrangement, Table};
 use component_package_manager::Reference;
 use component_package_manager::manager::Manager;
-use component_package_manager::oci::filter_wasm_layers;
 use wasm_metadata::{Metadata, Payload};
 
+use crate::output::OutputFormat;
+
 /// Inspect the metadata of a package on the registry.
 #[derive(clap::Args)]
 pub(crate) struct InspectOpts {
     /// The reference to inspect (e.g., ghcr.io/example/component:tag)
     #[arg(value_parser = crate::util::parse_reference)]
     reference: Reference,
-
-    /// Output in JSON encoding
-    #[clap(long)]
-    json: bool,
 }
 
 impl InspectOpts {
-    pub(crate) async fn run(self, store: &Manager) -> Result<()> {
+    pub(crate) async fn run(self, store: &Manager, format: OutputFormat) -> Result<()> {
         let reference = self.reference;
         let pull_result = store.pull(reference.clone()).await?;
 
-        let manifest = pull_result.manifest.as_ref().ok_or_else(|| {
-            super::errors::InspectError::NoManifest {
+        if pull_result.manifest.is_none() {
+            return Err(super::errors::InspectError::NoManifest {
                 reference: reference.whole().clone(),
             }
-        })?;
-
-        let wasm_layers = filter_wasm_layers(&manifest.layers);
-        let layer =
-            wasm_layers
-                .first()
-                .ok_or_else(|| super::errors::InspectError::NoWasmLayer {
-                    reference: reference.whole().clone(),
-                })?;
+            .into());
+        }
 
-        let data = store.get(&layer.digest).await?;
+        let data = store.read_component(&reference).await?;
         let payload = Payload::from_binary(&data)?;
 
         let mut output = std::io::stdout();
-        if self.json {
+        if format.is_json() {
             write!(output, "{}", serde_json::to_string(&payload)?)?;
         } else {
             write_summary_table(&payload, &mut output)?;
@@ -73,7 +63,7 @@ fn find_range_max(max: &mut usize, payload: &Payload) {
 
 /// Write a table containing a summarized overview of a wasm binary's metadata to
 /// a writer.
-fn write_summary_table(payload: &Payload, f: &mut Stdout) -> Result<()> {
+pub(crate) fn write_summary_table(payload: &Payload, f: &mut Stdout) -> Result<()> {
     // Prepare a table and get the individual metadata
     let mut table = Table::new();
     table
@@ -176,7 +166,7 @@ fn write_summary_table_inner(
 
 /// Write a table containing a detailed overview of a wasm binary's metadata to
 /// a writer.
-fn write_details_table(payload: &Payload, f: &mut Stdout) -> Result<()> {
+pub(crate) fn write_details_table(payload: &Payload, f: &mut Stdout) -> Result<()> {
     // Prepare a table and get the individual metadata
     let mut table = Table::new();
     table
@@ -1,200 +1,426 @@
 #![allow(clippy::print_stdout, clippy::print_stderr)]
 
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::time::Duration;
+
 use anyhow::Result;
-use comfy_table::{ContentArrangement, Table};
 use component_package_manager::manager::Manager;
-use component_package_manager::oci::{ImageEntry, InsertResult};
-use component_package_manager::{Reference, format_size};
+use component_package_manager::oci::{ImageEntry, SortBy, SortDirection};
+use component_package_manager::storage::PrunePolicy;
+use component_package_manager::{Reference, format_size, serve};
+
+use crate::output::{Listing, OutputFormat};
 
 mod errors;
+mod import;
 mod inspect;
 mod notify;
+mod progress;
+mod pull;
 mod search;
+mod show;
+mod sign;
 mod sync;
+mod tags;
+mod verify;
+
+pub(crate) use errors::{PullError, VerifyError};
+pub(crate) use inspect::{write_details_table, write_summary_table};
 
 /// Manage Wasm Components and WIT interfaces in OCI registries
 #[derive(clap::Parser)]
 pub(crate) enum Opts {
-    /// Fetch OCI metadata for a component
-    Show,
-    /// Pull a component from the registry
-    Pull(PullOpts),
+    /// Fetch OCI metadata for a component without downloading it
+    Show(show::ShowOpts),
+    /// Pull components from the registry
+    Pull(pull::PullOpts),
     /// List all available tags for a component
-    Tags(TagsOpts),
+    Tags(tags::TagsOpts),
     /// Search for packages across configured registries
     Search(search::SearchOpts),
     /// Force-sync the package index from the configured meta-registry
     Sync(sync::SyncOpts),
+    /// Discover all packages a registry hosts, optionally within one namespace
+    #[command(visible_alias = "catalog")]
+    Discover(DiscoverOpts),
     /// Notify a meta-registry that a new version of a package is available
     Notify(notify::NotifyOpts),
+    /// Sign a component in its registry, in a way `cosign verify` accepts
+    Sign(sign::SignOpts),
+    /// Check a component's signatures and attestations against trusted keys
+    Verify(verify::VerifyOpts),
+    /// Print the digest a reference resolves to, and nothing else
+    Digest(ReferenceOpts),
+    /// Print a reference with its registry filled in, and nothing else
+    Resolve(ReferenceOpts),
+    /// Write a stored component's wasm bytes to a file
+    Extract(ExtractOpts),
     /// Delete a package from the local store
     Delete(DeleteOpts),
+    /// Remove unused or superseded packages from the local store
+    Prune(PruneOpts),
     /// List all installed packages
     List(ListOpts),
     /// List all known packages (previously synced or pulled)
     Known(KnownOpts),
     /// Inspect the metadata of a package on the registry
     Inspect(inspect::InspectOpts),
+    /// Serve the local store as a read-only OCI registry
+    Serve(ServeOpts),
+    /// Import wasm images from a local Docker or Podman daemon, or a bundle
+    Import(import::ImportOpts),
+    /// Write stored packages to a bundle, for moving them to offline machines
+    Export(import::ExportOpts),
 }
 
 #[derive(clap::Args)]
-pub(crate) struct PullOpts {
-    /// The reference to pull
-    #[arg(value_parser = crate::util::parse_reference)]
-    reference: Reference,
+pub(crate) struct DiscoverOpts {
+    /// The registry to list, optionally with a namespace (e.g., ghcr.io/bytecodealliance)
+    #[arg(value_name = "REGISTRY[/NAMESPACE]")]
+    target: String,
+    /// Maximum number of packages to show; all of them are still recorded
+    #[arg(long, value_name = "N")]
+    limit: Option<usize>,
+}
+
+#[derive(clap::Args)]
+pub(crate) struct ReferenceOpts {
+    /// The reference, optionally without a registry (e.g., wasi/http:0.2.0)
+    reference: String,
 }
 
 #[derive(clap::Args)]
-pub(crate) struct TagsOpts {
-    /// The reference to list tags for (e.g., ghcr.io/example/component or oci://ghcr.io/example/component)
+pub(crate) struct ExtractOpts {
+    /// The stored reference to extract (e.g., ghcr.io/example/component:tag)
     #[arg(value_parser = crate::util::parse_reference)]
     reference: Reference,
-    /// Include signature tags (ending in .sig)
-    #[arg(long)]
-    signatures: bool,
-    /// Include attestation tags (ending in .att)
-    #[arg(long)]
-    attestations: bool,
+    /// Where to write the component
+    output: PathBuf,
 }
 
 #[derive(clap::Args)]
 pub(crate) struct DeleteOpts {
     /// The reference to delete (e.g., ghcr.io/example/component:tag)
-    #[arg(value_parser = crate::util::parse_reference)]
-    reference: Reference,
+    #[arg(
+        required_unless_present_any = ["digest", "matching"],
+        conflicts_with_all = ["digest", "matching"],
+    )]
+    reference: Option<String>,
+    /// Delete every tag of the reference's repository
+    #[arg(long, requires = "reference")]
+    all_tags: bool,
+    /// Delete all images whose manifest digest starts with this prefix
+    #[arg(long, value_name = "PREFIX", conflicts_with = "matching")]
+    digest: Option<String>,
+    /// Delete all unpinned images whose reference matches this glob (e.g.,
+    /// 'ghcr.io/example/*')
+    #[arg(long, value_name = "GLOB")]
+    matching: Option<String>,
+    /// Delete without asking for confirmation
+    #[arg(short, long)]
+    yes: bool,
+}
+
+#[derive(clap::Args)]
+pub(crate) struct ListOpts {
+    /// Field to order packages by
+    #[arg(long, value_enum, default_value_t = ListSort::Name)]
+    sort: ListSort,
+    /// Reverse the order, e.g. largest or most recently pulled first
+    #[arg(long)]
+    reverse: bool,
+}
+
+/// Field to order `registry list` output by.
+#[derive(Clone, Copy, Debug, Default, clap::ValueEnum)]
+pub(crate) enum ListSort {
+    /// Repository name (default).
+    #[default]
+    Name,
+    /// Size on disk.
+    Size,
+    /// Registry hostname.
+    Registry,
+    /// When the package was pulled.
+    #[value(alias = "date")]
+    PulledAt,
 }
 
 #[derive(clap::Args)]
-pub(crate) struct ListOpts {}
+pub(crate) struct PruneOpts {
+    /// Remove packages not pulled, installed, or run in this many days
+    #[arg(long, value_name = "DAYS", required_unless_present = "keep_last")]
+    unused_for: Option<u64>,
+    /// Keep only the N most recently pulled packages in each repository
+    #[arg(long, value_name = "N")]
+    keep_last: Option<u32>,
+    /// Show what would be removed without removing anything
+    #[arg(long)]
+    dry_run: bool,
+}
 
 #[derive(clap::Args)]
 pub(crate) struct KnownOpts {
     /// Maximum number of results to show
     #[arg(long, default_value = "100")]
     limit: u32,
+    /// Number of results to skip, for paging through large stores
+    #[arg(long, default_value = "0")]
+    offset: u32,
+}
+
+#[derive(clap::Args)]
+pub(crate) struct ServeOpts {
+    /// Address to listen on; use 0.0.0.0 to share the store on the network
+    #[arg(long, value_name = "ADDR", default_value = "127.0.0.1:5000")]
+    listen: SocketAddr,
 }
 
 impl Opts {
-    pub(crate) async fn run(self, offline: bool) -> Result<()> {
-        let store = if offline {
+    pub(crate) async fn run(self, offline: bool, format: OutputFormat) -> Result<()> {
+        let mut store = if offline {
             Manager::open_offline().await?
         } else {
             Manager::open().await?
         };
         match self {
-            Opts::Show => todo!(),
-            Opts::Pull(opts) => {
-                let result = store.pull(opts.reference.clone()).await?;
-                if result.insert_result == InsertResult::AlreadyExists {
-                    tracing::warn!(
-                        "package '{}' already exists in the local store",
-                        opts.reference.whole()
-                    );
-                }
-                Ok(())
-            }
-            Opts::Tags(opts) => {
-                let all_tags = store.list_tags(&opts.reference).await?;
-
-                // Filter tags based on flags
-                let tags: Vec<_> = all_tags
-                    .into_iter()
-                    .filter(|tag| {
-                        use std::ffi::OsStr;
-                        let ext = std::path::Path::new(tag.as_str()).extension();
-                        let is_sig = ext == Some(OsStr::new("sig"));
-                        let is_att = ext == Some(OsStr::new("att"));
-
-                        if is_sig {
-                            opts.signatures
-                        } else if is_att {
-                            opts.attestations
-                        } else {
-                            true // Always include release tags
+            Opts::Show(opts) => opts.run(&store, format).await,
+            Opts::Pull(opts) => opts.run(&mut store).await,
+            Opts::Tags(opts) => opts.run(&store, offline, format).await,
+            Opts::Search(opts) => opts.run(offline, format).await,
+            Opts::Sign(opts) => opts.run(&store, format).await,
+            Opts::Verify(opts) => opts.run(&store, format).await,
+            Opts::Sync(opts) => opts.run().await,
+            Opts::Discover(opts) => {
+                let (registry, namespace) = match opts.target.split_once('/') {
+                    Some((registry, namespace)) => (registry, Some(namespace)),
+                    None => (opts.target.as_str(), None),
+                };
+                let mut repositories = store.discover_repositories(registry, namespace).await?;
+                let total = repositories.len();
+                repositories.truncate(opts.limit.unwrap_or(total));
+                match format {
+                    OutputFormat::Json => crate::output::print_json(
+                        &repositories
+                            .iter()
+                            .map(|repository| format!("{registry}/{repository}"))
+                            .collect::<Vec<_>>(),
+                    )?,
+                    OutputFormat::Plain | OutputFormat::Csv => {
+                        for repository in &repositories {
+                            println!("{registry}/{repository}");
                         }
-                    })
-                    .collect();
-
-                if tags.is_empty() {
-                    if offline {
-                        println!(
-                            "No cached tags found for '{}' (offline mode)",
-                            opts.reference.whole()
-                        );
-                    } else {
-                        println!("No tags found for '{}'", opts.reference.whole());
-                    }
-                } else {
-                    if offline {
-                        println!(
-                            "Cached tags for '{}' (offline mode):",
-                            opts.reference.whole()
-                        );
-                    } else {
-                        println!("Tags for '{}':", opts.reference.whole());
                     }
-                    for tag in tags {
-                        println!("  {tag}");
+                    OutputFormat::Table => {
+                        for repository in &repositories {
+                            println!("  {registry}/{repository}");
+                        }
+                        if repositories.len() < total {
+                            println!(
+                                "Showing {} of {total} discovered package(s)",
+                                repositories.len()
+                            );
+                        } else {
+                            println!("Discovered {total} package(s)");
+                        }
                     }
                 }
                 Ok(())
             }
-            Opts::Search(opts) => opts.run(offline).await,
-            Opts::Sync(opts) => opts.run().await,
             Opts::Notify(opts) => opts.run(offline).await,
+            Opts::Digest(opts) => {
+                let reference = store.resolve_reference(&opts.reference)?;
+                print_value(&store.resolve_digest(&reference).await?, format)
+            }
+            Opts::Resolve(opts) => {
+                let reference = store.resolve_reference(&opts.reference)?;
+                print_value(&reference.whole(), format)
+            }
+            Opts::Extract(opts) => {
+                let written = store.extract(&opts.reference, &opts.output).await?;
+                println!(
+                    "Extracted '{}' to {} ({})",
+                    opts.reference.whole(),
+                    opts.output.display(),
+                    format_size(written)
+                );
+                Ok(())
+            }
             Opts::Delete(opts) => {
-                let deleted = store.delete(opts.reference.clone()).await?;
-                if deleted {
-                    println!("Deleted '{}'", opts.reference.whole());
-                } else {
-                    println!(
-                        "Package '{}' not found in local store",
-                        opts.reference.whole()
-                    );
+                let reference = opts
+                    .reference
+                    .as_deref()
+                    .map(|reference| store.resolve_reference(reference))
+                    .transpose()?;
+                if !opts.yes {
+                    let target = match (&reference, &opts.digest, &opts.matching) {
+                        (Some(reference), _, _) if opts.all_tags => format!(
+                            "every tag of '{}/{}'",
+                            reference.registry(),
+                            reference.repository()
+                        ),
+                        (Some(reference), _, _) => format!("'{}'", reference.whole()),
+                        (None, Some(prefix), _) => {
+                            format!("all packages whose digest starts with '{prefix}'")
+                        }
+                        (None, None, Some(pattern)) => {
+                            format!("all packages matching '{pattern}'")
+                        }
+                        (None, None, None) => anyhow::bail!("nothing to delete"),
+                    };
+                    if !crate::util::confirm(&format!("Delete {target} from the local store?"))? {
+                        println!("Nothing deleted");
+                        return Ok(());
+                    }
                 }
+                let count = match (reference, opts.digest, opts.matching) {
+                    (Some(reference), _, _) if opts.all_tags => {
+                        store
+                            .delete_repository(reference.registry(), reference.repository())
+                            .await?
+                    }
+                    (Some(reference), _, _) => {
+                        if store.delete(reference.clone()).await? {
+                            println!("Deleted '{}'", reference.whole());
+                        } else {
+                            println!("Package '{}' not found in local store", reference.whole());
+                        }
+                        return Ok(());
+                    }
+                    (None, Some(prefix), _) => store.delete_by_digest_prefix(&prefix).await?,
+                    (None, None, Some(pattern)) => store.delete_matching(&pattern).await?,
+                    (None, None, None) => anyhow::bail!("nothing to delete"),
+                };
+                println!("Deleted {count} package(s)");
                 Ok(())
             }
-            Opts::List(_opts) => {
-                let images = store.list_all()?;
-                if images.is_empty() {
-                    println!("No installed packages");
+            Opts::Prune(opts) => {
+                let policy = PrunePolicy {
+                    unused_for: opts
+                        .unused_for
+                        .map(|days| Duration::from_hours(days.saturating_mul(24))),
+                    keep_last: opts.keep_last,
+                    max_size: None,
+                    dry_run: opts.dry_run,
+                };
+                let removed = store.prune(&policy).await?;
+                let verb = if opts.dry_run {
+                    "Would remove"
                 } else {
-                    println!("{}", render_list_table(&images));
+                    "Removed"
+                };
+                for reference in &removed {
+                    println!("{verb} '{reference}'");
                 }
+                println!("{verb} {} package(s)", removed.len());
                 Ok(())
             }
-            Opts::Known(opts) => {
-                let packages = store.list_known_packages(0, opts.limit)?;
-                if packages.is_empty() {
-                    println!("No known packages");
+            Opts::List(opts) => {
+                let sort = match opts.sort {
+                    ListSort::Name => SortBy::Name,
+                    ListSort::Size => SortBy::Size,
+                    ListSort::Registry => SortBy::Registry,
+                    ListSort::PulledAt => SortBy::PulledAt,
+                };
+                let direction = if opts.reverse {
+                    SortDirection::Descending
                 } else {
-                    println!("{}", search::render_search_table(&packages));
+                    SortDirection::Ascending
+                };
+                let images = store.list_all(sort, direction)?;
+                image_listing(&images).print(format, "No installed packages", || list_json(&images))
+            }
+            Opts::Known(opts) => {
+                let page = store.list_known_packages_page(opts.offset, opts.limit)?;
+                search::known_listing(&page.items).print(format, "No known packages", || {
+                    serde_json::json!(page.items)
+                })?;
+                if format == OutputFormat::Table
+                    && !page.items.is_empty()
+                    && let Some(next) = page.next_offset()
+                {
+                    println!(
+                        "Showing {} of {} known packages; use `--offset {next}` for more",
+                        page.items.len(),
+                        page.total
+                    );
                 }
                 Ok(())
             }
-            Opts::Inspect(opts) => opts.run(&store).await,
+            Opts::Inspect(opts) => opts.run(&store, format).await,
+            Opts::Import(opts) => opts.run(&store).await,
+            Opts::Export(opts) => opts.run(&store).await,
+            Opts::Serve(opts) => {
+                let listener = tokio::net::TcpListener::bind(opts.listen).await?;
+                let addr = listener.local_addr()?;
+                let count = store.stored_repositories()?.len();
+                println!("Serving {count} repositories from the local store on http://{addr}");
+                println!("Pull stored images as {addr}/<registry>/<repository>:<tag>");
+                serve::serve(store, listener).await
+            }
         }
     }
 }
 
-/// Render a list of [`ImageEntry`]s as a `comfy-table` table string.
+/// Print a single value for scripts: as a JSON string with `--json`, and
+/// otherwise on its own line.
+fn print_value(value: &str, format: OutputFormat) -> Result<()> {
+    if format.is_json() {
+        return crate::output::print_json(&value);
+    }
+    println!("{value}");
+    Ok(())
+}
+
+/// A list of [`ImageEntry`]s as a [`Listing`].
 ///
 /// Extracted for testability — the CLI calls this via `Opts::run`,
 /// but unit tests can call it directly without a database.
 #[must_use]
-fn render_list_table(images: &[ImageEntry]) -> String {
-    let mut table = Table::new();
-    table.set_content_arrangement(ContentArrangement::Dynamic);
-    table.set_header(vec!["PACKAGE", "TAG", "SIZE"]);
+fn image_listing(images: &[ImageEntry]) -> Listing {
+    let mut listing = Listing::new(["PACKAGE", "TAG", "SIZE", "DIGEST", "WORLD", "DESCRIPTION"]);
 
     for image in images {
         let reference = format!("{}/{}", image.ref_registry, image.ref_repository);
         let tag = image.ref_tag.as_deref().unwrap_or("-");
         let size = format_size(image.size_on_disk);
-        table.add_row(vec![&reference, tag, &size]);
+        let digest = image.ref_digest.as_deref().map_or("-", short_digest);
+        let world = image.world.as_deref().unwrap_or("-");
+        let description = image
+            .description
+            .as_deref()
+            .or(image.title.as_deref())
+            .unwrap_or("-");
+        listing.push([reference.as_str(), tag, &size, digest, world, description]);
     }
+    listing
+}
+
+/// The first 12 hex digits of a digest, as container tools show them.
+fn short_digest(digest: &str) -> &str {
+    let hex = digest.split_once(':').map_or(digest, |(_, hex)| hex);
+    hex.get(..12).unwrap_or(hex)
+}
 
-    table.to_string()
+/// The stored images as JSON, with full digests and sizes in bytes.
+fn list_json(images: &[ImageEntry]) -> serde_json::Value {
+    images
+        .iter()
+        .map(|image| {
+            serde_json::json!({
+                "registry": image.ref_registry,
+                "repository": image.ref_repository,
+                "tag": image.ref_tag,
+                "digest": image.ref_digest,
+                "size": image.size_on_disk,
+                "world": image.world,
+                "title": image.title,
+                "description": image.description,
+                "created": image.created,
+            })
+        })
+        .collect()
 }
 
 #[cfg(test)]
@@ -213,6 +439,11 @@ mod tests {
                 ref_digest: None,
                 manifest: OciImageManifest::default(),
                 size_on_disk: 1024 * 1024, // 1 MB
+                title: None,
+                description: Some("An HTTP server".into()),
+                source: None,
+                created: None,
+                world: Some("wasi:http/proxy@0.2.0".into()),
             },
             ImageEntry {
                 ref_registry: "ghcr.io".into(),
@@ -222,29 +453,50 @@ mod tests {
                 ref_digest: Some("sha256:abc123".into()),
                 manifest: OciImageManifest::default(),
                 size_on_disk: 512,
+                title: None,
+                description: None,
+                source: None,
+                created: None,
+                world: None,
             },
         ];
 
-        let output = render_list_table(&images);
+        let output = image_listing(&images).table().to_string();
 
         // Header row
         assert!(output.contains("PACKAGE"));
         assert!(output.contains("TAG"));
         assert!(output.contains("SIZE"));
+        assert!(output.contains("DIGEST"));
+        assert!(output.contains("WORLD"));
+        assert!(output.contains("DESCRIPTION"));
 
         // First image
         assert!(output.contains("ghcr.io/example/http-server"));
         assert!(output.contains("0.1.0"));
         assert!(output.contains("1.00 MB"));
+        assert!(output.contains("wasi:http/proxy@0.2.0"));
+        assert!(output.contains("An HTTP server"));
 
-        // Second image (no tag → dash)
+        // Second image (no tag → dash, short digest)
         assert!(output.contains("ghcr.io/example/logger"));
         assert!(output.contains("512 B"));
+        assert!(output.contains("abc123"));
+        assert!(!output.contains("sha256:abc123"));
+    }
+
+    #[test]
+    fn test_short_digest() {
+        assert_eq!(
+            short_digest("sha256:0123456789abcdef0123456789abcdef"),
+            "0123456789ab"
+        );
+        assert_eq!(short_digest("sha256:abc"), "abc");
     }
 
     #[test]
     fn test_render_list_table_empty() {
-        let output = render_list_table(&[]);
+        let output = image_listing(&[]).table().to_string();
         assert!(output.contains("PACKAGE"));
         // Table has headers but no data rows
         assert!(!output.contains("ghcr.io"));
@@ -84,12 +84,15 @@ async fn resolve_reference(input: &str, manager: &Manager) -> Result<Reference>
     // Refresh the known-package index so resolution can find packages
     // that haven't been touched locally yet. Failures here are
     // non-fatal — fall through to the local lookup.
-    let _ = manager
+    if let Err(e) = manager
         .sync_from_meta_registry(
             Manager::DEFAULT_REGISTRY_URL,
             Manager::DEFAULT_SYNC_INTERVAL,
             SyncPolicy::IfStale,
         )
-        .await;
+        .await
+    {
+        tracing::warn!("failed to sync package index: {e:#}");
+    }
     install::resolve_wit_name(input, manager)
 }
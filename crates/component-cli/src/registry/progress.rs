@@ -0,0 +1,198 @@
+//! Progress display for `component registry pull`.
+//!
+//! On a terminal each layer of the package gets its own progress bar with
+//! byte counts. Elsewhere, such as in CI logs, a plain line is printed as
+//! each layer is stored instead. Everything goes to stderr, so a component
+//! written to stdout with `--output -` isn't mixed with progress output.
+
+use component_package_manager::{ProgressEvent, format_size};
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+
+use super::short_digest;
+
+/// Template for layers whose size is known: yellow bar, dim sizes and ETA.
+const LAYER_TEMPLATE: &str =
+    "{prefix} {bar:20.yellow} {binary_bytes:.dim}/{binary_total_bytes:.dim} {eta:.dim}";
+
+/// Template for layers whose size isn't known: bytes downloaded only.
+const UNSIZED_LAYER_TEMPLATE: &str = "{prefix} {binary_bytes:.dim}";
+
+/// Template for stored layers: the size and a checkmark.
+const DONE_TEMPLATE: &str = "{prefix} {msg}";
+
+/// What a pull transferred, for the summary line.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct PullStats {
+    /// The number of layers in the package.
+    pub(crate) layers: usize,
+    /// The size of every stored layer, downloaded or already present.
+    pub(crate) bytes: u64,
+}
+
+/// One layer being pulled.
+struct Layer {
+    label: String,
+    total: Option<u64>,
+    downloaded: u64,
+    bar: Option<ProgressBar>,
+}
+
+impl Layer {
+    /// The layer's size once stored: its declared size, or failing that what
+    /// was downloaded.
+    fn size(&self) -> u64 {
+        self.total.unwrap_or(self.downloaded)
+    }
+}
+
+/// Render `events` as progress bars when `interactive`, or as plain lines
+/// otherwise, until the sender is dropped.
+pub(crate) async fn render(
+    mut events: tokio::sync::mpsc::Receiver<ProgressEvent>,
+    interactive: bool,
+) -> PullStats {
+    let multi = MultiProgress::new();
+    let mut layers: Vec<Layer> = Vec::new();
+    let mut layer_count = 0;
+
+    while let Some(event) = events.recv().await {
+        match event {
+            ProgressEvent::ManifestFetched { layer_count: n, .. } => layer_count = n,
+            ProgressEvent::LayerStarted {
+                index,
+                digest,
+                total_bytes,
+                title,
+                ..
+            } => {
+                let label = title.unwrap_or_else(|| short_digest(&digest).to_string());
+                let bar = interactive.then(|| layer_bar(&multi, &label, total_bytes));
+                if layers.len() <= index {
+                    layers.resize_with(index + 1, || Layer {
+                        label: String::new(),
+                        total: None,
+                        downloaded: 0,
+                        bar: None,
+                    });
+                }
+                if let Some(layer) = layers.get_mut(index) {
+                    *layer = Layer {
+                        label,
+                        total: total_bytes,
+                        downloaded: 0,
+                        bar,
+                    };
+                }
+            }
+            ProgressEvent::LayerProgress {
+                index,
+                bytes_downloaded,
+            } => {
+                if let Some(layer) = layers.get_mut(index) {
+                    layer.downloaded = bytes_downloaded;
+                    if let Some(bar) = &layer.bar {
+                        bar.set_position(bytes_downloaded);
+                    }
+                }
+            }
+            ProgressEvent::LayerStored { index } => {
+                if let Some(layer) = layers.get(index) {
+                    finish_layer(layer, index, layer_count.max(layers.len()));
+                }
+            }
+            ProgressEvent::LayerDownloaded { .. } | ProgressEvent::InstallComplete => {}
+        }
+    }
+
+    PullStats {
+        layers: layer_count.max(layers.len()),
+        bytes: layers.iter().map(Layer::size).sum(),
+    }
+}
+
+/// Add a bar for a layer to `multi`.
+fn layer_bar(multi: &MultiProgress, label: &str, total: Option<u64>) -> ProgressBar {
+    let bar = multi.add(ProgressBar::new(total.unwrap_or(0)));
+    let template = if total.is_some() {
+        LAYER_TEMPLATE
+    } else {
+        UNSIZED_LAYER_TEMPLATE
+    };
+    bar.set_style(
+        ProgressStyle::with_template(template)
+            .expect("valid progress bar template")
+            .progress_chars("━━┄"),
+    );
+    bar.set_prefix(format!("  {label}"));
+    bar
+}
+
+/// Mark a layer as stored: finish its bar, or print a line for it.
+#[allow(clippy::print_stderr)]
+fn finish_layer(layer: &Layer, index: usize, count: usize) {
+    let done = format!(
+        "{} {}",
+        console::style(format_size(layer.size())).dim(),
+        console::style("✓").green()
+    );
+    match &layer.bar {
+        Some(bar) => {
+            bar.set_style(ProgressStyle::with_template(DONE_TEMPLATE).expect("valid template"));
+            bar.finish_with_message(done);
+        }
+        None => eprintln!("  [{}/{count}] {} {done}", index + 1, layer.label),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn stats_count_layers_and_bytes() {
+        let (tx, rx) = tokio::sync::mpsc::channel(16);
+        let events = [
+            ProgressEvent::ManifestFetched {
+                layer_count: 2,
+                image_digest: "sha256:image".into(),
+            },
+            ProgressEvent::LayerStarted {
+                index: 0,
+                digest: "sha256:0123456789abcdef".into(),
+                total_bytes: Some(100),
+                title: None,
+                media_type: "application/wasm".into(),
+            },
+            ProgressEvent::LayerProgress {
+                index: 0,
+                bytes_downloaded: 100,
+            },
+            ProgressEvent::LayerStored { index: 0 },
+            ProgressEvent::LayerStarted {
+                index: 1,
+                digest: "sha256:fedcba".into(),
+                total_bytes: None,
+                title: Some("wit".into()),
+                media_type: "application/wasm".into(),
+            },
+            ProgressEvent::LayerProgress {
+                index: 1,
+                bytes_downloaded: 20,
+            },
+            ProgressEvent::LayerStored { index: 1 },
+        ];
+        for event in events {
+            tx.send(event).await.unwrap();
+        }
+        drop(tx);
+
+        let stats = render(rx, false).await;
+        assert_eq!(
+            stats,
+            PullStats {
+                layers: 2,
+                bytes: 120
+            }
+        );
+    }
+}
@@ -0,0 +1,196 @@
+#![allow(clippy::print_stdout, clippy::print_stderr)]
+
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use anyhow::{Context, Result};
+use comfy_table::{ContentArrangement, Table};
+use component_package_manager::manager::{Manager, PullResult};
+use component_package_manager::oci::InsertResult;
+use component_package_manager::{Reference, format_size};
+use futures_concurrency::prelude::*;
+
+use super::{PullError, progress};
+
+/// How many packages to pull at once.
+const MAX_CONCURRENT_PULLS: usize = 8;
+
+/// Pull components from the registry.
+#[derive(clap::Args)]
+pub(crate) struct PullOpts {
+    /// The references to pull
+    #[arg(required_unless_present = "lockfile")]
+    references: Vec<String>,
+    /// Also pull every package locked in this lockfile (e.g., wasm.lock.toml)
+    #[arg(long, value_name = "PATH")]
+    lockfile: Option<PathBuf>,
+    /// Reject artifacts that aren't wasm OCI artifacts, such as container images
+    #[arg(long)]
+    strict: bool,
+    /// Also write the component to this path after pulling, or to stdout if `-`
+    #[arg(short, long, value_name = "PATH", conflicts_with = "lockfile")]
+    output: Option<PathBuf>,
+    /// For image indexes, pull the entry for this platform, e.g. `wasip2/wasm`
+    #[arg(long, value_name = "PLATFORM", value_parser = parse_platform, conflicts_with = "lockfile")]
+    platform: Option<String>,
+    /// For image indexes, pull the entry with this artifact type, e.g.
+    /// `application/vnd.wasm.config.v0+json`
+    #[arg(long, value_name = "TYPE", conflicts_with = "lockfile")]
+    artifact_type: Option<String>,
+}
+
+impl PullOpts {
+    pub(crate) async fn run(self, store: &mut Manager) -> Result<()> {
+        if self.strict {
+            store.set_strict(true);
+        }
+        match (self.references.as_slice(), &self.lockfile) {
+            ([reference], None) => self.pull_one(store, reference).await,
+            _ if self.output.is_some() => {
+                anyhow::bail!("`--output` needs exactly one reference to pull")
+            }
+            _ if self.platform.is_some() || self.artifact_type.is_some() => {
+                anyhow::bail!(
+                    "`--platform` and `--artifact-type` need exactly one reference to pull"
+                )
+            }
+            _ => pull_batch(store, &self.references, self.lockfile.as_deref()).await,
+        }
+    }
+
+    /// Pull a single reference, showing the progress of each layer, and write
+    /// it to `--output` if given.
+    async fn pull_one(&self, store: &Manager, reference: &str) -> Result<()> {
+        let mut reference = store.resolve_reference(reference)?;
+        if self.platform.is_some() || self.artifact_type.is_some() {
+            reference = store
+                .select_index_entry(
+                    &reference,
+                    self.platform.as_deref(),
+                    self.artifact_type.as_deref(),
+                )
+                .await?;
+        }
+        let interactive = std::io::stderr().is_terminal();
+        if !interactive {
+            eprintln!("Pulling '{}'", reference.whole());
+        }
+
+        let started = Instant::now();
+        let (tx, rx) = tokio::sync::mpsc::channel(64);
+        let render = tokio::spawn(progress::render(rx, interactive));
+        let result = store.pull_with_progress(reference.clone(), &tx).await;
+        drop(tx);
+        let stats = render.await.unwrap_or_default();
+        let result = result?;
+
+        if result.insert_result == InsertResult::AlreadyExists {
+            eprintln!(
+                "{} '{}' is already in the local store",
+                console::style("✓").green(),
+                reference.whole()
+            );
+        } else {
+            eprintln!(
+                "{} Pulled '{}' ({} {}, {}) in {:.1}s",
+                console::style("✓").green(),
+                reference.whole(),
+                stats.layers,
+                if stats.layers == 1 { "layer" } else { "layers" },
+                format_size(stats.bytes),
+                started.elapsed().as_secs_f64()
+            );
+        }
+        match self.output.as_deref() {
+            Some(output) if output.as_os_str() == "-" => {
+                use std::io::Write;
+                let bytes = store.read_component(&reference).await?;
+                let mut stdout = std::io::stdout().lock();
+                stdout.write_all(&bytes)?;
+                stdout.flush()?;
+            }
+            Some(output) => {
+                let written = store.extract(&reference, output).await?;
+                println!(
+                    "Extracted '{}' to {} ({})",
+                    reference.whole(),
+                    output.display(),
+                    format_size(written)
+                );
+            }
+            None => {}
+        }
+        Ok(())
+    }
+}
+
+/// Parse a `--platform` value: `os/architecture` or `os/architecture/variant`.
+fn parse_platform(s: &str) -> Result<String, String> {
+    let parts: Vec<&str> = s.split('/').collect();
+    if (2..=3).contains(&parts.len()) && parts.iter().all(|part| !part.is_empty()) {
+        Ok(s.to_string())
+    } else {
+        Err("expected `os/architecture` or `os/architecture/variant`, e.g. `wasip2/wasm`".into())
+    }
+}
+
+/// Pull `references` and the packages locked in `lockfile` concurrently, and
+/// print a summary of what happened to each.
+async fn pull_batch(store: &Manager, references: &[String], lockfile: Option<&Path>) -> Result<()> {
+    let references = references
+        .iter()
+        .map(|reference| store.resolve_reference(reference))
+        .collect::<Result<Vec<_>, _>>()?;
+    let lockfile = match lockfile {
+        Some(path) => {
+            let text = std::fs::read_to_string(path)
+                .with_context(|| format!("could not read lockfile '{}'", path.display()))?;
+            let lockfile: component_manifest::Lockfile = toml::from_str(&text)
+                .with_context(|| format!("could not parse lockfile '{}'", path.display()))?;
+            Some(lockfile)
+        }
+        None => None,
+    };
+
+    let pull_locked = async {
+        match &lockfile {
+            Some(lockfile) => store.pull_locked(lockfile, MAX_CONCURRENT_PULLS).await,
+            None => Ok(Vec::new()),
+        }
+    };
+    let (mut results, locked) = (
+        store.pull_many(references, MAX_CONCURRENT_PULLS),
+        pull_locked,
+    )
+        .join()
+        .await;
+    results.extend(locked?);
+
+    let failed = results.iter().filter(|(_, result)| result.is_err()).count();
+    println!("{}", summary_table(&results));
+    if failed > 0 {
+        return Err(PullError::Failed {
+            failed,
+            total: results.len(),
+        }
+        .into());
+    }
+    Ok(())
+}
+
+/// A table of each pulled reference and the outcome of pulling it.
+fn summary_table(results: &[(Reference, component_package_manager::Result<PullResult>)]) -> Table {
+    let mut table = Table::new();
+    table.set_content_arrangement(ContentArrangement::Dynamic);
+    table.set_header(vec!["PACKAGE", "RESULT"]);
+    for (reference, result) in results {
+        let outcome = match result {
+            Ok(pulled) if pulled.insert_result == InsertResult::Inserted => "inserted".to_string(),
+            Ok(_) => "already present".to_string(),
+            Err(err) => format!("failed: {err}"),
+        };
+        table.add_row(vec![reference.whole(), outcome]);
+    }
+    table
+}
@@ -1,9 +1,10 @@
 //! `component registry search` subcommand.
 
 use anyhow::Result;
-use comfy_table::{ContentArrangement, Table};
 use component_package_manager::manager::{Manager, SyncPolicy, SyncResult};
 
+use crate::output::{Listing, OutputFormat};
+
 /// Default meta-registry URL.
 const REGISTRY_URL: &str = Manager::DEFAULT_REGISTRY_URL;
 
@@ -31,7 +32,7 @@ pub(crate) struct SearchOpts {
 }
 
 impl SearchOpts {
-    pub(crate) async fn run(self, offline: bool) -> Result<()> {
+    pub(crate) async fn run(self, offline: bool, format: OutputFormat) -> Result<()> {
         let manager = if offline {
             Manager::open_offline().await?
         } else {
@@ -71,41 +72,116 @@ impl SearchOpts {
             packages = filter_by_text(packages, query, self.limit);
         }
 
-        if packages.is_empty() {
-            let message = match (&self.exports, &self.imports) {
-                (Some(iface), _) if !query.is_empty() => {
-                    format!("No packages found exporting '{iface}' matching '{query}'")
-                }
-                (_, Some(iface)) if !query.is_empty() => {
-                    format!("No packages found importing '{iface}' matching '{query}'")
+        // Online, also look through what the configured registries list, for
+        // packages the index doesn't know about yet.
+        let remote = if offline || self.exports.is_some() || self.imports.is_some() {
+            Vec::new()
+        } else {
+            match manager.search_remote(query).await {
+                Ok(remote) => remote,
+                Err(e) => {
+                    tracing::warn!("{e}");
+                    Vec::new()
                 }
-                (Some(iface), _) => format!("No packages found exporting '{iface}'"),
-                (_, Some(iface)) => format!("No packages found importing '{iface}'"),
-                _ => format!(
-                    "No packages found matching '{}'",
-                    self.query.as_deref().unwrap_or_default()
-                ),
-            };
-            println!("{message}");
-            return Ok(());
+            }
+        };
+        let results = merge_results(packages, remote, self.limit);
+
+        let empty = match (&self.exports, &self.imports) {
+            (Some(iface), _) if !query.is_empty() => {
+                format!("No packages found exporting '{iface}' matching '{query}'")
+            }
+            (_, Some(iface)) if !query.is_empty() => {
+                format!("No packages found importing '{iface}' matching '{query}'")
+            }
+            (Some(iface), _) => format!("No packages found exporting '{iface}'"),
+            (_, Some(iface)) => format!("No packages found importing '{iface}'"),
+            _ => format!("No packages found matching '{query}'"),
+        };
+        result_listing(&results).print(format, &empty, || {
+            results
+                .iter()
+                .map(|(pkg, source)| {
+                    let mut value = serde_json::json!(pkg);
+                    if let Some(object) = value.as_object_mut() {
+                        object.insert("source".into(), source.as_str().into());
+                    }
+                    value
+                })
+                .collect()
+        })
+    }
+}
+
+/// Where a search result was found.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Source {
+    /// In the local package index.
+    Cached,
+    /// In a registry's listing, and not in the local index before.
+    Remote,
+}
+
+/// Label `cached` and `remote` results, dropping remote results that are
+/// also cached, and keep at most `limit` of them, cached ones first.
+fn merge_results(
+    cached: Vec<component_package_manager::storage::KnownPackage>,
+    remote: Vec<component_package_manager::storage::KnownPackage>,
+    limit: u32,
+) -> Vec<(component_package_manager::storage::KnownPackage, Source)> {
+    let known: std::collections::HashSet<String> = cached
+        .iter()
+        .map(component_package_manager::storage::KnownPackage::reference)
+        .collect();
+    let remote = remote
+        .into_iter()
+        .filter(|pkg| !known.contains(&pkg.reference()))
+        .map(|pkg| (pkg, Source::Remote));
+    cached
+        .into_iter()
+        .map(|pkg| (pkg, Source::Cached))
+        .chain(remote)
+        .take(limit as usize)
+        .collect()
+}
+
+impl Source {
+    fn as_str(self) -> &'static str {
+        match self {
+            Source::Cached => "cached",
+            Source::Remote => "remote",
         }
+    }
+}
+
+/// Labeled search results as a [`Listing`].
+fn result_listing(
+    results: &[(component_package_manager::storage::KnownPackage, Source)],
+) -> Listing {
+    let mut listing = Listing::new(["PACKAGE", "DESCRIPTION", "TAGS", "SOURCE"]);
 
-        println!("{}", render_search_table(&packages));
-        Ok(())
+    for (pkg, source) in results {
+        let reference = pkg.reference();
+        let description = pkg.description.as_deref().unwrap_or("-");
+        let tags = if pkg.tags.is_empty() {
+            "-".to_string()
+        } else {
+            pkg.tags.join(", ")
+        };
+        listing.push([reference.as_str(), description, &tags, source.as_str()]);
     }
+    listing
 }
 
-/// Render a list of [`KnownPackage`]s as a `comfy-table` table string.
+/// A list of [`KnownPackage`]s as a [`Listing`].
 ///
-/// Extracted for testability — the CLI calls this via `SearchOpts::run`,
-/// but unit tests can call it directly without a database.
+/// Extracted for testability — the CLI calls this via `Opts::run`, but unit
+/// tests can call it directly without a database.
 #[must_use]
-pub(crate) fn render_search_table(
+pub(crate) fn known_listing(
     packages: &[component_package_manager::storage::KnownPackage],
-) -> String {
-    let mut table = Table::new();
-    table.set_content_arrangement(ContentArrangement::Dynamic);
-    table.set_header(vec!["PACKAGE", "DESCRIPTION", "TAGS"]);
+) -> Listing {
+    let mut listing = Listing::new(["PACKAGE", "DESCRIPTION", "TAGS"]);
 
     for pkg in packages {
         let reference = pkg.reference();
@@ -115,10 +191,9 @@ pub(crate) fn render_search_table(
         } else {
             pkg.tags.join(", ")
         };
-        table.add_row(vec![&reference, description, &tags]);
+        listing.push([reference.as_str(), description, &tags]);
     }
-
-    table.to_string()
+    listing
 }
 
 /// Narrow a list of packages to those whose reference or description
@@ -165,6 +240,7 @@ mod tests {
                 wit_namespace: None,
                 wit_name: None,
                 dependencies: vec![],
+                pinned: false,
             },
             KnownPackage {
                 registry: "ghcr.io".into(),
@@ -179,10 +255,11 @@ mod tests {
                 wit_namespace: None,
                 wit_name: None,
                 dependencies: vec![],
+                pinned: false,
             },
         ];
 
-        let output = render_search_table(&packages);
+        let output = known_listing(&packages).table().to_string();
 
         // Header row
         assert!(output.contains("PACKAGE"));
@@ -200,7 +277,7 @@ mod tests {
 
     #[test]
     fn test_render_search_table_empty() {
-        let output = render_search_table(&[]);
+        let output = known_listing(&[]).table().to_string();
         assert!(output.contains("PACKAGE"));
         // Table has headers but no data rows
         assert!(!output.contains("ghcr.io"));
@@ -222,6 +299,7 @@ mod tests {
                 wit_namespace: None,
                 wit_name: None,
                 dependencies: vec![],
+                pinned: false,
             },
             KnownPackage {
                 registry: "ghcr.io".into(),
@@ -236,6 +314,7 @@ mod tests {
                 wit_namespace: None,
                 wit_name: None,
                 dependencies: vec![],
+                pinned: false,
             },
         ];
 
@@ -262,4 +341,56 @@ mod tests {
         let result = filter_by_text(packages, "example", 1);
         assert_eq!(result.len(), 1);
     }
+
+    fn known(repository: &str) -> KnownPackage {
+        KnownPackage {
+            registry: "ghcr.io".into(),
+            repository: repository.into(),
+            kind: None,
+            description: None,
+            tags: vec![],
+            signature_tags: vec![],
+            attestation_tags: vec![],
+            last_seen_at: "2025-01-01 00:00:00".into(),
+            created_at: "2025-01-01 00:00:00".into(),
+            wit_namespace: None,
+            wit_name: None,
+            dependencies: vec![],
+            pinned: false,
+        }
+    }
+
+    #[test]
+    fn test_merge_results_labels_and_dedups() {
+        let cached = vec![known("example/http-server")];
+        let remote = vec![known("example/http-server"), known("example/http-client")];
+
+        let results = merge_results(cached, remote, 20);
+        let labeled: Vec<_> = results
+            .iter()
+            .map(|(pkg, source)| (pkg.repository.as_str(), *source))
+            .collect();
+        assert_eq!(
+            labeled,
+            [
+                ("example/http-server", Source::Cached),
+                ("example/http-client", Source::Remote),
+            ]
+        );
+
+        let output = result_listing(&results).table().to_string();
+        assert!(output.contains("SOURCE"));
+        assert!(output.contains("cached"));
+        assert!(output.contains("remote"));
+    }
+
+    #[test]
+    fn test_merge_results_respects_limit() {
+        let cached = vec![known("example/a"), known("example/b")];
+        let remote = vec![known("example/c")];
+
+        let results = merge_results(cached, remote, 2);
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|(_, source)| *source == Source::Cached));
+    }
 }
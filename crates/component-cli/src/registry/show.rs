@@ -0,0 +1,98 @@
+#![allow(clippy::print_stdout, clippy::print_stderr)]
+
+use anyhow::Result;
+use comfy_table::{ContentArrangement, Table};
+use component_package_manager::format_size;
+use component_package_manager::manager::Manager;
+
+use crate::output::{OutputFormat, print_json};
+
+/// Fetch OCI metadata for a component without downloading it.
+#[derive(clap::Args)]
+pub(crate) struct ShowOpts {
+    /// The reference to show (e.g., ghcr.io/example/component:tag)
+    reference: String,
+}
+
+impl ShowOpts {
+    pub(crate) async fn run(self, store: &Manager, format: OutputFormat) -> Result<()> {
+        let reference = store.resolve_reference(&self.reference)?;
+        let inspection = store.inspect_remote(&reference).await?;
+
+        if format.is_json() {
+            print_json(&inspection)?;
+            return Ok(());
+        }
+
+        let mut table = Table::new();
+        table
+            .load_preset(comfy_table::presets::NOTHING)
+            .set_content_arrangement(ContentArrangement::Dynamic);
+        table.add_row(vec!["Reference", &inspection.reference]);
+        table.add_row(vec!["Digest", &inspection.digest]);
+        if let Some(media_type) = &inspection.media_type {
+            table.add_row(vec!["Media type", media_type]);
+        }
+        if let Some(artifact_type) = &inspection.artifact_type {
+            table.add_row(vec!["Artifact type", artifact_type]);
+        }
+        table.add_row(vec!["Config", &inspection.config_media_type]);
+        table.add_row(vec!["Size".to_string(), format_size(inspection.size)]);
+        if let Some(created) = &inspection.created {
+            table.add_row(vec!["Created", created]);
+        }
+        if let Some(author) = &inspection.author {
+            table.add_row(vec!["Author", author]);
+        }
+        if let Some(os) = &inspection.os {
+            table.add_row(vec!["OS", os]);
+        }
+        if let Some(target) = &inspection.target {
+            table.add_row(vec!["World", target]);
+        }
+        for layer in &inspection.layers {
+            table.add_row(vec![
+                "Layer".to_string(),
+                format!(
+                    "{} {} ({})",
+                    layer.digest,
+                    layer.media_type,
+                    format_size(layer.size)
+                ),
+            ]);
+        }
+        for (key, value) in &inspection.annotations {
+            table.add_row(vec!["Annotation".to_string(), format!("{key}={value}")]);
+        }
+        for import in &inspection.imports {
+            table.add_row(vec!["Import", import]);
+        }
+        for export in &inspection.exports {
+            table.add_row(vec!["Export", export]);
+        }
+        if !inspection.tags.is_empty() {
+            table.add_row(vec!["Tags".to_string(), inspection.tags.join(", ")]);
+        }
+        table.add_row(vec!["Signed", yes_no(inspection.signed)]);
+        table.add_row(vec!["Attested", yes_no(inspection.attested)]);
+        for referrer in &inspection.referrers {
+            let kind = if referrer.signature {
+                "signature"
+            } else if referrer.attestation {
+                "attestation"
+            } else {
+                referrer.media_type.as_str()
+            };
+            table.add_row(vec![
+                "Referrer".to_string(),
+                format!("{} ({kind})", referrer.digest),
+            ]);
+        }
+        println!("{table}");
+        Ok(())
+    }
+}
+
+fn yes_no(value: bool) -> &'static str {
+    if value { "yes" } else { "no" }
+}
@@ -0,0 +1,101 @@
+#![allow(clippy::print_stdout, clippy::print_stderr)]
+
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use component_package_manager::manager::Manager;
+use component_package_manager::sign::{
+    DEFAULT_FULCIO_URL, DEFAULT_REKOR_URL, SignOptions, Signer, SigningKey,
+};
+
+use crate::output::{OutputFormat, print_json};
+
+/// Environment variable holding the password of an encrypted cosign key.
+const PASSWORD_VAR: &str = "COSIGN_PASSWORD";
+
+/// Environment variable holding the OIDC identity token for keyless signing.
+const IDENTITY_TOKEN_VAR: &str = "SIGSTORE_ID_TOKEN";
+
+/// Sign a component in its registry, in a way `cosign verify` accepts.
+#[derive(clap::Args)]
+#[allow(clippy::struct_excessive_bools)]
+pub(crate) struct SignOpts {
+    /// The reference to sign (e.g., ghcr.io/example/component:tag)
+    reference: String,
+    /// Private key to sign with: an ECDSA P-256 key in PEM form, or a key
+    /// made with `cosign generate-key-pair` (password in `COSIGN_PASSWORD`)
+    #[arg(long, value_name = "PATH", required_unless_present = "keyless")]
+    key: Option<PathBuf>,
+    /// Sign without a key of your own, using a short-lived certificate for
+    /// your OIDC identity
+    #[arg(long, conflicts_with = "key")]
+    keyless: bool,
+    /// OIDC identity token for keyless signing; read from `SIGSTORE_ID_TOKEN`
+    /// if not given
+    #[arg(long, value_name = "TOKEN", requires = "keyless")]
+    identity_token: Option<String>,
+    /// Certificate authority that issues keyless certificates
+    #[arg(long, value_name = "URL", default_value = DEFAULT_FULCIO_URL)]
+    fulcio_url: String,
+    /// Transparency log to record the signature in
+    #[arg(long, value_name = "URL", default_value = DEFAULT_REKOR_URL)]
+    rekor_url: String,
+    /// Don't record the signature in the transparency log
+    #[arg(long, conflicts_with = "keyless")]
+    no_tlog: bool,
+    /// Replace the artifact's existing signature
+    #[arg(long)]
+    force: bool,
+}
+
+impl SignOpts {
+    pub(crate) async fn run(self, store: &Manager, format: OutputFormat) -> Result<()> {
+        let reference = store.resolve_reference(&self.reference)?;
+        let options = SignOptions {
+            signer: self.signer()?,
+            rekor_url: (!self.no_tlog).then(|| self.rekor_url.clone()),
+            force: self.force,
+        };
+        let signed = store.sign(&reference, &options).await?;
+
+        if format.is_json() {
+            print_json(&signed)?;
+            return Ok(());
+        }
+        println!("Signed '{}' ({})", signed.reference, signed.digest);
+        println!("  Signature: {}", signed.signature);
+        if let Some(identity) = &signed.identity {
+            println!("  Identity:  {identity}");
+        }
+        if let Some(log_index) = signed.log_index {
+            println!("  Log index: {log_index}");
+        }
+        Ok(())
+    }
+
+    /// The signer the flags ask for.
+    fn signer(&self) -> Result<Signer> {
+        if self.keyless {
+            let identity_token = match &self.identity_token {
+                Some(token) => token.clone(),
+                None => std::env::var(IDENTITY_TOKEN_VAR).with_context(|| {
+                    format!(
+                        "keyless signing needs an OIDC identity token; \
+                         pass `--identity-token` or set `{IDENTITY_TOKEN_VAR}`"
+                    )
+                })?,
+            };
+            return Ok(Signer::Keyless {
+                identity_token,
+                fulcio_url: self.fulcio_url.clone(),
+            });
+        }
+
+        let path = self.key.as_deref().context("pass `--key` or `--keyless`")?;
+        let pem = std::fs::read_to_string(path)
+            .with_context(|| format!("could not read key '{}'", path.display()))?;
+        let password = std::env::var(PASSWORD_VAR).ok();
+        let key = SigningKey::from_pem(&pem, password.as_deref().map(str::as_bytes))?;
+        Ok(Signer::Key(key))
+    }
+}
@@ -0,0 +1,127 @@
+#![allow(clippy::print_stdout, clippy::print_stderr)]
+
+use anyhow::Result;
+use component_package_manager::manager::{Manager, TagGroups, group_tags};
+
+use crate::output::{OutputFormat, print_json};
+
+/// List all available tags for a component.
+#[derive(clap::Args)]
+pub(crate) struct TagsOpts {
+    /// The reference to list tags for (e.g., ghcr.io/example/component or oci://ghcr.io/example/component)
+    reference: String,
+    /// Include signature tags (ending in .sig)
+    #[arg(long)]
+    signatures: bool,
+    /// Include attestation tags (ending in .att)
+    #[arg(long)]
+    attestations: bool,
+}
+
+impl TagsOpts {
+    pub(crate) async fn run(
+        self,
+        store: &Manager,
+        offline: bool,
+        format: OutputFormat,
+    ) -> Result<()> {
+        let reference = store.resolve_reference(&self.reference)?;
+        let groups = group_tags(&store.list_tags(&reference).await?);
+
+        if format.is_json() {
+            let payload = serde_json::json!({
+                "reference": reference.whole(),
+                "offline": offline,
+                "tags": groups,
+            });
+            print_json(&payload)?;
+            return Ok(());
+        }
+
+        let tags = self.listed(&groups);
+        if matches!(format, OutputFormat::Plain | OutputFormat::Csv) {
+            for tag in tags {
+                println!("{tag}");
+            }
+            return Ok(());
+        }
+        if tags.is_empty() {
+            if offline {
+                println!(
+                    "No cached tags found for '{}' (offline mode)",
+                    reference.whole()
+                );
+            } else {
+                println!("No tags found for '{}'", reference.whole());
+            }
+        } else {
+            if offline {
+                println!("Cached tags for '{}' (offline mode):", reference.whole());
+            } else {
+                println!("Tags for '{}':", reference.whole());
+            }
+            for tag in tags {
+                println!("  {tag}");
+            }
+        }
+        Ok(())
+    }
+
+    /// The tags to list, in display order: releases and pre-releases newest
+    /// first, then other tags, then signatures and attestations if asked for.
+    fn listed<'a>(&self, groups: &'a TagGroups) -> Vec<&'a String> {
+        let mut tags: Vec<_> = groups
+            .releases
+            .iter()
+            .chain(&groups.prereleases)
+            .chain(&groups.other)
+            .collect();
+        if self.signatures {
+            tags.extend(&groups.signatures);
+        }
+        if self.attestations {
+            tags.extend(&groups.attestations);
+        }
+        tags
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[derive(clap::Parser)]
+    struct Cli {
+        #[command(flatten)]
+        opts: TagsOpts,
+    }
+
+    fn opts(args: &[&str]) -> TagsOpts {
+        Cli::parse_from(std::iter::once("tags").chain(args.iter().copied())).opts
+    }
+
+    #[test]
+    fn test_listed_orders_and_filters_tags() {
+        let groups = group_tags(&[
+            "latest".into(),
+            "0.2.0".into(),
+            "sha256-aaa.sig".into(),
+            "0.3.0-rc.1".into(),
+            "0.10.0".into(),
+            "sha256-aaa.att".into(),
+        ]);
+
+        let tags = opts(&["ghcr.io/example/component"]).listed(&groups);
+        assert_eq!(tags, ["0.10.0", "0.2.0", "0.3.0-rc.1", "latest"]);
+
+        let tags = opts(&["ghcr.io/example/component", "--signatures"]).listed(&groups);
+        assert_eq!(
+            tags,
+            ["0.10.0", "0.2.0", "0.3.0-rc.1", "latest", "sha256-aaa.sig"]
+        );
+
+        let tags = opts(&["ghcr.io/example/component", "--attestations"]).listed(&groups);
+        assert_eq!(tags.last().map(|tag| tag.as_str()), Some("sha256-aaa.att"));
+    }
+}
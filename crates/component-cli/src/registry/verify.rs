@@ -0,0 +1,118 @@
+#![allow(clippy::print_stdout, clippy::print_stderr)]
+
+use std::fmt::Write as _;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use component_package_manager::manager::{Manager, VerifyReport};
+use component_package_manager::sign::Verification;
+
+use super::VerifyError;
+use crate::output::{OutputFormat, print_json};
+
+/// Check a component's signatures and attestations against trusted keys.
+#[derive(clap::Args)]
+pub(crate) struct VerifyOpts {
+    /// The reference to verify (e.g., ghcr.io/example/component:tag)
+    reference: String,
+    /// Also trust this public key, such as a `cosign.pub`, on top of the
+    /// `trusted-keys` in policy.toml
+    #[arg(long, value_name = "PATH")]
+    key: Vec<PathBuf>,
+}
+
+impl VerifyOpts {
+    pub(crate) async fn run(self, store: &Manager, format: OutputFormat) -> Result<()> {
+        let reference = store.resolve_reference(&self.reference)?;
+        let report = store.verify(&reference, &self.key).await?;
+
+        if format.is_json() {
+            print_json(&report)?;
+        } else {
+            print!("{}", render_report(&report));
+        }
+        if !report.passed() {
+            return Err(VerifyError::Failed {
+                reference: report.reference,
+            }
+            .into());
+        }
+        Ok(())
+    }
+}
+
+/// A human summary of a verification report.
+fn render_report(report: &VerifyReport) -> String {
+    let status = |verification: &Verification| match verification {
+        Verification::Verified { key } => format!("verified by {key}"),
+        Verification::Untrusted => "not signed by a trusted key".to_string(),
+        Verification::Invalid { reason } => format!("invalid: {reason}"),
+    };
+
+    let mut out = if report.passed() {
+        format!("Verified '{}' ({})\n", report.reference, report.digest)
+    } else {
+        format!(
+            "Verification failed for '{}' ({})\n",
+            report.reference, report.digest
+        )
+    };
+    if report.trusted_keys.is_empty() {
+        out.push_str("  No trusted keys configured\n");
+    }
+    for signature in &report.signatures {
+        let kind = if signature.keyless {
+            "Keyless signature"
+        } else {
+            "Signature"
+        };
+        let _ = writeln!(out, "  {kind}: {}", status(&signature.verification));
+    }
+    for attestation in &report.attestations {
+        let predicate = attestation.predicate_type.as_deref().unwrap_or("unknown");
+        let _ = writeln!(
+            out,
+            "  Attestation ({predicate}): {}",
+            status(&attestation.verification)
+        );
+    }
+    for failure in &report.failures {
+        let _ = writeln!(out, "  ✗ {failure}");
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use component_package_manager::manager::{VerifiedAttestation, VerifiedSignature};
+
+    #[test]
+    fn render_report_lists_checks_and_failures() {
+        let report = VerifyReport {
+            reference: "ghcr.io/example/hello:1.0.0".into(),
+            digest: "sha256:0123".into(),
+            trusted_keys: vec!["cosign.pub".into()],
+            signatures: vec![VerifiedSignature {
+                keyless: false,
+                verification: Verification::Verified {
+                    key: "cosign.pub".into(),
+                },
+            }],
+            attestations: vec![VerifiedAttestation {
+                predicate_type: Some("https://slsa.dev/provenance/v1".into()),
+                verification: Verification::Untrusted,
+            }],
+            failures: vec!["no attestation was made by a trusted key".into()],
+        };
+        let out = render_report(&report);
+        assert!(out.starts_with("Verification failed for 'ghcr.io/example/hello:1.0.0'"));
+        assert!(out.contains("Signature: verified by cosign.pub"));
+        assert!(
+            out.contains(
+                "Attestation (https://slsa.dev/provenance/v1): not signed by a trusted key"
+            )
+        );
+        assert!(out.contains("✗ no attestation was made by a trusted key"));
+    }
+}
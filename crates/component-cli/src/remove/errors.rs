@@ -0,0 +1,58 @@
+//! Error types for the `component remove` CLI command.
+
+use miette::Diagnostic;
+
+/// CLI-specific error type for `component remove` command failures.
+///
+/// Each variant carries a stable [diagnostic error code][miette::Diagnostic::code]
+/// that uniquely identifies the failure.
+#[derive(Debug, Clone, PartialEq, Eq, Diagnostic)]
+#[must_use]
+pub(crate) enum RemoveError {
+    /// The manifest has no dependency by that name.
+    #[diagnostic(
+        code(component::remove::not_a_dependency),
+        help("dependencies are removed by the name they have in `wasm.toml`")
+    )]
+    NotADependency {
+        /// The name that was asked for.
+        name: String,
+    },
+}
+
+impl std::fmt::Display for RemoveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RemoveError::NotADependency { name } => {
+                write!(f, "'{name}' is not a dependency in `wasm.toml`")
+            }
+        }
+    }
+}
+
+impl std::error::Error for RemoveError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_all_variants_have_error_codes() {
+        use miette::Diagnostic;
+
+        let not_a_dependency = RemoveError::NotADependency {
+            name: "wasi:logging".to_string(),
+        };
+        assert_eq!(
+            not_a_dependency
+                .code()
+                .expect("NotADependency must have a diagnostic code")
+                .to_string(),
+            "component::remove::not_a_dependency",
+        );
+        assert!(
+            not_a_dependency.help().is_some(),
+            "NotADependency must have a help message"
+        );
+    }
+}
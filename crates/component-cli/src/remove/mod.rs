@@ -0,0 +1,148 @@
+#![allow(clippy::print_stdout, clippy::print_stderr)]
+
+mod errors;
+
+use std::path::{Path, PathBuf};
+
+use component_manifest::{LockedPackage, ManifestDocument, PackageType};
+use component_package_manager::manager::vendor_filename;
+use miette::{IntoDiagnostic, WrapErr};
+
+use crate::install::InstallError;
+use crate::util::{into_miette, read_lock_file, write_lock_file};
+use errors::RemoveError;
+
+/// Options for the `remove` command.
+#[derive(clap::Parser)]
+pub(crate) struct Opts {
+    /// The name of the dependency in `wasm.toml` (e.g., wasi:logging)
+    #[arg(value_name = "NAME")]
+    name: String,
+    /// Also delete the vendored files of packages dropped from the lockfile
+    #[arg(long)]
+    vendor: bool,
+}
+
+impl Opts {
+    pub(crate) async fn run(self) -> miette::Result<()> {
+        let manifest_path = PathBuf::from("wasm.toml");
+        let lockfile_path = PathBuf::from("wasm.lock.toml");
+        let wasm_vendor_dir = PathBuf::from("vendor/wasm");
+        let wit_vendor_dir = PathBuf::from("vendor/wit");
+
+        if !manifest_path.exists() {
+            return Err(InstallError::NoManifest.into());
+        }
+        let source = tokio::fs::read_to_string(&manifest_path)
+            .await
+            .into_diagnostic()
+            .wrap_err_with(|| format!("could not read '{}'", manifest_path.display()))?;
+        let mut document: ManifestDocument = source.parse()?;
+        let kind =
+            document
+                .remove_dependency(&self.name)?
+                .ok_or_else(|| RemoveError::NotADependency {
+                    name: self.name.clone(),
+                })?;
+
+        // What remains in the manifest decides what the lockfile keeps.
+        let edited = document.to_string();
+        let manifest: component_manifest::Manifest = toml::from_str(&edited).into_diagnostic()?;
+        let mut lockfile = read_lock_file(&lockfile_path).await.map_err(into_miette)?;
+        let roots = manifest
+            .dependencies
+            .components
+            .keys()
+            .chain(manifest.dependencies.interfaces.keys())
+            .map(String::as_str);
+        let pruned = lockfile.prune_unreachable(roots);
+
+        let mut deleted = Vec::new();
+        if self.vendor {
+            for package in &pruned {
+                for path in vendored_paths(package, &wasm_vendor_dir, &wit_vendor_dir) {
+                    match tokio::fs::remove_file(&path).await {
+                        Ok(()) => deleted.push(path),
+                        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                        Err(e) => {
+                            return Err(e).into_diagnostic().wrap_err_with(|| {
+                                format!("could not delete '{}'", path.display())
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        tokio::fs::write(&manifest_path, edited)
+            .await
+            .into_diagnostic()
+            .wrap_err_with(|| format!("could not write '{}'", manifest_path.display()))?;
+        if lockfile_path.exists() {
+            write_lock_file(&lockfile_path, &lockfile)
+                .await
+                .into_diagnostic()?;
+        }
+
+        let section = match kind {
+            PackageType::Component => "components",
+            PackageType::Interface => "interfaces",
+        };
+        println!(
+            "{} Removed {} from [dependencies.{section}]",
+            console::style("✓").green().bold(),
+            self.name,
+        );
+        for package in &pruned {
+            println!("  Unlocked {} {}", package.name, package.version);
+        }
+        for path in &deleted {
+            println!("  Deleted {}", path.display());
+        }
+        Ok(())
+    }
+}
+
+/// Where `install` may have vendored `package`: the binary under
+/// `wasm_vendor_dir`, or its WIT text under `wit_vendor_dir`.
+fn vendored_paths(
+    package: &LockedPackage,
+    wasm_vendor_dir: &Path,
+    wit_vendor_dir: &Path,
+) -> Vec<PathBuf> {
+    // The lockfile `registry` field is "host/repository".
+    let Some((registry, repository)) = package.registry.split_once('/') else {
+        return Vec::new();
+    };
+    let tag = (!package.version.is_empty()).then_some(package.version.as_str());
+    let filename = vendor_filename(registry, repository, tag, &package.digest);
+    vec![
+        wasm_vendor_dir.join(&filename),
+        wit_vendor_dir.join(&filename).with_extension("wit"),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vendored_paths_match_install() {
+        let package = LockedPackage {
+            name: "wasi:logging".to_string(),
+            version: "1.0.0".to_string(),
+            registry: "ghcr.io/webassembly/wasi-logging".to_string(),
+            digest: "sha256:abcdef1234567890".to_string(),
+            dependencies: Vec::new(),
+        };
+        assert_eq!(
+            vendored_paths(&package, Path::new("vendor/wasm"), Path::new("vendor/wit")),
+            [
+                PathBuf::from(
+                    "vendor/wasm/ghcr-io-webassembly-wasi-logging-1.0.0-abcdef123456.wasm"
+                ),
+                PathBuf::from("vendor/wit/ghcr-io-webassembly-wasi-logging-1.0.0-abcdef123456.wit"),
+            ]
+        );
+    }
+}
@@ -19,13 +19,6 @@ pub(crate) enum RunError {
     )]
     NoManifest,
 
-    /// The OCI manifest contains no `application/wasm` layer.
-    #[diagnostic(
-        code(component::run::no_wasm_layer),
-        help("ensure the image contains an `application/wasm` layer")
-    )]
-    NoWasmLayer,
-
     /// A manifest component key is not present in the lockfile.
     #[diagnostic(
         code(component::run::not_in_lockfile),
@@ -93,6 +86,18 @@ pub(crate) enum RunError {
         /// The manifest key that was looked up.
         name: String,
     },
+
+    /// `--serve` was given for a component that doesn't handle HTTP requests.
+    #[diagnostic(
+        code(component::run::not_an_http_component),
+        help(
+            "only components that export `wasi:http/incoming-handler` can be served; drop `--serve` to run it"
+        )
+    )]
+    NotAnHttpComponent {
+        /// The component that was run.
+        input: String,
+    },
 }
 
 impl std::fmt::Display for RunError {
@@ -101,9 +106,6 @@ impl std::fmt::Display for RunError {
             RunError::NoManifest => {
                 write!(f, "pulled image has no manifest")
             }
-            RunError::NoWasmLayer => {
-                write!(f, "manifest contains no application/wasm layer")
-            }
             RunError::NotInLockfile { name } => {
                 write!(
                     f,
@@ -125,6 +127,9 @@ impl std::fmt::Display for RunError {
             RunError::NotInGlobalCache { name } => {
                 write!(f, "component '{name}' is not present in the global cache")
             }
+            RunError::NotAnHttpComponent { input } => {
+                write!(f, "'{input}' is not an HTTP component and can't be served")
+            }
         }
     }
 }
@@ -141,7 +146,6 @@ mod tests {
 
         let variants: Vec<Box<dyn Diagnostic>> = vec![
             Box::new(RunError::NoManifest),
-            Box::new(RunError::NoWasmLayer),
             Box::new(RunError::NotInLockfile {
                 name: "test".to_string(),
             }),
@@ -160,16 +164,23 @@ mod tests {
             Box::new(RunError::HttpAcceptFailed {
                 reason: "test".to_string(),
             }),
+            Box::new(RunError::NotInGlobalCache {
+                name: "test".to_string(),
+            }),
+            Box::new(RunError::NotAnHttpComponent {
+                input: "app.wasm".to_string(),
+            }),
         ];
 
         let expected_codes = [
             "component::run::no_manifest",
-            "component::run::no_wasm_layer",
             "component::run::not_in_lockfile",
             "component::run::invalid_registry_path",
             "component::run::vendored_file_missing",
             "component::run::http_bind_failed",
             "component::run::http_accept_failed",
+            "component::run::not_in_global_cache",
+            "component::run::not_an_http_component",
         ];
 
         for (variant, expected_code) in variants.iter().zip(expected_codes.iter()) {
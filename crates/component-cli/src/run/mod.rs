@@ -21,6 +21,7 @@ use miette::{Context, IntoDiagnostic};
 
 use component_manifest::RunPermissions;
 use component_package_manager::manager::Manager;
+use component_package_manager::oci::{SortBy, SortDirection};
 use wasmparser::{Parser, Payload};
 
 use wit2cli::{
@@ -61,6 +62,14 @@ pub(crate) struct Opts {
     #[arg(long, value_name = "ADDR", default_value = "127.0.0.1:8080")]
     listen: SocketAddr,
 
+    /// Serve the component over HTTP, on PORT of the `--listen` address when
+    /// given. Fails unless the component exports
+    /// `wasi:http/incoming-handler`.
+    // `Option<Option<_>>` is how clap tells `--serve` from `--serve=PORT`.
+    #[allow(clippy::option_option)]
+    #[arg(long, value_name = "PORT", num_args = 0..=1, require_equals = true)]
+    serve: Option<Option<u16>>,
+
     /// Run from the global cache, bypassing local installation.
     #[arg(long, short = 'g')]
     global: bool,
@@ -153,7 +162,15 @@ impl Opts {
         let permissions = self.resolve_permissions(reference.as_ref());
 
         // 5. Detect world and execute.
-        if http::exports_http_incoming_handler(&bytes) {
+        let serves_http = http::exports_http_incoming_handler(&bytes);
+        // r[impl run.http-serve-flag]
+        if self.serve.is_some() && !serves_http {
+            return Err(RunError::NotAnHttpComponent {
+                input: input.to_string(),
+            }
+            .into());
+        }
+        if serves_http {
             // wasi:http/proxy — start an HTTP server.
             // r[impl run.host-flags-before-input]
             if !self.extra.is_empty() {
@@ -162,7 +179,7 @@ impl Opts {
                     self.extra
                 ));
             }
-            http::serve(&bytes, &permissions, self.listen).await?;
+            http::serve(&bytes, &permissions, self.listen_addr()).await?;
         } else if exports_cli_run(&bytes) {
             // wasi:cli/command — run as a CLI program, forwarding
             // trailing args as guest argv.
@@ -187,6 +204,15 @@ impl Opts {
         Ok(())
     }
 
+    /// The address to serve HTTP components on: `--listen`, with the port
+    /// replaced by the one given to `--serve`.
+    fn listen_addr(&self) -> SocketAddr {
+        match self.serve {
+            Some(Some(port)) => SocketAddr::new(self.listen.ip(), port),
+            _ => self.listen,
+        }
+    }
+
     /// Build a [`RunPermissions`] from CLI flags (only the explicitly
     /// provided flags are `Some`).
     fn cli_permissions(&self) -> RunPermissions {
@@ -310,14 +336,16 @@ async fn load_from_global_cache(input: &str, offline: bool) -> miette::Result<Ve
     // Refresh the known-package index so WIT-style name resolution can find
     // packages that haven't been installed locally yet. Failures here are
     // non-fatal — fall through to the local cache lookup below.
-    if !offline {
-        let _ = manager
+    if !offline
+        && let Err(e) = manager
             .sync_from_meta_registry(
                 Manager::DEFAULT_REGISTRY_URL,
                 Manager::DEFAULT_SYNC_INTERVAL,
                 component_package_manager::manager::SyncPolicy::IfStale,
             )
-            .await;
+            .await
+    {
+        tracing::warn!("failed to sync package index: {e:#}");
     }
 
     // Try resolving through the known-package index and pulling the latest
@@ -337,21 +365,26 @@ async fn load_from_global_cache(input: &str, offline: bool) -> miette::Result<Ve
     let pattern = input.replace(':', "/");
     let suffix = format!("/{pattern}");
 
-    let entries = manager.list_all().map_err(crate::util::into_miette)?;
+    let entries = manager
+        .list_all(SortBy::Name, SortDirection::Ascending)
+        .map_err(crate::util::into_miette)?;
     let entry = entries
         .iter()
         .find(|e| e.ref_repository == pattern || e.ref_repository.ends_with(&suffix))
         .ok_or_else(|| RunError::NotInGlobalCache {
             name: input.to_string(),
         })?;
+    if let Err(e) = manager.record_access(entry) {
+        tracing::debug!("failed to record access to {}: {e}", entry.reference());
+    }
 
-    let wasm_layers = component_package_manager::oci::filter_wasm_layers(&entry.manifest.layers);
-    let layer = wasm_layers.first().ok_or(RunError::NoWasmLayer)?;
+    let reference = component_package_manager::parse_reference(&entry.reference())
+        .map_err(|e| miette::miette!("invalid stored reference '{}': {e}", entry.reference()))?;
     manager
-        .get(&layer.digest)
+        .read_component(&reference)
         .await
-        .into_diagnostic()
-        .wrap_err_with(|| format!("failed to read cached component for {}", layer.digest))
+        .map_err(crate::util::into_miette)
+        .wrap_err_with(|| format!("failed to read cached component for {}", entry.reference()))
 }
 
 /// Search the known-package index for entries matching `input` and, if a
@@ -433,15 +466,14 @@ async fn fetch_oci_bytes(
         .pull(oci_ref.clone())
         .await
         .map_err(crate::util::into_miette)?;
-    let manifest = pull_result.manifest.as_ref().ok_or(RunError::NoManifest)?;
-    let wasm_layers = component_package_manager::oci::filter_wasm_layers(&manifest.layers);
-    let layer = wasm_layers.first().ok_or(RunError::NoWasmLayer)?;
-    let key = &layer.digest;
+    if pull_result.manifest.is_none() {
+        return Err(RunError::NoManifest.into());
+    }
     manager
-        .get(key)
+        .read_component(oci_ref)
         .await
-        .into_diagnostic()
-        .wrap_err_with(|| format!("failed to read cached component for {key}"))
+        .map_err(crate::util::into_miette)
+        .wrap_err_with(|| format!("failed to read cached component for {}", oci_ref.whole()))
 }
 
 /// Check whether `input` looks like a manifest key (`scope:component`).
@@ -0,0 +1,210 @@
+use anyhow::{Result, bail};
+use component_package_manager::format_size;
+use component_package_manager::manager::Manager;
+use component_package_manager::oci::{SortBy, SortDirection};
+use component_package_manager::storage::{FsckMode, PrunePolicy};
+
+use crate::output::{Fields, OutputFormat, print_json};
+
+/// Inspect and trim the local package cache
+#[derive(clap::Parser)]
+pub(crate) enum CacheOpts {
+    /// Show how much space the cache uses, and how much deduplication saves
+    Status,
+    /// Remove every cached package, keeping known packages and pins
+    Clean {
+        /// Remove without asking for confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Remove data no cached package uses, and packages with missing layers
+    Gc,
+    /// Remove one package, or the least recently used ones until the cache
+    /// fits in a size
+    Evict {
+        /// The package to remove (e.g., ghcr.io/example/component:tag)
+        #[arg(required_unless_present = "max_size", conflicts_with = "max_size")]
+        reference: Option<String>,
+        /// Remove least recently used packages until the cache is at most
+        /// this size (e.g., 500MB or 2GB)
+        #[arg(long, value_name = "SIZE", value_parser = parse_size)]
+        max_size: Option<u64>,
+        /// Show what would be removed without removing anything
+        #[arg(long, requires = "max_size")]
+        dry_run: bool,
+    },
+}
+
+impl CacheOpts {
+    pub(crate) async fn run(&self, format: OutputFormat) -> Result<()> {
+        match self {
+            CacheOpts::Status => {
+                let store = Manager::open().await?;
+                let state_info = store.state_info();
+                let images = store.list_all(SortBy::Name, SortDirection::Ascending)?;
+                let dedup = state_info.dedup_stats();
+
+                let mut fields = Fields::new();
+                fields.push("Location", state_info.store_dir().display().to_string());
+                fields.push("Packages", images.len().to_string());
+                fields.push(
+                    "Size",
+                    format!(
+                        "{} ({} content, {} metadata)",
+                        format_size(state_info.store_size() + state_info.metadata_size()),
+                        format_size(state_info.store_size()),
+                        format_size(state_info.metadata_size())
+                    ),
+                );
+                fields.push(
+                    "Layers",
+                    format!(
+                        "{} ({} unique, {} shared)",
+                        dedup.total_layers, dedup.unique_layers, dedup.shared_layers
+                    ),
+                );
+                fields.push(
+                    "Dedup savings",
+                    format!(
+                        "{} of {}",
+                        format_size(dedup.saved_size()),
+                        format_size(dedup.logical_size)
+                    ),
+                );
+                fields.print(format, || {
+                    serde_json::json!({
+                        "location": state_info.store_dir(),
+                        "packages": images.len(),
+                        "store_size": state_info.store_size(),
+                        "metadata_size": state_info.metadata_size(),
+                        "total_layers": dedup.total_layers,
+                        "unique_layers": dedup.unique_layers,
+                        "shared_layers": dedup.shared_layers,
+                        "logical_size": dedup.logical_size,
+                        "saved_size": dedup.saved_size(),
+                    })
+                })
+            }
+            CacheOpts::Clean { yes } => {
+                let store = Manager::open().await?;
+                if !yes && !crate::util::confirm("Remove every package from the local cache?")? {
+                    println!("Nothing removed");
+                    return Ok(());
+                }
+                let before = store.content_size().await;
+                let count = store.delete_matching("*").await?;
+                let after = store.content_size().await;
+                println!(
+                    "Removed {count} package(s), freeing {}",
+                    format_size(before.saturating_sub(after))
+                );
+                Ok(())
+            }
+            CacheOpts::Gc => {
+                let store = Manager::open().await?;
+                let before = store.content_size().await;
+                let report = store.fsck(FsckMode::Prune).await?;
+                let after = store.content_size().await;
+
+                for issue in report.issues.iter().filter(|issue| issue.repaired) {
+                    println!("removed: {}", issue.problem);
+                }
+                println!(
+                    "Removed {} unused item(s), freeing {}",
+                    report.issues.iter().filter(|issue| issue.repaired).count(),
+                    format_size(before.saturating_sub(after))
+                );
+                if !report.is_repaired() {
+                    bail!("some problems couldn't be removed; see `component self fsck`");
+                }
+                Ok(())
+            }
+            CacheOpts::Evict {
+                reference,
+                max_size,
+                dry_run,
+            } => {
+                let store = Manager::open().await?;
+                if let Some(reference) = reference {
+                    let reference = store.resolve_reference(reference)?;
+                    if store.delete(reference.clone()).await? {
+                        println!("Evicted '{}'", reference.whole());
+                    } else {
+                        println!("Package '{}' is not in the cache", reference.whole());
+                    }
+                    return Ok(());
+                }
+                let policy = PrunePolicy {
+                    max_size: *max_size,
+                    dry_run: *dry_run,
+                    ..PrunePolicy::default()
+                };
+                let before = store.content_size().await;
+                let evicted = store.prune(&policy).await?;
+                let freed = before.saturating_sub(store.content_size().await);
+                if format.is_json() {
+                    return print_json(&serde_json::json!({
+                        "dry_run": dry_run,
+                        "evicted": evicted,
+                        "freed": freed,
+                    }));
+                }
+                let verb = if *dry_run { "Would evict" } else { "Evicted" };
+                for reference in &evicted {
+                    println!("{verb} '{reference}'");
+                }
+                if *dry_run {
+                    println!("{verb} {} package(s)", evicted.len());
+                } else {
+                    println!(
+                        "{verb} {} package(s), freeing {}",
+                        evicted.len(),
+                        format_size(freed)
+                    );
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Parse a size such as `500MB`, `2 GB`, `64k`, or `1024`, in the same
+/// 1024-based units [`format_size`] prints.
+fn parse_size(s: &str) -> Result<u64, String> {
+    let s = s.trim();
+    let split = s
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(s.len());
+    let (number, unit) = s.split_at(split);
+    let number: f64 = number
+        .parse()
+        .map_err(|_| format!("'{s}' is not a size, such as 500MB or 2GB"))?;
+    let multiplier: u64 = match unit.trim().to_ascii_lowercase().as_str() {
+        "" | "b" => 1,
+        "k" | "kb" | "kib" => 1024,
+        "m" | "mb" | "mib" => 1024 * 1024,
+        "g" | "gb" | "gib" => 1024 * 1024 * 1024,
+        other => return Err(format!("unknown size unit '{other}'; use B, KB, MB, or GB")),
+    };
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    Ok((number * multiplier as f64) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_size_accepts_units() {
+        assert_eq!(parse_size("1024"), Ok(1024));
+        assert_eq!(parse_size("64k"), Ok(64 * 1024));
+        assert_eq!(parse_size("500MB"), Ok(500 * 1024 * 1024));
+        assert_eq!(parse_size("1.5 GB"), Ok(1536 * 1024 * 1024));
+        assert!(parse_size("lots").is_err());
+        assert!(parse_size("5TB").is_err());
+    }
+}
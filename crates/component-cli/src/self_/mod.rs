@@ -1,13 +1,20 @@
 #![allow(clippy::print_stdout, clippy::print_stderr)]
 
 use std::io::{self, BufRead, Seek, Write};
+use std::path::PathBuf;
 
 use anyhow::Result;
 use clap::CommandFactory;
 use clap_complete::Shell;
 use component_package_manager::manager::Manager;
+use component_package_manager::storage::{FsckMode, RelocateProgress, StateInfo};
 use component_package_manager::{Config, format_size};
 
+use crate::output::{OutputFormat, print_json};
+
+mod cache;
+mod uninstall;
+
 /// The path of the dotenv file relative to the current working directory.
 const DOTENV_PATH: &str = ".env";
 
@@ -16,6 +23,8 @@ const DOTENV_PATH: &str = ".env";
 pub(crate) enum Opts {
     /// Print diagnostics about the local state
     State,
+    /// Print the data directory holding the store, and nothing else
+    DataDir,
     /// Show configuration file location and current settings
     Config,
     /// Show the application log file
@@ -36,10 +45,54 @@ pub(crate) enum Opts {
     ManPages,
     /// Clean up storage (remove all data, images, and metadata)
     Clean,
+    /// Inspect and trim the local package cache
+    #[command(subcommand)]
+    Cache(cache::CacheOpts),
+    /// Check the store for missing or corrupt layers and orphaned data
+    Fsck {
+        /// Remove broken images, orphaned WIT packages, and stray blobs
+        #[arg(long, conflicts_with = "repull")]
+        prune: bool,
+        /// Remove broken data, then pull broken images again
+        #[arg(long)]
+        repull: bool,
+    },
+    /// Migrate the metadata database to a version, backing it up first
+    Migrate {
+        /// The migration version to migrate up or down to
+        target: u32,
+    },
+    /// Snapshot the metadata database to a file
+    Backup {
+        /// Where to write the snapshot
+        path: PathBuf,
+    },
+    /// Replace the metadata database with a snapshot, then check the store
+    Restore {
+        /// The snapshot to restore
+        path: PathBuf,
+    },
+    /// Move the content store and metadata database to another directory
+    Relocate {
+        /// The data directory to move the store to
+        target: PathBuf,
+    },
+    /// Remove the data, config, and log directories, listing them first
+    Uninstall {
+        /// Also remove the `component` binary
+        #[arg(long)]
+        binary: bool,
+        /// Remove without asking for confirmation
+        #[arg(short, long)]
+        yes: bool,
+        /// Only list what would be removed
+        #[arg(long, conflicts_with = "yes")]
+        dry_run: bool,
+    },
 }
 
 impl Opts {
-    pub(crate) async fn run(&self) -> Result<()> {
+    pub(crate) async fn run(&self, format: OutputFormat) -> Result<()> {
         match self {
             Opts::Log { follow, lines } => {
                 let log_path = crate::log_dir().join("component.log");
@@ -84,9 +137,21 @@ impl Opts {
                 }
                 Ok(())
             }
+            Opts::DataDir => {
+                let store = Manager::open_offline().await?;
+                let data_dir = store.state_info().data_dir().to_path_buf();
+                if format.is_json() {
+                    return print_json(&data_dir);
+                }
+                println!("{}", data_dir.display());
+                Ok(())
+            }
             Opts::State => {
                 let store = Manager::open().await?;
                 let state_info = store.state_info();
+                if format.is_json() {
+                    return print_json(&state_json(&state_info));
+                }
 
                 println!("[Migrations]");
                 println!(
@@ -94,6 +159,9 @@ impl Opts {
                     state_info.migration_current(),
                     state_info.migration_total()
                 );
+                if let Some(last) = state_info.migration_history().last() {
+                    println!("Last applied: \t{} ({})", last.name, last.applied_at);
+                }
                 println!();
                 println!("[Storage]");
                 println!("Executable: \t{}", state_info.executable().display());
@@ -109,6 +177,41 @@ impl Opts {
                     format_size(state_info.metadata_size())
                 );
                 println!();
+                let dedup = state_info.dedup_stats();
+                println!("[Deduplication]");
+                println!(
+                    "Layers: \t{} ({} unique, {} shared)",
+                    dedup.total_layers, dedup.unique_layers, dedup.shared_layers
+                );
+                println!("Logical size: \t{}", format_size(dedup.logical_size));
+                println!(
+                    "Physical size: \t{} ({} saved)",
+                    format_size(dedup.physical_size),
+                    format_size(dedup.saved_size())
+                );
+                if !dedup.top_consumers.is_empty() {
+                    println!("Top consumers:");
+                    for consumer in &dedup.top_consumers {
+                        println!("  {} ({})", consumer.reference, format_size(consumer.size));
+                    }
+                }
+                println!();
+                let metrics = state_info.pull_metrics();
+                println!("[Pulls]");
+                println!(
+                    "Pulls: \t\t{} ({} cache hits, {} misses)",
+                    metrics.pulls,
+                    metrics.cache_hits,
+                    metrics.cache_misses()
+                );
+                if let Some(ratio) = metrics.hit_ratio() {
+                    println!("Hit ratio: \t{:.1}%", ratio * 100.0);
+                }
+                println!("Downloaded: \t{}", format_size(metrics.bytes_downloaded));
+                if let Some(average) = metrics.average_pull_time() {
+                    println!("Avg latency: \t{} ms", average.as_millis());
+                }
+                println!();
                 println!("[Logging]");
                 println!("Log directory: \t{}", state_info.log_dir().display());
                 println!(
@@ -121,6 +224,24 @@ impl Opts {
                 // Get the global and local config paths
                 let global_config_path = Config::config_path();
                 let local_config_path = Config::local_config_path();
+                if format.is_json() {
+                    let config = Config::load()?;
+                    let dotenv_path = std::path::Path::new(DOTENV_PATH);
+                    return print_json(&serde_json::json!({
+                        "global_config": global_config_path,
+                        "global_config_exists": global_config_path.as_ref().is_some_and(|p| p.exists()),
+                        "local_config": local_config_path,
+                        "local_config_exists": local_config_path.exists(),
+                        "registries": config.registries.iter().map(|(name, registry)| {
+                            serde_json::json!({
+                                "name": name,
+                                "credential_helper": registry.credential_helper.is_some(),
+                            })
+                        }).collect::<Vec<_>>(),
+                        "dotenv": dotenv_path,
+                        "dotenv_exists": dotenv_path.exists(),
+                    }));
+                }
 
                 println!("[Configuration]");
                 if let Some(ref global_path) = global_config_path {
@@ -225,6 +346,147 @@ impl Opts {
                 println!("Cleaned up {} of data", format_size(total_size));
                 Ok(())
             }
+            Opts::Cache(opts) => opts.run(format).await,
+            Opts::Migrate { target } => {
+                let store = Manager::open().await?;
+                match store.migrate_to(*target).await? {
+                    Some(backup) => {
+                        println!("Migrated database to version {target}");
+                        println!("Backup: \t{}", backup.display());
+                    }
+                    None => println!("Database is already at version {target}"),
+                }
+                Ok(())
+            }
+            Opts::Backup { path } => {
+                let store = Manager::open().await?;
+                store.backup(path).await?;
+                println!("Backed up the metadata database to {}", path.display());
+                Ok(())
+            }
+            Opts::Restore { path } => {
+                let mut store = Manager::open().await?;
+                let report = store.restore(path).await?;
+                println!("Restored the metadata database from {}", path.display());
+                for issue in &report.issues {
+                    println!("found: {}", issue.problem);
+                }
+                if !report.is_clean() {
+                    println!(
+                        "Run `component self fsck --repull` to pull images with missing layers again"
+                    );
+                }
+                Ok(())
+            }
+            Opts::Relocate { target } => {
+                let mut store = Manager::open().await?;
+                let from = store.state_info().data_dir().to_path_buf();
+
+                let bar = indicatif::ProgressBar::new(0).with_style(
+                    indicatif::ProgressStyle::with_template(
+                        "{bar:40} {binary_bytes}/{binary_total_bytes}",
+                    )
+                    .expect("valid progress bar template"),
+                );
+                let (progress_tx, mut progress_rx) =
+                    tokio::sync::mpsc::channel::<RelocateProgress>(64);
+                let progress_bar = bar.clone();
+                let progress_handle = tokio::task::spawn(async move {
+                    while let Some(progress) = progress_rx.recv().await {
+                        progress_bar.set_length(progress.total_bytes);
+                        progress_bar.set_position(progress.bytes_moved);
+                    }
+                });
+                let result = store.relocate_with_progress(target, &progress_tx).await;
+                drop(progress_tx);
+                let _ = progress_handle.await;
+                bar.finish_and_clear();
+                result?;
+
+                println!(
+                    "Moved the store from {} to {}",
+                    from.display(),
+                    store.state_info().data_dir().display()
+                );
+                Ok(())
+            }
+            Opts::Uninstall {
+                binary,
+                yes,
+                dry_run,
+            } => uninstall::run(*binary, *yes, *dry_run).await,
+            Opts::Fsck { prune, repull } => {
+                let mode = match (prune, repull) {
+                    (_, true) => FsckMode::Repull,
+                    (true, false) => FsckMode::Prune,
+                    (false, false) => FsckMode::Check,
+                };
+                let store = Manager::open().await?;
+                let report = store.fsck(mode).await?;
+
+                for issue in &report.issues {
+                    let status = if issue.repaired { "repaired" } else { "found" };
+                    println!("{status}: {}", issue.problem);
+                }
+                println!(
+                    "Checked {} layers and {} blobs: {} problems",
+                    report.layers_checked,
+                    report.blobs_checked,
+                    report.issues.len()
+                );
+                if !report.is_repaired() {
+                    anyhow::bail!(
+                        "store has unrepaired problems; run with `--prune` or `--repull`"
+                    );
+                }
+                Ok(())
+            }
         }
     }
 }
+
+/// The local state as JSON, with sizes in bytes and durations in
+/// milliseconds.
+fn state_json(state_info: &StateInfo) -> serde_json::Value {
+    let dedup = state_info.dedup_stats();
+    let metrics = state_info.pull_metrics();
+    let last_migration = state_info.migration_history().last();
+    serde_json::json!({
+        "migrations": {
+            "current": state_info.migration_current(),
+            "total": state_info.migration_total(),
+            "last_applied": last_migration.map(|last| serde_json::json!({
+                "name": last.name,
+                "applied_at": last.applied_at,
+            })),
+        },
+        "storage": {
+            "executable": state_info.executable(),
+            "data_dir": state_info.data_dir(),
+            "store_dir": state_info.store_dir(),
+            "store_size": state_info.store_size(),
+            "metadata_file": state_info.metadata_file(),
+            "metadata_size": state_info.metadata_size(),
+        },
+        "dedup": {
+            "total_layers": dedup.total_layers,
+            "unique_layers": dedup.unique_layers,
+            "shared_layers": dedup.shared_layers,
+            "logical_size": dedup.logical_size,
+            "physical_size": dedup.physical_size,
+            "saved_size": dedup.saved_size(),
+            "top_consumers": dedup.top_consumers.iter().map(|consumer| serde_json::json!({
+                "reference": consumer.reference,
+                "size": consumer.size,
+            })).collect::<Vec<_>>(),
+        },
+        "pulls": {
+            "pulls": metrics.pulls,
+            "cache_hits": metrics.cache_hits,
+            "cache_misses": metrics.cache_misses(),
+            "bytes_downloaded": metrics.bytes_downloaded,
+            "average_pull_ms": metrics.average_pull_time().map(|average| average.as_millis()),
+        },
+        "log_dir": state_info.log_dir(),
+    })
+}
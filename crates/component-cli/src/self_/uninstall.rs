@@ -0,0 +1,123 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use component_package_manager::Config;
+use component_package_manager::manager::Manager;
+use component_package_manager::storage::StateInfo;
+
+/// Something `self uninstall` removes.
+struct Target {
+    /// What the path holds, e.g. "Data:".
+    label: &'static str,
+    path: PathBuf,
+}
+
+/// Remove the data, config, and log directories, and optionally the binary.
+///
+/// Every path is printed before anything is removed, and nothing is removed
+/// without confirmation unless `yes` is set.
+pub(crate) async fn run(binary: bool, yes: bool, dry_run: bool) -> Result<()> {
+    let store = Manager::open().await?;
+    let state_info = store.state_info().clone();
+    // Release the database before its directory is removed.
+    drop(store);
+
+    let mut targets = Vec::new();
+    push_data_dir(&mut targets, state_info.data_dir());
+    // A relocated store leaves a pointer behind in the default location.
+    if let Some(default_dir) = StateInfo::default_data_dir()
+        && default_dir != state_info.data_dir()
+    {
+        push_data_dir(&mut targets, &default_dir);
+    }
+    // The default config directory is ours, but a config file passed with
+    // `--config` or `WASM_CONFIG` may sit among unrelated files.
+    let config_file = state_info.config_file();
+    let config_path = match config_file.parent() {
+        Some(config_dir) if Config::config_path_from(None).as_deref() == Some(config_file) => {
+            config_dir
+        }
+        _ => config_file,
+    };
+    targets.push(Target {
+        label: "Config:",
+        path: config_path.to_path_buf(),
+    });
+    targets.push(Target {
+        label: "Logs:",
+        path: state_info.log_dir(),
+    });
+    if binary {
+        targets.push(Target {
+            label: "Binary:",
+            path: state_info.executable().to_path_buf(),
+        });
+    }
+    targets.retain(|target| target.path.exists());
+
+    if targets.is_empty() {
+        println!("Nothing to remove");
+        return Ok(());
+    }
+    println!("This removes:");
+    for target in &targets {
+        println!("  {:<8}{}", target.label, target.path.display());
+    }
+    if dry_run {
+        return Ok(());
+    }
+    if !yes && !crate::util::confirm("Remove these paths?")? {
+        println!("Nothing removed");
+        return Ok(());
+    }
+
+    for target in &targets {
+        remove(&target.path)
+            .with_context(|| format!("failed to remove {}", target.path.display()))?;
+        println!("Removed {}", target.path.display());
+    }
+    // The log directory sits in a `wasm` directory of its own; drop it too
+    // if nothing else lives there. Failing to is harmless.
+    if let Some(parent) = state_info.log_dir().parent() {
+        let _ = std::fs::remove_dir(parent);
+    }
+    if !binary {
+        println!(
+            "The binary is still at {}; pass `--binary` to remove it too",
+            state_info.executable().display()
+        );
+    }
+    Ok(())
+}
+
+/// The entries of a data directory that belong to the store.
+const DATA_ENTRIES: [&str; 3] = ["store", "db", "relocated"];
+
+/// Add the data directory `dir` to `targets`.
+///
+/// The default `$XDG_DATA_HOME/wasm` is removed whole. A directory chosen
+/// through `WASM_DATA_DIR` or `self relocate` may hold other files, so only
+/// the store's own entries in it are removed.
+fn push_data_dir(targets: &mut Vec<Target>, dir: &Path) {
+    if StateInfo::xdg_data_dir().as_deref() == Some(dir) {
+        targets.push(Target {
+            label: "Data:",
+            path: dir.to_path_buf(),
+        });
+        return;
+    }
+    for entry in DATA_ENTRIES {
+        targets.push(Target {
+            label: "Data:",
+            path: dir.join(entry),
+        });
+    }
+}
+
+fn remove(path: &Path) -> std::io::Result<()> {
+    if path.is_dir() {
+        std::fs::remove_dir_all(path)
+    } else {
+        std::fs::remove_file(path)
+    }
+}
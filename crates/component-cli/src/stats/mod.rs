@@ -0,0 +1,60 @@
+#![allow(clippy::print_stdout, clippy::print_stderr)]
+
+use anyhow::Result;
+use component_package_manager::format_size;
+use component_package_manager::manager::Manager;
+
+use crate::output::{Fields, OutputFormat};
+
+/// Options for the `stats` command.
+#[derive(clap::Parser)]
+pub(crate) struct Opts {}
+
+impl Opts {
+    pub(crate) async fn run(&self, format: OutputFormat) -> Result<()> {
+        let store = Manager::open_offline().await?;
+        let stats = store.stats()?;
+
+        let mut fields = Fields::new();
+        fields.push("Images", stats.images.to_string());
+        fields.push("Interfaces", stats.interfaces.to_string());
+        fields.push("Total size", format_size(stats.logical_size));
+        fields.push(
+            "Physical size",
+            format!(
+                "{} ({} saved by deduplication and compression)",
+                format_size(stats.physical_size),
+                format_size(stats.saved_size())
+            ),
+        );
+        for image in &stats.largest {
+            fields.push(
+                "Largest",
+                format!("{} ({})", image.reference, format_size(image.size)),
+            );
+        }
+        for image in &stats.oldest_unused {
+            fields.push(
+                "Oldest unused",
+                format!("{} (last used {})", image.reference, image.last_used),
+            );
+        }
+        fields.print(format, || {
+            serde_json::json!({
+                "images": stats.images,
+                "interfaces": stats.interfaces,
+                "logical_size": stats.logical_size,
+                "physical_size": stats.physical_size,
+                "saved_size": stats.saved_size(),
+                "largest": stats.largest.iter().map(|image| serde_json::json!({
+                    "reference": image.reference,
+                    "size": image.size,
+                })).collect::<Vec<_>>(),
+                "oldest_unused": stats.oldest_unused.iter().map(|image| serde_json::json!({
+                    "reference": image.reference,
+                    "last_used": image.last_used,
+                })).collect::<Vec<_>>(),
+            })
+        })
+    }
+}
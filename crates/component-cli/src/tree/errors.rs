@@ -0,0 +1,58 @@
+//! Error types for the `component tree` CLI command.
+
+use miette::Diagnostic;
+
+/// CLI-specific error type for `component tree` command failures.
+///
+/// Each variant carries a stable [diagnostic error code][miette::Diagnostic::code]
+/// that uniquely identifies the failure.
+#[derive(Debug, Clone, PartialEq, Eq, Diagnostic)]
+#[must_use]
+pub(crate) enum TreeError {
+    /// `--invert` named a package that isn't in the lockfile.
+    #[diagnostic(
+        code(component::tree::not_locked),
+        help("run `component install` to lock the project's dependencies")
+    )]
+    NotLocked {
+        /// The package name that was asked for.
+        name: String,
+    },
+}
+
+impl std::fmt::Display for TreeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TreeError::NotLocked { name } => {
+                write!(f, "'{name}' is not in `wasm.lock.toml`")
+            }
+        }
+    }
+}
+
+impl std::error::Error for TreeError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_all_variants_have_error_codes() {
+        use miette::Diagnostic;
+
+        let not_locked = TreeError::NotLocked {
+            name: "wasi:logging".to_string(),
+        };
+        assert_eq!(
+            not_locked
+                .code()
+                .expect("NotLocked must have a diagnostic code")
+                .to_string(),
+            "component::tree::not_locked",
+        );
+        assert!(
+            not_locked.help().is_some(),
+            "NotLocked must have a help message"
+        );
+    }
+}
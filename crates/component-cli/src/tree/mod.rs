@@ -0,0 +1,224 @@
+#![allow(clippy::print_stdout, clippy::print_stderr)]
+
+mod errors;
+
+use std::collections::HashSet;
+use std::fmt::Write as _;
+use std::path::PathBuf;
+
+use component_manifest::LockedPackage;
+use miette::{IntoDiagnostic, WrapErr};
+
+use crate::install::InstallError;
+use crate::util::{into_miette, read_lock_file};
+use errors::TreeError;
+
+/// Options for the `tree` command.
+#[derive(clap::Parser)]
+pub(crate) struct Opts {
+    /// Show what depends on this package, instead of what the project
+    /// depends on (e.g., wasi:io)
+    #[arg(long, value_name = "NAME")]
+    invert: Option<String>,
+}
+
+impl Opts {
+    pub(crate) async fn run(self) -> miette::Result<()> {
+        let manifest_path = PathBuf::from("wasm.toml");
+        let lockfile_path = PathBuf::from("wasm.lock.toml");
+
+        if !manifest_path.exists() {
+            return Err(InstallError::NoManifest.into());
+        }
+        let source = tokio::fs::read_to_string(&manifest_path)
+            .await
+            .into_diagnostic()
+            .wrap_err_with(|| format!("could not read '{}'", manifest_path.display()))?;
+        let manifest: component_manifest::Manifest = toml::from_str(&source).into_diagnostic()?;
+        let lockfile = read_lock_file(&lockfile_path).await.map_err(into_miette)?;
+
+        let out = if let Some(name) = &self.invert {
+            let roots: Vec<&LockedPackage> = lockfile
+                .all_packages()
+                .map(|(package, _)| package)
+                .filter(|package| package.name == *name)
+                .collect();
+            if roots.is_empty() {
+                return Err(TreeError::NotLocked { name: name.clone() }.into());
+            }
+            render_tree(&roots, |package| lockfile.dependents_of(package))
+        } else {
+            let names: Vec<&str> = manifest
+                .all_dependencies()
+                .map(|(name, _, _)| name.as_str())
+                .collect();
+            let roots: Vec<&LockedPackage> = names
+                .iter()
+                .filter_map(|name| {
+                    lockfile
+                        .all_packages()
+                        .map(|(package, _)| package)
+                        .find(|package| package.name == *name)
+                })
+                .collect();
+            let mut out = render_tree(&roots, |package| lockfile.dependencies_of(package));
+            for name in names {
+                if !roots.iter().any(|package| package.name == name) {
+                    if !out.is_empty() {
+                        out.push('\n');
+                    }
+                    let _ = writeln!(out, "{name} (not installed)");
+                }
+            }
+            out
+        };
+        print!("{out}");
+        Ok(())
+    }
+}
+
+/// A locked package as `name@version`, or just `name` when it has no
+/// version.
+pub(crate) fn package_label(package: &LockedPackage) -> String {
+    if package.version.is_empty() {
+        package.name.clone()
+    } else {
+        format!("{}@{}", package.name, package.version)
+    }
+}
+
+/// Render each of `roots` and its `children`, recursively, as an indented
+/// tree.
+///
+/// A package is expanded the first time it appears. Later appearances of a
+/// package with children are marked `(*)` and not expanded again, which
+/// also keeps cycles finite.
+fn render_tree<'a>(
+    roots: &[&'a LockedPackage],
+    children: impl Fn(&'a LockedPackage) -> Vec<&'a LockedPackage>,
+) -> String {
+    let mut out = String::new();
+    let mut seen = HashSet::new();
+    for (i, root) in roots.iter().enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+        render_node(root, "", "", &children, &mut seen, &mut out);
+    }
+    out
+}
+
+fn render_node<'a>(
+    package: &'a LockedPackage,
+    marker: &str,
+    indent: &str,
+    children: &impl Fn(&'a LockedPackage) -> Vec<&'a LockedPackage>,
+    seen: &mut HashSet<(&'a str, &'a str)>,
+    out: &mut String,
+) {
+    let label = package_label(package);
+    let below = children(package);
+    if !seen.insert((package.name.as_str(), package.version.as_str())) && !below.is_empty() {
+        let _ = writeln!(out, "{indent}{marker}{label} (*)");
+        return;
+    }
+    let _ = writeln!(out, "{indent}{marker}{label}");
+
+    let indent = match marker {
+        "" => String::new(),
+        "├── " => format!("{indent}│   "),
+        _ => format!("{indent}    "),
+    };
+    for (i, child) in below.iter().enumerate() {
+        let marker = if i + 1 == below.len() {
+            "└── "
+        } else {
+            "├── "
+        };
+        render_node(child, marker, &indent, children, seen, out);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use component_manifest::{Lockfile, PackageDependency};
+
+    fn locked(name: &str, version: &str, deps: &[(&str, &str)]) -> LockedPackage {
+        LockedPackage {
+            name: name.to_string(),
+            version: version.to_string(),
+            registry: format!("ghcr.io/example/{name}"),
+            digest: "sha256:abc123".to_string(),
+            dependencies: deps
+                .iter()
+                .map(|(name, version)| PackageDependency {
+                    name: (*name).to_string(),
+                    version: (*version).to_string(),
+                    registry: String::new(),
+                    digest: String::new(),
+                })
+                .collect(),
+        }
+    }
+
+    fn lockfile() -> Lockfile {
+        Lockfile {
+            components: vec![locked(
+                "root:app",
+                "0.1.0",
+                &[("wasi:http", "0.2.0"), ("wasi:clocks", "0.2.0")],
+            )],
+            interfaces: vec![
+                locked("wasi:http", "0.2.0", &[("wasi:io", "0.2.0")]),
+                locked("wasi:clocks", "0.2.0", &[("wasi:io", "0.2.0")]),
+                locked("wasi:io", "0.2.0", &[("wasi:poll", "0.2.0")]),
+                locked("wasi:poll", "0.2.0", &[]),
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn tree_marks_repeated_packages() {
+        let lockfile = lockfile();
+        let roots: Vec<_> = lockfile.components.iter().collect();
+        let out = render_tree(&roots, |package| lockfile.dependencies_of(package));
+        assert_eq!(
+            out,
+            [
+                "root:app@0.1.0",
+                "├── wasi:http@0.2.0",
+                "│   └── wasi:io@0.2.0",
+                "│       └── wasi:poll@0.2.0",
+                "└── wasi:clocks@0.2.0",
+                "    └── wasi:io@0.2.0 (*)",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn inverted_tree_shows_dependents() {
+        let lockfile = lockfile();
+        let roots: Vec<_> = lockfile
+            .interfaces
+            .iter()
+            .filter(|p| p.name == "wasi:io")
+            .collect();
+        let out = render_tree(&roots, |package| lockfile.dependents_of(package));
+        assert_eq!(
+            out,
+            [
+                "wasi:io@0.2.0",
+                "├── wasi:http@0.2.0",
+                "│   └── root:app@0.1.0",
+                "└── wasi:clocks@0.2.0",
+                "    └── root:app@0.1.0",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+}
@@ -10,16 +10,131 @@ pub(crate) fn parse_reference(s: &str) -> Result<Reference, String> {
     component_package_manager::parse_reference(s)
 }
 
+/// The input or output path that stands for stdin or stdout.
+pub(crate) const STDIO: &str = "-";
+
+/// Whether `path` is [`STDIO`].
+pub(crate) fn is_stdio(path: &Path) -> bool {
+    path.as_os_str() == STDIO
+}
+
+/// Read a file, or all of stdin when `path` is [`STDIO`].
+pub(crate) fn read_input(path: &Path) -> anyhow::Result<Vec<u8>> {
+    use anyhow::Context;
+    use std::io::Read;
+
+    if is_stdio(path) {
+        let mut bytes = Vec::new();
+        std::io::stdin()
+            .lock()
+            .read_to_end(&mut bytes)
+            .context("failed to read stdin")?;
+        return Ok(bytes);
+    }
+    std::fs::read(path).with_context(|| format!("failed to read {}", path.display()))
+}
+
+/// Read Wasm bytes from a local file, stdin, or the local store by OCI
+/// reference.
+///
+/// `-` reads stdin, and inputs that exist on disk or end in `.wasm` are read
+/// as files. References are pulled into the store first, or read from it
+/// as-is when `offline`.
+pub(crate) async fn read_wasm(input: &str, offline: bool) -> anyhow::Result<Vec<u8>> {
+    use anyhow::Context;
+    use component_package_manager::manager::Manager;
+
+    let path = Path::new(input);
+    if is_stdio(path) || path.exists() || path.extension().is_some_and(|ext| ext == "wasm") {
+        return read_input(path);
+    }
+    let reference = parse_reference(input).map_err(|e| {
+        anyhow::anyhow!("{input} is neither a local file nor an OCI reference: {e}")
+    })?;
+
+    // Offline, only what is already in the store can be read.
+    let store = if offline {
+        Manager::open_offline().await?
+    } else {
+        let store = Manager::open().await?;
+        let pull_result = store.pull(reference.clone()).await?;
+        if pull_result.manifest.is_none() {
+            anyhow::bail!("{} has no manifest", reference.whole());
+        }
+        store
+    };
+    store
+        .read_component(&reference)
+        .await
+        .with_context(|| format!("failed to read {} from the store", reference.whole()))
+}
+
+/// Ask a yes-or-no question on stderr, defaulting to no.
+///
+/// Fails rather than asking when stdin isn't a terminal, so scripts have to
+/// pass `--yes` instead of hanging on a prompt.
+#[allow(clippy::print_stderr)]
+pub(crate) fn confirm(prompt: &str) -> anyhow::Result<bool> {
+    use std::io::{BufRead, IsTerminal, Write};
+
+    if !std::io::stdin().is_terminal() {
+        anyhow::bail!("{prompt} Not asking without a terminal; pass `--yes` to confirm");
+    }
+    eprint!("{prompt} [y/N] ");
+    std::io::stderr().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().lock().read_line(&mut answer)?;
+    Ok(matches!(
+        answer.trim().to_ascii_lowercase().as_str(),
+        "y" | "yes"
+    ))
+}
+
+/// Ask for a value on stderr, offering `default` when there is one.
+///
+/// Returns `default` without asking when stdin isn't a terminal, and `None`
+/// when the answer is empty and there is no default.
+#[allow(clippy::print_stderr)]
+pub(crate) fn prompt(question: &str, default: Option<&str>) -> anyhow::Result<Option<String>> {
+    use std::io::{BufRead, IsTerminal, Write};
+
+    if !std::io::stdin().is_terminal() {
+        return Ok(default.map(str::to_string));
+    }
+    match default {
+        Some(default) => eprint!("{question} [{default}]: "),
+        None => eprint!("{question}: "),
+    }
+    std::io::stderr().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().lock().read_line(&mut answer)?;
+    let answer = answer.trim();
+    Ok(if answer.is_empty() {
+        default.map(str::to_string)
+    } else {
+        Some(answer.to_string())
+    })
+}
+
 /// Convert an error into a [`miette::Report`], preserving the cause chain.
 ///
 /// This bridges subsystems that return [`anyhow::Error`], [`wasmtime::Error`],
 /// or other `Display` errors with the top-level CLI that uses [`miette`] for
-/// rich error display.
+/// rich error display. The exit status the cause maps to is kept alongside
+/// the message; see [`crate::exit::ExitStatus`].
 #[allow(dead_code, clippy::needless_pass_by_value)]
-pub(crate) fn into_miette(err: impl std::fmt::Display) -> miette::Report {
-    // Use the alternate Display format which renders the full cause chain
-    // as "outer: inner: root cause".
-    miette::miette!("{err:#}")
+pub(crate) fn into_miette(err: impl std::fmt::Display + 'static) -> miette::Report {
+    miette::Report::new(crate::exit::Failure::new(&err))
+}
+
+/// Read a lockfile from disk, or an empty one if it doesn't exist yet.
+pub(crate) async fn read_lock_file<P: AsRef<Path>>(path: P) -> anyhow::Result<Lockfile> {
+    let path = path.as_ref();
+    match tokio::fs::read_to_string(path).await {
+        Ok(s) => Ok(toml::from_str(&s)?),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Lockfile::default()),
+        Err(e) => anyhow::bail!("could not read '{}': {e}", path.display()),
+    }
 }
 
 /// Write a lockfile to disk with a header comment.
@@ -0,0 +1,58 @@
+//! Error types for the `component why` CLI command.
+
+use miette::Diagnostic;
+
+/// CLI-specific error type for `component why` command failures.
+///
+/// Each variant carries a stable [diagnostic error code][miette::Diagnostic::code]
+/// that uniquely identifies the failure.
+#[derive(Debug, Clone, PartialEq, Eq, Diagnostic)]
+#[must_use]
+pub(crate) enum WhyError {
+    /// The package isn't in the lockfile, so nothing needs it.
+    #[diagnostic(
+        code(component::why::not_locked),
+        help("run `component tree` to see every locked package")
+    )]
+    NotLocked {
+        /// The package name that was asked for.
+        name: String,
+    },
+}
+
+impl std::fmt::Display for WhyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WhyError::NotLocked { name } => {
+                write!(f, "'{name}' is not in `wasm.lock.toml`")
+            }
+        }
+    }
+}
+
+impl std::error::Error for WhyError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_all_variants_have_error_codes() {
+        use miette::Diagnostic;
+
+        let not_locked = WhyError::NotLocked {
+            name: "wasi:io".to_string(),
+        };
+        assert_eq!(
+            not_locked
+                .code()
+                .expect("NotLocked must have a diagnostic code")
+                .to_string(),
+            "component::why::not_locked",
+        );
+        assert!(
+            not_locked.help().is_some(),
+            "NotLocked must have a help message"
+        );
+    }
+}
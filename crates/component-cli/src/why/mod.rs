@@ -0,0 +1,171 @@
+#![allow(clippy::print_stdout, clippy::print_stderr)]
+
+mod errors;
+
+use std::path::PathBuf;
+
+use component_manifest::{LockedPackage, Lockfile};
+use miette::{IntoDiagnostic, WrapErr};
+
+use crate::install::InstallError;
+use crate::tree::package_label;
+use crate::util::{into_miette, read_lock_file};
+use errors::WhyError;
+
+/// Options for the `why` command.
+#[derive(clap::Parser)]
+pub(crate) struct Opts {
+    /// The locked package to explain (e.g., wasi:io)
+    #[arg(value_name = "NAME")]
+    name: String,
+}
+
+impl Opts {
+    pub(crate) async fn run(self) -> miette::Result<()> {
+        let manifest_path = PathBuf::from("wasm.toml");
+        let lockfile_path = PathBuf::from("wasm.lock.toml");
+
+        if !manifest_path.exists() {
+            return Err(InstallError::NoManifest.into());
+        }
+        let source = tokio::fs::read_to_string(&manifest_path)
+            .await
+            .into_diagnostic()
+            .wrap_err_with(|| format!("could not read '{}'", manifest_path.display()))?;
+        let manifest: component_manifest::Manifest = toml::from_str(&source).into_diagnostic()?;
+        let lockfile = read_lock_file(&lockfile_path).await.map_err(into_miette)?;
+
+        if !lockfile.all_packages().any(|(p, _)| p.name == self.name) {
+            return Err(WhyError::NotLocked { name: self.name }.into());
+        }
+        let roots: Vec<&LockedPackage> = manifest
+            .all_dependencies()
+            .filter_map(|(name, _, _)| {
+                lockfile
+                    .all_packages()
+                    .map(|(package, _)| package)
+                    .find(|package| package.name == *name)
+            })
+            .collect();
+
+        let paths = paths_to(&lockfile, &roots, &self.name);
+        if paths.is_empty() {
+            println!(
+                "'{}' is locked, but nothing in `wasm.toml` depends on it",
+                self.name
+            );
+        }
+        for path in paths {
+            let labels: Vec<String> = path.into_iter().map(package_label).collect();
+            if let [label] = labels.as_slice() {
+                println!("{label} (in wasm.toml)");
+            } else {
+                println!("{}", labels.join(" → "));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Every path from one of `roots` to a package named `name`, following
+/// dependency edges. Each path starts at a root and ends at a package named
+/// `name`, and never visits a package twice.
+fn paths_to<'a>(
+    lockfile: &'a Lockfile,
+    roots: &[&'a LockedPackage],
+    name: &str,
+) -> Vec<Vec<&'a LockedPackage>> {
+    fn walk<'a>(
+        lockfile: &'a Lockfile,
+        name: &str,
+        path: &mut Vec<&'a LockedPackage>,
+        paths: &mut Vec<Vec<&'a LockedPackage>>,
+    ) {
+        let Some(&package) = path.last() else {
+            return;
+        };
+        if package.name == name {
+            paths.push(path.clone());
+            return;
+        }
+        for dependency in lockfile.dependencies_of(package) {
+            if path.contains(&dependency) {
+                continue;
+            }
+            path.push(dependency);
+            walk(lockfile, name, path, paths);
+            path.pop();
+        }
+    }
+
+    let mut paths = Vec::new();
+    for root in roots {
+        walk(lockfile, name, &mut vec![*root], &mut paths);
+    }
+    paths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use component_manifest::PackageDependency;
+
+    fn locked(name: &str, version: &str, deps: &[(&str, &str)]) -> LockedPackage {
+        LockedPackage {
+            name: name.to_string(),
+            version: version.to_string(),
+            registry: format!("ghcr.io/example/{name}"),
+            digest: "sha256:abc123".to_string(),
+            dependencies: deps
+                .iter()
+                .map(|(name, version)| PackageDependency {
+                    name: (*name).to_string(),
+                    version: (*version).to_string(),
+                    registry: String::new(),
+                    digest: String::new(),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn paths_to_finds_every_route() {
+        let lockfile = Lockfile {
+            components: vec![locked(
+                "root:app",
+                "0.1.0",
+                &[("wasi:http", "0.2.0"), ("wasi:clocks", "0.2.0")],
+            )],
+            interfaces: vec![
+                locked("wasi:http", "0.2.0", &[("wasi:io", "0.2.0")]),
+                locked("wasi:clocks", "0.2.0", &[("wasi:io", "0.2.0")]),
+                // A cycle, which must not be followed forever.
+                locked("wasi:io", "0.2.0", &[("wasi:http", "0.2.0")]),
+            ],
+            ..Default::default()
+        };
+        let roots: Vec<_> = lockfile
+            .all_packages()
+            .map(|(p, _)| p)
+            .filter(|p| p.name == "root:app" || p.name == "wasi:io")
+            .collect();
+
+        let paths: Vec<String> = paths_to(&lockfile, &roots, "wasi:io")
+            .into_iter()
+            .map(|path| {
+                path.into_iter()
+                    .map(package_label)
+                    .collect::<Vec<_>>()
+                    .join(" → ")
+            })
+            .collect();
+        assert_eq!(
+            paths,
+            [
+                "root:app@0.1.0 → wasi:http@0.2.0 → wasi:io@0.2.0",
+                "root:app@0.1.0 → wasi:clocks@0.2.0 → wasi:io@0.2.0",
+                "wasi:io@0.2.0",
+            ]
+        );
+    }
+}
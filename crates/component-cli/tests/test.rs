@@ -114,6 +114,145 @@ fn test_cli_local_list_help_snapshot() {
     assert_snapshot!(output);
 }
 
+// r[verify cli.local-inspect.help]
+#[test]
+fn test_cli_local_inspect_help_snapshot() {
+    let output = run_cli(&["local", "inspect", "--help"]);
+    assert_snapshot!(output);
+}
+
+#[test]
+fn test_local_inspect_picks_files_from_the_listing() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    std::fs::write(dir.path().join("a.wasm"), b"\0asm\x01\0\0\0").unwrap();
+    std::fs::write(dir.path().join("b.wasm"), b"\0asm\x01\0\0\0").unwrap();
+    let path = dir.path().to_str().unwrap();
+
+    let output = run_cli_raw(&["local", "inspect", "b.wasm", "--path", path, "--size"]);
+    assert!(output.status.success(), "{output:?}");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("b.wasm"), "{stderr}");
+
+    let output = run_cli_raw(&["local", "inspect", "3", "--path", path, "--size"]);
+    assert_eq!(output.status.code(), Some(3), "{output:?}");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("there is no file #3"), "{stderr}");
+}
+
+// r[verify cli.local-diff.help]
+#[test]
+fn test_cli_local_diff_help_snapshot() {
+    let output = run_cli(&["local", "diff", "--help"]);
+    assert_snapshot!(output);
+}
+
+#[test]
+fn test_local_diff_reports_interface_changes() {
+    let old = library_fixture("library_wordmark.wasm");
+    let new = library_fixture("library_kitchen_sink.wasm");
+    let output = run_cli_raw(&["--json", "local", "diff", &old, &new]);
+    assert!(output.status.success(), "{output:?}");
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let changes = json.get("changes").and_then(|c| c.as_array()).unwrap();
+    let has_change = |change: &str, item: &str| {
+        changes.iter().any(|c| {
+            c.get("change").and_then(|v| v.as_str()) == Some(change)
+                && c.get("item").and_then(|v| v.as_str()) == Some(item)
+        })
+    };
+    assert!(has_change("removed", "to-word"), "{json}");
+    assert!(has_change("added", "greet"), "{json}");
+    let size = |which: &str| {
+        json.pointer(&format!("/size/{which}"))
+            .and_then(serde_json::Value::as_u64)
+    };
+    assert!(size("new") > size("old"), "{json}");
+
+    let output = run_cli(&["local", "diff", &old, &old]);
+    assert!(
+        output.contains("No changes to imports or exports"),
+        "{output}"
+    );
+}
+
+// r[verify cli.local-watch.help]
+#[test]
+fn test_cli_local_watch_help_snapshot() {
+    let output = run_cli(&["local", "watch", "--help"]);
+    assert_snapshot!(output);
+}
+
+// r[verify cli.local-publish.help]
+#[test]
+fn test_cli_local_publish_help_snapshot() {
+    let output = run_cli(&["local", "publish", "--help"]);
+    assert_snapshot!(output);
+}
+
+#[test]
+fn test_local_publish_dry_run_picks_the_freshest_component() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    std::fs::write(
+        dir.path().join("wasm.toml"),
+        "[package]\n\
+         name = \"example:app\"\n\
+         version = \"0.1.0\"\n\
+         registry_ref = \"ghcr.io/example/app\"\n\
+         kind = \"component\"\n\
+         license = \"Apache-2.0\"\n",
+    )
+    .unwrap();
+    let release = dir.path().join("target/wasm32-wasip2/release");
+    std::fs::create_dir_all(&release).unwrap();
+    std::fs::create_dir_all(dir.path().join("dist")).unwrap();
+    let now = std::time::SystemTime::now();
+    let write = |path: std::path::PathBuf, bytes: &[u8], age: u64| {
+        std::fs::write(&path, bytes).unwrap();
+        std::fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(now - std::time::Duration::from_secs(age))
+            .unwrap();
+    };
+    write(dir.path().join("dist/old.wasm"), b"\0asm\x0d\0\x01\0", 60);
+    write(release.join("app.wasm"), b"\0asm\x0d\0\x01\0", 10);
+    // Core modules aren't published, however new they are.
+    write(release.join("core.wasm"), b"\0asm\x01\0\0\0", 0);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_component"))
+        .args(["local", "publish", "localhost:5000/app", "--dry-run"])
+        .arg("--manifest-path")
+        .arg(dir.path())
+        .output()
+        .expect("Failed to execute local publish");
+    assert!(output.status.success(), "{output:?}");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Target reference: localhost:5000/app:0.1.0"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("target/wasm32-wasip2/release/app.wasm"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("org.opencontainers.image.title = example:app"),
+        "{stdout}"
+    );
+
+    // Without any build outputs there's nothing to publish.
+    std::fs::remove_dir_all(dir.path().join("target")).unwrap();
+    std::fs::remove_dir_all(dir.path().join("dist")).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_component"))
+        .args(["local", "publish", "localhost:5000/app", "--dry-run"])
+        .arg("--manifest-path")
+        .arg(dir.path())
+        .output()
+        .expect("Failed to execute local publish");
+    assert_eq!(output.status.code(), Some(3), "{output:?}");
+}
+
 // r[verify cli.local-clean.help]
 #[test]
 fn test_cli_local_clean_help_snapshot() {
@@ -190,6 +329,45 @@ fn test_local_clean_removes_artifacts() {
     );
 }
 
+#[test]
+fn test_local_clean_build_removes_build_outputs() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let release = dir.path().join("target/wasm32-wasip2/release");
+    std::fs::create_dir_all(&release).unwrap();
+    std::fs::create_dir_all(dir.path().join("dist")).unwrap();
+    std::fs::write(release.join("app.wasm"), b"\0asm\x0d\0\x01\0").unwrap();
+    std::fs::write(dir.path().join("dist/app.wasm"), b"\0asm\x0d\0\x01\0").unwrap();
+    std::fs::write(dir.path().join("keep.wasm"), b"\0asm\x01\0\0\0").unwrap();
+    let clean = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_component"))
+            .args(["local", "clean", "--build"])
+            .args(args)
+            .current_dir(dir.path())
+            .env("NO_COLOR", "1")
+            .output()
+            .expect("Failed to execute local clean")
+    };
+
+    let output = clean(&["--dry-run"]);
+    assert!(output.status.success(), "{output:?}");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Would remove"), "{stdout}");
+    assert!(stdout.contains("dist/app.wasm"), "{stdout}");
+    assert!(!stdout.contains("keep.wasm"), "{stdout}");
+    assert!(release.join("app.wasm").exists());
+
+    // Without a terminal to confirm on, nothing is removed.
+    let output = clean(&[]);
+    assert!(!output.status.success(), "{output:?}");
+    assert!(release.join("app.wasm").exists());
+
+    let output = clean(&["--yes"]);
+    assert!(output.status.success(), "{output:?}");
+    assert!(!release.join("app.wasm").exists());
+    assert!(!dir.path().join("dist/app.wasm").exists());
+    assert!(dir.path().join("keep.wasm").exists());
+}
+
 #[test]
 fn test_local_clean_succeeds_when_nothing_to_clean() {
     let dir = TempDir::new().expect("Failed to create temp dir");
@@ -295,6 +473,144 @@ fn test_cli_registry_delete_help_snapshot() {
     assert_snapshot!(output);
 }
 
+#[test]
+fn test_registry_delete_asks_for_confirmation() {
+    let output = run_cli_error(
+        &[
+            "--offline",
+            "registry",
+            "delete",
+            "ghcr.io/example/test:1.0.0",
+        ],
+        None,
+    );
+    assert!(
+        output.contains("Delete 'ghcr.io/example/test:1.0.0' from the local store?"),
+        "{output}"
+    );
+    assert!(output.contains("pass `--yes` to confirm"), "{output}");
+}
+
+#[test]
+fn test_registry_delete_yes_skips_confirmation() {
+    let output = run_cli_raw(&[
+        "--offline",
+        "registry",
+        "delete",
+        "ghcr.io/example/does-not-exist:1.0.0",
+        "--yes",
+    ]);
+    assert!(output.status.success(), "{output:?}");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("not found in local store"), "{stdout}");
+}
+
+// r[verify cli.registry-sign.help]
+#[test]
+fn test_cli_registry_sign_help_snapshot() {
+    let output = run_cli(&["registry", "sign", "--help"]);
+    assert_snapshot!(output);
+}
+
+#[test]
+fn test_registry_sign_needs_a_key_or_keyless() {
+    let output = run_cli_error(&["registry", "sign", "ghcr.io/example/test:1.0.0"], None);
+    assert!(output.contains("--key <PATH>"), "{output}");
+
+    let output = run_cli_error(
+        &[
+            "registry",
+            "sign",
+            "ghcr.io/example/test:1.0.0",
+            "--keyless",
+            "--no-tlog",
+        ],
+        None,
+    );
+    assert!(output.contains("cannot be used with"), "{output}");
+}
+
+#[test]
+fn test_registry_sign_rejects_invalid_key() {
+    let dir = TempDir::new().unwrap();
+    let key = dir.path().join("cosign.key");
+    std::fs::write(&key, "not a key").unwrap();
+    let output = run_cli_error(
+        &[
+            "--offline",
+            "registry",
+            "sign",
+            "ghcr.io/example/test:1.0.0",
+            "--key",
+            key.to_str().unwrap(),
+        ],
+        None,
+    );
+    assert!(
+        output.contains("could not read the signing key"),
+        "{output}"
+    );
+}
+
+// r[verify cli.registry-verify.help]
+#[test]
+fn test_cli_registry_verify_help_snapshot() {
+    let output = run_cli(&["registry", "verify", "--help"]);
+    assert_snapshot!(output);
+}
+
+#[test]
+fn test_registry_verify_fails_offline() {
+    let output = run_cli_error(
+        &[
+            "--offline",
+            "registry",
+            "verify",
+            "ghcr.io/example/test:1.0.0",
+        ],
+        None,
+    );
+    assert!(
+        output.contains("cannot verify packages in offline mode"),
+        "{output}"
+    );
+}
+
+// r[verify cli.registry-discover.help]
+#[test]
+fn test_cli_registry_discover_help_snapshot() {
+    let output = run_cli(&["registry", "discover", "--help"]);
+    assert_snapshot!(output);
+}
+
+// r[verify cli.registry-catalog.help]
+#[test]
+fn test_cli_registry_catalog_help_snapshot() {
+    let output = run_cli(&["registry", "catalog", "--help"]);
+    assert_snapshot!(output);
+}
+
+// r[verify cli.registry-extract.help]
+#[test]
+fn test_cli_registry_extract_help_snapshot() {
+    let output = run_cli(&["registry", "extract", "--help"]);
+    assert_snapshot!(output);
+}
+
+// r[verify cli.registry-prune.help]
+#[test]
+fn test_cli_registry_prune_help_snapshot() {
+    let output = run_cli(&["registry", "prune", "--help"]);
+    assert_snapshot!(output);
+}
+
+// r[verify cli.registry-serve.help]
+#[test]
+fn test_cli_registry_serve_help_snapshot() {
+    let output = run_cli(&["registry", "serve", "--help"]);
+    assert_snapshot!(output);
+}
+
 // r[verify cli.registry-list.help]
 #[test]
 fn test_cli_registry_list_help_snapshot() {
@@ -316,6 +632,52 @@ fn test_cli_registry_inspect_help_snapshot() {
     assert_snapshot!(output);
 }
 
+// r[verify cli.registry-import.help]
+#[test]
+fn test_cli_registry_import_help_snapshot() {
+    let output = run_cli(&["registry", "import", "--help"]);
+    assert_snapshot!(output);
+}
+
+// r[verify cli.registry-export.help]
+#[test]
+fn test_cli_registry_export_help_snapshot() {
+    let output = run_cli(&["registry", "export", "--help"]);
+    assert_snapshot!(output);
+}
+
+#[test]
+fn test_registry_bundles_report_missing_and_damaged_packages() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let registry = |args: &[&str]| {
+        isolated_cli(dir.path())
+            .args(["--offline", "registry"])
+            .args(args)
+            .output()
+            .expect("Failed to execute command")
+    };
+    let bundle = dir.path().join("bundle.tar.zst");
+    let bundle = bundle.to_str().unwrap();
+
+    let output = registry(&["export", "ghcr.io/example/missing:1.0.0", "-o", bundle]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("is not in the local store"), "{stderr}");
+
+    std::fs::write(bundle, b"not a bundle").unwrap();
+    let output = registry(&["import", "--bundle", bundle]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("is invalid"), "{stderr}");
+}
+
+// r[verify cli.registry-show.help]
+#[test]
+fn test_cli_registry_show_help_snapshot() {
+    let output = run_cli(&["registry", "show", "--help"]);
+    assert_snapshot!(output);
+}
+
 // r[verify cli.self-clean.help]
 #[test]
 fn test_cli_self_clean_help_snapshot() {
@@ -334,6 +696,96 @@ fn test_cli_self_help_snapshot() {
     assert_snapshot!(output);
 }
 
+// r[verify cli.registry-digest.help]
+#[test]
+fn test_cli_registry_digest_help_snapshot() {
+    let output = run_cli(&["registry", "digest", "--help"]);
+    assert_snapshot!(output);
+}
+
+// r[verify cli.registry-resolve.help]
+#[test]
+fn test_cli_registry_resolve_help_snapshot() {
+    let output = run_cli(&["registry", "resolve", "--help"]);
+    assert_snapshot!(output);
+}
+
+// r[verify cli.self-data-dir.help]
+#[test]
+fn test_cli_self_data_dir_help_snapshot() {
+    let output = run_cli(&["self", "data-dir", "--help"]);
+    assert_snapshot!(output);
+}
+
+#[test]
+fn test_plumbing_commands_print_a_single_value() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let data_dir = dir.path().join("data");
+    let config = dir.path().join("config.toml");
+    std::fs::write(&config, "default-registry = \"registry.example.com\"\n").unwrap();
+    let component = |args: &[&str]| {
+        let output = isolated_cli(dir.path())
+            .args(["--offline", "--config", config.to_str().unwrap()])
+            .args(args)
+            .env("WASM_DATA_DIR", &data_dir)
+            .output()
+            .expect("Failed to execute command");
+        assert!(output.status.success(), "{output:?}");
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    assert_eq!(
+        component(&["registry", "resolve", "example/app:1.0"]),
+        "registry.example.com/example/app:1.0\n"
+    );
+    let digest = format!("sha256:{}", "a".repeat(64));
+    assert_eq!(
+        component(&["registry", "digest", &format!("example/app@{digest}")]),
+        format!("{digest}\n")
+    );
+    assert_eq!(
+        component(&["self", "data-dir"]),
+        format!("{}\n", data_dir.display())
+    );
+    assert_eq!(
+        component(&["--json", "self", "data-dir"]).trim(),
+        serde_json::to_string(&data_dir).unwrap()
+    );
+}
+
+// r[verify cli.stats.help]
+#[test]
+fn test_cli_stats_help_snapshot() {
+    let output = run_cli(&["stats", "--help"]);
+    assert_snapshot!(output);
+}
+
+#[test]
+fn test_stats_reports_an_empty_store() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let component = |args: &[&str]| {
+        let output = run_cli_isolated(dir.path(), args);
+        assert!(output.status.success(), "{output:?}");
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    let stats: serde_json::Value = serde_json::from_str(&component(&["--json", "stats"])).unwrap();
+    let field = |name: &str| stats.get(name).cloned();
+    assert_eq!(field("images"), Some(0.into()));
+    assert_eq!(field("interfaces"), Some(0.into()));
+    assert_eq!(field("physical_size"), Some(0.into()));
+    assert_eq!(field("largest"), Some(serde_json::json!([])));
+    assert_eq!(field("oldest_unused"), Some(serde_json::json!([])));
+
+    let table = component(&["stats"]);
+    assert!(table.contains("Images"), "{table}");
+    assert!(!table.contains("Largest"), "{table}");
+
+    let plain = component(&["--format", "plain", "stats"]);
+    assert!(plain.lines().any(|line| line == "Images\t0"), "{plain}");
+    assert!(plain.lines().all(|line| line.contains('\t')), "{plain}");
+}
+
 // r[verify cli.self-state.help]
 #[test]
 fn test_cli_self_state_help_snapshot() {
@@ -348,6 +800,55 @@ fn test_cli_self_log_help_snapshot() {
     assert_snapshot!(output);
 }
 
+// r[verify cli.self-backup.help]
+#[test]
+fn test_cli_self_backup_help_snapshot() {
+    let output = run_cli(&["self", "backup", "--help"]);
+    assert_snapshot!(output);
+}
+
+// r[verify cli.self-restore.help]
+#[test]
+fn test_cli_self_restore_help_snapshot() {
+    let output = run_cli(&["self", "restore", "--help"]);
+    assert_snapshot!(output);
+}
+
+// r[verify cli.self-relocate.help]
+#[test]
+fn test_cli_self_relocate_help_snapshot() {
+    let output = run_cli(&["self", "relocate", "--help"]);
+    assert_snapshot!(output);
+}
+
+// r[verify cli.self-migrate.help]
+#[test]
+fn test_cli_self_migrate_help_snapshot() {
+    let output = run_cli(&["self", "migrate", "--help"]);
+    assert_snapshot!(output);
+}
+
+// r[verify cli.self-uninstall.help]
+#[test]
+fn test_cli_self_uninstall_help_snapshot() {
+    let output = run_cli(&["self", "uninstall", "--help"]);
+    assert_snapshot!(output);
+}
+
+// r[verify cli.self-cache.help]
+#[test]
+fn test_cli_self_cache_help_snapshot() {
+    let output = run_cli(&["self", "cache", "--help"]);
+    assert_snapshot!(output);
+}
+
+// r[verify cli.self-cache-evict.help]
+#[test]
+fn test_cli_self_cache_evict_help_snapshot() {
+    let output = run_cli(&["self", "cache", "evict", "--help"]);
+    assert_snapshot!(output);
+}
+
 // =============================================================================
 // Completions Tests
 // =============================================================================
@@ -578,38 +1079,214 @@ fn test_offline_flag_with_local_list() {
     assert!(output.status.success());
 }
 
-// r[verify cli.offline.registry-blocked]
 #[test]
-fn test_offline_flag_with_registry_pull() {
-    // Test that --offline mode causes registry pull to fail with clear error
-    let output = Command::new(env!("CARGO_BIN_EXE_component"))
-        .args(&[
-            "--offline",
-            "registry",
-            "pull",
-            "ghcr.io/example/test:latest",
-        ])
-        .output()
-        .expect("Failed to execute command");
+fn test_format_flag_applies_to_listings() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    std::fs::write(dir.path().join("b.wasm"), b"\0asm\x01\0\0\0").unwrap();
+    std::fs::write(dir.path().join("a.wasm"), b"\0asm\x01\0\0\0").unwrap();
+    let path = dir.path().to_str().unwrap();
 
-    // The command should fail with an offline mode error
-    assert!(!output.status.success());
-    let stderr = String::from_utf8_lossy(&output.stderr);
+    let output = run_cli_raw(&["local", "list", path, "--json"]);
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let paths: Vec<&str> = json
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|entry| entry.get("path").and_then(|p| p.as_str()))
+        .collect();
+    assert_eq!(paths.len(), 2, "{json}");
     assert!(
-        stderr.contains("offline"),
-        "Expected 'offline' error message, got: {}",
-        stderr
+        paths.first().is_some_and(|p| p.ends_with("a.wasm")),
+        "{json}"
+    );
+    let first = json.get(0).expect("a listed file");
+    assert_eq!(
+        first.get("kind").and_then(|v| v.as_str()),
+        Some("module"),
+        "{json}"
+    );
+    assert_eq!(
+        first.get("size").and_then(serde_json::Value::as_u64),
+        Some(8),
+        "{json}"
     );
-}
-
-// r[verify cli.offline.with-inspect]
-#[test]
-fn test_offline_flag_with_registry_inspect() {
-    // Test that --offline works with registry inspect command
-    let output = Command::new(env!("CARGO_BIN_EXE_component"))
-        .args(&["--offline", "registry", "inspect", "--help"])
-        .output()
-        .expect("Failed to execute command");
+
+    let output = run_cli_raw(&["--format", "plain", "local", "list", path]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout.lines().count(), 2, "{stdout}");
+    assert!(stdout.starts_with("1\t"), "{stdout}");
+
+    let output = run_cli_raw(&["--format", "csv", "local", "list", path]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut lines = stdout.lines();
+    assert_eq!(lines.next(), Some("#,File Path,Kind,Size,Modified"));
+    assert!(
+        lines.next().unwrap().contains("a.wasm,module,8,"),
+        "{stdout}"
+    );
+
+    let output = run_cli_raw(&["--format", "table", "--json", "local", "list", path]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn test_rust_log_refines_the_log_level() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let status = |rust_log: Option<&str>| {
+        let mut cmd = isolated_cli(dir.path());
+        cmd.args(["self", "cache", "status"])
+            .env("NO_COLOR", "1")
+            .env_remove("RUST_LOG");
+        if let Some(rust_log) = rust_log {
+            cmd.env("RUST_LOG", rust_log);
+        }
+        let output = cmd.output().expect("Failed to execute command");
+        assert!(output.status.success());
+        String::from_utf8_lossy(&output.stderr).into_owned()
+    };
+
+    let stderr = status(None);
+    assert!(!stderr.contains("sql statement"), "{stderr}");
+    let stderr = status(Some("component_package_manager::storage=trace"));
+    assert!(stderr.contains("sql statement"), "{stderr}");
+}
+
+#[test]
+fn test_errors_map_to_exit_codes() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let run = |args: &[&str]| {
+        isolated_cli(dir.path())
+            .args(args)
+            .env("NO_COLOR", "1")
+            .output()
+            .expect("Failed to execute command")
+    };
+    let pull = [
+        "--offline",
+        "registry",
+        "pull",
+        "ghcr.io/example/test:1.0.0",
+    ];
+
+    let output = run(&pull);
+    assert_eq!(output.status.code(), Some(5), "{output:?}");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("offline mode"), "{stderr}");
+
+    let output = run(&[&["--error-format", "json"], &pull[..]].concat());
+    assert_eq!(output.status.code(), Some(5), "{output:?}");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let line = stderr.lines().last().expect("an error line");
+    let json: serde_json::Value = serde_json::from_str(line).expect(line);
+    assert_eq!(
+        json.get("kind").and_then(|v| v.as_str()),
+        Some("offline"),
+        "{json}"
+    );
+    assert_eq!(
+        json.get("exit_code").and_then(serde_json::Value::as_u64),
+        Some(5),
+        "{json}"
+    );
+    let message = json.get("message").and_then(|v| v.as_str());
+    assert!(
+        message.is_some_and(|m| m.contains("offline mode")),
+        "{json}"
+    );
+}
+
+// r[verify cli.offline.registry-blocked]
+#[test]
+fn test_offline_flag_with_registry_pull() {
+    // Test that --offline mode causes registry pull to fail with clear error
+    let output = Command::new(env!("CARGO_BIN_EXE_component"))
+        .args(&[
+            "--offline",
+            "registry",
+            "pull",
+            "ghcr.io/example/test:latest",
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    // The command should fail with an offline mode error
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("offline"),
+        "Expected 'offline' error message, got: {}",
+        stderr
+    );
+}
+
+#[test]
+fn test_offline_registry_pull_many_reports_each_failure() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let lockfile = dir.path().join("wasm.lock.toml");
+    std::fs::write(
+        &lockfile,
+        r#"lockfile_version = 3
+
+[[interfaces]]
+name = "wasi:logging"
+version = "0.2.0"
+registry = "ghcr.io/webassembly/wasi-logging"
+digest = ""
+"#,
+    )
+    .unwrap();
+
+    let output = run_cli_raw(&[
+        "--offline",
+        "registry",
+        "pull",
+        "ghcr.io/example/one:1.0.0",
+        "ghcr.io/example/two:1.0.0",
+        "--lockfile",
+        lockfile.to_str().unwrap(),
+    ]);
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    for reference in [
+        "ghcr.io/example/one:1.0.0",
+        "ghcr.io/example/two:1.0.0",
+        "ghcr.io/webassembly/wasi-logging:0.2.0",
+    ] {
+        assert!(stdout.contains(reference), "{stdout}");
+    }
+    assert!(stdout.contains("failed"), "{stdout}");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("3 of 3 pulls failed"), "{stderr}");
+}
+
+#[test]
+fn test_registry_pull_output_needs_one_reference() {
+    let output = run_cli_error(
+        &[
+            "--offline",
+            "registry",
+            "pull",
+            "ghcr.io/example/one:1.0.0",
+            "ghcr.io/example/two:1.0.0",
+            "--output",
+            "out.wasm",
+        ],
+        None,
+    );
+    assert!(output.contains("needs exactly one reference"), "{output}");
+}
+
+// r[verify cli.offline.with-inspect]
+#[test]
+fn test_offline_flag_with_registry_inspect() {
+    // Test that --offline works with registry inspect command
+    let output = Command::new(env!("CARGO_BIN_EXE_component"))
+        .args(&["--offline", "registry", "inspect", "--help"])
+        .output()
+        .expect("Failed to execute command");
 
     assert!(output.status.success());
 }
@@ -707,6 +1384,96 @@ fn test_init_help_snapshot() {
     assert_snapshot!(output);
 }
 
+#[test]
+fn test_init_from_template() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    std::fs::write(dir.path().join(".gitignore"), "/target\n").unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_component"))
+        .args([
+            "init",
+            "--template",
+            "http-proxy",
+            "--name",
+            "my-org:hello",
+            "--registry",
+            "ghcr.io/me/",
+            "--gitignore",
+        ])
+        .current_dir(dir.path())
+        .output()
+        .expect("Failed to execute command");
+    assert!(
+        output.status.success(),
+        "init failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    assert!(dir.path().join("vendor/wit").is_dir());
+    let manifest: component_manifest::Manifest =
+        toml::from_str(&std::fs::read_to_string(dir.path().join("wasm.toml")).unwrap()).unwrap();
+    let package = manifest.package.expect("package section");
+    assert_eq!(package.name, "my-org:hello");
+    assert_eq!(package.registry_ref, "ghcr.io/me/hello");
+    assert_eq!(package.world.as_deref(), Some("wasi:http/proxy"));
+    assert!(manifest.dependencies.interfaces.contains_key("wasi:http"));
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("wit/world.wit")).unwrap(),
+        "package my-org:hello;\n\nworld hello {\n    include wasi:http/proxy@0.2.3;\n}\n"
+    );
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join(".gitignore")).unwrap(),
+        "/target\n/build/\n"
+    );
+}
+
+#[test]
+fn test_init_rejects_invalid_name() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let stderr = run_cli_error(&["init", "--name", "hello"], Some(dir.path()));
+    assert!(
+        stderr.contains("'hello' is not a valid package name"),
+        "{stderr}"
+    );
+}
+
+// r[verify cli.new.help]
+#[test]
+fn test_new_help_snapshot() {
+    let output = run_cli(&["new", "--help"]);
+    assert_snapshot!(output);
+}
+
+#[test]
+fn test_new_creates_project_directory() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let output = Command::new(env!("CARGO_BIN_EXE_component"))
+        .args(["new", "my-cli", "--template", "cli-command"])
+        .current_dir(dir.path())
+        .output()
+        .expect("Failed to execute command");
+    assert!(
+        output.status.success(),
+        "new failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let target = dir.path().join("my-cli");
+    let manifest: component_manifest::Manifest =
+        toml::from_str(&std::fs::read_to_string(target.join("wasm.toml")).unwrap()).unwrap();
+    let package = manifest.package.expect("package section");
+    assert_eq!(package.name, "local:my-cli");
+    assert_eq!(package.registry_ref, "ghcr.io/local/my-cli");
+    assert_eq!(package.world.as_deref(), Some("wasi:cli/command"));
+    assert!(target.join("wit/world.wit").is_file());
+    assert!(target.join("wasm.lock.toml").is_file());
+
+    let stderr = run_cli_error(&["new", "my-cli"], Some(dir.path()));
+    assert!(
+        stderr.contains("destination `my-cli` already exists"),
+        "{stderr}"
+    );
+}
+
 // =============================================================================
 // Install Command Help Tests
 // =============================================================================
@@ -726,6 +1493,467 @@ fn test_install_without_init() {
     assert_snapshot!(stderr);
 }
 
+// r[verify cli.add.help]
+#[test]
+fn test_add_help_snapshot() {
+    let output = run_cli(&["add", "--help"]);
+    assert_snapshot!(output);
+}
+
+#[test]
+fn test_add_without_init() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let stderr = run_cli_error(&["add", "ghcr.io/example/test:1.0.0"], Some(dir.path()));
+    assert!(
+        stderr.contains("no local `wasm.toml` manifest found"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn test_add_failure_leaves_manifest_untouched() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let manifest =
+        "# my project\n[dependencies.interfaces]\n\"wasi:logging\" = \"1.0.0\" # pinned\n";
+    std::fs::write(dir.path().join("wasm.toml"), manifest).unwrap();
+
+    // Nothing is cached, so an offline add can't pull the package.
+    run_cli_error(
+        &["--offline", "add", "ghcr.io/example/test:1.0.0"],
+        Some(dir.path()),
+    );
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("wasm.toml")).unwrap(),
+        manifest
+    );
+    assert!(!dir.path().join("wasm.lock.toml").exists());
+}
+
+// =============================================================================
+// Remove Command Tests
+// =============================================================================
+
+// r[verify cli.remove.help]
+#[test]
+fn test_remove_help_snapshot() {
+    let output = run_cli(&["remove", "--help"]);
+    assert_snapshot!(output);
+}
+
+#[test]
+fn test_remove_without_init() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let stderr = run_cli_error(&["remove", "wasi:logging"], Some(dir.path()));
+    assert!(
+        stderr.contains("no local `wasm.toml` manifest found"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn test_remove_unknown_dependency() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let manifest = "[dependencies.interfaces]\n\"wasi:logging\" = \"1.0.0\"\n";
+    std::fs::write(dir.path().join("wasm.toml"), manifest).unwrap();
+
+    let stderr = run_cli_error(&["remove", "wasi:clocks"], Some(dir.path()));
+    assert!(
+        stderr.contains("'wasi:clocks' is not a dependency in `wasm.toml`"),
+        "{stderr}"
+    );
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("wasm.toml")).unwrap(),
+        manifest
+    );
+}
+
+#[test]
+fn test_remove_prunes_lockfile_and_vendored_files() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    std::fs::write(
+        dir.path().join("wasm.toml"),
+        "# my project\n\
+         [dependencies.interfaces]\n\
+         \"wasi:key-value\" = \"2.0.0\" # storage\n\
+         \"wasi:clocks\" = \"0.2.5\"\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.path().join("wasm.lock.toml"),
+        "lockfile_version = 3\n\
+         \n\
+         [[interfaces]]\n\
+         name = \"wasi:key-value\"\n\
+         version = \"2.0.0\"\n\
+         registry = \"ghcr.io/webassembly/wasi-key-value\"\n\
+         digest = \"sha256:def456def456def456\"\n\
+         \n\
+         [[interfaces.dependencies]]\n\
+         name = \"wasi:logging\"\n\
+         version = \"1.0.0\"\n\
+         \n\
+         [[interfaces]]\n\
+         name = \"wasi:logging\"\n\
+         version = \"1.0.0\"\n\
+         registry = \"ghcr.io/webassembly/wasi-logging\"\n\
+         digest = \"sha256:abc123abc123abc123\"\n\
+         \n\
+         [[interfaces]]\n\
+         name = \"wasi:clocks\"\n\
+         version = \"0.2.5\"\n\
+         registry = \"ghcr.io/webassembly/wasi-clocks\"\n\
+         digest = \"sha256:789789789789789789\"\n",
+    )
+    .unwrap();
+    std::fs::create_dir_all(dir.path().join("vendor/wit")).unwrap();
+    let vendored = [
+        "vendor/wit/ghcr-io-webassembly-wasi-key-value-2.0.0-def456def456.wit",
+        "vendor/wit/ghcr-io-webassembly-wasi-logging-1.0.0-abc123abc123.wit",
+        "vendor/wit/ghcr-io-webassembly-wasi-clocks-0.2.5-789789789789.wit",
+    ];
+    for path in vendored {
+        std::fs::write(dir.path().join(path), "package wasi:placeholder;\n").unwrap();
+    }
+
+    let output = Command::new(env!("CARGO_BIN_EXE_component"))
+        .args(["remove", "wasi:key-value", "--vendor"])
+        .current_dir(dir.path())
+        .output()
+        .expect("Failed to execute remove");
+    assert!(
+        output.status.success(),
+        "remove failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Removed wasi:key-value from [dependencies.interfaces]"),
+        "{stdout}"
+    );
+    assert!(stdout.contains("Unlocked wasi:logging 1.0.0"), "{stdout}");
+
+    let manifest = std::fs::read_to_string(dir.path().join("wasm.toml")).unwrap();
+    assert_eq!(
+        manifest,
+        "# my project\n[dependencies.interfaces]\n\"wasi:clocks\" = \"0.2.5\"\n"
+    );
+    let lockfile = std::fs::read_to_string(dir.path().join("wasm.lock.toml")).unwrap();
+    assert!(lockfile.contains("wasi:clocks"), "{lockfile}");
+    assert!(!lockfile.contains("wasi:key-value"), "{lockfile}");
+    assert!(!lockfile.contains("wasi:logging"), "{lockfile}");
+
+    let [key_value, logging, clocks] = vendored.map(|path| dir.path().join(path));
+    assert!(!key_value.exists());
+    assert!(!logging.exists());
+    assert!(clocks.exists());
+}
+
+// =============================================================================
+// Tree Command Tests
+// =============================================================================
+
+// r[verify cli.tree.help]
+#[test]
+fn test_tree_help_snapshot() {
+    let output = run_cli(&["tree", "--help"]);
+    assert_snapshot!(output);
+}
+
+#[test]
+fn test_tree_without_init() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let stderr = run_cli_error(&["tree"], Some(dir.path()));
+    assert!(
+        stderr.contains("no local `wasm.toml` manifest found"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn test_tree_renders_lockfile() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    std::fs::write(
+        dir.path().join("wasm.toml"),
+        "[dependencies.interfaces]\n\
+         \"wasi:key-value\" = \"2.0.0\"\n\
+         \"wasi:clocks\" = \"0.2.5\"\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.path().join("wasm.lock.toml"),
+        "lockfile_version = 3\n\
+         \n\
+         [[interfaces]]\n\
+         name = \"wasi:key-value\"\n\
+         version = \"2.0.0\"\n\
+         registry = \"ghcr.io/webassembly/wasi-key-value\"\n\
+         digest = \"sha256:def456\"\n\
+         \n\
+         [[interfaces.dependencies]]\n\
+         name = \"wasi:logging\"\n\
+         version = \"1.0.0\"\n\
+         \n\
+         [[interfaces]]\n\
+         name = \"wasi:logging\"\n\
+         version = \"1.0.0\"\n\
+         registry = \"ghcr.io/webassembly/wasi-logging\"\n\
+         digest = \"sha256:abc123\"\n",
+    )
+    .unwrap();
+
+    let tree = |args: &[&str]| {
+        let output = Command::new(env!("CARGO_BIN_EXE_component"))
+            .args(args)
+            .current_dir(dir.path())
+            .output()
+            .expect("Failed to execute tree");
+        assert!(
+            output.status.success(),
+            "tree failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    };
+    assert_eq!(
+        tree(&["tree"]),
+        "wasi:key-value@2.0.0\n\
+         └── wasi:logging@1.0.0\n\
+         \n\
+         wasi:clocks (not installed)\n"
+    );
+    assert_eq!(
+        tree(&["tree", "--invert", "wasi:logging"]),
+        "wasi:logging@1.0.0\n\
+         └── wasi:key-value@2.0.0\n"
+    );
+
+    let stderr = run_cli_error(&["tree", "--invert", "wasi:io"], Some(dir.path()));
+    assert!(
+        stderr.contains("'wasi:io' is not in `wasm.lock.toml`"),
+        "{stderr}"
+    );
+}
+
+// =============================================================================
+// Why Command Tests
+// =============================================================================
+
+// r[verify cli.why.help]
+#[test]
+fn test_why_help_snapshot() {
+    let output = run_cli(&["why", "--help"]);
+    assert_snapshot!(output);
+}
+
+#[test]
+fn test_why_prints_paths_from_the_manifest() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    std::fs::write(
+        dir.path().join("wasm.toml"),
+        "[dependencies.interfaces]\n\"wasi:key-value\" = \"2.0.0\"\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.path().join("wasm.lock.toml"),
+        "lockfile_version = 3\n\
+         \n\
+         [[interfaces]]\n\
+         name = \"wasi:key-value\"\n\
+         version = \"2.0.0\"\n\
+         registry = \"ghcr.io/webassembly/wasi-key-value\"\n\
+         digest = \"sha256:def456\"\n\
+         \n\
+         [[interfaces.dependencies]]\n\
+         name = \"wasi:logging\"\n\
+         version = \"1.0.0\"\n\
+         \n\
+         [[interfaces]]\n\
+         name = \"wasi:logging\"\n\
+         version = \"1.0.0\"\n\
+         registry = \"ghcr.io/webassembly/wasi-logging\"\n\
+         digest = \"sha256:abc123\"\n",
+    )
+    .unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_component"))
+        .args(["why", "wasi:logging"])
+        .current_dir(dir.path())
+        .output()
+        .expect("Failed to execute why");
+    assert!(
+        output.status.success(),
+        "why failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "wasi:key-value@2.0.0 → wasi:logging@1.0.0\n"
+    );
+
+    let stderr = run_cli_error(&["why", "wasi:io"], Some(dir.path()));
+    assert!(
+        stderr.contains("'wasi:io' is not in `wasm.lock.toml`"),
+        "{stderr}"
+    );
+}
+
+// =============================================================================
+// Lock Command Tests
+// =============================================================================
+
+// r[verify cli.lock.verify.help]
+#[test]
+fn test_lock_verify_help_snapshot() {
+    let output = run_cli(&["lock", "verify", "--help"]);
+    assert_snapshot!(output);
+}
+
+#[test]
+fn test_lock_verify_offline() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    std::fs::write(
+        dir.path().join("wasm.toml"),
+        "[dependencies.interfaces]\n\"wasi:logging\" = \"1.0.0\"\n",
+    )
+    .unwrap();
+    let lockfile = format!(
+        "lockfile_version = 3\n\n[[interfaces]]\nname = \"wasi:logging\"\nversion = \"1.0.0\"\nregistry = \"ghcr.io/webassembly/wasi-logging\"\ndigest = \"sha256:{}\"\n",
+        "a".repeat(64)
+    );
+    std::fs::write(dir.path().join("wasm.lock.toml"), &lockfile).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_component"))
+        .args(["--offline", "lock", "verify"])
+        .current_dir(dir.path())
+        .output()
+        .expect("Failed to execute lock verify");
+    assert!(
+        output.status.success(),
+        "lock verify failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        String::from_utf8_lossy(&output.stdout).contains("Verified 1 locked package"),
+        "{}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+
+    // A second dependency that was never locked, and a truncated digest.
+    std::fs::write(
+        dir.path().join("wasm.toml"),
+        "[dependencies.interfaces]\n\"wasi:logging\" = \"1.0.0\"\n\"wasi:io\" = \"0.2.0\"\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.path().join("wasm.lock.toml"),
+        lockfile.replace(&"a".repeat(64), "abc123"),
+    )
+    .unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_component"))
+        .args(["--offline", "lock", "verify"])
+        .current_dir(dir.path())
+        .env("NO_COLOR", "1")
+        .output()
+        .expect("Failed to execute lock verify");
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Dependency 'wasi:io' is in the manifest but not in the lockfile"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("Package 'wasi:logging' has invalid digest 'sha256:abc123'"),
+        "{stdout}"
+    );
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("failed verification with 2 problems"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn test_lock_verify_requires_a_lockfile() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    std::fs::write(dir.path().join("wasm.toml"), "").unwrap();
+    let stderr = run_cli_error(&["--offline", "lock", "verify"], Some(dir.path()));
+    assert!(stderr.contains("no `wasm.lock.toml` found"), "{stderr}");
+}
+
+// =============================================================================
+// Audit Command Tests
+// =============================================================================
+
+// r[verify cli.audit.help]
+#[test]
+fn test_audit_help_snapshot() {
+    let output = run_cli(&["audit", "--help"]);
+    assert_snapshot!(output);
+}
+
+#[test]
+fn test_audit_offline_reports_denied_and_unpinned_packages() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let denied = format!("sha256:{}", "d".repeat(64));
+    std::fs::write(
+        dir.path().join("wasm.lock.toml"),
+        format!(
+            "lockfile_version = 3\n\n\
+             [[interfaces]]\nname = \"wasi:logging\"\nversion = \"1.0.0\"\n\
+             registry = \"ghcr.io/webassembly/wasi-logging\"\ndigest = \"{denied}\"\n\n\
+             [[interfaces]]\nname = \"wasi:io\"\nversion = \"0.2.0\"\n\
+             registry = \"ghcr.io/webassembly/wasi-io\"\ndigest = \"\"\n"
+        ),
+    )
+    .unwrap();
+    std::fs::write(
+        dir.path().join("denied.txt"),
+        format!("# known bad\n{denied} leaked signing key\n"),
+    )
+    .unwrap();
+
+    let audit = |args: &[&str]| {
+        isolated_cli(dir.path())
+            .args(["--offline", "audit"])
+            .args(args)
+            .current_dir(dir.path())
+            .env("NO_COLOR", "1")
+            .output()
+            .expect("Failed to execute audit")
+    };
+
+    let output = audit(&["--denylist", "denied.txt"]);
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains(&format!(
+            "critical wasi:logging@1.0.0: digest {denied} is denied: leaked signing key"
+        )),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("    high wasi:io@0.2.0: locked by the tag '0.2.0' without a digest"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("Audited 2 packages: 1 critical, 1 high, 0 medium, 0 low"),
+        "{stdout}"
+    );
+    assert!(
+        String::from_utf8_lossy(&output.stderr)
+            .contains("audit found 2 problems of high severity or worse")
+    );
+
+    // Without the denylist, only the unpinned tag is left, which
+    // `--fail-on critical` accepts.
+    let output = audit(&["--fail-on", "critical"]);
+    assert!(
+        output.status.success(),
+        "audit failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
 // =============================================================================
 // Publish Command Tests
 // =============================================================================
@@ -866,6 +2094,25 @@ fn run_cli_raw(args: &[&str]) -> std::process::Output {
         .expect("Failed to execute command")
 }
 
+/// A command for the binary with its home, config, data, and state
+/// directories all inside `dir`, so tests never touch the real store.
+fn isolated_cli(dir: &std::path::Path) -> Command {
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_component"));
+    cmd.env("HOME", dir)
+        .env("XDG_CONFIG_HOME", dir)
+        .env("XDG_DATA_HOME", dir)
+        .env("XDG_STATE_HOME", dir);
+    cmd
+}
+
+/// Run the binary isolated inside `dir` and return its raw output.
+fn run_cli_isolated(dir: &std::path::Path, args: &[&str]) -> std::process::Output {
+    isolated_cli(dir)
+        .args(args)
+        .output()
+        .expect("Failed to execute command")
+}
+
 /// **Headline test**: `to-word "# hi"` must produce byte-exact
 /// `DOCX:# hi` on stdout, with no trailing framing or newline.
 // r[verify run.library-output-bytes]
@@ -987,6 +2234,34 @@ fn test_library_resources_fixture_is_rejected() {
     );
 }
 
+// r[verify run.http-serve-flag]
+#[test]
+fn test_run_serve_rejects_non_http_components() {
+    let fixture = library_fixture("library_kitchen_sink.wasm");
+    let out = run_cli_raw(&["run", "--serve=3000", &fixture]);
+    assert!(!out.status.success());
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains("not an HTTP component"),
+        "expected 'not an HTTP component' in stderr: {stderr}"
+    );
+}
+
+// r[verify run.host-flags-before-input]
+#[test]
+fn test_run_serve_before_listen_is_a_host_flag() {
+    // `--serve` takes no separate value, so `--listen` after it is still
+    // a host flag rather than the input.
+    let fixture = library_fixture("library_kitchen_sink.wasm");
+    let out = run_cli_raw(&["run", "--serve", "--listen", "127.0.0.1:9", &fixture]);
+    assert!(!out.status.success());
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains("not an HTTP component"),
+        "expected 'not an HTTP component' in stderr: {stderr}"
+    );
+}
+
 // r[verify run.library-help.dynamic]
 #[test]
 fn test_library_dynamic_help_on_root() {
@@ -1112,6 +2387,48 @@ fn test_init_prints_success_message() {
     );
 }
 
+#[test]
+fn test_install_vendors_into_vendor_only() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let output = Command::new(env!("CARGO_BIN_EXE_component"))
+        .args(["init"])
+        .current_dir(dir.path())
+        .output()
+        .expect("Failed to execute init");
+    assert!(output.status.success(), "init failed");
+
+    // Nothing to pull, so this works offline and leaves the lockfile as is.
+    let output = Command::new(env!("CARGO_BIN_EXE_component"))
+        .args(["install", "--offline", "--locked"])
+        .current_dir(dir.path())
+        .output()
+        .expect("Failed to execute install");
+    assert!(
+        output.status.success(),
+        "install failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(dir.path().join("vendor/wasm").is_dir());
+    assert!(!dir.path().join("deps").exists());
+}
+
+#[test]
+fn test_install_locked_rejects_unlocked_dependencies() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    std::fs::write(
+        dir.path().join("wasm.toml"),
+        "[dependencies.interfaces]\n\"wasi:logging\" = \"1.0.0\"\n",
+    )
+    .unwrap();
+
+    let stderr = run_cli_error(&["install", "--offline", "--locked"], Some(dir.path()));
+    assert!(
+        stderr.contains("`wasm.lock.toml` needs to be updated for wasi:logging"),
+        "{stderr}"
+    );
+    assert!(!dir.path().join("wasm.lock.toml").exists());
+}
+
 #[test]
 fn test_install_scope_component_not_in_manifest() {
     let dir = TempDir::new().expect("Failed to create temp dir");
@@ -1350,6 +2667,674 @@ fn test_cli_compose_help_snapshot() {
     assert_snapshot!(output);
 }
 
+#[test]
+fn test_cli_compose_plug_help_snapshot() {
+    let output = run_cli(&["compose", "plug", "--help"]);
+    assert_snapshot!(output);
+}
+
+// =============================================================================
+// Convert Command Tests
+// =============================================================================
+
+// r[verify cli.convert.help]
+#[test]
+fn test_cli_convert_help_snapshot() {
+    let output = run_cli(&["convert", "--help"]);
+    assert_snapshot!(output);
+}
+
+#[test]
+fn test_convert_roundtrips_between_text_and_binary() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let wat = dir.path().join("add.wat");
+    std::fs::write(
+        &wat,
+        "(module (func (export \"add\") (param i32 i32) (result i32) local.get 0 local.get 1 i32.add))",
+    )
+    .unwrap();
+
+    let wasm = dir.path().join("add.wasm");
+    let output = run_cli_raw(&[
+        "convert",
+        "wasm",
+        wat.to_str().unwrap(),
+        "--output",
+        wasm.to_str().unwrap(),
+    ]);
+    assert!(output.status.success(), "{output:?}");
+    let bytes = std::fs::read(&wasm).unwrap();
+    assert!(bytes.starts_with(b"\0asm"));
+
+    let output = run_cli_raw(&["convert", "wat", wasm.to_str().unwrap()]);
+    assert!(output.status.success(), "{output:?}");
+    let text = String::from_utf8(output.stdout).unwrap();
+    assert!(text.contains("(export \"add\""), "{text}");
+    assert!(text.contains("i32.add"), "{text}");
+}
+
+#[test]
+fn test_convert_component_wraps_core_modules() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let module = dir.path().join("module.wat");
+    std::fs::write(&module, "(module (func (export \"noop\")))").unwrap();
+
+    let component = dir.path().join("component.wasm");
+    let output = run_cli_raw(&[
+        "convert",
+        "component",
+        module.to_str().unwrap(),
+        "--output",
+        component.to_str().unwrap(),
+    ]);
+    assert!(output.status.success(), "{output:?}");
+    let bytes = std::fs::read(&component).unwrap();
+    assert!(wasmparser::Parser::is_component(&bytes));
+
+    // Components can't be wrapped again.
+    let output = run_cli_raw(&["convert", "component", component.to_str().unwrap()]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("not a core Wasm module"), "{stderr}");
+}
+
+#[test]
+fn test_convert_wit_writes_a_wit_directory() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let mut resolve = wit_parser::Resolve::default();
+    let package = resolve
+        .push_str(
+            "greeter.wit",
+            "package test:greeter@1.0.0;\ninterface greet { hello: func() -> string; }",
+        )
+        .unwrap();
+    let input = dir.path().join("greeter.wasm");
+    std::fs::write(&input, wit_component::encode(&resolve, package).unwrap()).unwrap();
+
+    let output = run_cli_raw(&["convert", "wit", input.to_str().unwrap()]);
+    assert!(output.status.success(), "{output:?}");
+    let text = String::from_utf8(output.stdout).unwrap();
+    assert!(text.contains("package test:greeter@1.0.0"), "{text}");
+
+    let wit_dir = dir.path().join("wit");
+    let output = run_cli_raw(&[
+        "convert",
+        "wit",
+        input.to_str().unwrap(),
+        "--output",
+        wit_dir.to_str().unwrap(),
+    ]);
+    assert!(output.status.success(), "{output:?}");
+    let text = std::fs::read_to_string(wit_dir.join("greeter.wit")).unwrap();
+    assert!(text.contains("interface greet"), "{text}");
+}
+
+// =============================================================================
+// Inspect Command Tests
+// =============================================================================
+
+// r[verify cli.inspect.help]
+#[test]
+fn test_cli_inspect_help_snapshot() {
+    let output = run_cli(&["inspect", "--help"]);
+    assert_snapshot!(output);
+}
+
+#[test]
+fn test_inspect_prints_worlds_of_local_files() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let mut resolve = wit_parser::Resolve::default();
+    resolve
+        .push_str(
+            "dep.wit",
+            "package test:dep@1.0.0;\ninterface greeter { hello: func(); }",
+        )
+        .unwrap();
+    let package = resolve
+        .push_str(
+            "app.wit",
+            "package test:app;\nworld app { import test:dep/greeter@1.0.0; }",
+        )
+        .unwrap();
+    let input = dir.path().join("app.wasm");
+    std::fs::write(&input, wit_component::encode(&resolve, package).unwrap()).unwrap();
+
+    let output = run_cli_raw(&["inspect", input.to_str().unwrap()]);
+    assert!(output.status.success(), "{output:?}");
+    let text = String::from_utf8(output.stdout).unwrap();
+    assert!(text.contains("test:app"), "{text}");
+    assert!(text.contains("test:dep/greeter@1.0.0"), "{text}");
+
+    let output = run_cli_raw(&["--format", "plain", "inspect", input.to_str().unwrap()]);
+    assert!(output.status.success(), "{output:?}");
+    let text = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(
+        text,
+        "Package\ttest:app\nKind\tWIT package\nWorld\tapp\n\
+         Import\ttest:dep/greeter@1.0.0\nDependency\ttest:dep@1.0.0\n"
+    );
+
+    let output = run_cli_raw(&["inspect", input.to_str().unwrap(), "--json"]);
+    assert!(output.status.success(), "{output:?}");
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    for (pointer, expected) in [
+        ("/package_name", "test:app"),
+        ("/worlds/0/name", "app"),
+        ("/worlds/0/imports/0/interface", "greeter"),
+        ("/dependencies/0/package", "test:dep"),
+    ] {
+        assert_eq!(
+            json.pointer(pointer).and_then(|v| v.as_str()),
+            Some(expected),
+            "{pointer}"
+        );
+    }
+}
+
+#[test]
+fn test_inspect_and_convert_read_stdin() {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let pipe = |args: &[&str], input: &[u8]| {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_component"))
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("Failed to execute command");
+        child.stdin.take().unwrap().write_all(input).unwrap();
+        let output = child.wait_with_output().unwrap();
+        assert!(output.status.success(), "{output:?}");
+        output.stdout
+    };
+    let component = std::fs::read(library_fixture("library_wordmark.wasm")).unwrap();
+
+    let text = pipe(&["inspect", "-", "--exports"], &component);
+    let text = String::from_utf8(text).unwrap();
+    assert!(text.contains("to-word"), "{text}");
+
+    let minimal = std::fs::read(library_fixture("minimal_component.wasm")).unwrap();
+    let wat = pipe(&["convert", "wat", "-"], &minimal);
+    let wasm = pipe(&["convert", "wasm", "-", "-o", "-"], &wat);
+    assert_eq!(wasm, minimal);
+}
+
+#[test]
+fn test_inspect_filters_imports_and_exports() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let mut resolve = wit_parser::Resolve::default();
+    resolve
+        .push_str(
+            "dep.wit",
+            "package test:dep@1.0.0;\ninterface greeter { hello: func(name: string) -> string; }",
+        )
+        .unwrap();
+    let package = resolve
+        .push_str(
+            "app.wit",
+            "package test:app;\nworld app { import test:dep/greeter@1.0.0; export run: func(); }",
+        )
+        .unwrap();
+    let input = dir.path().join("app.wasm");
+    std::fs::write(&input, wit_component::encode(&resolve, package).unwrap()).unwrap();
+    let input = input.to_str().unwrap();
+
+    let output = run_cli_raw(&["inspect", input, "--imports"]);
+    assert!(output.status.success(), "{output:?}");
+    let text = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(
+        text,
+        "world app\n  import test:dep/greeter@1.0.0\n    hello: func(name: string) -> string\n"
+    );
+
+    let output = run_cli_raw(&["inspect", input, "--exports", "--json"]);
+    assert!(output.status.success(), "{output:?}");
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!([{
+            "world": "app",
+            "exports": [{
+                "name": "run",
+                "functions": [{ "name": "run", "signature": "func()" }],
+            }],
+        }])
+    );
+}
+
+#[test]
+fn test_inspect_reports_core_modules() {
+    let output = run_cli_raw(&["inspect", &library_fixture("core_module.wasm")]);
+    assert!(output.status.success(), "{output:?}");
+    let text = String::from_utf8(output.stdout).unwrap();
+    assert!(text.contains("core module"), "{text}");
+    assert!(!text.contains("component"), "{text}");
+    assert!(!text.contains("World"), "{text}");
+
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let module = wat::parse_str(
+        r#"(module
+            (import "env" "log" (func (param i32)))
+            (memory (export "memory") 1)
+            (func (export "add") (param i32 i32) (result i32)
+                local.get 0
+                local.get 1
+                i32.add))"#,
+    )
+    .unwrap();
+    let input = dir.path().join("module.wasm");
+    std::fs::write(&input, module).unwrap();
+    let input = input.to_str().unwrap();
+
+    let output = run_cli_raw(&["inspect", input]);
+    assert!(output.status.success(), "{output:?}");
+    let text = String::from_utf8(output.stdout).unwrap();
+    assert!(text.contains("env.log (func)"), "{text}");
+    assert!(text.contains("memory (memory)"), "{text}");
+    assert!(text.contains("add (func)"), "{text}");
+
+    let output = run_cli_raw(&["inspect", input, "--json"]);
+    assert!(output.status.success(), "{output:?}");
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "kind": "core module",
+            "imports": [{ "module": "env", "name": "log", "kind": "func" }],
+            "exports": [
+                { "name": "memory", "kind": "memory" },
+                { "name": "add", "kind": "func" },
+            ],
+        })
+    );
+}
+
+#[test]
+fn test_inspect_filters_core_module_imports_and_exports() {
+    let output = run_cli_raw(&["inspect", &library_fixture("core_module.wasm"), "--imports"]);
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "core module\n");
+
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let module = wat::parse_str(
+        r#"(module
+            (import "env" "log" (func (param i32)))
+            (func (export "run")))"#,
+    )
+    .unwrap();
+    let input = dir.path().join("module.wasm");
+    std::fs::write(&input, module).unwrap();
+    let input = input.to_str().unwrap();
+
+    let output = run_cli_raw(&["inspect", input, "--imports"]);
+    assert!(output.status.success(), "{output:?}");
+    let text = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(text, "core module\n  import env.log: func\n");
+
+    let output = run_cli_raw(&["inspect", input, "--exports", "--json"]);
+    assert!(output.status.success(), "{output:?}");
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "kind": "core module",
+            "exports": [{ "name": "run", "kind": "func" }],
+        })
+    );
+}
+
+#[test]
+fn test_inspect_metadata_lists_producers_and_custom_sections() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let input = dir.path().join("module.wasm");
+    let module = wat::parse_str(
+        r#"(module
+            (@producers (language "Rust" "") (processed-by "rustc" "1.90.0"))
+            (@custom "build-info" "abcdef")
+        )"#,
+    )
+    .unwrap();
+    std::fs::write(&input, module).unwrap();
+    let input = input.to_str().unwrap();
+
+    let output = run_cli_raw(&["inspect", input, "--metadata"]);
+    assert!(output.status.success(), "{output:?}");
+    let text = String::from_utf8(output.stdout).unwrap();
+    assert!(text.contains("rustc [1.90.0]"), "{text}");
+    assert!(text.contains("build-info"), "{text}");
+    assert!(text.contains("Custom section"), "{text}");
+
+    let output = run_cli_raw(&["inspect", input, "--metadata", "--json"]);
+    assert!(output.status.success(), "{output:?}");
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let sections = json
+        .get("custom_sections")
+        .and_then(|v| v.as_array())
+        .unwrap();
+    assert!(
+        sections.contains(&serde_json::json!({ "name": "build-info", "size": 6 })),
+        "{sections:?}"
+    );
+    assert!(
+        sections
+            .iter()
+            .any(|s| s.get("name") == Some(&"producers".into())),
+        "{sections:?}"
+    );
+}
+
+#[test]
+fn test_inspect_size_profiles_sections_and_imports() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let input = dir.path().join("module.wasm");
+    let module = wat::parse_str(
+        r#"(module
+            (import "env" "a" (func))
+            (import "env" "b" (func))
+            (import "host" "c" (func))
+            (memory 1)
+            (data (i32.const 0) "0123456789012345678901234567890123456789")
+        )"#,
+    )
+    .unwrap();
+    std::fs::write(&input, &module).unwrap();
+    let input = input.to_str().unwrap();
+
+    let output = run_cli_raw(&["inspect", input, "--size"]);
+    assert!(output.status.success(), "{output:?}");
+    let text = String::from_utf8(output.stdout).unwrap();
+    assert!(text.contains("Section"), "{text}");
+    assert!(text.contains("Imported from"), "{text}");
+
+    let output = run_cli_raw(&["inspect", input, "--size", "--json"]);
+    assert!(output.status.success(), "{output:?}");
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json.get("size"), Some(&module.len().into()));
+    // The data section is the largest.
+    assert_eq!(
+        json.pointer("/sections/0/name").and_then(|v| v.as_str()),
+        Some("data")
+    );
+    assert_eq!(
+        json.get("imports"),
+        Some(&serde_json::json!([
+            { "module": "env", "count": 2 },
+            { "module": "host", "count": 1 },
+        ]))
+    );
+    assert_eq!(
+        json.pointer("/modules/0/kind").and_then(|v| v.as_str()),
+        Some("module")
+    );
+}
+
+#[test]
+fn test_inspect_offline_reads_references_from_the_store() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let output = run_cli_isolated(
+        dir.path(),
+        &["--offline", "inspect", "ghcr.io/example/missing:1.0.0"],
+    );
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("is not in the local store"), "{stderr}");
+}
+
+#[test]
+fn test_environment_overrides_the_data_dir_and_config() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let data_dir = dir.path().join("data");
+    let config = dir.path().join("ci.toml");
+    let self_ = |command: &str| {
+        let output = isolated_cli(dir.path())
+            .args(["--json", "self", command])
+            .env("WASM_DATA_DIR", &data_dir)
+            .env("WASM_CONFIG", &config)
+            .output()
+            .expect("Failed to execute command");
+        assert!(output.status.success(), "{output:?}");
+        serde_json::from_slice::<serde_json::Value>(&output.stdout).unwrap()
+    };
+
+    let state = self_("state");
+    assert_eq!(
+        state.pointer("/storage/data_dir").and_then(|v| v.as_str()),
+        data_dir.to_str()
+    );
+    assert!(data_dir.join("db").exists());
+    let config_json = self_("config");
+    assert_eq!(
+        config_json
+            .pointer("/global_config")
+            .and_then(|v| v.as_str()),
+        config.to_str()
+    );
+
+    let output = isolated_cli(dir.path())
+        .args(["registry", "discover", "ghcr.io/example"])
+        .env("WASM_DATA_DIR", &data_dir)
+        .env("WASM_OFFLINE", "1")
+        .output()
+        .expect("Failed to execute command");
+    assert_eq!(output.status.code(), Some(5), "{output:?}");
+}
+
+#[test]
+fn test_config_flag_takes_precedence_over_the_environment() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let config = dir.path().join("flag.toml");
+    std::fs::write(&config, "default-registry = \"registry.example.com\"\n").unwrap();
+    let component = |args: &[&str]| {
+        isolated_cli(dir.path())
+            .args(args)
+            .env("WASM_CONFIG", dir.path().join("env.toml"))
+            .output()
+            .expect("Failed to execute command")
+    };
+
+    let output = component(&[
+        "--json",
+        "--config",
+        config.to_str().unwrap(),
+        "self",
+        "config",
+    ]);
+    assert!(output.status.success(), "{output:?}");
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        json.pointer("/global_config").and_then(|v| v.as_str()),
+        config.to_str()
+    );
+
+    let missing = dir.path().join("missing.toml");
+    let output = component(&["--config", missing.to_str().unwrap(), "self", "config"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("does not exist"), "{stderr}");
+}
+
+#[test]
+fn test_self_cache_manages_an_empty_store() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let cache = |args: &[&str]| {
+        isolated_cli(dir.path())
+            .args(["self", "cache"])
+            .args(args)
+            .output()
+            .expect("Failed to execute command")
+    };
+
+    let output = cache(&["status"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Packages"), "{stdout}");
+    assert!(!stdout.contains('\t'), "{stdout}");
+
+    let output = isolated_cli(dir.path())
+        .args(["--format", "plain", "self", "cache", "status"])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.lines().any(|line| line == "Packages\t0"), "{stdout}");
+
+    let output = cache(&["evict", "--max-size", "0", "--dry-run"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Would evict 0 package(s)"), "{stdout}");
+
+    let output = cache(&["gc"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Removed 0 unused item(s)"), "{stdout}");
+
+    let output = cache(&["clean", "--yes"]);
+    assert!(output.status.success());
+
+    let output = cache(&["evict"]);
+    assert!(!output.status.success());
+}
+
+#[test]
+fn test_self_uninstall_lists_then_removes_state() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let config_dir = dir.path().join("wasm");
+    let uninstall = |args: &[&str]| {
+        isolated_cli(dir.path())
+            .args(["self", "uninstall"])
+            .args(args)
+            .env("XDG_DATA_HOME", dir.path().join("data"))
+            .env("XDG_STATE_HOME", dir.path().join("state"))
+            .output()
+            .expect("Failed to execute command")
+    };
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::write(config_dir.join("config.toml"), "").unwrap();
+
+    let output = uninstall(&["--dry-run"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains(&config_dir.display().to_string()),
+        "{stdout}"
+    );
+    assert!(config_dir.exists());
+
+    let output = uninstall(&["--yes"]);
+    assert!(output.status.success());
+    assert!(!config_dir.exists());
+    assert!(!dir.path().join("data").join("wasm").exists());
+    assert!(std::path::Path::new(env!("CARGO_BIN_EXE_component")).exists());
+}
+
+#[test]
+fn test_self_uninstall_keeps_the_parents_of_overridden_paths() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let dotfiles = dir.path().join("dotfiles");
+    let config = dotfiles.join("wasm.toml");
+    let data_dir = dir.path().join("cache");
+    std::fs::create_dir_all(&dotfiles).unwrap();
+    std::fs::write(&config, "").unwrap();
+    std::fs::write(dotfiles.join(".bashrc"), "").unwrap();
+    std::fs::create_dir_all(&data_dir).unwrap();
+    std::fs::write(data_dir.join("notes.txt"), "").unwrap();
+
+    let output = isolated_cli(dir.path())
+        .args([
+            "--config",
+            config.to_str().unwrap(),
+            "self",
+            "uninstall",
+            "--yes",
+        ])
+        .env("XDG_DATA_HOME", dir.path().join("data"))
+        .env("XDG_STATE_HOME", dir.path().join("state"))
+        .env("WASM_DATA_DIR", &data_dir)
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success(), "{output:?}");
+
+    assert!(!config.exists());
+    assert!(dotfiles.join(".bashrc").exists());
+    assert!(!data_dir.join("db").exists());
+    assert!(!data_dir.join("store").exists());
+    assert!(data_dir.join("notes.txt").exists());
+}
+
+#[test]
+fn test_inspect_reports_missing_wasm_files() {
+    let output = run_cli_raw(&["inspect", "does-not-exist.wasm"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("failed to read does-not-exist.wasm"),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn test_compose_plug_reports_unsatisfied_imports() {
+    let dir = TempDir::new().expect("Failed to create temp dir");
+    let socket = dir.path().join("app.wasm");
+    let socket_wat = r#"(component
+        (type $greet (instance (export "hello" (func))))
+        (import "test:demo/greet" (instance $g (type $greet)))
+        (type $log (instance (export "log" (func))))
+        (import "test:demo/log" (instance $l (type $log)))
+        (alias export $g "hello" (func $hello))
+        (core func $hello_core (canon lower (func $hello)))
+        (core module $m (import "host" "hello" (func)) (func (export "run")))
+        (core instance $ci (instantiate $m
+            (with "host" (instance (export "hello" (func $hello_core))))))
+        (func $run (canon lift (core func $ci "run")))
+        (export "run" (func $run))
+    )"#;
+    std::fs::write(&socket, wat::parse_str(socket_wat).unwrap()).unwrap();
+    let plug = dir.path().join("greeter.wasm");
+    let plug_wat = r#"(component
+        (core module $m (func (export "hello")))
+        (core instance $ci (instantiate $m))
+        (func $hello (canon lift (core func $ci "hello")))
+        (instance $inst (export "hello" (func $hello)))
+        (export "test:demo/greet" (instance $inst))
+    )"#;
+    std::fs::write(&plug, wat::parse_str(plug_wat).unwrap()).unwrap();
+
+    let out_dir = dir.path().join("build");
+    let output = run_cli_raw(&[
+        "compose",
+        "plug",
+        socket.to_str().unwrap(),
+        plug.to_str().unwrap(),
+        "--output",
+        out_dir.to_str().unwrap(),
+    ]);
+    assert!(output.status.success(), "{output:?}");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout.contains(&format!("Plugged test:demo/greet from {}", plug.display())),
+        "{stdout}"
+    );
+    let Some((_, unsatisfied)) = stdout.split_once("Unsatisfied imports") else {
+        panic!("{stdout}");
+    };
+    assert!(unsatisfied.contains("  test:demo/log\n"), "{unsatisfied}");
+    assert!(!unsatisfied.contains("test:demo/greet"), "{unsatisfied}");
+    let composed = std::fs::read(out_dir.join("app.wasm")).unwrap();
+    assert!(wasmparser::Parser::is_component(&composed));
+}
+
+#[test]
+fn test_compose_reports_missing_wac_files() {
+    let output = run_cli_error(&["compose", "missing/file.wac"], None);
+    assert!(
+        output.contains("WAC file 'missing/file.wac' not found"),
+        "{output}"
+    );
+}
+
 // =============================================================================
 // Compose Init Integration Tests
 // =============================================================================
@@ -49,6 +49,49 @@ pub const WELL_KNOWN_WASM_DIRS: &[&str] = &[
 /// Patterns to match within the target directory for wasm-specific subdirectories.
 const TARGET_WASM_PREFIXES: &[&str] = &["wasm32-"];
 
+/// What kind of WebAssembly binary a file holds, going by its header.
+///
+/// # Example
+///
+/// ```
+/// use component_detector::WasmKind;
+///
+/// let header = b"\0asm\x0d\0\x01\0";
+/// assert_eq!(WasmKind::from_header(header), Some(WasmKind::Component));
+/// assert_eq!(WasmKind::Component.to_string(), "component");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WasmKind {
+    /// A component, as built for `wasm32-wasip2` or by `wasm-tools component new`.
+    Component,
+    /// A core module, as built for `wasm32-unknown-unknown` or `wasm32-wasip1`.
+    Module,
+}
+
+impl WasmKind {
+    /// The kind of binary that starts with `header`, or `None` if it isn't
+    /// a WebAssembly binary. Only the first 8 bytes are looked at.
+    // r[impl detector.kind]
+    #[must_use]
+    pub fn from_header(header: &[u8]) -> Option<Self> {
+        match header.get(..8)? {
+            // The layer field is 0 for core modules and 1 for components.
+            [0, b'a', b's', b'm', _, _, 0, 0] => Some(Self::Module),
+            [0, b'a', b's', b'm', _, _, 1, 0] => Some(Self::Component),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for WasmKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Component => f.pad("component"),
+            Self::Module => f.pad("module"),
+        }
+    }
+}
+
 /// A discovered WebAssembly file entry.
 ///
 /// # Example
@@ -115,6 +158,24 @@ impl WasmEntry {
         self.path.file_name().and_then(|s| s.to_str())
     }
 
+    /// Reads the file's header to tell components from core modules.
+    ///
+    /// Returns `None` for files that aren't WebAssembly binaries, such as
+    /// empty or truncated files.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be read.
+    pub fn kind(&self) -> std::io::Result<Option<WasmKind>> {
+        use std::io::Read;
+
+        let mut header = Vec::with_capacity(8);
+        std::fs::File::open(&self.path)?
+            .take(8)
+            .read_to_end(&mut header)?;
+        Ok(WasmKind::from_header(&header))
+    }
+
     /// Consumes the entry and returns the underlying path.
     ///
     /// # Example
@@ -205,11 +266,15 @@ impl WasmDetector {
         WasmDetectorIter::new(self)
     }
 
-    /// Find all well-known wasm directories that exist in the root.
+    /// Find all well-known wasm directories that exist in the root: the
+    /// build outputs in `pkg/`, `dist/`, and `target/wasm32-*/`.
+    ///
+    /// These are scanned even when `.gitignore` excludes them.
     // r[impl detector.target-dir]
     // r[impl detector.pkg-dir]
     // r[impl detector.dist-dir]
-    fn find_well_known_dirs(&self) -> Vec<PathBuf> {
+    #[must_use]
+    pub fn well_known_dirs(&self) -> Vec<PathBuf> {
         let mut dirs = Vec::new();
 
         // Check for pkg/ and dist/ directories
@@ -320,7 +385,7 @@ impl WasmDetectorIter {
             .build();
 
         // Build walkers for well-known directories (ignoring gitignore)
-        let well_known_dirs = detector.find_well_known_dirs();
+        let well_known_dirs = detector.well_known_dirs();
         let well_known_walkers: Vec<_> = well_known_dirs
             .into_iter()
             .map(|dir| {
@@ -1,6 +1,6 @@
 //! Integration tests for the component-detector crate.
 
-use component_detector::WasmDetector;
+use component_detector::{WasmDetector, WasmEntry, WasmKind};
 use std::fs::{self, File};
 use tempfile::TempDir;
 
@@ -210,3 +210,36 @@ fn test_detector_empty_directory() {
         "Empty directory should yield no results"
     );
 }
+
+// r[verify detector.kind]
+#[test]
+fn test_wasm_entry_kind() {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let root = temp_dir.path();
+    fs::write(root.join("component.wasm"), b"\0asm\x0d\0\x01\0").unwrap();
+    fs::write(root.join("module.wasm"), b"\0asm\x01\0\0\0").unwrap();
+    fs::write(root.join("empty.wasm"), b"").unwrap();
+
+    let kind = |name: &str| WasmEntry::new(root.join(name)).kind().unwrap();
+    assert_eq!(kind("component.wasm"), Some(WasmKind::Component));
+    assert_eq!(kind("module.wasm"), Some(WasmKind::Module));
+    assert_eq!(kind("empty.wasm"), None);
+    assert!(WasmEntry::new(root.join("missing.wasm")).kind().is_err());
+}
+
+#[test]
+fn test_well_known_dirs() {
+    let temp_dir = setup_test_dir();
+    let root = temp_dir.path();
+    let mut dirs = WasmDetector::new(root).well_known_dirs();
+    dirs.sort();
+    assert_eq!(
+        dirs,
+        [
+            root.join("dist"),
+            root.join("pkg"),
+            root.join("target/wasm32-unknown-unknown"),
+            root.join("target/wasm32-wasip2"),
+        ]
+    );
+}
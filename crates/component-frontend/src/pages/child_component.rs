@@ -108,6 +108,7 @@ mod tests {
             wit_namespace: Some("example".to_string()),
             wit_name: Some("pkg".to_string()),
             dependencies: vec![],
+            pinned: false,
         }
     }
 
@@ -455,6 +455,7 @@ mod tests {
             wit_namespace: Some(ns.into()),
             wit_name: Some(name.into()),
             dependencies: vec![],
+            pinned: false,
         }
     }
 
@@ -550,6 +550,7 @@ mod tests {
                 package: "wasi:io".to_string(),
                 version: Some("0.2.0".to_string()),
             }],
+            pinned: false,
         }
     }
 
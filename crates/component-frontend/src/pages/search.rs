@@ -128,6 +128,7 @@ mod tests {
             wit_namespace: None,
             wit_name: None,
             dependencies: vec![],
+            pinned: false,
         }
     }
 
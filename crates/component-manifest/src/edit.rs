@@ -0,0 +1,373 @@
+//! In-place editing of the manifest file (`wasm.toml`).
+
+use crate::{Dependency, PackageType};
+use miette::Diagnostic;
+use serde::Serialize;
+use toml_edit::{DocumentMut, Item, Table};
+
+/// A manifest that can be edited without losing its comments, key order, or
+/// formatting.
+///
+/// Deserializing into a [`Manifest`](crate::Manifest) and serializing it
+/// back drops everything TOML doesn't carry as data. Commands that change a
+/// user's `wasm.toml` edit it through this type instead.
+///
+/// # Example
+///
+/// ```rust
+/// use component_manifest::{Dependency, ManifestDocument, PackageType};
+///
+/// let mut doc: ManifestDocument = r#"
+/// [dependencies.interfaces] # used by the app
+/// "wasi:logging" = "1.0.0" # keep pinned
+/// "#
+/// .parse()
+/// .unwrap();
+///
+/// doc.insert_dependency(
+///     &PackageType::Interface,
+///     "wasi:clocks",
+///     &Dependency::Compact("0.2.5".to_string()),
+/// )
+/// .unwrap();
+///
+/// let edited = doc.to_string();
+/// assert!(edited.contains("[dependencies.interfaces] # used by the app"));
+/// assert!(edited.contains("\"wasi:logging\" = \"1.0.0\" # keep pinned"));
+/// assert!(edited.contains("\"wasi:clocks\" = \"0.2.5\""));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ManifestDocument {
+    doc: DocumentMut,
+}
+
+/// Errors produced when editing a [`ManifestDocument`].
+///
+/// Each variant carries a stable [diagnostic error code][miette::Diagnostic::code]
+/// that uniquely identifies the failure.
+#[derive(Debug, Clone, PartialEq, Eq, Diagnostic)]
+#[must_use]
+pub enum EditError {
+    /// The manifest is not valid TOML.
+    #[diagnostic(
+        code(component::manifest::invalid_toml),
+        help("fix the syntax error in `wasm.toml` and try again")
+    )]
+    InvalidToml {
+        /// The parse error message.
+        reason: String,
+    },
+    /// A key that should hold a table of dependencies holds something else.
+    #[diagnostic(
+        code(component::manifest::not_a_table),
+        help("`{key}` must be a table, such as `[{key}]`")
+    )]
+    NotATable {
+        /// The dotted key of the offending value.
+        key: String,
+    },
+}
+
+impl std::fmt::Display for EditError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EditError::InvalidToml { reason } => write!(f, "invalid manifest: {reason}"),
+            EditError::NotATable { key } => write!(f, "`{key}` in the manifest is not a table"),
+        }
+    }
+}
+
+impl std::error::Error for EditError {}
+
+impl std::str::FromStr for ManifestDocument {
+    type Err = EditError;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        let doc = source
+            .parse::<DocumentMut>()
+            .map_err(|err| EditError::InvalidToml {
+                reason: err.message().to_string(),
+            })?;
+        Ok(Self { doc })
+    }
+}
+
+impl std::fmt::Display for ManifestDocument {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.doc.fmt(f)
+    }
+}
+
+impl ManifestDocument {
+    /// Add `dependency` as `name` to the components or interfaces section,
+    /// creating the section if needed.
+    ///
+    /// An entry with the same name is replaced, in either section. When the
+    /// old and new entries are both tables, the old table's other keys (such
+    /// as `permissions`) and its formatting are kept.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`EditError::NotATable`] if `dependencies` or the section is
+    /// not a table.
+    pub fn insert_dependency(
+        &mut self,
+        kind: &PackageType,
+        name: &str,
+        dependency: &Dependency,
+    ) -> Result<(), EditError> {
+        let item = dependency_item(dependency);
+        let other = match kind {
+            PackageType::Component => PackageType::Interface,
+            PackageType::Interface => PackageType::Component,
+        };
+        if let Some(section) = self.section(section_name(&other), false)? {
+            section.remove(name);
+        }
+        let section =
+            self.section(section_name(kind), true)?
+                .ok_or_else(|| EditError::NotATable {
+                    key: format!("dependencies.{}", section_name(kind)),
+                })?;
+
+        match (section.get_mut(name), item.as_table_like()) {
+            (Some(existing), Some(fields)) if existing.is_table_like() => {
+                if let Some(existing) = existing.as_table_like_mut() {
+                    for (key, value) in fields.iter() {
+                        existing.insert(key, value.clone());
+                    }
+                }
+            }
+            _ => {
+                section.insert(name, item);
+            }
+        }
+        Ok(())
+    }
+
+    /// Remove the dependency `name` from whichever section holds it, and
+    /// return that section's package type.
+    ///
+    /// Returns `Ok(None)` if the manifest has no such dependency. The
+    /// sections are kept even when they end up empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`EditError::NotATable`] if `dependencies` or one of its
+    /// sections is not a table.
+    pub fn remove_dependency(&mut self, name: &str) -> Result<Option<PackageType>, EditError> {
+        for kind in [PackageType::Component, PackageType::Interface] {
+            if let Some(section) = self.section(section_name(&kind), false)?
+                && section.remove(name).is_some()
+            {
+                return Ok(Some(kind));
+            }
+        }
+        Ok(None)
+    }
+
+    /// The `dependencies.<name>` table, created when `create` is set.
+    fn section(
+        &mut self,
+        name: &str,
+        create: bool,
+    ) -> Result<Option<&mut dyn toml_edit::TableLike>, EditError> {
+        if create && !self.doc.contains_key("dependencies") {
+            let mut dependencies = Table::new();
+            dependencies.set_implicit(true);
+            self.doc.insert("dependencies", Item::Table(dependencies));
+        }
+        let Some(dependencies) = self.doc.get_mut("dependencies") else {
+            return Ok(None);
+        };
+        let dependencies =
+            dependencies
+                .as_table_like_mut()
+                .ok_or_else(|| EditError::NotATable {
+                    key: "dependencies".to_string(),
+                })?;
+        if create && !dependencies.contains_key(name) {
+            dependencies.insert(name, Item::Table(Table::new()));
+        }
+        match dependencies.get_mut(name) {
+            Some(section) => {
+                section
+                    .as_table_like_mut()
+                    .map(Some)
+                    .ok_or_else(|| EditError::NotATable {
+                        key: format!("dependencies.{name}"),
+                    })
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+/// The key of the dependency section for `kind`.
+fn section_name(kind: &PackageType) -> &'static str {
+    match kind {
+        PackageType::Component => "components",
+        PackageType::Interface => "interfaces",
+    }
+}
+
+/// A dependency as a TOML value: a string for the compact form, and an
+/// inline table for the explicit form.
+fn dependency_item(dependency: &Dependency) -> Item {
+    #[derive(Serialize)]
+    struct Wrapper<'a> {
+        dependency: &'a Dependency,
+    }
+    let item = toml::to_string(&Wrapper { dependency })
+        .ok()
+        .and_then(|source| source.parse::<DocumentMut>().ok())
+        .and_then(|mut doc| doc.remove("dependency"));
+    match item {
+        Some(Item::Table(table)) => {
+            Item::Value(toml_edit::Value::InlineTable(table.into_inline_table()))
+        }
+        Some(item) => item,
+        None => toml_edit::value(dependency.version()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Manifest;
+
+    fn explicit(version: &str) -> Dependency {
+        Dependency::Explicit {
+            registry: "ghcr.io".to_string(),
+            namespace: "webassembly".to_string(),
+            package: "wasi-logging".to_string(),
+            version: version.to_string(),
+            permissions: None,
+        }
+    }
+
+    #[test]
+    fn insert_creates_sections_in_an_empty_manifest() {
+        let mut doc: ManifestDocument = "".parse().unwrap();
+        doc.insert_dependency(
+            &PackageType::Component,
+            "root:app",
+            &Dependency::Compact("0.1.0".to_string()),
+        )
+        .unwrap();
+        doc.insert_dependency(&PackageType::Interface, "wasi:logging", &explicit("1.0.0"))
+            .unwrap();
+
+        let edited = doc.to_string();
+        assert!(!edited.contains("[dependencies]\n"), "{edited}");
+        let manifest: Manifest = toml::from_str(&edited).unwrap();
+        assert_eq!(
+            manifest.dependencies.components.get("root:app"),
+            Some(&Dependency::Compact("0.1.0".to_string()))
+        );
+        assert_eq!(
+            manifest.dependencies.interfaces.get("wasi:logging"),
+            Some(&explicit("1.0.0"))
+        );
+    }
+
+    #[test]
+    fn insert_updates_tables_in_place() {
+        let mut doc: ManifestDocument = r#"
+[package]
+name = "example:app" # the app
+
+# Logging
+[dependencies.interfaces."wasi:logging"]
+registry = "ghcr.io"
+namespace = "webassembly"
+package = "wasi-logging"
+version = "0.9.0"
+permissions = { inherit-env = true }
+"#
+        .parse()
+        .unwrap();
+        doc.insert_dependency(&PackageType::Interface, "wasi:logging", &explicit("1.0.0"))
+            .unwrap();
+
+        let edited = doc.to_string();
+        assert!(
+            edited.contains("name = \"example:app\" # the app"),
+            "{edited}"
+        );
+        assert!(edited.contains("# Logging\n[dependencies.interfaces.\"wasi:logging\"]"));
+        assert!(edited.contains("version = \"1.0.0\""));
+        assert!(edited.contains("permissions = { inherit-env = true }"));
+    }
+
+    #[test]
+    fn insert_moves_an_entry_between_sections() {
+        let mut doc: ManifestDocument = "[dependencies.interfaces]\n\"example:app\" = \"1.0.0\"\n"
+            .parse()
+            .unwrap();
+        doc.insert_dependency(
+            &PackageType::Component,
+            "example:app",
+            &Dependency::Compact("1.1.0".to_string()),
+        )
+        .unwrap();
+
+        let manifest: Manifest = toml::from_str(&doc.to_string()).unwrap();
+        assert!(manifest.dependencies.interfaces.is_empty());
+        assert_eq!(
+            manifest.dependencies.components.get("example:app"),
+            Some(&Dependency::Compact("1.1.0".to_string()))
+        );
+    }
+
+    #[test]
+    fn remove_keeps_the_rest_of_the_manifest() {
+        let mut doc: ManifestDocument = r#"
+[dependencies.components]
+"root:app" = "0.1.0" # the app
+
+# Logging
+[dependencies.interfaces."wasi:logging"]
+registry = "ghcr.io"
+namespace = "webassembly"
+package = "wasi-logging"
+version = "1.0.0"
+"#
+        .parse()
+        .unwrap();
+
+        assert_eq!(
+            doc.remove_dependency("wasi:logging"),
+            Ok(Some(PackageType::Interface))
+        );
+        assert_eq!(doc.remove_dependency("wasi:logging"), Ok(None));
+
+        let edited = doc.to_string();
+        assert!(
+            edited.contains("\"root:app\" = \"0.1.0\" # the app"),
+            "{edited}"
+        );
+        assert!(!edited.contains("wasi-logging"), "{edited}");
+        let manifest: Manifest = toml::from_str(&edited).unwrap();
+        assert!(manifest.dependencies.interfaces.is_empty());
+    }
+
+    #[test]
+    fn invalid_manifests_are_rejected() {
+        assert!(matches!(
+            "[dependencies".parse::<ManifestDocument>(),
+            Err(EditError::InvalidToml { .. })
+        ));
+        let mut doc: ManifestDocument = "dependencies = 3\n".parse().unwrap();
+        assert_eq!(
+            doc.insert_dependency(
+                &PackageType::Interface,
+                "wasi:logging",
+                &Dependency::Compact("1.0.0".to_string()),
+            ),
+            Err(EditError::NotATable {
+                key: "dependencies".to_string()
+            })
+        );
+    }
+}
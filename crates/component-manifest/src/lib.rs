@@ -38,14 +38,16 @@
 #![deny(missing_debug_implementations)]
 #![warn(missing_docs)]
 
+mod edit;
 mod lockfile;
 mod manifest;
 mod package;
 mod permissions;
 mod validation;
 
+pub use edit::{EditError, ManifestDocument};
 pub use lockfile::{LOCKFILE_VERSION, LockedPackage, Lockfile, PackageDependency};
 pub use manifest::{Dependencies, Dependency, Manifest, PackageType};
 pub use package::{Package, PackageError, PackageKind};
 pub use permissions::{ResolvedPermissions, RunPermissions};
-pub use validation::{ValidationError, validate};
+pub use validation::{ValidationError, validate, validate_digests};
@@ -1,6 +1,6 @@
 //! Types for the WASM lockfile (`wasm.lock`).
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use serde::{Deserialize, Serialize};
 
@@ -140,6 +140,152 @@ impl Lockfile {
             });
         }
     }
+
+    /// The locked packages that `package` depends on, matched by
+    /// `(name, version)`. Dependencies that aren't locked are left out.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use component_manifest::Lockfile;
+    ///
+    /// let toml = r#"
+    /// lockfile_version = 3
+    ///
+    /// [[interfaces]]
+    /// name = "wasi:key-value"
+    /// version = "2.0.0"
+    /// registry = "ghcr.io/webassembly/wasi-key-value"
+    /// digest = "sha256:def456"
+    ///
+    /// [[interfaces.dependencies]]
+    /// name = "wasi:logging"
+    /// version = "1.0.0"
+    ///
+    /// [[interfaces]]
+    /// name = "wasi:logging"
+    /// version = "1.0.0"
+    /// registry = "ghcr.io/webassembly/wasi-logging"
+    /// digest = "sha256:abc123"
+    /// "#;
+    ///
+    /// let lockfile: Lockfile = toml::from_str(toml).unwrap();
+    /// let key_value = &lockfile.interfaces[0];
+    /// let logging = &lockfile.interfaces[1];
+    /// assert_eq!(lockfile.dependencies_of(key_value), [logging]);
+    /// assert_eq!(lockfile.dependents_of(logging), [key_value]);
+    /// ```
+    #[must_use]
+    pub fn dependencies_of(&self, package: &LockedPackage) -> Vec<&LockedPackage> {
+        package
+            .dependencies
+            .iter()
+            .filter_map(|dep| {
+                self.components
+                    .iter()
+                    .chain(self.interfaces.iter())
+                    .find(|p| p.name == dep.name && p.version == dep.version)
+            })
+            .collect()
+    }
+
+    /// The locked packages that depend on `package`, the reverse of
+    /// [`dependencies_of`](Self::dependencies_of).
+    #[must_use]
+    pub fn dependents_of(&self, package: &LockedPackage) -> Vec<&LockedPackage> {
+        self.components
+            .iter()
+            .chain(self.interfaces.iter())
+            .filter(|p| {
+                p.dependencies
+                    .iter()
+                    .any(|dep| dep.name == package.name && dep.version == package.version)
+            })
+            .collect()
+    }
+
+    /// Remove every package that is neither named in `roots` nor a
+    /// dependency, directly or transitively, of a package that is.
+    ///
+    /// Roots are matched by name alone; dependencies by `(name, version)`.
+    /// Returns the removed packages.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use component_manifest::Lockfile;
+    ///
+    /// let toml = r#"
+    /// lockfile_version = 3
+    ///
+    /// [[interfaces]]
+    /// name = "wasi:key-value"
+    /// version = "2.0.0"
+    /// registry = "ghcr.io/webassembly/wasi-key-value"
+    /// digest = "sha256:def456"
+    ///
+    /// [[interfaces.dependencies]]
+    /// name = "wasi:logging"
+    /// version = "1.0.0"
+    ///
+    /// [[interfaces]]
+    /// name = "wasi:logging"
+    /// version = "1.0.0"
+    /// registry = "ghcr.io/webassembly/wasi-logging"
+    /// digest = "sha256:abc123"
+    ///
+    /// [[interfaces]]
+    /// name = "wasi:clocks"
+    /// version = "0.2.5"
+    /// registry = "ghcr.io/webassembly/wasi/clocks"
+    /// digest = "sha256:iface456"
+    /// "#;
+    ///
+    /// let mut lockfile: Lockfile = toml::from_str(toml).unwrap();
+    /// let removed = lockfile.prune_unreachable(["wasi:key-value"]);
+    /// assert_eq!(removed.len(), 1);
+    /// assert_eq!(removed[0].name, "wasi:clocks");
+    /// assert_eq!(lockfile.interfaces.len(), 2);
+    /// ```
+    pub fn prune_unreachable<'a>(
+        &mut self,
+        roots: impl IntoIterator<Item = &'a str>,
+    ) -> Vec<LockedPackage> {
+        let roots: HashSet<&str> = roots.into_iter().collect();
+        let mut reachable: HashSet<(String, String)> = HashSet::new();
+        let mut queue: Vec<(String, String)> = self
+            .components
+            .iter()
+            .chain(self.interfaces.iter())
+            .filter(|pkg| roots.contains(pkg.name.as_str()))
+            .map(|pkg| (pkg.name.clone(), pkg.version.clone()))
+            .collect();
+
+        while let Some(key) = queue.pop() {
+            if !reachable.insert(key.clone()) {
+                continue;
+            }
+            for pkg in self.components.iter().chain(self.interfaces.iter()) {
+                if pkg.name == key.0 && pkg.version == key.1 {
+                    queue.extend(
+                        pkg.dependencies
+                            .iter()
+                            .map(|dep| (dep.name.clone(), dep.version.clone())),
+                    );
+                }
+            }
+        }
+
+        let mut removed = Vec::new();
+        for packages in [&mut self.components, &mut self.interfaces] {
+            let (kept, dropped): (Vec<_>, Vec<_>) = std::mem::take(packages)
+                .into_iter()
+                .partition(|pkg| reachable.contains(&(pkg.name.clone(), pkg.version.clone())));
+            *packages = kept;
+            removed.extend(dropped);
+        }
+        removed
+    }
 }
 
 /// A resolved package entry in the lockfile.
@@ -546,4 +692,45 @@ mod tests {
             "unresolved deps should be stripped"
         );
     }
+
+    #[test]
+    fn test_prune_unreachable_follows_dependencies_across_sections() {
+        let locked = |name: &str, version: &str, deps: &[(&str, &str)]| LockedPackage {
+            name: name.to_string(),
+            version: version.to_string(),
+            registry: format!("ghcr.io/example/{name}"),
+            digest: "sha256:abc123".to_string(),
+            dependencies: deps
+                .iter()
+                .map(|(name, version)| PackageDependency {
+                    name: (*name).to_string(),
+                    version: (*version).to_string(),
+                    registry: String::new(),
+                    digest: String::new(),
+                })
+                .collect(),
+        };
+        let mut lockfile = Lockfile {
+            lockfile_version: 3,
+            components: vec![
+                locked("root:app", "0.1.0", &[("wasi:http", "0.2.0")]),
+                locked("root:old", "0.1.0", &[("wasi:io", "0.2.0")]),
+            ],
+            interfaces: vec![
+                locked("wasi:http", "0.2.0", &[("wasi:io", "0.2.1")]),
+                locked("wasi:io", "0.2.1", &[]),
+                locked("wasi:io", "0.2.0", &[]),
+            ],
+        };
+
+        let removed = lockfile.prune_unreachable(["root:app"]);
+
+        let removed: Vec<_> = removed
+            .iter()
+            .map(|pkg| (pkg.name.as_str(), pkg.version.as_str()))
+            .collect();
+        assert_eq!(removed, [("root:old", "0.1.0"), ("wasi:io", "0.2.0")]);
+        assert_eq!(lockfile.components.len(), 1);
+        assert_eq!(lockfile.interfaces.len(), 2);
+    }
 }
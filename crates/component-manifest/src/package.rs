@@ -121,6 +121,11 @@ pub struct Package {
     /// [`Package::artifact_path`] resolves it to `wit` at use time.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub wit: Option<PathBuf>,
+    /// The WIT world a component targets, such as `wasi:http/proxy`. Set
+    /// by `component init --world` as a record for bindings generators; it
+    /// isn't used when publishing.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub world: Option<String>,
     /// Human-readable short description of the package.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
@@ -316,6 +321,7 @@ mod tests {
             kind: PackageKind::Component,
             file: None,
             wit: Some(PathBuf::from("wit")),
+            world: None,
             description: None,
             source: None,
             homepage: None,
@@ -336,6 +342,7 @@ mod tests {
             kind: PackageKind::Interface,
             file: Some(PathBuf::from("x.wasm")),
             wit: None,
+            world: None,
             description: None,
             source: None,
             homepage: None,
@@ -356,6 +363,7 @@ mod tests {
             kind: PackageKind::Component,
             file: None,
             wit: None,
+            world: None,
             description: None,
             source: None,
             homepage: None,
@@ -379,6 +387,7 @@ mod tests {
             kind: PackageKind::Component,
             file: None,
             wit: None,
+            world: None,
             description: None,
             source: None,
             homepage: None,
@@ -399,6 +408,7 @@ mod tests {
             kind: PackageKind::Component,
             file: None,
             wit: None,
+            world: None,
             description: None,
             source: None,
             homepage: None,
@@ -419,6 +429,7 @@ mod tests {
             kind: PackageKind::Component,
             file: None,
             wit: None,
+            world: None,
             description: None,
             source: None,
             homepage: None,
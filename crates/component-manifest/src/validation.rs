@@ -51,6 +51,32 @@ pub enum ValidationError {
         /// The name of the missing package.
         name: String,
     },
+    /// A dependency in the manifest has no entry in the lockfile.
+    ///
+    /// See spec: `r[validation.unlocked-dependency]`
+    #[diagnostic(
+        code(component::validation::unlocked_dependency),
+        help("install the manifest's dependencies to lock '{name}'")
+    )]
+    UnlockedDependency {
+        /// The name of the unlocked dependency.
+        name: String,
+    },
+    /// A locked package's digest is not a well-formed content digest.
+    ///
+    /// See spec: `r[validation.invalid-digest]`
+    #[diagnostic(
+        code(component::validation::invalid_digest),
+        help("lock '{name}' again to record the digest the registry reports")
+    )]
+    InvalidDigest {
+        /// The locked package with the invalid digest.
+        name: String,
+        /// The digest as written in the lockfile.
+        digest: String,
+        /// Why the digest is invalid.
+        reason: String,
+    },
     /// A package dependency references a package that doesn't exist in the lockfile.
     ///
     /// See spec: `r[validation.invalid-dependency]`
@@ -110,6 +136,22 @@ impl std::fmt::Display for ValidationError {
                     "Package '{name}' is in the lockfile but not in the manifest",
                 )
             }
+            ValidationError::UnlockedDependency { name } => {
+                write!(
+                    f,
+                    "Dependency '{name}' is in the manifest but not in the lockfile",
+                )
+            }
+            ValidationError::InvalidDigest {
+                name,
+                digest,
+                reason,
+            } => {
+                write!(
+                    f,
+                    "Package '{name}' has invalid digest '{digest}': {reason}"
+                )
+            }
             ValidationError::InvalidDependency {
                 package,
                 dependency,
@@ -150,9 +192,12 @@ impl std::error::Error for ValidationError {}
 /// This function checks that:
 /// - All version strings in the manifest are valid semver requirements
 /// - There are no conflicting version constraints for the same package across sections
-/// - All packages in the lockfile have corresponding entries in the manifest
+/// - All dependencies in the manifest are locked
+/// - All packages in the lockfile are in the manifest, or are needed by one that is
 /// - All package dependencies reference packages that exist in the lockfile
 ///
+/// Digests are checked separately by [`validate_digests`].
+///
 /// # Example
 ///
 /// ```rust
@@ -206,9 +251,36 @@ pub fn validate(manifest: &Manifest, lockfile: &Lockfile) -> Result<(), Vec<Vali
         .map(|(p, _)| p.name.as_str())
         .collect();
 
-    // Check that all packages in the lockfile exist in the manifest
+    // r[impl validation.unlocked-dependency]
+    // Check that every manifest dependency has been locked
+    let mut unlocked: Vec<&str> = manifest_deps
+        .iter()
+        .copied()
+        .filter(|name| !lockfile_packages.contains(name))
+        .collect();
+    unlocked.sort_unstable();
+    for name in unlocked {
+        errors.push(ValidationError::UnlockedDependency {
+            name: name.to_string(),
+        });
+    }
+
+    // Packages the manifest needs, directly or through another package
+    let mut needed = manifest_deps.clone();
+    let mut queue: Vec<&str> = manifest_deps.iter().copied().collect();
+    while let Some(name) = queue.pop() {
+        for (package, _) in lockfile.all_packages().filter(|(p, _)| p.name == name) {
+            for dep in &package.dependencies {
+                if needed.insert(dep.name.as_str()) {
+                    queue.push(dep.name.as_str());
+                }
+            }
+        }
+    }
+
+    // Check that all packages in the lockfile are needed by the manifest
     for (package, _pkg_type) in lockfile.all_packages() {
-        if !manifest_deps.contains(package.name.as_str()) {
+        if !needed.contains(package.name.as_str()) {
             errors.push(ValidationError::MissingDependency {
                 name: package.name.clone(),
             });
@@ -232,6 +304,87 @@ pub fn validate(manifest: &Manifest, lockfile: &Lockfile) -> Result<(), Vec<Vali
     }
 }
 
+/// Validates the digest of every package in a lockfile.
+///
+/// Digests must have the OCI `algorithm:encoded` form, and `sha256` and
+/// `sha512` digests must be lowercase hex of the right length.
+///
+/// # Example
+///
+/// ```rust
+/// use component_manifest::{Lockfile, LockedPackage, validate_digests};
+///
+/// let mut lockfile = Lockfile::default();
+/// lockfile.interfaces.push(LockedPackage {
+///     name: "wasi:logging".to_string(),
+///     version: "1.0.0".to_string(),
+///     registry: "ghcr.io/webassembly/wasi-logging".to_string(),
+///     digest: format!("sha256:{}", "a".repeat(64)),
+///     dependencies: vec![],
+/// });
+/// assert!(validate_digests(&lockfile).is_ok());
+///
+/// lockfile.interfaces[0].digest = "sha256:abc123".to_string();
+/// assert!(validate_digests(&lockfile).is_err());
+/// ```
+///
+/// # Errors
+///
+/// Returns a [`ValidationError::InvalidDigest`] for every malformed digest.
+// r[impl validation.invalid-digest]
+pub fn validate_digests(lockfile: &Lockfile) -> Result<(), Vec<ValidationError>> {
+    let errors: Vec<ValidationError> = lockfile
+        .all_packages()
+        .filter_map(|(package, _)| {
+            let reason = digest_problem(&package.digest)?;
+            Some(ValidationError::InvalidDigest {
+                name: package.name.clone(),
+                digest: package.digest.clone(),
+                reason: reason.to_string(),
+            })
+        })
+        .collect();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// What's wrong with `digest`, if anything.
+fn digest_problem(digest: &str) -> Option<&'static str> {
+    if digest.is_empty() {
+        return Some("the package was locked without a digest");
+    }
+    let Some((algorithm, encoded)) = digest.split_once(':') else {
+        return Some("expected `algorithm:encoded`");
+    };
+    let valid_algorithm = !algorithm.is_empty()
+        && algorithm
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || "+._-".contains(c));
+    if !valid_algorithm {
+        return Some("the algorithm must be lowercase letters and digits");
+    }
+    let is_hex = |s: &str| s.chars().all(|c| matches!(c, '0'..='9' | 'a'..='f'));
+    match algorithm {
+        "sha256" if encoded.len() != 64 || !is_hex(encoded) => {
+            Some("sha256 digests are 64 lowercase hex characters")
+        }
+        "sha512" if encoded.len() != 128 || !is_hex(encoded) => {
+            Some("sha512 digests are 128 lowercase hex characters")
+        }
+        _ if encoded.is_empty()
+            || !encoded
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "=_-".contains(c)) =>
+        {
+            Some("the encoded part must be letters, digits, `=`, `_` or `-`")
+        }
+        _ => None,
+    }
+}
+
 /// Validate that all version strings in the manifest parse as valid semver
 /// requirements.
 fn validate_version_constraints(manifest: &Manifest, errors: &mut Vec<ValidationError>) {
@@ -617,6 +770,38 @@ mod tests {
             conflict.help().is_some(),
             "VersionConflict must have a help message"
         );
+
+        let unlocked = ValidationError::UnlockedDependency {
+            name: "test".to_string(),
+        };
+        assert_eq!(
+            unlocked
+                .code()
+                .expect("UnlockedDependency must have a diagnostic code")
+                .to_string(),
+            "component::validation::unlocked_dependency",
+        );
+        assert!(
+            unlocked.help().is_some(),
+            "UnlockedDependency must have a help message"
+        );
+
+        let digest = ValidationError::InvalidDigest {
+            name: "test".to_string(),
+            digest: "sha256:abc".to_string(),
+            reason: "too short".to_string(),
+        };
+        assert_eq!(
+            digest
+                .code()
+                .expect("InvalidDigest must have a diagnostic code")
+                .to_string(),
+            "component::validation::invalid_digest",
+        );
+        assert!(
+            digest.help().is_some(),
+            "InvalidDigest must have a help message"
+        );
     }
 
     // r[verify validation.invalid-version-constraint]
@@ -689,4 +874,113 @@ mod tests {
             ValidationError::VersionConflict { name, .. } if name == "test:pkg"
         )));
     }
+
+    // r[verify validation.unlocked-dependency]
+    #[test]
+    fn test_validate_unlocked_dependency() {
+        let mut interfaces = HashMap::new();
+        interfaces.insert(
+            "wasi:logging".to_string(),
+            Dependency::Compact("1.0.0".to_string()),
+        );
+        let manifest = Manifest {
+            package: None,
+            dependencies: Dependencies {
+                interfaces,
+                ..Default::default()
+            },
+        };
+
+        let errors = validate(&manifest, &Lockfile::default()).unwrap_err();
+        assert_eq!(
+            errors,
+            [ValidationError::UnlockedDependency {
+                name: "wasi:logging".to_string()
+            }]
+        );
+    }
+
+    // r[verify validation.missing-dependency]
+    #[test]
+    fn test_validate_transitive_dependency() {
+        let mut components = HashMap::new();
+        components.insert(
+            "root:component".to_string(),
+            Dependency::Compact("0.1.0".to_string()),
+        );
+        let manifest = Manifest {
+            package: None,
+            dependencies: Dependencies {
+                components,
+                ..Default::default()
+            },
+        };
+
+        let lockfile = Lockfile {
+            lockfile_version: 3,
+            components: vec![LockedPackage {
+                name: "root:component".to_string(),
+                version: "0.1.0".to_string(),
+                registry: "ghcr.io/example/component".to_string(),
+                digest: "sha256:comp123".to_string(),
+                dependencies: vec![PackageDependency {
+                    name: "wasi:logging".to_string(),
+                    version: "1.0.0".to_string(),
+                    registry: "ghcr.io/webassembly/wasi-logging".to_string(),
+                    digest: "sha256:abc123".to_string(),
+                }],
+            }],
+            interfaces: vec![LockedPackage {
+                name: "wasi:logging".to_string(),
+                version: "1.0.0".to_string(),
+                registry: "ghcr.io/webassembly/wasi-logging".to_string(),
+                digest: "sha256:abc123".to_string(),
+                dependencies: vec![],
+            }],
+        };
+
+        // wasi:logging isn't in the manifest, but root:component needs it.
+        assert!(validate(&manifest, &lockfile).is_ok());
+    }
+
+    // r[verify validation.invalid-digest]
+    #[test]
+    fn test_validate_digests() {
+        let locked = |digest: &str| LockedPackage {
+            name: "wasi:logging".to_string(),
+            version: "1.0.0".to_string(),
+            registry: "ghcr.io/webassembly/wasi-logging".to_string(),
+            digest: digest.to_string(),
+            dependencies: vec![],
+        };
+        let sha256 = format!("sha256:{}", "0123456789abcdef".repeat(4));
+        let sha512 = format!("sha512:{}", "0123456789abcdef".repeat(8));
+        let lockfile = Lockfile {
+            interfaces: vec![locked(&sha256), locked(&sha512)],
+            ..Default::default()
+        };
+        assert!(validate_digests(&lockfile).is_ok());
+
+        for digest in [
+            "",
+            "abc123",
+            "sha256:abc123",
+            &sha256.to_uppercase(),
+            "SHA256:abc",
+            "blake3:",
+        ] {
+            let lockfile = Lockfile {
+                interfaces: vec![locked(digest)],
+                ..Default::default()
+            };
+            let errors = validate_digests(&lockfile).unwrap_err();
+            assert!(
+                matches!(
+                    errors.as_slice(),
+                    [ValidationError::InvalidDigest { digest: d, .. }] if d == digest
+                ),
+                "{digest:?} should be invalid"
+            );
+        }
+    }
 }
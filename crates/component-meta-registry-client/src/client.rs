@@ -69,6 +69,7 @@ impl fmt::Display for ApiError {
 ///         wit_namespace: None,
 ///         wit_name: None,
 ///         dependencies: vec![],
+///         pinned: false,
 ///     }],
 ///     etag: Some("\"abc123\"".into()),
 /// };
@@ -66,6 +66,7 @@ mod tests {
             wit_namespace: None,
             wit_name: None,
             dependencies: vec![dep],
+            pinned: false,
         };
         assert_eq!(pkg.reference(), "ghcr.io/user/repo");
         assert_eq!(pkg.reference_with_tag(), "ghcr.io/user/repo:v1.0");
@@ -88,6 +88,7 @@ pub struct PackageDependencyRef {
 ///     wit_namespace: None,
 ///     wit_name: None,
 ///     dependencies: vec![],
+///     pinned: false,
 /// };
 ///
 /// assert_eq!(pkg.reference(), "ghcr.io/user/my-component");
@@ -131,6 +132,12 @@ pub struct KnownPackage {
     // r[impl client.known-package.dependencies]
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub dependencies: Vec<PackageDependencyRef>,
+    /// Whether the user pinned this package in their local store.
+    ///
+    /// Pinned packages are exempt from pruning and listed first. Always
+    /// `false` in meta-registry responses.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pinned: bool,
 }
 
 impl KnownPackage {
@@ -154,6 +161,7 @@ impl KnownPackage {
     ///     wit_namespace: None,
     ///     wit_name: None,
     ///     dependencies: vec![],
+    ///     pinned: false,
     /// };
     ///
     /// assert_eq!(pkg.reference(), "ghcr.io/user/repo");
@@ -186,6 +194,7 @@ impl KnownPackage {
     ///     wit_namespace: None,
     ///     wit_name: None,
     ///     dependencies: vec![],
+    ///     pinned: false,
     /// };
     ///
     /// assert_eq!(pkg.reference_with_tag(), "ghcr.io/user/repo:v1.0");
@@ -696,6 +705,7 @@ mod tests {
             wit_namespace: None,
             wit_name: None,
             dependencies: vec![],
+            pinned: false,
         };
         assert_eq!(pkg.reference(), "ghcr.io/user/repo");
     }
@@ -716,6 +726,7 @@ mod tests {
             wit_namespace: None,
             wit_name: None,
             dependencies: vec![],
+            pinned: false,
         };
         assert_eq!(pkg.reference_with_tag(), "ghcr.io/user/repo:v1.0");
     }
@@ -736,6 +747,7 @@ mod tests {
             wit_namespace: None,
             wit_name: None,
             dependencies: vec![],
+            pinned: false,
         };
         assert_eq!(pkg.reference_with_tag(), "ghcr.io/user/repo:latest");
     }
@@ -765,6 +777,7 @@ mod tests {
                     version: None,
                 },
             ],
+            pinned: false,
         };
 
         let json = serde_json::to_string(&pkg).unwrap();
@@ -792,6 +805,7 @@ mod tests {
             wit_namespace: None,
             wit_name: None,
             dependencies: vec![],
+            pinned: false,
         };
 
         let json = serde_json::to_string(&pkg).unwrap();
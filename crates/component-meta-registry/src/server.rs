@@ -85,6 +85,9 @@ pub struct ListParams {
     pub limit: u32,
 }
 
+/// Response header carrying the total number of matches across all pages.
+const TOTAL_COUNT_HEADER: &str = "x-total-count";
+
 fn default_limit() -> u32 {
     20
 }
@@ -167,8 +170,11 @@ async fn search(
     let manager = manager
         .lock()
         .map_err(|e| anyhow::anyhow!("lock poisoned: {e}"))?;
-    let packages = manager.search_packages(&params.q, params.offset, params.limit)?;
-    Ok(Json(packages))
+    let page = manager.search_packages_page(&params.q, params.offset, params.limit)?;
+    Ok((
+        [(TOTAL_COUNT_HEADER, page.total.to_string())],
+        Json(page.items),
+    ))
 }
 
 /// List all known packages.
@@ -179,8 +185,11 @@ async fn list_packages(
     let manager = manager
         .lock()
         .map_err(|e| anyhow::anyhow!("lock poisoned: {e}"))?;
-    let packages = manager.list_known_packages(params.offset, params.limit)?;
-    Ok(Json(packages))
+    let page = manager.list_known_packages_page(params.offset, params.limit)?;
+    Ok((
+        [(TOTAL_COUNT_HEADER, page.total.to_string())],
+        Json(page.items),
+    ))
 }
 
 /// List recently updated known packages.
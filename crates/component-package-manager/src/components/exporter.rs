@@ -0,0 +1,214 @@
+use rusqlite::Connection;
+
+use crate::storage::image_ref::latest_tag_sql;
+
+/// A stored component that exports a given WIT interface.
+///
+/// Returned by [`Manager::find_exporters`](crate::manager::Manager::find_exporters)
+/// to answer "which components implement `wasi:http/incoming-handler`?"
+/// when planning a composition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComponentExporter {
+    /// Registry hostname of the component, e.g. `ghcr.io`.
+    pub registry: String,
+    /// Repository path of the component, e.g. `example/http-server`.
+    pub repository: String,
+    /// Manifest digest of the exporting component.
+    pub digest: String,
+    /// Most recently updated tag pointing at the manifest, if any.
+    pub tag: Option<String>,
+    /// Component name from its embedded metadata, if present.
+    pub component_name: Option<String>,
+    /// The world whose exports include the interface.
+    pub world: String,
+    /// Declared package of the export, e.g. `wasi:http`.
+    pub package: String,
+    /// Declared interface within the package, e.g. `incoming-handler`.
+    /// `None` when the whole package is exported.
+    pub interface: Option<String>,
+    /// Declared version of the export, if any.
+    pub version: Option<String>,
+}
+
+impl ComponentExporter {
+    /// Find every stored component whose target world exports the given
+    /// interface.
+    ///
+    /// `interface` and `version` narrow the match when set; when unset, any
+    /// interface or version of `package` matches.
+    pub(crate) fn find(
+        conn: &Connection,
+        package: &str,
+        interface: Option<&str>,
+        version: Option<&str>,
+    ) -> anyhow::Result<Vec<Self>> {
+        let mut stmt = conn.prepare_cached(&format!(
+            "SELECT DISTINCT r.registry, r.repository, m.digest,
+                    {} AS tag,
+                    wc.name, ww.name,
+                    we.declared_package, we.declared_interface, we.declared_version
+             FROM wasm_component wc
+             JOIN component_target ct ON ct.wasm_component_id = wc.id
+             JOIN wit_world ww ON ww.id = ct.wit_world_id
+             JOIN wit_world_export we ON we.wit_world_id = ww.id
+             JOIN oci_manifest m ON m.id = wc.oci_manifest_id
+             JOIN oci_repository r ON r.id = m.oci_repository_id
+             WHERE we.declared_package = ?1
+               AND (?2 IS NULL OR we.declared_interface = ?2)
+               AND (?3 IS NULL OR we.declared_version = ?3)
+             ORDER BY r.registry ASC, r.repository ASC, tag DESC",
+            latest_tag_sql("r.id", "m.digest")
+        ))?;
+
+        let rows = stmt.query_map(rusqlite::params![package, interface, version], |row| {
+            Ok(Self {
+                registry: row.get(0)?,
+                repository: row.get(1)?,
+                digest: row.get(2)?,
+                tag: row.get(3)?,
+                component_name: row.get(4)?,
+                world: row.get(5)?,
+                package: row.get(6)?,
+                interface: row.get(7)?,
+                version: row.get(8)?,
+            })
+        })?;
+
+        let mut result = Vec::new();
+        for row in rows {
+            result.push(row?);
+        }
+        Ok(result)
+    }
+}
+
+/// Split an interface reference such as `wasi:http/incoming-handler@0.2.0`
+/// into its package, optional interface, and optional version.
+pub(crate) fn split_interface_ref(raw: &str) -> (&str, Option<&str>, Option<&str>) {
+    let (path, version) = match raw.rsplit_once('@') {
+        Some((path, version)) => (path, Some(version)),
+        None => (raw, None),
+    };
+    match path.split_once('/') {
+        Some((package, interface)) => (package, Some(interface), version),
+        None => (path, None, version),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::{ComponentTarget, WasmComponent};
+    use crate::oci::{OciManifest, OciRepository, OciTag};
+    use crate::storage::Migrations;
+    use crate::types::{RawWitPackage, WitWorld, WitWorldExport};
+    use std::collections::HashMap;
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        Migrations::run_all(&conn).unwrap();
+        conn
+    }
+
+    /// Store a component in `repository` whose `world` exports `exports`.
+    fn insert_component(conn: &Connection, repository: &str, exports: &[(&str, &str, &str)]) {
+        let repo_id = OciRepository::upsert(conn, "ghcr.io", repository).unwrap();
+        let digest = format!("sha256:{repository}");
+        let (mid, _) = OciManifest::upsert(
+            conn,
+            repo_id,
+            &digest,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            &HashMap::new(),
+        )
+        .unwrap();
+        OciTag::upsert(conn, repo_id, "1.0.0", &digest).unwrap();
+
+        let package_name = format!("root:{}", repository.replace('/', "-"));
+        let pkg_id =
+            RawWitPackage::insert(conn, &package_name, None, None, None, Some(mid), None).unwrap();
+        let world_id = WitWorld::insert(conn, pkg_id, "root", None).unwrap();
+        for (package, interface, version) in exports {
+            WitWorldExport::insert(
+                conn,
+                world_id,
+                package,
+                Some(interface),
+                Some(version),
+                None,
+            )
+            .unwrap();
+        }
+        let comp_id = WasmComponent::insert(conn, mid, None, Some(repository), None, None).unwrap();
+        ComponentTarget::insert(
+            conn,
+            comp_id,
+            &package_name,
+            "root",
+            None,
+            Some(world_id),
+            false,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn find_matches_package_interface_and_version() {
+        let conn = setup_test_db();
+        insert_component(
+            &conn,
+            "example/server",
+            &[("wasi:http", "incoming-handler", "0.2.0")],
+        );
+        insert_component(&conn, "example/cli", &[("wasi:cli", "run", "0.2.0")]);
+
+        let found =
+            ComponentExporter::find(&conn, "wasi:http", Some("incoming-handler"), None).unwrap();
+        let [exporter] = found.as_slice() else {
+            panic!("expected exactly one exporter, got {}", found.len());
+        };
+        assert_eq!(exporter.repository, "example/server");
+        assert_eq!(exporter.tag.as_deref(), Some("1.0.0"));
+        assert_eq!(exporter.world, "root");
+        assert_eq!(exporter.version.as_deref(), Some("0.2.0"));
+
+        assert_eq!(
+            ComponentExporter::find(&conn, "wasi:http", None, None)
+                .unwrap()
+                .len(),
+            1
+        );
+        assert!(
+            ComponentExporter::find(&conn, "wasi:http", Some("incoming-handler"), Some("0.3.0"))
+                .unwrap()
+                .is_empty()
+        );
+        assert!(
+            ComponentExporter::find(&conn, "wasi:http", Some("outgoing-handler"), None)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn split_interface_ref_handles_all_forms() {
+        assert_eq!(
+            split_interface_ref("wasi:http/incoming-handler@0.2.0"),
+            ("wasi:http", Some("incoming-handler"), Some("0.2.0"))
+        );
+        assert_eq!(
+            split_interface_ref("wasi:http/incoming-handler"),
+            ("wasi:http", Some("incoming-handler"), None)
+        );
+        assert_eq!(
+            split_interface_ref("wasi:http@0.2.0"),
+            ("wasi:http", None, Some("0.2.0"))
+        );
+        assert_eq!(split_interface_ref("wasi:http"), ("wasi:http", None, None));
+    }
+}
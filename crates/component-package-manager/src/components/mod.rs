@@ -1,8 +1,12 @@
 //! WebAssembly component types.
 //!
 //! This module groups the compiled-component data models:
-//! `WasmComponent` and `ComponentTarget`.
+//! `WasmComponent` and `ComponentTarget`, plus the `ComponentExporter`
+//! query result.
 
+mod exporter;
 mod models;
 
+pub use exporter::ComponentExporter;
+pub(crate) use exporter::split_interface_ref;
 pub use models::{ComponentTarget, WasmComponent};
@@ -96,7 +96,7 @@ impl WasmComponent {
     /// List every component in the database.
     #[allow(dead_code)]
     pub(crate) fn list_all(conn: &Connection) -> anyhow::Result<Vec<Self>> {
-        let mut stmt = conn.prepare(
+        let mut stmt = conn.prepare_cached(
             "SELECT id, oci_manifest_id, oci_layer_id, name, description, producers_json, created_at
              FROM wasm_component
              ORDER BY name ASC, created_at ASC",
@@ -208,7 +208,7 @@ impl ComponentTarget {
         conn: &Connection,
         wasm_component_id: i64,
     ) -> anyhow::Result<Vec<Self>> {
-        let mut stmt = conn.prepare(
+        let mut stmt = conn.prepare_cached(
             "SELECT id, wasm_component_id, declared_package, declared_world,
                     declared_version, wit_world_id, is_native_package
              FROM component_target
@@ -16,6 +16,16 @@ pub enum ComposeError {
     )]
     NoWacFiles,
 
+    /// The given `.wac` file does not exist.
+    #[diagnostic(
+        code(component::compose::wac_file_not_found),
+        help("check the path to the `.wac` file, or pass a name from `seams/`")
+    )]
+    WacFileNotFound {
+        /// The path that was given.
+        path: String,
+    },
+
     /// The composition name contains path separators or traversal sequences.
     #[diagnostic(
         code(component::compose::invalid_name),
@@ -99,6 +109,43 @@ pub enum ComposeError {
         /// The underlying encode error.
         reason: String,
     },
+    /// A component to plug together is not a valid component.
+    #[diagnostic(
+        code(component::compose::invalid_component),
+        help(
+            "only components can be composed; wrap core modules with `component convert component`"
+        )
+    )]
+    InvalidComponent {
+        /// The component's name, such as its path or OCI reference.
+        name: String,
+        /// Why it could not be read.
+        reason: String,
+    },
+
+    /// None of the plugs export anything the socket imports.
+    #[diagnostic(
+        code(component::compose::nothing_to_plug),
+        help(
+            "check that the plugs export interfaces '{socket}' imports, with `component inspect --imports`"
+        )
+    )]
+    NothingToPlug {
+        /// The component the plugs were plugged into.
+        socket: String,
+    },
+
+    /// Plugging the components together failed.
+    #[diagnostic(
+        code(component::compose::plug_failed),
+        help("check that the plugs are compatible with '{socket}': {reason}")
+    )]
+    PlugFailed {
+        /// The component the plugs were plugged into.
+        socket: String,
+        /// The underlying error message.
+        reason: String,
+    },
 }
 
 impl std::fmt::Display for ComposeError {
@@ -107,6 +154,9 @@ impl std::fmt::Display for ComposeError {
             ComposeError::NoWacFiles => {
                 write!(f, "no .wac files found; add files to `seams/`")
             }
+            ComposeError::WacFileNotFound { path } => {
+                write!(f, "WAC file '{path}' not found")
+            }
             ComposeError::InvalidName { name } => {
                 write!(
                     f,
@@ -131,6 +181,15 @@ impl std::fmt::Display for ComposeError {
             ComposeError::EncodeFailed { file, .. } => {
                 write!(f, "encode error for '{file}'")
             }
+            ComposeError::InvalidComponent { name, reason } => {
+                write!(f, "'{name}' is not a valid component: {reason}")
+            }
+            ComposeError::NothingToPlug { socket } => {
+                write!(f, "no plug exports anything '{socket}' imports")
+            }
+            ComposeError::PlugFailed { socket, .. } => {
+                write!(f, "could not plug components into '{socket}'")
+            }
         }
     }
 }
@@ -147,6 +206,9 @@ mod tests {
 
         let variants: Vec<Box<dyn Diagnostic>> = vec![
             Box::new(ComposeError::NoWacFiles),
+            Box::new(ComposeError::WacFileNotFound {
+                path: "other/test.wac".to_string(),
+            }),
             Box::new(ComposeError::InvalidName {
                 name: "foo/bar".to_string(),
             }),
@@ -174,10 +236,22 @@ mod tests {
                 file: "seams/test.wac".to_string(),
                 reason: "invalid graph".to_string(),
             }),
+            Box::new(ComposeError::InvalidComponent {
+                name: "app.wasm".to_string(),
+                reason: "not a component".to_string(),
+            }),
+            Box::new(ComposeError::NothingToPlug {
+                socket: "app.wasm".to_string(),
+            }),
+            Box::new(ComposeError::PlugFailed {
+                socket: "app.wasm".to_string(),
+                reason: "type mismatch".to_string(),
+            }),
         ];
 
         let expected_codes = [
             "component::compose::no_wac_files",
+            "component::compose::wac_file_not_found",
             "component::compose::invalid_name",
             "component::compose::wac_not_found",
             "component::compose::parse_failed",
@@ -185,6 +259,9 @@ mod tests {
             "component::compose::package_resolution_failed",
             "component::compose::resolution_failed",
             "component::compose::encode_failed",
+            "component::compose::invalid_component",
+            "component::compose::nothing_to_plug",
+            "component::compose::plug_failed",
         ];
 
         for (variant, expected_code) in variants.iter().zip(expected_codes.iter()) {
@@ -25,7 +25,8 @@ pub enum LinkerMode {
 
 /// Compose a set of `.wac` files under a `seams/` directory.
 ///
-/// If `name` is `Some`, only the named `.wac` file is composed.
+/// If `name` is `Some`, only the named `.wac` file is composed; a name
+/// ending in `.wac` is read as a path rather than looked up in `seams/`.
 /// If `name` is `None`, all `.wac` files in `seams/` are composed.
 ///
 /// Composed components are written to the `output` directory.
@@ -53,11 +54,185 @@ pub fn compose(name: Option<&str>, linker: &LinkerMode, output: &Path) -> Result
     Ok(results)
 }
 
+/// A component to compose, with a name to report it by.
+#[derive(Debug, Clone)]
+pub struct ComposeInput {
+    /// The component's name, such as its path or OCI reference.
+    pub name: String,
+    /// The component's bytes.
+    pub bytes: Vec<u8>,
+}
+
+/// An import of the socket that was wired to the export of a plug.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlugConnection {
+    /// The name of the socket's import.
+    pub import: String,
+    /// The name of the plug that satisfies it.
+    pub plug: String,
+    /// The name of the plug's export. This differs from `import` when the
+    /// two were matched by a semver-compatible version.
+    pub export: String,
+}
+
+/// The result of plugging components together.
+#[derive(Debug, Clone)]
+pub struct Plugged {
+    /// The composed component.
+    pub bytes: Vec<u8>,
+    /// The imports of the socket that were wired to plugs, in import order.
+    pub connections: Vec<PlugConnection>,
+}
+
+/// Compose components by plugging the exports of `plugs` into the matching
+/// imports of `socket`.
+///
+/// Exports are matched to imports by interface name, falling back to a
+/// semver-compatible version of the same interface. When several plugs
+/// export the same interface, the last one wins. The composed component
+/// exports everything `socket` exports. Imports of `socket` that no plug
+/// satisfies remain imports of the composition; see [`unsatisfied_imports`].
+///
+/// # Errors
+///
+/// Returns an error if an input is not a component, if no plug satisfies
+/// any import of `socket`, or if the composition can't be encoded.
+pub fn plug(socket: &ComposeInput, plugs: &[ComposeInput], linker: &LinkerMode) -> Result<Plugged> {
+    let mut graph = wac_graph::CompositionGraph::new();
+    let mut register = |index: usize, input: &ComposeInput| {
+        let package = wac_graph::types::Package::from_bytes(
+            &format!("compose:input{index}"),
+            None,
+            input.bytes.clone(),
+            graph.types_mut(),
+        )
+        .map_err(|e| ComposeError::InvalidComponent {
+            name: input.name.clone(),
+            reason: format!("{e:#}"),
+        })?;
+        graph
+            .register_package(package)
+            .map_err(|e| ComposeError::PlugFailed {
+                socket: socket.name.clone(),
+                reason: e.to_string(),
+            })
+    };
+    let socket_id = register(0, socket)?;
+    let plug_ids = plugs
+        .iter()
+        .enumerate()
+        .map(|(index, input)| register(index + 1, input))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    wac_graph::plug(&mut graph, plug_ids.clone(), socket_id).map_err(|e| match e {
+        wac_graph::PlugError::NoPlugHappened => ComposeError::NothingToPlug {
+            socket: socket.name.clone(),
+        },
+        wac_graph::PlugError::GraphError { source } => ComposeError::PlugFailed {
+            socket: socket.name.clone(),
+            reason: format!("{source:#}"),
+        },
+    })?;
+
+    let connections = plug_connections(&graph, socket_id, &plug_ids, plugs);
+    let bytes = graph
+        .encode(encode_options(linker))
+        .map_err(|e| ComposeError::EncodeFailed {
+            file: socket.name.clone(),
+            reason: e.to_string(),
+        })?;
+    Ok(Plugged { bytes, connections })
+}
+
+/// The imports of the socket's instantiation in `graph`, and the plug
+/// exports they were wired to.
+fn plug_connections(
+    graph: &wac_graph::CompositionGraph,
+    socket_id: wac_graph::PackageId,
+    plug_ids: &[wac_graph::PackageId],
+    plugs: &[ComposeInput],
+) -> Vec<PlugConnection> {
+    let Some(instantiation) = graph.node_ids().find(|id| {
+        matches!(graph[*id].kind(), wac_graph::NodeKind::Instantiation(_))
+            && graph[*id].package() == Some(socket_id)
+    }) else {
+        return Vec::new();
+    };
+
+    let imports = &graph.types()[graph[socket_id].ty()].imports;
+    let mut connections: Vec<PlugConnection> = graph
+        .get_instantiation_arguments(instantiation)
+        .filter_map(|(import, node)| {
+            let (_, export) = graph.get_alias_source(node)?;
+            let index = plug_ids
+                .iter()
+                .position(|id| graph[node].package() == Some(*id))?;
+            Some(PlugConnection {
+                import: import.to_string(),
+                plug: plugs.get(index)?.name.clone(),
+                export: export.to_string(),
+            })
+        })
+        .collect();
+    connections.sort_by_key(|c| imports.get_index_of(&c.import));
+    connections
+}
+
+/// The imports of a composed component, which the host has to satisfy.
+///
+/// Only the imports of the outermost component are listed, by name.
+///
+/// # Errors
+///
+/// Returns an error if `bytes` is not a valid component.
+pub fn unsatisfied_imports(bytes: &[u8]) -> Result<Vec<String>> {
+    use wasmparser::{Parser, Payload};
+
+    let mut imports = Vec::new();
+    // Nested modules and components are parsed inline; their sections are
+    // skipped by tracking how deep into them we are.
+    let mut depth = 0usize;
+    for payload in Parser::new(0).parse_all(bytes) {
+        match payload? {
+            Payload::ModuleSection { .. } | Payload::ComponentSection { .. } => depth += 1,
+            Payload::End(_) => depth = depth.saturating_sub(1),
+            Payload::ComponentImportSection(reader) if depth == 0 => {
+                for import in reader {
+                    imports.push(import?.name.0.to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(imports)
+}
+
+/// The encode options for `linker`.
+fn encode_options(linker: &LinkerMode) -> wac_graph::EncodeOptions<'static> {
+    let mut encode_options = wac_graph::EncodeOptions::default();
+    if matches!(linker, LinkerMode::Dynamic) {
+        encode_options.define_components = false;
+    }
+    encode_options
+}
+
 /// Collect the `.wac` files to process.
 fn collect_wac_files(name: Option<&str>) -> Result<Vec<PathBuf>> {
     let seams_dir = PathBuf::from("seams");
 
     if let Some(name) = name {
+        // A path to a `.wac` file is composed as-is.
+        let path = Path::new(name);
+        if path.extension().and_then(|e| e.to_str()) == Some("wac") {
+            if path.is_file() {
+                return Ok(vec![path.to_path_buf()]);
+            }
+            return Err(ComposeError::WacFileNotFound {
+                path: name.to_string(),
+            }
+            .into());
+        }
+
         // Reject names with path separators or traversal sequences.
         if name.contains('/') || name.contains('\\') || name.contains("..") {
             return Err(ComposeError::InvalidName {
@@ -168,17 +343,13 @@ fn compose_one(wac_file: &Path, linker: &LinkerMode, output: &Path) -> Result<Pa
             reason: e.to_string(),
         })?;
 
-    let mut encode_options = wac_graph::EncodeOptions::default();
-    if matches!(linker, LinkerMode::Dynamic) {
-        encode_options.define_components = false;
-    }
-
-    let bytes = resolution
-        .encode(encode_options)
-        .map_err(|e| ComposeError::EncodeFailed {
-            file: wac_file.display().to_string(),
-            reason: e.to_string(),
-        })?;
+    let bytes =
+        resolution
+            .encode(encode_options(linker))
+            .map_err(|e| ComposeError::EncodeFailed {
+                file: wac_file.display().to_string(),
+                reason: e.to_string(),
+            })?;
 
     let stem = wac_file
         .file_stem()
@@ -191,3 +362,125 @@ fn compose_one(wac_file: &Path, linker: &LinkerMode, output: &Path) -> Result<Pa
 
     Ok(out_path)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A component that imports `test:demo/greet` and `test:demo/log`, and
+    /// exports `run`.
+    const SOCKET: &str = r#"(component
+        (type $greet (instance (export "hello" (func))))
+        (import "test:demo/greet" (instance $g (type $greet)))
+        (type $log (instance (export "log" (func))))
+        (import "test:demo/log" (instance $l (type $log)))
+        (alias export $g "hello" (func $hello))
+        (core func $hello_core (canon lower (func $hello)))
+        (core module $m (import "host" "hello" (func)) (func (export "run")))
+        (core instance $ci (instantiate $m
+            (with "host" (instance (export "hello" (func $hello_core))))))
+        (func $run (canon lift (core func $ci "run")))
+        (export "run" (func $run))
+    )"#;
+
+    /// A component that exports `test:demo/greet`.
+    const PLUG: &str = r#"(component
+        (core module $m (func (export "hello")))
+        (core instance $ci (instantiate $m))
+        (func $hello (canon lift (core func $ci "hello")))
+        (instance $inst (export "hello" (func $hello)))
+        (export "test:demo/greet" (instance $inst))
+    )"#;
+
+    fn input(name: &str, wat: &str) -> ComposeInput {
+        ComposeInput {
+            name: name.to_string(),
+            bytes: wat::parse_str(wat).unwrap(),
+        }
+    }
+
+    #[test]
+    fn plug_reports_unsatisfied_imports() {
+        let socket = input("socket.wasm", SOCKET);
+        let plug = input("plug.wasm", PLUG);
+        let composed = super::plug(&socket, &[plug], &LinkerMode::Static).unwrap();
+
+        assert_eq!(
+            unsatisfied_imports(&composed.bytes).unwrap(),
+            ["test:demo/log"]
+        );
+        assert_eq!(
+            unsatisfied_imports(&socket.bytes).unwrap(),
+            ["test:demo/greet", "test:demo/log"]
+        );
+    }
+
+    #[test]
+    fn plug_reports_connections() {
+        let socket = input("socket.wasm", SOCKET);
+        let plug = input("plug.wasm", PLUG);
+        let composed = super::plug(&socket, &[plug], &LinkerMode::Static).unwrap();
+
+        assert_eq!(
+            composed.connections,
+            [PlugConnection {
+                import: "test:demo/greet".to_string(),
+                plug: "plug.wasm".to_string(),
+                export: "test:demo/greet".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn plug_matches_semver_compatible_versions() {
+        let socket = input(
+            "socket.wasm",
+            &SOCKET.replace("test:demo/greet", "test:demo/greet@0.2.0"),
+        );
+        let plug = input(
+            "plug.wasm",
+            &PLUG.replace("test:demo/greet", "test:demo/greet@0.2.1"),
+        );
+        let composed = super::plug(&socket, &[plug], &LinkerMode::Static).unwrap();
+
+        assert_eq!(
+            composed.connections,
+            [PlugConnection {
+                import: "test:demo/greet@0.2.0".to_string(),
+                plug: "plug.wasm".to_string(),
+                export: "test:demo/greet@0.2.1".to_string(),
+            }]
+        );
+        assert_eq!(
+            unsatisfied_imports(&composed.bytes).unwrap(),
+            ["test:demo/log"]
+        );
+    }
+
+    #[test]
+    fn plug_requires_a_matching_export() {
+        let socket = input("socket.wasm", SOCKET);
+        let err = plug(&socket, std::slice::from_ref(&socket), &LinkerMode::Static).unwrap_err();
+        assert!(
+            matches!(
+                err.downcast_ref::<ComposeError>(),
+                Some(ComposeError::NothingToPlug { .. })
+            ),
+            "{err:#}"
+        );
+    }
+
+    #[test]
+    fn plug_rejects_core_modules() {
+        let socket = input("socket.wasm", SOCKET);
+        let module = input("module.wasm", "(module)");
+        let err = plug(&socket, &[module], &LinkerMode::Static).unwrap_err();
+        assert!(
+            matches!(
+                err.downcast_ref::<ComposeError>(),
+                Some(ComposeError::InvalidComponent { name, .. }) if name == "module.wasm"
+            ),
+            "{err:#}"
+        );
+    }
+}
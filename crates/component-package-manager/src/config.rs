@@ -7,18 +7,54 @@
 //! - **Local**: `.config/wasm/config.toml` (relative to the current working directory)
 //!
 //! The configuration file supports per-registry credential helpers for secure authentication.
+//! With the `keyring` feature, credentials can also be kept in the OS keyring.
+//! Bearer tokens can be passed in `WASM_REGISTRY_TOKEN_<HOST>` or
+//! `WASM_REGISTRY_TOKEN` environment variables, e.g. in CI.
+//!
+//! Containers and CI jobs can also override core settings without a config
+//! file: [`CONFIG_VAR`] names the global config file, [`DEFAULT_REGISTRY_VAR`]
+//! the default registry, [`DATA_DIR_VAR`] the data directory, and
+//! [`OFFLINE_VAR`] turns on offline mode. Command-line flags take precedence
+//! over these variables, which take precedence over the config files; a
+//! `--config` flag is passed in with [`Config::use_path`].
 
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::sync::RwLock;
+use std::sync::{OnceLock, RwLock};
+use std::time::Duration;
 
 use crate::xdg_config_home;
 
 use crate::credential_helper::CredentialHelper;
 
+/// Environment variable holding a bearer token for every registry.
+///
+/// A per-registry variable, see [`Config::registry_token_var`], takes
+/// precedence.
+pub const REGISTRY_TOKEN_VAR: &str = "WASM_REGISTRY_TOKEN";
+
+/// Environment variable naming the global configuration file, in place of
+/// `$XDG_CONFIG_HOME/wasm/config.toml`.
+pub const CONFIG_VAR: &str = "WASM_CONFIG";
+
+/// Environment variable naming the registry for references without one,
+/// overriding `default-registry` in the configuration files.
+pub const DEFAULT_REGISTRY_VAR: &str = "WASM_DEFAULT_REGISTRY";
+
+/// Environment variable naming the data directory, in place of
+/// `$XDG_DATA_HOME/wasm`.
+pub const DATA_DIR_VAR: &str = "WASM_DATA_DIR";
+
+/// Environment variable that turns on offline mode when set to anything but
+/// an empty string, `0`, `false`, `no`, or `off`.
+pub const OFFLINE_VAR: &str = "WASM_OFFLINE";
+
+/// The global configuration file set with [`Config::use_path`].
+static PATH_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
 /// Default configuration file content with commented examples.
 const DEFAULT_CONFIG: &str = r#"# component(1) configuration file
 # https://github.com/yoshuawuyts/wasm
@@ -35,6 +71,22 @@ const DEFAULT_CONFIG: &str = r#"# component(1) configuration file
 # credential-helper.username = "/path/to/get-user.sh"
 # credential-helper.password = "/path/to/get-pass.sh"
 
+# Registry used for references without one, e.g. `wasi/http:0.2.0`, and
+# per-namespace overrides (a registry, optionally followed by a path prefix):
+# default-registry = "ghcr.io"
+# [namespaces]
+# wasi = "ghcr.io/webassembly"
+
+# Reject pulled artifacts that don't follow the CNCF wasm OCI artifact layout:
+# [pull]
+# strict = true
+
+# Timeouts for registry requests, in seconds (0 disables the total timeout):
+# [network]
+# connect-timeout = 10
+# read-timeout = 30
+# total-timeout = 300
+
 # Default sandbox permissions for `component run`:
 # [run.permissions]
 # inherit-stdio = true
@@ -61,10 +113,30 @@ pub struct Config {
     #[serde(default)]
     pub registries: HashMap<String, RegistryConfig>,
 
+    /// Registry used for references that don't name one, such as
+    /// `wasi/http:0.2.0`.
+    #[serde(default, rename = "default-registry")]
+    pub default_registry: Option<String>,
+
+    /// Registries for references without one, keyed by their first path
+    /// segment. Values are a registry, optionally followed by a path prefix
+    /// (e.g. `ghcr.io/webassembly`), and take precedence over
+    /// [`default_registry`](Self::default_registry).
+    #[serde(default)]
+    pub namespaces: HashMap<String, String>,
+
     /// Default runtime settings for `component run`.
     #[serde(default)]
     pub run: Option<RunConfig>,
 
+    /// Settings for pulling packages from registries.
+    #[serde(default)]
+    pub pull: Option<PullConfig>,
+
+    /// Timeouts for registry requests.
+    #[serde(default)]
+    pub network: Option<NetworkConfig>,
+
     /// Runtime credential cache (not serialized).
     #[serde(skip)]
     credential_cache: CredentialCache,
@@ -126,6 +198,82 @@ pub struct RunConfig {
     pub permissions: component_manifest::RunPermissions,
 }
 
+/// Settings for pulling packages from registries.
+///
+/// # Examples
+///
+/// ```rust
+/// use component_package_manager::PullConfig;
+///
+/// let pull = PullConfig { strict: true };
+/// assert!(pull.strict);
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PullConfig {
+    /// Reject artifacts whose config and layer media types don't follow the
+    /// [CNCF wasm OCI artifact](https://tag-runtime.cncf.io/wgs/wasm/deliverables/wasm-oci-artifact/)
+    /// layout, such as arbitrary container images.
+    pub strict: bool,
+}
+
+/// Timeouts for registry requests, in seconds.
+///
+/// Unset fields use the defaults below, so a registry that stops responding
+/// fails the request instead of hanging it.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::time::Duration;
+/// use component_package_manager::NetworkConfig;
+///
+/// let network = NetworkConfig { total_timeout: Some(0), ..NetworkConfig::default() };
+/// assert_eq!(network.connect_timeout(), Duration::from_secs(10));
+/// assert_eq!(network.total_timeout(), None);
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct NetworkConfig {
+    /// How long to wait for a connection to be established. Defaults to 10.
+    pub connect_timeout: Option<u64>,
+    /// How long a response may go without sending data. Defaults to 30.
+    pub read_timeout: Option<u64>,
+    /// How long a single registry request may take, including retries.
+    /// Defaults to 300; `0` disables it.
+    pub total_timeout: Option<u64>,
+}
+
+impl NetworkConfig {
+    const DEFAULT_CONNECT_TIMEOUT: u64 = 10;
+    const DEFAULT_READ_TIMEOUT: u64 = 30;
+    const DEFAULT_TOTAL_TIMEOUT: u64 = 300;
+
+    /// The connect timeout.
+    #[must_use]
+    pub fn connect_timeout(&self) -> Duration {
+        Duration::from_secs(
+            self.connect_timeout
+                .unwrap_or(Self::DEFAULT_CONNECT_TIMEOUT),
+        )
+    }
+
+    /// The read timeout.
+    #[must_use]
+    pub fn read_timeout(&self) -> Duration {
+        Duration::from_secs(self.read_timeout.unwrap_or(Self::DEFAULT_READ_TIMEOUT))
+    }
+
+    /// The total timeout for one request, or `None` when disabled.
+    #[must_use]
+    pub fn total_timeout(&self) -> Option<Duration> {
+        match self.total_timeout.unwrap_or(Self::DEFAULT_TOTAL_TIMEOUT) {
+            0 => None,
+            secs => Some(Duration::from_secs(secs)),
+        }
+    }
+}
+
 impl Config {
     /// Load configuration by merging global and local configs.
     ///
@@ -151,10 +299,27 @@ impl Config {
     /// # Ok::<(), anyhow::Error>(())
     /// ```
     pub fn load() -> Result<Self> {
-        let global = Self::load_from(None).with_context(|| "Failed to load global config")?;
+        let global_path = Self::config_path()
+            .context("Could not determine config directory (set $XDG_CONFIG_HOME or $HOME)")?;
+        let global =
+            Self::load_from_path(&global_path).with_context(|| "Failed to load global config")?;
         let local = Self::load_from_path(&Self::local_config_path())
             .with_context(|| "Failed to load local config (.config/wasm/config.toml)")?;
-        Ok(global.merge(local))
+        Ok(global.merge(local).with_env(env_var))
+    }
+
+    /// Whether [`OFFLINE_VAR`] turns on offline mode.
+    #[must_use]
+    pub fn offline_from_env() -> bool {
+        env_var(OFFLINE_VAR).is_some_and(|value| is_truthy(&value))
+    }
+
+    /// Apply the overrides from environment variables read with `var`.
+    fn with_env(mut self, var: impl Fn(&str) -> Option<String>) -> Self {
+        if let Some(registry) = var(DEFAULT_REGISTRY_VAR) {
+            self.default_registry = Some(registry);
+        }
+        self
     }
 
     /// Load configuration from a specified directory (for testing).
@@ -185,8 +350,9 @@ impl Config {
 
     /// Merge another config into this one, with the other config taking precedence.
     ///
-    /// Per-registry settings from `other` override those in `self`.
-    /// The `run` section is merged at the permissions level.
+    /// Per-registry and per-namespace settings from `other` override those
+    /// in `self`, as does its `default-registry` when set. The `run` section is merged at the permissions level, and `pull` and
+    /// `network` sections in `other` replace the ones in `self`.
     ///
     /// # Examples
     ///
@@ -209,6 +375,10 @@ impl Config {
         for (name, registry) in other.registries {
             self.registries.insert(name, registry);
         }
+        if other.default_registry.is_some() {
+            self.default_registry = other.default_registry;
+        }
+        self.namespaces.extend(other.namespaces);
         if let Some(other_run) = other.run {
             self.run = Some(match self.run {
                 Some(base) => RunConfig {
@@ -217,9 +387,107 @@ impl Config {
                 None => other_run,
             });
         }
+        if other.pull.is_some() {
+            self.pull = other.pull;
+        }
+        if other.network.is_some() {
+            self.network = other.network;
+        }
         self
     }
 
+    /// The registries to search for packages that aren't known locally, each
+    /// with the namespace to list within, if any.
+    ///
+    /// These are the targets of [`namespaces`](Self::namespaces), split into
+    /// a registry and a path prefix, and the
+    /// [`default_registry`](Self::default_registry), sorted and without
+    /// duplicates.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use component_package_manager::Config;
+    ///
+    /// let mut config = Config::default();
+    /// config.default_registry = Some("registry.example.com".into());
+    /// config.namespaces.insert("wasi".into(), "ghcr.io/webassembly".into());
+    ///
+    /// assert_eq!(
+    ///     config.search_sources(),
+    ///     [
+    ///         ("ghcr.io".to_string(), Some("webassembly".to_string())),
+    ///         ("registry.example.com".to_string(), None),
+    ///     ],
+    /// );
+    /// ```
+    #[must_use]
+    pub fn search_sources(&self) -> Vec<(String, Option<String>)> {
+        let mut sources: Vec<_> = self
+            .namespaces
+            .values()
+            .chain(&self.default_registry)
+            .map(|target| {
+                let target = target.trim_matches('/');
+                match target.split_once('/') {
+                    Some((registry, namespace)) => {
+                        (registry.to_string(), Some(namespace.to_string()))
+                    }
+                    None => (target.to_string(), None),
+                }
+            })
+            .collect();
+        sources.sort();
+        sources.dedup();
+        sources
+    }
+
+    /// Prefix a reference that doesn't name a registry with the configured
+    /// one.
+    ///
+    /// A reference names a registry when its first path segment contains a
+    /// `.` or `:`, or is `localhost`. Otherwise the first segment is looked
+    /// up in [`namespaces`](Self::namespaces), falling back to
+    /// [`default_registry`](Self::default_registry). References are returned
+    /// unchanged when neither applies. An `oci://` prefix is removed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use component_package_manager::Config;
+    ///
+    /// let mut config = Config::default();
+    /// config.default_registry = Some("ghcr.io".into());
+    /// config.namespaces.insert("wasi".into(), "ghcr.io/webassembly".into());
+    ///
+    /// assert_eq!(config.expand_reference("example/app:1.0"), "ghcr.io/example/app:1.0");
+    /// assert_eq!(
+    ///     config.expand_reference("wasi/http:0.2.0"),
+    ///     "ghcr.io/webassembly/wasi/http:0.2.0",
+    /// );
+    /// assert_eq!(config.expand_reference("docker.io/a/b:1"), "docker.io/a/b:1");
+    /// ```
+    #[must_use]
+    pub fn expand_reference(&self, reference: &str) -> String {
+        let reference = reference.strip_prefix("oci://").unwrap_or(reference);
+        let namespace = match reference.split_once('/') {
+            Some((first, _))
+                if first.contains('.') || first.contains(':') || first == "localhost" =>
+            {
+                return reference.to_string();
+            }
+            Some((first, _)) => Some(first),
+            None => None,
+        };
+        let registry = namespace
+            .and_then(|namespace| self.namespaces.get(namespace))
+            .or(self.default_registry.as_ref());
+        match registry {
+            Some(registry) => format!("{}/{reference}", registry.trim_end_matches('/')),
+            None => reference.to_string(),
+        }
+    }
+
     /// Load configuration from a specific file path.
     ///
     /// If the file doesn't exist, returns a default configuration.
@@ -253,7 +521,25 @@ impl Config {
         Ok(config)
     }
 
-    /// Returns the path to the configuration file.
+    /// Use the configuration file at `path` in place of the global one for
+    /// the rest of the process, e.g. for a `--config` flag.
+    ///
+    /// This takes precedence over [`CONFIG_VAR`] in
+    /// [`config_path`](Self::config_path), and so in [`load`](Self::load)
+    /// and everything that opens a [`Manager`](crate::manager::Manager).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a path was already set.
+    pub fn use_path(path: PathBuf) -> Result<()> {
+        PATH_OVERRIDE
+            .set(path)
+            .map_err(|path| anyhow::anyhow!("config file already set, to {}", path.display()))
+    }
+
+    /// Returns the path to the configuration file: the one set with
+    /// [`use_path`](Self::use_path), else [`CONFIG_VAR`] when set, and
+    /// otherwise `wasm/config.toml` in the XDG config directory.
     ///
     /// Returns `None` when no suitable config directory can be determined.
     ///
@@ -268,7 +554,11 @@ impl Config {
     /// ```
     #[must_use]
     pub fn config_path() -> Option<PathBuf> {
-        Self::config_path_from(None)
+        PATH_OVERRIDE
+            .get()
+            .cloned()
+            .or_else(|| env_var(CONFIG_VAR).map(PathBuf::from))
+            .or_else(|| Self::config_path_from(None))
     }
 
     /// Returns the path to the configuration file from a specified directory.
@@ -524,6 +814,98 @@ impl Config {
         Ok(Some(credentials))
     }
 
+    /// Get a bearer token for a registry from the environment.
+    ///
+    /// The registry's own variable (see
+    /// [`registry_token_var`](Self::registry_token_var)) is checked first,
+    /// then [`REGISTRY_TOKEN_VAR`]. Empty variables are ignored.
+    ///
+    /// Tokens are tried after anonymous access and before any other
+    /// credentials, so CI pipelines can authenticate without a config file
+    /// or credential helper.
+    #[must_use]
+    pub fn registry_token(registry: &str) -> Option<String> {
+        registry_token_from(registry, |var| std::env::var(var).ok())
+    }
+
+    /// The environment variable holding a bearer token for `registry` alone:
+    /// `WASM_REGISTRY_TOKEN_` followed by the registry in upper case, with
+    /// every character other than a letter or digit replaced by `_`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use component_package_manager::Config;
+    ///
+    /// assert_eq!(Config::registry_token_var("ghcr.io"), "WASM_REGISTRY_TOKEN_GHCR_IO");
+    /// assert_eq!(
+    ///     Config::registry_token_var("localhost:5000"),
+    ///     "WASM_REGISTRY_TOKEN_LOCALHOST_5000",
+    /// );
+    /// ```
+    #[must_use]
+    pub fn registry_token_var(registry: &str) -> String {
+        let host: String = registry
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() {
+                    c.to_ascii_uppercase()
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        format!("{REGISTRY_TOKEN_VAR}_{host}")
+    }
+
+    /// Get credentials for a registry stored in the OS keyring.
+    ///
+    /// Returns `None` if no credentials are stored for the registry.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the keyring is unavailable or the stored entry is
+    /// malformed.
+    #[cfg(feature = "keyring")]
+    pub fn keyring_credentials(registry: &str) -> Result<Option<(String, String)>> {
+        crate::os_keyring::get(registry)
+    }
+
+    /// Store credentials for a registry in the OS keyring, replacing any
+    /// previously stored for it.
+    ///
+    /// Stored credentials are tried after the Docker credential store and
+    /// before the registry's credential helper.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the keyring is unavailable.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use component_package_manager::Config;
+    ///
+    /// Config::store_credentials("ghcr.io", "my-user", "my-token")?;
+    /// # Ok::<(), anyhow::Error>(())
+    /// ```
+    #[cfg(feature = "keyring")]
+    pub fn store_credentials(registry: &str, username: &str, password: &str) -> Result<()> {
+        crate::os_keyring::set(registry, username, password)
+    }
+
+    /// Remove credentials for a registry from the OS keyring.
+    ///
+    /// Returns `false` if no credentials were stored for the registry.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the keyring is unavailable.
+    #[cfg(feature = "keyring")]
+    pub fn delete_credentials(registry: &str) -> Result<bool> {
+        crate::os_keyring::delete(registry)
+    }
+
     /// Clear the credential cache.
     ///
     /// # Examples
@@ -542,6 +924,32 @@ impl Config {
     }
 }
 
+/// Look up a bearer token for `registry` with `var`, which reads an
+/// environment variable.
+fn registry_token_from(registry: &str, var: impl Fn(&str) -> Option<String>) -> Option<String> {
+    [
+        Config::registry_token_var(registry).as_str(),
+        REGISTRY_TOKEN_VAR,
+    ]
+    .into_iter()
+    .filter_map(var)
+    .find(|token| !token.is_empty())
+}
+
+/// The value of the environment variable `name`, unless it's unset or empty.
+pub(crate) fn env_var(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|value| !value.is_empty())
+}
+
+/// Whether a flag's environment variable is set to something other than a
+/// false value, as for [`OFFLINE_VAR`].
+fn is_truthy(value: &str) -> bool {
+    !matches!(
+        value.trim().to_ascii_lowercase().as_str(),
+        "" | "0" | "false" | "no" | "off"
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -738,9 +1146,122 @@ credential-helper.password = "/path/to/get-pass.sh"
         assert!(merged.registries.contains_key("local-only.io"));
     }
 
+    #[test]
+    fn test_config_load_pull_strict() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "[pull]\nstrict = true\n").unwrap();
+
+        let config = Config::load_from_path(&path).unwrap();
+        assert!(config.pull.as_ref().is_some_and(|pull| pull.strict));
+
+        let merged = config.merge(Config::default());
+        assert!(merged.pull.is_some_and(|pull| pull.strict));
+    }
+
+    #[test]
+    fn test_config_load_network_timeouts() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "[network]\nconnect-timeout = 5\ntotal-timeout = 0\n").unwrap();
+
+        let config = Config::load_from_path(&path).unwrap();
+        let network = config.network.unwrap_or_default();
+        assert_eq!(network.connect_timeout(), Duration::from_secs(5));
+        assert_eq!(network.read_timeout(), Duration::from_secs(30));
+        assert_eq!(network.total_timeout(), None);
+    }
+
+    #[test]
+    fn test_expand_reference_from_loaded_config() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(
+            &path,
+            "default-registry = \"ghcr.io/\"\n[namespaces]\nwasi = \"ghcr.io/webassembly\"\n",
+        )
+        .unwrap();
+
+        let config = Config::load_from_path(&path).unwrap();
+        assert_eq!(config.expand_reference("app:1.0"), "ghcr.io/app:1.0");
+        assert_eq!(
+            config.expand_reference("oci://wasi/http:0.2.0"),
+            "ghcr.io/webassembly/wasi/http:0.2.0"
+        );
+        assert_eq!(
+            config.expand_reference("localhost/example/app"),
+            "localhost/example/app"
+        );
+        assert_eq!(
+            config.expand_reference("localhost:5000/app"),
+            "localhost:5000/app"
+        );
+        assert_eq!(
+            Config::default().expand_reference("example/app"),
+            "example/app"
+        );
+    }
+
     #[test]
     fn test_local_config_path() {
         let path = Config::local_config_path();
         assert_eq!(path, PathBuf::from(".config/wasm/config.toml"));
     }
+
+    #[test]
+    fn registry_token_prefers_the_registry_variable() {
+        let env = |vars: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                vars.iter()
+                    .find(|(var, _)| *var == name)
+                    .map(|(_, value)| (*value).to_string())
+            }
+        };
+
+        let both = env(&[
+            ("WASM_REGISTRY_TOKEN", "generic"),
+            ("WASM_REGISTRY_TOKEN_GHCR_IO", "ghcr"),
+        ]);
+        assert_eq!(
+            registry_token_from("ghcr.io", both).as_deref(),
+            Some("ghcr")
+        );
+        assert_eq!(
+            registry_token_from("docker.io", both).as_deref(),
+            Some("generic")
+        );
+
+        let empty = env(&[
+            ("WASM_REGISTRY_TOKEN", "generic"),
+            ("WASM_REGISTRY_TOKEN_GHCR_IO", ""),
+        ]);
+        assert_eq!(
+            registry_token_from("ghcr.io", empty).as_deref(),
+            Some("generic")
+        );
+        assert_eq!(registry_token_from("ghcr.io", env(&[])), None);
+    }
+
+    #[test]
+    fn env_overrides_the_default_registry() {
+        let config = Config {
+            default_registry: Some("ghcr.io".into()),
+            ..Config::default()
+        };
+        let overridden = config.clone().with_env(|var| {
+            (var == DEFAULT_REGISTRY_VAR).then(|| "registry.example.com".to_string())
+        });
+        assert_eq!(
+            overridden.default_registry.as_deref(),
+            Some("registry.example.com")
+        );
+        let unchanged = config.with_env(|_| None);
+        assert_eq!(unchanged.default_registry.as_deref(), Some("ghcr.io"));
+
+        assert!(is_truthy("1"));
+        assert!(is_truthy("TRUE"));
+        for value in ["", "0", "false", "No", "off"] {
+            assert!(!is_truthy(value), "{value}");
+        }
+    }
 }
@@ -0,0 +1,370 @@
+//! Top-level error type for the package manager's public API.
+
+use oci_client::errors::{OciDistributionError, OciErrorCode};
+
+use crate::manager::ManagerError;
+use crate::oci::OciLayerError;
+use crate::policy::PolicyViolation;
+use crate::sign::SignError;
+
+/// A boxed error used as the source of [`PackageManagerError::RegistryError`].
+type BoxError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+/// A specialized [`Result`](std::result::Result) type for package manager
+/// operations.
+pub type Result<T, E = PackageManagerError> = std::result::Result<T, E>;
+
+/// Error type returned by the public [`Manager`](crate::manager::Manager) API.
+///
+/// Each variant describes a broad failure kind so that embedders can branch
+/// on it without matching on error strings. The underlying error is kept as
+/// the variant's payload, so the full cause chain remains available for
+/// display.
+///
+/// # Example
+///
+/// ```rust
+/// use component_package_manager::PackageManagerError;
+/// use component_package_manager::manager::ManagerError;
+///
+/// let err = PackageManagerError::from(anyhow::Error::from(ManagerError::OfflinePull));
+/// assert!(matches!(err, PackageManagerError::Offline(_)));
+/// assert_eq!(err.to_string(), "cannot pull packages in offline mode");
+/// ```
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum PackageManagerError {
+    /// The requested package, tag, manifest, or layer does not exist.
+    #[error(transparent)]
+    NotFound(anyhow::Error),
+
+    /// The registry rejected the request because of missing or invalid
+    /// credentials.
+    #[error(transparent)]
+    AuthFailed(anyhow::Error),
+
+    /// The registry is throttling requests. Unlike
+    /// [`AuthFailed`](Self::AuthFailed), retrying later may succeed; the
+    /// chain contains an [`OciLayerError::RateLimited`] with the requested
+    /// delay when the registry gave one.
+    #[error(transparent)]
+    RateLimited(anyhow::Error),
+
+    /// The operation requires network access but offline mode is enabled.
+    #[error(transparent)]
+    Offline(anyhow::Error),
+
+    /// Stored or fetched data failed validation (digest mismatch, malformed
+    /// manifest, unexpected layers, or a damaged database).
+    #[error(transparent)]
+    Corrupt(anyhow::Error),
+
+    /// The content trust policy rejected the artifact; the chain contains
+    /// the [`PolicyViolation`].
+    #[error(transparent)]
+    PolicyViolation(anyhow::Error),
+
+    /// The registry responded with an unexpected HTTP status.
+    #[error("registry returned HTTP {status}")]
+    RegistryError {
+        /// The HTTP status code returned by the registry.
+        status: u16,
+        /// The underlying error.
+        source: BoxError,
+    },
+
+    /// Any other failure.
+    #[error(transparent)]
+    Other(anyhow::Error),
+}
+
+/// The failure kind detected for a single error in a cause chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    NotFound,
+    AuthFailed,
+    RateLimited,
+    Offline,
+    Corrupt,
+    Policy,
+    Registry(u16),
+}
+
+impl From<anyhow::Error> for PackageManagerError {
+    fn from(err: anyhow::Error) -> Self {
+        match err.chain().find_map(kind_of) {
+            Some(Kind::NotFound) => Self::NotFound(err),
+            Some(Kind::AuthFailed) => Self::AuthFailed(err),
+            Some(Kind::RateLimited) => Self::RateLimited(err),
+            Some(Kind::Offline) => Self::Offline(err),
+            Some(Kind::Corrupt) => Self::Corrupt(err),
+            Some(Kind::Policy) => Self::PolicyViolation(err),
+            Some(Kind::Registry(status)) => Self::RegistryError {
+                status,
+                source: err.into(),
+            },
+            None => Self::Other(err),
+        }
+    }
+}
+
+impl From<ManagerError> for PackageManagerError {
+    fn from(err: ManagerError) -> Self {
+        anyhow::Error::from(err).into()
+    }
+}
+
+impl From<OciLayerError> for PackageManagerError {
+    fn from(err: OciLayerError) -> Self {
+        anyhow::Error::from(err).into()
+    }
+}
+
+impl From<PolicyViolation> for PackageManagerError {
+    fn from(err: PolicyViolation) -> Self {
+        anyhow::Error::from(err).into()
+    }
+}
+
+impl From<SignError> for PackageManagerError {
+    fn from(err: SignError) -> Self {
+        anyhow::Error::from(err).into()
+    }
+}
+
+impl From<rusqlite::Error> for PackageManagerError {
+    fn from(err: rusqlite::Error) -> Self {
+        anyhow::Error::from(err).into()
+    }
+}
+
+impl From<cacache::Error> for PackageManagerError {
+    fn from(err: cacache::Error) -> Self {
+        anyhow::Error::from(err).into()
+    }
+}
+
+impl From<oci_client::ParseError> for PackageManagerError {
+    fn from(err: oci_client::ParseError) -> Self {
+        anyhow::Error::from(err).into()
+    }
+}
+
+impl From<std::io::Error> for PackageManagerError {
+    fn from(err: std::io::Error) -> Self {
+        anyhow::Error::from(err).into()
+    }
+}
+
+/// Classify a single error from a cause chain, if it is a known kind.
+fn kind_of(cause: &(dyn std::error::Error + 'static)) -> Option<Kind> {
+    if let Some(err) = cause.downcast_ref::<ManagerError>() {
+        return manager_kind(err);
+    }
+    if cause.is::<PolicyViolation>() {
+        return Some(Kind::Policy);
+    }
+    if let Some(err) = cause.downcast_ref::<OciLayerError>() {
+        return match err {
+            OciLayerError::IdentityTokenNotSupported => Some(Kind::AuthFailed),
+            OciLayerError::RateLimited { .. } => Some(Kind::RateLimited),
+            OciLayerError::TimedOut { .. } => None,
+            _ => Some(Kind::Corrupt),
+        };
+    }
+    if let Some(err) = cause.downcast_ref::<OciDistributionError>() {
+        return oci_kind(err);
+    }
+    if let Some(err) = cause.downcast_ref::<cacache::Error>() {
+        return match err {
+            cacache::Error::EntryNotFound(..) => Some(Kind::NotFound),
+            cacache::Error::IntegrityError(_) | cacache::Error::SizeMismatch(..) => {
+                Some(Kind::Corrupt)
+            }
+            _ => None,
+        };
+    }
+    cause
+        .downcast_ref::<rusqlite::Error>()
+        .and_then(sqlite_kind)
+}
+
+fn manager_kind(err: &ManagerError) -> Option<Kind> {
+    match err {
+        ManagerError::OfflinePull
+        | ManagerError::OfflineIndex
+        | ManagerError::OfflinePublish
+        | ManagerError::OfflineNotify
+        | ManagerError::OfflineSign
+        | ManagerError::OfflineVerify => Some(Kind::Offline),
+        ManagerError::IndexRetrievalFailed
+        | ManagerError::NoTagsFound { .. }
+        | ManagerError::ManifestNotFound { .. }
+        | ManagerError::NotInStore { .. }
+        | ManagerError::UnresolvedImageIndex { .. }
+        | ManagerError::NoMatchingIndexEntry { .. } => Some(Kind::NotFound),
+        ManagerError::SyncNoLocalData { .. }
+        | ManagerError::NotAComponent { .. }
+        | ManagerError::StoreLocked { .. }
+        | ManagerError::InvalidDigestPrefix { .. }
+        | ManagerError::InvalidVendorName { .. }
+        | ManagerError::InvalidReference { .. }
+        | ManagerError::AmbiguousIndexEntry { .. } => None,
+    }
+}
+
+fn oci_kind(err: &OciDistributionError) -> Option<Kind> {
+    match err {
+        OciDistributionError::AuthenticationFailure(_)
+        | OciDistributionError::UnauthorizedError { .. }
+        | OciDistributionError::RegistryTokenDecodeError(_) => Some(Kind::AuthFailed),
+        OciDistributionError::ImageManifestNotFoundError(_) => Some(Kind::NotFound),
+        OciDistributionError::DigestError(_)
+        | OciDistributionError::ManifestEncodingError(_)
+        | OciDistributionError::ManifestParsingError(_)
+        | OciDistributionError::VersionedParsingError(_)
+        | OciDistributionError::SpecViolationError(_) => Some(Kind::Corrupt),
+        OciDistributionError::ServerError { code, .. } => Some(status_kind(*code)),
+        OciDistributionError::RegistryError { envelope, .. } => {
+            envelope.errors.iter().find_map(|e| envelope_kind(&e.code))
+        }
+        _ => None,
+    }
+}
+
+fn envelope_kind(code: &OciErrorCode) -> Option<Kind> {
+    match code {
+        OciErrorCode::BlobUnknown
+        | OciErrorCode::ManifestBlobUnknown
+        | OciErrorCode::ManifestUnknown
+        | OciErrorCode::NameUnknown
+        | OciErrorCode::NotFound => Some(Kind::NotFound),
+        OciErrorCode::Unauthorized | OciErrorCode::Denied => Some(Kind::AuthFailed),
+        OciErrorCode::Toomanyrequests => Some(Kind::RateLimited),
+        OciErrorCode::DigestInvalid
+        | OciErrorCode::ManifestInvalid
+        | OciErrorCode::ManifestUnverified
+        | OciErrorCode::SizeInvalid => Some(Kind::Corrupt),
+        _ => None,
+    }
+}
+
+fn status_kind(status: u16) -> Kind {
+    match status {
+        401 | 403 => Kind::AuthFailed,
+        404 => Kind::NotFound,
+        429 => Kind::RateLimited,
+        _ => Kind::Registry(status),
+    }
+}
+
+fn sqlite_kind(err: &rusqlite::Error) -> Option<Kind> {
+    match err {
+        rusqlite::Error::SqliteFailure(e, _)
+            if matches!(
+                e.code,
+                rusqlite::ErrorCode::DatabaseCorrupt | rusqlite::ErrorCode::NotADatabase
+            ) =>
+        {
+            Some(Kind::Corrupt)
+        }
+        rusqlite::Error::QueryReturnedNoRows => Some(Kind::NotFound),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offline_manager_errors_classify_as_offline() {
+        let err = PackageManagerError::from(ManagerError::OfflineIndex);
+        assert!(matches!(err, PackageManagerError::Offline(_)));
+    }
+
+    #[test]
+    fn missing_tag_classifies_as_not_found() {
+        let err = PackageManagerError::from(ManagerError::NoTagsFound {
+            registry: "ghcr.io".to_string(),
+            repository: "example/pkg".to_string(),
+        });
+        assert!(matches!(err, PackageManagerError::NotFound(_)));
+    }
+
+    #[test]
+    fn invalid_layers_classify_as_corrupt() {
+        let err = PackageManagerError::from(OciLayerError::InvalidLayerCount { found: 2 });
+        assert!(matches!(err, PackageManagerError::Corrupt(_)));
+    }
+
+    #[test]
+    fn policy_violations_classify_as_policy_violation() {
+        let err = PackageManagerError::from(PolicyViolation::ArtifactTooLarge {
+            reference: "ghcr.io/example/pkg:1".to_string(),
+            size: 2048,
+            limit: 1024,
+        });
+        assert!(matches!(err, PackageManagerError::PolicyViolation(_)));
+    }
+
+    #[test]
+    fn server_errors_carry_status() {
+        let err =
+            PackageManagerError::from(anyhow::Error::from(OciDistributionError::ServerError {
+                code: 503,
+                url: "https://ghcr.io/v2/".to_string(),
+                message: "unavailable".to_string(),
+            }));
+        assert!(matches!(
+            err,
+            PackageManagerError::RegistryError { status: 503, .. }
+        ));
+    }
+
+    #[test]
+    fn unauthorized_status_classifies_as_auth_failed() {
+        let err =
+            PackageManagerError::from(anyhow::Error::from(OciDistributionError::ServerError {
+                code: 401,
+                url: "https://ghcr.io/v2/".to_string(),
+                message: "unauthorized".to_string(),
+            }));
+        assert!(matches!(err, PackageManagerError::AuthFailed(_)));
+    }
+
+    #[test]
+    fn throttling_classifies_as_rate_limited() {
+        let err =
+            PackageManagerError::from(anyhow::Error::from(OciDistributionError::ServerError {
+                code: 429,
+                url: "https://ghcr.io/v2/".to_string(),
+                message: "too many requests".to_string(),
+            }));
+        assert!(matches!(err, PackageManagerError::RateLimited(_)));
+
+        let err = PackageManagerError::from(OciLayerError::RateLimited {
+            registry: "ghcr.io".to_string(),
+            retry_after_secs: None,
+            remaining: Some(0),
+        });
+        assert!(matches!(err, PackageManagerError::RateLimited(_)));
+    }
+
+    #[test]
+    fn classification_looks_through_context() {
+        let err = anyhow::Error::from(ManagerError::OfflinePull).context("while installing");
+        let err = PackageManagerError::from(err);
+        assert!(matches!(err, PackageManagerError::Offline(_)));
+        assert_eq!(
+            format!("{err:#}"),
+            "while installing: cannot pull packages in offline mode"
+        );
+    }
+
+    #[test]
+    fn unknown_errors_classify_as_other() {
+        let err = PackageManagerError::from(anyhow::anyhow!("something else"));
+        assert!(matches!(err, PackageManagerError::Other(_)));
+    }
+}
@@ -0,0 +1,55 @@
+/// Events emitted when the local store changes.
+///
+/// Subscribe with [`Manager::subscribe`](crate::manager::Manager::subscribe)
+/// to react to changes without polling
+/// [`Manager::list_all`](crate::manager::Manager::list_all). Events are
+/// delivered over a `tokio::sync::broadcast` channel: they are only sent to
+/// receivers that exist at the time, and a receiver that falls behind
+/// observes [`RecvError::Lagged`](tokio::sync::broadcast::error::RecvError::Lagged)
+/// rather than blocking the store.
+///
+/// # Examples
+///
+/// ```rust
+/// use component_package_manager::StoreEvent;
+///
+/// let event = StoreEvent::PostPull {
+///     reference: "ghcr.io/example/component:1.0.0".into(),
+///     digest: Some("sha256:abc123".into()),
+///     inserted: true,
+/// };
+///
+/// let gc = StoreEvent::GcCompleted { removed: 3 };
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StoreEvent {
+    /// A package was pulled into the store.
+    PostPull {
+        /// The reference that was pulled.
+        reference: String,
+        /// The manifest digest, if known.
+        digest: Option<String>,
+        /// Whether the image was new, rather than already stored.
+        inserted: bool,
+    },
+    /// Packages were deleted from the store.
+    PostDelete {
+        /// What was deleted: a reference, repository, digest prefix, or glob.
+        target: String,
+        /// The number of images removed.
+        count: usize,
+    },
+    /// A WIT package was extracted from a stored layer and indexed.
+    WitExtracted {
+        /// The WIT package name, e.g. `wasi:http@0.2.0`.
+        package: String,
+    },
+    /// A pass that removes unused images or blobs finished.
+    GcCompleted {
+        /// The number of images or blobs removed.
+        removed: usize,
+    },
+}
+
+/// How many events a slow subscriber may fall behind before it lags.
+pub(crate) const EVENT_CAPACITY: usize = 256;
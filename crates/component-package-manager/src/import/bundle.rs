@@ -0,0 +1,179 @@
+//! Bundles of stored images, for moving them to machines that can't reach
+//! a registry.
+//!
+//! A bundle is a tar archive in the OCI image layout, compressed with zstd.
+//! Each image's full reference is kept in the
+//! `org.opencontainers.image.ref.name` annotation of its `index.json` entry,
+//! so importing a bundle stores every image under the name it was exported
+//! with. Blobs shared between images are written once.
+
+use std::collections::BTreeMap;
+
+use oci_client::Reference;
+use oci_client::client::ImageData;
+use oci_client::manifest::{OCI_IMAGE_INDEX_MEDIA_TYPE, OciImageIndex, OciImageManifest};
+
+use super::{ImportError, image_data, layout_blob, tar};
+use crate::manager::StoredManifest;
+
+/// The annotation holding each image's reference.
+const REF_NAME: &str = "org.opencontainers.image.ref.name";
+
+/// The zstd compression level bundles are written with.
+const LEVEL: i32 = 3;
+
+/// The bytes every zstd frame starts with.
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// Write a bundle of `images`, whose config and layer blobs are in `blobs`
+/// by digest.
+pub(crate) fn write(
+    images: &[(Reference, StoredManifest)],
+    blobs: &BTreeMap<String, Vec<u8>>,
+) -> anyhow::Result<Vec<u8>> {
+    let manifests: Vec<_> = images
+        .iter()
+        .map(|(reference, manifest)| {
+            serde_json::json!({
+                "mediaType": manifest.media_type,
+                "digest": manifest.digest,
+                "size": manifest.body.len(),
+                "annotations": { REF_NAME: reference.whole() },
+            })
+        })
+        .collect();
+    let index = serde_json::to_vec(&serde_json::json!({
+        "schemaVersion": 2,
+        "mediaType": OCI_IMAGE_INDEX_MEDIA_TYPE,
+        "manifests": manifests,
+    }))?;
+
+    let mut contents = BTreeMap::new();
+    for (_, manifest) in images {
+        contents.insert(manifest.digest.as_str(), manifest.body.as_slice());
+    }
+    for (digest, data) in blobs {
+        contents.insert(digest.as_str(), data.as_slice());
+    }
+    let paths: Vec<(String, &[u8])> = contents
+        .into_iter()
+        .map(|(digest, data)| (super::blob_path(digest), data))
+        .collect();
+
+    let mut files: Vec<(&str, &[u8])> = vec![
+        ("oci-layout", br#"{"imageLayoutVersion":"1.0.0"}"#),
+        ("index.json", &index),
+    ];
+    files.extend(paths.iter().map(|(path, data)| (path.as_str(), *data)));
+    let archive = tar::archive(&files)?;
+    Ok(zstd::encode_all(archive.as_slice(), LEVEL)?)
+}
+
+/// Read every image in the bundle at `path`, whose contents are `bytes`.
+///
+/// Uncompressed tar archives in the same layout are read too.
+pub(crate) fn read(path: &str, bytes: &[u8]) -> anyhow::Result<Vec<(Reference, ImageData)>> {
+    let invalid = |reason: String| ImportError::InvalidBundle {
+        path: path.to_string(),
+        reason,
+    };
+    let archive = if bytes.starts_with(&ZSTD_MAGIC) {
+        zstd::decode_all(bytes).map_err(|e| invalid(format!("failed to decompress: {e}")))?
+    } else {
+        bytes.to_vec()
+    };
+    let files = tar::files(&archive).map_err(|e| invalid(e.to_string()))?;
+    let blob = |digest: &str| layout_blob(&files, digest).map_err(invalid);
+
+    let index = files
+        .get("index.json")
+        .ok_or_else(|| invalid("index.json is missing".to_string()))?;
+    let index: OciImageIndex =
+        serde_json::from_slice(index).map_err(|e| invalid(format!("index.json: {e}")))?;
+
+    let mut images = Vec::with_capacity(index.manifests.len());
+    for entry in index.manifests {
+        let name = entry
+            .annotations
+            .as_ref()
+            .and_then(|annotations| annotations.get(REF_NAME))
+            .ok_or_else(|| invalid(format!("manifest {} has no reference", entry.digest)))?;
+        let reference = crate::parse_reference(name)
+            .map_err(|reason| invalid(format!("reference '{name}': {reason}")))?;
+        let manifest: OciImageManifest = serde_json::from_slice(blob(&entry.digest)?)
+            .map_err(|e| invalid(format!("manifest {}: {e}", entry.digest)))?;
+        images.push((reference, image_data(&entry.digest, manifest, blob)?));
+    }
+    Ok(images)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::oci::sha256_digest;
+
+    const COMPONENT: [u8; 8] = [0x00, 0x61, 0x73, 0x6d, 0x0d, 0x00, 0x01, 0x00];
+
+    fn image(reference: &str) -> ((Reference, StoredManifest), BTreeMap<String, Vec<u8>>) {
+        let config = br#"{"architecture":"wasm","os":"wasip2"}"#.to_vec();
+        let body = serde_json::to_vec(&serde_json::json!({
+            "schemaVersion": 2,
+            "mediaType": "application/vnd.oci.image.manifest.v1+json",
+            "config": {
+                "mediaType": "application/vnd.wasm.config.v0+json",
+                "digest": sha256_digest(&config),
+                "size": config.len(),
+            },
+            "layers": [{
+                "mediaType": "application/wasm",
+                "digest": sha256_digest(&COMPONENT),
+                "size": COMPONENT.len(),
+            }],
+        }))
+        .unwrap();
+        let manifest = StoredManifest {
+            digest: sha256_digest(&body),
+            media_type: "application/vnd.oci.image.manifest.v1+json".to_string(),
+            body,
+        };
+        let blobs = BTreeMap::from([
+            (sha256_digest(&config), config),
+            (sha256_digest(&COMPONENT), COMPONENT.to_vec()),
+        ]);
+        ((reference.parse().unwrap(), manifest), blobs)
+    }
+
+    #[test]
+    fn round_trips_images_by_reference() {
+        let (image, blobs) = image("ghcr.io/example/app:1.0");
+        let bundle = write(&[image], &blobs).unwrap();
+        assert!(bundle.starts_with(&ZSTD_MAGIC));
+
+        let images = read("bundle.tar.zst", &bundle).unwrap();
+        let [(reference, data)] = images.as_slice() else {
+            panic!("expected one image, got {}", images.len());
+        };
+        assert_eq!(reference.whole(), "ghcr.io/example/app:1.0");
+        assert_eq!(
+            data.layers.first().map(|layer| layer.data.as_ref()),
+            Some(COMPONENT.as_slice())
+        );
+    }
+
+    #[test]
+    fn rejects_tampered_bundles() {
+        let (image, mut blobs) = image("ghcr.io/example/app:1.0");
+        for data in blobs.values_mut() {
+            data.push(0);
+        }
+        let bundle = write(&[image], &blobs).unwrap();
+        let err = read("bundle.tar.zst", &bundle).err().unwrap();
+        assert!(
+            matches!(
+                err.downcast_ref::<ImportError>(),
+                Some(ImportError::InvalidBundle { .. })
+            ),
+            "{err}"
+        );
+    }
+}
@@ -0,0 +1,168 @@
+//! Error types for importing images from a container engine.
+
+use miette::Diagnostic;
+
+/// An image that could not be imported from Docker, Podman, or a bundle.
+///
+/// Each variant carries a stable [diagnostic error code][miette::Diagnostic::code]
+/// that uniquely identifies the failure.
+///
+/// # Example
+///
+/// ```rust
+/// use miette::Diagnostic;
+/// use component_package_manager::import::ImportError;
+///
+/// let err = ImportError::EngineUnavailable {
+///     engine: "podman".into(),
+///     reason: "No such file or directory".into(),
+/// };
+/// assert_eq!(
+///     err.code().expect("should have a code").to_string(),
+///     "component::import::engine_unavailable",
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Diagnostic)]
+#[must_use]
+pub enum ImportError {
+    /// The container engine's command could not be run.
+    #[diagnostic(
+        code(component::import::engine_unavailable),
+        help("install {engine}, or make sure it is on your PATH")
+    )]
+    EngineUnavailable {
+        /// The engine's command, e.g. `docker`.
+        engine: String,
+        /// Why it could not be run.
+        reason: String,
+    },
+
+    /// The container engine's command exited with an error.
+    #[diagnostic(
+        code(component::import::engine_failed),
+        help("check that the {engine} daemon is running and the image exists")
+    )]
+    EngineFailed {
+        /// The engine's command, e.g. `docker`.
+        engine: String,
+        /// The arguments it was run with.
+        args: String,
+        /// What it printed to stderr.
+        stderr: String,
+    },
+
+    /// The saved image is not in the OCI image layout.
+    #[diagnostic(
+        code(component::import::unsupported_archive),
+        help("Docker saves images in the OCI layout since version 25; upgrade and try again")
+    )]
+    UnsupportedArchive {
+        /// The image that was saved.
+        image: String,
+    },
+
+    /// The saved image lacks a blob or has one that doesn't match its digest.
+    #[diagnostic(
+        code(component::import::invalid_archive),
+        help("save the image again; if this persists, pull it with `docker pull` first")
+    )]
+    InvalidArchive {
+        /// The image that was saved.
+        image: String,
+        /// What is wrong with the archive.
+        reason: String,
+    },
+
+    /// A bundle is not one `component registry export` wrote, or is damaged.
+    #[diagnostic(
+        code(component::import::invalid_bundle),
+        help("export the bundle again with `component registry export`")
+    )]
+    InvalidBundle {
+        /// The bundle's path.
+        path: String,
+        /// What is wrong with the bundle.
+        reason: String,
+    },
+}
+
+impl std::fmt::Display for ImportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ImportError::EngineUnavailable { engine, reason } => {
+                write!(f, "could not run `{engine}`: {reason}")
+            }
+            ImportError::EngineFailed {
+                engine,
+                args,
+                stderr,
+            } => {
+                write!(f, "`{engine} {args}` failed: {}", stderr.trim())
+            }
+            ImportError::UnsupportedArchive { image } => {
+                write!(f, "'{image}' was not saved in the OCI image layout")
+            }
+            ImportError::InvalidArchive { image, reason } => {
+                write!(f, "the saved image '{image}' is invalid: {reason}")
+            }
+            ImportError::InvalidBundle { path, reason } => {
+                write!(f, "the bundle {path} is invalid: {reason}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ImportError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_all_variants_have_error_codes() {
+        let cases = [
+            (
+                ImportError::EngineUnavailable {
+                    engine: "docker".into(),
+                    reason: "not found".into(),
+                },
+                "component::import::engine_unavailable",
+            ),
+            (
+                ImportError::EngineFailed {
+                    engine: "docker".into(),
+                    args: "save app:1".into(),
+                    stderr: "no such image".into(),
+                },
+                "component::import::engine_failed",
+            ),
+            (
+                ImportError::UnsupportedArchive {
+                    image: "app:1".into(),
+                },
+                "component::import::unsupported_archive",
+            ),
+            (
+                ImportError::InvalidArchive {
+                    image: "app:1".into(),
+                    reason: "missing index.json".into(),
+                },
+                "component::import::invalid_archive",
+            ),
+            (
+                ImportError::InvalidBundle {
+                    path: "bundle.tar.zst".into(),
+                    reason: "missing index.json".into(),
+                },
+                "component::import::invalid_bundle",
+            ),
+        ];
+        for (err, code) in cases {
+            assert_eq!(
+                err.code().expect("must have a diagnostic code").to_string(),
+                code
+            );
+            assert!(err.help().is_some(), "{code} must have a help message");
+        }
+    }
+}
@@ -0,0 +1,368 @@
+//! Importing wasm images from a local Docker or Podman daemon, and moving
+//! stored images between machines as bundles.
+//!
+//! Images are listed with `<engine> image ls` and `<engine> image inspect`,
+//! keeping those built for the `wasm` architecture. Importing one runs
+//! `<engine> save`, reads the image from the OCI image layout in the archive,
+//! and stores it as though it had been pulled from its registry.
+//!
+//! Docker writes the OCI image layout since version 25; Podman is asked for
+//! it with `--format oci-archive`.
+
+pub(crate) mod bundle;
+mod errors;
+mod tar;
+
+use std::collections::HashMap;
+
+use oci_client::client::{Config, ImageData, ImageLayer};
+use oci_client::manifest::{
+    IMAGE_MANIFEST_LIST_MEDIA_TYPE, ImageIndexEntry, OCI_IMAGE_INDEX_MEDIA_TYPE, OciImageIndex,
+    OciImageManifest,
+};
+use serde::Deserialize;
+
+use crate::oci::sha256_digest;
+
+pub use errors::ImportError;
+
+/// How many image indexes deep the manifest of a saved image may be.
+const MAX_INDEX_DEPTH: usize = 4;
+
+/// A container engine whose local images can be imported.
+///
+/// # Example
+///
+/// ```rust
+/// use component_package_manager::import::ContainerEngine;
+///
+/// assert_eq!(ContainerEngine::default().command(), "docker");
+/// assert_eq!(ContainerEngine::Podman.command(), "podman");
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ContainerEngine {
+    /// Docker, through the `docker` command.
+    #[default]
+    Docker,
+    /// Podman, through the `podman` command.
+    Podman,
+}
+
+impl ContainerEngine {
+    /// The command used to talk to the engine.
+    #[must_use]
+    pub fn command(self) -> &'static str {
+        match self {
+            ContainerEngine::Docker => "docker",
+            ContainerEngine::Podman => "podman",
+        }
+    }
+}
+
+/// A wasm image in a container engine's local storage.
+///
+/// # Example
+///
+/// ```rust
+/// use component_package_manager::import::DaemonImage;
+///
+/// let image = DaemonImage {
+///     reference: "ghcr.io/example/app:1.0.0".into(),
+///     os: "wasip2".into(),
+///     size: 1024,
+/// };
+/// assert_eq!(image.os, "wasip2");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct DaemonImage {
+    /// The image's repository and tag, as the engine lists it.
+    pub reference: String,
+    /// The image's target OS, e.g. `wasip2`.
+    pub os: String,
+    /// The image's size, in bytes, as the engine reports it.
+    pub size: u64,
+}
+
+/// The fields of `<engine> image inspect` output that are used.
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct InspectedImage {
+    #[serde(default)]
+    architecture: String,
+    #[serde(default)]
+    os: String,
+    #[serde(default)]
+    size: u64,
+}
+
+/// List the wasm images in `engine`'s local storage.
+pub(crate) async fn list_images(engine: ContainerEngine) -> anyhow::Result<Vec<DaemonImage>> {
+    let listed = run(
+        engine,
+        &["image", "ls", "--format", "{{.Repository}}:{{.Tag}}"],
+    )
+    .await?;
+    let references: Vec<String> = String::from_utf8_lossy(&listed)
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.contains("<none>"))
+        .map(str::to_owned)
+        .collect();
+    if references.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut args = vec!["image", "inspect", "--format", "{{json .}}"];
+    args.extend(references.iter().map(String::as_str));
+    let inspected = run(engine, &args).await?;
+    let mut images = Vec::new();
+    for (reference, line) in references
+        .into_iter()
+        .zip(String::from_utf8_lossy(&inspected).lines())
+    {
+        let image: InspectedImage = serde_json::from_str(line)?;
+        if image.architecture == "wasm" {
+            images.push(DaemonImage {
+                reference,
+                os: image.os,
+                size: image.size,
+            });
+        }
+    }
+    Ok(images)
+}
+
+/// Save `image` from `engine`'s local storage and read it.
+pub(crate) async fn save(engine: ContainerEngine, image: &str) -> anyhow::Result<ImageData> {
+    let args = match engine {
+        ContainerEngine::Docker => vec!["save", image],
+        ContainerEngine::Podman => vec!["save", "--format", "oci-archive", image],
+    };
+    let archive = run(engine, &args).await?;
+    Ok(read_layout(image, &archive)?)
+}
+
+/// Run `engine` with `args`, returning what it printed to stdout.
+async fn run(engine: ContainerEngine, args: &[&str]) -> Result<Vec<u8>, ImportError> {
+    let output = tokio::process::Command::new(engine.command())
+        .args(args)
+        .output()
+        .await
+        .map_err(|e| ImportError::EngineUnavailable {
+            engine: engine.command().to_string(),
+            reason: e.to_string(),
+        })?;
+    if !output.status.success() {
+        return Err(ImportError::EngineFailed {
+            engine: engine.command().to_string(),
+            args: args.join(" "),
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        });
+    }
+    Ok(output.stdout)
+}
+
+/// Read `image` from a tar archive in the OCI image layout.
+///
+/// Image indexes are followed to the entry for the `wasm` architecture, or
+/// else the first entry whose manifest the archive holds.
+fn read_layout(image: &str, archive: &[u8]) -> Result<ImageData, ImportError> {
+    let invalid = |reason: String| ImportError::InvalidArchive {
+        image: image.to_string(),
+        reason,
+    };
+    let files = tar::files(archive).map_err(|e| invalid(e.to_string()))?;
+    let blob = |digest: &str| layout_blob(&files, digest).map_err(invalid);
+
+    let index = files
+        .get("index.json")
+        .ok_or_else(|| ImportError::UnsupportedArchive {
+            image: image.to_string(),
+        })?;
+    let mut entries = serde_json::from_slice::<OciImageIndex>(index)
+        .map_err(|e| invalid(format!("index.json: {e}")))?
+        .manifests;
+    for _ in 0..MAX_INDEX_DEPTH {
+        let saved: Vec<&ImageIndexEntry> = entries
+            .iter()
+            .filter(|entry| files.contains_key(blob_path(&entry.digest).as_str()))
+            .collect();
+        let entry = saved
+            .iter()
+            .find(|entry| {
+                entry
+                    .platform
+                    .as_ref()
+                    .is_some_and(|p| p.architecture.to_string() == "wasm")
+            })
+            .or_else(|| saved.first())
+            .ok_or_else(|| invalid("the archive holds no manifest".to_string()))?;
+        let data = blob(&entry.digest)?;
+
+        if matches!(
+            entry.media_type.as_str(),
+            OCI_IMAGE_INDEX_MEDIA_TYPE | IMAGE_MANIFEST_LIST_MEDIA_TYPE
+        ) {
+            entries = serde_json::from_slice::<OciImageIndex>(data)
+                .map_err(|e| invalid(format!("image index {}: {e}", entry.digest)))?
+                .manifests;
+            continue;
+        }
+
+        let manifest: OciImageManifest = serde_json::from_slice(data)
+            .map_err(|e| invalid(format!("manifest {}: {e}", entry.digest)))?;
+        return image_data(&entry.digest, manifest, blob);
+    }
+    Err(invalid("image indexes are nested too deeply".to_string()))
+}
+
+/// The path of the blob with `digest` in an OCI image layout.
+fn blob_path(digest: &str) -> String {
+    format!("blobs/{}", digest.replacen(':', "/", 1))
+}
+
+/// The blob with `digest` in the files of an OCI image layout, checked
+/// against its digest; otherwise what's wrong with it.
+fn layout_blob<'a>(files: &HashMap<String, &'a [u8]>, digest: &str) -> Result<&'a [u8], String> {
+    let data = files
+        .get(blob_path(digest).as_str())
+        .copied()
+        .ok_or_else(|| format!("blob {digest} is missing"))?;
+    if sha256_digest(data) != digest {
+        return Err(format!("blob {digest} does not match its digest"));
+    }
+    Ok(data)
+}
+
+/// The image with `manifest`, whose digest is `digest`, reading its config
+/// and layers with `blob`.
+fn image_data<'a>(
+    digest: &str,
+    manifest: OciImageManifest,
+    blob: impl Fn(&str) -> Result<&'a [u8], ImportError>,
+) -> Result<ImageData, ImportError> {
+    let config = Config::new(
+        blob(&manifest.config.digest)?.to_vec(),
+        manifest.config.media_type.clone(),
+        manifest.config.annotations.clone(),
+    );
+    let layers = manifest
+        .layers
+        .iter()
+        .map(|layer| {
+            Ok(ImageLayer::new(
+                blob(&layer.digest)?.to_vec(),
+                layer.media_type.clone(),
+                layer.annotations.clone(),
+            ))
+        })
+        .collect::<Result<_, ImportError>>()?;
+    Ok(ImageData {
+        layers,
+        digest: Some(digest.to_string()),
+        config,
+        manifest: Some(manifest),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const COMPONENT: [u8; 8] = [0x00, 0x61, 0x73, 0x6d, 0x0d, 0x00, 0x01, 0x00];
+
+    /// Build an archive in the OCI image layout, with the image manifest
+    /// behind an image index like `docker save` writes.
+    fn layout(wasm: &[u8]) -> (Vec<u8>, String) {
+        let config = br#"{"architecture":"wasm","os":"wasip2"}"#;
+        let manifest = serde_json::to_vec(&json!({
+            "schemaVersion": 2,
+            "mediaType": "application/vnd.oci.image.manifest.v1+json",
+            "config": {
+                "mediaType": "application/vnd.wasm.config.v0+json",
+                "digest": sha256_digest(config),
+                "size": config.len(),
+            },
+            "layers": [{
+                "mediaType": "application/wasm",
+                "digest": sha256_digest(&COMPONENT),
+                "size": COMPONENT.len(),
+            }],
+        }))
+        .unwrap();
+        let manifest_digest = sha256_digest(&manifest);
+        let image_index = serde_json::to_vec(&json!({
+            "schemaVersion": 2,
+            "mediaType": "application/vnd.oci.image.index.v1+json",
+            "manifests": [
+                {
+                    "mediaType": "application/vnd.oci.image.manifest.v1+json",
+                    "digest": "sha256:0000000000000000000000000000000000000000000000000000000000000000",
+                    "size": 1,
+                    "platform": { "architecture": "amd64", "os": "linux" },
+                },
+                {
+                    "mediaType": "application/vnd.oci.image.manifest.v1+json",
+                    "digest": manifest_digest,
+                    "size": manifest.len(),
+                    "platform": { "architecture": "wasm", "os": "wasip2" },
+                },
+            ],
+        }))
+        .unwrap();
+        let index = serde_json::to_vec(&json!({
+            "schemaVersion": 2,
+            "manifests": [{
+                "mediaType": "application/vnd.oci.image.index.v1+json",
+                "digest": sha256_digest(&image_index),
+                "size": image_index.len(),
+            }],
+        }))
+        .unwrap();
+
+        let path = |data: &[u8]| format!("blobs/{}", sha256_digest(data).replacen(':', "/", 1));
+        let archive = tar::tests::archive(&[
+            ("index.json", &index),
+            (&path(&image_index), &image_index),
+            (&path(&manifest), &manifest),
+            (&path(config), config),
+            (&path(&COMPONENT), wasm),
+        ]);
+        (archive, manifest_digest)
+    }
+
+    #[test]
+    fn read_layout_follows_indexes_to_the_wasm_manifest() {
+        let (archive, digest) = layout(&COMPONENT);
+        let image = read_layout("app:1", &archive).unwrap();
+        assert_eq!(image.digest, Some(digest));
+        assert_eq!(image.layers.len(), 1);
+        assert_eq!(image.layers.first().unwrap().data.as_ref(), COMPONENT);
+        assert_eq!(
+            image.config.media_type,
+            "application/vnd.wasm.config.v0+json"
+        );
+    }
+
+    #[test]
+    fn read_layout_rejects_tampered_blobs() {
+        let (archive, _) = layout(&[0; 8]);
+        let Err(err) = read_layout("app:1", &archive) else {
+            panic!("the archive should be rejected");
+        };
+        assert!(matches!(err, ImportError::InvalidArchive { .. }), "{err}");
+    }
+
+    #[test]
+    fn read_layout_requires_an_oci_layout() {
+        let archive = tar::tests::archive(&[("manifest.json", b"[]")]);
+        let Err(err) = read_layout("app:1", &archive) else {
+            panic!("the archive should be rejected");
+        };
+        assert!(
+            matches!(err, ImportError::UnsupportedArchive { .. }),
+            "{err}"
+        );
+    }
+}
@@ -0,0 +1,121 @@
+//! A minimal reader for the tar archives written by `docker save`, and a
+//! writer for the bundles written by `component registry export`.
+
+use std::collections::HashMap;
+
+/// The size of a tar header and of the blocks file contents are padded to.
+const BLOCK: usize = 512;
+
+/// Read every regular file in a tar archive, keyed by path.
+///
+/// Only what image archives use is supported: ustar and GNU headers, with
+/// paths that fit the header's name and prefix fields. Directories, links,
+/// and PAX extended headers are skipped.
+pub(crate) fn files(archive: &[u8]) -> anyhow::Result<HashMap<String, &[u8]>> {
+    let mut files = HashMap::new();
+    let mut offset = 0;
+    while let Some(header) = archive.get(offset..offset + BLOCK) {
+        // The archive ends with zero-filled blocks.
+        if header.iter().all(|b| *b == 0) {
+            break;
+        }
+        let size = usize::try_from(octal(field(header, 124, 12)?)?)?;
+        let data_start = offset + BLOCK;
+        let data = archive
+            .get(data_start..data_start + size)
+            .ok_or_else(|| anyhow::anyhow!("tar entry at offset {offset} is truncated"))?;
+
+        // `0` and NUL both mark a regular file.
+        if matches!(header.get(156), Some(b'0' | 0)) {
+            let name = text(field(header, 0, 100)?);
+            let prefix = text(field(header, 345, 155)?);
+            let path = if prefix.is_empty() {
+                name
+            } else {
+                format!("{prefix}/{name}")
+            };
+            files.insert(path.trim_start_matches("./").to_string(), data);
+        }
+        offset = data_start + size.div_ceil(BLOCK) * BLOCK;
+    }
+    Ok(files)
+}
+
+/// Write `files` to a ustar archive, in order.
+///
+/// Paths must fit the header's 100-byte name field, which the paths of an
+/// OCI image layout always do.
+pub(crate) fn archive(files: &[(&str, &[u8])]) -> anyhow::Result<Vec<u8>> {
+    let mut archive = Vec::new();
+    for (path, data) in files {
+        anyhow::ensure!(path.len() < 100, "tar entry path '{path}' is too long");
+        let mut header = Vec::with_capacity(BLOCK);
+        header.extend_from_slice(path.as_bytes());
+        header.resize(100, 0);
+        header.extend_from_slice(b"0000644\0");
+        header.resize(124, 0);
+        header.extend_from_slice(format!("{:011o}\0", data.len()).as_bytes());
+        header.resize(156, 0);
+        header.push(b'0');
+        header.resize(257, 0);
+        header.extend_from_slice(b"ustar\x0000");
+        header.resize(BLOCK, 0);
+        // The checksum is taken with its own field filled with spaces.
+        header.splice(148..156, *b"        ");
+        let checksum: u32 = header.iter().map(|b| u32::from(*b)).sum();
+        header.splice(148..156, format!("{checksum:06o}\0 ").into_bytes());
+        archive.extend_from_slice(&header);
+        archive.extend_from_slice(data);
+        archive.resize(archive.len().div_ceil(BLOCK) * BLOCK, 0);
+    }
+    archive.resize(archive.len() + 2 * BLOCK, 0);
+    Ok(archive)
+}
+
+fn field(header: &[u8], start: usize, len: usize) -> anyhow::Result<&[u8]> {
+    header
+        .get(start..start + len)
+        .ok_or_else(|| anyhow::anyhow!("tar header is truncated"))
+}
+
+/// A NUL-terminated string field.
+fn text(field: &[u8]) -> String {
+    let end = field.iter().position(|b| *b == 0).unwrap_or(field.len());
+    String::from_utf8_lossy(field.get(..end).unwrap_or_default()).into_owned()
+}
+
+/// A NUL- or space-terminated octal number field.
+fn octal(field: &[u8]) -> anyhow::Result<u64> {
+    let digits = text(field);
+    let digits = digits.trim_matches(|c: char| c == ' ' || c == '\0');
+    if digits.is_empty() {
+        return Ok(0);
+    }
+    Ok(u64::from_str_radix(digits, 8)?)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Build a tar archive holding `files`.
+    pub(crate) fn archive(files: &[(&str, &[u8])]) -> Vec<u8> {
+        super::archive(files).unwrap()
+    }
+
+    #[test]
+    fn reads_files_by_path() {
+        let archive = archive(&[("index.json", b"{}"), ("blobs/sha256/abc", &[7; 600])]);
+        let files = files(&archive).unwrap();
+        assert_eq!(files.len(), 2);
+        assert_eq!(files.get("index.json"), Some(&&b"{}"[..]));
+        assert_eq!(files.get("blobs/sha256/abc"), Some(&&[7; 600][..]));
+    }
+
+    #[test]
+    fn rejects_truncated_archives() {
+        let mut archive = archive(&[("index.json", &[1; 100])]);
+        archive.truncate(BLOCK + 10);
+        assert!(files(&archive).is_err());
+    }
+}
@@ -8,6 +8,7 @@
 //! ```no_run
 //! use component_package_manager::Config;
 //! use component_package_manager::manager::Manager;
+//! use component_package_manager::oci::{SortBy, SortDirection};
 //! use std::path::Path;
 //!
 //! #[tokio::main]
@@ -27,8 +28,8 @@
 //!         println!("Installed: {}", path.display());
 //!     }
 //!
-//!     // List all cached images
-//!     let images = manager.list_all()?;
+//!     // List all cached images, largest first
+//!     let images = manager.list_all(SortBy::Size, SortDirection::Descending)?;
 //!     for image in &images {
 //!         println!("{} ({} bytes)", image.reference(), image.size_on_disk);
 //!     }
@@ -43,24 +44,42 @@ pub mod components;
 pub mod compose;
 mod config;
 mod credential_helper;
+mod errors;
+mod events;
+/// Importing wasm images from a local Docker or Podman daemon.
+pub mod import;
 /// Core manager functionality for pulling, installing, and listing packages.
 pub mod manager;
 pub mod oci;
+#[cfg(feature = "keyring")]
+mod os_keyring;
 /// Permission resolution for running WebAssembly components.
 pub mod permissions;
+/// Content trust policy (`policy.toml`) enforced when pulling.
+pub mod policy;
 mod progress;
 /// Publish helpers: build a [`crate::publish::PublishPlan`] from a
 /// `wasm.toml` manifest and push the artifact to an OCI registry.
 pub mod publish;
 /// Dependency resolver using the PubGrub version-solving algorithm.
 pub mod resolver;
+/// Read-only OCI registry over the local store (requires the `serve` feature).
+#[cfg(feature = "serve")]
+pub mod serve;
+/// Cosign-compatible signing of artifacts in a registry.
+pub mod sign;
 /// Storage layer for persisting package metadata and state.
 pub mod storage;
 pub mod types;
 mod xdg;
 
-pub use config::{Config, RegistryConfig, RunConfig};
+pub use config::{
+    CONFIG_VAR, Config, DATA_DIR_VAR, DEFAULT_REGISTRY_VAR, NetworkConfig, OFFLINE_VAR, PullConfig,
+    REGISTRY_TOKEN_VAR, RegistryConfig, RunConfig,
+};
 pub use credential_helper::{CredentialError, CredentialHelper};
+pub use errors::{PackageManagerError, Result};
+pub use events::StoreEvent;
 pub use oci_client::Reference;
 pub use progress::ProgressEvent;
 pub(crate) use xdg::xdg_config_home;
@@ -0,0 +1,342 @@
+//! WIT compatibility between one component's exports and another's imports.
+//!
+//! The comparison works on decoded [`wit_parser`] worlds and contains no IO,
+//! so it can be unit-tested against WIT text directly.
+
+use wit_parser::{InterfaceId, Resolve, WorldId, WorldItem};
+
+/// Result of [`Manager::check_compat`](super::Manager::check_compat).
+///
+/// Each imported interface of the consumer lands in exactly one bucket.
+/// Interfaces are named as `namespace:package/interface@version`.
+///
+/// # Example
+///
+/// ```
+/// use component_package_manager::manager::CompatReport;
+///
+/// let report = CompatReport {
+///     satisfied: vec!["wasi:http/incoming-handler@0.2.0".to_string()],
+///     unsatisfied: vec!["wasi:cli/stdout@0.2.0".to_string()],
+///     mismatched: vec![],
+/// };
+/// assert!(report.is_compatible());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CompatReport {
+    /// Imports the provider exports with a compatible version and every
+    /// function and type the consumer uses.
+    pub satisfied: Vec<String>,
+    /// Imports the provider does not export at all. These must be supplied
+    /// by another component or the host.
+    pub unsatisfied: Vec<String>,
+    /// Imports the provider exports, but not in a form the consumer can use.
+    pub mismatched: Vec<CompatMismatch>,
+}
+
+impl CompatReport {
+    /// Returns `true` when no import is mismatched.
+    ///
+    /// Unsatisfied imports don't make two components incompatible; they
+    /// are simply left for the host or another component to provide.
+    #[must_use]
+    pub fn is_compatible(&self) -> bool {
+        self.mismatched.is_empty()
+    }
+}
+
+/// An imported interface that the provider exports in an unusable form.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompatMismatch {
+    /// The provider exports the interface at a semver-incompatible version,
+    /// or only one side declares a version.
+    Version {
+        /// The interface the consumer imports.
+        import: String,
+        /// The interface the provider exports.
+        export: String,
+    },
+    /// The provider's export lacks functions or types the consumer imports.
+    MissingItems {
+        /// The interface the consumer imports.
+        import: String,
+        /// The interface the provider exports.
+        export: String,
+        /// Names of the missing functions and types.
+        items: Vec<String>,
+    },
+}
+
+/// Compare the exports of `provider` against the imports of `consumer`.
+///
+/// Only interface imports named by a package are considered; inline
+/// interfaces, bare functions, and types have no identity to match on.
+pub(crate) fn compare_worlds(
+    provider: (&Resolve, WorldId),
+    consumer: (&Resolve, WorldId),
+) -> CompatReport {
+    let (p_resolve, p_world) = provider;
+    let (c_resolve, c_world) = consumer;
+    let mut report = CompatReport::default();
+
+    let Some(c_world) = c_resolve.worlds.get(c_world) else {
+        return report;
+    };
+    let exports: Vec<InterfaceId> = p_resolve
+        .worlds
+        .get(p_world)
+        .map(|w| w.exports.values().filter_map(interface_id).collect())
+        .unwrap_or_default();
+
+    for import_id in c_world.imports.values().filter_map(interface_id) {
+        let Some(import) = InterfaceName::of(c_resolve, import_id) else {
+            continue;
+        };
+        let import_name = import.to_string();
+
+        let Some((export_id, export)) = exports.iter().find_map(|&id| {
+            InterfaceName::of(p_resolve, id)
+                .filter(|e| e.package == import.package && e.interface == import.interface)
+                .map(|e| (id, e))
+        }) else {
+            report.unsatisfied.push(import_name);
+            continue;
+        };
+
+        if !versions_compatible(export.version.as_ref(), import.version.as_ref()) {
+            report.mismatched.push(CompatMismatch::Version {
+                import: import_name,
+                export: export.to_string(),
+            });
+            continue;
+        }
+
+        let missing = missing_items(p_resolve, export_id, c_resolve, import_id);
+        if missing.is_empty() {
+            report.satisfied.push(import_name);
+        } else {
+            report.mismatched.push(CompatMismatch::MissingItems {
+                import: import_name,
+                export: export.to_string(),
+                items: missing,
+            });
+        }
+    }
+
+    report
+}
+
+/// The interface ID of a world item, if it is an interface.
+fn interface_id(item: &WorldItem) -> Option<InterfaceId> {
+    match item {
+        WorldItem::Interface { id, .. } => Some(*id),
+        WorldItem::Function(_) | WorldItem::Type { .. } => None,
+    }
+}
+
+/// The package-qualified name of an interface.
+struct InterfaceName {
+    package: String,
+    interface: String,
+    version: Option<semver::Version>,
+}
+
+impl InterfaceName {
+    fn of(resolve: &Resolve, id: InterfaceId) -> Option<Self> {
+        let iface = resolve.interfaces.get(id)?;
+        let pkg = resolve.packages.get(iface.package?)?;
+        Some(Self {
+            package: format!("{}:{}", pkg.name.namespace, pkg.name.name),
+            interface: iface.name.clone()?,
+            version: pkg.name.version.clone(),
+        })
+    }
+}
+
+impl std::fmt::Display for InterfaceName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.package, self.interface)?;
+        if let Some(version) = &self.version {
+            write!(f, "@{version}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Whether an export at `export` can satisfy an import at `import`.
+///
+/// Follows the component model's semver rules: versions must share a
+/// major version (or, for `0.x`, a minor version), and the export must be
+/// at least as new as the import.
+fn versions_compatible(export: Option<&semver::Version>, import: Option<&semver::Version>) -> bool {
+    match (export, import) {
+        (None, None) => true,
+        (Some(export), Some(import)) => {
+            let same_track = if import.major == 0 {
+                export.major == 0 && export.minor == import.minor
+            } else {
+                export.major == import.major
+            };
+            same_track && export >= import
+        }
+        _ => false,
+    }
+}
+
+/// Names of functions and types in the imported interface that the exported
+/// interface doesn't define.
+fn missing_items(
+    p_resolve: &Resolve,
+    export: InterfaceId,
+    c_resolve: &Resolve,
+    import: InterfaceId,
+) -> Vec<String> {
+    let (Some(export), Some(import)) = (
+        p_resolve.interfaces.get(export),
+        c_resolve.interfaces.get(import),
+    ) else {
+        return Vec::new();
+    };
+    import
+        .functions
+        .keys()
+        .filter(|name| !export.functions.contains_key(*name))
+        .chain(
+            import
+                .types
+                .keys()
+                .filter(|name| !export.types.contains_key(*name)),
+        )
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parse `wit` and select the world named `world`.
+    fn world(wit: &str, world: &str) -> (Resolve, WorldId) {
+        let mut resolve = Resolve::new();
+        let pkg = resolve.push_str("test.wit", wit).unwrap();
+        let id = resolve.select_world(&[pkg], Some(world)).unwrap();
+        (resolve, id)
+    }
+
+    const HANDLER_020: &str = "
+        package wasi:http@0.2.0;
+        interface handler {
+            handle: func(req: string) -> string;
+        }
+        interface types {
+            type fields = list<string>;
+        }
+    ";
+
+    /// Parse `deps` followed by `wit` and select the world named `name`.
+    fn with_deps(deps: &str, wit: &str, name: &str) -> (Resolve, WorldId) {
+        let mut resolve = Resolve::new();
+        resolve.push_str("dep.wit", deps).unwrap();
+        let pkg = resolve.push_str("main.wit", wit).unwrap();
+        let id = resolve.select_world(&[pkg], Some(name)).unwrap();
+        (resolve, id)
+    }
+
+    #[test]
+    fn matching_export_satisfies_import() {
+        let provider = with_deps(
+            HANDLER_020,
+            "package example:server; world server { export wasi:http/handler@0.2.0; }",
+            "server",
+        );
+        let consumer = with_deps(
+            HANDLER_020,
+            "package example:client; world client {
+                import wasi:http/handler@0.2.0;
+                import wasi:http/types@0.2.0;
+            }",
+            "client",
+        );
+
+        let report = compare_worlds((&provider.0, provider.1), (&consumer.0, consumer.1));
+        assert_eq!(report.satisfied, vec!["wasi:http/handler@0.2.0"]);
+        assert_eq!(report.unsatisfied, vec!["wasi:http/types@0.2.0"]);
+        assert!(report.mismatched.is_empty());
+        assert!(report.is_compatible());
+    }
+
+    #[test]
+    fn incompatible_version_is_mismatched() {
+        let provider = with_deps(
+            HANDLER_020,
+            "package example:server; world server { export wasi:http/handler@0.2.0; }",
+            "server",
+        );
+        let consumer = with_deps(
+            &HANDLER_020.replace("0.2.0", "0.3.0"),
+            "package example:client; world client { import wasi:http/handler@0.3.0; }",
+            "client",
+        );
+
+        let report = compare_worlds((&provider.0, provider.1), (&consumer.0, consumer.1));
+        assert_eq!(
+            report.mismatched,
+            vec![CompatMismatch::Version {
+                import: "wasi:http/handler@0.3.0".to_string(),
+                export: "wasi:http/handler@0.2.0".to_string(),
+            }]
+        );
+        assert!(!report.is_compatible());
+    }
+
+    #[test]
+    fn missing_function_is_mismatched() {
+        let provider = with_deps(
+            HANDLER_020,
+            "package example:server; world server { export wasi:http/handler@0.2.0; }",
+            "server",
+        );
+        let consumer = with_deps(
+            &HANDLER_020.replace(
+                "handle: func(req: string) -> string;",
+                "handle: func(req: string) -> string;\n            close: func();",
+            ),
+            "package example:client; world client { import wasi:http/handler@0.2.0; }",
+            "client",
+        );
+
+        let report = compare_worlds((&provider.0, provider.1), (&consumer.0, consumer.1));
+        assert_eq!(
+            report.mismatched,
+            vec![CompatMismatch::MissingItems {
+                import: "wasi:http/handler@0.2.0".to_string(),
+                export: "wasi:http/handler@0.2.0".to_string(),
+                items: vec!["close".to_string()],
+            }]
+        );
+    }
+
+    #[test]
+    fn inline_imports_are_ignored() {
+        let provider = world("package example:server; world server {}", "server");
+        let consumer = world(
+            "package example:client; world client { import log: func(msg: string); }",
+            "client",
+        );
+
+        let report = compare_worlds((&provider.0, provider.1), (&consumer.0, consumer.1));
+        assert_eq!(report, CompatReport::default());
+    }
+
+    #[test]
+    fn semver_compatibility_rules() {
+        let v = |s: &str| semver::Version::parse(s).unwrap();
+        assert!(versions_compatible(Some(&v("0.2.3")), Some(&v("0.2.0"))));
+        assert!(!versions_compatible(Some(&v("0.2.0")), Some(&v("0.2.3"))));
+        assert!(!versions_compatible(Some(&v("0.3.0")), Some(&v("0.2.0"))));
+        assert!(versions_compatible(Some(&v("1.4.0")), Some(&v("1.1.0"))));
+        assert!(!versions_compatible(Some(&v("2.0.0")), Some(&v("1.1.0"))));
+        assert!(versions_compatible(None, None));
+        assert!(!versions_compatible(None, Some(&v("1.0.0"))));
+    }
+}
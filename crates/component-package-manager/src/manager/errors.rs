@@ -42,6 +42,34 @@ pub enum ManagerError {
     )]
     OfflineIndex,
 
+    /// An attempt was made to publish a package while in offline mode.
+    #[diagnostic(
+        code(component::manager::offline_publish),
+        help("run without `--offline` to publish packages to the registry")
+    )]
+    OfflinePublish,
+
+    /// An attempt was made to notify the meta-registry while in offline mode.
+    #[diagnostic(
+        code(component::manager::offline_notify),
+        help("run without `--offline` to notify the meta-registry")
+    )]
+    OfflineNotify,
+
+    /// An attempt was made to sign a package while in offline mode.
+    #[diagnostic(
+        code(component::manager::offline_sign),
+        help("run without `--offline` to sign packages in the registry")
+    )]
+    OfflineSign,
+
+    /// An attempt was made to verify a package while in offline mode.
+    #[diagnostic(
+        code(component::manager::offline_verify),
+        help("run without `--offline` to verify packages in the registry")
+    )]
+    OfflineVerify,
+
     /// A previously indexed package could not be retrieved from the database.
     #[diagnostic(
         code(component::manager::index_retrieval_failed),
@@ -89,6 +117,121 @@ pub enum ManagerError {
         /// Human-readable hint about available tags.
         hint: String,
     },
+
+    /// The reference has not been pulled into the local store.
+    #[diagnostic(
+        code(component::manager::not_in_store),
+        help("pull it first with `component registry pull {reference}`")
+    )]
+    NotInStore {
+        /// The reference that was looked up.
+        reference: String,
+    },
+
+    /// The stored image does not contain a decodable Wasm component.
+    #[diagnostic(
+        code(component::manager::not_a_component),
+        help(
+            "'{reference}' must be a component with a WIT world, not a core module or WIT package"
+        )
+    )]
+    NotAComponent {
+        /// The reference that was inspected.
+        reference: String,
+    },
+
+    /// Another process held the store lock for longer than the wait timeout.
+    #[diagnostic(
+        code(component::manager::store_locked),
+        help("wait for the other process to finish and try again")
+    )]
+    StoreLocked {
+        /// The process holding the lock, if it could be determined.
+        pid: Option<u32>,
+    },
+
+    /// A digest prefix has no hex digits, so it would match every image.
+    #[diagnostic(
+        code(component::manager::invalid_digest_prefix),
+        help("pass at least one hex digit, e.g. `sha256:ab12` or `ab12`")
+    )]
+    InvalidDigestPrefix {
+        /// The prefix that was passed.
+        prefix: String,
+    },
+
+    /// A locked package's name or version would place it outside the vendor
+    /// tree.
+    #[diagnostic(
+        code(component::manager::invalid_vendor_name),
+        help(
+            "fix the entry for '{name}' in the lockfile; names may not contain `.` or `..` segments"
+        )
+    )]
+    InvalidVendorName {
+        /// The package name from the lockfile.
+        name: String,
+        /// The package version from the lockfile.
+        version: String,
+    },
+
+    /// A reference could not be parsed, even after expanding it with the
+    /// configured default registry.
+    #[diagnostic(
+        code(component::manager::invalid_reference),
+        help("use a reference such as `ghcr.io/example/component:1.0.0`")
+    )]
+    InvalidReference {
+        /// The reference as it was passed.
+        reference: String,
+        /// Why it could not be parsed.
+        reason: String,
+    },
+
+    /// The reference points at an OCI image index with no entry that is
+    /// recognizably a WebAssembly artifact.
+    #[diagnostic(
+        code(component::manager::unresolved_image_index),
+        help(
+            "pick an entry with `--platform` or `--artifact-type`, or pin one by digest: {candidates}"
+        )
+    )]
+    UnresolvedImageIndex {
+        /// The reference that resolved to an image index.
+        reference: String,
+        /// Human-readable list of the index entries.
+        candidates: String,
+    },
+
+    /// No entry of an OCI image index matches the requested platform or
+    /// artifact type.
+    #[diagnostic(
+        code(component::manager::no_matching_index_entry),
+        help("the index has these entries: {candidates}")
+    )]
+    NoMatchingIndexEntry {
+        /// The reference that resolved to an image index.
+        reference: String,
+        /// The platform and artifact type that were asked for.
+        selection: String,
+        /// Human-readable list of the index entries.
+        candidates: String,
+    },
+
+    /// More than one entry of an OCI image index matches the requested
+    /// platform or artifact type.
+    #[diagnostic(
+        code(component::manager::ambiguous_index_entry),
+        help("narrow the selection, or pin one of the matching entries by digest: {candidates}")
+    )]
+    AmbiguousIndexEntry {
+        /// The reference that resolved to an image index.
+        reference: String,
+        /// The platform and artifact type that were asked for.
+        selection: String,
+        /// Human-readable list of the matching entries.
+        candidates: String,
+    },
 }
 
 impl std::fmt::Display for ManagerError {
@@ -100,6 +243,18 @@ impl std::fmt::Display for ManagerError {
             ManagerError::OfflineIndex => {
                 write!(f, "cannot index packages in offline mode")
             }
+            ManagerError::OfflinePublish => {
+                write!(f, "cannot publish in offline mode")
+            }
+            ManagerError::OfflineNotify => {
+                write!(f, "cannot notify meta-registry in offline mode")
+            }
+            ManagerError::OfflineSign => {
+                write!(f, "cannot sign packages in offline mode")
+            }
+            ManagerError::OfflineVerify => {
+                write!(f, "cannot verify packages in offline mode")
+            }
             ManagerError::IndexRetrievalFailed => {
                 write!(f, "failed to retrieve indexed package")
             }
@@ -123,6 +278,53 @@ impl std::fmt::Display for ManagerError {
                     "tag '{tag}' not found for {registry}/{repository}\n  help: {hint}"
                 )
             }
+            ManagerError::NotInStore { reference } => {
+                write!(f, "'{reference}' is not in the local store")
+            }
+            ManagerError::NotAComponent { reference } => {
+                write!(f, "'{reference}' is not a Wasm component")
+            }
+            ManagerError::StoreLocked { pid: Some(pid) } => {
+                write!(f, "store is in use by PID {pid}")
+            }
+            ManagerError::StoreLocked { pid: None } => {
+                write!(f, "store is in use by another process")
+            }
+            ManagerError::InvalidDigestPrefix { prefix } => {
+                write!(f, "'{prefix}' is not a valid digest prefix")
+            }
+            ManagerError::InvalidVendorName { name, version } => {
+                write!(f, "'{name}@{version}' is not a valid package to vendor")
+            }
+            ManagerError::InvalidReference { reference, reason } => {
+                write!(f, "'{reference}' is not a valid reference: {reason}")
+            }
+            ManagerError::UnresolvedImageIndex { reference, .. } => {
+                write!(
+                    f,
+                    "'{reference}' is an image index without a WebAssembly entry"
+                )
+            }
+            ManagerError::NoMatchingIndexEntry {
+                reference,
+                selection,
+                ..
+            } => {
+                write!(
+                    f,
+                    "no entry of the image index '{reference}' matches {selection}"
+                )
+            }
+            ManagerError::AmbiguousIndexEntry {
+                reference,
+                selection,
+                ..
+            } => {
+                write!(
+                    f,
+                    "more than one entry of the image index '{reference}' matches {selection}"
+                )
+            }
         }
     }
 }
@@ -163,6 +365,58 @@ mod tests {
             "OfflineIndex must have a help message"
         );
 
+        let offline_publish = ManagerError::OfflinePublish;
+        assert_eq!(
+            offline_publish
+                .code()
+                .expect("OfflinePublish must have a diagnostic code")
+                .to_string(),
+            "component::manager::offline_publish",
+        );
+        assert!(
+            offline_publish.help().is_some(),
+            "OfflinePublish must have a help message"
+        );
+
+        let offline_notify = ManagerError::OfflineNotify;
+        assert_eq!(
+            offline_notify
+                .code()
+                .expect("OfflineNotify must have a diagnostic code")
+                .to_string(),
+            "component::manager::offline_notify",
+        );
+        assert!(
+            offline_notify.help().is_some(),
+            "OfflineNotify must have a help message"
+        );
+
+        let offline_sign = ManagerError::OfflineSign;
+        assert_eq!(
+            offline_sign
+                .code()
+                .expect("OfflineSign must have a diagnostic code")
+                .to_string(),
+            "component::manager::offline_sign",
+        );
+        assert!(
+            offline_sign.help().is_some(),
+            "OfflineSign must have a help message"
+        );
+
+        let offline_verify = ManagerError::OfflineVerify;
+        assert_eq!(
+            offline_verify
+                .code()
+                .expect("OfflineVerify must have a diagnostic code")
+                .to_string(),
+            "component::manager::offline_verify",
+        );
+        assert!(
+            offline_verify.help().is_some(),
+            "OfflineVerify must have a help message"
+        );
+
         let index_failed = ManagerError::IndexRetrievalFailed;
         assert_eq!(
             index_failed
@@ -228,5 +482,146 @@ mod tests {
             manifest_not_found.to_string(),
             "tag 'latest' not found for ghcr.io/example/component\n  help: available tags: 1.0.0, 2.0.0",
         );
+
+        let not_in_store = ManagerError::NotInStore {
+            reference: "ghcr.io/example/component:1.0.0".to_string(),
+        };
+        assert_eq!(
+            not_in_store
+                .code()
+                .expect("NotInStore must have a diagnostic code")
+                .to_string(),
+            "component::manager::not_in_store",
+        );
+        assert!(
+            not_in_store.help().is_some(),
+            "NotInStore must have a help message"
+        );
+
+        let not_a_component = ManagerError::NotAComponent {
+            reference: "ghcr.io/example/component:1.0.0".to_string(),
+        };
+        assert_eq!(
+            not_a_component
+                .code()
+                .expect("NotAComponent must have a diagnostic code")
+                .to_string(),
+            "component::manager::not_a_component",
+        );
+        assert!(
+            not_a_component.help().is_some(),
+            "NotAComponent must have a help message"
+        );
+
+        let store_locked = ManagerError::StoreLocked { pid: Some(4242) };
+        assert_eq!(
+            store_locked
+                .code()
+                .expect("StoreLocked must have a diagnostic code")
+                .to_string(),
+            "component::manager::store_locked",
+        );
+        assert!(
+            store_locked.help().is_some(),
+            "StoreLocked must have a help message"
+        );
+        assert_eq!(store_locked.to_string(), "store is in use by PID 4242");
+
+        let invalid_prefix = ManagerError::InvalidDigestPrefix {
+            prefix: "sha256:".to_string(),
+        };
+        assert_eq!(
+            invalid_prefix
+                .code()
+                .expect("InvalidDigestPrefix must have a diagnostic code")
+                .to_string(),
+            "component::manager::invalid_digest_prefix",
+        );
+        assert!(
+            invalid_prefix.help().is_some(),
+            "InvalidDigestPrefix must have a help message"
+        );
+
+        let invalid_vendor_name = ManagerError::InvalidVendorName {
+            name: "..".to_string(),
+            version: String::new(),
+        };
+        assert_eq!(
+            invalid_vendor_name
+                .code()
+                .expect("InvalidVendorName must have a diagnostic code")
+                .to_string(),
+            "component::manager::invalid_vendor_name",
+        );
+        assert!(
+            invalid_vendor_name.help().is_some(),
+            "InvalidVendorName must have a help message"
+        );
+
+        let invalid_reference = ManagerError::InvalidReference {
+            reference: "Example/App".to_string(),
+            reason: "repository name must be lowercase".to_string(),
+        };
+        assert_eq!(
+            invalid_reference
+                .code()
+                .expect("InvalidReference must have a diagnostic code")
+                .to_string(),
+            "component::manager::invalid_reference",
+        );
+        assert!(
+            invalid_reference.help().is_some(),
+            "InvalidReference must have a help message"
+        );
+
+        let unresolved_index = ManagerError::UnresolvedImageIndex {
+            reference: "ghcr.io/example/component:1.0.0".to_string(),
+            candidates: "sha256:aaa (linux/amd64)".to_string(),
+        };
+        assert_eq!(
+            unresolved_index
+                .code()
+                .expect("UnresolvedImageIndex must have a diagnostic code")
+                .to_string(),
+            "component::manager::unresolved_image_index",
+        );
+        assert!(
+            unresolved_index.help().is_some(),
+            "UnresolvedImageIndex must have a help message"
+        );
+
+        let no_matching_entry = ManagerError::NoMatchingIndexEntry {
+            reference: "ghcr.io/example/component:1.0.0".to_string(),
+            selection: "platform 'wasip2/wasm'".to_string(),
+            candidates: "sha256:aaa (linux/amd64)".to_string(),
+        };
+        assert_eq!(
+            no_matching_entry
+                .code()
+                .expect("NoMatchingIndexEntry must have a diagnostic code")
+                .to_string(),
+            "component::manager::no_matching_index_entry",
+        );
+        assert!(
+            no_matching_entry.help().is_some(),
+            "NoMatchingIndexEntry must have a help message"
+        );
+
+        let ambiguous_entry = ManagerError::AmbiguousIndexEntry {
+            reference: "ghcr.io/example/component:1.0.0".to_string(),
+            selection: "platform 'linux/amd64'".to_string(),
+            candidates: "sha256:aaa (linux/amd64), sha256:bbb (linux/amd64)".to_string(),
+        };
+        assert_eq!(
+            ambiguous_entry
+                .code()
+                .expect("AmbiguousIndexEntry must have a diagnostic code")
+                .to_string(),
+            "component::manager::ambiguous_index_entry",
+        );
+        assert!(
+            ambiguous_entry.help().is_some(),
+            "AmbiguousIndexEntry must have a help message"
+        );
     }
 }
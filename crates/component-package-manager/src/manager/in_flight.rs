@@ -0,0 +1,95 @@
+//! Sharing layer downloads between concurrent pulls.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use tokio::sync::OnceCell;
+
+/// A layer's bytes, set once its download finishes.
+type SharedLayer = Arc<OnceCell<Arc<Vec<u8>>>>;
+
+/// Layers downloaded, or being downloaded, by any of a set of concurrent
+/// pulls, keyed by digest.
+///
+/// The first pull that needs a layer downloads it; pulls that need the same
+/// layer meanwhile wait for that download instead of starting their own.
+#[derive(Debug, Default)]
+pub(crate) struct LayersInFlight {
+    layers: Mutex<HashMap<String, SharedLayer>>,
+}
+
+impl LayersInFlight {
+    /// Return the layer `digest`, downloading it with `fetch` unless another
+    /// pull already has.
+    ///
+    /// The flag is `true` if this call did the download. A failed download
+    /// isn't remembered, so the next caller tries again.
+    pub(crate) async fn get_or_fetch<F, Fut>(
+        &self,
+        digest: &str,
+        fetch: F,
+    ) -> anyhow::Result<(Arc<Vec<u8>>, bool)>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = anyhow::Result<Vec<u8>>>,
+    {
+        let cell = Arc::clone(
+            self.layers
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .entry(digest.to_string())
+                .or_default(),
+        );
+        let mut fetched = false;
+        let data = cell
+            .get_or_try_init(|| async {
+                fetched = true;
+                fetch().await.map(Arc::new)
+            })
+            .await?;
+        Ok((Arc::clone(data), fetched))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn concurrent_requests_share_one_download() {
+        let in_flight = LayersInFlight::default();
+        let fetches = std::sync::atomic::AtomicUsize::new(0);
+        let fetch = || async {
+            fetches.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            tokio::task::yield_now().await;
+            Ok(vec![1, 2, 3])
+        };
+
+        let (a, b) = tokio::join!(
+            in_flight.get_or_fetch("sha256:a", fetch),
+            in_flight.get_or_fetch("sha256:a", fetch),
+        );
+        let (a, b) = (a.unwrap(), b.unwrap());
+        assert_eq!(*a.0, [1, 2, 3]);
+        assert!(Arc::ptr_eq(&a.0, &b.0));
+        assert_ne!(a.1, b.1, "exactly one caller downloads");
+        assert_eq!(fetches.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn failed_downloads_are_retried() {
+        let in_flight = LayersInFlight::default();
+        let err = in_flight
+            .get_or_fetch("sha256:a", || async { anyhow::bail!("reset") })
+            .await;
+        assert!(err.is_err());
+
+        let (data, fetched) = in_flight
+            .get_or_fetch("sha256:a", || async { Ok(vec![7]) })
+            .await
+            .unwrap();
+        assert_eq!(*data, [7]);
+        assert!(fetched);
+    }
+}
@@ -4,6 +4,9 @@
 
 use std::collections::HashSet;
 
+use super::models::TagGroups;
+use crate::oci::{TagKind, classify_tag};
+
 /// Truncated digest length used in vendor filenames.
 const DIGEST_PREFIX_LEN: usize = 12;
 
@@ -39,6 +42,52 @@ pub fn vendor_filename(
     format!("{registry_part}-{repo_part}{tag_part}-{short_sha}.wasm")
 }
 
+/// Compute the directory name for a locked package in a vendor tree.
+///
+/// Characters other than ASCII letters, digits, `-`, `_`, and `.` in the
+/// package name are replaced with `-`, so WIT names like `wasi:clocks` and
+/// registry paths like `ghcr.io/user/repo` are valid on every platform.
+///
+/// Returns `None` if the name is empty or any of its segments, or the
+/// version's, is `.` or `..`, since a lockfile is untrusted input and such a
+/// name could point outside the vendor tree.
+///
+/// # Example
+///
+/// ```
+/// use component_package_manager::manager::vendor_dir_name;
+///
+/// assert_eq!(vendor_dir_name("wasi:clocks", "0.2.5").as_deref(), Some("wasi-clocks@0.2.5"));
+/// assert_eq!(vendor_dir_name("ghcr.io/user/repo", "").as_deref(), Some("ghcr.io-user-repo"));
+/// assert_eq!(vendor_dir_name("ghcr.io/..", ""), None);
+/// ```
+#[must_use]
+pub fn vendor_dir_name(name: &str, version: &str) -> Option<String> {
+    let is_traversal = |s: &str| {
+        s.split(['/', '\\', ':'])
+            .any(|segment| segment == "." || segment == "..")
+    };
+    if name.is_empty() || is_traversal(name) || is_traversal(version) {
+        return None;
+    }
+    let name: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let version = version.replace(['/', '\\'], "-");
+    if version.is_empty() {
+        Some(name)
+    } else {
+        Some(format!("{name}@{version}"))
+    }
+}
+
 /// Determine whether a sync from the meta-registry should proceed.
 ///
 /// Returns `true` when enough time has elapsed since `last_synced_epoch`,
@@ -226,6 +275,63 @@ pub fn pick_latest_stable_tag(tags: &[String]) -> Option<String> {
         .map(|(t, _)| t.clone())
 }
 
+/// Group tags into releases, pre-releases, other tags, signatures, and
+/// attestations.
+///
+/// Semver tags (with an optional leading `v`) are ordered by version, newest
+/// first, so the first release matching a prefix is the newest one. Ties
+/// between equal versions, like `1.0.0` and `v1.0.0`, are broken by name.
+/// Everything else is ordered by name.
+///
+/// # Example
+///
+/// ```
+/// use component_package_manager::manager::group_tags;
+///
+/// let tags = vec![
+///     "0.2.0".into(),
+///     "0.2.10".into(),
+///     "0.3.0-rc.1".into(),
+///     "latest".into(),
+///     "sha256-abc123.sig".into(),
+///     "sha256-abc123.att".into(),
+/// ];
+/// let groups = group_tags(&tags);
+/// assert_eq!(groups.releases, vec!["0.2.10", "0.2.0"]);
+/// assert_eq!(groups.prereleases, vec!["0.3.0-rc.1"]);
+/// assert_eq!(groups.other, vec!["latest"]);
+/// assert_eq!(groups.signatures, vec!["sha256-abc123.sig"]);
+/// assert_eq!(groups.attestations, vec!["sha256-abc123.att"]);
+/// ```
+#[must_use]
+pub fn group_tags(tags: &[String]) -> TagGroups {
+    let mut releases = Vec::new();
+    let mut prereleases = Vec::new();
+    let mut groups = TagGroups::default();
+    for tag in tags {
+        match classify_tag(tag) {
+            TagKind::Signature => groups.signatures.push(tag.clone()),
+            TagKind::Attestation => groups.attestations.push(tag.clone()),
+            TagKind::Release => match parse_tag_as_semver(tag) {
+                Some(version) if version.pre.is_empty() => releases.push((version, tag)),
+                Some(version) => prereleases.push((version, tag)),
+                None => groups.other.push(tag.clone()),
+            },
+        }
+    }
+
+    let newest_first = |mut versions: Vec<(semver::Version, &String)>| {
+        versions.sort_by(|(a, a_tag), (b, b_tag)| b.cmp(a).then_with(|| a_tag.cmp(b_tag)));
+        versions.into_iter().map(|(_, tag)| tag.clone()).collect()
+    };
+    groups.releases = newest_first(releases);
+    groups.prereleases = newest_first(prereleases);
+    groups.other.sort();
+    groups.signatures.sort();
+    groups.attestations.sort();
+    groups
+}
+
 /// Filter tags for display in user-facing suggestions.
 ///
 /// When `requested_version` is `None` (bare install), all pre-release tags
@@ -352,6 +458,26 @@ mod tests {
         assert_eq!(name, "ghcr-io-org-team-component-v2-0123456789ab.wasm");
     }
 
+    // ── vendor_dir_name ─────────────────────────────────────────────────
+
+    #[test]
+    fn vendor_dir_name_rejects_traversal() {
+        assert_eq!(
+            vendor_dir_name("wasi:clocks", "0.2.5").as_deref(),
+            Some("wasi-clocks@0.2.5")
+        );
+        assert_eq!(
+            vendor_dir_name("ghcr.io/user/..repo", "").as_deref(),
+            Some("ghcr.io-user-..repo")
+        );
+        assert_eq!(vendor_dir_name("..", ""), None);
+        assert_eq!(vendor_dir_name(".", ""), None);
+        assert_eq!(vendor_dir_name("ghcr.io/../repo", "1.0"), None);
+        assert_eq!(vendor_dir_name("wasi:..", "1.0"), None);
+        assert_eq!(vendor_dir_name("wasi:clocks", "../1.0"), None);
+        assert_eq!(vendor_dir_name("", ""), None);
+    }
+
     #[test]
     fn vendor_filename_unknown_digest() {
         let name = vendor_filename("ghcr.io", "user/repo", None, "unknown");
@@ -580,6 +706,44 @@ mod tests {
         assert_eq!(pick_latest_stable_tag(&tags), Some("2.0.0".to_string()));
     }
 
+    // ── group_tags ──────────────────────────────────────────────────────
+
+    #[test]
+    fn group_tags_orders_versions_newest_first() {
+        let tags = vec![
+            "0.2.9".into(),
+            "v0.2.10".into(),
+            "0.10.0".into(),
+            "0.3.0-rc.1".into(),
+            "0.3.0-rc.2".into(),
+            "1.0.0".into(),
+            "v1.0.0".into(),
+        ];
+        let groups = group_tags(&tags);
+        assert_eq!(
+            groups.releases,
+            vec!["1.0.0", "v1.0.0", "0.10.0", "v0.2.10", "0.2.9"]
+        );
+        assert_eq!(groups.prereleases, vec!["0.3.0-rc.2", "0.3.0-rc.1"]);
+        assert!(groups.other.is_empty());
+    }
+
+    #[test]
+    fn group_tags_separates_non_semver_and_cosign_tags() {
+        let tags = vec![
+            "nightly".into(),
+            "sha256-bbb.sig".into(),
+            "latest".into(),
+            "sha256-aaa.sig".into(),
+            "sha256-aaa.att".into(),
+        ];
+        let groups = group_tags(&tags);
+        assert!(groups.releases.is_empty());
+        assert_eq!(groups.other, vec!["latest", "nightly"]);
+        assert_eq!(groups.signatures, vec!["sha256-aaa.sig", "sha256-bbb.sig"]);
+        assert_eq!(groups.attestations, vec!["sha256-aaa.att"]);
+    }
+
     // ── filter_tag_suggestions ──────────────────────────────────────────
 
     #[test]
@@ -1,28 +1,54 @@
 use oci_client::Reference;
+use oci_client::client::ImageData;
 use oci_client::errors::{OciDistributionError, OciErrorCode};
+use oci_client::manifest::{
+    ImageIndexEntry, OCI_IMAGE_MEDIA_TYPE, OciDescriptor, OciImageIndex, OciImageManifest,
+};
 use std::path::Path;
+use std::time::Instant;
 use tokio_stream::StreamExt;
 
+use in_flight::LayersInFlight;
+
+mod compat;
 mod errors;
+mod in_flight;
 /// Install helpers — core logic for resolving inputs, managing lockfiles,
 /// and unpacking WIT files.
 pub mod install;
 mod logic;
 mod models;
 
+use crate::components::{ComponentExporter, split_interface_ref};
 use crate::config::Config;
-use crate::oci::{Client, ImageEntry, InsertResult};
+use crate::import::{ContainerEngine, DaemonImage};
+use crate::oci::{
+    Client, ImageEntry, InsertResult, SortBy, SortDirection, attestation_tag, classify_tags,
+    is_attestation_media_type, is_signature_media_type, manifest_description,
+    normalize_digest_prefix, signature_tag,
+};
+use crate::policy::{Denylist, Policy, PolicyViolation};
 use crate::progress::ProgressEvent;
-use crate::storage::{FetchTaskKind, KnownPackage, KnownPackageParams, StateInfo, Store};
+use crate::sign::{SignError, SignOptions};
+use crate::storage::{
+    FetchTaskKind, FsckMode, FsckReport, KnownPackage, KnownPackageParams, PrunePolicy,
+    PullMetrics, RelocateProgress, StateInfo, Store, StoreStats,
+};
 use crate::types::WitPackage;
+use crate::{Result, StoreEvent};
 use component_meta_registry_types::PackageKind;
 
+pub use compat::{CompatMismatch, CompatReport};
 pub use errors::ManagerError;
 pub use logic::{
-    derive_component_name, filter_tag_suggestions, pick_latest_stable_tag,
-    sanitize_to_wit_identifier, should_sync, vendor_filename,
+    derive_component_name, filter_tag_suggestions, group_tags, pick_latest_stable_tag,
+    sanitize_to_wit_identifier, should_sync, vendor_dir_name, vendor_filename,
+};
+pub use models::{
+    DigestUsage, InstallResult, Page, PullResult, RemoteInspection, RemoteLayer, RemoteReferrer,
+    SignResult, StoredManifest, SyncPolicy, SyncResult, TagGroups, VendoredPackage,
+    VerifiedAttestation, VerifiedSignature, VerifyReport,
 };
-pub use models::{InstallResult, PullResult, SyncPolicy, SyncResult};
 
 /// Outcome of [`Manager::process_next_task`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -36,6 +62,9 @@ pub enum TaskOutcome {
     Empty,
 }
 
+/// The marker file [`Manager::vendor`] writes at the root of a vendor tree.
+pub const VENDOR_MARKER: &str = "vendor.toml";
+
 /// How long (in seconds) to skip re-pulling a tag during background indexing
 /// when its layers are already present in the local store.  Set to one hour
 /// so server restarts don't trigger a full re-fetch of every known version.
@@ -47,11 +76,12 @@ const PULL_COOLDOWN_SECS: u64 = 3600;
 ///
 /// ```no_run
 /// use component_package_manager::manager::Manager;
+/// use component_package_manager::oci::{SortBy, SortDirection};
 ///
 /// # #[tokio::main]
 /// # async fn main() -> anyhow::Result<()> {
 /// let manager = Manager::open().await?;
-/// let images = manager.list_all()?;
+/// let images = manager.list_all(SortBy::Name, SortDirection::Ascending)?;
 /// for image in &images {
 ///     println!("{}", image.reference());
 /// }
@@ -63,6 +93,7 @@ pub struct Manager {
     client: Client,
     store: Store,
     config: Config,
+    policy: Policy,
     offline: bool,
 }
 
@@ -81,22 +112,24 @@ impl Manager {
     /// Create a new store at a location on disk.
     ///
     /// This may return an error if it fails to create the cache location on disk.
-    /// Loads configuration from the default config location.
-    pub async fn open() -> anyhow::Result<Self> {
-        Self::open_with_offline(false).await
+    /// Loads configuration from the default config location. Offline mode is
+    /// on when [`OFFLINE_VAR`](crate::OFFLINE_VAR) asks for it.
+    pub async fn open() -> Result<Self> {
+        Ok(Self::open_with_offline(Config::offline_from_env()).await?)
     }
 
     /// Create a new Manager at a location on disk with offline mode.
     ///
     /// When offline is true, network operations will fail with an error.
     /// This may return an error if it fails to create the cache location on disk.
-    pub async fn open_offline() -> anyhow::Result<Self> {
-        Self::open_with_offline(true).await
+    pub async fn open_offline() -> Result<Self> {
+        Ok(Self::open_with_offline(true).await?)
     }
 
     /// Create a new Manager with the specified offline mode.
     async fn open_with_offline(offline: bool) -> anyhow::Result<Self> {
         let config = Config::load()?;
+        let policy = Policy::load()?;
         let client = Client::new(config.clone());
         let store = Store::open().await?;
 
@@ -104,6 +137,7 @@ impl Manager {
             client,
             store,
             config,
+            policy,
             offline,
         })
     }
@@ -115,8 +149,9 @@ impl Manager {
     /// registry server) without sharing state.
     ///
     /// This may return an error if it fails to create the cache location on disk.
-    pub async fn open_at(data_dir: impl Into<std::path::PathBuf>) -> anyhow::Result<Self> {
+    pub async fn open_at(data_dir: impl Into<std::path::PathBuf>) -> Result<Self> {
         let config = Config::load()?;
+        let policy = Policy::load()?;
         let client = Client::new(config.clone());
         let store = Store::open_at(data_dir).await?;
 
@@ -124,10 +159,72 @@ impl Manager {
             client,
             store,
             config,
+            policy,
             offline: false,
         })
     }
 
+    /// Set whether pulls reject artifacts that don't follow the CNCF wasm
+    /// OCI artifact layout, overriding the `[pull]` config section.
+    pub fn set_strict(&mut self, strict: bool) {
+        self.config.pull.get_or_insert_default().strict = strict;
+    }
+
+    /// Returns whether pulls reject artifacts that aren't wasm OCI artifacts.
+    #[must_use]
+    pub fn is_strict(&self) -> bool {
+        self.config.pull.as_ref().is_some_and(|pull| pull.strict)
+    }
+
+    /// The content trust policy that pulls are checked against.
+    #[must_use]
+    pub fn policy(&self) -> &Policy {
+        &self.policy
+    }
+
+    /// Replace the content trust policy loaded from `policy.toml`.
+    pub fn set_policy(&mut self, policy: Policy) {
+        self.policy = policy;
+    }
+
+    /// Validate a pulled manifest, following the CNCF wasm OCI artifact
+    /// layout exactly in strict mode.
+    fn validate_manifest(&self, manifest: &OciImageManifest) -> Result<()> {
+        if self.is_strict() {
+            crate::oci::validate_wasm_artifact(manifest)?;
+        } else {
+            crate::oci::validate_single_wasm_layer(&manifest.layers)?;
+        }
+        Ok(())
+    }
+
+    /// Subscribe to [`StoreEvent`]s, such as packages being pulled or
+    /// deleted, emitted from now on.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use component_package_manager::StoreEvent;
+    /// use component_package_manager::manager::Manager;
+    ///
+    /// # async fn run() -> anyhow::Result<()> {
+    /// let manager = Manager::open().await?;
+    /// let mut events = manager.subscribe();
+    /// tokio::spawn(async move {
+    ///     while let Ok(event) = events.recv().await {
+    ///         if let StoreEvent::PostPull { reference, .. } = event {
+    ///             println!("pulled {reference}");
+    ///         }
+    ///     }
+    /// });
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn subscribe(&self) -> tokio::sync::broadcast::Receiver<StoreEvent> {
+        self.store.subscribe()
+    }
+
     /// Returns whether the manager is in offline mode.
     #[must_use]
     pub fn is_offline(&self) -> bool {
@@ -137,7 +234,8 @@ impl Manager {
     /// Create a new store with a specific configuration.
     ///
     /// This may return an error if it fails to create the cache location on disk.
-    pub async fn with_config(config: Config) -> anyhow::Result<Self> {
+    pub async fn with_config(config: Config) -> Result<Self> {
+        let policy = Policy::load()?;
         let client = Client::new(config.clone());
         let store = Store::open().await?;
 
@@ -145,10 +243,54 @@ impl Manager {
             client,
             store,
             config,
+            policy,
+            offline: false,
+        })
+    }
+
+    /// Create a manager at `data_dir` that sends registry requests through
+    /// `transport`, such as an [`oci::FakeRegistry`](crate::oci::FakeRegistry),
+    /// instead of the network.
+    ///
+    /// The user's configuration and policy are not loaded; the manager starts
+    /// from [`Config::default`] and [`Policy::default`].
+    #[cfg(any(test, feature = "test-util"))]
+    pub async fn with_transport(
+        data_dir: impl Into<std::path::PathBuf>,
+        transport: std::sync::Arc<dyn crate::oci::Transport>,
+    ) -> Result<Self> {
+        let config = Config::default();
+        let client = Client::with_transport(config.clone(), transport);
+        let store = Store::open_at(data_dir).await?;
+
+        Ok(Self {
+            client,
+            store,
+            config,
+            policy: Policy::default(),
             offline: false,
         })
     }
 
+    /// Parse a reference, expanding one without a registry (such as
+    /// `wasi/http:0.2.0`) with the configured `default-registry` or
+    /// `namespaces`; see [`Config::expand_reference`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ManagerError::InvalidReference`] if the expanded reference
+    /// can't be parsed.
+    pub fn resolve_reference(&self, reference: &str) -> Result<Reference> {
+        let expanded = self.config.expand_reference(reference);
+        crate::parse_reference(&expanded).map_err(|reason| {
+            ManagerError::InvalidReference {
+                reference: reference.to_string(),
+                reason,
+            }
+            .into()
+        })
+    }
+
     /// Pull a package from the registry.
     /// Returns the insert result indicating whether the package was newly inserted
     /// or already existed in the database.
@@ -158,23 +300,51 @@ impl Manager {
     ///
     /// # Errors
     ///
-    /// Returns an error if offline mode is enabled.
-    pub async fn pull(&self, reference: Reference) -> anyhow::Result<PullResult> {
+    /// Returns an error if offline mode is enabled, or a [`PolicyViolation`]
+    /// if the content trust [`Policy`] rejects the package; nothing is stored
+    /// in that case.
+    #[tracing::instrument(skip_all, fields(reference = %reference))]
+    pub async fn pull(&self, reference: Reference) -> Result<PullResult> {
         if self.offline {
             return Err(ManagerError::OfflinePull.into());
         }
+        self.policy.check_registry(&reference)?;
+
+        // Check the size limit before downloading any layers.
+        if self.policy.max_artifact_size.is_some() {
+            let (manifest, _digest) = match self.client.pull_manifest(&reference).await {
+                Ok(result) => result,
+                Err(err) => return Err(self.enrich_manifest_error(err, &reference).await.into()),
+            };
+            self.policy
+                .check_size(&reference, layers_size(&manifest.layers))?;
+        }
 
+        let started = Instant::now();
         let image = match self.client.pull(&reference).await {
             Ok(image) => image,
-            Err(err) => return Err(self.enrich_manifest_error(err, &reference).await),
+            Err(err) => return Err(self.enrich_manifest_error(err, &reference).await.into()),
         };
 
+        // A digest-pinned pull must yield exactly the requested manifest.
+        crate::oci::verify_manifest_digest(reference.digest(), image.digest.as_deref())?;
+
         // Validate the OCI bundle has exactly one WASM layer.
         if let Some(ref manifest) = image.manifest {
-            crate::oci::validate_single_wasm_layer(&manifest.layers)?;
+            self.validate_manifest(manifest)?;
         }
 
+        let downloaded: u64 = image
+            .layers
+            .iter()
+            .map(|l| u64::try_from(l.data.len()).unwrap_or(u64::MAX))
+            .sum();
+        self.policy.check_size(&reference, downloaded)?;
+        if let Some(digest) = &image.digest {
+            self.check_signature(&reference, digest).await?;
+        }
         let (result, digest, manifest, manifest_id) = self.store.insert(&reference, image).await?;
+        log_insert_result(result, digest.as_deref());
 
         // Add to known packages when pulling (with tag if present)
         self.store.add_known_package(
@@ -192,6 +362,12 @@ impl Manager {
                 .await;
         }
 
+        self.record_pull(result, downloaded, started);
+        self.store.emit(StoreEvent::PostPull {
+            reference: reference.whole(),
+            digest: digest.clone(),
+            inserted: result == InsertResult::Inserted,
+        });
         Ok(PullResult {
             insert_result: result,
             digest,
@@ -199,6 +375,83 @@ impl Manager {
         })
     }
 
+    /// Pin `reference` to the entry of the image index it points at that
+    /// is for `platform` (`os/architecture[/variant]`) and declares
+    /// `artifact_type`, for indexes with more than one artifact.
+    ///
+    /// References that point at a single manifest are returned as they are.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if offline mode is enabled, or if no entry or more
+    /// than one entry matches; either error lists the candidates.
+    #[tracing::instrument(skip_all, fields(reference = %reference))]
+    pub async fn select_index_entry(
+        &self,
+        reference: &Reference,
+        platform: Option<&str>,
+        artifact_type: Option<&str>,
+    ) -> Result<Reference> {
+        if self.offline {
+            return Err(ManagerError::OfflinePull.into());
+        }
+        let index = match self.client.pull_index(reference).await {
+            Ok(Some(index)) => index,
+            Ok(None) => return Ok(reference.clone()),
+            Err(err) => return Err(self.enrich_manifest_error(err, reference).await.into()),
+        };
+
+        let mut matches = Vec::new();
+        for entry in &index.manifests {
+            if platform.is_some_and(|platform| !crate::oci::matches_platform(entry, platform)) {
+                continue;
+            }
+            if let Some(artifact_type) = artifact_type {
+                // Index entries don't carry the artifact type, so look in
+                // the manifest each one points at.
+                let pinned = reference.clone_with_digest(entry.digest.clone());
+                let (manifest, _digest) = self.client.pull_manifest(&pinned).await?;
+                if !crate::oci::matches_artifact_type(&manifest, artifact_type) {
+                    continue;
+                }
+            }
+            matches.push(entry);
+        }
+
+        let selection = [
+            platform.map(|platform| format!("platform '{platform}'")),
+            artifact_type.map(|artifact_type| format!("artifact type '{artifact_type}'")),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(" and ");
+        match matches.as_slice() {
+            // Keep the tag, so the package is stored under it.
+            [entry] => Ok(match reference.tag() {
+                Some(tag) => Reference::with_tag_and_digest(
+                    reference.registry().to_owned(),
+                    reference.repository().to_owned(),
+                    tag.to_owned(),
+                    entry.digest.clone(),
+                ),
+                None => reference.clone_with_digest(entry.digest.clone()),
+            }),
+            [] => Err(ManagerError::NoMatchingIndexEntry {
+                reference: reference.whole(),
+                selection,
+                candidates: format_index_candidates(reference, &index.manifests),
+            }
+            .into()),
+            _ => Err(ManagerError::AmbiguousIndexEntry {
+                reference: reference.whole(),
+                selection,
+                candidates: format_index_candidates(reference, matches),
+            }
+            .into()),
+        }
+    }
+
     /// Pull a package from the registry with per-layer progress reporting.
     ///
     /// This method streams layers individually and sends `ProgressEvent`s
@@ -207,24 +460,106 @@ impl Manager {
     /// # Errors
     ///
     /// Returns an error if offline mode is enabled or if any network/storage
-    /// operation fails.
+    /// operation fails, or a [`PolicyViolation`] if the content trust
+    /// [`Policy`] rejects the package.
+    #[tracing::instrument(skip_all, fields(reference = %reference))]
     pub async fn pull_with_progress(
         &self,
         reference: Reference,
         progress_tx: &tokio::sync::mpsc::Sender<ProgressEvent>,
-    ) -> anyhow::Result<PullResult> {
+    ) -> Result<PullResult> {
+        self.pull_streamed(reference, progress_tx, &LayersInFlight::default())
+            .await
+    }
+
+    /// Pull many packages at once, at most `max_concurrent` at a time.
+    ///
+    /// Duplicate references are pulled once. When several packages share a
+    /// layer, it is downloaded once and reused by the others. Returns each
+    /// reference with the result of pulling it, in the order given; one
+    /// failed pull doesn't stop the others.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use component_package_manager::manager::Manager;
+    ///
+    /// # async fn run() -> anyhow::Result<()> {
+    /// let manager = Manager::open().await?;
+    /// let references = vec![
+    ///     "ghcr.io/webassembly/wasi/clocks:0.2.0".parse()?,
+    ///     "ghcr.io/webassembly/wasi/io:0.2.0".parse()?,
+    /// ];
+    /// for (reference, result) in manager.pull_many(references, 4).await {
+    ///     match result {
+    ///         Ok(pulled) => println!("{reference}: {:?}", pulled.insert_result),
+    ///         Err(err) => eprintln!("{reference}: {err}"),
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn pull_many(
+        &self,
+        references: impl IntoIterator<Item = Reference>,
+        max_concurrent: usize,
+    ) -> Vec<(Reference, Result<PullResult>)> {
+        use futures_concurrency::prelude::*;
+
+        let mut unique = Vec::new();
+        for reference in references {
+            if !unique.contains(&reference) {
+                unique.push(reference);
+            }
+        }
+
+        let in_flight = LayersInFlight::default();
+        let in_flight = &in_flight;
+        let mut results: Vec<(usize, (Reference, Result<PullResult>))> = unique
+            .into_co_stream()
+            .enumerate()
+            .limit(std::num::NonZeroUsize::new(max_concurrent.max(1)))
+            .map(|(index, reference)| async move {
+                // Nobody listens for progress of a batch pull.
+                let (progress_tx, _) = tokio::sync::mpsc::channel(1);
+                let result =
+                    Box::pin(self.pull_streamed(reference.clone(), &progress_tx, in_flight)).await;
+                (index, (reference, result))
+            })
+            .collect()
+            .await;
+        results.sort_by_key(|(index, _)| *index);
+        results.into_iter().map(|(_, result)| result).collect()
+    }
+
+    /// Pull a package layer by layer, sharing layer downloads through
+    /// `in_flight`.
+    async fn pull_streamed(
+        &self,
+        reference: Reference,
+        progress_tx: &tokio::sync::mpsc::Sender<ProgressEvent>,
+        in_flight: &LayersInFlight,
+    ) -> Result<PullResult> {
         if self.offline {
             return Err(ManagerError::OfflinePull.into());
         }
+        self.policy.check_registry(&reference)?;
 
+        let started = Instant::now();
         // Fetch manifest and config
         let (manifest, digest) = match self.client.pull_manifest(&reference).await {
             Ok(result) => result,
-            Err(err) => return Err(self.enrich_manifest_error(err, &reference).await),
+            Err(err) => return Err(self.enrich_manifest_error(err, &reference).await.into()),
         };
 
+        // A digest-pinned pull must yield exactly the requested manifest.
+        crate::oci::verify_manifest_digest(reference.digest(), Some(&digest))?;
+
         // Validate the OCI bundle has exactly one WASM layer.
-        crate::oci::validate_single_wasm_layer(&manifest.layers)?;
+        self.validate_manifest(&manifest)?;
+        self.policy
+            .check_size(&reference, layers_size(&manifest.layers))?;
+        self.check_signature(&reference, &digest).await?;
 
         let layer_count = manifest.layers.len();
         let _ = progress_tx
@@ -235,18 +570,30 @@ impl Manager {
             .await;
 
         // Calculate total size from manifest layer descriptors
-        let size_on_disk: u64 = manifest
-            .layers
-            .iter()
-            .map(|l| u64::try_from(l.size.max(0)).unwrap_or(0))
-            .sum();
+        let size_on_disk = layers_size(&manifest.layers);
 
         // Insert metadata into the database
         let (result, image_id) =
             self.store
                 .insert_metadata(&reference, Some(&digest), &manifest, size_on_disk)?;
+        log_insert_result(result, Some(&digest));
 
+        let mut downloaded: u64 = 0;
         if result == InsertResult::Inserted {
+            // Keep the config blob as well, so the image can be served as-is.
+            let mut stream = self
+                .client
+                .pull_layer_stream(&reference, &manifest.config)
+                .await?;
+            let mut config_data = Vec::new();
+            while let Some(chunk) = stream.next().await {
+                config_data.extend_from_slice(&chunk?);
+            }
+            downloaded += u64::try_from(config_data.len()).unwrap_or(0);
+            self.store
+                .insert_config(&manifest.config.digest, &config_data)
+                .await?;
+
             // Stream and store each layer individually with progress
             for (index, layer_descriptor) in manifest.layers.iter().enumerate() {
                 let total_bytes = if layer_descriptor.size > 0 {
@@ -268,28 +615,14 @@ impl Manager {
                     })
                     .await;
 
-                // Stream the layer data
-                let mut stream = self
-                    .client
-                    .pull_layer_stream(&reference, layer_descriptor)
+                let (layer_data, fetched) = in_flight
+                    .get_or_fetch(&layer_descriptor.digest, || {
+                        self.download_layer(&reference, layer_descriptor, index, progress_tx)
+                    })
                     .await?;
-
-                let mut layer_data = Vec::new();
-                let mut bytes_downloaded: u64 = 0;
-
-                while let Some(chunk) = stream.next().await {
-                    let chunk = chunk?;
-                    bytes_downloaded += u64::try_from(chunk.len()).unwrap_or(0);
-                    layer_data.extend_from_slice(&chunk);
-
-                    let _ = progress_tx
-                        .send(ProgressEvent::LayerProgress {
-                            index,
-                            bytes_downloaded,
-                        })
-                        .await;
+                if fetched {
+                    downloaded += u64::try_from(layer_data.len()).unwrap_or(0);
                 }
-
                 let _ = progress_tx
                     .send(ProgressEvent::LayerDownloaded { index })
                     .await;
@@ -350,6 +683,12 @@ impl Manager {
                 .await;
         }
 
+        self.record_pull(result, downloaded, started);
+        self.store.emit(StoreEvent::PostPull {
+            reference: reference.whole(),
+            digest: Some(digest.clone()),
+            inserted: result == InsertResult::Inserted,
+        });
         Ok(PullResult {
             insert_result: result,
             digest: Some(digest),
@@ -357,20 +696,509 @@ impl Manager {
         })
     }
 
+    /// The digest of the manifest or image index `reference` points at.
+    ///
+    /// A reference pinned to a digest is answered without a lookup. Offline,
+    /// the digest is read from the store; otherwise only the manifest is
+    /// fetched from the registry.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`NotInStore`](ManagerError::NotInStore) error if offline
+    /// and the package hasn't been pulled, or an error if the manifest can't
+    /// be fetched or doesn't match a pinned digest.
+    #[tracing::instrument(skip_all, fields(reference = %reference))]
+    pub async fn resolve_digest(&self, reference: &Reference) -> Result<String> {
+        if let Some(digest) = reference.digest() {
+            return Ok(digest.to_string());
+        }
+        if self.offline {
+            let manifest =
+                self.stored_manifest(reference)?
+                    .ok_or_else(|| ManagerError::NotInStore {
+                        reference: reference.whole(),
+                    })?;
+            return Ok(manifest.digest);
+        }
+        match self.client.pull_manifest(reference).await {
+            Ok((_, digest)) => Ok(digest),
+            Err(err) => Err(self.enrich_manifest_error(err, reference).await.into()),
+        }
+    }
+
+    /// Fetch a package's metadata from the registry without downloading its
+    /// layers: the manifest, the config blob, and the list of referrers.
+    ///
+    /// Nothing is stored locally.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if offline mode is enabled, or if the manifest or
+    /// config can't be fetched.
+    #[tracing::instrument(skip_all, fields(reference = %reference))]
+    pub async fn inspect_remote(&self, reference: &Reference) -> Result<RemoteInspection> {
+        if self.offline {
+            return Err(ManagerError::OfflinePull.into());
+        }
+
+        let (manifest, digest) = match self.client.pull_manifest(reference).await {
+            Ok(result) => result,
+            Err(err) => return Err(self.enrich_manifest_error(err, reference).await.into()),
+        };
+        crate::oci::verify_manifest_digest(reference.digest(), Some(&digest))?;
+
+        let mut stream = self
+            .client
+            .pull_layer_stream(reference, &manifest.config)
+            .await?;
+        let mut config_data = Vec::new();
+        while let Some(chunk) = stream.next().await {
+            config_data.extend_from_slice(&chunk?);
+        }
+        // Only wasm configs carry anything worth showing.
+        let config = serde_json::from_slice::<oci_wasm::WasmConfig>(&config_data).ok();
+        let component = config.as_ref().and_then(|c| c.component.as_ref());
+
+        let referrers = match self.client.pull_referrers(reference, &digest).await {
+            Ok(Some(index)) => index
+                .manifests
+                .iter()
+                .map(|entry| RemoteReferrer {
+                    digest: entry.digest.clone(),
+                    media_type: entry.media_type.clone(),
+                    size: u64::try_from(entry.size.max(0)).unwrap_or(0),
+                    signature: is_signature_media_type(&entry.media_type),
+                    attestation: is_attestation_media_type(&entry.media_type),
+                })
+                .collect(),
+            Ok(None) | Err(_) => Vec::new(),
+        };
+
+        // Cosign stores signatures and attestations under tags named after
+        // the digest, so the tag list shows them too.
+        let all_tags = self.client.list_tags(reference).await.unwrap_or_default();
+        let (tags, signature_tags, attestation_tags) = classify_tags(&all_tags);
+        let signed = referrers.iter().any(|r| r.signature)
+            || signature_tags.contains(&signature_tag(&digest));
+        let attested = referrers.iter().any(|r| r.attestation)
+            || attestation_tags.contains(&attestation_tag(&digest));
+
+        Ok(RemoteInspection {
+            reference: reference.whole(),
+            digest,
+            media_type: manifest.media_type.clone(),
+            artifact_type: manifest.artifact_type.clone(),
+            config_media_type: manifest.config.media_type.clone(),
+            size: layers_size(&manifest.layers),
+            layers: manifest
+                .layers
+                .iter()
+                .map(|layer| RemoteLayer {
+                    digest: layer.digest.clone(),
+                    media_type: layer.media_type.clone(),
+                    size: u64::try_from(layer.size.max(0)).unwrap_or(0),
+                })
+                .collect(),
+            annotations: manifest.annotations.clone().unwrap_or_default(),
+            created: config.as_ref().map(|c| c.created.to_rfc3339()),
+            author: config.as_ref().and_then(|c| c.author.clone()),
+            os: config.as_ref().map(|c| c.os.clone()),
+            target: component.and_then(|c| c.target.clone()),
+            imports: component.map(|c| c.imports.clone()).unwrap_or_default(),
+            exports: component.map(|c| c.exports.clone()).unwrap_or_default(),
+            referrers,
+            tags,
+            signed,
+            attested,
+        })
+    }
+
+    /// Load the known-bad digests from denylist `feeds`: `http(s)://` URLs,
+    /// or files. Offline, URL feeds are skipped and listed in
+    /// [`Denylist::skipped_feeds`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a feed can't be read or fetched, or isn't a valid
+    /// denylist.
+    pub async fn load_denylist(&self, feeds: &[String]) -> Result<Denylist> {
+        use anyhow::Context as _;
+
+        let mut denylist = Denylist::default();
+        for feed in feeds {
+            let contents = if crate::policy::is_url(feed) {
+                if self.offline {
+                    denylist.skip_feed(feed.clone());
+                    continue;
+                }
+                let network = self.config.network.clone().unwrap_or_default();
+                let http = reqwest::Client::builder()
+                    .user_agent(concat!("component/", env!("CARGO_PKG_VERSION")))
+                    .connect_timeout(network.connect_timeout())
+                    .read_timeout(network.read_timeout())
+                    .build()
+                    .map_err(anyhow::Error::from)?;
+                http.get(feed)
+                    .send()
+                    .await
+                    .and_then(reqwest::Response::error_for_status)
+                    .map_err(anyhow::Error::from)
+                    .with_context(|| format!("Failed to fetch denylist {feed}"))?
+                    .text()
+                    .await
+                    .map_err(anyhow::Error::from)
+                    .with_context(|| format!("Failed to fetch denylist {feed}"))?
+            } else {
+                tokio::fs::read_to_string(feed)
+                    .await
+                    .with_context(|| format!("Failed to read denylist {feed}"))?
+            };
+            denylist
+                .extend_from_feed(&contents)
+                .with_context(|| format!("Invalid denylist {feed}"))?;
+        }
+        Ok(denylist)
+    }
+
+    /// Check whether the registry still serves the manifest a locked package
+    /// points at: its digest, or its version tag if it was locked without one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if offline mode is enabled, if the package's registry
+    /// isn't a valid reference, or if the registry can't be reached.
+    #[tracing::instrument(skip_all, fields(package = %package.name))]
+    pub async fn locked_package_exists(
+        &self,
+        package: &component_manifest::LockedPackage,
+    ) -> Result<bool> {
+        if self.offline {
+            return Err(ManagerError::OfflinePull.into());
+        }
+        let reference = locked_reference(package)?;
+        match self.client.pull_any_manifest(&reference).await {
+            Ok(_) => Ok(true),
+            Err(err) if is_manifest_unknown(&err) => Ok(false),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// List the wasm images in a local Docker or Podman daemon's storage:
+    /// those built for the `wasm` architecture.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`ImportError`](crate::import::ImportError) if the engine's
+    /// command can't be run or fails.
+    pub async fn daemon_images(&self, engine: ContainerEngine) -> Result<Vec<DaemonImage>> {
+        Ok(crate::import::list_images(engine).await?)
+    }
+
+    /// Copy `image` from a local Docker or Podman daemon into the store, as
+    /// though it had been pulled from its registry.
+    ///
+    /// `image` is named as the engine names it, so `app:1.0` means
+    /// `docker.io/library/app:1.0`. The image must be a wasm artifact, as
+    /// for [`pull`](Self::pull), and the registry and size rules of the
+    /// content trust [`Policy`] apply. Importing works in offline mode.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`ImportError`](crate::import::ImportError) if the image
+    /// can't be saved from the engine, or an error if it isn't a wasm
+    /// artifact or the policy rejects it.
+    #[tracing::instrument(skip(self))]
+    pub async fn import_image(&self, engine: ContainerEngine, image: &str) -> Result<PullResult> {
+        let reference =
+            crate::parse_reference(image).map_err(|reason| ManagerError::InvalidReference {
+                reference: image.to_string(),
+                reason,
+            })?;
+        self.policy.check_registry(&reference)?;
+
+        let data = crate::import::save(engine, image).await?;
+        self.store_imported(&reference, data).await
+    }
+
+    /// Write the stored images `references` name to a bundle at `output`,
+    /// for [`import_bundle`](Self::import_bundle) to read on another
+    /// machine, and return the bundle's size.
+    ///
+    /// A bundle is a zstd-compressed tar archive in the OCI image layout.
+    /// Exporting never contacts a registry.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`NotInStore`](ManagerError::NotInStore) error if an image
+    /// hasn't been pulled, or an error if its blobs can't be read from the
+    /// store or the bundle can't be written.
+    #[tracing::instrument(skip(self, references), fields(count = references.len()))]
+    pub async fn export_bundle(&self, references: &[Reference], output: &Path) -> Result<u64> {
+        let store_dir = self.store.state_info.store_dir();
+        let mut images = Vec::with_capacity(references.len());
+        let mut blobs = std::collections::BTreeMap::new();
+        for reference in references {
+            let manifest =
+                self.stored_manifest(reference)?
+                    .ok_or_else(|| ManagerError::NotInStore {
+                        reference: reference.whole(),
+                    })?;
+            let parsed: OciImageManifest = serde_json::from_slice(&manifest.body)
+                .map_err(|e| anyhow::anyhow!("stored manifest {}: {e}", manifest.digest))?;
+            let digests = std::iter::once(&parsed.config.digest)
+                .chain(parsed.layers.iter().map(|layer| &layer.digest));
+            for digest in digests {
+                if !blobs.contains_key(digest) {
+                    let data = crate::storage::blob::read(store_dir, digest)
+                        .await
+                        .map_err(anyhow::Error::from)?;
+                    blobs.insert(digest.clone(), data);
+                }
+            }
+            images.push((reference.clone(), manifest));
+        }
+        let bundle = crate::import::bundle::write(&images, &blobs)?;
+        tokio::fs::write(output, &bundle).await?;
+        Ok(u64::try_from(bundle.len()).unwrap_or(u64::MAX))
+    }
+
+    /// Copy every image in the bundle at `path` into the store, under the
+    /// reference it was exported with.
+    ///
+    /// As for [`import_image`](Self::import_image), each image must be a
+    /// wasm artifact, the registry and size rules of the content trust
+    /// [`Policy`] apply, and importing works in offline mode.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`ImportError`](crate::import::ImportError) if the bundle
+    /// can't be read or is damaged, or an error if an image isn't a wasm
+    /// artifact or the policy rejects it.
+    #[tracing::instrument(skip(self))]
+    pub async fn import_bundle(&self, path: &Path) -> Result<Vec<(Reference, PullResult)>> {
+        let bytes = tokio::fs::read(path).await?;
+        let images = crate::import::bundle::read(&path.display().to_string(), &bytes)?;
+        for (reference, _) in &images {
+            self.policy.check_registry(reference)?;
+        }
+        let mut results = Vec::with_capacity(images.len());
+        for (reference, data) in images {
+            let result = self.store_imported(&reference, data).await?;
+            results.push((reference, result));
+        }
+        Ok(results)
+    }
+
+    /// Store an image read from outside a registry, as though it had been
+    /// pulled as `reference`.
+    async fn store_imported(&self, reference: &Reference, data: ImageData) -> Result<PullResult> {
+        if let Some(ref manifest) = data.manifest {
+            self.validate_manifest(manifest)?;
+        }
+        let size = data
+            .layers
+            .iter()
+            .map(|l| u64::try_from(l.data.len()).unwrap_or(u64::MAX))
+            .sum();
+        self.policy.check_size(reference, size)?;
+
+        let (result, digest, manifest, _) = self.store.insert(reference, data).await?;
+        log_insert_result(result, digest.as_deref());
+        self.store.add_known_package(
+            reference.registry(),
+            reference.repository(),
+            reference.tag(),
+            None,
+        )?;
+        self.store.emit(StoreEvent::PostPull {
+            reference: reference.whole(),
+            digest: digest.clone(),
+            inserted: result == InsertResult::Inserted,
+        });
+        Ok(PullResult {
+            insert_result: result,
+            digest,
+            manifest,
+        })
+    }
+
+    /// Download a layer, reporting progress as layer `index`.
+    async fn download_layer(
+        &self,
+        reference: &Reference,
+        layer: &OciDescriptor,
+        index: usize,
+        progress_tx: &tokio::sync::mpsc::Sender<ProgressEvent>,
+    ) -> anyhow::Result<Vec<u8>> {
+        let mut stream = self.client.pull_layer_stream(reference, layer).await?;
+
+        let mut layer_data = Vec::new();
+        let mut bytes_downloaded: u64 = 0;
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk?;
+            bytes_downloaded += u64::try_from(chunk.len()).unwrap_or(0);
+            layer_data.extend_from_slice(&chunk);
+
+            let _ = progress_tx
+                .send(ProgressEvent::LayerProgress {
+                    index,
+                    bytes_downloaded,
+                })
+                .await;
+        }
+        Ok(layer_data)
+    }
+
     /// Hard-link a cached layer to a destination path.
     ///
     /// Uses `cacache::hard_link` to create a hard-link from the global cache
-    /// to the specified destination, saving disk space.
+    /// to the specified destination, saving disk space. Layers stored
+    /// compressed can't be linked, so they are decompressed and written to
+    /// the destination instead.
     ///
     /// # Errors
     ///
     /// Returns an error if the hard-link operation fails (e.g., layer not
     /// found in cache, or destination path is invalid).
-    pub async fn vendor(&self, layer_digest: &str, dest: &Path) -> anyhow::Result<()> {
-        cacache::hard_link(self.store.state_info.store_dir(), layer_digest, dest).await?;
+    pub async fn vendor_layer(&self, layer_digest: &str, dest: &Path) -> Result<()> {
+        let store_dir = self.store.state_info.store_dir();
+        let entry = cacache::metadata(store_dir, layer_digest)
+            .await?
+            .ok_or_else(|| {
+                cacache::Error::EntryNotFound(store_dir.to_path_buf(), layer_digest.to_string())
+            })?;
+        if crate::storage::blob::is_compressed(&entry) {
+            let bytes = crate::storage::blob::read(store_dir, layer_digest).await?;
+            tokio::fs::write(dest, bytes).await?;
+        } else {
+            cacache::hard_link_hash(store_dir, &entry.integrity, dest).await?;
+        }
         Ok(())
     }
 
+    /// Pull every package in `lockfile`, at most `max_concurrent` at a time.
+    ///
+    /// Packages are pulled by their locked digest, or by their version when
+    /// no digest is locked. Like [`pull_many`](Self::pull_many), returns each
+    /// reference with the result of pulling it, in lockfile order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a locked package doesn't form a valid reference;
+    /// nothing is pulled in that case.
+    pub async fn pull_locked(
+        &self,
+        lockfile: &component_manifest::Lockfile,
+        max_concurrent: usize,
+    ) -> Result<Vec<(Reference, Result<PullResult>)>> {
+        let references = lockfile
+            .all_packages()
+            .map(|(package, _)| locked_reference(package))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(self.pull_many(references, max_concurrent).await)
+    }
+
+    /// Copy every package in `lockfile` into a vendor tree at `dir`, so that
+    /// builds can run offline from a checked-in copy.
+    ///
+    /// Each package gets a directory `deps/<name>@<version>` (see
+    /// [`vendor_dir_name`]) holding its wasm bytes as `package.wasm`. WIT
+    /// packages also get their decoded WIT as `package.wit`. `deps/` is
+    /// rebuilt from scratch, so packages no longer in the lockfile are
+    /// removed, and a [`VENDOR_MARKER`] file recording the vendored lockfile
+    /// is written next to it.
+    ///
+    /// Packages are looked up in the store by their locked digest and pulled
+    /// when missing.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ManagerError::InvalidVendorName`] if a package's name or
+    /// version has a `.` or `..` segment, before anything is written. Returns
+    /// an error if a package is missing from the store and can't be pulled
+    /// (for example in offline mode), or if writing the tree fails.
+    pub async fn vendor(
+        &self,
+        lockfile: &component_manifest::Lockfile,
+        dir: &Path,
+    ) -> Result<Vec<VendoredPackage>> {
+        // Check every name before touching the tree, so a bad lockfile
+        // leaves the previous vendor tree in place.
+        let packages = lockfile
+            .all_packages()
+            .map(|(package, _)| {
+                let dir_name =
+                    vendor_dir_name(&package.name, &package.version).ok_or_else(|| {
+                        ManagerError::InvalidVendorName {
+                            name: package.name.clone(),
+                            version: package.version.clone(),
+                        }
+                    })?;
+                Ok((package, dir_name))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let deps = dir.join("deps");
+        match tokio::fs::remove_dir_all(&deps).await {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+        tokio::fs::create_dir_all(&deps).await?;
+
+        let mut vendored = Vec::new();
+        for (package, dir_name) in packages {
+            let reference = locked_reference(package)?;
+
+            let mut pulled = false;
+            let layer = match self.stored_component_layer(&reference) {
+                Ok(layer) => layer,
+                Err(err)
+                    if !self.offline
+                        && matches!(
+                            err.downcast_ref::<ManagerError>(),
+                            Some(ManagerError::NotInStore { .. })
+                        ) =>
+                {
+                    self.pull(reference.clone()).await?;
+                    pulled = true;
+                    self.stored_component_layer(&reference)?
+                }
+                Err(err) => return Err(err.into()),
+            };
+            let bytes =
+                crate::storage::blob::read(self.store.state_info.store_dir(), &layer.digest)
+                    .await?;
+
+            let package_dir = deps.join(dir_name);
+            tokio::fs::create_dir_all(&package_dir).await?;
+            tokio::fs::write(package_dir.join("package.wasm"), &bytes).await?;
+            let wit = if crate::types::is_wit_package(&bytes) {
+                crate::types::extract_wit_text(&bytes)
+            } else {
+                None
+            };
+            if let Some(wit) = &wit {
+                tokio::fs::write(package_dir.join("package.wit"), wit).await?;
+            }
+
+            vendored.push(VendoredPackage {
+                name: package.name.clone(),
+                version: package.version.clone(),
+                digest: package.digest.clone(),
+                dir: package_dir,
+                has_wit: wit.is_some(),
+                pulled,
+            });
+        }
+
+        let header = "# This directory is generated by component(1) from the lockfile below.\n\
+                      # It should not be manually edited.\n";
+        let body = toml::to_string_pretty(lockfile).map_err(anyhow::Error::from)?;
+        tokio::fs::write(dir.join(VENDOR_MARKER), format!("{header}\n{body}")).await?;
+        Ok(vendored)
+    }
+
     /// Install a package from the registry.
     ///
     /// This high-level method:
@@ -382,11 +1210,8 @@ impl Manager {
     /// # Errors
     ///
     /// Returns an error if pulling, vendoring, or filesystem operations fail.
-    pub async fn install(
-        &self,
-        reference: Reference,
-        vendor_dir: &Path,
-    ) -> anyhow::Result<InstallResult> {
+    #[tracing::instrument(skip_all, fields(reference = %reference))]
+    pub async fn install(&self, reference: Reference, vendor_dir: &Path) -> Result<InstallResult> {
         use crate::oci::filter_wasm_layers;
 
         let pull_result = self.pull(reference.clone()).await?;
@@ -422,7 +1247,7 @@ impl Manager {
                 // Remove existing file if present (hard-link requires non-existent target)
                 let _ = tokio::fs::remove_file(&dest).await;
 
-                self.vendor(&layer.digest, &dest).await?;
+                self.vendor_layer(&layer.digest, &dest).await?;
                 vendored_files.push(dest);
 
                 if package_name.is_none() {
@@ -458,12 +1283,13 @@ impl Manager {
     /// # Errors
     ///
     /// Returns an error if pulling, vendoring, or filesystem operations fail.
+    #[tracing::instrument(skip_all, fields(reference = %reference))]
     pub async fn install_with_progress(
         &self,
         reference: Reference,
         vendor_dir: &Path,
         progress_tx: &tokio::sync::mpsc::Sender<ProgressEvent>,
-    ) -> anyhow::Result<InstallResult> {
+    ) -> Result<InstallResult> {
         use crate::oci::filter_wasm_layers;
 
         let pull_result = self
@@ -501,7 +1327,7 @@ impl Manager {
                 // Remove existing file if present (hard-link requires non-existent target)
                 let _ = tokio::fs::remove_file(&dest).await;
 
-                self.vendor(&layer.digest, &dest).await?;
+                self.vendor_layer(&layer.digest, &dest).await?;
                 vendored_files.push(dest);
 
                 if package_name.is_none() {
@@ -531,17 +1357,131 @@ impl Manager {
         })
     }
 
-    /// List all stored images and their metadata.
-    pub fn list_all(&self) -> anyhow::Result<Vec<ImageEntry>> {
+    /// List all stored images and their metadata, ordered by `sort` in
+    /// `direction`.
+    ///
+    /// Ordering happens in the database, so callers can show e.g. the
+    /// largest or most recently pulled images first without re-sorting.
+    pub fn list_all(&self, sort: SortBy, direction: SortDirection) -> Result<Vec<ImageEntry>> {
         Ok(self
             .store
-            .list_all()?
+            .list_sorted(sort, direction, 0, None)?
             .into_iter()
             .map(ImageEntry::from)
             .collect())
     }
 
-    /// Resolve a WIT dependency to an OCI [`Reference`].
+    /// List one page of stored images, in the same order as [`Self::list_all`].
+    ///
+    /// The returned [`Page`] carries the total number of stored images and
+    /// the offset of the next page.
+    pub fn list_page(
+        &self,
+        sort: SortBy,
+        direction: SortDirection,
+        offset: u32,
+        limit: u32,
+    ) -> Result<Page<ImageEntry>> {
+        let entries = self
+            .store
+            .list_sorted(sort, direction, offset, Some(limit))?;
+        Ok(Page {
+            items: entries.into_iter().map(ImageEntry::from).collect(),
+            offset,
+            total: self.store.count_images()?,
+        })
+    }
+
+    /// Find everything in the local store that points at `digest`, such as
+    /// all tags that ship one exact component binary.
+    ///
+    /// `digest` may be a manifest or a layer digest, with or without the
+    /// `sha256:` prefix.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ManagerError::InvalidDigestPrefix`] if `digest` isn't a
+    /// hex digest.
+    pub fn find_by_digest(&self, digest: &str) -> Result<DigestUsage> {
+        let normalized =
+            normalize_digest_prefix(digest).ok_or_else(|| ManagerError::InvalidDigestPrefix {
+                prefix: digest.to_string(),
+            })?;
+        let (images, tags) = self.store.find_by_digest(&normalized)?;
+        Ok(DigestUsage {
+            images: images.into_iter().map(ImageEntry::from).collect(),
+            tags,
+        })
+    }
+
+    /// Look up a stored manifest by the tag or digest of `reference`.
+    ///
+    /// The body is the manifest JSON as kept in the store, and the returned
+    /// digest is the digest of exactly those bytes. A digest lookup only
+    /// matches if the stored bytes hash to it, so a client that checks what
+    /// it receives never gets a manifest it would reject.
+    ///
+    /// Returns `None` if nothing matching `reference` has been pulled.
+    pub fn stored_manifest(&self, reference: &Reference) -> Result<Option<StoredManifest>> {
+        let Some((manifests, tags)) = self
+            .store
+            .repository_contents(reference.registry(), reference.repository())?
+        else {
+            return Ok(None);
+        };
+        let by_tag = reference.digest().is_none();
+        let wanted = if let Some(digest) = reference.digest() {
+            digest.to_owned()
+        } else {
+            let tag = reference.tag().unwrap_or("latest");
+            let Some(tag) = tags.into_iter().find(|t| t.tag == tag) else {
+                return Ok(None);
+            };
+            tag.manifest_digest
+        };
+        for manifest in manifests {
+            // Placeholders recorded during referrer discovery have no body.
+            let Some(body) = manifest.raw_json.filter(|json| json != "null") else {
+                continue;
+            };
+            let digest = crate::oci::sha256_digest(body.as_bytes());
+            if digest == wanted || (by_tag && manifest.digest == wanted) {
+                return Ok(Some(StoredManifest {
+                    digest,
+                    media_type: manifest
+                        .media_type
+                        .unwrap_or_else(|| OCI_IMAGE_MEDIA_TYPE.to_string()),
+                    body: body.into_bytes(),
+                }));
+            }
+        }
+        Ok(None)
+    }
+
+    /// List the tags stored for a repository, in order.
+    ///
+    /// Unlike [`list_tags`](Self::list_tags), this only returns tags whose
+    /// image has been pulled, and never contacts the registry. Returns
+    /// `None` if nothing has been stored for the repository.
+    pub fn stored_tags(&self, registry: &str, repository: &str) -> Result<Option<Vec<String>>> {
+        let contents = self.store.repository_contents(registry, repository)?;
+        Ok(contents.map(|(_, tags)| tags.into_iter().map(|t| t.tag).collect()))
+    }
+
+    /// List every repository in the local store as `registry/repository`,
+    /// in order.
+    pub fn stored_repositories(&self) -> Result<Vec<String>> {
+        let mut names: Vec<String> = self
+            .store
+            .list_repositories()?
+            .into_iter()
+            .map(|r| format!("{}/{}", r.registry, r.repository))
+            .collect();
+        names.sort();
+        Ok(names)
+    }
+
+    /// Resolve a WIT dependency to an OCI [`Reference`].
     ///
     /// Resolution order:
     /// 1. Exact match via `RawWitPackage::find_oci_reference()` (DB JOIN lookup).
@@ -554,7 +1494,7 @@ impl Manager {
     pub fn resolve_wit_dependency(
         &self,
         dep: &crate::types::DependencyItem,
-    ) -> anyhow::Result<Option<Reference>> {
+    ) -> Result<Option<Reference>> {
         // 1. Exact DB lookup: WIT package → OCI reference
         if let Some((registry, repository)) = self
             .store
@@ -623,9 +1563,124 @@ impl Manager {
         pick_latest_stable_tag(&tags)
     }
 
-    /// Get data from the store
-    pub async fn get(&self, key: &str) -> cacache::Result<Vec<u8>> {
-        cacache::read(self.store.state_info.store_dir(), key).await
+    /// Read the component bytes of a stored image.
+    ///
+    /// Looks the reference up in the local store and returns the contents
+    /// of its first `application/wasm` layer. Nothing is fetched from the
+    /// registry; [`pull`](Self::pull) the reference first.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PackageManagerError::NotFound`](crate::PackageManagerError::NotFound)
+    /// if the reference has not been pulled, or an error if the image has no
+    /// wasm layer.
+    pub async fn read_component(&self, reference: &Reference) -> Result<Vec<u8>> {
+        let layer = self.stored_component_layer(reference)?;
+        Ok(crate::storage::blob::read(self.store.state_info.store_dir(), &layer.digest).await?)
+    }
+
+    /// Open the component bytes of a stored image for streaming.
+    ///
+    /// Like [`read_component`](Self::read_component), but returns a reader
+    /// instead of loading the whole layer into memory. Call
+    /// [`ComponentReader::check`](crate::storage::ComponentReader::check)
+    /// once the reader is exhausted to verify the bytes against their digest.
+    /// Layers stored compressed are decompressed into memory and verified
+    /// up front.
+    ///
+    /// # Errors
+    ///
+    /// Returns the same errors as [`read_component`](Self::read_component).
+    pub async fn open_component(
+        &self,
+        reference: &Reference,
+    ) -> Result<crate::storage::ComponentReader> {
+        let layer = self.stored_component_layer(reference)?;
+        Ok(
+            crate::storage::ComponentReader::open(self.store.state_info.store_dir(), &layer.digest)
+                .await?,
+        )
+    }
+
+    /// Write the component bytes of a stored image to `output`.
+    ///
+    /// The stored blob is checked against the layer's OCI digest and
+    /// verified against its integrity hash while it is copied. The copy goes
+    /// to a sibling `.part` file that only replaces `output` once it checks
+    /// out. Returns the number of bytes written.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of [`read_component`](Self::read_component), a
+    /// [`Corrupt`](crate::PackageManagerError::Corrupt) error if the stored
+    /// bytes don't match their digest, or an I/O error writing `output`.
+    pub async fn extract(&self, reference: &Reference, output: &Path) -> Result<u64> {
+        let layer = self.stored_component_layer(reference)?;
+        let store_dir = self.store.state_info.store_dir();
+        let entry = cacache::metadata(store_dir, &layer.digest)
+            .await?
+            .ok_or_else(|| {
+                cacache::Error::EntryNotFound(store_dir.to_path_buf(), layer.digest.clone())
+            })?;
+        let mut partial = output.as_os_str().to_owned();
+        partial.push(".part");
+        let partial = std::path::PathBuf::from(partial);
+
+        let copied = if crate::storage::blob::is_compressed(&entry) {
+            // Decompressing checks the bytes against the layer's digest.
+            let bytes = crate::storage::blob::read(store_dir, &layer.digest).await?;
+            tokio::fs::write(&partial, &bytes)
+                .await
+                .map(|()| u64::try_from(bytes.len()).unwrap_or(u64::MAX))
+                .map_err(|e| {
+                    cacache::Error::IoError(e, format!("failed to write {}", partial.display()))
+                })
+        } else {
+            let (algorithm, hex) = entry.integrity.to_hex();
+            crate::oci::verify_manifest_digest(
+                Some(&layer.digest),
+                Some(&format!("{algorithm}:{hex}")),
+            )?;
+            cacache::copy_hash(store_dir, &entry.integrity, &partial).await
+        };
+        let written = match copied {
+            Ok(written) => written,
+            Err(e) => {
+                let _ = tokio::fs::remove_file(&partial).await;
+                return Err(anyhow::Error::from(e).into());
+            }
+        };
+        tokio::fs::rename(&partial, output).await?;
+        Ok(written)
+    }
+
+    /// Find the first wasm layer of the stored image `reference` names.
+    fn stored_component_layer(&self, reference: &Reference) -> anyhow::Result<OciDescriptor> {
+        let entries = self.store.list_all()?;
+        let entry = entries
+            .iter()
+            .find(|e| {
+                e.ref_registry == reference.registry()
+                    && e.ref_repository == reference.repository()
+                    && match (reference.digest(), reference.tag()) {
+                        (Some(digest), _) => e.ref_digest.as_deref() == Some(digest),
+                        (None, Some(tag)) => e.ref_tag.as_deref() == Some(tag),
+                        (None, None) => true,
+                    }
+            })
+            .ok_or_else(|| ManagerError::NotInStore {
+                reference: reference.whole(),
+            })?;
+        crate::oci::filter_wasm_layers(&entry.manifest.layers)
+            .into_iter()
+            .next()
+            .cloned()
+            .ok_or_else(|| {
+                ManagerError::NotAComponent {
+                    reference: reference.whole(),
+                }
+                .into()
+            })
     }
 
     /// Get information about the current state of the package manager.
@@ -633,15 +1688,257 @@ impl Manager {
         self.store.state_info.clone()
     }
 
+    /// Measure the blob content on disk, in bytes.
+    ///
+    /// Unlike [`StateInfo::store_size`], which is measured once when the
+    /// store opens, this walks the store every time, so it can tell how much
+    /// a removal freed.
+    pub async fn content_size(&self) -> u64 {
+        self.store.content_size().await
+    }
+
+    /// Get cumulative pull statistics: pull count, cache hits and misses,
+    /// bytes downloaded, and pull latency.
+    ///
+    /// Unlike [`state_info`](Self::state_info), which is captured when the
+    /// manager opens, this reads the current values.
+    pub fn metrics(&self) -> Result<PullMetrics> {
+        Ok(self.store.pull_metrics()?)
+    }
+
+    /// Get store-wide statistics: image and interface counts, total and
+    /// physical size, the largest images, and those unused the longest.
+    pub fn stats(&self) -> Result<StoreStats> {
+        Ok(self.store.store_stats()?)
+    }
+
+    /// Migrate the metadata database up or down to migration `version`.
+    ///
+    /// The database is snapshotted next to `metadata.db3` first; the path of
+    /// the snapshot is returned, or `None` if the database was already at
+    /// `version`. Some early migrations cannot be reverted. Opening a
+    /// manager migrates the database to the latest version again, so a
+    /// downgrade is only useful before switching to an older build.
+    pub async fn migrate_to(&self, version: u32) -> Result<Option<std::path::PathBuf>> {
+        Ok(self.store.migrate_to(version).await?)
+    }
+
+    /// Snapshot the metadata database to `path`.
+    ///
+    /// Uses SQLite's online backup API, so the snapshot is consistent even
+    /// while other processes read the store. The content store is not
+    /// included; see [`restore`](Self::restore).
+    pub async fn backup(&self, path: impl AsRef<Path>) -> Result<()> {
+        Ok(self.store.backup(path.as_ref()).await?)
+    }
+
+    /// Replace the metadata database with a snapshot taken by
+    /// [`backup`](Self::backup).
+    ///
+    /// The current database is first backed up next to `metadata.db3` as
+    /// `metadata.db3.pre-restore.bak`. Snapshots from older builds are
+    /// migrated after restoring. The restored database is then checked
+    /// against the content store, as by [`fsck`](Self::fsck) with
+    /// [`FsckMode::Check`]; images whose layers are missing show up in the
+    /// returned report and can be pulled again with [`FsckMode::Repull`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `path` is not an intact metadata database, or is
+    /// from a newer build with migrations this one doesn't know.
+    pub async fn restore(&mut self, path: impl AsRef<Path>) -> Result<FsckReport> {
+        Ok(self.store.restore(path.as_ref()).await?)
+    }
+
+    /// Move the store to `new_dir`, e.g. onto a larger disk.
+    ///
+    /// The metadata database and the content store are renamed into place
+    /// when `new_dir` is on the same filesystem, and copied across otherwise.
+    /// A pointer is left in the old data directory, so managers opened there
+    /// afterwards use the new location. Other processes must not use the
+    /// store while it moves.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `new_dir` already holds a store or lies inside
+    /// the current one, or if moving fails. A failed move leaves the store
+    /// where it was.
+    pub async fn relocate(&mut self, new_dir: impl AsRef<Path>) -> Result<()> {
+        // Nobody listens for progress.
+        let (progress_tx, _) = tokio::sync::mpsc::channel(1);
+        self.relocate_with_progress(new_dir, &progress_tx).await
+    }
+
+    /// Like [`relocate`](Self::relocate), but sends [`RelocateProgress`]
+    /// updates via the provided channel as bytes are moved.
+    pub async fn relocate_with_progress(
+        &mut self,
+        new_dir: impl AsRef<Path>,
+        progress_tx: &tokio::sync::mpsc::Sender<RelocateProgress>,
+    ) -> Result<()> {
+        Ok(self.store.relocate(new_dir.as_ref(), progress_tx).await?)
+    }
+
     /// Get the current configuration.
     #[must_use]
     pub fn config(&self) -> &Config {
         &self.config
     }
 
+    /// Check the store for inconsistencies between the database and the
+    /// blob store, optionally repairing them.
+    ///
+    /// Finds layers whose blobs are missing or have the wrong size, WIT
+    /// packages left behind by deleted images, and blobs no image references.
+    /// With [`FsckMode::Prune`] these are removed; with [`FsckMode::Repull`]
+    /// broken images are additionally pulled again from their registry, and
+    /// an issue only counts as repaired once its image has been re-pulled.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store can't be read, or if
+    /// [`FsckMode::Repull`] is used in offline mode.
+    #[tracing::instrument(skip(self))]
+    pub async fn fsck(&self, mode: FsckMode) -> Result<FsckReport> {
+        if mode == FsckMode::Repull && self.offline {
+            return Err(ManagerError::OfflinePull.into());
+        }
+
+        let mut report = self.store.fsck(mode != FsckMode::Check).await?;
+        if mode == FsckMode::Prune {
+            self.emit_gc_completed(&report);
+        }
+        if mode != FsckMode::Repull {
+            return Ok(report);
+        }
+
+        let mut repulled: std::collections::HashMap<String, bool> =
+            std::collections::HashMap::new();
+        for issue in &mut report.issues {
+            let Some(image) = issue.problem.broken_image() else {
+                continue;
+            };
+            if let Some(ok) = repulled.get(image) {
+                issue.repaired = *ok;
+                continue;
+            }
+            let ok = match image.parse::<Reference>() {
+                Ok(reference) => match self.pull(reference).await {
+                    Ok(_) => true,
+                    Err(e) => {
+                        tracing::warn!(image, "failed to re-pull broken image: {e:#}");
+                        false
+                    }
+                },
+                Err(e) => {
+                    tracing::warn!(image, "cannot re-pull image with invalid reference: {e}");
+                    false
+                }
+            };
+            repulled.insert(image.to_string(), ok);
+            issue.repaired = ok;
+        }
+        self.emit_gc_completed(&report);
+        Ok(report)
+    }
+
+    /// Add a completed pull to the pull metrics, logging rather than
+    /// failing the pull if they can't be updated.
+    fn record_pull(&self, result: InsertResult, downloaded: u64, started: Instant) {
+        let cache_hit = result == InsertResult::AlreadyExists;
+        if let Err(e) = self
+            .store
+            .record_pull(cache_hit, downloaded, started.elapsed())
+        {
+            tracing::warn!("failed to record pull metrics: {e:#}");
+        }
+    }
+
+    fn emit_gc_completed(&self, report: &FsckReport) {
+        let removed = report.issues.iter().filter(|issue| issue.repaired).count();
+        self.store.emit(StoreEvent::GcCompleted { removed });
+    }
+
+    fn emit_deleted(&self, target: String, count: usize) {
+        if count > 0 {
+            self.store.emit(StoreEvent::PostDelete { target, count });
+        }
+    }
+
     /// Delete an image from the store by its reference.
-    pub async fn delete(&self, reference: Reference) -> anyhow::Result<bool> {
-        self.store.delete(&reference).await
+    #[tracing::instrument(skip_all, fields(reference = %reference))]
+    pub async fn delete(&self, reference: Reference) -> Result<bool> {
+        let deleted = self.store.delete(&reference).await?;
+        self.emit_deleted(reference.whole(), usize::from(deleted));
+        Ok(deleted)
+    }
+
+    /// Delete every stored image in a repository, whatever its tag.
+    ///
+    /// Returns the number of images removed.
+    #[tracing::instrument(skip(self))]
+    pub async fn delete_repository(&self, registry: &str, repository: &str) -> Result<usize> {
+        let count = self.store.delete_repository(registry, repository).await?;
+        self.emit_deleted(format!("{registry}/{repository}"), count);
+        Ok(count)
+    }
+
+    /// Delete every stored image whose manifest digest starts with `prefix`,
+    /// e.g. `sha256:ab12` or just `ab12`.
+    ///
+    /// Returns the number of images removed.
+    #[tracing::instrument(skip(self))]
+    pub async fn delete_by_digest_prefix(&self, prefix: &str) -> Result<usize> {
+        let normalized =
+            normalize_digest_prefix(prefix).ok_or_else(|| ManagerError::InvalidDigestPrefix {
+                prefix: prefix.to_string(),
+            })?;
+        let count = self.store.delete_by_digest_prefix(&normalized).await?;
+        self.emit_deleted(normalized, count);
+        Ok(count)
+    }
+
+    /// Delete every stored image matching a glob such as
+    /// `ghcr.io/example/*` or `*:0.1.*`.
+    ///
+    /// The pattern is matched against `registry/repository`, each
+    /// `registry/repository:tag`, and `registry/repository@digest`; see
+    /// [`glob_match`](crate::oci::glob_match) for the syntax. Images of
+    /// pinned packages are kept. Returns the number of images removed.
+    #[tracing::instrument(skip(self))]
+    pub async fn delete_matching(&self, pattern: &str) -> Result<usize> {
+        let count = self.store.delete_matching(pattern).await?;
+        self.emit_deleted(pattern.to_string(), count);
+        Ok(count)
+    }
+
+    /// Remove stored images according to `policy`, e.g. images unused for
+    /// 30 days or all but the newest N per repository.
+    ///
+    /// Returns the references of the removed images, or of the images that
+    /// would be removed when [`PrunePolicy::dry_run`] is set.
+    #[tracing::instrument(skip(self))]
+    pub async fn prune(&self, policy: &PrunePolicy) -> Result<Vec<String>> {
+        let removed = self.store.prune(policy).await?;
+        if !policy.dry_run {
+            self.store.emit(StoreEvent::GcCompleted {
+                removed: removed.len(),
+            });
+        }
+        Ok(removed)
+    }
+
+    /// Record that a stored image was just used, so
+    /// [`PrunePolicy::unused_for`] doesn't remove it.
+    ///
+    /// Pulling and installing record access automatically; call this when
+    /// reading an image's layers straight from the store.
+    pub fn record_access(&self, image: &ImageEntry) -> Result<()> {
+        if let Some(digest) = &image.ref_digest {
+            self.store
+                .record_access(&image.ref_registry, &image.ref_repository, digest)?;
+        }
+        Ok(())
     }
 
     /// Search for known packages by query string.
@@ -652,7 +1949,7 @@ impl Manager {
         query: &str,
         offset: u32,
         limit: u32,
-    ) -> anyhow::Result<Vec<KnownPackage>> {
+    ) -> Result<Vec<KnownPackage>> {
         self.store
             .search_known_packages(query, offset, limit)?
             .into_iter()
@@ -666,6 +1963,21 @@ impl Manager {
             .collect()
     }
 
+    /// Search for known packages by query string, returning one [`Page`]
+    /// together with the total number of matches.
+    pub fn search_packages_page(
+        &self,
+        query: &str,
+        offset: u32,
+        limit: u32,
+    ) -> Result<Page<KnownPackage>> {
+        Ok(Page {
+            items: self.search_packages(query, offset, limit)?,
+            offset,
+            total: self.store.count_search_known_packages(query)?,
+        })
+    }
+
     /// Search for known packages that import a given interface.
     /// Uses pagination with `offset` and `limit` parameters.
     pub fn search_packages_by_import(
@@ -673,7 +1985,7 @@ impl Manager {
         interface: &str,
         offset: u32,
         limit: u32,
-    ) -> anyhow::Result<Vec<KnownPackage>> {
+    ) -> Result<Vec<KnownPackage>> {
         self.store
             .search_known_packages_by_import(interface, offset, limit)?
             .into_iter()
@@ -694,7 +2006,7 @@ impl Manager {
         interface: &str,
         offset: u32,
         limit: u32,
-    ) -> anyhow::Result<Vec<KnownPackage>> {
+    ) -> Result<Vec<KnownPackage>> {
         self.store
             .search_known_packages_by_export(interface, offset, limit)?
             .into_iter()
@@ -719,11 +2031,7 @@ impl Manager {
     /// search (~50 items) and keeps the code simple. A future
     /// optimisation could batch-load all dependencies in a single query keyed
     /// by `(registry, repository)` pairs.
-    pub fn list_known_packages(
-        &self,
-        offset: u32,
-        limit: u32,
-    ) -> anyhow::Result<Vec<KnownPackage>> {
+    pub fn list_known_packages(&self, offset: u32, limit: u32) -> Result<Vec<KnownPackage>> {
         self.store
             .list_known_packages(offset, limit)?
             .into_iter()
@@ -737,14 +2045,20 @@ impl Manager {
             .collect()
     }
 
+    /// Get one [`Page`] of known packages together with the total number of
+    /// known packages.
+    pub fn list_known_packages_page(&self, offset: u32, limit: u32) -> Result<Page<KnownPackage>> {
+        Ok(Page {
+            items: self.list_known_packages(offset, limit)?,
+            offset,
+            total: self.store.count_known_packages()?,
+        })
+    }
+
     /// Get recently updated known packages.
     ///
     /// Uses pagination with `offset` and `limit` parameters.
-    pub fn list_recent_known_packages(
-        &self,
-        offset: u32,
-        limit: u32,
-    ) -> anyhow::Result<Vec<KnownPackage>> {
+    pub fn list_recent_known_packages(&self, offset: u32, limit: u32) -> Result<Vec<KnownPackage>> {
         self.store
             .list_recent_known_packages(offset, limit)?
             .into_iter()
@@ -765,29 +2079,108 @@ impl Manager {
         repository: &str,
         tag: Option<&str>,
         description: Option<&str>,
-    ) -> anyhow::Result<()> {
-        self.store
-            .add_known_package(registry, repository, tag, description)
+    ) -> Result<()> {
+        Ok(self
+            .store
+            .add_known_package(registry, repository, tag, description)?)
+    }
+
+    /// Pin a known package.
+    ///
+    /// Stored images of pinned packages are never removed by
+    /// [`prune`](Self::prune) or [`delete_matching`](Self::delete_matching),
+    /// and pinned packages are listed before all
+    /// others by [`list_known_packages`](Self::list_known_packages) and the
+    /// `search_packages*` methods. The package is recorded as known if it
+    /// wasn't already.
+    pub fn pin(&self, registry: &str, repository: &str) -> Result<()> {
+        Ok(self
+            .store
+            .set_repository_pinned(registry, repository, true)?)
+    }
+
+    /// Unpin a known package previously pinned with [`pin`](Self::pin).
+    pub fn unpin(&self, registry: &str, repository: &str) -> Result<()> {
+        Ok(self
+            .store
+            .set_repository_pinned(registry, repository, false)?)
     }
 
     /// Add or update a known package entry with WIT namespace mapping.
-    pub fn add_known_package_with_params(
-        &self,
-        params: &KnownPackageParams<'_>,
-    ) -> anyhow::Result<()> {
-        self.store.add_known_package_with_params(params)
+    pub fn add_known_package_with_params(&self, params: &KnownPackageParams<'_>) -> Result<()> {
+        Ok(self.store.add_known_package_with_params(params)?)
     }
 
     /// List all tags for a given reference from the registry.
     ///
     /// In offline mode, returns cached tags from the local database instead of
     /// fetching from the registry.
-    pub async fn list_tags(&self, reference: &Reference) -> anyhow::Result<Vec<String>> {
+    #[tracing::instrument(skip_all, fields(reference = %reference))]
+    pub async fn list_tags(&self, reference: &Reference) -> Result<Vec<String>> {
         if self.offline {
             // Return cached tags from known packages
-            return self.list_cached_tags(reference);
+            return Ok(self.list_cached_tags(reference)?);
+        }
+        let tags = self.client.list_tags(reference).await?;
+        self.refresh_description(reference, &tags).await;
+        Ok(tags)
+    }
+
+    /// Fetch the image index a reference points at, so callers can choose
+    /// between its entries when none is recognizably a wasm artifact.
+    ///
+    /// Returns `None` when the reference resolves to a single image
+    /// manifest, which can be pulled directly.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if offline mode is enabled or the registry request fails.
+    #[tracing::instrument(skip_all, fields(reference = %reference))]
+    pub async fn resolve_index(&self, reference: &Reference) -> Result<Option<OciImageIndex>> {
+        if self.offline {
+            return Err(ManagerError::OfflinePull.into());
+        }
+        Ok(self.client.pull_index(reference).await?)
+    }
+
+    /// Fill in a known package's missing description from the annotations
+    /// of its latest stable tag's manifest.
+    ///
+    /// This is best-effort: failures are logged and otherwise ignored.
+    async fn refresh_description(&self, reference: &Reference, tags: &[String]) {
+        let known = self
+            .store
+            .get_known_package(reference.registry(), reference.repository());
+        if matches!(known, Ok(Some(ref pkg)) if pkg.description.is_some()) {
+            return;
+        }
+        let Some(tag) = pick_latest_stable_tag(tags) else {
+            return;
+        };
+        let latest = Reference::with_tag(
+            reference.registry().to_owned(),
+            reference.repository().to_owned(),
+            tag,
+        );
+        let description = match self.client.pull_manifest(&latest).await {
+            Ok((manifest, _)) => manifest_description(&manifest).map(str::to_owned),
+            Err(e) => {
+                tracing::debug!("Failed to fetch manifest for '{}': {e}", latest.whole());
+                return;
+            }
+        };
+        if let Some(description) = description
+            && let Err(e) = self.store.set_repository_description(
+                reference.registry(),
+                reference.repository(),
+                &description,
+            )
+        {
+            tracing::debug!(
+                "Failed to store description for '{}': {e}",
+                reference.whole()
+            );
         }
-        self.client.list_tags(reference).await
     }
 
     /// List tags from the local cache for a given reference.
@@ -823,7 +2216,7 @@ impl Manager {
         &self,
         registry: &str,
         repository: &str,
-    ) -> anyhow::Result<Option<KnownPackage>> {
+    ) -> Result<Option<KnownPackage>> {
         match self.store.get_known_package(registry, repository)? {
             None => Ok(None),
             Some(raw) => {
@@ -844,26 +2237,40 @@ impl Manager {
     /// extraction logic.  OCI data (manifests, layers, blobs) is untouched.
     ///
     /// Returns the number of packages that were re-indexed.
-    pub async fn reindex_wit(&self) -> anyhow::Result<u64> {
-        self.store.reindex_wit_packages().await
+    #[tracing::instrument(skip_all)]
+    pub async fn reindex_wit(&self) -> Result<u64> {
+        Ok(self.store.reindex_wit_packages().await?)
+    }
+
+    /// Re-extract WIT interfaces from every stored image.
+    ///
+    /// Where [`Self::reindex_wit`] only refreshes packages that were
+    /// already indexed, this also picks up images pulled before WIT
+    /// extraction existed. Run it after upgrading the WIT parser to bring
+    /// the whole store up to date. OCI data is untouched.
+    ///
+    /// Returns the number of images a WIT package was extracted from.
+    #[tracing::instrument(skip_all)]
+    pub async fn rescan_wit_interfaces(&self) -> Result<u64> {
+        Ok(self.store.rescan_wit_packages().await?)
     }
 
     /// Enqueue reindex tasks for all known tags that have cached layers.
     ///
     /// Returns the number of tasks enqueued.
-    pub fn enqueue_reindex_all(&self) -> anyhow::Result<u64> {
-        self.store.enqueue_reindex_all()
+    pub fn enqueue_reindex_all(&self) -> Result<u64> {
+        Ok(self.store.enqueue_reindex_all()?)
     }
 
     /// Seed the fetch queue with completed entries for tags that were
     /// pulled before the queue existed.
-    pub fn seed_completed_from_tags(&self) -> anyhow::Result<u64> {
-        self.store.seed_completed_from_tags()
+    pub fn seed_completed_from_tags(&self) -> Result<u64> {
+        Ok(self.store.seed_completed_from_tags()?)
     }
 
     /// Return the current fetch queue status.
-    pub fn get_queue_status(&self) -> anyhow::Result<component_meta_registry_types::QueueStatus> {
-        self.store.get_queue_status()
+    pub fn get_queue_status(&self) -> Result<component_meta_registry_types::QueueStatus> {
+        Ok(self.store.get_queue_status()?)
     }
 
     /// Notify the registry that a specific version of a package was just
@@ -885,7 +2292,7 @@ impl Manager {
         registry: &str,
         repository: &str,
         tag: &str,
-    ) -> anyhow::Result<component_meta_registry_types::NotifyOutcome> {
+    ) -> Result<component_meta_registry_types::NotifyOutcome> {
         use component_meta_registry_types::NotifyOutcome;
 
         if self
@@ -906,6 +2313,92 @@ impl Manager {
         Ok(NotifyOutcome::Enqueued)
     }
 
+    /// Lists the repositories a registry hosts and records each one as a
+    /// known package, so packages can be searched without pulling them first.
+    ///
+    /// `namespace` limits discovery to one organization or user, and is
+    /// required for `ghcr.io`. Returns the discovered repositories.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if offline mode is enabled or if the registry does
+    /// not allow listing its repositories.
+    #[tracing::instrument(skip(self))]
+    pub async fn discover_repositories(
+        &self,
+        registry: &str,
+        namespace: Option<&str>,
+    ) -> Result<Vec<String>> {
+        if self.offline {
+            return Err(ManagerError::OfflineIndex.into());
+        }
+        let repositories = self.client.list_repositories(registry, namespace).await?;
+        for repository in &repositories {
+            self.store
+                .add_known_package(registry, repository, None, None)?;
+        }
+        Ok(repositories)
+    }
+
+    /// Search the registries in [`Config::search_sources`] for repositories
+    /// whose name contains `query`, ignoring case, and record each match as
+    /// a known package.
+    ///
+    /// The registries are listed concurrently. Registries that can't be
+    /// listed, such as those without a catalog API, are skipped. Returns the
+    /// matches ordered by reference.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if offline mode is enabled or if recording a match
+    /// fails.
+    #[tracing::instrument(skip(self))]
+    pub async fn search_remote(&self, query: &str) -> Result<Vec<KnownPackage>> {
+        use futures_concurrency::prelude::*;
+
+        if self.offline {
+            return Err(ManagerError::OfflineIndex.into());
+        }
+        let query = query.to_lowercase();
+        let listings: Vec<(String, Vec<String>)> = self
+            .config
+            .search_sources()
+            .into_co_stream()
+            .map(|(registry, namespace)| async move {
+                let repositories = match self
+                    .client
+                    .list_repositories(&registry, namespace.as_deref())
+                    .await
+                {
+                    Ok(repositories) => repositories,
+                    Err(err) => {
+                        tracing::debug!(registry, ?namespace, "skipping registry: {err:#}");
+                        Vec::new()
+                    }
+                };
+                (registry, repositories)
+            })
+            .collect()
+            .await;
+
+        let mut matches = Vec::new();
+        for (registry, repositories) in listings {
+            for repository in repositories {
+                if !repository.to_lowercase().contains(&query) {
+                    continue;
+                }
+                self.store
+                    .add_known_package(&registry, &repository, None, None)?;
+                if let Some(pkg) = self.get_known_package(&registry, &repository)? {
+                    matches.push(pkg);
+                }
+            }
+        }
+        matches.sort_by_key(KnownPackage::reference);
+        matches.dedup_by_key(|pkg| pkg.reference());
+        Ok(matches)
+    }
+
     /// Fetches the manifest and config to extract metadata (description from
     /// OCI annotations), lists all tags, and upserts into the known packages
     /// table. Also pulls the wasm layer for the most recent tag to extract
@@ -925,7 +2418,7 @@ impl Manager {
         wit_namespace: Option<&str>,
         wit_name: Option<&str>,
         kind: Option<PackageKind>,
-    ) -> anyhow::Result<KnownPackage> {
+    ) -> Result<KnownPackage> {
         self.index_package_inner(reference, wit_namespace, wit_name, kind, false)
             .await
     }
@@ -940,11 +2433,12 @@ impl Manager {
         wit_namespace: Option<&str>,
         wit_name: Option<&str>,
         kind: Option<PackageKind>,
-    ) -> anyhow::Result<KnownPackage> {
+    ) -> Result<KnownPackage> {
         self.index_package_inner(reference, wit_namespace, wit_name, kind, true)
             .await
     }
 
+    #[tracing::instrument(skip_all, fields(reference = %reference, skip_cooldown = skip_cooldown))]
     async fn index_package_inner(
         &self,
         reference: &Reference,
@@ -952,7 +2446,7 @@ impl Manager {
         wit_name: Option<&str>,
         kind: Option<PackageKind>,
         skip_cooldown: bool,
-    ) -> anyhow::Result<KnownPackage> {
+    ) -> Result<KnownPackage> {
         if self.offline {
             return Err(ManagerError::OfflineIndex.into());
         }
@@ -1101,7 +2595,8 @@ impl Manager {
     /// [`TaskOutcome::Failed`] when the task itself failed (the failure
     /// is recorded in the queue).  Errors are reserved for failures that
     /// prevent us from interacting with the queue at all.
-    pub async fn process_next_task(&self) -> anyhow::Result<TaskOutcome> {
+    #[tracing::instrument(skip_all)]
+    pub async fn process_next_task(&self) -> Result<TaskOutcome> {
         let Some(task) = self.store.dequeue_next()? else {
             return Ok(TaskOutcome::Empty);
         };
@@ -1156,7 +2651,7 @@ impl Manager {
     }
 
     /// Get all WIT interfaces with their associated component references.
-    pub fn list_wit_packages_with_components(&self) -> anyhow::Result<Vec<(WitPackage, String)>> {
+    pub fn list_wit_packages_with_components(&self) -> Result<Vec<(WitPackage, String)>> {
         Ok(self
             .store
             .list_wit_packages_with_components()?
@@ -1165,28 +2660,105 @@ impl Manager {
             .collect())
     }
 
-    /// Get declared dependencies for a package identified by its WIT name and
-    /// optional version.
-    ///
-    /// Queries `wit_package_dependency` directly by package name, bypassing
-    /// the OCI registry/repository path. This is the primary entry point for
-    /// the dependency resolver.
+    /// Find stored components that export a WIT interface.
     ///
-    /// Returns an empty list when the package has no recorded dependencies.
-    pub fn get_dependencies_by_name(
-        &self,
-        package_name: &str,
-        version: Option<&str>,
-    ) -> anyhow::Result<Vec<crate::storage::PackageDependencyRef>> {
-        self.store
-            .get_package_dependencies_by_name(package_name, version)
+    /// `interface` is given as `namespace:package/interface`, optionally
+    /// followed by `@version` (e.g. `wasi:http/incoming-handler@0.2.0`).
+    /// Omitting `/interface` matches any export from the package. This
+    /// answers "who implements this interface?" when composing components.
+    pub fn find_exporters(&self, interface: &str) -> Result<Vec<ComponentExporter>> {
+        let (package, name, version) = split_interface_ref(interface);
+        Ok(self.store.find_exporters(package, name, version)?)
     }
 
-    /// Resolve the complete transitive dependency graph for a root package and
-    /// version using the PubGrub algorithm over locally-cached metadata.
+    /// Check whether one stored component's exports satisfy another's imports.
     ///
-    /// Returns a map from WIT package name to the single selected version for
-    /// every package in the resolved set (including the root).
+    /// Both references must already be in the local store. Each component's
+    /// WIT world is decoded with `wit-parser`, and every interface the
+    /// consumer imports is reported as satisfied, unsatisfied, or mismatched
+    /// against the provider's exports.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PackageManagerError::NotFound`](crate::PackageManagerError::NotFound)
+    /// if either reference has not been pulled, or an error if a stored
+    /// image is not a decodable component.
+    pub async fn check_compat(
+        &self,
+        provider: &Reference,
+        consumer: &Reference,
+    ) -> Result<CompatReport> {
+        let (p_resolve, p_world) = self.decode_stored_world(provider).await?;
+        let (c_resolve, c_world) = self.decode_stored_world(consumer).await?;
+        Ok(compat::compare_worlds(
+            (&p_resolve, p_world),
+            (&c_resolve, c_world),
+        ))
+    }
+
+    /// Decode the WIT world of a stored component's first wasm layer.
+    async fn decode_stored_world(
+        &self,
+        reference: &Reference,
+    ) -> anyhow::Result<(wit_parser::Resolve, wit_parser::WorldId)> {
+        let layer = self.stored_component_layer(reference)?;
+        let bytes =
+            crate::storage::blob::read(self.store.state_info.store_dir(), &layer.digest).await?;
+        match wit_parser::decoding::decode(&bytes) {
+            Ok(wit_parser::decoding::DecodedWasm::Component(resolve, world)) => {
+                Ok((resolve, world))
+            }
+            _ => Err(ManagerError::NotAComponent {
+                reference: reference.whole(),
+            }
+            .into()),
+        }
+    }
+
+    /// Search stored WIT packages by the names that appear in their WIT.
+    ///
+    /// Matches function, type, interface, and world names (or any other
+    /// identifier) in the WIT text of every pulled package. Each term in
+    /// `query` is matched as a prefix and all terms must be present. Results
+    /// are ordered by relevance and paired with the `registry/repository`
+    /// the package was pulled from.
+    pub fn search_interfaces(
+        &self,
+        query: &str,
+        offset: u32,
+        limit: u32,
+    ) -> Result<Vec<(WitPackage, String)>> {
+        Ok(self
+            .store
+            .search_wit_packages(query, offset, limit)?
+            .into_iter()
+            .map(|(wt, s)| (WitPackage::from(wt), s))
+            .collect())
+    }
+
+    /// Get declared dependencies for a package identified by its WIT name and
+    /// optional version.
+    ///
+    /// Queries `wit_package_dependency` directly by package name, bypassing
+    /// the OCI registry/repository path. This is the primary entry point for
+    /// the dependency resolver.
+    ///
+    /// Returns an empty list when the package has no recorded dependencies.
+    pub fn get_dependencies_by_name(
+        &self,
+        package_name: &str,
+        version: Option<&str>,
+    ) -> Result<Vec<crate::storage::PackageDependencyRef>> {
+        Ok(self
+            .store
+            .get_package_dependencies_by_name(package_name, version)?)
+    }
+
+    /// Resolve the complete transitive dependency graph for a root package and
+    /// version using the PubGrub algorithm over locally-cached metadata.
+    ///
+    /// Returns a map from WIT package name to the single selected version for
+    /// every package in the resolved set (including the root).
     ///
     /// # Errors
     ///
@@ -1245,8 +2817,8 @@ impl Manager {
         &self,
         registry: &str,
         repository: &str,
-    ) -> anyhow::Result<Vec<component_meta_registry_types::PackageVersion>> {
-        self.store.get_package_versions(registry, repository)
+    ) -> Result<Vec<component_meta_registry_types::PackageVersion>> {
+        Ok(self.store.get_package_versions(registry, repository)?)
     }
 
     /// Return a single version of a package by its tag.
@@ -1255,9 +2827,10 @@ impl Manager {
         registry: &str,
         repository: &str,
         version_tag: &str,
-    ) -> anyhow::Result<Option<component_meta_registry_types::PackageVersion>> {
-        self.store
-            .get_package_version(registry, repository, version_tag)
+    ) -> Result<Option<component_meta_registry_types::PackageVersion>> {
+        Ok(self
+            .store
+            .get_package_version(registry, repository, version_tag)?)
     }
 
     /// Return full package detail including all versions and metadata.
@@ -1265,8 +2838,8 @@ impl Manager {
         &self,
         registry: &str,
         repository: &str,
-    ) -> anyhow::Result<Option<component_meta_registry_types::PackageDetail>> {
-        self.store.get_package_detail(registry, repository)
+    ) -> Result<Option<component_meta_registry_types::PackageDetail>> {
+        Ok(self.store.get_package_detail(registry, repository)?)
     }
 
     /// Sync the local package index from a meta-registry over HTTP.
@@ -1283,12 +2856,13 @@ impl Manager {
     /// Returns an error only when the sync fails **and** no cached data exists.
     /// When cached data exists but the sync fails, returns `SyncResult::Degraded`.
     #[cfg(feature = "http-sync")]
+    #[tracing::instrument(skip_all, fields(url = %url, policy = ?policy))]
     pub async fn sync_from_meta_registry(
         &self,
         url: &str,
         sync_interval: u64,
         policy: SyncPolicy,
-    ) -> anyhow::Result<SyncResult> {
+    ) -> Result<SyncResult> {
         use component_meta_registry_client::{FetchResult, RegistryClient};
 
         // Check the minimum interval unless forced.
@@ -1355,91 +2929,92 @@ impl Manager {
         registry: &str,
         repository: &str,
         tag: &str,
-    ) -> anyhow::Result<component_meta_registry_types::NotifyOutcome> {
+    ) -> Result<component_meta_registry_types::NotifyOutcome> {
         use anyhow::Context as _;
         use component_meta_registry_client::RegistryClient;
 
         if self.offline {
-            anyhow::bail!("cannot notify meta-registry in offline mode");
+            return Err(ManagerError::OfflineNotify.into());
         }
 
         let client = RegistryClient::new(url);
-        client
+        let outcome = client
             .notify_new_version(registry, repository, tag)
             .await
             .map_err(|e| anyhow::Error::msg(e.to_string()))
-            .with_context(|| format!("failed to notify meta-registry at {url}"))
+            .with_context(|| format!("failed to notify meta-registry at {url}"))?;
+        Ok(outcome)
     }
 
     #[cfg(feature = "http-sync")]
-    fn handle_update(
-        &self,
-        packages: &[KnownPackage],
-        etag: Option<String>,
-    ) -> anyhow::Result<SyncResult> {
+    fn handle_update(&self, packages: &[KnownPackage], etag: Option<String>) -> Result<SyncResult> {
         let count = packages.len();
-        // Bulk upsert all packages.
-        for pkg in packages {
-            let first_tag = pkg.tags.first().map(String::as_str);
-            self.store
-                .add_known_package_with_params(&KnownPackageParams {
-                    registry: &pkg.registry,
-                    repository: &pkg.repository,
-                    tag: first_tag,
-                    description: pkg.description.as_deref(),
-                    wit_namespace: pkg.wit_namespace.as_deref(),
-                    wit_name: pkg.wit_name.as_deref(),
-                    kind: pkg.kind,
-                })?;
-            // Also add remaining tags.
-            for tag in pkg.tags.iter().skip(1) {
+        // Bulk upsert all packages in one transaction: much faster than a
+        // commit per row, and other processes never see a half-synced index.
+        self.store.transaction(|| {
+            for pkg in packages {
+                let first_tag = pkg.tags.first().map(String::as_str);
                 self.store
                     .add_known_package_with_params(&KnownPackageParams {
                         registry: &pkg.registry,
                         repository: &pkg.repository,
-                        tag: Some(tag),
+                        tag: first_tag,
                         description: pkg.description.as_deref(),
                         wit_namespace: pkg.wit_namespace.as_deref(),
                         wit_name: pkg.wit_name.as_deref(),
                         kind: pkg.kind,
                     })?;
-            }
+                // Also add remaining tags.
+                for tag in pkg.tags.iter().skip(1) {
+                    self.store
+                        .add_known_package_with_params(&KnownPackageParams {
+                            registry: &pkg.registry,
+                            repository: &pkg.repository,
+                            tag: Some(tag),
+                            description: pkg.description.as_deref(),
+                            wit_namespace: pkg.wit_namespace.as_deref(),
+                            wit_name: pkg.wit_name.as_deref(),
+                            kind: pkg.kind,
+                        })?;
+                }
 
-            // r[impl db.wit-package-dependency.populate-on-sync]
-            // Store package and dependency information from the sync response
-            // so the local database can answer dependency and version queries
-            // without network access.  A `wit_package` stub row is created
-            // even for packages with no dependencies — the resolver needs the
-            // row to exist so that `choose_version` can enumerate available
-            // versions.
-            if let (Some(ns), Some(name)) = (&pkg.wit_namespace, &pkg.wit_name) {
-                let package_name = format!("{ns}:{name}");
-                // Use the latest stable semver tag as the canonical version;
-                // strip any leading "v" so it matches the WIT version string.
-                // When no stable semver tag is available, fall back to "0.0.0"
-                // so the resolver can still find the package (the installer
-                // shims unversioned roots to 0.0.0 for PubGrub resolution).
-                let version = pick_latest_stable_tag(&pkg.tags).map_or_else(
-                    || "0.0.0".to_string(),
-                    |t| t.trim_start_matches('v').to_string(),
-                );
-                if let Err(e) = self.store.upsert_package_dependencies_from_sync(
-                    &package_name,
-                    Some(&version),
-                    &pkg.dependencies,
-                ) {
-                    tracing::warn!(
-                        package = %package_name,
-                        error = %e,
-                        "Failed to store synced package"
+                // r[impl db.wit-package-dependency.populate-on-sync]
+                // Store package and dependency information from the sync response
+                // so the local database can answer dependency and version queries
+                // without network access.  A `wit_package` stub row is created
+                // even for packages with no dependencies — the resolver needs the
+                // row to exist so that `choose_version` can enumerate available
+                // versions.
+                if let (Some(ns), Some(name)) = (&pkg.wit_namespace, &pkg.wit_name) {
+                    let package_name = format!("{ns}:{name}");
+                    // Use the latest stable semver tag as the canonical version;
+                    // strip any leading "v" so it matches the WIT version string.
+                    // When no stable semver tag is available, fall back to "0.0.0"
+                    // so the resolver can still find the package (the installer
+                    // shims unversioned roots to 0.0.0 for PubGrub resolution).
+                    let version = pick_latest_stable_tag(&pkg.tags).map_or_else(
+                        || "0.0.0".to_string(),
+                        |t| t.trim_start_matches('v').to_string(),
                     );
+                    if let Err(e) = self.store.upsert_package_dependencies_from_sync(
+                        &package_name,
+                        Some(&version),
+                        &pkg.dependencies,
+                    ) {
+                        tracing::warn!(
+                            package = %package_name,
+                            error = %e,
+                            "Failed to store synced package"
+                        );
+                    }
                 }
             }
-        }
-        if let Some(etag_val) = etag {
-            self.store.set_sync_meta("packages_etag", &etag_val)?;
-        }
-        self.update_last_synced_at()?;
+            if let Some(etag_val) = etag {
+                self.store.set_sync_meta("packages_etag", &etag_val)?;
+            }
+            self.update_last_synced_at()?;
+            Ok(())
+        })?;
         Ok(SyncResult::Updated { count })
     }
 
@@ -1484,7 +3059,9 @@ impl Manager {
     ) {
         use crate::types::{extract_wit_metadata, is_wit_package};
 
-        let Ok(data) = self.get(layer_digest).await else {
+        let Ok(data) =
+            crate::storage::blob::read(self.store.state_info.store_dir(), layer_digest).await
+        else {
             return;
         };
         *is_component = !is_wit_package(&data);
@@ -1494,6 +3071,60 @@ impl Manager {
         }
     }
 
+    /// Enforce the policy's `require-signature` rule for the manifest at
+    /// `digest`.
+    async fn check_signature(&self, reference: &Reference, digest: &str) -> Result<()> {
+        let Some(namespace) = self.policy.signature_namespace(reference) else {
+            return Ok(());
+        };
+        if self.has_signature(reference, digest).await {
+            return Ok(());
+        }
+        Err(PolicyViolation::SignatureRequired {
+            reference: reference.whole(),
+            namespace: namespace.to_string(),
+        }
+        .into())
+    }
+
+    /// Whether the registry has a signature for the manifest at `digest`,
+    /// either as a referrer or as a cosign `sha256-<hex>.sig` tag.
+    ///
+    /// Registry errors count as no signature.
+    async fn has_signature(&self, reference: &Reference, digest: &str) -> bool {
+        if let Ok(Some(index)) = self.client.pull_referrers(reference, digest).await {
+            for entry in &index.manifests {
+                if is_signature_media_type(&entry.media_type) {
+                    return true;
+                }
+                // The index entry doesn't carry the artifact type, so look
+                // at the referrer's manifest.
+                let referrer = Reference::with_digest(
+                    reference.registry().to_owned(),
+                    reference.repository().to_owned(),
+                    entry.digest.clone(),
+                );
+                if let Ok((oci_client::manifest::OciManifest::Image(manifest), _)) =
+                    self.client.pull_any_manifest(&referrer).await
+                    && (manifest
+                        .artifact_type
+                        .as_deref()
+                        .is_some_and(is_signature_media_type)
+                        || is_signature_media_type(&manifest.config.media_type))
+                {
+                    return true;
+                }
+            }
+        }
+
+        let signature = Reference::with_tag(
+            reference.registry().to_owned(),
+            reference.repository().to_owned(),
+            signature_tag(digest),
+        );
+        self.client.pull_any_manifest(&signature).await.is_ok()
+    }
+
     /// Best-effort: fetch and store referrers (signatures, SBOMs, attestations)
     /// for a manifest. Silently skips if the registry doesn't support the
     /// Referrers API or if any error occurs, but logs unexpected errors.
@@ -1536,6 +3167,17 @@ impl Manager {
         err: anyhow::Error,
         reference: &Reference,
     ) -> anyhow::Error {
+        if is_unresolved_index(&err) {
+            let candidates = match self.client.pull_index(reference).await {
+                Ok(Some(index)) => format_index_candidates(reference, &index.manifests),
+                _ => "could not fetch the image index from the registry".to_string(),
+            };
+            return ManagerError::UnresolvedImageIndex {
+                reference: reference.whole(),
+                candidates,
+            }
+            .into();
+        }
         if !is_manifest_unknown(&err) {
             return err;
         }
@@ -1589,8 +3231,8 @@ impl Manager {
         &self,
         manifest: &component_manifest::Manifest,
         manifest_dir: &Path,
-    ) -> anyhow::Result<crate::publish::PublishPlan> {
-        crate::publish::plan(manifest, manifest_dir).await
+    ) -> Result<crate::publish::PublishPlan> {
+        Ok(crate::publish::plan(manifest, manifest_dir).await?)
     }
 
     /// Publish the artifact described by `manifest` to an OCI registry.
@@ -1601,15 +3243,30 @@ impl Manager {
     ///
     /// The target registry comes from the manifest's
     /// `[package].registry_ref` field — there is no implicit default.
+    #[tracing::instrument(skip_all)]
     pub async fn publish(
         &self,
         manifest: &component_manifest::Manifest,
         manifest_dir: &Path,
-    ) -> anyhow::Result<crate::publish::PublishPlan> {
+    ) -> Result<crate::publish::PublishPlan> {
+        let pkg = crate::publish::require_package(manifest)?;
+        let reference = crate::publish::resolve_reference(pkg)?;
+        self.publish_to(manifest, manifest_dir, reference).await
+    }
+
+    /// Publish the artifact described by `manifest` to `reference`,
+    /// rather than to the manifest's `[package].registry_ref`.
+    #[tracing::instrument(skip_all, fields(reference = %reference))]
+    pub async fn publish_to(
+        &self,
+        manifest: &component_manifest::Manifest,
+        manifest_dir: &Path,
+        reference: Reference,
+    ) -> Result<crate::publish::PublishPlan> {
         if self.offline {
-            anyhow::bail!("cannot publish in offline mode");
+            return Err(ManagerError::OfflinePublish.into());
         }
-        let mut plan = crate::publish::plan(manifest, manifest_dir).await?;
+        let mut plan = crate::publish::plan_for(manifest, manifest_dir, reference).await?;
         let bytes = std::mem::take(&mut plan.bytes);
         let annotations = std::mem::take(&mut plan.annotations);
         let _response = self
@@ -1624,6 +3281,221 @@ impl Manager {
         tracing::debug!(reference = %plan.reference, "published artifact");
         Ok(plan)
     }
+
+    /// Sign the artifact `reference` points at in its registry, the way
+    /// `cosign sign` does.
+    ///
+    /// The signature covers the manifest digest the registry resolves the
+    /// reference to, and is pushed under the cosign `sha256-<hex>.sig` tag
+    /// next to it. See [`crate::sign`] for the signers and what is attached.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`SignError::AlreadySigned`](crate::sign::SignError::AlreadySigned)
+    /// if the signature tag exists, unless [`SignOptions::force`] is set.
+    #[tracing::instrument(skip_all, fields(reference = %reference))]
+    pub async fn sign(&self, reference: &Reference, options: &SignOptions) -> Result<SignResult> {
+        if self.offline {
+            return Err(ManagerError::OfflineSign.into());
+        }
+        let (_manifest, digest) = self.client.pull_any_manifest(reference).await?;
+        let signature = Reference::with_tag(
+            reference.registry().to_owned(),
+            reference.repository().to_owned(),
+            signature_tag(&digest),
+        );
+        if !options.force && self.client.pull_any_manifest(&signature).await.is_ok() {
+            return Err(SignError::AlreadySigned {
+                reference: reference.whole(),
+                tag: signature_tag(&digest),
+            }
+            .into());
+        }
+
+        let payload = crate::sign::simple_signing_payload(reference, &digest);
+        let network = self.config.network.clone().unwrap_or_default();
+        let layer = crate::sign::sign(options, &network, payload).await?;
+        self.client
+            .push_signature(&signature, layer.payload, layer.annotations)
+            .await?;
+        tracing::debug!(%digest, signature = %signature, "signed artifact");
+
+        Ok(SignResult {
+            reference: reference.whole(),
+            digest,
+            signature: signature.whole(),
+            identity: layer.identity,
+            log_index: layer.log_index,
+        })
+    }
+
+    /// Check an artifact's cosign signatures and attestations against the
+    /// policy's trusted keys for its namespace, and any `extra_keys`.
+    ///
+    /// Verification fails if the artifact has no signature, if any of its
+    /// signatures or attestations is malformed or covers another artifact,
+    /// if keys are trusted but none signed it, or if the policy requires an
+    /// attestation that isn't there (or that no trusted key signed).
+    /// Failures are reported in [`VerifyReport::failures`], not as errors.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if offline mode is enabled, if a trusted key can't
+    /// be read, or if the registry can't be reached.
+    #[tracing::instrument(skip_all, fields(reference = %reference))]
+    pub async fn verify(
+        &self,
+        reference: &Reference,
+        extra_keys: &[std::path::PathBuf],
+    ) -> Result<VerifyReport> {
+        if self.offline {
+            return Err(ManagerError::OfflineVerify.into());
+        }
+        use anyhow::Context as _;
+
+        let mut keys = Vec::new();
+        let trusted = self.policy.trusted_keys_for(reference);
+        for path in trusted.iter().map(|trusted| &trusted.key).chain(extra_keys) {
+            let pem = std::fs::read_to_string(path)
+                .with_context(|| format!("Failed to read trusted key {}", path.display()))?;
+            let key = crate::sign::VerifyingKey::from_pem(&pem)
+                .with_context(|| format!("Failed to read trusted key {}", path.display()))?;
+            keys.push((path.display().to_string(), key));
+        }
+
+        let (_manifest, digest) = self.client.pull_any_manifest(reference).await?;
+        let tagged = |tag: String| {
+            Reference::with_tag(
+                reference.registry().to_owned(),
+                reference.repository().to_owned(),
+                tag,
+            )
+        };
+
+        let signatures: Vec<VerifiedSignature> = self
+            .read_layers(&tagged(signature_tag(&digest)))
+            .await?
+            .into_iter()
+            .filter(|(layer, _)| layer.media_type == crate::sign::SIMPLE_SIGNING_MEDIA_TYPE)
+            .map(|(layer, data)| {
+                let annotations = layer.annotations.unwrap_or_default();
+                VerifiedSignature {
+                    keyless: annotations.contains_key("dev.sigstore.cosign/certificate"),
+                    verification: crate::sign::check_signature(&data, &annotations, &digest, &keys),
+                }
+            })
+            .collect();
+        let attestations: Vec<VerifiedAttestation> = self
+            .read_layers(&tagged(attestation_tag(&digest)))
+            .await?
+            .into_iter()
+            .filter(|(layer, _)| is_attestation_media_type(&layer.media_type))
+            .map(|(_, data)| {
+                let checked = crate::sign::check_attestation(&data, &digest, &keys);
+                VerifiedAttestation {
+                    predicate_type: checked.predicate_type,
+                    verification: checked.verification,
+                }
+            })
+            .collect();
+
+        let mut failures = Vec::new();
+        if signatures.is_empty() {
+            failures.push("no signature found".to_string());
+        }
+        for (kind, verification) in signatures
+            .iter()
+            .map(|signature| ("signature", &signature.verification))
+            .chain(
+                attestations
+                    .iter()
+                    .map(|attestation| ("attestation", &attestation.verification)),
+            )
+        {
+            if let crate::sign::Verification::Invalid { reason } = verification {
+                failures.push(format!("invalid {kind}: {reason}"));
+            }
+        }
+        if !keys.is_empty()
+            && !signatures.is_empty()
+            && !signatures.iter().any(|s| s.verification.is_verified())
+        {
+            failures.push("no signature was made by a trusted key".to_string());
+        }
+        if let Some(namespace) = self.policy.attestation_namespace(reference) {
+            if attestations.is_empty() {
+                failures.push(format!(
+                    "no attestation found, but the policy requires one for {namespace}"
+                ));
+            } else if !keys.is_empty() && !attestations.iter().any(|a| a.verification.is_verified())
+            {
+                failures.push("no attestation was made by a trusted key".to_string());
+            }
+        }
+
+        Ok(VerifyReport {
+            reference: reference.whole(),
+            digest,
+            trusted_keys: keys.into_iter().map(|(name, _)| name).collect(),
+            signatures,
+            attestations,
+            failures,
+        })
+    }
+
+    /// Fetch every layer of the manifest at `reference`, or nothing if
+    /// there is no such manifest.
+    async fn read_layers(&self, reference: &Reference) -> Result<Vec<(OciDescriptor, Vec<u8>)>> {
+        let manifest = match self.client.pull_manifest(reference).await {
+            Ok((manifest, _digest)) => manifest,
+            Err(err) if is_manifest_unknown(&err) => return Ok(Vec::new()),
+            Err(err) => return Err(err.into()),
+        };
+        let mut layers = Vec::new();
+        for layer in manifest.layers {
+            let mut stream = self.client.pull_layer_stream(reference, &layer).await?;
+            let mut data = Vec::new();
+            while let Some(chunk) = stream.next().await {
+                data.extend_from_slice(&chunk?);
+            }
+            layers.push((layer, data));
+        }
+        Ok(layers)
+    }
+}
+
+/// Emit a cache hit/miss event for the outcome of a store insert.
+fn log_insert_result(result: InsertResult, digest: Option<&str>) {
+    let digest = digest.unwrap_or("unknown");
+    match result {
+        InsertResult::Inserted => tracing::debug!(digest, "cache miss, stored new image"),
+        InsertResult::AlreadyExists => tracing::debug!(digest, "cache hit, image already stored"),
+    }
+}
+
+/// The total size of the layers a manifest describes.
+fn layers_size(layers: &[OciDescriptor]) -> u64 {
+    layers
+        .iter()
+        .map(|l| u64::try_from(l.size.max(0)).unwrap_or(0))
+        .sum()
+}
+
+/// The reference a locked package is pulled by: its locked digest, or its
+/// version when no digest is locked.
+fn locked_reference(package: &component_manifest::LockedPackage) -> Result<Reference> {
+    let reference = if package.digest.is_empty() {
+        format!("{}:{}", package.registry, package.version)
+    } else {
+        format!("{}@{}", package.registry, package.digest)
+    };
+    crate::parse_reference(&reference).map_err(|reason| {
+        ManagerError::InvalidReference {
+            reference: reference.clone(),
+            reason,
+        }
+        .into()
+    })
 }
 
 /// Check whether an `anyhow::Error` wraps an OCI "manifest unknown" error.
@@ -1640,6 +3512,39 @@ fn is_manifest_unknown(err: &anyhow::Error) -> bool {
     })
 }
 
+/// Whether an error is oci-client failing to pick an entry from an image index.
+fn is_unresolved_index(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| {
+        matches!(
+            cause.downcast_ref::<OciDistributionError>(),
+            Some(OciDistributionError::ImageManifestNotFoundError(_))
+        )
+    })
+}
+
+/// Format the entries of an image index as digest-pinned references.
+fn format_index_candidates<'a>(
+    reference: &Reference,
+    entries: impl IntoIterator<Item = &'a ImageIndexEntry>,
+) -> String {
+    entries
+        .into_iter()
+        .map(|entry| {
+            let pinned = format!(
+                "{}/{}@{}",
+                reference.registry(),
+                reference.repository(),
+                entry.digest
+            );
+            match &entry.platform {
+                Some(platform) => format!("{pinned} ({}/{})", platform.os, platform.architecture),
+                None => pinned,
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
 /// Format a human-readable hint listing available tags.
 ///
 /// Uses [`filter_tag_suggestions`] for context-aware pre-release filtering:
@@ -1677,3 +3582,754 @@ fn format_available_tags_hint(tags: &[String], requested_tag: Option<&str>) -> S
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::oci::FakeRegistry;
+
+    /// The smallest valid component: just the header.
+    const COMPONENT: [u8; 8] = [0x00, 0x61, 0x73, 0x6d, 0x0d, 0x00, 0x01, 0x00];
+
+    async fn manager_with(registry: &Arc<FakeRegistry>) -> (tempfile::TempDir, Manager) {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = Manager::with_transport(dir.path(), Arc::clone(registry) as _)
+            .await
+            .unwrap();
+        (dir, manager)
+    }
+
+    #[tokio::test]
+    async fn pull_stores_image_from_registry() {
+        let registry = Arc::new(FakeRegistry::new());
+        let reference: Reference = "ghcr.io/example/hello:1.0.0".parse().unwrap();
+        let digest = registry
+            .insert_component(&reference, COMPONENT.to_vec())
+            .unwrap();
+        let (_dir, manager) = manager_with(&registry).await;
+
+        let pulled = manager.pull(reference.clone()).await.unwrap();
+        assert_eq!(pulled.insert_result, InsertResult::Inserted);
+        assert_eq!(pulled.digest.as_deref(), Some(digest.as_str()));
+        assert_eq!(manager.read_component(&reference).await.unwrap(), COMPONENT);
+
+        let again = manager.pull(reference).await.unwrap();
+        assert_eq!(again.insert_result, InsertResult::AlreadyExists);
+    }
+
+    #[tokio::test]
+    async fn select_index_entry_pins_the_matching_entry() {
+        let registry = Arc::new(FakeRegistry::new());
+        let entry = |tag: &str, platform: &str| {
+            let reference: Reference = format!("ghcr.io/example/hello:{tag}").parse().unwrap();
+            let digest = registry
+                .insert_component(&reference, COMPONENT.to_vec())
+                .unwrap();
+            let entry: ImageIndexEntry = serde_json::from_value(serde_json::json!({
+                "mediaType": "application/vnd.oci.image.manifest.v1+json",
+                "digest": digest,
+                "size": 0,
+                "platform": serde_json::from_str::<serde_json::Value>(platform).unwrap(),
+            }))
+            .unwrap();
+            entry
+        };
+        let p2 = entry("p2", r#"{"architecture": "wasm", "os": "wasip2"}"#);
+        let p3 = entry("p3", r#"{"architecture": "wasm", "os": "wasip3"}"#);
+        let reference: Reference = "ghcr.io/example/hello:1.0.0".parse().unwrap();
+        registry
+            .insert_index(&reference, vec![p2.clone(), p3])
+            .unwrap();
+        let (_dir, manager) = manager_with(&registry).await;
+
+        let pinned = manager
+            .select_index_entry(&reference, Some("wasip2/wasm"), None)
+            .await
+            .unwrap();
+        assert_eq!(pinned.digest(), Some(p2.digest.as_str()));
+        assert_eq!(pinned.tag(), Some("1.0.0"));
+
+        let err = manager
+            .select_index_entry(&reference, Some("linux/amd64"), None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("platform 'linux/amd64'"), "{err}");
+
+        let err = manager
+            .select_index_entry(
+                &reference,
+                None,
+                Some(oci_wasm::WASM_MANIFEST_CONFIG_MEDIA_TYPE),
+            )
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("more than one entry"), "{err}");
+
+        // Plain manifests need no selecting.
+        let single: Reference = "ghcr.io/example/hello:p2".parse().unwrap();
+        let selected = manager
+            .select_index_entry(&single, Some("linux/amd64"), None)
+            .await
+            .unwrap();
+        assert_eq!(selected, single);
+    }
+
+    #[tokio::test]
+    async fn pull_with_progress_streams_layers() {
+        let registry = Arc::new(FakeRegistry::new());
+        let reference: Reference = "ghcr.io/example/hello:1.0.0".parse().unwrap();
+        registry
+            .insert_component(&reference, COMPONENT.to_vec())
+            .unwrap();
+        let (_dir, manager) = manager_with(&registry).await;
+
+        let (tx, _rx) = tokio::sync::mpsc::channel(64);
+        let pulled = manager
+            .pull_with_progress(reference.clone(), &tx)
+            .await
+            .unwrap();
+        assert_eq!(pulled.insert_result, InsertResult::Inserted);
+        assert_eq!(manager.read_component(&reference).await.unwrap(), COMPONENT);
+    }
+
+    #[tokio::test]
+    async fn list_tags_pages_through_every_tag() {
+        let registry = Arc::new(FakeRegistry::new());
+        for tag in ["0.1.0", "0.2.0", "1.0.0"] {
+            let reference: Reference = format!("ghcr.io/example/hello:{tag}").parse().unwrap();
+            registry
+                .insert_component(&reference, COMPONENT.to_vec())
+                .unwrap();
+        }
+        let (_dir, manager) = manager_with(&registry).await;
+
+        let reference: Reference = "ghcr.io/example/hello:1.0.0".parse().unwrap();
+        let tags = manager.list_tags(&reference).await.unwrap();
+        assert_eq!(tags, ["0.1.0", "0.2.0", "1.0.0"]);
+    }
+
+    #[tokio::test]
+    async fn pulling_a_missing_tag_lists_available_tags() {
+        let registry = Arc::new(FakeRegistry::new());
+        let published: Reference = "ghcr.io/example/hello:1.0.0".parse().unwrap();
+        registry
+            .insert_component(&published, COMPONENT.to_vec())
+            .unwrap();
+        let (_dir, manager) = manager_with(&registry).await;
+
+        let missing: Reference = "ghcr.io/example/hello:2.0.0".parse().unwrap();
+        let crate::PackageManagerError::NotFound(err) = manager.pull(missing).await.unwrap_err()
+        else {
+            panic!("expected a not-found error");
+        };
+        match err.downcast_ref::<ManagerError>() {
+            Some(ManagerError::ManifestNotFound { tag, hint, .. }) => {
+                assert_eq!(tag, "2.0.0");
+                assert!(hint.contains("1.0.0"), "hint should list tags: {hint}");
+            }
+            other => panic!("expected ManifestNotFound, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn stored_manifest_matches_the_registry() {
+        let registry = Arc::new(FakeRegistry::new());
+        let reference: Reference = "ghcr.io/example/hello:1.0.0".parse().unwrap();
+        let digest = registry
+            .insert_component(&reference, COMPONENT.to_vec())
+            .unwrap();
+        let (_dir, manager) = manager_with(&registry).await;
+        let (tx, _rx) = tokio::sync::mpsc::channel(64);
+        manager
+            .pull_with_progress(reference.clone(), &tx)
+            .await
+            .unwrap();
+
+        let by_tag = manager.stored_manifest(&reference).unwrap().unwrap();
+        assert_eq!(by_tag.digest, digest);
+        let pinned = reference.clone_with_digest(digest);
+        let by_digest = manager.stored_manifest(&pinned).unwrap().unwrap();
+        assert_eq!(by_digest, by_tag);
+
+        // The config blob is kept so the image can be served.
+        let manifest: OciImageManifest = serde_json::from_slice(&by_tag.body).unwrap();
+        let store_dir = manager.state_info().store_dir().to_path_buf();
+        assert!(
+            cacache::metadata(&store_dir, &manifest.config.digest)
+                .await
+                .unwrap()
+                .is_some()
+        );
+
+        assert_eq!(
+            manager.stored_tags("ghcr.io", "example/hello").unwrap(),
+            Some(vec!["1.0.0".to_string()])
+        );
+        assert_eq!(
+            manager.stored_tags("ghcr.io", "example/other").unwrap(),
+            None
+        );
+        assert_eq!(
+            manager.stored_repositories().unwrap(),
+            ["ghcr.io/example/hello"]
+        );
+    }
+
+    #[tokio::test]
+    async fn policy_rejects_denied_registries_and_large_artifacts() {
+        let registry = Arc::new(FakeRegistry::new());
+        let reference: Reference = "ghcr.io/example/hello:1.0.0".parse().unwrap();
+        registry
+            .insert_component(&reference, COMPONENT.to_vec())
+            .unwrap();
+        let (_dir, mut manager) = manager_with(&registry).await;
+
+        manager.set_policy(Policy {
+            denied_registries: vec!["ghcr.io".into()],
+            ..Policy::default()
+        });
+        let err = manager.pull(reference.clone()).await.unwrap_err();
+        assert!(matches!(
+            err,
+            crate::PackageManagerError::PolicyViolation(_)
+        ));
+
+        manager.set_policy(Policy {
+            max_artifact_size: Some(4),
+            ..Policy::default()
+        });
+        let err = manager.pull(reference.clone()).await.unwrap_err();
+        assert!(err.to_string().contains("exceeds the 4 B limit"), "{err}");
+        let (tx, _rx) = tokio::sync::mpsc::channel(64);
+        assert!(manager.pull_with_progress(reference, &tx).await.is_err());
+        assert!(manager.stored_repositories().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn policy_requires_a_signature_tag() {
+        let registry = Arc::new(FakeRegistry::new());
+        let reference: Reference = "ghcr.io/example/hello:1.0.0".parse().unwrap();
+        let digest = registry
+            .insert_component(&reference, COMPONENT.to_vec())
+            .unwrap();
+        let (_dir, mut manager) = manager_with(&registry).await;
+        manager.set_policy(Policy {
+            require_signature: vec!["ghcr.io/example".into()],
+            ..Policy::default()
+        });
+
+        let err = manager.pull(reference.clone()).await.unwrap_err();
+        assert!(matches!(
+            err,
+            crate::PackageManagerError::PolicyViolation(_)
+        ));
+
+        let signature: Reference = format!("ghcr.io/example/hello:{}", signature_tag(&digest))
+            .parse()
+            .unwrap();
+        registry
+            .insert_component(&signature, COMPONENT.to_vec())
+            .unwrap();
+        let pulled = manager.pull(reference).await.unwrap();
+        assert_eq!(pulled.insert_result, InsertResult::Inserted);
+    }
+
+    #[tokio::test]
+    async fn sign_pushes_a_cosign_signature() {
+        use base64::Engine;
+        use p256::ecdsa::signature::Verifier;
+        use p256::pkcs8::DecodePublicKey;
+
+        let registry = Arc::new(FakeRegistry::new());
+        let reference: Reference = "ghcr.io/example/hello:1.0.0".parse().unwrap();
+        let digest = registry
+            .insert_component(&reference, COMPONENT.to_vec())
+            .unwrap();
+        let (_dir, mut manager) = manager_with(&registry).await;
+        let key = crate::sign::SigningKey::generate();
+        let mut options = SignOptions {
+            signer: crate::sign::Signer::Key(key.clone()),
+            rekor_url: None,
+            force: false,
+        };
+
+        let signed = manager.sign(&reference, &options).await.unwrap();
+        assert_eq!(signed.digest, digest);
+        assert_eq!(
+            signed.signature,
+            format!("ghcr.io/example/hello:{}", signature_tag(&digest))
+        );
+        assert_eq!(signed.log_index, None);
+
+        let image = manager
+            .client
+            .pull(&signed.signature.parse().unwrap())
+            .await
+            .unwrap();
+        let layer = image.layers.first().unwrap();
+        assert_eq!(layer.media_type, crate::sign::SIMPLE_SIGNING_MEDIA_TYPE);
+        let payload: serde_json::Value = serde_json::from_slice(&layer.data).unwrap();
+        assert_eq!(
+            payload
+                .pointer("/critical/image/docker-manifest-digest")
+                .and_then(serde_json::Value::as_str),
+            Some(digest.as_str())
+        );
+        let signature = base64::engine::general_purpose::STANDARD
+            .decode(&layer.annotations.as_ref().unwrap()["dev.cosignproject.cosign/signature"])
+            .unwrap();
+        let signature = p256::ecdsa::Signature::from_der(&signature).unwrap();
+        p256::ecdsa::VerifyingKey::from_public_key_pem(&key.public_key_pem())
+            .unwrap()
+            .verify(&layer.data, &signature)
+            .unwrap();
+
+        let err = manager.sign(&reference, &options).await.unwrap_err();
+        assert!(err.to_string().contains("already signed"), "{err}");
+        options.force = true;
+        manager.sign(&reference, &options).await.unwrap();
+
+        manager.set_policy(Policy {
+            require_signature: vec!["ghcr.io/example".into()],
+            ..Policy::default()
+        });
+        manager.pull(reference).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn verify_checks_signatures_against_trusted_keys() {
+        let registry = Arc::new(FakeRegistry::new());
+        let reference: Reference = "ghcr.io/example/hello:1.0.0".parse().unwrap();
+        let digest = registry
+            .insert_component(&reference, COMPONENT.to_vec())
+            .unwrap();
+        let (dir, mut manager) = manager_with(&registry).await;
+
+        let report = manager.verify(&reference, &[]).await.unwrap();
+        assert_eq!(report.digest, digest);
+        assert_eq!(report.failures, ["no signature found"]);
+
+        let key = crate::sign::SigningKey::generate();
+        let options = SignOptions {
+            signer: crate::sign::Signer::Key(key.clone()),
+            rekor_url: None,
+            force: false,
+        };
+        manager.sign(&reference, &options).await.unwrap();
+
+        let trusted = dir.path().join("cosign.pub");
+        std::fs::write(&trusted, key.public_key_pem()).unwrap();
+        let other = dir.path().join("other.pub");
+        std::fs::write(&other, crate::sign::SigningKey::generate().public_key_pem()).unwrap();
+
+        let report = manager.verify(&reference, &[]).await.unwrap();
+        assert!(report.passed(), "{:?}", report.failures);
+        let [signature] = report.signatures.as_slice() else {
+            panic!("{:?}", report.signatures);
+        };
+        assert_eq!(signature.verification, crate::sign::Verification::Untrusted);
+
+        manager.set_policy(Policy {
+            trusted_keys: vec![crate::policy::TrustedKey {
+                namespace: "ghcr.io/example".into(),
+                key: trusted.clone(),
+            }],
+            ..Policy::default()
+        });
+        let report = manager.verify(&reference, &[]).await.unwrap();
+        assert!(report.passed(), "{:?}", report.failures);
+        let [signature] = report.signatures.as_slice() else {
+            panic!("{:?}", report.signatures);
+        };
+        assert!(signature.verification.is_verified());
+        assert!(!signature.keyless);
+
+        let report = manager
+            .verify(&reference, std::slice::from_ref(&other))
+            .await
+            .unwrap();
+        assert!(report.passed(), "{:?}", report.failures);
+
+        manager.set_policy(Policy {
+            require_attestation: vec!["ghcr.io/example".into()],
+            ..Policy::default()
+        });
+        let report = manager.verify(&reference, &[other]).await.unwrap();
+        assert_eq!(
+            report.failures,
+            [
+                "no signature was made by a trusted key",
+                "no attestation found, but the policy requires one for ghcr.io/example",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn locked_package_exists_checks_the_digest() {
+        let registry = Arc::new(FakeRegistry::new());
+        let reference: Reference = "ghcr.io/example/hello:1.0.0".parse().unwrap();
+        let digest = registry
+            .insert_component(&reference, COMPONENT.to_vec())
+            .unwrap();
+        let (_dir, manager) = manager_with(&registry).await;
+        let mut package = component_manifest::LockedPackage {
+            name: "example:hello".into(),
+            version: "1.0.0".into(),
+            registry: "ghcr.io/example/hello".into(),
+            digest,
+            dependencies: Vec::new(),
+        };
+        assert!(manager.locked_package_exists(&package).await.unwrap());
+
+        package.digest = format!("sha256:{}", "0".repeat(64));
+        assert!(!manager.locked_package_exists(&package).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn vendor_materializes_locked_packages() {
+        let registry = Arc::new(FakeRegistry::new());
+        let reference: Reference = "ghcr.io/example/hello:1.0.0".parse().unwrap();
+        let digest = registry
+            .insert_component(&reference, COMPONENT.to_vec())
+            .unwrap();
+        let (_dir, manager) = manager_with(&registry).await;
+        let lockfile = component_manifest::Lockfile {
+            components: vec![component_manifest::LockedPackage {
+                name: "example:hello".into(),
+                version: "1.0.0".into(),
+                registry: "ghcr.io/example/hello".into(),
+                digest: digest.clone(),
+                dependencies: Vec::new(),
+            }],
+            ..component_manifest::Lockfile::default()
+        };
+        let vendor_dir = tempfile::tempdir().unwrap();
+        let stale = vendor_dir.path().join("deps/old@0.1.0");
+        std::fs::create_dir_all(&stale).unwrap();
+
+        let vendored = manager.vendor(&lockfile, vendor_dir.path()).await.unwrap();
+        let package = vendored.first().unwrap();
+        assert!(package.pulled);
+        assert_eq!(
+            package.dir,
+            vendor_dir.path().join("deps/example-hello@1.0.0")
+        );
+        assert_eq!(
+            std::fs::read(package.dir.join("package.wasm")).unwrap(),
+            COMPONENT
+        );
+        assert!(!stale.exists());
+        let marker = std::fs::read_to_string(vendor_dir.path().join(VENDOR_MARKER)).unwrap();
+        assert!(marker.contains(&digest));
+
+        // Already in the store, so vendoring again doesn't pull.
+        let vendored = manager.vendor(&lockfile, vendor_dir.path()).await.unwrap();
+        assert!(!vendored.first().unwrap().pulled);
+    }
+
+    #[tokio::test]
+    async fn vendor_rejects_names_outside_the_tree() {
+        let registry = Arc::new(FakeRegistry::new());
+        let (_dir, manager) = manager_with(&registry).await;
+        let lockfile = component_manifest::Lockfile {
+            components: vec![component_manifest::LockedPackage {
+                name: "..".into(),
+                version: String::new(),
+                registry: "ghcr.io/example/hello".into(),
+                digest: format!("sha256:{}", "0".repeat(64)),
+                dependencies: Vec::new(),
+            }],
+            ..component_manifest::Lockfile::default()
+        };
+        let vendor_dir = tempfile::tempdir().unwrap();
+        let existing = vendor_dir.path().join("deps/example-hello@1.0.0");
+        std::fs::create_dir_all(&existing).unwrap();
+
+        let err = manager
+            .vendor(&lockfile, vendor_dir.path())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("not a valid package"), "{err}");
+        assert!(existing.exists());
+        assert!(!vendor_dir.path().join("package.wasm").exists());
+    }
+
+    #[tokio::test]
+    async fn pull_locked_pulls_by_locked_digest() {
+        let registry = Arc::new(FakeRegistry::new());
+        let reference: Reference = "ghcr.io/example/hello:1.0.0".parse().unwrap();
+        let digest = registry
+            .insert_component(&reference, COMPONENT.to_vec())
+            .unwrap();
+        let (_dir, manager) = manager_with(&registry).await;
+        let lockfile = component_manifest::Lockfile {
+            components: vec![component_manifest::LockedPackage {
+                name: "example:hello".into(),
+                version: "1.0.0".into(),
+                registry: "ghcr.io/example/hello".into(),
+                digest: digest.clone(),
+                dependencies: Vec::new(),
+            }],
+            interfaces: vec![component_manifest::LockedPackage {
+                name: "example:missing".into(),
+                version: "1.0.0".into(),
+                registry: "ghcr.io/example/missing".into(),
+                digest: String::new(),
+                dependencies: Vec::new(),
+            }],
+            ..component_manifest::Lockfile::default()
+        };
+
+        let results = manager.pull_locked(&lockfile, 2).await.unwrap();
+        let references: Vec<_> = results.iter().map(|(r, _)| r.whole()).collect();
+        assert_eq!(
+            references,
+            [
+                format!("ghcr.io/example/hello@{digest}"),
+                "ghcr.io/example/missing:1.0.0".to_string(),
+            ]
+        );
+        let outcomes: Vec<_> = results.iter().map(|(_, result)| result.is_ok()).collect();
+        assert_eq!(outcomes, [true, false]);
+        let (locked, _) = results.first().unwrap();
+        assert_eq!(manager.read_component(locked).await.unwrap(), COMPONENT);
+    }
+
+    #[tokio::test]
+    async fn pull_many_reports_each_reference_in_order() {
+        let registry = Arc::new(FakeRegistry::new());
+        let first: Reference = "ghcr.io/example/hello:1.0.0".parse().unwrap();
+        let second: Reference = "ghcr.io/example/other:1.0.0".parse().unwrap();
+        let missing: Reference = "ghcr.io/example/missing:1.0.0".parse().unwrap();
+        for reference in [&first, &second] {
+            registry
+                .insert_component(reference, COMPONENT.to_vec())
+                .unwrap();
+        }
+        let (_dir, manager) = manager_with(&registry).await;
+
+        let results = manager
+            .pull_many(
+                [
+                    first.clone(),
+                    missing.clone(),
+                    second.clone(),
+                    first.clone(),
+                ],
+                2,
+            )
+            .await;
+        let references: Vec<_> = results.iter().map(|(r, _)| r.clone()).collect();
+        assert_eq!(references, [first.clone(), missing, second.clone()]);
+        let outcomes: Vec<_> = results.iter().map(|(_, result)| result.is_ok()).collect();
+        assert_eq!(outcomes, [true, false, true]);
+        for reference in [&first, &second] {
+            assert_eq!(manager.read_component(reference).await.unwrap(), COMPONENT);
+        }
+    }
+
+    #[tokio::test]
+    async fn inspect_remote_reads_metadata_without_storing() {
+        let registry = Arc::new(FakeRegistry::new());
+        let reference: Reference = "ghcr.io/example/hello:1.0.0".parse().unwrap();
+        registry
+            .insert_component(&reference, COMPONENT.to_vec())
+            .unwrap();
+        let (_dir, manager) = manager_with(&registry).await;
+
+        let inspection = manager.inspect_remote(&reference).await.unwrap();
+        assert_eq!(inspection.reference, reference.whole());
+        assert!(inspection.digest.starts_with("sha256:"));
+        assert_eq!(inspection.size, COMPONENT.len() as u64);
+        assert_eq!(inspection.layers.len(), 1);
+        assert!(inspection.referrers.is_empty());
+        assert!(manager.read_component(&reference).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn inspect_remote_lists_tags_and_signatures() {
+        let registry = Arc::new(FakeRegistry::new());
+        let reference: Reference = "ghcr.io/example/hello:1.0.0".parse().unwrap();
+        let digest = registry
+            .insert_component(&reference, COMPONENT.to_vec())
+            .unwrap();
+        let latest: Reference = "ghcr.io/example/hello:latest".parse().unwrap();
+        registry
+            .insert_component(&latest, COMPONENT.to_vec())
+            .unwrap();
+        let (_dir, manager) = manager_with(&registry).await;
+
+        let inspection = manager.inspect_remote(&reference).await.unwrap();
+        assert_eq!(inspection.tags, ["1.0.0", "latest"]);
+        assert!(!inspection.signed);
+        assert!(!inspection.attested);
+
+        let signature: Reference = format!("ghcr.io/example/hello:{}", signature_tag(&digest))
+            .parse()
+            .unwrap();
+        registry
+            .insert_component(&signature, COMPONENT.to_vec())
+            .unwrap();
+        let inspection = manager.inspect_remote(&reference).await.unwrap();
+        assert_eq!(inspection.tags, ["1.0.0", "latest"]);
+        assert!(inspection.signed);
+        assert!(!inspection.attested);
+    }
+
+    #[tokio::test]
+    async fn resolve_digest_asks_the_registry_or_the_store() {
+        let registry = Arc::new(FakeRegistry::new());
+        let reference: Reference = "ghcr.io/example/hello:1.0.0".parse().unwrap();
+        let digest = registry
+            .insert_component(&reference, COMPONENT.to_vec())
+            .unwrap();
+        let (dir, manager) = manager_with(&registry).await;
+        assert_eq!(manager.resolve_digest(&reference).await.unwrap(), digest);
+
+        let pinned = reference.clone_with_digest("sha256:pinned".into());
+        assert_eq!(
+            manager.resolve_digest(&pinned).await.unwrap(),
+            "sha256:pinned"
+        );
+
+        manager.pull(reference.clone()).await.unwrap();
+        drop(manager);
+        let mut offline = Manager::with_transport(dir.path(), registry as _)
+            .await
+            .unwrap();
+        offline.offline = true;
+        assert_eq!(offline.resolve_digest(&reference).await.unwrap(), digest);
+        let missing: Reference = "ghcr.io/example/missing:1.0.0".parse().unwrap();
+        assert!(offline.resolve_digest(&missing).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn delete_matching_everything_keeps_pinned_images() {
+        let registry = Arc::new(FakeRegistry::new());
+        let kept: Reference = "ghcr.io/example/kept:1.0.0".parse().unwrap();
+        let cleared: Reference = "ghcr.io/example/cleared:1.0.0".parse().unwrap();
+        for reference in [&kept, &cleared] {
+            registry
+                .insert_component(reference, COMPONENT.to_vec())
+                .unwrap();
+        }
+        let (_dir, manager) = manager_with(&registry).await;
+        manager.pull(kept.clone()).await.unwrap();
+        manager.pull(cleared.clone()).await.unwrap();
+        manager.pin("ghcr.io", "example/kept").unwrap();
+
+        assert_eq!(manager.delete_matching("*").await.unwrap(), 1);
+        assert!(manager.stored_manifest(&kept).unwrap().is_some());
+        assert!(manager.stored_manifest(&cleared).unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn export_bundle_moves_images_to_another_store() {
+        let registry = Arc::new(FakeRegistry::new());
+        let reference: Reference = "ghcr.io/example/hello:1.0.0".parse().unwrap();
+        registry
+            .insert_component(&reference, COMPONENT.to_vec())
+            .unwrap();
+        let (dir, manager) = manager_with(&registry).await;
+        manager.pull(reference.clone()).await.unwrap();
+        let bundle = dir.path().join("bundle.tar.zst");
+        let size = manager
+            .export_bundle(std::slice::from_ref(&reference), &bundle)
+            .await
+            .unwrap();
+        assert_eq!(size, std::fs::metadata(&bundle).unwrap().len());
+
+        let missing: Reference = "ghcr.io/example/missing:1.0.0".parse().unwrap();
+        let err = manager
+            .export_bundle(&[missing], &bundle)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("missing"), "{err}");
+
+        // The other store has never seen the registry.
+        let (_other_dir, other) = manager_with(&Arc::new(FakeRegistry::new())).await;
+        let imported = other.import_bundle(&bundle).await.unwrap();
+        let [(imported, result)] = imported.as_slice() else {
+            panic!("expected one image, got {}", imported.len());
+        };
+        assert_eq!(*imported, reference);
+        assert_eq!(result.insert_result, InsertResult::Inserted);
+        assert_eq!(other.read_component(&reference).await.unwrap(), COMPONENT);
+    }
+
+    #[tokio::test]
+    async fn relocate_moves_the_store_and_leaves_a_pointer() {
+        let registry = Arc::new(FakeRegistry::new());
+        let reference: Reference = "ghcr.io/example/hello:1.0.0".parse().unwrap();
+        registry
+            .insert_component(&reference, COMPONENT.to_vec())
+            .unwrap();
+        let (dir, mut manager) = manager_with(&registry).await;
+        manager.pull(reference.clone()).await.unwrap();
+
+        let new_dir = tempfile::tempdir().unwrap();
+        let target = new_dir.path().join("wasm");
+        let (tx, mut rx) = tokio::sync::mpsc::channel(64);
+        manager.relocate_with_progress(&target, &tx).await.unwrap();
+        drop(tx);
+
+        let mut last = None;
+        while let Some(progress) = rx.recv().await {
+            last = Some(progress);
+        }
+        let last = last.expect("relocating reports progress");
+        assert_eq!(last.bytes_moved, last.total_bytes);
+        assert_eq!(manager.state_info().data_dir(), target);
+        assert!(!dir.path().join("db").exists());
+        assert_eq!(manager.read_component(&reference).await.unwrap(), COMPONENT);
+
+        // Opening the old location finds the store at the new one.
+        drop(manager);
+        let transport: Arc<dyn crate::oci::Transport> = registry;
+        let reopened = Manager::with_transport(dir.path(), transport)
+            .await
+            .unwrap();
+        assert_eq!(reopened.state_info().data_dir(), target);
+        assert_eq!(
+            reopened.read_component(&reference).await.unwrap(),
+            COMPONENT
+        );
+    }
+
+    #[tokio::test]
+    async fn restore_replaces_the_database_and_checks_blobs() {
+        let registry = Arc::new(FakeRegistry::new());
+        let reference: Reference = "ghcr.io/example/hello:1.0.0".parse().unwrap();
+        registry
+            .insert_component(&reference, COMPONENT.to_vec())
+            .unwrap();
+        let (dir, mut manager) = manager_with(&registry).await;
+        manager.pull(reference.clone()).await.unwrap();
+
+        let backup = dir.path().join("backup.db3");
+        manager.backup(&backup).await.unwrap();
+
+        let report = manager.restore(&backup).await.unwrap();
+        assert!(report.is_clean(), "{report:?}");
+        assert_eq!(manager.read_component(&reference).await.unwrap(), COMPONENT);
+        assert!(
+            dir.path()
+                .join("db")
+                .join("metadata.db3.pre-restore.bak")
+                .exists()
+        );
+
+        // Deleting the image removes its blobs, so restoring it again
+        // reports them missing.
+        manager.delete(reference.clone()).await.unwrap();
+        let report = manager.restore(&backup).await.unwrap();
+        assert_eq!(report.issues.len(), 1);
+        assert!(report.issues.iter().all(|issue| !issue.repaired));
+
+        let not_a_database = dir.path().join("not-a-database");
+        std::fs::write(&not_a_database, b"hello").unwrap();
+        assert!(manager.restore(&not_a_database).await.is_err());
+    }
+}
@@ -1,6 +1,7 @@
 use oci_client::manifest::OciImageManifest;
 
-use crate::oci::InsertResult;
+use crate::oci::{ImageEntry, InsertResult};
+use crate::sign::Verification;
 
 /// Result of syncing the package index from a meta-registry.
 ///
@@ -120,3 +121,385 @@ pub struct InstallResult {
     /// Dependencies on other WIT packages extracted from the component metadata.
     pub dependencies: Vec<crate::types::DependencyItem>,
 }
+
+/// Everything in the local store that points at one content digest, as
+/// returned by [`Manager::find_by_digest`](super::Manager::find_by_digest).
+///
+/// # Example
+///
+/// ```
+/// use component_package_manager::manager::DigestUsage;
+///
+/// let usage = DigestUsage {
+///     images: vec![],
+///     tags: vec!["ghcr.io/example/app:1.0.0".to_string()],
+/// };
+/// assert!(!usage.is_empty());
+/// assert!(DigestUsage::default().is_empty());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct DigestUsage {
+    /// Stored images whose manifest, or one of whose layers, has the digest.
+    pub images: Vec<ImageEntry>,
+    /// Every known `registry/repository:tag` pointing at such a manifest,
+    /// including tags whose image was indexed but never pulled.
+    pub tags: Vec<String>,
+}
+
+impl DigestUsage {
+    /// Returns `true` when nothing points at the digest.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.images.is_empty() && self.tags.is_empty()
+    }
+}
+
+/// A stored manifest, as a registry would serve it, returned by
+/// [`Manager::stored_manifest`](super::Manager::stored_manifest).
+///
+/// # Example
+///
+/// ```
+/// use component_package_manager::manager::StoredManifest;
+///
+/// let manifest = StoredManifest {
+///     digest: "sha256:abc".to_string(),
+///     media_type: "application/vnd.oci.image.manifest.v1+json".to_string(),
+///     body: b"{}".to_vec(),
+/// };
+/// assert_eq!(manifest.body, b"{}");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredManifest {
+    /// The digest of `body`.
+    pub digest: String,
+    /// The manifest's media type.
+    pub media_type: String,
+    /// The manifest JSON.
+    pub body: Vec<u8>,
+}
+
+/// One page of a paginated listing, with the total number of matches.
+///
+/// `next_offset` is the cursor for the following page; pass it back as the
+/// `offset` of the next call until it returns `None`.
+///
+/// # Example
+///
+/// ```
+/// use component_package_manager::manager::Page;
+///
+/// let page = Page {
+///     items: vec!["a", "b"],
+///     offset: 0,
+///     total: 5,
+/// };
+/// assert_eq!(page.next_offset(), Some(2));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct Page<T> {
+    /// The items on this page.
+    pub items: Vec<T>,
+    /// The offset this page starts at.
+    pub offset: u32,
+    /// Total number of matching items across all pages.
+    pub total: u64,
+}
+
+impl<T> Page<T> {
+    /// The offset of the next page, or `None` if this is the last page.
+    #[must_use]
+    pub fn next_offset(&self) -> Option<u32> {
+        let len = u32::try_from(self.items.len()).ok()?;
+        let next = self.offset.checked_add(len)?;
+        (len > 0 && u64::from(next) < self.total).then_some(next)
+    }
+}
+
+/// A locked package copied into a vendor tree by
+/// [`Manager::vendor`](super::Manager::vendor).
+///
+/// # Example
+///
+/// ```
+/// use component_package_manager::manager::VendoredPackage;
+/// use std::path::PathBuf;
+///
+/// let package = VendoredPackage {
+///     name: "wasi:clocks".into(),
+///     version: "0.2.5".into(),
+///     digest: "sha256:abc123".into(),
+///     dir: PathBuf::from("vendor/deps/wasi-clocks@0.2.5"),
+///     has_wit: true,
+///     pulled: false,
+/// };
+/// assert!(package.dir.ends_with("wasi-clocks@0.2.5"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VendoredPackage {
+    /// The package name from the lockfile (e.g. `wasi:clocks`).
+    pub name: String,
+    /// The locked version.
+    pub version: String,
+    /// The manifest digest the package was vendored from.
+    pub digest: String,
+    /// The package's directory, holding `package.wasm` and, for WIT
+    /// packages, `package.wit`.
+    pub dir: std::path::PathBuf,
+    /// Whether a `package.wit` was extracted.
+    pub has_wit: bool,
+    /// Whether the package had to be pulled because it wasn't in the store.
+    pub pulled: bool,
+}
+
+/// A package's registry metadata, fetched by
+/// [`Manager::inspect_remote`](super::Manager::inspect_remote) without
+/// downloading its layers.
+///
+/// # Example
+///
+/// ```
+/// use component_package_manager::manager::{RemoteInspection, RemoteLayer};
+///
+/// let inspection = RemoteInspection {
+///     reference: "ghcr.io/example/app:1.0.0".into(),
+///     digest: "sha256:abc123".into(),
+///     media_type: Some("application/vnd.oci.image.manifest.v1+json".into()),
+///     artifact_type: None,
+///     config_media_type: "application/vnd.wasm.config.v0+json".into(),
+///     size: 1024,
+///     layers: vec![RemoteLayer {
+///         digest: "sha256:def456".into(),
+///         media_type: "application/wasm".into(),
+///         size: 1024,
+///     }],
+///     annotations: Default::default(),
+///     created: None,
+///     author: None,
+///     os: Some("wasip2".into()),
+///     target: None,
+///     imports: vec!["wasi:io/streams@0.2.0".into()],
+///     exports: vec![],
+///     referrers: vec![],
+///     tags: vec!["1.0.0".into()],
+///     signed: false,
+///     attested: false,
+/// };
+/// assert_eq!(inspection.size, 1024);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct RemoteInspection {
+    /// The reference that was inspected.
+    pub reference: String,
+    /// The manifest digest the reference resolved to.
+    pub digest: String,
+    /// The manifest's media type.
+    pub media_type: Option<String>,
+    /// The manifest's artifact type.
+    pub artifact_type: Option<String>,
+    /// The config blob's media type.
+    pub config_media_type: String,
+    /// The total size of the layers, in bytes.
+    pub size: u64,
+    /// The layers, in manifest order.
+    pub layers: Vec<RemoteLayer>,
+    /// The manifest annotations.
+    pub annotations: std::collections::BTreeMap<String, String>,
+    /// When the artifact was built, from a wasm config.
+    pub created: Option<String>,
+    /// The artifact's author, from a wasm config.
+    pub author: Option<String>,
+    /// The target OS (e.g. `wasip2`), from a wasm config.
+    pub os: Option<String>,
+    /// The WIT world the component targets, from a wasm config.
+    pub target: Option<String>,
+    /// The interfaces the component imports, from a wasm config.
+    pub imports: Vec<String>,
+    /// The interfaces the component exports, from a wasm config.
+    pub exports: Vec<String>,
+    /// Artifacts that refer to this one, such as signatures and SBOMs.
+    pub referrers: Vec<RemoteReferrer>,
+    /// The release tags available in the repository, without signature and
+    /// attestation tags.
+    pub tags: Vec<String>,
+    /// Whether the registry has a signature for the manifest, either as a
+    /// referrer or as a cosign `sha256-<hex>.sig` tag.
+    pub signed: bool,
+    /// Whether the registry has an attestation for the manifest, either as a
+    /// referrer or as a cosign `sha256-<hex>.att` tag.
+    pub attested: bool,
+}
+
+/// A layer listed in a [`RemoteInspection`].
+///
+/// # Example
+///
+/// ```
+/// use component_package_manager::manager::RemoteLayer;
+///
+/// let layer = RemoteLayer {
+///     digest: "sha256:def456".into(),
+///     media_type: "application/wasm".into(),
+///     size: 1024,
+/// };
+/// assert_eq!(layer.media_type, "application/wasm");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct RemoteLayer {
+    /// The layer digest.
+    pub digest: String,
+    /// The layer's media type.
+    pub media_type: String,
+    /// The layer size, in bytes.
+    pub size: u64,
+}
+
+/// A referrer listed in a [`RemoteInspection`].
+///
+/// # Example
+///
+/// ```
+/// use component_package_manager::manager::RemoteReferrer;
+///
+/// let referrer = RemoteReferrer {
+///     digest: "sha256:0123".into(),
+///     media_type: "application/vnd.oci.image.manifest.v1+json".into(),
+///     size: 512,
+///     signature: false,
+///     attestation: false,
+/// };
+/// assert!(!referrer.signature);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct RemoteReferrer {
+    /// The referrer's manifest digest.
+    pub digest: String,
+    /// The referrer's media type, as listed by the registry.
+    pub media_type: String,
+    /// The referrer manifest's size, in bytes.
+    pub size: u64,
+    /// Whether the media type marks the referrer as a signature.
+    pub signature: bool,
+    /// Whether the media type marks the referrer as an attestation.
+    pub attestation: bool,
+}
+
+/// A repository's tags, grouped by kind, with versions newest first.
+///
+/// Produced by [`group_tags`](super::group_tags).
+///
+/// # Example
+///
+/// ```
+/// use component_package_manager::manager::group_tags;
+///
+/// let groups = group_tags(&["0.2.0".into(), "0.10.0".into(), "latest".into()]);
+/// assert_eq!(groups.releases, vec!["0.10.0", "0.2.0"]);
+/// assert_eq!(groups.other, vec!["latest"]);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize)]
+pub struct TagGroups {
+    /// Stable semver tags, newest first.
+    pub releases: Vec<String>,
+    /// Pre-release semver tags, newest first.
+    pub prereleases: Vec<String>,
+    /// Tags that aren't semver versions, such as `latest`, in name order.
+    pub other: Vec<String>,
+    /// Cosign signature tags (`sha256-<hex>.sig`), in name order.
+    pub signatures: Vec<String>,
+    /// Cosign attestation tags (`sha256-<hex>.att`), in name order.
+    pub attestations: Vec<String>,
+}
+
+/// The outcome of signing an artifact with [`Manager::sign`](super::Manager::sign).
+///
+/// # Example
+///
+/// ```
+/// use component_package_manager::manager::SignResult;
+///
+/// let result = SignResult {
+///     reference: "ghcr.io/example/hello:1.0.0".into(),
+///     digest: "sha256:0123".into(),
+///     signature: "ghcr.io/example/hello:sha256-0123.sig".into(),
+///     identity: None,
+///     log_index: Some(42),
+/// };
+/// assert_eq!(result.log_index, Some(42));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct SignResult {
+    /// The reference that was signed.
+    pub reference: String,
+    /// The manifest digest the signature covers.
+    pub digest: String,
+    /// The reference the signature was pushed to.
+    pub signature: String,
+    /// The identity a keyless signature's certificate names.
+    pub identity: Option<String>,
+    /// The signature's index in the transparency log, if it was recorded.
+    pub log_index: Option<i64>,
+}
+
+/// A signature found by [`Manager::verify`](super::Manager::verify).
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct VerifiedSignature {
+    /// Whether it is a keyless signature. Its certificate isn't checked, so
+    /// it can't be trusted.
+    pub keyless: bool,
+    /// Whether a trusted key signed it.
+    #[serde(flatten)]
+    pub verification: Verification,
+}
+
+/// An attestation found by [`Manager::verify`](super::Manager::verify).
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct VerifiedAttestation {
+    /// The in-toto predicate type, such as `https://slsa.dev/provenance/v1`.
+    pub predicate_type: Option<String>,
+    /// Whether a trusted key signed it.
+    #[serde(flatten)]
+    pub verification: Verification,
+}
+
+/// The outcome of checking an artifact's signatures and attestations with
+/// [`Manager::verify`](super::Manager::verify).
+///
+/// # Example
+///
+/// ```
+/// use component_package_manager::manager::VerifyReport;
+///
+/// let report = VerifyReport {
+///     reference: "ghcr.io/example/hello:1.0.0".into(),
+///     digest: "sha256:0123".into(),
+///     trusted_keys: vec![],
+///     signatures: vec![],
+///     attestations: vec![],
+///     failures: vec!["no signature found".into()],
+/// };
+/// assert!(!report.passed());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct VerifyReport {
+    /// The reference that was verified.
+    pub reference: String,
+    /// The manifest digest that was verified.
+    pub digest: String,
+    /// The trusted keys that were checked against.
+    pub trusted_keys: Vec<String>,
+    /// The artifact's cosign signatures.
+    pub signatures: Vec<VerifiedSignature>,
+    /// The artifact's cosign attestations.
+    pub attestations: Vec<VerifiedAttestation>,
+    /// Why verification failed; empty if it passed.
+    pub failures: Vec<String>,
+}
+
+impl VerifyReport {
+    /// Whether verification passed.
+    #[must_use]
+    pub fn passed(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
@@ -0,0 +1,223 @@
+//! Choosing how to authenticate with a registry.
+//!
+//! Requests are first made anonymously, then with a bearer token from the
+//! environment, then with Docker/Podman credentials, then with credentials
+//! from the OS keyring (with the `keyring` feature),
+//! then with the configured credential helper, moving on whenever the
+//! registry rejects the credentials. The strategy that works is remembered
+//! per registry for the rest of the session, so later requests go straight
+//! to it and credential helpers only run when they're actually needed.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use docker_credential::DockerCredential;
+use oci_client::errors::{OciDistributionError, OciErrorCode};
+use oci_client::secrets::RegistryAuth;
+
+use super::OciLayerError;
+use crate::config::Config;
+
+/// A way of authenticating with a registry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum AuthStrategy {
+    /// No credentials.
+    Anonymous,
+    /// A bearer token from a `WASM_REGISTRY_TOKEN` environment variable.
+    EnvToken,
+    /// Credentials from the Docker/Podman credential store.
+    DockerConfig,
+    /// Credentials stored in the OS keyring.
+    Keyring,
+    /// Credentials from the credential helper configured for the registry.
+    CredentialHelper,
+}
+
+impl AuthStrategy {
+    /// Every strategy, in the order they are tried.
+    pub(crate) const CHAIN: [Self; 5] = [
+        Self::Anonymous,
+        Self::EnvToken,
+        Self::DockerConfig,
+        Self::Keyring,
+        Self::CredentialHelper,
+    ];
+
+    /// Look up this strategy's credentials for `registry`.
+    ///
+    /// Returns `None` when the strategy has nothing to offer, e.g. no helper
+    /// is configured or the credential store has no entry.
+    pub(crate) fn resolve(
+        self,
+        registry: &str,
+        config: &Config,
+    ) -> anyhow::Result<Option<RegistryAuth>> {
+        match self {
+            Self::Anonymous => Ok(Some(RegistryAuth::Anonymous)),
+            Self::EnvToken => Ok(Config::registry_token(registry).map(RegistryAuth::Bearer)),
+            Self::DockerConfig => docker_credentials(registry),
+            Self::Keyring => Ok(keyring_credentials(registry)),
+            Self::CredentialHelper => Ok(config
+                .get_credentials(registry)?
+                .map(|(username, password)| RegistryAuth::Basic(username, password))),
+        }
+    }
+}
+
+/// The strategy that last worked for each registry during this session.
+#[derive(Debug, Default)]
+pub(crate) struct AuthCache {
+    strategies: Mutex<HashMap<String, AuthStrategy>>,
+}
+
+impl AuthCache {
+    /// The strategies to try for `registry`: the one that worked last time
+    /// first, then the rest of the chain.
+    pub(crate) fn chain(&self, registry: &str) -> Vec<AuthStrategy> {
+        let cached = self
+            .strategies
+            .lock()
+            .ok()
+            .and_then(|strategies| strategies.get(registry).copied());
+        let mut chain = Vec::with_capacity(AuthStrategy::CHAIN.len());
+        chain.extend(cached);
+        chain.extend(
+            AuthStrategy::CHAIN
+                .into_iter()
+                .filter(|strategy| Some(*strategy) != cached),
+        );
+        chain
+    }
+
+    /// Remember that `strategy` worked for `registry`.
+    pub(crate) fn remember(&self, registry: &str, strategy: AuthStrategy) {
+        // If the lock is poisoned we just lose the shortcut.
+        if let Ok(mut strategies) = self.strategies.lock() {
+            strategies.insert(registry.to_owned(), strategy);
+        }
+    }
+}
+
+/// Whether `err` means the registry rejected our credentials, so the next
+/// strategy in the chain is worth trying.
+pub(crate) fn is_auth_failure(err: &anyhow::Error) -> bool {
+    err.chain()
+        .filter_map(|cause| cause.downcast_ref::<OciDistributionError>())
+        .any(|err| match err {
+            OciDistributionError::AuthenticationFailure(_)
+            | OciDistributionError::UnauthorizedError { .. } => true,
+            OciDistributionError::ServerError { code, .. } => matches!(code, 401 | 403),
+            OciDistributionError::RegistryError { envelope, .. } => envelope
+                .errors
+                .iter()
+                .any(|e| matches!(e.code, OciErrorCode::Unauthorized | OciErrorCode::Denied)),
+            _ => false,
+        })
+}
+
+/// Resolve the best credentials for a registry without trying them:
+/// the credential helper, then the OS keyring, then the Docker credential
+/// store, then an environment token, then anonymous.
+///
+/// Used for requests made outside `oci-client`, such as catalog listing,
+/// which are rarely allowed anonymously.
+pub(crate) fn resolve_registry_auth(
+    registry: &str,
+    config: &Config,
+) -> anyhow::Result<RegistryAuth> {
+    // If a helper is configured but fails, propagate the error rather than
+    // silently falling back to Docker credentials.
+    for strategy in AuthStrategy::CHAIN.into_iter().rev() {
+        if let Some(auth) = strategy.resolve(registry, config)? {
+            tracing::debug!(registry, ?strategy, "resolved registry credentials");
+            return Ok(auth);
+        }
+    }
+    Ok(RegistryAuth::Anonymous)
+}
+
+/// Look up `registry` in the Docker credential store.
+fn docker_credentials(registry: &str) -> anyhow::Result<Option<RegistryAuth>> {
+    // NOTE: copied approach from https://github.com/bytecodealliance/wasm-pkg-tools/blob/48c28825a7dfb585b3fe1d42be65fe73a17d84fe/crates/wkg/src/oci.rs#L59-L66
+    let server_url = match registry {
+        "index.docker.io" => "https://index.docker.io/v1/",
+        other => other,
+    };
+
+    match docker_credential::get_credential(server_url) {
+        Ok(DockerCredential::UsernamePassword(username, password)) => {
+            Ok(Some(RegistryAuth::Basic(username, password)))
+        }
+        Ok(DockerCredential::IdentityToken(_)) => {
+            Err(OciLayerError::IdentityTokenNotSupported.into())
+        }
+        Err(_) => Ok(None),
+    }
+}
+
+/// Look up `registry` in the OS keyring.
+///
+/// An unavailable keyring, such as on a headless machine without a secret
+/// service, is treated like a missing entry.
+#[cfg(feature = "keyring")]
+fn keyring_credentials(registry: &str) -> Option<RegistryAuth> {
+    match Config::keyring_credentials(registry) {
+        Ok(credentials) => {
+            credentials.map(|(username, password)| RegistryAuth::Basic(username, password))
+        }
+        Err(err) => {
+            tracing::debug!(registry, error = %err, "failed to read the keyring");
+            None
+        }
+    }
+}
+
+/// Without the `keyring` feature there is never a keyring entry.
+#[cfg(not(feature = "keyring"))]
+fn keyring_credentials(_registry: &str) -> Option<RegistryAuth> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chain_starts_with_the_remembered_strategy() {
+        let cache = AuthCache::default();
+        assert_eq!(cache.chain("ghcr.io"), AuthStrategy::CHAIN);
+
+        cache.remember("ghcr.io", AuthStrategy::CredentialHelper);
+        assert_eq!(
+            cache.chain("ghcr.io"),
+            [
+                AuthStrategy::CredentialHelper,
+                AuthStrategy::Anonymous,
+                AuthStrategy::EnvToken,
+                AuthStrategy::DockerConfig,
+                AuthStrategy::Keyring,
+            ]
+        );
+        assert_eq!(cache.chain("docker.io"), AuthStrategy::CHAIN);
+    }
+
+    #[test]
+    fn only_rejected_credentials_are_auth_failures() {
+        let unauthorized = anyhow::Error::from(OciDistributionError::UnauthorizedError {
+            url: "https://ghcr.io/v2/".to_string(),
+        });
+        assert!(is_auth_failure(&unauthorized));
+
+        let forbidden = anyhow::Error::from(OciDistributionError::ServerError {
+            code: 403,
+            url: "https://ghcr.io/v2/".to_string(),
+            message: "denied".to_string(),
+        });
+        assert!(is_auth_failure(&forbidden));
+
+        let missing = anyhow::Error::from(OciDistributionError::ImageManifestNotFoundError(
+            "ghcr.io/example/missing:1.0".to_string(),
+        ));
+        assert!(!is_auth_failure(&missing));
+    }
+}
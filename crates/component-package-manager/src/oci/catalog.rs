@@ -0,0 +1,132 @@
+//! Helpers for listing the repositories a registry hosts.
+//!
+//! Most registries implement the Docker Registry `GET /v2/_catalog`
+//! endpoint. GitHub Container Registry does not, so for `ghcr.io` we list
+//! an owner's container packages through the GitHub REST API instead.
+
+/// The number of entries requested per catalog page.
+pub(crate) const PAGE_SIZE: u32 = 100;
+
+/// Registries whose repositories are listed through the GitHub REST API.
+pub(crate) fn is_github_registry(registry: &str) -> bool {
+    registry.eq_ignore_ascii_case("ghcr.io")
+}
+
+/// The repository entry returned by the GitHub packages API.
+#[derive(Debug, serde::Deserialize)]
+pub(crate) struct GithubPackage {
+    pub(crate) name: String,
+}
+
+/// The body of a `GET /v2/_catalog` response.
+#[derive(Debug, Default, serde::Deserialize)]
+pub(crate) struct CatalogPage {
+    #[serde(default)]
+    pub(crate) repositories: Option<Vec<String>>,
+}
+
+/// Whether `repository` lives in `namespace` (or any namespace, if `None`).
+pub(crate) fn in_namespace(repository: &str, namespace: Option<&str>) -> bool {
+    let Some(namespace) = namespace.map(|ns| ns.trim_matches('/')) else {
+        return true;
+    };
+    repository
+        .strip_prefix(namespace)
+        .is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// Extract the `rel="next"` target from an RFC 8288 `Link` header.
+pub(crate) fn next_link(header: &str) -> Option<&str> {
+    header.split(',').find_map(|link| {
+        let (target, params) = link.split_once(';')?;
+        let is_next = params
+            .split(';')
+            .filter_map(|param| param.split_once('='))
+            .any(|(key, value)| key.trim() == "rel" && value.trim().trim_matches('"') == "next");
+        is_next.then(|| target.trim().trim_start_matches('<').trim_end_matches('>'))
+    })
+}
+
+/// A parsed `WWW-Authenticate: Bearer ...` challenge.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct BearerChallenge {
+    pub(crate) realm: String,
+    pub(crate) service: Option<String>,
+    pub(crate) scope: Option<String>,
+}
+
+impl BearerChallenge {
+    /// Parse a `WWW-Authenticate` header, returning `None` for anything but
+    /// a Bearer challenge with a realm.
+    pub(crate) fn parse(header: &str) -> Option<Self> {
+        let (scheme, params) = header.trim().split_once(' ')?;
+        if !scheme.eq_ignore_ascii_case("bearer") {
+            return None;
+        }
+        let mut challenge = Self::default();
+        let mut rest = params.trim();
+        while let Some((key, value)) = rest.split_once('=') {
+            let key = key.trim().trim_start_matches(',').trim();
+            let value = value.trim_start();
+            let (value, remainder) = if let Some(quoted) = value.strip_prefix('"') {
+                let end = quoted.find('"')?;
+                (quoted.get(..end)?, quoted.get(end + 1..)?)
+            } else {
+                let end = value.find(',').unwrap_or(value.len());
+                (value.get(..end)?, value.get(end..)?)
+            };
+            match key {
+                "realm" => value.clone_into(&mut challenge.realm),
+                "service" => challenge.service = Some(value.to_owned()),
+                "scope" => challenge.scope = Some(value.to_owned()),
+                _ => {}
+            }
+            rest = remainder;
+        }
+        (!challenge.realm.is_empty()).then_some(challenge)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn namespace_filter_matches_whole_segments() {
+        assert!(in_namespace("bytecodealliance/wasi", None));
+        assert!(in_namespace(
+            "bytecodealliance/wasi",
+            Some("bytecodealliance")
+        ));
+        assert!(in_namespace(
+            "bytecodealliance/wasi",
+            Some("bytecodealliance/")
+        ));
+        assert!(!in_namespace(
+            "bytecodealliance-x/wasi",
+            Some("bytecodealliance")
+        ));
+        assert!(!in_namespace("bytecodealliance", Some("bytecodealliance")));
+    }
+
+    #[test]
+    fn next_link_is_extracted() {
+        let header = r#"</v2/_catalog?last=b&n=100>; rel="next""#;
+        assert_eq!(next_link(header), Some("/v2/_catalog?last=b&n=100"));
+        assert_eq!(next_link(r#"</prev>; rel="prev""#), None);
+    }
+
+    #[test]
+    fn bearer_challenge_is_parsed() {
+        let header = r#"Bearer realm="https://auth.example.com/token",service="registry.example.com",scope="registry:catalog:*""#;
+        assert_eq!(
+            BearerChallenge::parse(header),
+            Some(BearerChallenge {
+                realm: "https://auth.example.com/token".into(),
+                service: Some("registry.example.com".into()),
+                scope: Some("registry:catalog:*".into()),
+            })
+        );
+        assert_eq!(BearerChallenge::parse(r#"Basic realm="x""#), None);
+    }
+}
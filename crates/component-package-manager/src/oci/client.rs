@@ -1,16 +1,30 @@
 use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::Arc;
 
-use docker_credential::DockerCredential;
 use oci_client::Reference;
-use oci_client::client::{ClientConfig, ClientProtocol, ImageData, PushResponse, SizedStream};
-use oci_client::manifest::{OciDescriptor, OciImageIndex, OciImageManifest};
+use oci_client::client::{
+    ClientConfig, ClientProtocol, Config as ImageConfig, ImageData, ImageLayer, PushResponse,
+    SizedStream,
+};
+use oci_client::manifest::{OciDescriptor, OciImageIndex, OciImageManifest, OciManifest};
 use oci_client::secrets::RegistryAuth;
-use oci_wasm::{WasmClient, WasmConfig};
+use oci_wasm::{ToConfig, WasmClient, WasmConfig};
 
+use super::auth::{self, AuthCache, AuthStrategy};
+use super::rate_limit::{self, RateLimit};
+use super::{OciLayerError, Transport, catalog};
 use crate::config::Config;
 
 pub(crate) struct Client {
-    inner: WasmClient,
+    // `oci-client` keeps the first credentials it sees for a registry, so
+    // each auth strategy gets a transport of its own.
+    anonymous: Arc<dyn Transport>,
+    env_token: Arc<dyn Transport>,
+    docker_config: Arc<dyn Transport>,
+    keyring: Arc<dyn Transport>,
+    credential_helper: Arc<dyn Transport>,
+    auth_cache: AuthCache,
     config: Config,
 }
 
@@ -22,20 +36,137 @@ impl std::fmt::Debug for Client {
 
 impl Client {
     pub(crate) fn new(config: Config) -> Self {
-        let client_config = ClientConfig {
-            protocol: ClientProtocol::Https,
-            ..Default::default()
+        let network = config.network.clone().unwrap_or_default();
+        let client = || {
+            let client_config = ClientConfig {
+                protocol: ClientProtocol::Https,
+                connect_timeout: Some(network.connect_timeout()),
+                read_timeout: Some(network.read_timeout()),
+                // Resolve image indexes to their wasm entry rather than to the
+                // host's platform, which a wasm artifact never matches.
+                platform_resolver: Some(Box::new(|entries| {
+                    super::select_wasm_manifest(entries).map(|entry| entry.digest.clone())
+                })),
+                ..Default::default()
+            };
+            Arc::new(WasmClient::new(oci_client::Client::new(client_config)))
         };
-        let client = WasmClient::new(oci_client::Client::new(client_config));
         Self {
-            inner: client,
+            anonymous: client(),
+            env_token: client(),
+            docker_config: client(),
+            keyring: client(),
+            credential_helper: client(),
+            auth_cache: AuthCache::default(),
             config,
         }
     }
 
+    /// Create a client that sends every request through `transport`,
+    /// whichever credentials it uses.
+    #[cfg(any(test, feature = "test-util"))]
+    pub(crate) fn with_transport(config: Config, transport: Arc<dyn Transport>) -> Self {
+        Self {
+            anonymous: Arc::clone(&transport),
+            env_token: Arc::clone(&transport),
+            docker_config: Arc::clone(&transport),
+            keyring: Arc::clone(&transport),
+            credential_helper: transport,
+            auth_cache: AuthCache::default(),
+            config,
+        }
+    }
+
+    /// The transport that authenticates with `strategy`.
+    fn client(&self, strategy: AuthStrategy) -> &dyn Transport {
+        match strategy {
+            AuthStrategy::Anonymous => &*self.anonymous,
+            AuthStrategy::EnvToken => &*self.env_token,
+            AuthStrategy::DockerConfig => &*self.docker_config,
+            AuthStrategy::Keyring => &*self.keyring,
+            AuthStrategy::CredentialHelper => &*self.credential_helper,
+        }
+    }
+
+    /// Run `op` against `registry` with each auth strategy in turn until the
+    /// registry accepts one, remembering it for later requests.
+    ///
+    /// Strategies without credentials for the registry are skipped. When
+    /// every strategy fails, the last error is returned. The whole attempt
+    /// is bounded by the configured total timeout.
+    async fn with_auth<T>(
+        &self,
+        registry: &str,
+        op: impl AsyncFnMut(&dyn Transport, &RegistryAuth) -> anyhow::Result<T>,
+    ) -> anyhow::Result<T> {
+        self.with_timeout(registry, self.try_strategies(registry, op))
+            .await
+    }
+
+    /// Run `fut`, failing with [`OciLayerError::TimedOut`] if it outlives the
+    /// configured total timeout.
+    async fn with_timeout<T>(
+        &self,
+        registry: &str,
+        fut: impl Future<Output = anyhow::Result<T>>,
+    ) -> anyhow::Result<T> {
+        let network = self.config.network.clone().unwrap_or_default();
+        let Some(total) = network.total_timeout() else {
+            return fut.await;
+        };
+        tokio::time::timeout(total, fut).await.unwrap_or_else(|_| {
+            Err(OciLayerError::TimedOut {
+                registry: registry.to_owned(),
+                after_secs: total.as_secs(),
+            }
+            .into())
+        })
+    }
+
+    /// [`Self::with_auth`] without the timeout.
+    async fn try_strategies<T>(
+        &self,
+        registry: &str,
+        mut op: impl AsyncFnMut(&dyn Transport, &RegistryAuth) -> anyhow::Result<T>,
+    ) -> anyhow::Result<T> {
+        let mut last_err = None;
+        for strategy in self.auth_cache.chain(registry) {
+            let auth = match strategy.resolve(registry, &self.config) {
+                Ok(Some(auth)) => auth,
+                Ok(None) => continue,
+                Err(err) => {
+                    tracing::debug!(registry, ?strategy, error = %err, "failed to resolve credentials");
+                    last_err = Some(err);
+                    continue;
+                }
+            };
+            // Boxed to keep callers' futures within the compiler's layout
+            // depth limit.
+            match Box::pin(op(self.client(strategy), &auth)).await {
+                Ok(value) => {
+                    tracing::debug!(registry, ?strategy, "authenticated");
+                    self.auth_cache.remember(registry, strategy);
+                    return Ok(value);
+                }
+                Err(err) if auth::is_auth_failure(&err) => {
+                    tracing::debug!(registry, ?strategy, error = %err, "credentials rejected");
+                    last_err = Some(err);
+                }
+                Err(err) => return Err(err),
+            }
+        }
+        Err(last_err.unwrap_or_else(|| anyhow::anyhow!("no credentials available for {registry}")))
+    }
+
+    #[tracing::instrument(level = "debug", skip_all, fields(reference = %reference))]
     pub(crate) async fn pull(&self, reference: &Reference) -> anyhow::Result<ImageData> {
-        let auth = resolve_auth(reference, &self.config)?;
-        let image = self.inner.pull(reference, &auth).await?;
+        let registry = reference.resolve_registry();
+        let image = self
+            .with_auth(registry, async |client, auth| {
+                with_backoff(registry, || client.pull(reference, auth)).await
+            })
+            .await?;
+        tracing::debug!(layers = image.layers.len(), "pulled image");
         Ok(image)
     }
 
@@ -48,63 +179,153 @@ impl Client {
     /// manifest. The supplied `annotations` are attached to the OCI
     /// manifest using the `org.opencontainers.image.*` keys (callers are
     /// responsible for picking the right keys).
+    #[tracing::instrument(level = "debug", skip_all, fields(reference = %reference))]
     pub(crate) async fn push(
         &self,
         reference: &Reference,
         bytes: Vec<u8>,
         annotations: BTreeMap<String, String>,
     ) -> anyhow::Result<PushResponse> {
-        let auth = resolve_auth(reference, &self.config)?;
         let (config, layer) = WasmConfig::from_raw_component(bytes, None)?;
+        let config = config.to_config()?;
         let annotations_opt = if annotations.is_empty() {
             None
         } else {
             Some(annotations)
         };
-        self.inner
-            .push(reference, &auth, layer, config, annotations_opt)
-            .await
+        self.with_auth(reference.resolve_registry(), async |client, auth| {
+            client
+                .push(
+                    reference,
+                    auth,
+                    layer.clone(),
+                    config.clone(),
+                    annotations_opt.clone(),
+                )
+                .await
+        })
+        .await
+    }
+
+    /// Push a cosign signature manifest to `reference`, usually the
+    /// signed manifest's `sha256-<hex>.sig` tag.
+    ///
+    /// The manifest has a single simple signing layer holding `payload`,
+    /// with the signature and its certificates in the layer's
+    /// `annotations`, and an image config listing that layer, as cosign
+    /// writes it.
+    #[tracing::instrument(level = "debug", skip_all, fields(reference = %reference))]
+    pub(crate) async fn push_signature(
+        &self,
+        reference: &Reference,
+        payload: Vec<u8>,
+        annotations: BTreeMap<String, String>,
+    ) -> anyhow::Result<PushResponse> {
+        let layer = ImageLayer::new(
+            payload,
+            crate::sign::SIMPLE_SIGNING_MEDIA_TYPE.to_string(),
+            Some(annotations),
+        );
+        let config = serde_json::json!({
+            "architecture": "",
+            "config": {},
+            "created": "0001-01-01T00:00:00Z",
+            "history": [{ "created": "0001-01-01T00:00:00Z" }],
+            "os": "",
+            "rootfs": { "type": "layers", "diff_ids": [layer.sha256_digest()] },
+        });
+        let config = ImageConfig::new(
+            serde_json::to_vec(&config)?,
+            oci_client::manifest::IMAGE_CONFIG_MEDIA_TYPE.to_string(),
+            None,
+        );
+        self.with_auth(reference.resolve_registry(), async |client, auth| {
+            client
+                .push(reference, auth, layer.clone(), config.clone(), None)
+                .await
+        })
+        .await
     }
 
     /// Fetches the manifest and config digest for a given reference.
     ///
     /// Returns the OCI image manifest and the content digest.
+    #[tracing::instrument(level = "debug", skip_all, fields(reference = %reference))]
     pub(crate) async fn pull_manifest(
         &self,
         reference: &Reference,
     ) -> anyhow::Result<(OciImageManifest, String)> {
-        let auth = resolve_auth(reference, &self.config)?;
-        let (manifest, _config, digest) = self
-            .inner
-            .pull_manifest_and_config(reference, &auth)
+        let registry = reference.resolve_registry();
+        let (manifest, digest) = self
+            .with_auth(registry, async |client, auth| {
+                with_backoff(registry, || {
+                    client.pull_manifest_and_config(reference, auth)
+                })
+                .await
+            })
             .await?;
+        tracing::debug!(%digest, layers = manifest.layers.len(), "fetched manifest");
         Ok((manifest, digest))
     }
 
+    /// Fetches the image index a reference points at.
+    ///
+    /// Returns `None` when the reference resolves to a single image manifest.
+    #[tracing::instrument(level = "debug", skip_all, fields(reference = %reference))]
+    pub(crate) async fn pull_index(
+        &self,
+        reference: &Reference,
+    ) -> anyhow::Result<Option<OciImageIndex>> {
+        let (manifest, _digest) = self.pull_any_manifest(reference).await?;
+        match manifest {
+            OciManifest::ImageIndex(index) => {
+                tracing::debug!(entries = index.manifests.len(), "fetched image index");
+                Ok(Some(index))
+            }
+            OciManifest::Image(_) => Ok(None),
+        }
+    }
+
+    /// Fetches whatever manifest a reference points at, without requiring
+    /// it to describe a wasm artifact.
+    ///
+    /// Returns the manifest or image index and its digest.
+    #[tracing::instrument(level = "debug", skip_all, fields(reference = %reference))]
+    pub(crate) async fn pull_any_manifest(
+        &self,
+        reference: &Reference,
+    ) -> anyhow::Result<(OciManifest, String)> {
+        let registry = reference.resolve_registry();
+        self.with_auth(registry, async |client, auth| {
+            with_backoff(registry, || client.pull_manifest(reference, auth)).await
+        })
+        .await
+    }
+
     /// Streams a single layer from the registry.
     ///
     /// Returns a `SizedStream` that yields chunks of bytes and optionally
     /// provides the content length.
+    #[tracing::instrument(level = "debug", skip_all, fields(reference = %reference, layer = %layer.digest))]
     pub(crate) async fn pull_layer_stream(
         &self,
         reference: &Reference,
         layer: &OciDescriptor,
     ) -> anyhow::Result<SizedStream> {
-        let auth = resolve_auth(reference, &self.config)?;
-        // Ensure auth is stored before calling pull_blob_stream
-        self.inner
-            .store_auth_if_needed(reference.resolve_registry(), &auth)
-            .await;
-        let stream = self.inner.pull_blob_stream(reference, layer).await?;
-        Ok(stream)
+        let registry = reference.resolve_registry();
+        self.with_auth(registry, async |client, auth| {
+            with_backoff(registry, || client.pull_blob_stream(reference, auth, layer)).await
+        })
+        .await
     }
 
     /// Fetches all tags for a given reference from the registry.
     ///
     /// This method handles pagination automatically, fetching all available tags
     /// by making multiple requests if necessary.
+    #[tracing::instrument(level = "debug", skip_all, fields(reference = %reference))]
     pub(crate) async fn list_tags(&self, reference: &Reference) -> anyhow::Result<Vec<String>> {
-        let auth = resolve_auth(reference, &self.config)?;
+        let registry = reference.resolve_registry();
         let mut all_tags = Vec::new();
         let mut last: Option<String> = None;
 
@@ -112,17 +333,30 @@ impl Client {
             // Some registries return null for tags instead of an empty array,
             // which causes deserialization to fail. We handle this gracefully.
             let response = match self
-                .inner
-                .list_tags(reference, &auth, None, last.as_deref())
+                .with_auth(registry, async |client, auth| {
+                    with_backoff(registry, || {
+                        client.list_tags(reference, auth, None, last.as_deref())
+                    })
+                    .await
+                })
                 .await
             {
                 Ok(resp) => resp,
-                Err(_) if all_tags.is_empty() => {
+                // Throttling isn't a missing tag list; report it rather
+                // than returning an empty or partial list.
+                Err(e) if rate_limit::is_rate_limited(&e) => return Err(e),
+                Err(e) if all_tags.is_empty() => {
                     // First request failed, likely due to null tags - return empty
+                    tracing::debug!(error = %e, "failed to list tags, treating as empty");
                     return Ok(Vec::new());
                 }
-                Err(_) => {
+                Err(e) => {
                     // Subsequent request failed, return what we have
+                    tracing::debug!(
+                        error = %e,
+                        fetched = all_tags.len(),
+                        "failed to fetch next tag page, returning partial list"
+                    );
                     break;
                 }
             };
@@ -143,8 +377,12 @@ impl Client {
 
             // Make another request to check if there are more tags
             let Ok(next_response) = self
-                .inner
-                .list_tags(reference, &auth, Some(1), last.as_deref())
+                .with_auth(registry, async |client, auth| {
+                    with_backoff(registry, || {
+                        client.list_tags(reference, auth, Some(1), last.as_deref())
+                    })
+                    .await
+                })
                 .await
             else {
                 break;
@@ -155,6 +393,7 @@ impl Client {
             }
         }
 
+        tracing::debug!(count = all_tags.len(), "listed tags");
         Ok(all_tags)
     }
 
@@ -166,15 +405,13 @@ impl Client {
     ///
     /// Returns the OCI image index listing all referrer manifests. If the
     /// registry does not support the Referrers API, returns `Ok(None)`.
+    #[tracing::instrument(level = "debug", skip_all, fields(reference = %reference, digest = %digest))]
     pub(crate) async fn pull_referrers(
         &self,
         reference: &Reference,
         digest: &str,
     ) -> anyhow::Result<Option<OciImageIndex>> {
-        let auth = resolve_auth(reference, &self.config)?;
-        self.inner
-            .store_auth_if_needed(reference.resolve_registry(), &auth)
-            .await;
+        let registry = reference.resolve_registry();
 
         // The Referrers API requires a digest-based reference — build one
         // from the original reference with the digest instead of a tag.
@@ -184,7 +421,12 @@ impl Client {
             digest.to_owned(),
         );
 
-        match self.inner.pull_referrers(&digest_ref, None).await {
+        let referrers = self
+            .with_auth(registry, async |client, auth| {
+                client.pull_referrers(&digest_ref, auth).await
+            })
+            .await;
+        match referrers {
             Ok(index) => Ok(Some(index)),
             // Registry may not support the Referrers API — log and skip.
             Err(e) => {
@@ -198,56 +440,310 @@ impl Client {
             }
         }
     }
+
+    /// Lists the repositories a registry hosts, optionally limited to a
+    /// single namespace (e.g. an organization).
+    ///
+    /// Uses the `/v2/_catalog` endpoint, following pagination links. For
+    /// `ghcr.io`, which does not implement the catalog, the owner's container
+    /// packages are listed through the GitHub REST API instead; there the
+    /// namespace is required and credentials must be a GitHub token.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub(crate) async fn list_repositories(
+        &self,
+        registry: &str,
+        namespace: Option<&str>,
+    ) -> anyhow::Result<Vec<String>> {
+        self.with_timeout(registry, self.list_repositories_inner(registry, namespace))
+            .await
+    }
+
+    async fn list_repositories_inner(
+        &self,
+        registry: &str,
+        namespace: Option<&str>,
+    ) -> anyhow::Result<Vec<String>> {
+        let auth = auth::resolve_registry_auth(registry, &self.config)?;
+        let network = self.config.network.clone().unwrap_or_default();
+        let http = reqwest::Client::builder()
+            .user_agent(concat!("component/", env!("CARGO_PKG_VERSION")))
+            .connect_timeout(network.connect_timeout())
+            .read_timeout(network.read_timeout())
+            .build()?;
+        let mut repositories = if catalog::is_github_registry(registry) {
+            let owner = namespace
+                .map(|ns| ns.trim_matches('/'))
+                .filter(|ns| !ns.is_empty())
+                .ok_or_else(|| {
+                    anyhow::anyhow!("listing repositories on {registry} requires a namespace")
+                })?;
+            list_github_packages(&http, owner, &auth).await?
+        } else {
+            list_catalog(&http, registry, namespace, &auth).await?
+        };
+        repositories.sort();
+        repositories.dedup();
+        tracing::debug!(count = repositories.len(), "listed repositories");
+        Ok(repositories)
+    }
 }
 
-/// Resolve authentication for a registry reference.
-///
-/// The authentication is resolved in the following order:
-/// 1. Check if a credential helper is configured in the config file for this registry
-/// 2. Fall back to Docker credential store
-/// 3. Use anonymous access if no credentials are found
-fn resolve_auth(reference: &Reference, config: &Config) -> anyhow::Result<RegistryAuth> {
-    let registry = reference.resolve_registry();
-
-    // First, check if a credential helper is configured in the config file.
-    // If a helper is configured but fails, propagate the error rather than
-    // silently falling back to Docker credentials.
-    match config.get_credentials(registry)? {
-        Some((username, password)) => {
-            tracing::debug!(registry, "using credential helper for authentication");
-            return Ok(RegistryAuth::Basic(username, password));
+/// Page through a registry's `/v2/_catalog`, keeping repositories in
+/// `namespace`.
+async fn list_catalog(
+    http: &reqwest::Client,
+    registry: &str,
+    namespace: Option<&str>,
+    auth: &RegistryAuth,
+) -> anyhow::Result<Vec<String>> {
+    let base = format!("https://{registry}");
+    let mut url = format!("{base}/v2/_catalog?n={}", catalog::PAGE_SIZE);
+    let mut token: Option<String> = None;
+    let mut repositories = Vec::new();
+
+    loop {
+        let mut response =
+            send_catalog_request(http, registry, &url, auth, token.as_deref()).await?;
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED && token.is_none() {
+            let challenge = response
+                .headers()
+                .get(reqwest::header::WWW_AUTHENTICATE)
+                .and_then(|value| value.to_str().ok())
+                .and_then(catalog::BearerChallenge::parse);
+            if let Some(challenge) = challenge {
+                token = Some(fetch_bearer_token(http, registry, &challenge, auth).await?);
+                response =
+                    send_catalog_request(http, registry, &url, auth, token.as_deref()).await?;
+            }
         }
-        None => {
-            tracing::debug!(registry, "no credential helper configured");
+        let response = response.error_for_status()?;
+        let next = response
+            .headers()
+            .get(reqwest::header::LINK)
+            .and_then(|value| value.to_str().ok())
+            .and_then(catalog::next_link)
+            .map(|link| {
+                if link.starts_with('/') {
+                    format!("{base}{link}")
+                } else {
+                    link.to_owned()
+                }
+            });
+        let page: catalog::CatalogPage = response.json().await?;
+        repositories.extend(
+            page.repositories
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|repo| catalog::in_namespace(repo, namespace)),
+        );
+        match next {
+            Some(next) => url = next,
+            None => break,
         }
     }
+    Ok(repositories)
+}
+
+async fn send_catalog_request(
+    http: &reqwest::Client,
+    registry: &str,
+    url: &str,
+    auth: &RegistryAuth,
+    token: Option<&str>,
+) -> anyhow::Result<reqwest::Response> {
+    let request = http.get(url);
+    let request = match (token, auth) {
+        (Some(token), _) => request.bearer_auth(token),
+        (None, RegistryAuth::Basic(username, password)) => {
+            request.basic_auth(username, Some(password))
+        }
+        (None, RegistryAuth::Bearer(token)) => request.bearer_auth(token),
+        (None, _) => request,
+    };
+    send_with_backoff(registry, request).await
+}
+
+/// Exchange credentials for a catalog-scoped token at the challenge's realm.
+async fn fetch_bearer_token(
+    http: &reqwest::Client,
+    registry: &str,
+    challenge: &catalog::BearerChallenge,
+    auth: &RegistryAuth,
+) -> anyhow::Result<String> {
+    #[derive(serde::Deserialize)]
+    struct TokenResponse {
+        token: Option<String>,
+        access_token: Option<String>,
+    }
 
-    // Fall back to Docker credential store
-    // NOTE: copied approach from https://github.com/bytecodealliance/wasm-pkg-tools/blob/48c28825a7dfb585b3fe1d42be65fe73a17d84fe/crates/wkg/src/oci.rs#L59-L66
-    let server_url = match registry {
-        "index.docker.io" => "https://index.docker.io/v1/",
-        other => other,
+    let mut query = vec![(
+        "scope",
+        challenge.scope.as_deref().unwrap_or("registry:catalog:*"),
+    )];
+    if let Some(service) = &challenge.service {
+        query.push(("service", service));
+    }
+    let mut request = http.get(&challenge.realm).query(&query);
+    if let RegistryAuth::Basic(username, password) = auth {
+        request = request.basic_auth(username, Some(password));
+    }
+    let response: TokenResponse = send_with_backoff(registry, request)
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    response
+        .token
+        .or(response.access_token)
+        .ok_or_else(|| anyhow::anyhow!("token endpoint {} returned no token", challenge.realm))
+}
+
+/// The host of the GitHub REST API, which `ghcr.io` packages are listed from.
+const GITHUB_API: &str = "api.github.com";
+
+/// List an owner's container packages through the GitHub REST API, trying
+/// the owner as an organization first and then as a user.
+async fn list_github_packages(
+    http: &reqwest::Client,
+    owner: &str,
+    auth: &RegistryAuth,
+) -> anyhow::Result<Vec<String>> {
+    let token = match auth {
+        RegistryAuth::Basic(_, token) | RegistryAuth::Bearer(token) => Some(token.as_str()),
+        RegistryAuth::Anonymous => None,
     };
+    for kind in ["orgs", "users"] {
+        let mut repositories = Vec::new();
+        for page in 1.. {
+            let mut request = http
+                .get(format!("https://{GITHUB_API}/{kind}/{owner}/packages"))
+                .header(reqwest::header::ACCEPT, "application/vnd.github+json")
+                .query(&[
+                    ("package_type", "container".to_owned()),
+                    ("per_page", catalog::PAGE_SIZE.to_string()),
+                    ("page", page.to_string()),
+                ]);
+            if let Some(token) = token {
+                request = request.bearer_auth(token);
+            }
+            let response = send_with_backoff(GITHUB_API, request).await?;
+            if response.status() == reqwest::StatusCode::NOT_FOUND && page == 1 {
+                break;
+            }
+            let packages: Vec<catalog::GithubPackage> = response.error_for_status()?.json().await?;
+            let done = packages.len() < catalog::PAGE_SIZE as usize;
+            repositories.extend(
+                packages
+                    .into_iter()
+                    .map(|pkg| format!("{owner}/{}", pkg.name).to_lowercase()),
+            );
+            if done {
+                return Ok(repositories);
+            }
+        }
+    }
+    anyhow::bail!("no GitHub organization or user named '{owner}'")
+}
 
-    match docker_credential::get_credential(server_url) {
-        Ok(DockerCredential::UsernamePassword(username, password)) => {
-            tracing::debug!(registry, "using Docker credential store for authentication");
-            Ok(RegistryAuth::Basic(username, password))
+/// Run a registry operation, retrying with exponential backoff while the
+/// registry rate-limits it.
+///
+/// `oci-client` doesn't expose response headers, so `Retry-After` can't be
+/// honoured here; once the attempts run out the error is wrapped in
+/// [`OciLayerError::RateLimited`].
+async fn with_backoff<T, F, Fut>(registry: &str, mut op: F) -> anyhow::Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = anyhow::Result<T>>,
+{
+    let mut attempt = 0;
+    loop {
+        match op().await {
+            Err(err) if rate_limit::is_rate_limited(&err) => {
+                let Some(delay) = rate_limit::backoff_delay(attempt, None) else {
+                    return Err(err.context(OciLayerError::RateLimited {
+                        registry: registry.to_owned(),
+                        retry_after_secs: None,
+                        remaining: None,
+                    }));
+                };
+                tracing::warn!(%registry, ?delay, "rate limited by registry, backing off");
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            result => return result,
         }
-        Ok(DockerCredential::IdentityToken(_)) => {
-            Err(crate::oci::OciLayerError::IdentityTokenNotSupported.into())
+    }
+}
+
+/// Send an HTTP request, retrying while the response says we're rate
+/// limited and honouring the registry's `Retry-After`.
+async fn send_with_backoff(
+    registry: &str,
+    request: reqwest::RequestBuilder,
+) -> anyhow::Result<reqwest::Response> {
+    let mut attempt = 0;
+    loop {
+        let response = request
+            .try_clone()
+            .ok_or_else(|| anyhow::anyhow!("cannot retry a request with a streaming body"))?
+            .send()
+            .await?;
+        let limit = RateLimit::from_headers(response.headers());
+        if let Some(remaining) = limit.remaining {
+            tracing::debug!(%registry, remaining, "rate limit quota");
         }
-        Err(_) => {
-            tracing::debug!(registry, "no credentials found, using anonymous access");
-            Ok(RegistryAuth::Anonymous)
+        if !limit.is_limited(response.status()) {
+            return Ok(response);
         }
+        let Some(delay) = rate_limit::backoff_delay(attempt, limit.retry_after) else {
+            return Err(OciLayerError::RateLimited {
+                registry: registry.to_owned(),
+                retry_after_secs: limit.retry_after.map(|delay| delay.as_secs()),
+                remaining: limit.remaining,
+            }
+            .into());
+        };
+        tracing::warn!(%registry, ?delay, "rate limited by registry, backing off");
+        tokio::time::sleep(delay).await;
+        attempt += 1;
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeMap;
+    use std::sync::Arc;
+
     use oci_client::Reference;
 
+    use super::Client;
+    use crate::config::Config;
+    use crate::oci::FakeRegistry;
+
+    #[tokio::test]
+    async fn pushed_artifact_can_be_pulled() {
+        let registry = Arc::new(FakeRegistry::new());
+        let client = Client::with_transport(Config::default(), Arc::clone(&registry) as _);
+        let reference: Reference = "ghcr.io/example/hello:1.0.0".parse().unwrap();
+        let component = vec![0x00, 0x61, 0x73, 0x6d, 0x0d, 0x00, 0x01, 0x00];
+        let annotations = BTreeMap::from([(
+            "org.opencontainers.image.description".to_string(),
+            "hello".to_string(),
+        )]);
+
+        client
+            .push(&reference, component.clone(), annotations)
+            .await
+            .unwrap();
+        assert_eq!(registry.tags(&reference), ["1.0.0"]);
+
+        let (manifest, _digest) = client.pull_manifest(&reference).await.unwrap();
+        assert_eq!(crate::oci::manifest_description(&manifest), Some("hello"));
+        let image = client.pull(&reference).await.unwrap();
+        assert_eq!(image.layers.first().unwrap().data.as_ref(), component);
+    }
+
     /// Verify that a digest-pinned reference built from a tag-based reference
     /// has `digest().is_some()` and `tag().is_none()`, matching the
     /// requirements of the OCI Referrers API.
@@ -62,6 +62,66 @@ pub enum OciLayerError {
         help("configure username/password credentials instead of an identity token")
     )]
     IdentityTokenNotSupported,
+    /// The manifest's config media type is not the wasm config media type,
+    /// so the artifact is not a wasm OCI artifact (e.g. a container image).
+    #[diagnostic(
+        code(component::oci::not_a_wasm_artifact),
+        help(
+            "expected config media type 'application/vnd.wasm.config.v0+json' but found \
+             '{config_media_type}'; this looks like a container image rather than a wasm \
+             artifact. Set `strict = false` under `[pull]` to pull it anyway"
+        )
+    )]
+    NotAWasmArtifact {
+        /// The config media type that was found.
+        config_media_type: String,
+    },
+    /// The manifest fetched for a digest-pinned reference does not hash to
+    /// the requested digest.
+    #[diagnostic(
+        code(component::oci::digest_mismatch),
+        help(
+            "the registry served different content than was requested; \
+             retry the pull or verify the digest"
+        )
+    )]
+    DigestMismatch {
+        /// The digest that was requested.
+        expected: String,
+        /// The digest of the manifest that was received.
+        actual: String,
+    },
+    /// The registry throttled our requests and retrying with backoff did
+    /// not get through.
+    #[diagnostic(
+        code(component::oci::rate_limited),
+        help(
+            "the registry is limiting how many requests you can make; wait and \
+             try again, or log in to the registry to raise the limit"
+        )
+    )]
+    RateLimited {
+        /// The registry that throttled the request.
+        registry: String,
+        /// How long the registry asked us to wait, in seconds, if it said.
+        retry_after_secs: Option<u64>,
+        /// How many requests remain in the current window, if reported.
+        remaining: Option<u64>,
+    },
+    /// A registry request did not finish within the configured total timeout.
+    #[diagnostic(
+        code(component::oci::timed_out),
+        help(
+            "the registry did not respond in time; check your connection, or raise \
+             `total-timeout` under `[network]` in the config file"
+        )
+    )]
+    TimedOut {
+        /// The registry the request was sent to.
+        registry: String,
+        /// The timeout that elapsed, in seconds.
+        after_secs: u64,
+    },
 }
 
 impl std::fmt::Display for OciLayerError {
@@ -79,6 +139,35 @@ impl std::fmt::Display for OciLayerError {
             OciLayerError::IdentityTokenNotSupported => {
                 write!(f, "identity tokens are not supported")
             }
+            OciLayerError::NotAWasmArtifact { config_media_type } => {
+                write!(
+                    f,
+                    "not a wasm OCI artifact: unexpected config media type `{config_media_type}`"
+                )
+            }
+            OciLayerError::DigestMismatch { expected, actual } => {
+                write!(
+                    f,
+                    "manifest digest mismatch: expected {expected}, got {actual}"
+                )
+            }
+            OciLayerError::RateLimited {
+                registry,
+                retry_after_secs,
+                ..
+            } => {
+                write!(f, "rate limited by {registry}")?;
+                if let Some(secs) = retry_after_secs {
+                    write!(f, "; retry after {secs}s")?;
+                }
+                Ok(())
+            }
+            OciLayerError::TimedOut {
+                registry,
+                after_secs,
+            } => {
+                write!(f, "request to {registry} timed out after {after_secs}s")
+            }
         }
     }
 }
@@ -133,5 +222,69 @@ mod tests {
             identity_token.help().is_some(),
             "IdentityTokenNotSupported must have a help message"
         );
+
+        let not_wasm = OciLayerError::NotAWasmArtifact {
+            config_media_type: "application/vnd.oci.image.config.v1+json".to_string(),
+        };
+        assert_eq!(
+            not_wasm
+                .code()
+                .expect("NotAWasmArtifact must have a diagnostic code")
+                .to_string(),
+            "component::oci::not_a_wasm_artifact",
+        );
+        assert!(
+            not_wasm.help().is_some(),
+            "NotAWasmArtifact must have a help message"
+        );
+
+        let digest_mismatch = OciLayerError::DigestMismatch {
+            expected: "sha256:aaa".to_string(),
+            actual: "sha256:bbb".to_string(),
+        };
+        assert_eq!(
+            digest_mismatch
+                .code()
+                .expect("DigestMismatch must have a diagnostic code")
+                .to_string(),
+            "component::oci::digest_mismatch",
+        );
+        assert!(
+            digest_mismatch.help().is_some(),
+            "DigestMismatch must have a help message"
+        );
+
+        let rate_limited = OciLayerError::RateLimited {
+            registry: "ghcr.io".to_string(),
+            retry_after_secs: Some(30),
+            remaining: Some(0),
+        };
+        assert_eq!(
+            rate_limited
+                .code()
+                .expect("RateLimited must have a diagnostic code")
+                .to_string(),
+            "component::oci::rate_limited",
+        );
+        assert!(
+            rate_limited.help().is_some(),
+            "RateLimited must have a help message"
+        );
+
+        let timed_out = OciLayerError::TimedOut {
+            registry: "ghcr.io".to_string(),
+            after_secs: 300,
+        };
+        assert_eq!(
+            timed_out
+                .code()
+                .expect("TimedOut must have a diagnostic code")
+                .to_string(),
+            "component::oci::timed_out",
+        );
+        assert!(
+            timed_out.help().is_some(),
+            "TimedOut must have a help message"
+        );
     }
 }
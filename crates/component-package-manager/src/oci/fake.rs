@@ -0,0 +1,359 @@
+//! An in-process registry for tests.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+
+use oci_client::Reference;
+use oci_client::client::{Config, ImageData, ImageLayer, PushResponse, SizedStream, TagResponse};
+use oci_client::errors::{OciDistributionError, OciEnvelope, OciError, OciErrorCode};
+use oci_client::manifest::{
+    ImageIndexEntry, OCI_IMAGE_INDEX_MEDIA_TYPE, OciDescriptor, OciImageIndex, OciImageManifest,
+    OciManifest,
+};
+use oci_client::secrets::RegistryAuth;
+use oci_wasm::{ToConfig, WASM_MANIFEST_MEDIA_TYPE, WasmConfig};
+
+use super::logic::sha256_digest;
+use super::transport::{Transport, TransportFuture};
+
+/// A [`Transport`] that keeps pushed artifacts in memory instead of talking
+/// to a registry.
+///
+/// Every registry and repository starts out empty and accepts any
+/// credentials. Missing manifests and blobs are reported with the same
+/// errors a real registry returns.
+///
+/// # Example
+///
+/// ```
+/// use component_package_manager::Reference;
+/// use component_package_manager::oci::FakeRegistry;
+///
+/// let registry = FakeRegistry::new();
+/// let reference: Reference = "ghcr.io/example/hello:1.0.0".parse().unwrap();
+/// let component = [0x00, 0x61, 0x73, 0x6d, 0x0d, 0x00, 0x01, 0x00];
+/// let digest = registry.insert_component(&reference, component.to_vec()).unwrap();
+/// assert!(digest.starts_with("sha256:"));
+/// assert_eq!(registry.tags(&reference), ["1.0.0"]);
+/// ```
+#[derive(Debug, Default)]
+pub struct FakeRegistry {
+    repositories: Mutex<HashMap<String, Repository>>,
+}
+
+/// The contents of one repository.
+#[derive(Debug, Default)]
+struct Repository {
+    /// Manifests by digest.
+    manifests: HashMap<String, OciImageManifest>,
+    /// Image indexes by digest.
+    indexes: HashMap<String, OciImageIndex>,
+    /// Manifest digests by tag, in tag order.
+    tags: BTreeMap<String, String>,
+    /// Config and layer blobs by digest.
+    blobs: HashMap<String, Blob>,
+}
+
+/// A stored blob and its media type.
+#[derive(Debug, Clone)]
+struct Blob {
+    data: Vec<u8>,
+    media_type: String,
+}
+
+impl FakeRegistry {
+    /// Create an empty registry.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Store a component or WIT package under `reference`, as
+    /// `component publish` would, and return its manifest digest.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `bytes` is not a wasm component.
+    pub fn insert_component(
+        &self,
+        reference: &Reference,
+        bytes: Vec<u8>,
+    ) -> anyhow::Result<String> {
+        let (config, layer) = WasmConfig::from_raw_component(bytes, None)?;
+        self.store(reference, &layer, &config.to_config()?, None)
+    }
+
+    /// Store an image index listing `entries` under `reference`, as a
+    /// multi-platform build would, and return its digest.
+    ///
+    /// The manifests the entries point at are stored separately, e.g. with
+    /// [`FakeRegistry::insert_component`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the index can't be serialized.
+    pub fn insert_index(
+        &self,
+        reference: &Reference,
+        entries: Vec<ImageIndexEntry>,
+    ) -> anyhow::Result<String> {
+        let index = OciImageIndex {
+            schema_version: 2,
+            media_type: Some(OCI_IMAGE_INDEX_MEDIA_TYPE.to_string()),
+            manifests: entries,
+            artifact_type: None,
+            annotations: None,
+        };
+        let digest = sha256_digest(&serde_json::to_vec(&index)?);
+        self.with_repository(reference, |repository| {
+            if let Some(tag) = reference.tag() {
+                repository.tags.insert(tag.to_owned(), digest.clone());
+            }
+            repository.indexes.insert(digest.clone(), index);
+        });
+        Ok(digest)
+    }
+
+    /// The tags of the repository `reference` points at, in order.
+    #[must_use]
+    pub fn tags(&self, reference: &Reference) -> Vec<String> {
+        self.with_repository(reference, |repository| {
+            repository.tags.keys().cloned().collect()
+        })
+    }
+
+    fn with_repository<T>(&self, reference: &Reference, f: impl FnOnce(&mut Repository) -> T) -> T {
+        let key = format!(
+            "{}/{}",
+            reference.resolve_registry(),
+            reference.repository()
+        );
+        let mut repositories = self
+            .repositories
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        f(repositories.entry(key).or_default())
+    }
+
+    fn store(
+        &self,
+        reference: &Reference,
+        layer: &ImageLayer,
+        config: &Config,
+        annotations: Option<BTreeMap<String, String>>,
+    ) -> anyhow::Result<String> {
+        let mut manifest =
+            OciImageManifest::build(std::slice::from_ref(layer), config, annotations);
+        manifest.media_type = Some(WASM_MANIFEST_MEDIA_TYPE.to_string());
+        let digest = sha256_digest(&serde_json::to_vec(&manifest)?);
+        self.with_repository(reference, |repository| {
+            repository.blobs.insert(
+                manifest.config.digest.clone(),
+                Blob {
+                    data: config.data.to_vec(),
+                    media_type: config.media_type.clone(),
+                },
+            );
+            repository.blobs.insert(
+                layer.sha256_digest(),
+                Blob {
+                    data: layer.data.to_vec(),
+                    media_type: layer.media_type.clone(),
+                },
+            );
+            if let Some(tag) = reference.tag() {
+                repository.tags.insert(tag.to_owned(), digest.clone());
+            }
+            repository.manifests.insert(digest.clone(), manifest);
+        });
+        Ok(digest)
+    }
+
+    /// Look up the manifest `reference` points at by digest or tag.
+    fn manifest(&self, reference: &Reference) -> anyhow::Result<(OciImageManifest, String)> {
+        self.with_repository(reference, |repository| {
+            let digest = match reference.digest() {
+                Some(digest) => Some(digest.to_owned()),
+                None => repository
+                    .tags
+                    .get(reference.tag().unwrap_or("latest"))
+                    .cloned(),
+            };
+            digest
+                .and_then(|digest| {
+                    let manifest = repository.manifests.get(&digest)?.clone();
+                    Some((manifest, digest))
+                })
+                .ok_or_else(|| not_found(reference, OciErrorCode::ManifestUnknown))
+        })
+    }
+
+    /// Look up the image index `reference` points at by digest or tag, if
+    /// it points at one.
+    fn index(&self, reference: &Reference) -> Option<(OciManifest, String)> {
+        self.with_repository(reference, |repository| {
+            let digest = match reference.digest() {
+                Some(digest) => digest,
+                None => repository.tags.get(reference.tag().unwrap_or("latest"))?,
+            };
+            let index = repository.indexes.get(digest)?.clone();
+            Some((OciManifest::ImageIndex(index), digest.to_owned()))
+        })
+    }
+
+    fn blob(&self, reference: &Reference, digest: &str) -> anyhow::Result<Blob> {
+        self.with_repository(reference, |repository| {
+            repository.blobs.get(digest).cloned()
+        })
+        .ok_or_else(|| not_found(reference, OciErrorCode::BlobUnknown))
+    }
+}
+
+impl Transport for FakeRegistry {
+    fn pull<'a>(
+        &'a self,
+        reference: &'a Reference,
+        _auth: &'a RegistryAuth,
+    ) -> TransportFuture<'a, ImageData> {
+        Box::pin(async move {
+            let (manifest, digest) = self.manifest(reference)?;
+            let config = self.blob(reference, &manifest.config.digest)?;
+            let layers = manifest
+                .layers
+                .iter()
+                .map(|descriptor| {
+                    let blob = self.blob(reference, &descriptor.digest)?;
+                    Ok(ImageLayer::new(
+                        blob.data,
+                        blob.media_type,
+                        descriptor.annotations.clone(),
+                    ))
+                })
+                .collect::<anyhow::Result<_>>()?;
+            Ok(ImageData {
+                layers,
+                digest: Some(digest),
+                config: Config::new(config.data, config.media_type, None),
+                manifest: Some(manifest),
+            })
+        })
+    }
+
+    fn push<'a>(
+        &'a self,
+        reference: &'a Reference,
+        _auth: &'a RegistryAuth,
+        layer: ImageLayer,
+        config: Config,
+        annotations: Option<BTreeMap<String, String>>,
+    ) -> TransportFuture<'a, PushResponse> {
+        Box::pin(async move {
+            let digest = self.store(reference, &layer, &config, annotations)?;
+            let repository = format!(
+                "{}/{}",
+                reference.resolve_registry(),
+                reference.repository()
+            );
+            Ok(PushResponse {
+                config_url: format!("https://{repository}/blobs/{}", config.sha256_digest()),
+                manifest_url: format!("https://{repository}/manifests/{digest}"),
+            })
+        })
+    }
+
+    fn pull_manifest_and_config<'a>(
+        &'a self,
+        reference: &'a Reference,
+        _auth: &'a RegistryAuth,
+    ) -> TransportFuture<'a, (OciImageManifest, String)> {
+        Box::pin(async move { self.manifest(reference) })
+    }
+
+    fn pull_manifest<'a>(
+        &'a self,
+        reference: &'a Reference,
+        _auth: &'a RegistryAuth,
+    ) -> TransportFuture<'a, (OciManifest, String)> {
+        Box::pin(async move {
+            if let Some(found) = self.index(reference) {
+                return Ok(found);
+            }
+            let (manifest, digest) = self.manifest(reference)?;
+            Ok((OciManifest::Image(manifest), digest))
+        })
+    }
+
+    fn pull_blob_stream<'a>(
+        &'a self,
+        reference: &'a Reference,
+        _auth: &'a RegistryAuth,
+        layer: &'a OciDescriptor,
+    ) -> TransportFuture<'a, SizedStream> {
+        Box::pin(async move {
+            let blob = self.blob(reference, &layer.digest)?;
+            let content_length = u64::try_from(blob.data.len()).ok();
+            Ok(SizedStream {
+                content_length,
+                digest_header_value: None,
+                stream: Box::pin(tokio_stream::once(Ok(blob.data.into()))),
+            })
+        })
+    }
+
+    fn list_tags<'a>(
+        &'a self,
+        reference: &'a Reference,
+        _auth: &'a RegistryAuth,
+        n: Option<usize>,
+        last: Option<&'a str>,
+    ) -> TransportFuture<'a, TagResponse> {
+        Box::pin(async move {
+            let tags = self
+                .tags(reference)
+                .into_iter()
+                .filter(|tag| last.is_none_or(|last| tag.as_str() > last))
+                .take(n.unwrap_or(usize::MAX))
+                .collect();
+            Ok(TagResponse {
+                name: reference.repository().to_owned(),
+                tags,
+            })
+        })
+    }
+
+    fn pull_referrers<'a>(
+        &'a self,
+        _reference: &'a Reference,
+        _auth: &'a RegistryAuth,
+    ) -> TransportFuture<'a, OciImageIndex> {
+        // Nothing refers to artifacts in the fake registry.
+        Box::pin(async move {
+            Ok(OciImageIndex {
+                schema_version: 2,
+                media_type: Some(OCI_IMAGE_INDEX_MEDIA_TYPE.to_string()),
+                manifests: Vec::new(),
+                artifact_type: None,
+                annotations: None,
+            })
+        })
+    }
+}
+
+/// The error a registry returns for a missing manifest or blob.
+fn not_found(reference: &Reference, code: OciErrorCode) -> anyhow::Error {
+    OciDistributionError::RegistryError {
+        envelope: OciEnvelope {
+            errors: vec![OciError {
+                code,
+                message: format!("{reference} not found"),
+                detail: serde_json::Value::Null,
+            }],
+        },
+        url: format!(
+            "https://{}/v2/{}",
+            reference.resolve_registry(),
+            reference.repository()
+        ),
+    }
+    .into()
+}
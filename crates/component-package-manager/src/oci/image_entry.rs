@@ -22,6 +22,11 @@ use super::raw::RawImageEntry;
 ///     ref_digest: None,
 ///     manifest: OciImageManifest::default(),
 ///     size_on_disk: 2048,
+///     title: None,
+///     description: None,
+///     source: None,
+///     created: None,
+///     world: None,
 /// };
 /// assert_eq!(entry.ref_registry, "ghcr.io");
 /// ```
@@ -41,6 +46,16 @@ pub struct ImageEntry {
     pub manifest: OciImageManifest,
     /// Size of the image on disk in bytes
     pub size_on_disk: u64,
+    /// The `org.opencontainers.image.title` annotation, if present
+    pub title: Option<String>,
+    /// The `org.opencontainers.image.description` annotation, if present
+    pub description: Option<String>,
+    /// The `org.opencontainers.image.source` annotation (source repository URL), if present
+    pub source: Option<String>,
+    /// The `org.opencontainers.image.created` annotation (RFC 3339 build time), if present
+    pub created: Option<String>,
+    /// The WIT world the component targets (e.g., `wasi:http/proxy@0.2.0`), if known
+    pub world: Option<String>,
 }
 
 impl ImageEntry {
@@ -60,6 +75,11 @@ impl ImageEntry {
     ///     ref_digest: None,
     ///     manifest: OciImageManifest::default(),
     ///     size_on_disk: 0,
+    ///     title: None,
+    ///     description: None,
+    ///     source: None,
+    ///     created: None,
+    ///     world: None,
     /// };
     /// assert_eq!(entry.reference(), "ghcr.io/user/repo:v1.0");
     /// ```
@@ -87,6 +107,11 @@ impl From<RawImageEntry> for ImageEntry {
             ref_digest: entry.ref_digest,
             manifest: entry.manifest,
             size_on_disk: entry.size_on_disk,
+            title: entry.title,
+            description: entry.description,
+            source: entry.source,
+            created: entry.created,
+            world: entry.world,
         }
     }
 }
@@ -107,6 +132,11 @@ mod tests {
             ref_digest: Some("sha256:abc123".into()),
             manifest: OciImageManifest::default(),
             size_on_disk: 0,
+            title: None,
+            description: None,
+            source: None,
+            created: None,
+            world: None,
         };
         assert_eq!(entry.reference(), "ghcr.io/user/repo:v1.0");
     }
@@ -121,6 +151,11 @@ mod tests {
             ref_digest: Some("sha256:abc123".into()),
             manifest: OciImageManifest::default(),
             size_on_disk: 0,
+            title: None,
+            description: None,
+            source: None,
+            created: None,
+            world: None,
         };
         assert_eq!(entry.reference(), "docker.io/library/nginx@sha256:abc123");
     }
@@ -135,6 +170,11 @@ mod tests {
             ref_digest: None,
             manifest: OciImageManifest::default(),
             size_on_disk: 0,
+            title: None,
+            description: None,
+            source: None,
+            created: None,
+            world: None,
         };
         assert_eq!(entry.reference(), "ghcr.io/user/repo");
     }
@@ -3,7 +3,7 @@
 //!
 //! These functions contain no IO and can be unit-tested in isolation.
 
-use oci_client::manifest::OciDescriptor;
+use oci_client::manifest::{ImageIndexEntry, OciDescriptor, OciImageManifest};
 use std::collections::HashSet;
 use std::ffi::OsStr;
 
@@ -76,6 +76,158 @@ pub fn validate_single_wasm_layer(
     Ok(())
 }
 
+/// Pick the WebAssembly artifact from the entries of an OCI image index.
+///
+/// An entry whose platform architecture is `wasm` wins. Otherwise, when the
+/// index holds a single entry besides any attestation manifests, that entry
+/// is used. Returns `None` when no entry can be chosen unambiguously.
+///
+/// # Example
+///
+/// ```
+/// use oci_client::manifest::ImageIndexEntry;
+/// use component_package_manager::oci::select_wasm_manifest;
+///
+/// let entries: Vec<ImageIndexEntry> = serde_json::from_str(r#"[
+///     {"mediaType": "application/vnd.oci.image.manifest.v1+json", "digest": "sha256:aaa",
+///      "size": 0, "platform": {"architecture": "amd64", "os": "linux"}},
+///     {"mediaType": "application/vnd.oci.image.manifest.v1+json", "digest": "sha256:bbb",
+///      "size": 0, "platform": {"architecture": "wasm", "os": "wasip2"}}
+/// ]"#).unwrap();
+/// assert_eq!(select_wasm_manifest(&entries).unwrap().digest, "sha256:bbb");
+/// assert!(select_wasm_manifest(&entries[..1]).is_some());
+/// ```
+#[must_use]
+pub fn select_wasm_manifest(entries: &[ImageIndexEntry]) -> Option<&ImageIndexEntry> {
+    let is_wasm = |entry: &&ImageIndexEntry| {
+        entry
+            .platform
+            .as_ref()
+            .is_some_and(|platform| platform.architecture.to_string() == "wasm")
+    };
+    if let Some(entry) = entries.iter().find(is_wasm) {
+        return Some(entry);
+    }
+    let mut artifacts = entries.iter().filter(|entry| {
+        entry
+            .annotations
+            .as_ref()
+            .and_then(|a| a.get("vnd.docker.reference.type"))
+            .is_none_or(|kind| kind != "attestation-manifest")
+    });
+    match (artifacts.next(), artifacts.next()) {
+        (Some(entry), None) => Some(entry),
+        _ => None,
+    }
+}
+
+/// Whether an image index entry is for `platform`, written as
+/// `os/architecture` or `os/architecture/variant`.
+///
+/// # Example
+///
+/// ```
+/// use oci_client::manifest::ImageIndexEntry;
+/// use component_package_manager::oci::matches_platform;
+///
+/// let entry: ImageIndexEntry = serde_json::from_str(r#"
+///     {"mediaType": "application/vnd.oci.image.manifest.v1+json", "digest": "sha256:aaa",
+///      "size": 0, "platform": {"architecture": "wasm", "os": "wasip2"}}
+/// "#).unwrap();
+/// assert!(matches_platform(&entry, "wasip2/wasm"));
+/// assert!(!matches_platform(&entry, "linux/amd64"));
+/// assert!(!matches_platform(&entry, "wasip2/wasm/v1"));
+/// ```
+#[must_use]
+pub fn matches_platform(entry: &ImageIndexEntry, platform: &str) -> bool {
+    let Some(actual) = &entry.platform else {
+        return false;
+    };
+    let mut parts = platform.split('/');
+    let (Some(os), Some(architecture)) = (parts.next(), parts.next()) else {
+        return false;
+    };
+    let variant = parts.next();
+    actual.os.to_string() == os
+        && actual.architecture.to_string() == architecture
+        && variant.is_none_or(|variant| actual.variant.as_deref() == Some(variant))
+}
+
+/// Whether a manifest declares `artifact_type`, either as its
+/// `artifactType` or, for artifacts that predate that field, as the media
+/// type of its config.
+#[must_use]
+pub fn matches_artifact_type(manifest: &OciImageManifest, artifact_type: &str) -> bool {
+    manifest.artifact_type.as_deref() == Some(artifact_type)
+        || manifest.config.media_type == artifact_type
+}
+
+/// Verify that the manifest fetched for a digest-pinned reference is the one
+/// that was asked for.
+///
+/// `fetched` is the digest the client computed over the manifest bytes it
+/// received. References without a digest are not checked. When the two
+/// digests use different algorithms they cannot be compared directly, and
+/// the client's own check of the body against the requested digest is
+/// relied upon instead.
+///
+/// # Example
+///
+/// ```
+/// use component_package_manager::oci::verify_manifest_digest;
+///
+/// assert!(verify_manifest_digest(Some("sha256:aaa"), Some("sha256:aaa")).is_ok());
+/// assert!(verify_manifest_digest(Some("sha256:aaa"), Some("sha256:bbb")).is_err());
+/// assert!(verify_manifest_digest(None, Some("sha256:bbb")).is_ok());
+/// ```
+///
+/// # Errors
+///
+/// Returns [`OciLayerError::DigestMismatch`](super::errors::OciLayerError::DigestMismatch)
+/// if the fetched digest differs from the requested one, or is missing.
+pub fn verify_manifest_digest(
+    requested: Option<&str>,
+    fetched: Option<&str>,
+) -> Result<(), super::errors::OciLayerError> {
+    let Some(expected) = requested else {
+        return Ok(());
+    };
+    let algorithm = |digest: &str| digest.split_once(':').map(|(alg, _)| alg.to_owned());
+    match fetched {
+        Some(actual) if actual.eq_ignore_ascii_case(expected) => Ok(()),
+        Some(actual) if algorithm(actual) != algorithm(expected) => Ok(()),
+        actual => Err(super::errors::OciLayerError::DigestMismatch {
+            expected: expected.to_owned(),
+            actual: actual.unwrap_or("unknown").to_owned(),
+        }),
+    }
+}
+
+/// Validate that a manifest follows the [CNCF wasm OCI artifact] layout: a
+/// `application/vnd.wasm.config.v0+json` config and a single
+/// `application/wasm` layer.
+///
+/// This is stricter than [`validate_single_wasm_layer`], and rejects
+/// container images that happen to carry a wasm layer.
+///
+/// [CNCF wasm OCI artifact]: https://tag-runtime.cncf.io/wgs/wasm/deliverables/wasm-oci-artifact/
+///
+/// # Errors
+///
+/// Returns [`OciLayerError::NotAWasmArtifact`](super::errors::OciLayerError::NotAWasmArtifact)
+/// for any other config media type, or the errors of
+/// [`validate_single_wasm_layer`].
+pub fn validate_wasm_artifact(
+    manifest: &OciImageManifest,
+) -> Result<(), super::errors::OciLayerError> {
+    if manifest.config.media_type != oci_wasm::WASM_MANIFEST_CONFIG_MEDIA_TYPE {
+        return Err(super::errors::OciLayerError::NotAWasmArtifact {
+            config_media_type: manifest.config.media_type.clone(),
+        });
+    }
+    validate_single_wasm_layer(&manifest.layers)
+}
+
 /// Compute which layer digests are orphaned after removing a set of manifests.
 ///
 /// Given the digests belonging to the manifests being deleted and the digests
@@ -139,6 +291,46 @@ pub fn classify_tag(tag: &str) -> TagKind {
     }
 }
 
+/// Media and artifact types of signature manifests: cosign, Notation, and
+/// Sigstore bundles.
+const SIGNATURE_MEDIA_TYPES: &[&str] = &[
+    "application/vnd.dev.cosign.artifact.sig.v1+json",
+    "application/vnd.dev.cosign.simplesigning.v1+json",
+    "application/vnd.cncf.notary.signature",
+    "application/vnd.dev.sigstore.bundle",
+];
+
+/// Whether a referrer's media or artifact type marks it as a signature.
+pub(crate) fn is_signature_media_type(media_type: &str) -> bool {
+    SIGNATURE_MEDIA_TYPES
+        .iter()
+        .any(|signature| media_type.starts_with(signature))
+}
+
+/// The cosign signature tag (`sha256-<hex>.sig`) for a manifest digest.
+pub(crate) fn signature_tag(digest: &str) -> String {
+    format!("{}.sig", digest.replacen(':', "-", 1))
+}
+
+/// Media and artifact types of attestation manifests: in-toto statements and
+/// the DSSE envelopes cosign wraps them in.
+const ATTESTATION_MEDIA_TYPES: &[&str] = &[
+    "application/vnd.in-toto",
+    "application/vnd.dsse.envelope.v1+json",
+];
+
+/// Whether a referrer's media or artifact type marks it as an attestation.
+pub(crate) fn is_attestation_media_type(media_type: &str) -> bool {
+    ATTESTATION_MEDIA_TYPES
+        .iter()
+        .any(|attestation| media_type.starts_with(attestation))
+}
+
+/// The cosign attestation tag (`sha256-<hex>.att`) for a manifest digest.
+pub(crate) fn attestation_tag(digest: &str) -> String {
+    format!("{}.att", digest.replacen(':', "-", 1))
+}
+
 /// The kind of an OCI tag.
 ///
 /// # Example
@@ -196,6 +388,120 @@ pub fn classify_tags(tags: &[String]) -> (Vec<String>, Vec<String>, Vec<String>)
     (release, signature, attestation)
 }
 
+/// The human-readable description of an image from its manifest
+/// annotations: `org.opencontainers.image.description`, falling back to
+/// `org.opencontainers.image.title`.
+///
+/// # Example
+///
+/// ```
+/// use std::collections::BTreeMap;
+/// use oci_client::manifest::OciImageManifest;
+/// use component_package_manager::oci::manifest_description;
+///
+/// let mut manifest = OciImageManifest::default();
+/// assert_eq!(manifest_description(&manifest), None);
+///
+/// manifest.annotations = Some(BTreeMap::from([(
+///     "org.opencontainers.image.title".to_string(),
+///     "HTTP server".to_string(),
+/// )]));
+/// assert_eq!(manifest_description(&manifest), Some("HTTP server"));
+/// ```
+#[must_use]
+pub fn manifest_description(manifest: &OciImageManifest) -> Option<&str> {
+    let annotations = manifest.annotations.as_ref()?;
+    [
+        "org.opencontainers.image.description",
+        "org.opencontainers.image.title",
+    ]
+    .iter()
+    .filter_map(|key| annotations.get(*key))
+    .map(|value| value.trim())
+    .find(|value| !value.is_empty())
+}
+
+/// Match `text` against a shell-style glob `pattern`.
+///
+/// `*` matches any run of characters (including `/` and `:`) and `?`
+/// matches exactly one character. All other characters match literally.
+///
+/// # Example
+///
+/// ```
+/// use component_package_manager::oci::glob_match;
+///
+/// assert!(glob_match("ghcr.io/webassembly/*", "ghcr.io/webassembly/wasi/io:0.2.0"));
+/// assert!(glob_match("*:0.?.0", "ghcr.io/example/app:0.2.0"));
+/// assert!(!glob_match("docker.io/*", "ghcr.io/example/app:0.2.0"));
+/// ```
+#[must_use]
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` and the text index it is currently matched up to.
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match (pattern.get(p), text.get(t)) {
+            (Some('*'), _) => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            (Some('?'), _) => {
+                p += 1;
+                t += 1;
+            }
+            (Some(pc), Some(tc)) if pc == tc => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    t = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern
+        .get(p..)
+        .is_some_and(|rest| rest.iter().all(|&c| c == '*'))
+}
+
+/// Normalize a manifest digest prefix for lookups.
+///
+/// A bare hex prefix is assumed to be `sha256`. Returns `None` when the
+/// prefix has no hex digits, since it would match every manifest.
+///
+/// # Example
+///
+/// ```
+/// use component_package_manager::oci::normalize_digest_prefix;
+///
+/// assert_eq!(normalize_digest_prefix("ab12").as_deref(), Some("sha256:ab12"));
+/// assert_eq!(normalize_digest_prefix("sha512:AB").as_deref(), Some("sha512:ab"));
+/// assert_eq!(normalize_digest_prefix("sha256:"), None);
+/// assert_eq!(normalize_digest_prefix("xyz"), None);
+/// ```
+#[must_use]
+pub fn normalize_digest_prefix(raw: &str) -> Option<String> {
+    let (algorithm, hex) = raw.split_once(':').unwrap_or(("sha256", raw));
+    if algorithm.is_empty() || hex.is_empty() || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    Some(format!("{algorithm}:{}", hex.to_ascii_lowercase()))
+}
+
+/// The `sha256:` digest of `bytes`, as a registry would compute it.
+pub(crate) fn sha256_digest(bytes: &[u8]) -> String {
+    // `oci-client` only exposes its digest function through blob types.
+    oci_client::client::Config::new(bytes.to_vec(), String::new(), None).sha256_digest()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -393,6 +699,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn signature_tag_round_trips_through_classify_tag() {
+        let tag = signature_tag("sha256:abc123");
+        assert_eq!(tag, "sha256-abc123.sig");
+        assert_eq!(classify_tag(&tag), TagKind::Signature);
+    }
+
+    #[test]
+    fn attestation_tag_round_trips_through_classify_tag() {
+        let tag = attestation_tag("sha256:abc123");
+        assert_eq!(tag, "sha256-abc123.att");
+        assert_eq!(classify_tag(&tag), TagKind::Attestation);
+    }
+
+    #[test]
+    fn signature_media_types() {
+        assert!(is_signature_media_type(
+            "application/vnd.dev.sigstore.bundle.v0.3+json"
+        ));
+        assert!(is_signature_media_type(
+            "application/vnd.cncf.notary.signature"
+        ));
+        assert!(!is_signature_media_type("application/spdx+json"));
+    }
+
     #[test]
     fn classify_tag_sha256_without_suffix() {
         // sha256- prefix but no .sig or .att → release
@@ -478,4 +809,124 @@ mod tests {
         let bad_key = "ghcr.io/example/my-component:1.0.0";
         assert!(cacache::read(cache, bad_key).await.is_err());
     }
+
+    // ── validate_wasm_artifact ──────────────────────────────────────────
+
+    #[test]
+    fn validate_wasm_artifact_rejects_container_images() {
+        let layer = OciDescriptor {
+            media_type: "application/wasm".to_string(),
+            digest: "sha256:aaa".to_string(),
+            size: 100,
+            urls: None,
+            annotations: None,
+        };
+        let mut manifest = OciImageManifest {
+            layers: vec![layer],
+            ..OciImageManifest::default()
+        };
+        manifest.config.media_type = oci_wasm::WASM_MANIFEST_CONFIG_MEDIA_TYPE.to_string();
+        assert!(validate_wasm_artifact(&manifest).is_ok());
+
+        manifest.config.media_type = "application/vnd.oci.image.config.v1+json".to_string();
+        assert!(matches!(
+            validate_wasm_artifact(&manifest),
+            Err(super::super::errors::OciLayerError::NotAWasmArtifact { .. })
+        ));
+    }
+
+    // ── glob_match ──────────────────────────────────────────────────────
+
+    #[test]
+    fn glob_match_wildcards() {
+        assert!(glob_match("*", ""));
+        assert!(glob_match("*", "ghcr.io/a/b:1"));
+        assert!(glob_match("ghcr.io/*/b:*", "ghcr.io/a/b:1"));
+        assert!(glob_match("ghcr.io/a/b:?", "ghcr.io/a/b:1"));
+        assert!(glob_match("*b*b*", "abcbd"));
+        assert!(!glob_match("ghcr.io/a/b:?", "ghcr.io/a/b:10"));
+        assert!(!glob_match("ghcr.io/a", "ghcr.io/a/b"));
+        assert!(!glob_match("", "a"));
+    }
+
+    // ── select_wasm_manifest ────────────────────────────────────────────
+
+    fn index_entry(digest: &str, platform: &str, annotations: &str) -> ImageIndexEntry {
+        serde_json::from_str(&format!(
+            r#"{{"mediaType": "application/vnd.oci.image.manifest.v1+json",
+                "digest": "{digest}", "size": 0, "platform": {platform},
+                "annotations": {annotations}}}"#
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn select_wasm_manifest_skips_attestations_and_rejects_ambiguity() {
+        let linux = r#"{"architecture": "amd64", "os": "linux"}"#;
+        let unknown = r#"{"architecture": "unknown", "os": "unknown"}"#;
+        let attestation = r#"{"vnd.docker.reference.type": "attestation-manifest"}"#;
+
+        let entries = [
+            index_entry("sha256:aaa", linux, "null"),
+            index_entry("sha256:att", unknown, attestation),
+        ];
+        let selected = select_wasm_manifest(&entries).map(|e| e.digest.as_str());
+        assert_eq!(selected, Some("sha256:aaa"));
+
+        let entries = [
+            index_entry("sha256:aaa", linux, "null"),
+            index_entry("sha256:bbb", unknown, "null"),
+        ];
+        assert!(select_wasm_manifest(&entries).is_none());
+        assert!(select_wasm_manifest(&[]).is_none());
+    }
+
+    // ── matches_platform / matches_artifact_type ────────────────────────
+
+    #[test]
+    fn matches_platform_compares_os_architecture_and_variant() {
+        let arm = r#"{"architecture": "arm64", "os": "linux", "variant": "v8"}"#;
+        let entry = index_entry("sha256:aaa", arm, "null");
+        assert!(matches_platform(&entry, "linux/arm64"));
+        assert!(matches_platform(&entry, "linux/arm64/v8"));
+        assert!(!matches_platform(&entry, "linux/arm64/v7"));
+        assert!(!matches_platform(&entry, "linux"));
+        assert!(!matches_platform(
+            &index_entry("sha256:bbb", "null", "null"),
+            "linux/arm64"
+        ));
+    }
+
+    #[test]
+    fn matches_artifact_type_accepts_the_field_or_config_media_type() {
+        let mut manifest = OciImageManifest::default();
+        manifest.config.media_type = oci_wasm::WASM_MANIFEST_CONFIG_MEDIA_TYPE.to_string();
+        assert!(matches_artifact_type(
+            &manifest,
+            oci_wasm::WASM_MANIFEST_CONFIG_MEDIA_TYPE
+        ));
+        assert!(!matches_artifact_type(
+            &manifest,
+            "application/vnd.example+json"
+        ));
+
+        manifest.artifact_type = Some("application/vnd.example+json".to_string());
+        assert!(matches_artifact_type(
+            &manifest,
+            "application/vnd.example+json"
+        ));
+    }
+
+    // ── normalize_digest_prefix ─────────────────────────────────────────
+
+    #[test]
+    fn normalize_digest_prefix_rejects_empty_and_non_hex() {
+        assert_eq!(
+            normalize_digest_prefix("sha256:abc").as_deref(),
+            Some("sha256:abc")
+        );
+        assert_eq!(normalize_digest_prefix(""), None);
+        assert_eq!(normalize_digest_prefix(":abc"), None);
+        assert_eq!(normalize_digest_prefix("sha256:xyz"), None);
+    }
 }
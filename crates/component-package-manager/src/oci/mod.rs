@@ -4,21 +4,38 @@
 //! client communication, data models, image entries, and
 //! pure logic for tag classification and layer management.
 
+mod auth;
+mod catalog;
 mod client;
 mod errors;
+#[cfg(any(test, feature = "test-util"))]
+mod fake;
 mod image_entry;
 mod logic;
 mod models;
+mod rate_limit;
 mod raw;
+mod sort;
+mod transport;
 
 pub(crate) use client::Client;
 pub use errors::OciLayerError;
+#[cfg(any(test, feature = "test-util"))]
+pub use fake::FakeRegistry;
 pub use image_entry::ImageEntry;
 pub use logic::{
-    TagKind, classify_tag, classify_tags, compute_orphaned_layers, filter_wasm_layers,
-    validate_single_wasm_layer,
+    TagKind, classify_tag, classify_tags, compute_orphaned_layers, filter_wasm_layers, glob_match,
+    manifest_description, matches_artifact_type, matches_platform, normalize_digest_prefix,
+    select_wasm_manifest, validate_single_wasm_layer, validate_wasm_artifact,
+    verify_manifest_digest,
+};
+pub(crate) use logic::{
+    attestation_tag, is_attestation_media_type, is_signature_media_type, sha256_digest,
+    signature_tag,
 };
 pub use models::InsertResult;
 #[allow(unreachable_pub)]
 pub use models::{OciLayer, OciLayerAnnotation, OciManifest, OciReferrer, OciRepository, OciTag};
 pub(crate) use raw::RawImageEntry;
+pub use sort::{SortBy, SortDirection};
+pub use transport::{Transport, TransportFuture};
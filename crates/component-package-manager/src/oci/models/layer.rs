@@ -32,11 +32,17 @@ impl OciLayer {
         size_bytes: Option<i64>,
         position: i32,
     ) -> anyhow::Result<i64> {
-        conn.execute(
+        conn.prepare_cached(
             "INSERT INTO oci_layer (oci_manifest_id, digest, media_type, size_bytes, position)
              VALUES (?1, ?2, ?3, ?4, ?5)",
-            rusqlite::params![oci_manifest_id, digest, media_type, size_bytes, position],
-        )?;
+        )?
+        .execute(rusqlite::params![
+            oci_manifest_id,
+            digest,
+            media_type,
+            size_bytes,
+            position
+        ])?;
 
         Ok(conn.last_insert_rowid())
     }
@@ -46,7 +52,7 @@ impl OciLayer {
         conn: &Connection,
         oci_manifest_id: i64,
     ) -> anyhow::Result<Vec<Self>> {
-        let mut stmt = conn.prepare(
+        let mut stmt = conn.prepare_cached(
             "SELECT id, oci_manifest_id, digest, media_type, size_bytes, position
              FROM oci_layer WHERE oci_manifest_id = ?1 ORDER BY position ASC",
         )?;
@@ -69,6 +75,15 @@ impl OciLayer {
         Ok(result)
     }
 
+    /// Whether any manifest still references a layer with this digest.
+    pub(crate) fn is_referenced(conn: &Connection, digest: &str) -> anyhow::Result<bool> {
+        Ok(conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM oci_layer WHERE digest = ?1)",
+            [digest],
+            |row| row.get(0),
+        )?)
+    }
+
     /// Find a layer by manifest id and digest.
     #[allow(dead_code)]
     pub(crate) fn get_by_digest(
@@ -26,12 +26,12 @@ impl OciLayerAnnotation {
         key: &str,
         value: &str,
     ) -> anyhow::Result<i64> {
-        conn.execute(
+        conn.prepare_cached(
             "INSERT INTO oci_layer_annotation (oci_layer_id, `key`, `value`)
              VALUES (?1, ?2, ?3)
              ON CONFLICT(oci_layer_id, `key`) DO UPDATE SET `value` = ?3",
-            rusqlite::params![oci_layer_id, key, value],
-        )?;
+        )?
+        .execute(rusqlite::params![oci_layer_id, key, value])?;
 
         let id: i64 = conn.query_row(
             "SELECT id FROM oci_layer_annotation
@@ -46,7 +46,7 @@ impl OciLayerAnnotation {
     /// List all annotations for a given layer.
     #[allow(dead_code)]
     pub(crate) fn list_by_layer(conn: &Connection, oci_layer_id: i64) -> anyhow::Result<Vec<Self>> {
-        let mut stmt = conn.prepare(
+        let mut stmt = conn.prepare_cached(
             "SELECT id, oci_layer_id, `key`, `value`
              FROM oci_layer_annotation WHERE oci_layer_id = ?1 ORDER BY `key` ASC",
         )?;
@@ -208,12 +208,12 @@ impl OciManifest {
 
         // Store extra (non-well-known) annotations.
         for (key, value) in &extra {
-            conn.execute(
+            conn.prepare_cached(
                 "INSERT INTO oci_manifest_annotation (oci_manifest_id, `key`, `value`)
                  VALUES (?1, ?2, ?3)
                  ON CONFLICT(oci_manifest_id, `key`) DO UPDATE SET `value` = ?3",
-                rusqlite::params![manifest_id, key, value],
-            )?;
+            )?
+            .execute(rusqlite::params![manifest_id, key, value])?;
         }
 
         Ok((manifest_id, was_inserted))
@@ -269,7 +269,7 @@ impl OciManifest {
         conn: &Connection,
         oci_repository_id: i64,
     ) -> anyhow::Result<Vec<Self>> {
-        let mut stmt = conn.prepare(
+        let mut stmt = conn.prepare_cached(
             "SELECT id, oci_repository_id, digest, media_type, raw_json, size_bytes,
                     created_at, artifact_type, config_media_type, config_digest,
                     oci_created, oci_authors, oci_url, oci_documentation, oci_source,
@@ -287,6 +287,48 @@ impl OciManifest {
         Ok(result)
     }
 
+    /// List all manifests, in any repository, whose digest starts with `prefix`.
+    pub(crate) fn list_by_digest_prefix(
+        conn: &Connection,
+        prefix: &str,
+    ) -> anyhow::Result<Vec<Self>> {
+        let mut stmt = conn.prepare_cached(
+            "SELECT id, oci_repository_id, digest, media_type, raw_json, size_bytes,
+                    created_at, artifact_type, config_media_type, config_digest,
+                    oci_created, oci_authors, oci_url, oci_documentation, oci_source,
+                    oci_version, oci_revision, oci_vendor, oci_licenses, oci_ref_name,
+                    oci_title, oci_description, oci_base_digest, oci_base_name
+             FROM oci_manifest WHERE substr(digest, 1, length(?1)) = ?1
+             ORDER BY created_at ASC",
+        )?;
+
+        let rows = stmt.query_map([prefix], Self::from_row)?;
+
+        let mut result = Vec::new();
+        for row in rows {
+            result.push(row?);
+        }
+        Ok(result)
+    }
+
+    /// Whether any manifest still uses a config blob with this digest.
+    pub(crate) fn is_config_referenced(conn: &Connection, digest: &str) -> anyhow::Result<bool> {
+        Ok(conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM oci_manifest WHERE config_digest = ?1)",
+            [digest],
+            |row| row.get(0),
+        )?)
+    }
+
+    /// Record that the image was just pulled, installed, or run.
+    pub(crate) fn touch(conn: &Connection, id: i64) -> anyhow::Result<()> {
+        conn.execute(
+            "UPDATE oci_manifest SET last_accessed_at = CURRENT_TIMESTAMP WHERE id = ?1",
+            [id],
+        )?;
+        Ok(())
+    }
+
     /// Delete a manifest by primary key. Returns `true` if a row was removed.
     pub(crate) fn delete(conn: &Connection, id: i64) -> anyhow::Result<bool> {
         let rows = conn.execute("DELETE FROM oci_manifest WHERE id = ?1", [id])?;
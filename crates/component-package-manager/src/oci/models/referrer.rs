@@ -51,7 +51,7 @@ impl OciReferrer {
         conn: &Connection,
         subject_manifest_id: i64,
     ) -> anyhow::Result<Vec<Self>> {
-        let mut stmt = conn.prepare(
+        let mut stmt = conn.prepare_cached(
             "SELECT id, subject_manifest_id, referrer_manifest_id, artifact_type, created_at
              FROM oci_referrer WHERE subject_manifest_id = ?1 ORDER BY created_at ASC",
         )?;
@@ -83,6 +83,39 @@ impl OciRepository {
         Ok(id)
     }
 
+    /// Set the repository's description, replacing any previous one.
+    pub(crate) fn set_description(
+        conn: &Connection,
+        id: i64,
+        description: &str,
+    ) -> anyhow::Result<()> {
+        conn.execute(
+            "UPDATE oci_repository SET description = ?1
+             WHERE id = ?2 AND description IS NOT ?1",
+            rusqlite::params![description, id],
+        )?;
+        Ok(())
+    }
+
+    /// Set whether the repository is pinned.
+    pub(crate) fn set_pinned(conn: &Connection, id: i64, pinned: bool) -> anyhow::Result<()> {
+        conn.execute(
+            "UPDATE oci_repository SET pinned = ?1 WHERE id = ?2 AND pinned IS NOT ?1",
+            rusqlite::params![pinned, id],
+        )?;
+        Ok(())
+    }
+
+    /// Returns whether the repository is pinned.
+    pub(crate) fn is_pinned(conn: &Connection, id: i64) -> anyhow::Result<bool> {
+        let pinned = conn.query_row(
+            "SELECT pinned FROM oci_repository WHERE id = ?1",
+            [id],
+            |row| row.get(0),
+        )?;
+        Ok(pinned)
+    }
+
     /// Get a repository by its primary key.
     #[allow(dead_code)]
     pub(crate) fn get_by_id(conn: &Connection, id: i64) -> anyhow::Result<Option<Self>> {
@@ -137,9 +170,8 @@ impl OciRepository {
     }
 
     /// List every repository.
-    #[allow(dead_code)]
     pub(crate) fn list_all(conn: &Connection) -> anyhow::Result<Vec<Self>> {
-        let mut stmt = conn.prepare(
+        let mut stmt = conn.prepare_cached(
             "SELECT id, registry, repository, created_at, updated_at
              FROM oci_repository ORDER BY repository ASC, registry ASC",
         )?;
@@ -12,7 +12,6 @@ pub struct OciTag {
     /// Digest of the manifest this tag references.
     pub manifest_digest: String,
     /// The tag string (e.g. "latest", "v1.0.0").
-    #[allow(dead_code)]
     pub tag: String,
     /// When the row was created.
     #[allow(dead_code)]
@@ -30,14 +29,14 @@ impl OciTag {
         tag: &str,
         manifest_digest: &str,
     ) -> anyhow::Result<i64> {
-        conn.execute(
+        conn.prepare_cached(
             "INSERT INTO oci_tag (oci_repository_id, tag, manifest_digest)
              VALUES (?1, ?2, ?3)
              ON CONFLICT(oci_repository_id, tag) DO UPDATE SET
                  manifest_digest = ?3,
                  updated_at = CURRENT_TIMESTAMP",
-            (oci_repository_id, tag, manifest_digest),
-        )?;
+        )?
+        .execute((oci_repository_id, tag, manifest_digest))?;
 
         let id: i64 = conn.query_row(
             "SELECT id FROM oci_tag WHERE oci_repository_id = ?1 AND tag = ?2",
@@ -49,12 +48,11 @@ impl OciTag {
     }
 
     /// List all tags for a repository.
-    #[allow(dead_code)]
     pub(crate) fn list_by_repository(
         conn: &Connection,
         oci_repository_id: i64,
     ) -> anyhow::Result<Vec<Self>> {
-        let mut stmt = conn.prepare(
+        let mut stmt = conn.prepare_cached(
             "SELECT id, oci_repository_id, manifest_digest, tag, created_at, updated_at
              FROM oci_tag WHERE oci_repository_id = ?1 ORDER BY tag ASC",
         )?;
@@ -105,4 +103,31 @@ impl OciTag {
             Err(e) => Err(e.into()),
         }
     }
+
+    /// List `registry/repository:tag` for every tag pointing at a manifest
+    /// that has `digest`, either as its own digest or as one of its layers.
+    pub(crate) fn references_to_digest(
+        conn: &Connection,
+        digest: &str,
+    ) -> anyhow::Result<Vec<String>> {
+        let mut stmt = conn.prepare_cached(
+            "SELECT DISTINCT r.registry || '/' || r.repository || ':' || t.tag
+             FROM oci_tag t
+             JOIN oci_repository r ON r.id = t.oci_repository_id
+             LEFT JOIN oci_manifest m
+               ON m.oci_repository_id = t.oci_repository_id AND m.digest = t.manifest_digest
+             WHERE t.manifest_digest = ?1
+                OR EXISTS (
+                    SELECT 1 FROM oci_layer l WHERE l.oci_manifest_id = m.id AND l.digest = ?1
+                )
+             ORDER BY 1 ASC",
+        )?;
+        let rows = stmt.query_map([digest], |row| row.get(0))?;
+
+        let mut result = Vec::new();
+        for row in rows {
+            result.push(row?);
+        }
+        Ok(result)
+    }
 }
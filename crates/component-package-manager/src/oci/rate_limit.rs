@@ -0,0 +1,179 @@
+//! Detecting registry rate limits and backing off from them.
+//!
+//! Registries signal throttling with `429 Too Many Requests`, or, like the
+//! GitHub API, with `403 Forbidden` and an exhausted quota header. Responses
+//! may carry a `Retry-After` header and a remaining-quota header; where they
+//! do, we honour the former and report the latter.
+
+use std::time::Duration;
+
+use oci_client::errors::{OciDistributionError, OciErrorCode};
+use reqwest::StatusCode;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+
+/// How many times a rate-limited request is attempted before giving up.
+pub(crate) const MAX_ATTEMPTS: u32 = 4;
+
+/// The delay before the first retry when the registry gives no `Retry-After`.
+const BASE_DELAY: Duration = Duration::from_secs(1);
+
+/// The longest we wait between attempts. A registry asking for a longer
+/// pause gets a [`RateLimited`](super::OciLayerError::RateLimited) error
+/// instead of a hung command.
+pub(crate) const MAX_DELAY: Duration = Duration::from_mins(1);
+
+/// Remaining-quota headers, in order of preference: the IETF draft name
+/// used by Docker Hub and the `X-` name used by GitHub.
+const REMAINING_HEADERS: [&str; 2] = ["ratelimit-remaining", "x-ratelimit-remaining"];
+
+/// The rate-limit information a registry response carries.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct RateLimit {
+    /// How long the registry asked us to wait before retrying.
+    pub(crate) retry_after: Option<Duration>,
+    /// How many requests remain in the current window.
+    pub(crate) remaining: Option<u64>,
+}
+
+impl RateLimit {
+    /// Read the rate-limit headers of a response.
+    pub(crate) fn from_headers(headers: &HeaderMap) -> Self {
+        let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+        let remaining = REMAINING_HEADERS
+            .iter()
+            .find_map(|name| header(name))
+            .and_then(parse_remaining);
+        let retry_after = headers
+            .get(RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(parse_retry_after)
+            .or_else(|| {
+                // GitHub reports when an exhausted quota resets instead.
+                (remaining == Some(0))
+                    .then(|| header("x-ratelimit-reset"))
+                    .flatten()
+                    .and_then(parse_reset)
+            });
+        Self {
+            retry_after,
+            remaining,
+        }
+    }
+
+    /// Whether a response with this status and these limits was throttled.
+    pub(crate) fn is_limited(&self, status: StatusCode) -> bool {
+        status == StatusCode::TOO_MANY_REQUESTS
+            || (status == StatusCode::FORBIDDEN && self.remaining == Some(0))
+    }
+}
+
+/// Whether an error returned by `oci-client` means the registry throttled us.
+pub(crate) fn is_rate_limited(err: &anyhow::Error) -> bool {
+    err.chain()
+        .filter_map(|cause| cause.downcast_ref::<OciDistributionError>())
+        .any(|err| match err {
+            OciDistributionError::ServerError { code, .. } => {
+                *code == StatusCode::TOO_MANY_REQUESTS.as_u16()
+            }
+            OciDistributionError::RegistryError { envelope, .. } => envelope
+                .errors
+                .iter()
+                .any(|e| e.code == OciErrorCode::Toomanyrequests),
+            _ => false,
+        })
+}
+
+/// How long to wait before attempt `attempt + 1`, or `None` to give up.
+///
+/// A `Retry-After` from the registry wins over exponential backoff.
+pub(crate) fn backoff_delay(attempt: u32, retry_after: Option<Duration>) -> Option<Duration> {
+    if attempt + 1 >= MAX_ATTEMPTS {
+        return None;
+    }
+    let delay = retry_after.unwrap_or_else(|| BASE_DELAY.saturating_mul(1 << attempt));
+    (delay <= MAX_DELAY).then_some(delay)
+}
+
+/// Parse a `Retry-After` value: either delay-seconds or an HTTP date.
+fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let at = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let secs = (at.timestamp() - chrono::Utc::now().timestamp()).max(0);
+    Some(Duration::from_secs(secs.unsigned_abs()))
+}
+
+/// Parse a remaining-quota value such as `42` or Docker Hub's `42;w=21600`.
+fn parse_remaining(value: &str) -> Option<u64> {
+    value.split(';').next()?.trim().parse().ok()
+}
+
+/// Parse an `X-RateLimit-Reset` Unix timestamp into a delay from now.
+fn parse_reset(value: &str) -> Option<Duration> {
+    let reset: i64 = value.trim().parse().ok()?;
+    let secs = (reset - chrono::Utc::now().timestamp()).max(0);
+    Some(Duration::from_secs(secs.unsigned_abs()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn headers_are_parsed() {
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("30"));
+        headers.insert("ratelimit-remaining", HeaderValue::from_static("0;w=21600"));
+        let limit = RateLimit::from_headers(&headers);
+        assert_eq!(limit.retry_after, Some(Duration::from_secs(30)));
+        assert_eq!(limit.remaining, Some(0));
+        assert!(limit.is_limited(StatusCode::TOO_MANY_REQUESTS));
+        assert!(limit.is_limited(StatusCode::FORBIDDEN));
+
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ratelimit-remaining", HeaderValue::from_static("12"));
+        let limit = RateLimit::from_headers(&headers);
+        assert_eq!(limit.remaining, Some(12));
+        assert_eq!(limit.retry_after, None);
+        // A 403 with quota left is an authorization failure, not a limit.
+        assert!(!limit.is_limited(StatusCode::FORBIDDEN));
+    }
+
+    #[test]
+    fn retry_after_accepts_past_http_dates() {
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon"), None);
+    }
+
+    #[test]
+    fn backoff_grows_and_gives_up() {
+        assert_eq!(backoff_delay(0, None), Some(Duration::from_secs(1)));
+        assert_eq!(backoff_delay(2, None), Some(Duration::from_secs(4)));
+        assert_eq!(backoff_delay(MAX_ATTEMPTS - 1, None), None);
+        assert_eq!(
+            backoff_delay(0, Some(Duration::from_secs(5))),
+            Some(Duration::from_secs(5))
+        );
+        assert_eq!(backoff_delay(0, Some(MAX_DELAY * 2)), None);
+    }
+
+    #[test]
+    fn oci_errors_are_classified() {
+        let limited = anyhow::Error::from(OciDistributionError::ServerError {
+            code: 429,
+            url: "https://ghcr.io/v2/".to_string(),
+            message: "slow down".to_string(),
+        });
+        assert!(is_rate_limited(&limited));
+        let denied = anyhow::Error::from(OciDistributionError::UnauthorizedError {
+            url: "https://ghcr.io/v2/".to_string(),
+        });
+        assert!(!is_rate_limited(&denied));
+    }
+}
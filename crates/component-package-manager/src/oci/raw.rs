@@ -1,6 +1,8 @@
 use oci_client::manifest::OciImageManifest;
 use rusqlite::Connection;
 
+use super::{SortBy, SortDirection};
+
 /// Metadata for a stored OCI image.
 ///
 /// This is an internal type constructed by joining `oci_manifest`, `oci_repository`,
@@ -26,6 +28,16 @@ pub struct RawImageEntry {
     pub manifest: OciImageManifest,
     /// Size of the image on disk in bytes
     pub size_on_disk: u64,
+    /// The `org.opencontainers.image.title` annotation, if present
+    pub title: Option<String>,
+    /// The `org.opencontainers.image.description` annotation, if present
+    pub description: Option<String>,
+    /// The `org.opencontainers.image.source` annotation (source repository URL), if present
+    pub source: Option<String>,
+    /// The `org.opencontainers.image.created` annotation (RFC 3339 build time), if present
+    pub created: Option<String>,
+    /// The WIT world the component targets (e.g., `wasi:http/proxy@0.2.0`), if known
+    pub world: Option<String>,
 }
 
 impl RawImageEntry {
@@ -46,19 +58,82 @@ impl RawImageEntry {
     /// Returns all stored images by joining `oci_manifest` with `oci_repository`
     /// and optionally `oci_tag`, ordered alphabetically by repository.
     pub(crate) fn get_all(conn: &Connection) -> anyhow::Result<Vec<RawImageEntry>> {
-        let mut stmt = conn.prepare(
+        Self::get_page(conn, SortBy::Name, SortDirection::Ascending, 0, None)
+    }
+
+    /// Returns one page of stored images ordered by `sort` in `direction`.
+    ///
+    /// A `limit` of `None` returns every image from `offset` onwards.
+    pub(crate) fn get_page(
+        conn: &Connection,
+        sort: SortBy,
+        direction: SortDirection,
+        offset: u32,
+        limit: Option<u32>,
+    ) -> anyhow::Result<Vec<RawImageEntry>> {
+        let mut stmt = conn.prepare_cached(&format!(
             "SELECT m.id, r.registry, r.repository, m.digest, m.raw_json, m.size_bytes,
                     (SELECT t.tag FROM oci_tag t
                      WHERE t.oci_repository_id = r.id AND t.manifest_digest = m.digest
-                     ORDER BY t.updated_at DESC LIMIT 1) as tag
+                     ORDER BY t.updated_at DESC LIMIT 1) as tag,
+                    m.oci_title, m.oci_description, m.oci_source, m.oci_created,
+                    (SELECT ct.declared_package || '/' || ct.declared_world
+                            || COALESCE('@' || ct.declared_version, '')
+                     FROM component_target ct
+                     JOIN wasm_component wc ON ct.wasm_component_id = wc.id
+                     WHERE wc.oci_manifest_id = m.id
+                     ORDER BY ct.id LIMIT 1) as world
              FROM oci_manifest m
              JOIN oci_repository r ON m.oci_repository_id = r.id
              WHERE m.raw_json IS NOT NULL
-             ORDER BY r.repository ASC, r.registry ASC",
-        )?;
+             ORDER BY {}
+             LIMIT ?1 OFFSET ?2",
+            sort.order_by(direction)
+        ))?;
+
+        // SQLite treats a negative LIMIT as "no limit".
+        let limit = limit.map_or(-1, i64::from);
+        Self::collect_rows(&mut stmt, (limit, offset))
+    }
+
+    /// Returns the stored images whose manifest, or one of whose layers,
+    /// has `digest`, ordered alphabetically by repository.
+    pub(crate) fn find_by_digest(
+        conn: &Connection,
+        digest: &str,
+    ) -> anyhow::Result<Vec<RawImageEntry>> {
+        let mut stmt = conn.prepare_cached(&format!(
+            "SELECT m.id, r.registry, r.repository, m.digest, m.raw_json, m.size_bytes,
+                    (SELECT t.tag FROM oci_tag t
+                     WHERE t.oci_repository_id = r.id AND t.manifest_digest = m.digest
+                     ORDER BY t.updated_at DESC LIMIT 1) as tag,
+                    m.oci_title, m.oci_description, m.oci_source, m.oci_created,
+                    (SELECT ct.declared_package || '/' || ct.declared_world
+                            || COALESCE('@' || ct.declared_version, '')
+                     FROM component_target ct
+                     JOIN wasm_component wc ON ct.wasm_component_id = wc.id
+                     WHERE wc.oci_manifest_id = m.id
+                     ORDER BY ct.id LIMIT 1) as world
+             FROM oci_manifest m
+             JOIN oci_repository r ON m.oci_repository_id = r.id
+             WHERE m.raw_json IS NOT NULL
+               AND (m.digest = ?1 OR EXISTS (
+                   SELECT 1 FROM oci_layer l WHERE l.oci_manifest_id = m.id AND l.digest = ?1
+               ))
+             ORDER BY {}",
+            SortBy::Name.order_by(SortDirection::Ascending)
+        ))?;
+        Self::collect_rows(&mut stmt, [digest])
+    }
 
+    /// Build entries from rows of the `SELECT` shared by [`Self::get_page`]
+    /// and [`Self::find_by_digest`], skipping unparseable manifests.
+    fn collect_rows(
+        stmt: &mut rusqlite::Statement<'_>,
+        params: impl rusqlite::Params,
+    ) -> anyhow::Result<Vec<RawImageEntry>> {
         let mut entries = Vec::new();
-        let rows = stmt.query_map([], |row| {
+        let rows = stmt.query_map(params, |row| {
             let raw_json: Option<String> = row.get(4)?;
             let size_bytes: Option<i64> = row.get(5)?;
             Ok((
@@ -69,11 +144,20 @@ impl RawImageEntry {
                 raw_json,
                 size_bytes,
                 row.get::<_, Option<String>>(6)?,
+                (
+                    row.get::<_, Option<String>>(7)?,
+                    row.get::<_, Option<String>>(8)?,
+                    row.get::<_, Option<String>>(9)?,
+                    row.get::<_, Option<String>>(10)?,
+                ),
+                row.get::<_, Option<String>>(11)?,
             ))
         })?;
 
         for row in rows {
-            let (id, registry, repository, digest, raw_json, size_bytes, tag) = row?;
+            let (id, registry, repository, digest, raw_json, size_bytes, tag, annotations, world) =
+                row?;
+            let (title, description, source, created) = annotations;
             let Some(json) = raw_json else {
                 continue;
             };
@@ -93,10 +177,25 @@ impl RawImageEntry {
                 ref_digest: Some(digest),
                 manifest,
                 size_on_disk: u64::try_from(size_bytes.unwrap_or(0)).unwrap_or(0),
+                title,
+                description,
+                source,
+                created,
+                world,
             });
         }
         Ok(entries)
     }
+
+    /// Returns the number of stored images listed by [`Self::get_all`].
+    pub(crate) fn count(conn: &Connection) -> anyhow::Result<u64> {
+        let count: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM oci_manifest WHERE raw_json IS NOT NULL",
+            [],
+            |row| row.get(0),
+        )?;
+        Ok(u64::try_from(count).unwrap_or(0))
+    }
 }
 
 #[cfg(test)]
@@ -129,6 +228,11 @@ mod tests {
             ref_digest: Some("sha256:abc".to_string()),
             manifest: OciImageManifest::default(),
             size_on_disk: 1024,
+            title: None,
+            description: None,
+            source: None,
+            created: None,
+            world: None,
         };
         assert_eq!(entry.reference(), "ghcr.io/user/repo:latest");
     }
@@ -144,6 +248,11 @@ mod tests {
             ref_digest: Some("sha256:abc123".to_string()),
             manifest: OciImageManifest::default(),
             size_on_disk: 512,
+            title: None,
+            description: None,
+            source: None,
+            created: None,
+            world: None,
         };
         assert_eq!(entry.reference(), "ghcr.io/user/repo@sha256:abc123");
     }
@@ -159,6 +268,11 @@ mod tests {
             ref_digest: None,
             manifest: OciImageManifest::default(),
             size_on_disk: 0,
+            title: None,
+            description: None,
+            source: None,
+            created: None,
+            world: None,
         };
         assert_eq!(entry.reference(), "ghcr.io/user/repo");
     }
@@ -197,6 +311,100 @@ mod tests {
         assert_eq!(entries[0].size_on_disk, 2048);
     }
 
+    #[test]
+    fn test_image_entry_get_all_includes_world() {
+        use crate::oci::{OciManifest, OciRepository};
+        use std::collections::HashMap;
+
+        let conn = setup_test_db();
+        let repo_id = OciRepository::upsert(&conn, "ghcr.io", "user/repo").unwrap();
+        let manifest_json = serde_json::to_string(&OciImageManifest::default()).unwrap();
+        let (manifest_id, _) = OciManifest::upsert(
+            &conn,
+            repo_id,
+            "sha256:valid",
+            Some("application/vnd.oci.image.manifest.v1+json"),
+            Some(&manifest_json),
+            Some(2048),
+            None,
+            None,
+            None,
+            &HashMap::new(),
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO wasm_component (id, oci_manifest_id) VALUES (1, ?1)",
+            [manifest_id],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO component_target
+                 (wasm_component_id, declared_package, declared_world, declared_version)
+             VALUES (1, 'wasi:http', 'proxy', '0.2.0')",
+            [],
+        )
+        .unwrap();
+
+        let entries = RawImageEntry::get_all(&conn).unwrap();
+        assert_eq!(
+            entries.first().and_then(|e| e.world.as_deref()),
+            Some("wasi:http/proxy@0.2.0")
+        );
+    }
+
+    #[test]
+    fn test_image_entry_get_all_includes_annotations() {
+        use crate::oci::{OciManifest, OciRepository};
+        use std::collections::HashMap;
+
+        let conn = setup_test_db();
+        let repo_id = OciRepository::upsert(&conn, "ghcr.io", "user/repo").unwrap();
+        let manifest_json = serde_json::to_string(&OciImageManifest::default()).unwrap();
+        let annotations = HashMap::from([
+            (
+                "org.opencontainers.image.title".to_string(),
+                "Repo".to_string(),
+            ),
+            (
+                "org.opencontainers.image.description".to_string(),
+                "An example component".to_string(),
+            ),
+            (
+                "org.opencontainers.image.source".to_string(),
+                "https://github.com/user/repo".to_string(),
+            ),
+            (
+                "org.opencontainers.image.created".to_string(),
+                "2024-01-01T00:00:00Z".to_string(),
+            ),
+        ]);
+        OciManifest::upsert(
+            &conn,
+            repo_id,
+            "sha256:annotated",
+            None,
+            Some(&manifest_json),
+            None,
+            None,
+            None,
+            None,
+            &annotations,
+        )
+        .unwrap();
+
+        let entries = RawImageEntry::get_all(&conn).unwrap();
+        let [entry] = entries.as_slice() else {
+            panic!("expected one entry, got {}", entries.len());
+        };
+        assert_eq!(entry.title.as_deref(), Some("Repo"));
+        assert_eq!(entry.description.as_deref(), Some("An example component"));
+        assert_eq!(
+            entry.source.as_deref(),
+            Some("https://github.com/user/repo")
+        );
+        assert_eq!(entry.created.as_deref(), Some("2024-01-01T00:00:00Z"));
+    }
+
     #[test]
     fn test_image_entry_get_all_skips_invalid_json() {
         use crate::oci::{OciManifest, OciRepository};
@@ -226,4 +434,104 @@ mod tests {
             "invalid JSON manifests should be skipped"
         );
     }
+
+    #[test]
+    fn test_image_entry_get_page_and_count() {
+        use crate::oci::{OciManifest, OciRepository};
+        use std::collections::HashMap;
+
+        let conn = setup_test_db();
+        let manifest_json = serde_json::to_string(&OciImageManifest::default()).unwrap();
+        for (name, size) in [("user/a", 20), ("user/b", 30), ("user/c", 10)] {
+            let repo_id = OciRepository::upsert(&conn, "ghcr.io", name).unwrap();
+            OciManifest::upsert(
+                &conn,
+                repo_id,
+                &format!("sha256:{name}"),
+                None,
+                Some(&manifest_json),
+                Some(size),
+                None,
+                None,
+                None,
+                &HashMap::new(),
+            )
+            .unwrap();
+        }
+        let repos = |entries: Vec<RawImageEntry>| -> Vec<String> {
+            entries.into_iter().map(|e| e.ref_repository).collect()
+        };
+
+        assert_eq!(RawImageEntry::count(&conn).unwrap(), 3);
+        let page =
+            RawImageEntry::get_page(&conn, SortBy::Name, SortDirection::Ascending, 1, Some(1));
+        assert_eq!(repos(page.unwrap()), ["user/b"]);
+        let page = RawImageEntry::get_page(&conn, SortBy::Name, SortDirection::Ascending, 1, None);
+        assert_eq!(repos(page.unwrap()), ["user/b", "user/c"]);
+
+        let by_size =
+            RawImageEntry::get_page(&conn, SortBy::Size, SortDirection::Descending, 0, None);
+        assert_eq!(repos(by_size.unwrap()), ["user/b", "user/a", "user/c"]);
+        let by_name =
+            RawImageEntry::get_page(&conn, SortBy::Name, SortDirection::Descending, 0, None);
+        assert_eq!(repos(by_name.unwrap()), ["user/c", "user/b", "user/a"]);
+    }
+
+    #[test]
+    fn test_image_entry_find_by_digest_matches_manifest_and_layer() {
+        use crate::oci::{OciLayer, OciManifest, OciRepository, OciTag};
+        use std::collections::HashMap;
+
+        let conn = setup_test_db();
+        let manifest_json = serde_json::to_string(&OciImageManifest::default()).unwrap();
+        // Two repositories ship the same wasm layer; a third manifest is
+        // only known from metadata and so isn't a stored image.
+        for (name, raw_json) in [
+            ("user/a", Some(manifest_json.as_str())),
+            ("user/b", Some(manifest_json.as_str())),
+            ("user/c", None),
+        ] {
+            let repo_id = OciRepository::upsert(&conn, "ghcr.io", name).unwrap();
+            let digest = format!("sha256:{name}");
+            let (manifest_id, _) = OciManifest::upsert(
+                &conn,
+                repo_id,
+                &digest,
+                None,
+                raw_json,
+                None,
+                None,
+                None,
+                None,
+                &HashMap::new(),
+            )
+            .unwrap();
+            OciLayer::insert(&conn, manifest_id, "sha256:wasm", None, None, 0).unwrap();
+            OciTag::upsert(&conn, repo_id, "1.0.0", &digest).unwrap();
+        }
+
+        let by_layer = RawImageEntry::find_by_digest(&conn, "sha256:wasm").unwrap();
+        let repos: Vec<_> = by_layer.iter().map(|e| e.ref_repository.as_str()).collect();
+        assert_eq!(repos, ["user/a", "user/b"]);
+        assert_eq!(
+            OciTag::references_to_digest(&conn, "sha256:wasm").unwrap(),
+            [
+                "ghcr.io/user/a:1.0.0",
+                "ghcr.io/user/b:1.0.0",
+                "ghcr.io/user/c:1.0.0"
+            ]
+        );
+
+        let by_manifest = RawImageEntry::find_by_digest(&conn, "sha256:user/b").unwrap();
+        assert_eq!(by_manifest.len(), 1);
+        assert_eq!(
+            OciTag::references_to_digest(&conn, "sha256:user/b").unwrap(),
+            ["ghcr.io/user/b:1.0.0"]
+        );
+        assert!(
+            RawImageEntry::find_by_digest(&conn, "sha256:none")
+                .unwrap()
+                .is_empty()
+        );
+    }
 }
@@ -0,0 +1,53 @@
+/// The field stored images are ordered by in
+/// [`Manager::list_all`](crate::manager::Manager::list_all).
+///
+/// # Example
+///
+/// ```
+/// use component_package_manager::oci::SortBy;
+///
+/// assert_eq!(SortBy::default(), SortBy::Name);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SortBy {
+    /// Repository path, then registry.
+    #[default]
+    Name,
+    /// Total size of the manifest and its layers.
+    Size,
+    /// Registry hostname, then repository path.
+    Registry,
+    /// When the image was first stored locally.
+    PulledAt,
+}
+
+/// The direction stored images are ordered in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SortDirection {
+    /// Smallest, oldest, or alphabetically first entries first.
+    #[default]
+    Ascending,
+    /// Largest, newest, or alphabetically last entries first.
+    Descending,
+}
+
+impl SortBy {
+    /// The SQL `ORDER BY` terms for this field over `oci_manifest m` joined
+    /// with `oci_repository r`.
+    ///
+    /// Every ordering ends in a unique column so pages never overlap.
+    pub(crate) fn order_by(self, direction: SortDirection) -> String {
+        let dir = match direction {
+            SortDirection::Ascending => "ASC",
+            SortDirection::Descending => "DESC",
+        };
+        match self {
+            Self::Name => format!("r.repository {dir}, r.registry {dir}, m.id {dir}"),
+            Self::Registry => format!("r.registry {dir}, r.repository {dir}, m.id {dir}"),
+            Self::Size => format!(
+                "COALESCE(m.size_bytes, 0) {dir}, r.repository ASC, r.registry ASC, m.id ASC"
+            ),
+            Self::PulledAt => format!("m.created_at {dir}, m.id {dir}"),
+        }
+    }
+}
@@ -0,0 +1,181 @@
+//! The HTTP layer of the registry client.
+//!
+//! [`Client`](super::Client) decides which credentials to try, backs off when
+//! rate limited, and pages through tag lists; a [`Transport`] makes the
+//! individual OCI distribution requests. The real transport is `oci-wasm`'s
+//! [`WasmClient`]. With the `test-util` feature, the in-process
+//! [`FakeRegistry`](super::FakeRegistry) can stand in for it.
+
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::pin::Pin;
+
+use oci_client::Reference;
+use oci_client::client::{Config, ImageData, ImageLayer, PushResponse, SizedStream, TagResponse};
+use oci_client::manifest::{OciDescriptor, OciImageIndex, OciImageManifest, OciManifest};
+use oci_client::secrets::RegistryAuth;
+use oci_wasm::{ToConfig, WasmClient};
+
+/// The future returned by [`Transport`] methods.
+pub type TransportFuture<'a, T> = Pin<Box<dyn Future<Output = anyhow::Result<T>> + Send + 'a>>;
+
+/// Makes OCI distribution requests against a registry.
+///
+/// Every request carries the credentials to use; implementations must not
+/// fall back to other credentials on their own.
+pub trait Transport: Send + Sync {
+    /// Pull a wasm artifact: its manifest, config, and single layer.
+    fn pull<'a>(
+        &'a self,
+        reference: &'a Reference,
+        auth: &'a RegistryAuth,
+    ) -> TransportFuture<'a, ImageData>;
+
+    /// Push a wasm artifact with a single layer, tagging it if `reference`
+    /// has a tag.
+    fn push<'a>(
+        &'a self,
+        reference: &'a Reference,
+        auth: &'a RegistryAuth,
+        layer: ImageLayer,
+        config: Config,
+        annotations: Option<BTreeMap<String, String>>,
+    ) -> TransportFuture<'a, PushResponse>;
+
+    /// Fetch a wasm artifact's manifest, returned with its digest.
+    fn pull_manifest_and_config<'a>(
+        &'a self,
+        reference: &'a Reference,
+        auth: &'a RegistryAuth,
+    ) -> TransportFuture<'a, (OciImageManifest, String)>;
+
+    /// Fetch the manifest or image index a reference points at, returned
+    /// with its digest.
+    fn pull_manifest<'a>(
+        &'a self,
+        reference: &'a Reference,
+        auth: &'a RegistryAuth,
+    ) -> TransportFuture<'a, (OciManifest, String)>;
+
+    /// Stream a single blob from the repository.
+    fn pull_blob_stream<'a>(
+        &'a self,
+        reference: &'a Reference,
+        auth: &'a RegistryAuth,
+        layer: &'a OciDescriptor,
+    ) -> TransportFuture<'a, SizedStream>;
+
+    /// Fetch one page of the repository's tags: at most `n`, starting
+    /// after `last`.
+    fn list_tags<'a>(
+        &'a self,
+        reference: &'a Reference,
+        auth: &'a RegistryAuth,
+        n: Option<usize>,
+        last: Option<&'a str>,
+    ) -> TransportFuture<'a, TagResponse>;
+
+    /// Fetch the referrers of a digest-pinned reference.
+    fn pull_referrers<'a>(
+        &'a self,
+        reference: &'a Reference,
+        auth: &'a RegistryAuth,
+    ) -> TransportFuture<'a, OciImageIndex>;
+}
+
+impl Transport for WasmClient {
+    fn pull<'a>(
+        &'a self,
+        reference: &'a Reference,
+        auth: &'a RegistryAuth,
+    ) -> TransportFuture<'a, ImageData> {
+        Box::pin(WasmClient::pull(self, reference, auth))
+    }
+
+    fn push<'a>(
+        &'a self,
+        reference: &'a Reference,
+        auth: &'a RegistryAuth,
+        layer: ImageLayer,
+        config: Config,
+        annotations: Option<BTreeMap<String, String>>,
+    ) -> TransportFuture<'a, PushResponse> {
+        Box::pin(WasmClient::push(
+            self,
+            reference,
+            auth,
+            layer,
+            RawConfig(config),
+            annotations,
+        ))
+    }
+
+    fn pull_manifest_and_config<'a>(
+        &'a self,
+        reference: &'a Reference,
+        auth: &'a RegistryAuth,
+    ) -> TransportFuture<'a, (OciImageManifest, String)> {
+        Box::pin(async move {
+            let (manifest, _config, digest) =
+                WasmClient::pull_manifest_and_config(self, reference, auth).await?;
+            Ok((manifest, digest))
+        })
+    }
+
+    fn pull_manifest<'a>(
+        &'a self,
+        reference: &'a Reference,
+        auth: &'a RegistryAuth,
+    ) -> TransportFuture<'a, (OciManifest, String)> {
+        Box::pin(async move { Ok(oci_client::Client::pull_manifest(self, reference, auth).await?) })
+    }
+
+    fn pull_blob_stream<'a>(
+        &'a self,
+        reference: &'a Reference,
+        auth: &'a RegistryAuth,
+        layer: &'a OciDescriptor,
+    ) -> TransportFuture<'a, SizedStream> {
+        Box::pin(async move {
+            // Blob requests don't take credentials, so hand them over first.
+            self.store_auth_if_needed(reference.resolve_registry(), auth)
+                .await;
+            Ok(oci_client::Client::pull_blob_stream(self, reference, layer).await?)
+        })
+    }
+
+    fn list_tags<'a>(
+        &'a self,
+        reference: &'a Reference,
+        auth: &'a RegistryAuth,
+        n: Option<usize>,
+        last: Option<&'a str>,
+    ) -> TransportFuture<'a, TagResponse> {
+        Box::pin(
+            async move { Ok(oci_client::Client::list_tags(self, reference, auth, n, last).await?) },
+        )
+    }
+
+    fn pull_referrers<'a>(
+        &'a self,
+        reference: &'a Reference,
+        auth: &'a RegistryAuth,
+    ) -> TransportFuture<'a, OciImageIndex> {
+        Box::pin(async move {
+            // Referrer requests don't take credentials, so hand them over first.
+            self.store_auth_if_needed(reference.resolve_registry(), auth)
+                .await;
+            Ok(oci_client::Client::pull_referrers(self, reference, None).await?)
+        })
+    }
+}
+
+/// Passes an already-built [`Config`] to [`WasmClient::push`], which takes
+/// anything that converts to one.
+struct RawConfig(Config);
+
+impl ToConfig for RawConfig {
+    fn to_config(&self) -> anyhow::Result<Config> {
+        Ok(self.0.clone())
+    }
+}
@@ -0,0 +1,60 @@
+//! Registry credentials persisted in the operating system's keyring.
+//!
+//! Uses the Secret Service on Linux, the Keychain on macOS, and the
+//! Credential Manager on Windows. Each registry gets one entry whose secret
+//! holds both the username and the password.
+
+use anyhow::{Context, Result};
+use keyring::Entry;
+use serde::{Deserialize, Serialize};
+
+/// The keyring service that registry entries are stored under.
+const SERVICE: &str = "wasm";
+
+/// The secret stored for a registry.
+#[derive(Serialize, Deserialize)]
+struct StoredCredentials {
+    username: String,
+    password: String,
+}
+
+/// Look up the credentials stored for `registry`.
+///
+/// Returns `None` if the keyring has no entry for the registry.
+pub(crate) fn get(registry: &str) -> Result<Option<(String, String)>> {
+    let secret = match entry(registry)?.get_password() {
+        Ok(secret) => secret,
+        Err(keyring::Error::NoEntry) => return Ok(None),
+        Err(e) => return Err(e).context("Failed to read credentials from the keyring"),
+    };
+    let stored: StoredCredentials = serde_json::from_str(&secret)
+        .with_context(|| format!("Malformed keyring entry for {registry}"))?;
+    Ok(Some((stored.username, stored.password)))
+}
+
+/// Store credentials for `registry`, replacing any existing entry.
+pub(crate) fn set(registry: &str, username: &str, password: &str) -> Result<()> {
+    let secret = serde_json::to_string(&StoredCredentials {
+        username: username.to_string(),
+        password: password.to_string(),
+    })?;
+    entry(registry)?
+        .set_password(&secret)
+        .context("Failed to write credentials to the keyring")
+}
+
+/// Remove the credentials stored for `registry`.
+///
+/// Returns `false` if the keyring had no entry for the registry.
+pub(crate) fn delete(registry: &str) -> Result<bool> {
+    match entry(registry)?.delete_credential() {
+        Ok(()) => Ok(true),
+        Err(keyring::Error::NoEntry) => Ok(false),
+        Err(e) => Err(e).context("Failed to remove credentials from the keyring"),
+    }
+}
+
+fn entry(registry: &str) -> Result<Entry> {
+    Entry::new(SERVICE, registry)
+        .with_context(|| format!("Invalid keyring entry for registry {registry}"))
+}
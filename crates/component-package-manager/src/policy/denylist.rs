@@ -0,0 +1,119 @@
+//! Feeds of known-bad artifact digests.
+
+use std::collections::BTreeMap;
+
+use anyhow::{Result, bail};
+
+/// Digests that must not be used, gathered from one or more feeds.
+///
+/// A feed is plain text with one digest per line, optionally followed by a
+/// reason. Blank lines and lines starting with `#` are ignored:
+///
+/// ```text
+/// # Builds published with a leaked signing key.
+/// sha256:4f2a...e1 example:hello 1.2.0 was built from a compromised runner
+/// ```
+///
+/// # Example
+///
+/// ```rust
+/// use component_package_manager::policy::Denylist;
+///
+/// let mut denylist = Denylist::default();
+/// denylist
+///     .extend_from_feed("# bad builds\nsha256:0f0f compromised runner\nsha256:1e1e\n")
+///     .unwrap();
+///
+/// assert_eq!(denylist.len(), 2);
+/// assert_eq!(denylist.reason("sha256:0f0f"), Some(Some("compromised runner")));
+/// assert_eq!(denylist.reason("sha256:1e1e"), Some(None));
+/// assert_eq!(denylist.reason("sha256:2d2d"), None);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Denylist {
+    digests: BTreeMap<String, Option<String>>,
+    skipped_feeds: Vec<String>,
+}
+
+impl Denylist {
+    /// Add the digests in `feed`. A digest listed twice keeps the first
+    /// reason given for it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error naming the line if an entry isn't an
+    /// `algorithm:encoded` digest.
+    pub fn extend_from_feed(&mut self, feed: &str) -> Result<()> {
+        for (index, line) in feed.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (digest, reason) = match line.split_once(char::is_whitespace) {
+                Some((digest, reason)) => (digest, Some(reason.trim())),
+                None => (line, None),
+            };
+            if !digest
+                .split_once(':')
+                .is_some_and(|(algorithm, encoded)| !algorithm.is_empty() && !encoded.is_empty())
+            {
+                bail!(
+                    "line {}: expected a digest such as `sha256:<hex>`, found '{digest}'",
+                    index + 1
+                );
+            }
+            let reason = reason.filter(|r| !r.is_empty()).map(str::to_string);
+            self.digests.entry(digest.to_string()).or_insert(reason);
+        }
+        Ok(())
+    }
+
+    /// Record a feed that couldn't be consulted, such as a URL while offline.
+    pub fn skip_feed(&mut self, feed: impl Into<String>) {
+        self.skipped_feeds.push(feed.into());
+    }
+
+    /// The feeds that were skipped rather than loaded.
+    #[must_use]
+    pub fn skipped_feeds(&self) -> &[String] {
+        &self.skipped_feeds
+    }
+
+    /// Whether `digest` is denied, and if so the reason given for it, if any.
+    #[must_use]
+    pub fn reason(&self, digest: &str) -> Option<Option<&str>> {
+        self.digests.get(digest).map(Option::as_deref)
+    }
+
+    /// The number of denied digests.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.digests.len()
+    }
+
+    /// Whether no digests are denied.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.digests.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_reason_wins_and_bad_lines_are_reported() {
+        let mut denylist = Denylist::default();
+        denylist
+            .extend_from_feed("sha256:aa first\n\n  sha256:aa second  \n")
+            .unwrap();
+        assert_eq!(denylist.reason("sha256:aa"), Some(Some("first")));
+        assert_eq!(denylist.len(), 1);
+
+        let err = denylist
+            .extend_from_feed("# ok\nnot-a-digest\n")
+            .unwrap_err();
+        assert!(err.to_string().contains("line 2"), "{err}");
+    }
+}
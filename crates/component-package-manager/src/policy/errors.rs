@@ -0,0 +1,182 @@
+//! Error types for content trust policy violations.
+
+use miette::Diagnostic;
+
+/// A pull that the content trust policy rejected.
+///
+/// Each variant carries a stable [diagnostic error code][miette::Diagnostic::code]
+/// that uniquely identifies the violated rule.
+///
+/// # Example
+///
+/// ```rust
+/// use miette::Diagnostic;
+/// use component_package_manager::policy::PolicyViolation;
+///
+/// let err = PolicyViolation::RegistryDenied {
+///     reference: "docker.io/library/hello:1".into(),
+///     registry: "docker.io".into(),
+///     pattern: "docker.io".into(),
+/// };
+/// assert_eq!(
+///     err.code().expect("should have a code").to_string(),
+///     "component::policy::registry_denied",
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Diagnostic)]
+#[must_use]
+pub enum PolicyViolation {
+    /// The registry matches none of the `allowed-registries` patterns.
+    #[diagnostic(
+        code(component::policy::registry_not_allowed),
+        help("add '{registry}' to `allowed-registries` in policy.toml to pull from it")
+    )]
+    RegistryNotAllowed {
+        /// The reference that was pulled.
+        reference: String,
+        /// The registry the reference points at.
+        registry: String,
+    },
+
+    /// The registry matches one of the `denied-registries` patterns.
+    #[diagnostic(
+        code(component::policy::registry_denied),
+        help("remove '{pattern}' from `denied-registries` in policy.toml to pull from it")
+    )]
+    RegistryDenied {
+        /// The reference that was pulled.
+        reference: String,
+        /// The registry the reference points at.
+        registry: String,
+        /// The `denied-registries` pattern that matched.
+        pattern: String,
+    },
+
+    /// The artifact is in a namespace that requires a signature, and the
+    /// registry has none for it.
+    #[diagnostic(
+        code(component::policy::signature_required),
+        help(
+            "sign the artifact with `component registry sign` or `cosign sign`, or pull a \
+             signed version"
+        )
+    )]
+    SignatureRequired {
+        /// The reference that was pulled.
+        reference: String,
+        /// The `require-signature` namespace that matched.
+        namespace: String,
+    },
+
+    /// The artifact's layers are larger than `max-artifact-size`.
+    #[diagnostic(
+        code(component::policy::artifact_too_large),
+        help("raise `max-artifact-size` in policy.toml to pull artifacts this large")
+    )]
+    ArtifactTooLarge {
+        /// The reference that was pulled.
+        reference: String,
+        /// The total size of the artifact's layers, in bytes.
+        size: u64,
+        /// The configured limit, in bytes.
+        limit: u64,
+    },
+}
+
+impl std::fmt::Display for PolicyViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PolicyViolation::RegistryNotAllowed {
+                reference,
+                registry,
+            } => {
+                write!(
+                    f,
+                    "policy does not allow pulling '{reference}': registry '{registry}' is not allowed"
+                )
+            }
+            PolicyViolation::RegistryDenied {
+                reference,
+                registry,
+                ..
+            } => {
+                write!(
+                    f,
+                    "policy does not allow pulling '{reference}': registry '{registry}' is denied"
+                )
+            }
+            PolicyViolation::SignatureRequired {
+                reference,
+                namespace,
+            } => {
+                write!(
+                    f,
+                    "policy does not allow pulling '{reference}': artifacts in '{namespace}' must be signed"
+                )
+            }
+            PolicyViolation::ArtifactTooLarge {
+                reference,
+                size,
+                limit,
+            } => {
+                write!(
+                    f,
+                    "policy does not allow pulling '{reference}': {} exceeds the {} limit",
+                    crate::format_size(*size),
+                    crate::format_size(*limit)
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for PolicyViolation {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_all_variants_have_error_codes() {
+        let reference = "ghcr.io/example/app:1".to_string();
+        let cases = [
+            (
+                PolicyViolation::RegistryNotAllowed {
+                    reference: reference.clone(),
+                    registry: "ghcr.io".into(),
+                },
+                "component::policy::registry_not_allowed",
+            ),
+            (
+                PolicyViolation::RegistryDenied {
+                    reference: reference.clone(),
+                    registry: "ghcr.io".into(),
+                    pattern: "ghcr.io".into(),
+                },
+                "component::policy::registry_denied",
+            ),
+            (
+                PolicyViolation::SignatureRequired {
+                    reference: reference.clone(),
+                    namespace: "ghcr.io/example".into(),
+                },
+                "component::policy::signature_required",
+            ),
+            (
+                PolicyViolation::ArtifactTooLarge {
+                    reference,
+                    size: 2048,
+                    limit: 1024,
+                },
+                "component::policy::artifact_too_large",
+            ),
+        ];
+        for (err, code) in cases {
+            assert_eq!(
+                err.code().expect("must have a diagnostic code").to_string(),
+                code
+            );
+            assert!(err.help().is_some(), "{code} must have a help message");
+        }
+    }
+}
@@ -0,0 +1,557 @@
+//! Content trust policy for pulls.
+//!
+//! A policy restricts which artifacts [`Manager::pull`](crate::manager::Manager::pull)
+//! accepts. It is loaded from two locations and combined, so that a rule from
+//! either file applies:
+//!
+//! - **Global**: `$XDG_CONFIG_HOME/wasm/policy.toml`
+//! - **Local**: `.config/wasm/policy.toml` (relative to the current working directory)
+//!
+//! ```toml
+//! # Only pull from these registries (glob patterns; empty allows all).
+//! allowed-registries = ["ghcr.io", "*.azurecr.io"]
+//! # Never pull from these registries, even if allowed above.
+//! denied-registries = ["docker.io"]
+//! # Artifacts under these namespaces must have a signature in the registry.
+//! require-signature = ["ghcr.io/webassembly"]
+//! # Reject artifacts whose layers add up to more than this many bytes.
+//! max-artifact-size = 104857600
+//! # Artifacts under these namespaces must have an attestation.
+//! require-attestation = ["ghcr.io/webassembly"]
+//! # Public keys that signatures under a namespace are verified against.
+//! trusted-keys = [{ namespace = "ghcr.io/webassembly", key = "keys/webassembly.pub" }]
+//! # Feeds of known-bad digests that `component audit` checks against: URLs,
+//! # or files relative to this policy file.
+//! denylist = ["https://example.com/wasm-denylist.txt", "denied.txt"]
+//! ```
+//!
+//! On pull, signatures are checked for presence only: a cosign, Notation, or
+//! Sigstore signature must be attached to the artifact's digest, either
+//! through the OCI Referrers API or as a cosign `sha256-<hex>.sig` tag.
+//! [`Manager::verify`](crate::manager::Manager::verify) goes further and
+//! checks cosign signatures and attestations against `trusted-keys`.
+
+mod denylist;
+mod errors;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use oci_client::Reference;
+use serde::{Deserialize, Serialize};
+
+use crate::oci::glob_match;
+use crate::xdg_config_home;
+
+pub use denylist::Denylist;
+pub use errors::PolicyViolation;
+
+/// Rules that pulled artifacts must satisfy.
+///
+/// The default policy allows everything.
+///
+/// # Example
+///
+/// ```rust
+/// use component_package_manager::policy::Policy;
+///
+/// let policy: Policy = toml::from_str(r#"
+///     denied-registries = ["docker.io"]
+///     max-artifact-size = 1024
+/// "#).unwrap();
+///
+/// let reference = "docker.io/library/hello:1".parse().unwrap();
+/// assert!(policy.check_registry(&reference).is_err());
+///
+/// let reference = "ghcr.io/example/hello:1".parse().unwrap();
+/// assert!(policy.check_registry(&reference).is_ok());
+/// assert!(policy.check_size(&reference, 4096).is_err());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct Policy {
+    /// Registries that may be pulled from, as glob patterns such as
+    /// `*.azurecr.io`. When empty, every registry is allowed.
+    pub allowed_registries: Vec<String>,
+    /// Registries that may never be pulled from, as glob patterns. Takes
+    /// precedence over `allowed-registries`.
+    pub denied_registries: Vec<String>,
+    /// Namespaces, such as `ghcr.io/webassembly`, whose artifacts must be
+    /// signed. A namespace covers every repository below it; glob patterns
+    /// are matched against the whole `registry/repository`.
+    pub require_signature: Vec<String>,
+    /// The largest total layer size, in bytes, that may be pulled.
+    pub max_artifact_size: Option<u64>,
+    /// Namespaces whose artifacts must have an attestation, such as SLSA
+    /// provenance. Matched like `require-signature`, and only checked by
+    /// `registry verify`.
+    pub require_attestation: Vec<String>,
+    /// Public keys that signatures and attestations under a namespace must
+    /// verify against.
+    pub trusted_keys: Vec<TrustedKey>,
+    /// Feeds of known-bad digests, as `http(s)://` URLs or files. Only
+    /// checked by `audit`; see [`Denylist`] for the format.
+    pub denylist: Vec<String>,
+}
+
+/// A public key trusted to sign artifacts under a namespace.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct TrustedKey {
+    /// The namespace the key signs for, matched like `require-signature`.
+    pub namespace: String,
+    /// A PEM `PUBLIC KEY` file, such as `cosign.pub`. A relative path is
+    /// relative to the policy file it comes from.
+    pub key: PathBuf,
+}
+
+impl Policy {
+    /// Load the policy from the default locations, combining the global and
+    /// local policy files.
+    ///
+    /// Missing files are treated as empty policies.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a policy file exists but cannot be read or parsed.
+    pub fn load() -> Result<Self> {
+        let global = match Self::policy_path() {
+            Some(path) => {
+                Self::load_from_path(&path).with_context(|| "Failed to load global policy")?
+            }
+            None => Self::default(),
+        };
+        let local = Self::load_from_path(&Self::local_policy_path())
+            .with_context(|| "Failed to load local policy (.config/wasm/policy.toml)")?;
+        Ok(global.combine(local))
+    }
+
+    /// Load a policy from a specific file path.
+    ///
+    /// If the file doesn't exist, returns the default policy.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file exists but cannot be read or parsed.
+    pub fn load_from_path(policy_path: &Path) -> Result<Self> {
+        if !policy_path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(policy_path)
+            .with_context(|| format!("Failed to read policy file: {}", policy_path.display()))?;
+
+        let mut policy: Self = toml::from_str(&content)
+            .with_context(|| format!("Failed to parse policy file: {}", policy_path.display()))?;
+        if let Some(dir) = policy_path.parent() {
+            for trusted in &mut policy.trusted_keys {
+                trusted.key = dir.join(&trusted.key);
+            }
+            for feed in &mut policy.denylist {
+                if !is_url(feed) {
+                    *feed = dir.join(&*feed).display().to_string();
+                }
+            }
+        }
+        Ok(policy)
+    }
+
+    /// Returns the path to the global policy file.
+    ///
+    /// Returns `None` when no suitable config directory can be determined.
+    #[must_use]
+    pub fn policy_path() -> Option<PathBuf> {
+        Self::policy_path_from(None)
+    }
+
+    /// Returns the path to the policy file from a specified config directory.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use component_package_manager::policy::Policy;
+    /// use std::path::PathBuf;
+    ///
+    /// let path = Policy::policy_path_from(Some(PathBuf::from("/tmp/cfg")));
+    /// assert_eq!(path, Some(PathBuf::from("/tmp/cfg/wasm/policy.toml")));
+    /// ```
+    #[must_use]
+    pub fn policy_path_from(config_dir: Option<PathBuf>) -> Option<PathBuf> {
+        let base = config_dir.or_else(xdg_config_home)?;
+        Some(base.join("wasm").join("policy.toml"))
+    }
+
+    /// Returns the path to the local policy file, relative to the current
+    /// working directory.
+    #[must_use]
+    pub fn local_policy_path() -> PathBuf {
+        PathBuf::from(".config").join("wasm").join("policy.toml")
+    }
+
+    /// Combine two policies so that the rules of both apply.
+    ///
+    /// Allowed registries must be allowed by both (a side without an allow
+    /// list allows everything), denied registries, signature and attestation
+    /// namespaces, trusted keys, and denylist feeds are joined, and the
+    /// smaller size limit wins.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use component_package_manager::policy::Policy;
+    ///
+    /// let global = Policy {
+    ///     allowed_registries: vec!["ghcr.io".into(), "docker.io".into()],
+    ///     max_artifact_size: Some(1024),
+    ///     ..Policy::default()
+    /// };
+    /// let local = Policy {
+    ///     allowed_registries: vec!["ghcr.io".into()],
+    ///     max_artifact_size: Some(4096),
+    ///     ..Policy::default()
+    /// };
+    ///
+    /// let policy = global.combine(local);
+    /// assert!(policy.check_registry(&"docker.io/a/b:1".parse().unwrap()).is_err());
+    /// assert_eq!(policy.max_artifact_size, Some(1024));
+    /// ```
+    #[must_use]
+    pub fn combine(mut self, other: Self) -> Self {
+        if self.allowed_registries.is_empty() {
+            self.allowed_registries = other.allowed_registries;
+        } else if !other.allowed_registries.is_empty() {
+            // Keep the patterns of each side that the other side allows.
+            let ours: Vec<String> = self
+                .allowed_registries
+                .iter()
+                .filter(|pattern| {
+                    other
+                        .allowed_registries
+                        .iter()
+                        .any(|other| glob_match(other, pattern))
+                })
+                .cloned()
+                .collect();
+            let theirs = other.allowed_registries.into_iter().filter(|pattern| {
+                self.allowed_registries
+                    .iter()
+                    .any(|ours| glob_match(ours, pattern))
+            });
+            let mut allowed = ours;
+            for pattern in theirs {
+                if !allowed.contains(&pattern) {
+                    allowed.push(pattern);
+                }
+            }
+            // Nothing in common: deny every registry.
+            if allowed.is_empty() {
+                self.denied_registries.push("*".to_string());
+            }
+            self.allowed_registries = allowed;
+        }
+        for pattern in other.denied_registries {
+            if !self.denied_registries.contains(&pattern) {
+                self.denied_registries.push(pattern);
+            }
+        }
+        for namespace in other.require_signature {
+            if !self.require_signature.contains(&namespace) {
+                self.require_signature.push(namespace);
+            }
+        }
+        for namespace in other.require_attestation {
+            if !self.require_attestation.contains(&namespace) {
+                self.require_attestation.push(namespace);
+            }
+        }
+        for trusted in other.trusted_keys {
+            if !self.trusted_keys.contains(&trusted) {
+                self.trusted_keys.push(trusted);
+            }
+        }
+        for feed in other.denylist {
+            if !self.denylist.contains(&feed) {
+                self.denylist.push(feed);
+            }
+        }
+        self.max_artifact_size = match (self.max_artifact_size, other.max_artifact_size) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        self
+    }
+
+    /// Check that `reference` points at a registry the policy allows.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PolicyViolation::RegistryDenied`] or
+    /// [`PolicyViolation::RegistryNotAllowed`] otherwise.
+    pub fn check_registry(&self, reference: &Reference) -> Result<(), PolicyViolation> {
+        let registry = reference.registry();
+        if let Some(pattern) = self
+            .denied_registries
+            .iter()
+            .find(|pattern| glob_match(pattern, registry))
+        {
+            return Err(PolicyViolation::RegistryDenied {
+                reference: reference.whole(),
+                registry: registry.to_string(),
+                pattern: pattern.clone(),
+            });
+        }
+        if !self.allowed_registries.is_empty()
+            && !self
+                .allowed_registries
+                .iter()
+                .any(|pattern| glob_match(pattern, registry))
+        {
+            return Err(PolicyViolation::RegistryNotAllowed {
+                reference: reference.whole(),
+                registry: registry.to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Check an artifact whose layers add up to `size` bytes against
+    /// `max-artifact-size`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PolicyViolation::ArtifactTooLarge`] if it is too large.
+    pub fn check_size(&self, reference: &Reference, size: u64) -> Result<(), PolicyViolation> {
+        match self.max_artifact_size {
+            Some(limit) if size > limit => Err(PolicyViolation::ArtifactTooLarge {
+                reference: reference.whole(),
+                size,
+                limit,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the `require-signature` namespace that covers `reference`,
+    /// if any.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use component_package_manager::policy::Policy;
+    ///
+    /// let policy = Policy {
+    ///     require_signature: vec!["ghcr.io/webassembly".into()],
+    ///     ..Policy::default()
+    /// };
+    /// let signed = "ghcr.io/webassembly/wasi/http:0.2.0".parse().unwrap();
+    /// let unsigned = "ghcr.io/webassembly-fork/http:0.2.0".parse().unwrap();
+    /// assert_eq!(policy.signature_namespace(&signed), Some("ghcr.io/webassembly"));
+    /// assert_eq!(policy.signature_namespace(&unsigned), None);
+    /// ```
+    #[must_use]
+    pub fn signature_namespace(&self, reference: &Reference) -> Option<&str> {
+        self.require_signature
+            .iter()
+            .find(|namespace| covers(namespace, reference))
+            .map(String::as_str)
+    }
+
+    /// Returns the `require-attestation` namespace that covers `reference`,
+    /// if any.
+    #[must_use]
+    pub fn attestation_namespace(&self, reference: &Reference) -> Option<&str> {
+        self.require_attestation
+            .iter()
+            .find(|namespace| covers(namespace, reference))
+            .map(String::as_str)
+    }
+
+    /// Returns the trusted keys whose namespace covers `reference`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use component_package_manager::policy::{Policy, TrustedKey};
+    ///
+    /// let policy = Policy {
+    ///     trusted_keys: vec![TrustedKey {
+    ///         namespace: "ghcr.io/webassembly".into(),
+    ///         key: "webassembly.pub".into(),
+    ///     }],
+    ///     ..Policy::default()
+    /// };
+    /// let reference = "ghcr.io/webassembly/wasi/http:0.2.0".parse().unwrap();
+    /// assert_eq!(policy.trusted_keys_for(&reference).len(), 1);
+    /// let reference = "ghcr.io/example/http:0.2.0".parse().unwrap();
+    /// assert!(policy.trusted_keys_for(&reference).is_empty());
+    /// ```
+    #[must_use]
+    pub fn trusted_keys_for(&self, reference: &Reference) -> Vec<&TrustedKey> {
+        self.trusted_keys
+            .iter()
+            .filter(|trusted| covers(&trusted.namespace, reference))
+            .collect()
+    }
+}
+
+/// Whether a denylist feed is fetched over HTTP rather than read from disk.
+pub(crate) fn is_url(feed: &str) -> bool {
+    feed.starts_with("https://") || feed.starts_with("http://")
+}
+
+/// Whether `namespace` covers the repository `reference` points at: as a
+/// glob over the whole `registry/repository`, or as a path prefix of it.
+fn covers(namespace: &str, reference: &Reference) -> bool {
+    let name = format!("{}/{}", reference.registry(), reference.repository());
+    let namespace = namespace.trim_end_matches('/');
+    glob_match(namespace, &name)
+        || name
+            .strip_prefix(namespace)
+            .is_some_and(|rest| rest.starts_with('/'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reference(s: &str) -> Reference {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn default_policy_allows_everything() {
+        let policy = Policy::default();
+        let r = reference("docker.io/library/hello:1");
+        assert!(policy.check_registry(&r).is_ok());
+        assert!(policy.check_size(&r, u64::MAX).is_ok());
+        assert_eq!(policy.signature_namespace(&r), None);
+    }
+
+    #[test]
+    fn denied_registries_take_precedence() {
+        let policy = Policy {
+            allowed_registries: vec!["*.io".into()],
+            denied_registries: vec!["docker.io".into()],
+            ..Policy::default()
+        };
+        assert!(policy.check_registry(&reference("ghcr.io/a/b:1")).is_ok());
+        assert!(matches!(
+            policy.check_registry(&reference("docker.io/a/b:1")),
+            Err(PolicyViolation::RegistryDenied { .. })
+        ));
+        assert!(matches!(
+            policy.check_registry(&reference("localhost:5000/a/b:1")),
+            Err(PolicyViolation::RegistryNotAllowed { .. })
+        ));
+    }
+
+    #[test]
+    fn signature_namespaces_match_whole_segments() {
+        let policy = Policy {
+            require_signature: vec!["ghcr.io/acme".into(), "*.azurecr.io/*".into()],
+            ..Policy::default()
+        };
+        assert!(
+            policy
+                .signature_namespace(&reference("ghcr.io/acme/app:1"))
+                .is_some()
+        );
+        assert!(
+            policy
+                .signature_namespace(&reference("ghcr.io/acme-corp/app:1"))
+                .is_none()
+        );
+        assert!(
+            policy
+                .signature_namespace(&reference("team.azurecr.io/app:1"))
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn combine_applies_both_policies() {
+        let global = Policy {
+            allowed_registries: vec!["ghcr.io".into()],
+            require_signature: vec!["ghcr.io/a".into()],
+            ..Policy::default()
+        };
+        let local = Policy {
+            allowed_registries: vec!["docker.io".into()],
+            require_signature: vec!["ghcr.io/b".into()],
+            max_artifact_size: Some(10),
+            ..Policy::default()
+        };
+        let policy = global.combine(local);
+        assert!(policy.check_registry(&reference("ghcr.io/a/b:1")).is_err());
+        assert!(
+            policy
+                .check_registry(&reference("docker.io/a/b:1"))
+                .is_err()
+        );
+        assert_eq!(policy.require_signature, ["ghcr.io/a", "ghcr.io/b"]);
+        assert_eq!(policy.max_artifact_size, Some(10));
+    }
+
+    #[test]
+    fn load_from_path_parses_kebab_case() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("policy.toml");
+        fs::write(
+            &path,
+            "allowed-registries = [\"ghcr.io\"]\nrequire-signature = [\"ghcr.io/acme\"]\nmax-artifact-size = 2048\n",
+        )
+        .unwrap();
+        let policy = Policy::load_from_path(&path).unwrap();
+        assert_eq!(policy.allowed_registries, ["ghcr.io"]);
+        assert_eq!(policy.require_signature, ["ghcr.io/acme"]);
+        assert_eq!(policy.max_artifact_size, Some(2048));
+
+        let missing = Policy::load_from_path(&dir.path().join("missing.toml")).unwrap();
+        assert_eq!(missing, Policy::default());
+    }
+
+    #[test]
+    fn trusted_keys_resolve_relative_to_the_policy_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("policy.toml");
+        fs::write(
+            &path,
+            "require-attestation = [\"ghcr.io/acme\"]\n\
+             trusted-keys = [{ namespace = \"ghcr.io/acme\", key = \"keys/acme.pub\" }]\n",
+        )
+        .unwrap();
+        let policy = Policy::load_from_path(&path).unwrap();
+        let r = reference("ghcr.io/acme/app:1");
+        assert_eq!(policy.attestation_namespace(&r), Some("ghcr.io/acme"));
+        let keys = policy.trusted_keys_for(&r);
+        let [key] = keys.as_slice() else {
+            panic!("{keys:?}");
+        };
+        assert_eq!(key.key, dir.path().join("keys").join("acme.pub"));
+        assert!(
+            policy
+                .trusted_keys_for(&reference("ghcr.io/other/app:1"))
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn denylist_files_resolve_relative_to_the_policy_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("policy.toml");
+        fs::write(
+            &path,
+            "denylist = [\"https://example.com/denied.txt\", \"denied.txt\"]\n",
+        )
+        .unwrap();
+        let policy = Policy::load_from_path(&path).unwrap();
+        assert_eq!(
+            policy.denylist,
+            [
+                "https://example.com/denied.txt".to_string(),
+                dir.path().join("denied.txt").display().to_string(),
+            ]
+        );
+    }
+}
@@ -202,6 +202,19 @@ pub fn require_package(manifest: &Manifest) -> Result<&Package> {
 pub async fn plan(manifest: &Manifest, manifest_dir: &Path) -> Result<PublishPlan> {
     let pkg = require_package(manifest)?;
     let reference = resolve_reference(pkg)?;
+    plan_for(manifest, manifest_dir, reference).await
+}
+
+/// Build the [`PublishPlan`] for pushing the manifest's artifact to
+/// `reference` instead of the one its `[package]` section resolves to.
+///
+/// The annotations are still taken from the `[package]` section.
+pub async fn plan_for(
+    manifest: &Manifest,
+    manifest_dir: &Path,
+    reference: Reference,
+) -> Result<PublishPlan> {
+    let pkg = require_package(manifest)?;
     let (bytes, source_path, built) = load_artifact(manifest_dir, pkg).await?;
     let size_bytes = bytes.len() as u64;
     let annotations = build_annotations(pkg, Utc::now());
@@ -228,6 +241,7 @@ mod tests {
             kind: PackageKind::Component,
             file: None,
             wit: None,
+            world: None,
             description: Some("Fetch helper".into()),
             source: Some("https://github.com/yoshuawuyts/fetch".into()),
             homepage: Some("https://example.com".into()),
@@ -0,0 +1,425 @@
+//! A read-only OCI registry backed by the local store.
+//!
+//! Every stored image is served under its original registry and
+//! repository, so `ghcr.io/example/app:1.0.0` can be pulled from a machine
+//! running the server as `<host>:<port>/ghcr.io/example/app:1.0.0`. Only
+//! the pull side of the [distribution API] is implemented: manifests,
+//! blobs, tag lists, and the catalog. Anything that would change the store
+//! is rejected with `405 Method Not Allowed`.
+//!
+//! Images pulled before config blobs were kept in the store are listed, but
+//! fetching their config fails with `BLOB_UNKNOWN`; pull them again to make
+//! them servable.
+//!
+//! [distribution API]: https://github.com/opencontainers/distribution-spec/blob/main/spec.md
+
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use axum::extract::{Path, Query, State};
+use axum::http::{StatusCode, header};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use oci_client::Reference;
+use serde::Deserialize;
+
+use crate::manager::Manager;
+
+/// Response header carrying the digest of a manifest or blob.
+const DIGEST_HEADER: &str = "docker-content-digest";
+
+/// Response header advertising support for the distribution API.
+const API_VERSION_HEADER: &str = "docker-distribution-api-version";
+
+/// Shared server state.
+///
+/// `Manager` is not `Sync`, so it sits behind a `std::sync::Mutex` that is
+/// never held across an `.await`. Blobs are read straight from the store
+/// directory without taking the lock.
+#[derive(Clone)]
+struct AppState {
+    manager: Arc<Mutex<Manager>>,
+    store_dir: Arc<PathBuf>,
+}
+
+impl AppState {
+    fn manager(&self) -> Result<MutexGuard<'_, Manager>, ServeError> {
+        self.manager
+            .lock()
+            .map_err(|e| ServeError::Internal(anyhow::anyhow!("lock poisoned: {e}")))
+    }
+}
+
+/// Pagination parameters for tag and catalog listings.
+#[derive(Debug, Default, Deserialize)]
+struct PageParams {
+    /// Maximum number of entries to return.
+    n: Option<usize>,
+    /// Only return entries that sort after this one.
+    last: Option<String>,
+}
+
+/// Build the router serving `manager`'s store.
+///
+/// # Example
+///
+/// ```no_run
+/// use component_package_manager::manager::Manager;
+/// use component_package_manager::serve;
+///
+/// # async fn example() -> anyhow::Result<()> {
+/// let manager = Manager::open_offline().await?;
+/// let app = serve::router(manager);
+///
+/// let listener = tokio::net::TcpListener::bind("127.0.0.1:5000").await?;
+/// axum::serve(listener, app).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn router(manager: Manager) -> Router {
+    let state = AppState {
+        store_dir: Arc::new(manager.state_info().store_dir().to_path_buf()),
+        manager: Arc::new(Mutex::new(manager)),
+    };
+    // Repository names contain slashes, so everything below `/v2/` other
+    // than the catalog goes through one wildcard route and is split by its
+    // suffix.
+    Router::new()
+        .route("/v2", get(api_version))
+        .route("/v2/", get(api_version))
+        .route("/v2/_catalog", get(catalog))
+        .route("/v2/{*path}", get(repository_route))
+        .with_state(state)
+}
+
+/// Serve `manager`'s store on `listener` until the server fails.
+///
+/// # Errors
+///
+/// Returns an error if accepting connections fails.
+pub async fn serve(manager: Manager, listener: tokio::net::TcpListener) -> anyhow::Result<()> {
+    axum::serve(listener, router(manager)).await?;
+    Ok(())
+}
+
+/// The API version check clients make before anything else.
+async fn api_version() -> impl IntoResponse {
+    (
+        [(API_VERSION_HEADER, "registry/2.0")],
+        Json(serde_json::json!({})),
+    )
+}
+
+/// List every stored repository.
+async fn catalog(
+    State(state): State<AppState>,
+    Query(params): Query<PageParams>,
+) -> Result<Response, ServeError> {
+    let repositories = state.manager()?.stored_repositories()?;
+    let (repositories, next) = paginate(repositories, &params);
+    let mut response = Json(serde_json::json!({ "repositories": repositories })).into_response();
+    if let Some(last) = next {
+        set_next_link(&mut response, "/v2/_catalog", &params, &last);
+    }
+    Ok(response)
+}
+
+/// Dispatch `/v2/<name>/manifests/<reference>`, `/v2/<name>/blobs/<digest>`,
+/// and `/v2/<name>/tags/list`.
+async fn repository_route(
+    State(state): State<AppState>,
+    Path(path): Path<String>,
+    Query(params): Query<PageParams>,
+) -> Result<Response, ServeError> {
+    if let Some(name) = path.strip_suffix("/tags/list") {
+        return tags_list(&state, name, &params);
+    }
+    if let Some((name, reference)) = path.rsplit_once("/manifests/") {
+        return manifest(&state, name, reference);
+    }
+    if let Some((name, digest)) = path.rsplit_once("/blobs/") {
+        return blob(&state, name, digest).await;
+    }
+    Ok(StatusCode::NOT_FOUND.into_response())
+}
+
+/// List the stored tags of a repository.
+fn tags_list(state: &AppState, name: &str, params: &PageParams) -> Result<Response, ServeError> {
+    let (registry, repository) = split_name(name)?;
+    let tags = state
+        .manager()?
+        .stored_tags(registry, repository)?
+        .ok_or(ServeError::NameUnknown)?;
+    let (tags, next) = paginate(tags, params);
+    let mut response = Json(serde_json::json!({ "name": name, "tags": tags })).into_response();
+    if let Some(last) = next {
+        set_next_link(
+            &mut response,
+            &format!("/v2/{name}/tags/list"),
+            params,
+            &last,
+        );
+    }
+    Ok(response)
+}
+
+/// Serve a stored manifest by tag or digest.
+fn manifest(state: &AppState, name: &str, reference: &str) -> Result<Response, ServeError> {
+    let (registry, repository) = split_name(name)?;
+    let reference = if is_digest(reference) {
+        Reference::with_digest(registry.into(), repository.into(), reference.into())
+    } else {
+        Reference::with_tag(registry.into(), repository.into(), reference.into())
+    };
+    let manifest = state
+        .manager()?
+        .stored_manifest(&reference)?
+        .ok_or(ServeError::ManifestUnknown)?;
+    Ok((
+        [
+            (header::CONTENT_TYPE.as_str(), manifest.media_type),
+            (DIGEST_HEADER, manifest.digest),
+        ],
+        manifest.body,
+    )
+        .into_response())
+}
+
+/// Serve a blob from the store.
+async fn blob(state: &AppState, name: &str, digest: &str) -> Result<Response, ServeError> {
+    split_name(name)?;
+    if !is_digest(digest) {
+        return Err(ServeError::DigestInvalid);
+    }
+    let data = match crate::storage::blob::read(state.store_dir.as_path(), digest).await {
+        Ok(data) => data,
+        Err(cacache::Error::EntryNotFound(..)) => return Err(ServeError::BlobUnknown),
+        Err(e) => return Err(ServeError::Internal(e.into())),
+    };
+    Ok((
+        [
+            (header::CONTENT_TYPE.as_str(), "application/octet-stream"),
+            (DIGEST_HEADER, digest),
+        ],
+        data,
+    )
+        .into_response())
+}
+
+/// Split a served repository name into the registry and repository it was
+/// pulled from.
+fn split_name(name: &str) -> Result<(&str, &str), ServeError> {
+    match name.split_once('/') {
+        Some((registry, repository)) if !registry.is_empty() && !repository.is_empty() => {
+            Ok((registry, repository))
+        }
+        _ => Err(ServeError::NameUnknown),
+    }
+}
+
+/// Whether `s` has the `algorithm:hex` shape of a digest.
+fn is_digest(s: &str) -> bool {
+    s.split_once(':').is_some_and(|(algorithm, hex)| {
+        !algorithm.is_empty() && !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit())
+    })
+}
+
+/// Apply `n` and `last` to a sorted listing, returning the page and, if
+/// more entries follow, the last entry on it.
+fn paginate(items: Vec<String>, params: &PageParams) -> (Vec<String>, Option<String>) {
+    let mut remaining = items
+        .into_iter()
+        .filter(|item| params.last.as_ref().is_none_or(|last| item > last))
+        .peekable();
+    let page: Vec<String> = remaining
+        .by_ref()
+        .take(params.n.unwrap_or(usize::MAX))
+        .collect();
+    let next = remaining.peek().and_then(|_| page.last().cloned());
+    (page, next)
+}
+
+/// Point the client at the following page with a `Link` header.
+fn set_next_link(response: &mut Response, path: &str, params: &PageParams, last: &str) {
+    let n = params.n.unwrap_or_default();
+    let link = format!("<{path}?n={n}&last={last}>; rel=\"next\"");
+    if let Ok(value) = link.parse() {
+        response.headers_mut().insert(header::LINK, value);
+    }
+}
+
+/// An error response in the distribution API's format.
+#[derive(Debug)]
+enum ServeError {
+    /// The repository is not in the store.
+    NameUnknown,
+    /// The manifest is not in the store.
+    ManifestUnknown,
+    /// The blob is not in the store.
+    BlobUnknown,
+    /// The requested digest is malformed.
+    DigestInvalid,
+    /// Reading the store failed.
+    Internal(anyhow::Error),
+}
+
+impl IntoResponse for ServeError {
+    fn into_response(self) -> Response {
+        let (status, code, message) = match self {
+            Self::NameUnknown => (
+                StatusCode::NOT_FOUND,
+                "NAME_UNKNOWN",
+                "repository name not known to registry".to_string(),
+            ),
+            Self::ManifestUnknown => (
+                StatusCode::NOT_FOUND,
+                "MANIFEST_UNKNOWN",
+                "manifest unknown".to_string(),
+            ),
+            Self::BlobUnknown => (
+                StatusCode::NOT_FOUND,
+                "BLOB_UNKNOWN",
+                "blob unknown to registry".to_string(),
+            ),
+            Self::DigestInvalid => (
+                StatusCode::BAD_REQUEST,
+                "DIGEST_INVALID",
+                "provided digest did not match uploaded content".to_string(),
+            ),
+            Self::Internal(err) => {
+                tracing::warn!("Failed to serve from the local store: {err:#}");
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "UNKNOWN",
+                    err.to_string(),
+                )
+            }
+        };
+        let body = serde_json::json!({
+            "errors": [{ "code": code, "message": message, "detail": null }]
+        });
+        (status, Json(body)).into_response()
+    }
+}
+
+impl<E: Into<anyhow::Error>> From<E> for ServeError {
+    fn from(err: E) -> Self {
+        Self::Internal(err.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::oci::FakeRegistry;
+    use oci_client::client::{ClientConfig, ClientProtocol};
+    use oci_client::secrets::RegistryAuth;
+
+    /// The smallest valid component: just the header.
+    const COMPONENT: [u8; 8] = [0x00, 0x61, 0x73, 0x6d, 0x0d, 0x00, 0x01, 0x00];
+
+    /// Pull `reference` from a fake registry into a fresh store, then serve
+    /// the store on a random local port.
+    async fn serve_pulled(reference: &Reference) -> (tempfile::TempDir, std::net::SocketAddr) {
+        let registry = Arc::new(FakeRegistry::new());
+        registry
+            .insert_component(reference, COMPONENT.to_vec())
+            .unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let manager = Manager::with_transport(dir.path(), registry).await.unwrap();
+        manager.pull(reference.clone()).await.unwrap();
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(serve(manager, listener));
+        (dir, addr)
+    }
+
+    #[tokio::test]
+    async fn stored_image_can_be_pulled_with_an_oci_client() {
+        let reference: Reference = "ghcr.io/example/hello:1.0.0".parse().unwrap();
+        let (_dir, addr) = serve_pulled(&reference).await;
+
+        let client = oci_client::Client::new(ClientConfig {
+            protocol: ClientProtocol::Http,
+            ..Default::default()
+        });
+        let served: Reference = format!("{addr}/ghcr.io/example/hello:1.0.0")
+            .parse()
+            .unwrap();
+        let image = client
+            .pull(
+                &served,
+                &RegistryAuth::Anonymous,
+                vec![oci_wasm::WASM_LAYER_MEDIA_TYPE],
+            )
+            .await
+            .unwrap();
+        assert_eq!(image.layers.first().unwrap().data.as_ref(), COMPONENT);
+
+        let tags = client
+            .list_tags(&served, &RegistryAuth::Anonymous, None, None)
+            .await
+            .unwrap();
+        assert_eq!(tags.tags, ["1.0.0"]);
+    }
+
+    #[tokio::test]
+    async fn unknown_content_and_writes_are_rejected() {
+        let reference: Reference = "ghcr.io/example/hello:1.0.0".parse().unwrap();
+        let (_dir, addr) = serve_pulled(&reference).await;
+        let http = reqwest::Client::new();
+
+        let missing = http
+            .get(format!(
+                "http://{addr}/v2/ghcr.io/example/hello/manifests/2.0.0"
+            ))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(missing.status(), StatusCode::NOT_FOUND);
+        let body: serde_json::Value = missing.json().await.unwrap();
+        assert_eq!(
+            body.pointer("/errors/0/code"),
+            Some(&serde_json::json!("MANIFEST_UNKNOWN"))
+        );
+
+        let unknown = http
+            .get(format!("http://{addr}/v2/ghcr.io/example/other/tags/list"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(unknown.status(), StatusCode::NOT_FOUND);
+
+        let push = http
+            .put(format!(
+                "http://{addr}/v2/ghcr.io/example/hello/manifests/2.0.0"
+            ))
+            .body("{}")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(push.status(), StatusCode::METHOD_NOT_ALLOWED);
+    }
+
+    #[test]
+    fn paginate_follows_last_and_n() {
+        let items = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+        let params = PageParams {
+            n: Some(1),
+            last: Some("a".to_string()),
+        };
+        assert_eq!(
+            paginate(items.clone(), &params),
+            (vec!["b".to_string()], Some("b".to_string()))
+        );
+        assert_eq!(
+            paginate(items, &PageParams::default()),
+            (
+                vec!["a".to_string(), "b".to_string(), "c".to_string()],
+                None
+            )
+        );
+    }
+}
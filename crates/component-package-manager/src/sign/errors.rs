@@ -0,0 +1,127 @@
+//! Error types for signing artifacts.
+
+use miette::Diagnostic;
+
+/// A failure to produce or attach a signature.
+///
+/// Each variant carries a stable [diagnostic error code][miette::Diagnostic::code]
+/// that uniquely identifies the failure.
+///
+/// # Example
+///
+/// ```rust
+/// use miette::Diagnostic;
+/// use component_package_manager::sign::SignError;
+///
+/// let err = SignError::PasswordRequired;
+/// assert_eq!(
+///     err.code().expect("should have a code").to_string(),
+///     "component::sign::password_required",
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Diagnostic)]
+#[must_use]
+pub enum SignError {
+    /// The signing key could not be read.
+    #[diagnostic(
+        code(component::sign::invalid_key),
+        help(
+            "pass an ECDSA P-256 private key in PEM form, or a key made with \
+             `cosign generate-key-pair`"
+        )
+    )]
+    InvalidKey {
+        /// Why the key could not be read.
+        reason: String,
+    },
+
+    /// The signing key is encrypted and no password was given.
+    #[diagnostic(
+        code(component::sign::password_required),
+        help("set `COSIGN_PASSWORD` to the key's password")
+    )]
+    PasswordRequired,
+
+    /// The signing key could not be decrypted with the given password.
+    #[diagnostic(
+        code(component::sign::wrong_password),
+        help("check that `COSIGN_PASSWORD` is the password the key was made with")
+    )]
+    WrongPassword,
+
+    /// The OIDC identity token for keyless signing could not be read.
+    #[diagnostic(
+        code(component::sign::invalid_identity_token),
+        help("pass an OIDC identity token with `--identity-token` or `SIGSTORE_ID_TOKEN`")
+    )]
+    InvalidIdentityToken {
+        /// Why the token could not be read.
+        reason: String,
+    },
+
+    /// The certificate authority (Fulcio) did not issue a certificate.
+    #[diagnostic(
+        code(component::sign::certificate_failed),
+        help("check that the identity token is fresh and issued for `sigstore`")
+    )]
+    CertificateFailed {
+        /// The certificate authority's URL.
+        url: String,
+        /// Why no certificate was issued.
+        reason: String,
+    },
+
+    /// The transparency log (Rekor) did not record the signature.
+    #[diagnostic(
+        code(component::sign::transparency_log_failed),
+        help("try again later, or pass `--no-tlog` to sign with a key without a log entry")
+    )]
+    TransparencyLogFailed {
+        /// The transparency log's URL.
+        url: String,
+        /// Why the signature was not recorded.
+        reason: String,
+    },
+
+    /// The artifact already has a signature under its cosign signature tag.
+    #[diagnostic(
+        code(component::sign::already_signed),
+        help("pass `--force` to replace the existing signature")
+    )]
+    AlreadySigned {
+        /// The reference that was signed.
+        reference: String,
+        /// The signature tag that already exists.
+        tag: String,
+    },
+}
+
+impl std::fmt::Display for SignError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SignError::InvalidKey { reason } => {
+                write!(f, "could not read the signing key: {reason}")
+            }
+            SignError::PasswordRequired => {
+                write!(f, "the signing key is encrypted and no password was given")
+            }
+            SignError::WrongPassword => {
+                write!(f, "could not decrypt the signing key")
+            }
+            SignError::InvalidIdentityToken { reason } => {
+                write!(f, "could not read the identity token: {reason}")
+            }
+            SignError::CertificateFailed { url, reason } => {
+                write!(f, "{url} did not issue a signing certificate: {reason}")
+            }
+            SignError::TransparencyLogFailed { url, reason } => {
+                write!(f, "{url} did not record the signature: {reason}")
+            }
+            SignError::AlreadySigned { reference, tag } => {
+                write!(f, "'{reference}' is already signed (tag '{tag}')")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SignError {}
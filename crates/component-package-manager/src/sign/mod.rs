@@ -0,0 +1,454 @@
+//! Cosign-compatible signatures for artifacts in a registry.
+//!
+//! [`Manager::sign`](crate::manager::Manager::sign) signs an artifact's
+//! manifest digest the way `cosign sign` does. It signs a "simple signing"
+//! payload that names the repository and digest, and pushes it as the single
+//! layer of a manifest tagged `sha256-<hex>.sig`. That tag is where
+//! `cosign verify` looks for signatures, and it is what the content trust
+//! [policy](crate::policy) checks for.
+//!
+//! There are two kinds of [`Signer`]:
+//!
+//! - **Key**: a [`SigningKey`] read from an ECDSA P-256 private key in PEM
+//!   form. Plain PKCS#8 and SEC1 keys are read as-is. Keys made with
+//!   `cosign generate-key-pair` are decrypted with a password.
+//! - **Keyless**: a key made for this signature only. A certificate
+//!   authority (Fulcio) certifies it for the identity in an OIDC token, and
+//!   the certificate is attached to the signature.
+//!
+//! Either way, the signature can be recorded in a transparency log (Rekor).
+//! The log's receipt is attached as a cosign bundle, so `cosign verify`
+//! doesn't have to ask the log itself.
+//!
+//! [`Manager::verify`](crate::manager::Manager::verify) checks signatures and
+//! attestations made this way against [`VerifyingKey`]s.
+
+mod errors;
+mod sigstore;
+mod verify;
+
+use std::collections::BTreeMap;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use oci_client::Reference;
+use p256::ecdsa::signature::Signer as _;
+use p256::pkcs8::der::pem;
+use p256::pkcs8::{DecodePrivateKey, EncodePublicKey, LineEnding};
+
+use crate::NetworkConfig;
+
+pub use errors::SignError;
+pub use verify::{Verification, VerifyingKey};
+pub(crate) use verify::{check_attestation, check_signature};
+
+/// The public Sigstore certificate authority.
+pub const DEFAULT_FULCIO_URL: &str = "https://fulcio.sigstore.dev";
+
+/// The public Sigstore transparency log.
+pub const DEFAULT_REKOR_URL: &str = "https://rekor.sigstore.dev";
+
+/// Media type of a cosign signature layer.
+pub(crate) const SIMPLE_SIGNING_MEDIA_TYPE: &str =
+    "application/vnd.dev.cosign.simplesigning.v1+json";
+
+/// Layer annotation holding the base64 signature over the layer.
+const SIGNATURE_ANNOTATION: &str = "dev.cosignproject.cosign/signature";
+/// Layer annotation holding the PEM certificate of a keyless signature.
+const CERTIFICATE_ANNOTATION: &str = "dev.sigstore.cosign/certificate";
+/// Layer annotation holding the PEM certificates that issued it.
+const CHAIN_ANNOTATION: &str = "dev.sigstore.cosign/chain";
+/// Layer annotation holding the transparency log's receipt.
+const BUNDLE_ANNOTATION: &str = "dev.sigstore.cosign/bundle";
+
+/// An ECDSA P-256 key to sign with.
+///
+/// # Example
+///
+/// ```
+/// use component_package_manager::sign::SigningKey;
+///
+/// let key = SigningKey::generate();
+/// assert!(key.public_key_pem().starts_with("-----BEGIN PUBLIC KEY-----"));
+/// ```
+#[derive(Clone)]
+pub struct SigningKey {
+    key: p256::ecdsa::SigningKey,
+}
+
+impl std::fmt::Debug for SigningKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SigningKey").finish_non_exhaustive()
+    }
+}
+
+impl SigningKey {
+    /// Make a new random key.
+    #[must_use]
+    pub fn generate() -> Self {
+        Self {
+            key: p256::ecdsa::SigningKey::random(&mut rand_core::OsRng),
+        }
+    }
+
+    /// Read a private key in PEM form.
+    ///
+    /// Accepts PKCS#8 (`PRIVATE KEY`) and SEC1 (`EC PRIVATE KEY`) keys, and
+    /// keys encrypted by `cosign generate-key-pair`, which need `password`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SignError::PasswordRequired`] or [`SignError::WrongPassword`]
+    /// for an encrypted key that can't be decrypted, and
+    /// [`SignError::InvalidKey`] for anything that isn't a P-256 key.
+    pub fn from_pem(pem: &str, password: Option<&[u8]>) -> Result<Self, SignError> {
+        let invalid = |reason: String| SignError::InvalidKey { reason };
+        let (label, der) = pem::decode_vec(pem.trim().as_bytes())
+            .map_err(|err| invalid(format!("not a PEM file: {err}")))?;
+        let key = match label {
+            "PRIVATE KEY" => p256::ecdsa::SigningKey::from_pkcs8_der(&der)
+                .map_err(|err| invalid(err.to_string()))?,
+            "EC PRIVATE KEY" => p256::SecretKey::from_sec1_der(&der)
+                .map_err(|err| invalid(err.to_string()))?
+                .into(),
+            "ENCRYPTED SIGSTORE PRIVATE KEY" | "ENCRYPTED COSIGN PRIVATE KEY" => {
+                let password = password.ok_or(SignError::PasswordRequired)?;
+                let der = decrypt_cosign_key(&der, password)?;
+                p256::ecdsa::SigningKey::from_pkcs8_der(&der)
+                    .map_err(|err| invalid(err.to_string()))?
+            }
+            label => return Err(invalid(format!("unsupported PEM label '{label}'"))),
+        };
+        Ok(Self { key })
+    }
+
+    /// The public half of the key, as a PEM `PUBLIC KEY`, as `cosign verify
+    /// --key` expects it.
+    #[must_use]
+    pub fn public_key_pem(&self) -> String {
+        p256::PublicKey::from(self.key.verifying_key())
+            .to_public_key_pem(LineEnding::LF)
+            .unwrap_or_default()
+    }
+
+    /// The public half of the key, to verify its signatures with.
+    #[must_use]
+    pub fn verifying_key(&self) -> VerifyingKey {
+        VerifyingKey::new(*self.key.verifying_key())
+    }
+
+    /// Sign `data` with ECDSA over its SHA-256 hash, DER-encoded.
+    fn sign(&self, data: &[u8]) -> Vec<u8> {
+        let signature: p256::ecdsa::Signature = self.key.sign(data);
+        signature.to_der().as_bytes().to_vec()
+    }
+}
+
+/// Decrypt the PKCS#8 key inside a cosign encrypted key: a JSON envelope
+/// around a NaCl secretbox, keyed with scrypt.
+fn decrypt_cosign_key(envelope: &[u8], password: &[u8]) -> Result<Vec<u8>, SignError> {
+    use crypto_secretbox::aead::{Aead, KeyInit};
+
+    #[derive(serde::Deserialize)]
+    struct Envelope {
+        kdf: Kdf,
+        cipher: Cipher,
+        ciphertext: String,
+    }
+    #[derive(serde::Deserialize)]
+    struct Kdf {
+        params: KdfParams,
+        salt: String,
+    }
+    #[derive(serde::Deserialize)]
+    struct KdfParams {
+        #[serde(rename = "N")]
+        n: u64,
+        r: u32,
+        p: u32,
+    }
+    #[derive(serde::Deserialize)]
+    struct Cipher {
+        nonce: String,
+    }
+
+    let invalid = |reason: String| SignError::InvalidKey { reason };
+    let envelope: Envelope =
+        serde_json::from_slice(envelope).map_err(|err| invalid(err.to_string()))?;
+    let decode = |field: &str, value: &str| {
+        BASE64
+            .decode(value)
+            .map_err(|err| invalid(format!("bad {field}: {err}")))
+    };
+    let salt = decode("salt", &envelope.kdf.salt)?;
+    let nonce = decode("nonce", &envelope.cipher.nonce)?;
+    let ciphertext = decode("ciphertext", &envelope.ciphertext)?;
+
+    let KdfParams { n, r, p } = envelope.kdf.params;
+    if !n.is_power_of_two() {
+        return Err(invalid(format!("scrypt N must be a power of two, not {n}")));
+    }
+    let log_n = u8::try_from(n.trailing_zeros()).unwrap_or(u8::MAX);
+    let params = scrypt::Params::new(log_n, r, p, 32)
+        .map_err(|err| invalid(format!("bad scrypt parameters: {err}")))?;
+    let mut secret = [0u8; 32];
+    scrypt::scrypt(password, &salt, &params, &mut secret)
+        .map_err(|err| invalid(format!("scrypt failed: {err}")))?;
+
+    let nonce = crypto_secretbox::Nonce::from_exact_iter(nonce)
+        .ok_or_else(|| invalid("the nonce must be 24 bytes".to_string()))?;
+    crypto_secretbox::XSalsa20Poly1305::new(&secret.into())
+        .decrypt(&nonce, ciphertext.as_slice())
+        .map_err(|_| SignError::WrongPassword)
+}
+
+/// Who signs, and how their key is vouched for.
+#[derive(Debug, Clone)]
+pub enum Signer {
+    /// Sign with a key the signer holds; verified with its public key.
+    Key(SigningKey),
+    /// Sign with a new key, certified for the identity in an OIDC token.
+    Keyless {
+        /// The OIDC identity token to trade for a certificate.
+        identity_token: String,
+        /// The certificate authority, usually [`DEFAULT_FULCIO_URL`].
+        fulcio_url: String,
+    },
+}
+
+/// How to sign an artifact.
+///
+/// # Example
+///
+/// ```
+/// use component_package_manager::sign::{DEFAULT_REKOR_URL, SignOptions, Signer, SigningKey};
+///
+/// let options = SignOptions {
+///     signer: Signer::Key(SigningKey::generate()),
+///     rekor_url: Some(DEFAULT_REKOR_URL.to_string()),
+///     force: false,
+/// };
+/// assert!(options.rekor_url.is_some());
+/// ```
+#[derive(Debug, Clone)]
+pub struct SignOptions {
+    /// Who signs.
+    pub signer: Signer,
+    /// The transparency log to record the signature in, or `None` to not
+    /// record it. `cosign verify` needs `--insecure-ignore-tlog` for
+    /// unrecorded signatures, and keyless signatures must be recorded.
+    pub rekor_url: Option<String>,
+    /// Replace an existing signature instead of failing.
+    pub force: bool,
+}
+
+/// A signature, ready to push as the layer of a cosign signature manifest.
+#[derive(Debug, Clone)]
+pub(crate) struct SignatureLayer {
+    /// The signed simple signing payload; the layer's contents.
+    pub(crate) payload: Vec<u8>,
+    /// The layer's annotations: the signature, certificates, and bundle.
+    pub(crate) annotations: BTreeMap<String, String>,
+    /// The identity a keyless signature was certified for.
+    pub(crate) identity: Option<String>,
+    /// Where the transparency log recorded the signature.
+    pub(crate) log_index: Option<i64>,
+}
+
+/// The cosign "simple signing" payload for the manifest at `digest` in the
+/// repository `reference` points at.
+pub(crate) fn simple_signing_payload(reference: &Reference, digest: &str) -> Vec<u8> {
+    let payload = serde_json::json!({
+        "critical": {
+            "identity": {
+                "docker-reference": format!(
+                    "{}/{}",
+                    reference.resolve_registry(),
+                    reference.repository()
+                ),
+            },
+            "image": { "docker-manifest-digest": digest },
+            "type": "cosign container image signature",
+        },
+        "optional": null,
+    });
+    serde_json::to_vec(&payload).unwrap_or_default()
+}
+
+/// Sign `payload` as `options` asks, certifying and recording the signature
+/// with Sigstore services as needed.
+pub(crate) async fn sign(
+    options: &SignOptions,
+    network: &NetworkConfig,
+    payload: Vec<u8>,
+) -> anyhow::Result<SignatureLayer> {
+    let http = reqwest::Client::builder()
+        .user_agent(concat!("component/", env!("CARGO_PKG_VERSION")))
+        .connect_timeout(network.connect_timeout())
+        .read_timeout(network.read_timeout())
+        .build()?;
+
+    let mut annotations = BTreeMap::new();
+    let (key, certificate) = match &options.signer {
+        Signer::Key(key) => (key.clone(), None),
+        Signer::Keyless {
+            identity_token,
+            fulcio_url,
+        } => {
+            let key = SigningKey::generate();
+            let certificate =
+                sigstore::certificate(&http, fulcio_url, identity_token, &key).await?;
+            (key, Some(certificate))
+        }
+    };
+
+    let signature = key.sign(&payload);
+    annotations.insert(SIGNATURE_ANNOTATION.to_string(), BASE64.encode(&signature));
+    if let Some(certificate) = &certificate {
+        annotations.insert(CERTIFICATE_ANNOTATION.to_string(), certificate.leaf.clone());
+        annotations.insert(CHAIN_ANNOTATION.to_string(), certificate.chain.concat());
+    }
+
+    let mut log_index = None;
+    if let Some(rekor_url) = &options.rekor_url {
+        let public_key = match &certificate {
+            Some(certificate) => certificate.leaf.clone(),
+            None => key.public_key_pem(),
+        };
+        let entry = sigstore::record(&http, rekor_url, &signature, &payload, &public_key).await?;
+        annotations.insert(BUNDLE_ANNOTATION.to_string(), entry.bundle);
+        log_index = Some(entry.log_index);
+    }
+
+    Ok(SignatureLayer {
+        payload,
+        annotations,
+        identity: certificate.map(|certificate| certificate.identity),
+        log_index,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crypto_secretbox::aead::{Aead, KeyInit};
+    use p256::ecdsa::signature::Verifier;
+    use p256::pkcs8::EncodePrivateKey;
+
+    /// Encrypt `key` the way `cosign generate-key-pair` does, with a cheap
+    /// scrypt cost.
+    fn cosign_encrypted_pem(key: &SigningKey, password: &[u8]) -> String {
+        let der = key.key.to_pkcs8_der().unwrap();
+        let salt = [7u8; 32];
+        let nonce = [9u8; 24];
+        let params = scrypt::Params::new(4, 8, 1, 32).unwrap();
+        let mut secret = [0u8; 32];
+        scrypt::scrypt(password, &salt, &params, &mut secret).unwrap();
+        let ciphertext = crypto_secretbox::XSalsa20Poly1305::new(&secret.into())
+            .encrypt(&nonce.into(), der.as_bytes())
+            .unwrap();
+        let envelope = serde_json::json!({
+            "kdf": {
+                "name": "scrypt",
+                "params": { "N": 16, "r": 8, "p": 1 },
+                "salt": BASE64.encode(salt),
+            },
+            "cipher": { "name": "nacl/secretbox", "nonce": BASE64.encode(nonce) },
+            "ciphertext": BASE64.encode(ciphertext),
+        });
+        pem::encode_string(
+            "ENCRYPTED SIGSTORE PRIVATE KEY",
+            LineEnding::LF,
+            &serde_json::to_vec(&envelope).unwrap(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn reads_pkcs8_and_sec1_keys() {
+        let key = SigningKey::generate();
+        let pkcs8 = key.key.to_pkcs8_pem(LineEnding::LF).unwrap();
+        let sec1 = p256::SecretKey::from(&key.key)
+            .to_sec1_pem(LineEnding::LF)
+            .unwrap();
+
+        for pem in [pkcs8.as_str(), sec1.as_str()] {
+            let read = SigningKey::from_pem(pem, None).unwrap();
+            assert_eq!(read.public_key_pem(), key.public_key_pem());
+        }
+    }
+
+    #[test]
+    fn reads_cosign_encrypted_keys() {
+        let key = SigningKey::generate();
+        let pem = cosign_encrypted_pem(&key, b"hunter2");
+
+        let read = SigningKey::from_pem(&pem, Some(b"hunter2")).unwrap();
+        assert_eq!(read.public_key_pem(), key.public_key_pem());
+
+        assert_eq!(
+            SigningKey::from_pem(&pem, None).unwrap_err(),
+            SignError::PasswordRequired
+        );
+        assert_eq!(
+            SigningKey::from_pem(&pem, Some(b"wrong")).unwrap_err(),
+            SignError::WrongPassword
+        );
+    }
+
+    #[test]
+    fn rejects_other_keys() {
+        let err = SigningKey::from_pem("not a key", None).unwrap_err();
+        assert!(matches!(err, SignError::InvalidKey { .. }));
+
+        let public = SigningKey::generate().public_key_pem();
+        let err = SigningKey::from_pem(&public, None).unwrap_err();
+        assert!(matches!(err, SignError::InvalidKey { .. }));
+    }
+
+    #[test]
+    fn payload_names_repository_and_digest() {
+        let reference: Reference = "ghcr.io/example/hello:1.0.0".parse().unwrap();
+        let payload = simple_signing_payload(&reference, "sha256:abc");
+        let payload: serde_json::Value = serde_json::from_slice(&payload).unwrap();
+        let field = |pointer: &str| payload.pointer(pointer).and_then(serde_json::Value::as_str);
+        assert_eq!(
+            field("/critical/identity/docker-reference"),
+            Some("ghcr.io/example/hello")
+        );
+        assert_eq!(
+            field("/critical/image/docker-manifest-digest"),
+            Some("sha256:abc")
+        );
+        assert_eq!(
+            field("/critical/type"),
+            Some("cosign container image signature")
+        );
+    }
+
+    #[tokio::test]
+    async fn signs_with_a_key_without_a_log() {
+        let key = SigningKey::generate();
+        let options = SignOptions {
+            signer: Signer::Key(key.clone()),
+            rekor_url: None,
+            force: false,
+        };
+        let layer = sign(&options, &NetworkConfig::default(), b"payload".to_vec())
+            .await
+            .unwrap();
+
+        assert_eq!(layer.payload, b"payload");
+        assert_eq!(layer.identity, None);
+        assert_eq!(layer.log_index, None);
+        assert!(!layer.annotations.contains_key(BUNDLE_ANNOTATION));
+        let signature = BASE64
+            .decode(layer.annotations.get(SIGNATURE_ANNOTATION).unwrap())
+            .unwrap();
+        let signature = p256::ecdsa::Signature::from_der(&signature).unwrap();
+        key.key
+            .verifying_key()
+            .verify(b"payload", &signature)
+            .unwrap();
+    }
+}
@@ -0,0 +1,316 @@
+//! Requests to the Sigstore certificate authority (Fulcio) and
+//! transparency log (Rekor).
+
+use base64::Engine;
+use base64::engine::general_purpose::{STANDARD as BASE64, URL_SAFE_NO_PAD as BASE64_URL};
+
+use super::{SignError, SigningKey};
+use crate::oci::sha256_digest;
+
+/// A certificate Fulcio issued for a signing key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Certificate {
+    /// The identity from the OIDC token that the certificate names.
+    pub(super) identity: String,
+    /// The PEM certificate for the signing key.
+    pub(super) leaf: String,
+    /// The PEM certificates that issued it, leaf-most first.
+    pub(super) chain: Vec<String>,
+}
+
+/// Rekor's receipt for a recorded signature.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct LogEntry {
+    /// The receipt as a cosign bundle annotation.
+    pub(super) bundle: String,
+    /// The entry's index in the log.
+    pub(super) log_index: i64,
+}
+
+/// Ask Fulcio to certify `key` for the identity in `identity_token`.
+pub(super) async fn certificate(
+    http: &reqwest::Client,
+    fulcio_url: &str,
+    identity_token: &str,
+    key: &SigningKey,
+) -> anyhow::Result<Certificate> {
+    let identity = token_subject(identity_token)?;
+    let failed = |reason: String| SignError::CertificateFailed {
+        url: fulcio_url.to_string(),
+        reason,
+    };
+
+    // Fulcio wants proof that we hold the key: a signature over the subject.
+    let request = serde_json::json!({
+        "credentials": { "oidcIdentityToken": identity_token },
+        "publicKeyRequest": {
+            "publicKey": { "algorithm": "ECDSA", "content": key.public_key_pem() },
+            "proofOfPossession": BASE64.encode(key.sign(identity.as_bytes())),
+        },
+    });
+    let url = format!("{}/api/v2/signingCert", fulcio_url.trim_end_matches('/'));
+    let response = http
+        .post(&url)
+        .json(&request)
+        .send()
+        .await
+        .map_err(|err| failed(err.to_string()))?;
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(failed(format!("HTTP {status}: {}", body.trim())).into());
+    }
+    let response: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|err| failed(err.to_string()))?;
+    let (leaf, chain) = certificate_chain(&response).map_err(failed)?;
+    Ok(Certificate {
+        identity,
+        leaf,
+        chain,
+    })
+}
+
+/// Record `signature` over `payload` in Rekor as a `hashedrekord` entry.
+pub(super) async fn record(
+    http: &reqwest::Client,
+    rekor_url: &str,
+    signature: &[u8],
+    payload: &[u8],
+    public_key_pem: &str,
+) -> anyhow::Result<LogEntry> {
+    let failed = |reason: String| SignError::TransparencyLogFailed {
+        url: rekor_url.to_string(),
+        reason,
+    };
+    let url = format!("{}/api/v1/log/entries", rekor_url.trim_end_matches('/'));
+    let response = http
+        .post(&url)
+        .json(&hashed_rekord(signature, payload, public_key_pem))
+        .send()
+        .await
+        .map_err(|err| failed(err.to_string()))?;
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(failed(format!("HTTP {status}: {}", body.trim())).into());
+    }
+    let response: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|err| failed(err.to_string()))?;
+    Ok(log_entry(&response).map_err(failed)?)
+}
+
+/// The identity Fulcio certifies for an OIDC token: its verified email, or
+/// else its subject.
+fn token_subject(token: &str) -> Result<String, SignError> {
+    let invalid = |reason: &str| SignError::InvalidIdentityToken {
+        reason: reason.to_string(),
+    };
+    let claims = token
+        .split('.')
+        .nth(1)
+        .ok_or_else(|| invalid("not a JWT"))?;
+    let claims = BASE64_URL
+        .decode(claims.trim_end_matches('='))
+        .map_err(|_| invalid("the claims are not base64"))?;
+    let claims: serde_json::Value =
+        serde_json::from_slice(&claims).map_err(|_| invalid("the claims are not JSON"))?;
+
+    if let Some(email) = claims.get("email").and_then(serde_json::Value::as_str) {
+        if claims
+            .get("email_verified")
+            .and_then(serde_json::Value::as_bool)
+            != Some(true)
+        {
+            return Err(invalid("the email address is not verified"));
+        }
+        return Ok(email.to_string());
+    }
+    claims
+        .get("sub")
+        .and_then(serde_json::Value::as_str)
+        .map(str::to_string)
+        .ok_or_else(|| invalid("there is no subject"))
+}
+
+/// The certificates in a Fulcio `signingCert` response, split into the
+/// leaf and the rest of the chain.
+fn certificate_chain(response: &serde_json::Value) -> Result<(String, Vec<String>), String> {
+    let certificates = [
+        "signedCertificateEmbeddedSct",
+        "signedCertificateDetachedSct",
+    ]
+    .iter()
+    .find_map(|kind| {
+        response
+            .pointer(&format!("/{kind}/chain/certificates"))
+            .and_then(serde_json::Value::as_array)
+    })
+    .ok_or("the response has no certificate chain")?;
+    let mut certificates = certificates
+        .iter()
+        .map(|certificate| certificate.as_str().map(str::to_string))
+        .collect::<Option<Vec<_>>>()
+        .ok_or("the certificate chain is not a list of PEM certificates")?
+        .into_iter();
+    let leaf = certificates
+        .next()
+        .ok_or("the certificate chain is empty")?;
+    Ok((leaf, certificates.collect()))
+}
+
+/// A Rekor `hashedrekord` entry for `signature` over `payload`.
+fn hashed_rekord(signature: &[u8], payload: &[u8], public_key_pem: &str) -> serde_json::Value {
+    let digest = sha256_digest(payload);
+    let hash = digest.strip_prefix("sha256:").unwrap_or(&digest);
+    serde_json::json!({
+        "apiVersion": "0.0.1",
+        "kind": "hashedrekord",
+        "spec": {
+            "data": { "hash": { "algorithm": "sha256", "value": hash } },
+            "signature": {
+                "content": BASE64.encode(signature),
+                "publicKey": { "content": BASE64.encode(public_key_pem) },
+            },
+        },
+    })
+}
+
+/// The cosign bundle for the entry in a Rekor `log/entries` response, which
+/// maps the entry's UUID to the entry.
+fn log_entry(response: &serde_json::Value) -> Result<LogEntry, String> {
+    let (_uuid, entry) = response
+        .as_object()
+        .and_then(|entries| entries.iter().next())
+        .ok_or("the response has no log entry")?;
+    let field = |name: &str| {
+        entry
+            .get(name)
+            .filter(|value| !value.is_null())
+            .cloned()
+            .ok_or_else(|| format!("the log entry has no `{name}`"))
+    };
+    let log_index = field("logIndex")?
+        .as_i64()
+        .ok_or("the log index is not a number")?;
+    let signed_entry_timestamp = entry
+        .pointer("/verification/signedEntryTimestamp")
+        .cloned()
+        .ok_or("the log entry has no signed entry timestamp")?;
+    let bundle = serde_json::json!({
+        "SignedEntryTimestamp": signed_entry_timestamp,
+        "Payload": {
+            "body": field("body")?,
+            "integratedTime": field("integratedTime")?,
+            "logIndex": log_index,
+            "logID": field("logID")?,
+        },
+    });
+    Ok(LogEntry {
+        bundle: bundle.to_string(),
+        log_index,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn jwt(claims: &serde_json::Value) -> String {
+        format!(
+            "e30.{}.c2ln",
+            BASE64_URL.encode(serde_json::to_vec(claims).unwrap())
+        )
+    }
+
+    #[test]
+    fn token_subject_prefers_verified_email() {
+        let token = jwt(&serde_json::json!({
+            "sub": "1234",
+            "email": "dev@example.com",
+            "email_verified": true,
+        }));
+        assert_eq!(token_subject(&token).unwrap(), "dev@example.com");
+
+        let token = jwt(&serde_json::json!({ "sub": "repo:example/hello:ref:main" }));
+        assert_eq!(
+            token_subject(&token).unwrap(),
+            "repo:example/hello:ref:main"
+        );
+
+        let token = jwt(&serde_json::json!({ "email": "dev@example.com" }));
+        assert!(token_subject(&token).is_err());
+        assert!(token_subject("not-a-token").is_err());
+    }
+
+    #[test]
+    fn certificate_chain_splits_leaf() {
+        let response = serde_json::json!({
+            "signedCertificateEmbeddedSct": {
+                "chain": { "certificates": ["leaf", "intermediate", "root"] },
+            },
+        });
+        let (leaf, chain) = certificate_chain(&response).unwrap();
+        assert_eq!(leaf, "leaf");
+        assert_eq!(chain, ["intermediate", "root"]);
+
+        assert!(certificate_chain(&serde_json::json!({})).is_err());
+    }
+
+    #[test]
+    fn hashed_rekord_hashes_payload() {
+        let entry = hashed_rekord(b"sig", b"abc", "PEM");
+        let field = |pointer: &str| entry.pointer(pointer).and_then(serde_json::Value::as_str);
+        assert_eq!(
+            field("/spec/data/hash/value"),
+            Some("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+        );
+        assert_eq!(
+            field("/spec/signature/content"),
+            Some(BASE64.encode("sig").as_str())
+        );
+        assert_eq!(
+            field("/spec/signature/publicKey/content"),
+            Some(BASE64.encode("PEM").as_str())
+        );
+    }
+
+    #[test]
+    fn log_entry_builds_bundle() {
+        let response = serde_json::json!({
+            "24296fb24b8ad77a": {
+                "body": "Ym9keQ==",
+                "integratedTime": 1_700_000_000,
+                "logID": "c0d23d6a",
+                "logIndex": 42,
+                "verification": { "signedEntryTimestamp": "c2V0" },
+            },
+        });
+        let entry = log_entry(&response).unwrap();
+        assert_eq!(entry.log_index, 42);
+        let bundle: serde_json::Value = serde_json::from_str(&entry.bundle).unwrap();
+        assert_eq!(
+            bundle
+                .pointer("/SignedEntryTimestamp")
+                .and_then(serde_json::Value::as_str),
+            Some("c2V0")
+        );
+        assert_eq!(
+            bundle
+                .pointer("/Payload/logIndex")
+                .and_then(serde_json::Value::as_u64),
+            Some(42)
+        );
+        assert_eq!(
+            bundle
+                .pointer("/Payload/integratedTime")
+                .and_then(serde_json::Value::as_u64),
+            Some(1_700_000_000)
+        );
+
+        assert!(log_entry(&serde_json::json!({})).is_err());
+    }
+}
@@ -0,0 +1,324 @@
+//! Checks of cosign signatures and attestations against public keys.
+
+use std::collections::BTreeMap;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use p256::ecdsa::signature::Verifier as _;
+use p256::pkcs8::DecodePublicKey;
+use serde::Serialize;
+
+use super::{SIGNATURE_ANNOTATION, SignError};
+
+/// An ECDSA P-256 public key to verify signatures with.
+///
+/// # Example
+///
+/// ```
+/// use component_package_manager::sign::{SigningKey, VerifyingKey};
+///
+/// let key = SigningKey::generate();
+/// let public = VerifyingKey::from_pem(&key.public_key_pem()).unwrap();
+/// assert_eq!(public, key.verifying_key());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifyingKey {
+    key: p256::ecdsa::VerifyingKey,
+}
+
+impl VerifyingKey {
+    pub(super) fn new(key: p256::ecdsa::VerifyingKey) -> Self {
+        Self { key }
+    }
+
+    /// Read a public key in PEM form, such as the `cosign.pub` that
+    /// `cosign generate-key-pair` writes.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SignError::InvalidKey`] for anything that isn't a P-256
+    /// `PUBLIC KEY`.
+    pub fn from_pem(pem: &str) -> Result<Self, SignError> {
+        let key = p256::ecdsa::VerifyingKey::from_public_key_pem(pem.trim()).map_err(|err| {
+            SignError::InvalidKey {
+                reason: err.to_string(),
+            }
+        })?;
+        Ok(Self { key })
+    }
+
+    /// Whether `signature`, DER-encoded, is this key's signature over `data`.
+    fn verifies(&self, data: &[u8], signature: &[u8]) -> bool {
+        p256::ecdsa::Signature::from_der(signature)
+            .is_ok_and(|signature| self.key.verify(data, &signature).is_ok())
+    }
+}
+
+/// The outcome of checking one signature or attestation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", rename_all = "kebab-case")]
+pub enum Verification {
+    /// A trusted key signed it.
+    Verified {
+        /// The trusted key that signed it.
+        key: String,
+    },
+    /// It is well-formed and covers the artifact, but no trusted key signed
+    /// it. Keyless signatures are always untrusted, since their
+    /// certificates aren't checked.
+    Untrusted,
+    /// It is malformed, or it covers a different artifact.
+    Invalid {
+        /// What is wrong with it.
+        reason: String,
+    },
+}
+
+impl Verification {
+    /// Whether a trusted key signed it.
+    #[must_use]
+    pub fn is_verified(&self) -> bool {
+        matches!(self, Self::Verified { .. })
+    }
+}
+
+/// Whether one of `keys` made one of `signatures` over `data`, and which.
+fn signed_by(keys: &[(String, VerifyingKey)], data: &[u8], signatures: &[Vec<u8>]) -> Verification {
+    keys.iter()
+        .find(|(_, key)| {
+            signatures
+                .iter()
+                .any(|signature| key.verifies(data, signature))
+        })
+        .map_or(Verification::Untrusted, |(name, _)| {
+            Verification::Verified { key: name.clone() }
+        })
+}
+
+/// Check a cosign signature layer: its simple signing `payload` must name
+/// `digest`, and its signature annotation must verify with one of `keys`.
+pub(crate) fn check_signature(
+    payload: &[u8],
+    annotations: &BTreeMap<String, String>,
+    digest: &str,
+    keys: &[(String, VerifyingKey)],
+) -> Verification {
+    let invalid = |reason: String| Verification::Invalid { reason };
+    let Ok(payload_json) = serde_json::from_slice::<serde_json::Value>(payload) else {
+        return invalid("the payload is not JSON".to_string());
+    };
+    match payload_json
+        .pointer("/critical/image/docker-manifest-digest")
+        .and_then(serde_json::Value::as_str)
+    {
+        Some(signed) if signed == digest => {}
+        Some(signed) => return invalid(format!("it signs {signed}, not {digest}")),
+        None => return invalid("the payload names no manifest digest".to_string()),
+    }
+    let Some(signature) = annotations.get(SIGNATURE_ANNOTATION) else {
+        return invalid("the layer has no signature annotation".to_string());
+    };
+    let Ok(signature) = BASE64.decode(signature) else {
+        return invalid("the signature is not base64".to_string());
+    };
+    signed_by(keys, payload, &[signature])
+}
+
+/// A checked attestation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CheckedAttestation {
+    /// The in-toto predicate type, such as `https://slsa.dev/provenance/v1`.
+    pub(crate) predicate_type: Option<String>,
+    /// Whether a trusted key signed it.
+    pub(crate) verification: Verification,
+}
+
+/// Check a cosign attestation layer: a DSSE envelope around an in-toto
+/// statement whose subject must include `digest`, signed by one of `keys`.
+pub(crate) fn check_attestation(
+    envelope: &[u8],
+    digest: &str,
+    keys: &[(String, VerifyingKey)],
+) -> CheckedAttestation {
+    let invalid = |predicate_type: Option<String>, reason: &str| CheckedAttestation {
+        predicate_type,
+        verification: Verification::Invalid {
+            reason: reason.to_string(),
+        },
+    };
+    let Ok(envelope) = serde_json::from_slice::<serde_json::Value>(envelope) else {
+        return invalid(None, "the envelope is not JSON");
+    };
+    let payload_type = envelope
+        .get("payloadType")
+        .and_then(serde_json::Value::as_str)
+        .unwrap_or_default();
+    let Some(Ok(payload)) = envelope
+        .get("payload")
+        .and_then(serde_json::Value::as_str)
+        .map(|payload| BASE64.decode(payload))
+    else {
+        return invalid(None, "the envelope has no base64 payload");
+    };
+    let Ok(statement) = serde_json::from_slice::<serde_json::Value>(&payload) else {
+        return invalid(None, "the statement is not JSON");
+    };
+    let predicate_type = statement
+        .get("predicateType")
+        .and_then(serde_json::Value::as_str)
+        .map(str::to_string);
+
+    let hex = digest.strip_prefix("sha256:").unwrap_or(digest);
+    let covers_digest = statement
+        .get("subject")
+        .and_then(serde_json::Value::as_array)
+        .is_some_and(|subjects| {
+            subjects.iter().any(|subject| {
+                subject
+                    .pointer("/digest/sha256")
+                    .and_then(serde_json::Value::as_str)
+                    == Some(hex)
+            })
+        });
+    if !covers_digest {
+        return invalid(predicate_type, "its subject is a different artifact");
+    }
+
+    let signatures: Vec<Vec<u8>> = envelope
+        .get("signatures")
+        .and_then(serde_json::Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|signature| signature.get("sig").and_then(serde_json::Value::as_str))
+        .filter_map(|signature| BASE64.decode(signature).ok())
+        .collect();
+    if signatures.is_empty() {
+        return invalid(predicate_type, "the envelope has no signatures");
+    }
+    CheckedAttestation {
+        predicate_type,
+        verification: signed_by(keys, &pae(payload_type, &payload), &signatures),
+    }
+}
+
+/// The DSSE pre-authentication encoding of a payload: what an envelope's
+/// signatures are over.
+fn pae(payload_type: &str, payload: &[u8]) -> Vec<u8> {
+    let mut encoded = format!(
+        "DSSEv1 {} {payload_type} {} ",
+        payload_type.len(),
+        payload.len()
+    )
+    .into_bytes();
+    encoded.extend_from_slice(payload);
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sign::SigningKey;
+
+    const DIGEST: &str = "sha256:abc123";
+
+    fn keys(key: &SigningKey) -> Vec<(String, VerifyingKey)> {
+        vec![("cosign.pub".to_string(), key.verifying_key())]
+    }
+
+    fn signature_annotations(key: &SigningKey, payload: &[u8]) -> BTreeMap<String, String> {
+        BTreeMap::from([(
+            SIGNATURE_ANNOTATION.to_string(),
+            BASE64.encode(key.sign(payload)),
+        )])
+    }
+
+    fn envelope(key: &SigningKey, subject: &str) -> Vec<u8> {
+        let statement = serde_json::to_vec(&serde_json::json!({
+            "_type": "https://in-toto.io/Statement/v1",
+            "subject": [{ "name": "app", "digest": { "sha256": subject } }],
+            "predicateType": "https://slsa.dev/provenance/v1",
+            "predicate": {},
+        }))
+        .unwrap();
+        let payload_type = "application/vnd.in-toto+json";
+        serde_json::to_vec(&serde_json::json!({
+            "payloadType": payload_type,
+            "payload": BASE64.encode(&statement),
+            "signatures": [{ "sig": BASE64.encode(key.sign(&pae(payload_type, &statement))) }],
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn pae_matches_the_dsse_spec() {
+        assert_eq!(
+            pae("http://example.com/HelloWorld", b"hello world"),
+            b"DSSEv1 29 http://example.com/HelloWorld 11 hello world"
+        );
+    }
+
+    #[test]
+    fn signature_verifies_with_the_signing_key_only() {
+        let key = SigningKey::generate();
+        let reference = "ghcr.io/example/app:1".parse().unwrap();
+        let payload = super::super::simple_signing_payload(&reference, DIGEST);
+        let annotations = signature_annotations(&key, &payload);
+
+        assert_eq!(
+            check_signature(&payload, &annotations, DIGEST, &keys(&key)),
+            Verification::Verified {
+                key: "cosign.pub".to_string()
+            }
+        );
+        assert_eq!(
+            check_signature(
+                &payload,
+                &annotations,
+                DIGEST,
+                &keys(&SigningKey::generate())
+            ),
+            Verification::Untrusted
+        );
+        assert_eq!(
+            check_signature(&payload, &annotations, DIGEST, &[]),
+            Verification::Untrusted
+        );
+    }
+
+    #[test]
+    fn signature_for_another_digest_is_invalid() {
+        let key = SigningKey::generate();
+        let reference = "ghcr.io/example/app:1".parse().unwrap();
+        let payload = super::super::simple_signing_payload(&reference, "sha256:other");
+        let annotations = signature_annotations(&key, &payload);
+        assert!(matches!(
+            check_signature(&payload, &annotations, DIGEST, &keys(&key)),
+            Verification::Invalid { .. }
+        ));
+        assert!(matches!(
+            check_signature(b"{}", &BTreeMap::new(), DIGEST, &keys(&key)),
+            Verification::Invalid { .. }
+        ));
+    }
+
+    #[test]
+    fn attestation_verifies_over_the_envelope() {
+        let key = SigningKey::generate();
+        let checked = check_attestation(&envelope(&key, "abc123"), DIGEST, &keys(&key));
+        assert_eq!(
+            checked.predicate_type.as_deref(),
+            Some("https://slsa.dev/provenance/v1")
+        );
+        assert!(checked.verification.is_verified());
+
+        let checked = check_attestation(
+            &envelope(&key, "abc123"),
+            DIGEST,
+            &keys(&SigningKey::generate()),
+        );
+        assert_eq!(checked.verification, Verification::Untrusted);
+
+        let checked = check_attestation(&envelope(&key, "other"), DIGEST, &keys(&key));
+        assert!(matches!(checked.verification, Verification::Invalid { .. }));
+    }
+}
@@ -0,0 +1,289 @@
+//! Blobs in the content store, compressed with zstd.
+//!
+//! Blobs are compressed before they are written, unless compressing doesn't
+//! make them smaller. The index entry of a compressed blob records the
+//! encoding and the uncompressed size in its metadata, next to the stored
+//! size cacache keeps. Entries without that metadata, such as blobs stored
+//! before compression was added, hold their content as-is.
+//!
+//! Reads decompress transparently. cacache checks the stored bytes against
+//! their integrity hash, and the decompressed bytes are checked against the
+//! OCI digest the blob is keyed by.
+
+use std::borrow::Cow;
+use std::path::Path;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use cacache::{Algorithm, Integrity, Metadata, WriteOpts};
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncRead, ReadBuf};
+
+/// The zstd compression level; zstd's default, which favours speed.
+const LEVEL: i32 = 3;
+
+/// The index metadata of a compressed blob.
+#[derive(Debug, Serialize, Deserialize)]
+struct Compression {
+    /// The encoding of the stored bytes; always `zstd`.
+    encoding: String,
+    /// The size of the uncompressed content, in bytes.
+    size: usize,
+}
+
+impl Compression {
+    const ZSTD: &str = "zstd";
+
+    /// The compression of the blob `entry` indexes, if it is compressed.
+    fn of(entry: &Metadata) -> Option<Self> {
+        serde_json::from_value::<Self>(entry.metadata.clone())
+            .ok()
+            .filter(|c| c.encoding == Self::ZSTD)
+    }
+}
+
+/// A blob as it is written to the content store.
+#[derive(Debug)]
+pub(crate) struct Encoded<'a> {
+    /// The bytes to store.
+    pub(crate) data: Cow<'a, [u8]>,
+    /// The index metadata recording the compression, if any.
+    pub(crate) metadata: Option<serde_json::Value>,
+}
+
+/// Options that index a blob of `size` stored bytes as `integrity`, with the
+/// `metadata` [`encode`] recorded.
+pub(crate) fn write_opts(
+    integrity: Integrity,
+    size: usize,
+    metadata: Option<&serde_json::Value>,
+) -> WriteOpts {
+    let opts = WriteOpts::new().integrity(integrity).size(size);
+    match metadata {
+        Some(metadata) => opts.metadata(metadata.clone()),
+        None => opts,
+    }
+}
+
+/// Compress `data` for storage, or keep it as-is when that is smaller.
+pub(crate) fn encode(data: &[u8]) -> cacache::Result<Encoded<'_>> {
+    let compressed = zstd::bulk::compress(data, LEVEL)
+        .map_err(|e| cacache::Error::IoError(e, "failed to compress blob".to_string()))?;
+    if compressed.len() >= data.len() {
+        return Ok(Encoded {
+            data: Cow::Borrowed(data),
+            metadata: None,
+        });
+    }
+    let metadata = serde_json::to_value(Compression {
+        encoding: Compression::ZSTD.to_string(),
+        size: data.len(),
+    })
+    .map_err(|e| cacache::Error::SerdeError(e, "failed to record compression".to_string()))?;
+    Ok(Encoded {
+        data: Cow::Owned(compressed),
+        metadata: Some(metadata),
+    })
+}
+
+/// Write `data` to the store under `key`, compressing it.
+pub(crate) async fn write(cache: &Path, key: &str, data: &[u8]) -> cacache::Result<()> {
+    let encoded = encode(data)?;
+    let integrity = cacache::write_hash(cache, &encoded.data).await?;
+    let opts = write_opts(integrity, encoded.data.len(), encoded.metadata.as_ref());
+    cacache::index::insert_async(cache, key, opts).await?;
+    Ok(())
+}
+
+/// Remove the blob stored under `key`: its index entry and its content.
+///
+/// `cacache::remove` only drops the index entry and leaves the content on
+/// disk. Keys are the digests of their content, so no other key shares it.
+pub(crate) async fn remove(cache: &Path, key: &str) -> cacache::Result<()> {
+    match cacache::metadata(cache, key).await? {
+        Some(entry) if cacache::exists(cache, &entry.integrity).await => {
+            cacache::RemoveOpts::new()
+                .remove_fully(true)
+                .remove(cache, key)
+                .await
+        }
+        _ => cacache::remove(cache, key).await,
+    }
+}
+
+/// Read the uncompressed content stored under `key`.
+///
+/// # Errors
+///
+/// Returns [`cacache::Error::EntryNotFound`] if nothing is stored under
+/// `key`, and [`cacache::Error::IntegrityError`] if the stored or
+/// decompressed bytes don't match their hash.
+pub(crate) async fn read(cache: &Path, key: &str) -> cacache::Result<Vec<u8>> {
+    let entry = cacache::metadata(cache, key)
+        .await?
+        .ok_or_else(|| cacache::Error::EntryNotFound(cache.to_path_buf(), key.to_string()))?;
+    let stored = cacache::read_hash(cache, &entry.integrity).await?;
+    decode(&entry, stored)
+}
+
+/// Decompress the `stored` bytes of the blob `entry` indexes.
+fn decode(entry: &Metadata, stored: Vec<u8>) -> cacache::Result<Vec<u8>> {
+    let Some(compression) = Compression::of(entry) else {
+        return Ok(stored);
+    };
+    let data = zstd::bulk::decompress(&stored, compression.size).map_err(|e| {
+        cacache::Error::IoError(e, format!("failed to decompress blob {}", entry.key))
+    })?;
+    if data.len() != compression.size {
+        return Err(cacache::Error::SizeMismatch(compression.size, data.len()));
+    }
+    if let Some(digest) = digest_integrity(&entry.key) {
+        digest.check(&data)?;
+    }
+    Ok(data)
+}
+
+/// The integrity hash of a `sha256:<hex>` OCI digest.
+fn digest_integrity(digest: &str) -> Option<Integrity> {
+    let hex = digest.strip_prefix("sha256:")?;
+    Integrity::from_hex(hex, Algorithm::Sha256).ok()
+}
+
+/// Whether the blob `entry` indexes is stored compressed.
+pub(crate) fn is_compressed(entry: &Metadata) -> bool {
+    Compression::of(entry).is_some()
+}
+
+/// The uncompressed size of the blob `entry` indexes, in bytes.
+pub(crate) fn logical_size(entry: &Metadata) -> usize {
+    Compression::of(entry).map_or(entry.size, |c| c.size)
+}
+
+/// A reader over a stored component's bytes.
+///
+/// Returned by [`Manager::open_component`](crate::manager::Manager::open_component).
+/// Uncompressed blobs are streamed from the store; compressed ones are
+/// decompressed and checked against their digest when the reader is opened.
+pub enum ComponentReader {
+    /// A blob read straight from the store.
+    Stored(cacache::Reader),
+    /// A compressed blob, decompressed into memory.
+    Decompressed(std::io::Cursor<Vec<u8>>),
+}
+
+impl ComponentReader {
+    /// Open the blob stored under `key`.
+    pub(crate) async fn open(cache: &Path, key: &str) -> cacache::Result<Self> {
+        let entry = cacache::metadata(cache, key)
+            .await?
+            .ok_or_else(|| cacache::Error::EntryNotFound(cache.to_path_buf(), key.to_string()))?;
+        if !is_compressed(&entry) {
+            return Ok(Self::Stored(
+                cacache::Reader::open_hash(cache, entry.integrity).await?,
+            ));
+        }
+        let stored = cacache::read_hash(cache, &entry.integrity).await?;
+        Ok(Self::Decompressed(std::io::Cursor::new(decode(
+            &entry, stored,
+        )?)))
+    }
+
+    /// Verify the bytes read against their hash, once the reader is
+    /// exhausted.
+    ///
+    /// Decompressed blobs were verified when the reader was opened.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`cacache::Error::IntegrityError`] if the bytes don't match.
+    pub fn check(self) -> cacache::Result<Option<Algorithm>> {
+        match self {
+            Self::Stored(reader) => reader.check().map(Some),
+            Self::Decompressed(_) => Ok(None),
+        }
+    }
+}
+
+impl std::fmt::Debug for ComponentReader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Stored(_) => "Stored",
+            Self::Decompressed(_) => "Decompressed",
+        };
+        f.debug_tuple(name).finish_non_exhaustive()
+    }
+}
+
+impl AsyncRead for ComponentReader {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        match self.get_mut() {
+            Self::Stored(reader) => Pin::new(reader).poll_read(cx, buf),
+            Self::Decompressed(cursor) => Pin::new(cursor).poll_read(cx, buf),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::oci::sha256_digest;
+
+    #[tokio::test]
+    async fn compressible_blobs_are_stored_compressed() {
+        let dir = tempfile::tempdir().unwrap();
+        let data = vec![7u8; 4096];
+        let key = sha256_digest(&data);
+        write(dir.path(), &key, &data).await.unwrap();
+
+        let entry = cacache::metadata(dir.path(), &key).await.unwrap().unwrap();
+        assert!(is_compressed(&entry));
+        assert!(entry.size < data.len());
+        assert_eq!(logical_size(&entry), data.len());
+        assert_eq!(read(dir.path(), &key).await.unwrap(), data);
+    }
+
+    #[tokio::test]
+    async fn incompressible_blobs_are_stored_as_is() {
+        let dir = tempfile::tempdir().unwrap();
+        let data = b"\0asm";
+        let key = sha256_digest(data);
+        write(dir.path(), &key, data).await.unwrap();
+
+        let entry = cacache::metadata(dir.path(), &key).await.unwrap().unwrap();
+        assert!(!is_compressed(&entry));
+        assert_eq!(logical_size(&entry), data.len());
+        assert_eq!(cacache::read(dir.path(), &key).await.unwrap(), data);
+        assert_eq!(read(dir.path(), &key).await.unwrap(), data);
+    }
+
+    #[tokio::test]
+    async fn decompressed_blobs_are_checked_against_their_digest() {
+        let dir = tempfile::tempdir().unwrap();
+        let data = vec![7u8; 4096];
+        let key = sha256_digest(&[8u8; 4096]);
+        write(dir.path(), &key, &data).await.unwrap();
+
+        let err = read(dir.path(), &key).await.unwrap_err();
+        assert!(matches!(err, cacache::Error::IntegrityError(_)), "{err}");
+    }
+
+    #[tokio::test]
+    async fn component_reader_decompresses() {
+        use tokio::io::AsyncReadExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let data = vec![7u8; 4096];
+        let key = sha256_digest(&data);
+        write(dir.path(), &key, &data).await.unwrap();
+
+        let mut reader = ComponentReader::open(dir.path(), &key).await.unwrap();
+        let mut read_back = Vec::new();
+        reader.read_to_end(&mut read_back).await.unwrap();
+        assert_eq!(read_back, data);
+        reader.check().unwrap();
+    }
+}
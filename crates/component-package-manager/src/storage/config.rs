@@ -2,8 +2,10 @@ use anyhow::Context;
 use std::env;
 use std::path::{Path, PathBuf};
 
-use super::models::Migrations;
-use crate::xdg_config_home;
+use super::dedup::DedupStats;
+use super::metrics::PullMetrics;
+use super::models::{AppliedMigration, Migrations};
+use crate::{Config, DATA_DIR_VAR};
 
 /// Information about the current state of the package manager.
 ///
@@ -44,6 +46,12 @@ pub struct StateInfo {
     migration_current: u32,
     /// Total number of migrations available
     migration_total: u32,
+    /// Applied migrations with their timestamps, oldest first
+    migration_history: Vec<AppliedMigration>,
+    /// Layer deduplication statistics for the content store
+    dedup_stats: DedupStats,
+    /// Cumulative pull statistics
+    pull_metrics: PullMetrics,
 }
 
 impl StateInfo {
@@ -53,13 +61,10 @@ impl StateInfo {
         store_size: u64,
         metadata_size: u64,
     ) -> anyhow::Result<Self> {
-        let data_dir = dirs::data_local_dir()
-            .context("No local data dir known for the current OS")?
-            .join("wasm");
-        let config_file = xdg_config_home()
-            .context("Could not determine config directory (set $XDG_CONFIG_HOME or $HOME)")?
-            .join("wasm")
-            .join("config.toml");
+        let data_dir =
+            Self::default_data_dir().context("No local data dir known for the current OS")?;
+        let config_file = Config::config_path()
+            .context("Could not determine config directory (set $XDG_CONFIG_HOME or $HOME)")?;
         Ok(Self::new_at(
             data_dir,
             config_file,
@@ -88,6 +93,9 @@ impl StateInfo {
             data_dir,
             migration_current: migration_info.current,
             migration_total: migration_info.total,
+            migration_history: Vec::new(),
+            dedup_stats: DedupStats::default(),
+            pull_metrics: PullMetrics::default(),
         }
     }
 
@@ -101,6 +109,43 @@ impl StateInfo {
         self
     }
 
+    /// Attach layer deduplication statistics.
+    ///
+    /// By default, [`new_at`](Self::new_at) reports an empty store.
+    #[must_use]
+    pub fn with_dedup_stats(mut self, dedup_stats: DedupStats) -> Self {
+        self.dedup_stats = dedup_stats;
+        self
+    }
+
+    /// Attach cumulative pull statistics.
+    ///
+    /// By default, [`new_at`](Self::new_at) reports no pulls.
+    #[must_use]
+    pub fn with_pull_metrics(mut self, pull_metrics: PullMetrics) -> Self {
+        self.pull_metrics = pull_metrics;
+        self
+    }
+
+    /// Attach the applied migration history.
+    ///
+    /// By default, [`new_at`](Self::new_at) reports no applied migrations.
+    #[must_use]
+    pub fn with_migration_history(mut self, migration_history: Vec<AppliedMigration>) -> Self {
+        self.migration_history = migration_history;
+        self
+    }
+
+    /// Point the data, store, and metadata paths at `data_dir`, after the
+    /// store moved there.
+    #[must_use]
+    pub(crate) fn with_data_dir(mut self, data_dir: PathBuf) -> Self {
+        self.store_dir = data_dir.join("store");
+        self.metadata_file = data_dir.join("db").join("metadata.db3");
+        self.data_dir = data_dir;
+        self
+    }
+
     /// Get the path to the current executable
     #[must_use]
     pub fn executable(&self) -> &Path {
@@ -155,6 +200,24 @@ impl StateInfo {
         self.migration_total
     }
 
+    /// Get the applied migrations with their timestamps, oldest first
+    #[must_use]
+    pub fn migration_history(&self) -> &[AppliedMigration] {
+        &self.migration_history
+    }
+
+    /// Get the layer deduplication statistics for the content store
+    #[must_use]
+    pub fn dedup_stats(&self) -> &DedupStats {
+        &self.dedup_stats
+    }
+
+    /// Get the cumulative pull statistics
+    #[must_use]
+    pub fn pull_metrics(&self) -> &PullMetrics {
+        &self.pull_metrics
+    }
+
     /// Get the log directory for the application.
     ///
     /// Uses the XDG state directory (`$XDG_STATE_HOME/wasm/logs`) on Linux,
@@ -178,6 +241,29 @@ impl StateInfo {
             |p| p.join("wasm").join("logs"),
         )
     }
+
+    /// Compute the default data directory for the application without an
+    /// instance: [`DATA_DIR_VAR`] when set, and otherwise
+    /// `$XDG_DATA_HOME/wasm` on Linux.
+    ///
+    /// This is where the store lives unless it was relocated, in which case
+    /// the directory only holds a pointer to the new location.
+    #[must_use]
+    pub fn default_data_dir() -> Option<PathBuf> {
+        crate::config::env_var(DATA_DIR_VAR)
+            .map(PathBuf::from)
+            .or_else(Self::xdg_data_dir)
+    }
+
+    /// The data directory used when [`DATA_DIR_VAR`] isn't set:
+    /// `$XDG_DATA_HOME/wasm` on Linux.
+    ///
+    /// Unlike a directory chosen through [`DATA_DIR_VAR`] or a relocation,
+    /// this one only ever holds the store.
+    #[must_use]
+    pub fn xdg_data_dir() -> Option<PathBuf> {
+        dirs::data_local_dir().map(|p| p.join("wasm"))
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,203 @@
+use rusqlite::Connection;
+
+use super::image_ref::{image_reference, latest_tag_sql};
+
+/// How many of the top space consumers [`DedupStats`] reports.
+const TOP_CONSUMERS: u32 = 5;
+
+/// Layer deduplication statistics for the content store.
+///
+/// Layers are stored once per digest, so images that share a layer only pay
+/// for it once. `logical_size` is what the store would take without sharing;
+/// `physical_size` is what it actually takes.
+///
+/// # Example
+///
+/// ```
+/// use component_package_manager::storage::DedupStats;
+///
+/// let stats = DedupStats {
+///     total_layers: 4,
+///     unique_layers: 3,
+///     shared_layers: 1,
+///     logical_size: 4096,
+///     physical_size: 3072,
+///     top_consumers: vec![],
+/// };
+/// assert_eq!(stats.saved_size(), 1024);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DedupStats {
+    /// Number of layer references across all stored manifests.
+    pub total_layers: u64,
+    /// Number of distinct layer digests.
+    pub unique_layers: u64,
+    /// Number of distinct layer digests referenced by more than one manifest.
+    pub shared_layers: u64,
+    /// Sum of layer sizes as referenced by each manifest, in bytes.
+    pub logical_size: u64,
+    /// Sum of layer sizes counting each digest once, in bytes.
+    pub physical_size: u64,
+    /// The images referencing the most layer bytes, largest first.
+    pub top_consumers: Vec<SpaceConsumer>,
+}
+
+impl DedupStats {
+    /// Bytes saved by sharing layers across images.
+    #[must_use]
+    pub fn saved_size(&self) -> u64 {
+        self.logical_size.saturating_sub(self.physical_size)
+    }
+
+    /// Compute deduplication statistics from the layer table.
+    pub(crate) fn query(conn: &Connection) -> anyhow::Result<Self> {
+        let (total_layers, logical_size): (i64, i64) = conn.query_row(
+            "SELECT COUNT(*), COALESCE(SUM(size_bytes), 0) FROM oci_layer",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+
+        let (unique_layers, shared_layers, physical_size): (i64, i64, i64) = conn.query_row(
+            "SELECT COUNT(*),
+                    COALESCE(SUM(refs > 1), 0),
+                    COALESCE(SUM(size), 0)
+             FROM (
+                 SELECT COUNT(DISTINCT oci_manifest_id) AS refs,
+                        MAX(COALESCE(size_bytes, 0)) AS size
+                 FROM oci_layer
+                 GROUP BY digest
+             )",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )?;
+
+        let mut stmt = conn.prepare_cached(&format!(
+            "SELECT r.registry, r.repository, m.digest,
+                    {} AS tag,
+                    SUM(COALESCE(l.size_bytes, 0)) AS size
+             FROM oci_layer l
+             JOIN oci_manifest m ON m.id = l.oci_manifest_id
+             JOIN oci_repository r ON r.id = m.oci_repository_id
+             GROUP BY m.id
+             ORDER BY size DESC, r.registry ASC, r.repository ASC
+             LIMIT ?1",
+            latest_tag_sql("r.id", "m.digest")
+        ))?;
+        let rows = stmt.query_map([TOP_CONSUMERS], |row| {
+            let registry: String = row.get(0)?;
+            let repository: String = row.get(1)?;
+            let digest: String = row.get(2)?;
+            let tag: Option<String> = row.get(3)?;
+            let size: i64 = row.get(4)?;
+            Ok(SpaceConsumer {
+                reference: image_reference(&registry, &repository, &digest, tag.as_deref()),
+                size: to_u64(size),
+            })
+        })?;
+        let mut top_consumers = Vec::new();
+        for row in rows {
+            top_consumers.push(row?);
+        }
+
+        Ok(Self {
+            total_layers: to_u64(total_layers),
+            unique_layers: to_u64(unique_layers),
+            shared_layers: to_u64(shared_layers),
+            logical_size: to_u64(logical_size),
+            physical_size: to_u64(physical_size),
+            top_consumers,
+        })
+    }
+}
+
+/// A stored image and the layer bytes it references.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpaceConsumer {
+    /// The image reference, e.g. `ghcr.io/example/app:1.0.0`.
+    pub reference: String,
+    /// Total size of the image's layers in bytes, including shared layers.
+    pub size: u64,
+}
+
+fn to_u64(value: i64) -> u64 {
+    u64::try_from(value).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::oci::{OciLayer, OciManifest, OciRepository, OciTag};
+    use crate::storage::Migrations;
+    use std::collections::HashMap;
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        Migrations::run_all(&conn).unwrap();
+        conn
+    }
+
+    /// Store a tagged image in `repository` with the given `(digest, size)` layers.
+    fn insert_image(conn: &Connection, repository: &str, layers: &[(&str, i64)]) {
+        let repo_id = OciRepository::upsert(conn, "ghcr.io", repository).unwrap();
+        let digest = format!("sha256:{repository}");
+        let (mid, _) = OciManifest::upsert(
+            conn,
+            repo_id,
+            &digest,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            &HashMap::new(),
+        )
+        .unwrap();
+        OciTag::upsert(conn, repo_id, "1.0.0", &digest).unwrap();
+        for (position, (layer, size)) in (0i32..).zip(layers) {
+            OciLayer::insert(conn, mid, layer, None, Some(*size), position).unwrap();
+        }
+    }
+
+    #[test]
+    fn empty_store_has_no_layers() {
+        let conn = setup_test_db();
+        assert_eq!(DedupStats::query(&conn).unwrap(), DedupStats::default());
+    }
+
+    #[test]
+    fn shared_layers_are_counted_once() {
+        let conn = setup_test_db();
+        insert_image(
+            &conn,
+            "example/a",
+            &[("sha256:base", 100), ("sha256:a", 10)],
+        );
+        insert_image(
+            &conn,
+            "example/b",
+            &[("sha256:base", 100), ("sha256:b", 30)],
+        );
+
+        let stats = DedupStats::query(&conn).unwrap();
+        assert_eq!(stats.total_layers, 4);
+        assert_eq!(stats.unique_layers, 3);
+        assert_eq!(stats.shared_layers, 1);
+        assert_eq!(stats.logical_size, 240);
+        assert_eq!(stats.physical_size, 140);
+        assert_eq!(stats.saved_size(), 100);
+        assert_eq!(
+            stats.top_consumers,
+            vec![
+                SpaceConsumer {
+                    reference: "ghcr.io/example/b:1.0.0".to_string(),
+                    size: 130,
+                },
+                SpaceConsumer {
+                    reference: "ghcr.io/example/a:1.0.0".to_string(),
+                    size: 110,
+                },
+            ]
+        );
+    }
+}
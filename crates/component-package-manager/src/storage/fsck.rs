@@ -0,0 +1,594 @@
+//! Integrity checks between the metadata database and the blob store.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use rusqlite::Connection;
+
+use super::image_ref::{image_reference, latest_tag_sql};
+use crate::oci::OciManifest;
+
+/// How [`Manager::fsck`](crate::manager::Manager::fsck) handles the problems
+/// it finds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FsckMode {
+    /// Only report problems; change nothing.
+    #[default]
+    Check,
+    /// Delete broken images, orphaned WIT rows, and unreferenced blobs.
+    Prune,
+    /// Like [`Prune`](Self::Prune), but pull broken images again afterwards.
+    Repull,
+}
+
+/// A single inconsistency between the database and the blob store.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FsckProblem {
+    /// A layer is recorded in the database but its blob is missing.
+    MissingLayer {
+        /// The image the layer belongs to.
+        reference: String,
+        /// The layer digest.
+        digest: String,
+    },
+    /// A layer's blob differs in size from what its manifest declares.
+    SizeMismatch {
+        /// The image the layer belongs to.
+        reference: String,
+        /// The layer digest.
+        digest: String,
+        /// The size recorded in the manifest, in bytes.
+        expected: u64,
+        /// The size of the stored blob, in bytes.
+        actual: u64,
+    },
+    /// A WIT package extracted from an image that is no longer stored.
+    OrphanWitPackage {
+        /// The WIT package name, e.g. `wasi:http`.
+        package: String,
+        /// The WIT package version, if any.
+        version: Option<String>,
+    },
+    /// A blob in the content store that no layer or config references.
+    OrphanBlob {
+        /// The blob's cache key (its digest).
+        digest: String,
+    },
+    /// A content file that no blob's index entry points at, left behind
+    /// when its entry was removed.
+    OrphanContent {
+        /// The content's hash, e.g. `sha256:0a1b…`.
+        hash: String,
+    },
+}
+
+impl FsckProblem {
+    /// The image to pull again to fix this problem, if any.
+    #[must_use]
+    pub fn broken_image(&self) -> Option<&str> {
+        match self {
+            Self::MissingLayer { reference, .. } | Self::SizeMismatch { reference, .. } => {
+                Some(reference)
+            }
+            Self::OrphanWitPackage { .. }
+            | Self::OrphanBlob { .. }
+            | Self::OrphanContent { .. } => None,
+        }
+    }
+}
+
+impl std::fmt::Display for FsckProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingLayer { reference, digest } => {
+                write!(f, "{reference}: layer {digest} is missing from the store")
+            }
+            Self::SizeMismatch {
+                reference,
+                digest,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "{reference}: layer {digest} is {actual} bytes, expected {expected}"
+            ),
+            Self::OrphanWitPackage {
+                package,
+                version: Some(version),
+            } => write!(f, "WIT package {package}@{version} has no stored image"),
+            Self::OrphanWitPackage {
+                package,
+                version: None,
+            } => write!(f, "WIT package {package} has no stored image"),
+            Self::OrphanBlob { digest } => {
+                write!(f, "blob {digest} is not referenced by any image")
+            }
+            Self::OrphanContent { hash } => {
+                write!(f, "content {hash} is not referenced by any blob")
+            }
+        }
+    }
+}
+
+/// A problem found by fsck and whether it was repaired.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FsckIssue {
+    /// What is wrong.
+    pub problem: FsckProblem,
+    /// Whether the problem was repaired during this run.
+    pub repaired: bool,
+}
+
+/// Result of [`Manager::fsck`](crate::manager::Manager::fsck).
+///
+/// # Example
+///
+/// ```
+/// use component_package_manager::storage::FsckReport;
+///
+/// let report = FsckReport::default();
+/// assert!(report.is_clean());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FsckReport {
+    /// Number of layer rows checked against the blob store.
+    pub layers_checked: u64,
+    /// Number of blobs checked against the layer rows.
+    pub blobs_checked: u64,
+    /// Every problem found, in the order it was found.
+    pub issues: Vec<FsckIssue>,
+}
+
+impl FsckReport {
+    /// Returns `true` when no problems were found.
+    #[must_use]
+    pub fn is_clean(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Returns `true` when every problem found was repaired.
+    #[must_use]
+    pub fn is_repaired(&self) -> bool {
+        self.issues.iter().all(|issue| issue.repaired)
+    }
+}
+
+/// A layer row joined with the image it belongs to.
+struct LayerRow {
+    manifest_id: i64,
+    reference: String,
+    digest: String,
+    size_bytes: Option<i64>,
+}
+
+/// Check every layer row, WIT row, blob, and content file, optionally
+/// pruning what is broken.
+///
+/// Images with a missing or wrong-sized layer are deleted outright (FK
+/// cascades clear their layers, tags, and extracted metadata) so they can be
+/// pulled again cleanly.
+///
+/// Removing a blob's index entry leaves its content on disk, and other
+/// entries may share that content, so content files are swept last: any
+/// that no remaining entry points at is deleted.
+pub(crate) async fn run(
+    conn: &Connection,
+    store_dir: &Path,
+    repair: bool,
+) -> anyhow::Result<FsckReport> {
+    let mut report = FsckReport::default();
+    let mut broken_manifests: HashSet<i64> = HashSet::new();
+    let mut bad_blobs: Vec<String> = Vec::new();
+    // Content whose index entry this run removes (or would remove), so the
+    // sweep doesn't report it a second time.
+    let mut dropped: HashSet<String> = HashSet::new();
+
+    for layer in list_layers(conn)? {
+        report.layers_checked += 1;
+
+        let problem = match cacache::metadata(store_dir, &layer.digest).await? {
+            Some(meta) if cacache::exists(store_dir, &meta.integrity).await => {
+                let actual = u64::try_from(super::blob::logical_size(&meta)).unwrap_or(u64::MAX);
+                match layer.size_bytes.and_then(|s| u64::try_from(s).ok()) {
+                    Some(expected) if expected != actual => {
+                        bad_blobs.push(layer.digest.clone());
+                        FsckProblem::SizeMismatch {
+                            reference: layer.reference,
+                            digest: layer.digest,
+                            expected,
+                            actual,
+                        }
+                    }
+                    _ => continue,
+                }
+            }
+            _ => FsckProblem::MissingLayer {
+                reference: layer.reference,
+                digest: layer.digest,
+            },
+        };
+        broken_manifests.insert(layer.manifest_id);
+        report.issues.push(FsckIssue {
+            problem,
+            repaired: repair,
+        });
+    }
+
+    // Deleting a broken image orphans its WIT rows and any blobs no other
+    // image shares, so prune it first and let the passes below clean up.
+    if repair {
+        for id in broken_manifests {
+            OciManifest::delete(conn, id)?;
+        }
+        for digest in &bad_blobs {
+            if let Some(meta) = cacache::metadata(store_dir, digest).await? {
+                dropped.insert(content_name(&meta.integrity));
+            }
+            cacache::remove(store_dir, digest).await?;
+        }
+    }
+
+    for (id, package, version) in list_orphan_wit_packages(conn)? {
+        if repair {
+            conn.execute("DELETE FROM wit_package WHERE id = ?1", [id])?;
+        }
+        report.issues.push(FsckIssue {
+            problem: FsckProblem::OrphanWitPackage { package, version },
+            repaired: repair,
+        });
+    }
+
+    let referenced = list_blob_digests(conn)?;
+    let mut kept: HashSet<String> = HashSet::new();
+    for entry in cacache::index::ls(store_dir) {
+        let entry = match entry {
+            Ok(entry) => entry,
+            // A store nothing was ever written to has no index yet.
+            Err(cacache::Error::IoError(err, _)) if err.kind() == std::io::ErrorKind::NotFound => {
+                break;
+            }
+            Err(err) => return Err(err.into()),
+        };
+        report.blobs_checked += 1;
+        if referenced.contains(&entry.key) {
+            kept.insert(content_name(&entry.integrity));
+            continue;
+        }
+        dropped.insert(content_name(&entry.integrity));
+        if repair {
+            cacache::remove(store_dir, &entry.key).await?;
+        }
+        report.issues.push(FsckIssue {
+            problem: FsckProblem::OrphanBlob { digest: entry.key },
+            repaired: repair,
+        });
+    }
+
+    for (path, name) in list_content(store_dir).await? {
+        if kept.contains(&name) {
+            continue;
+        }
+        if repair {
+            tokio::fs::remove_file(&path).await?;
+        }
+        if dropped.contains(&name) {
+            continue;
+        }
+        report.issues.push(FsckIssue {
+            problem: FsckProblem::OrphanContent {
+                hash: name.replacen('/', ":", 1),
+            },
+            repaired: repair,
+        });
+    }
+
+    Ok(report)
+}
+
+/// The `algorithm/hex` name of a content file, as it is laid out under the
+/// store's `content-v2` directory.
+fn content_name(integrity: &cacache::Integrity) -> String {
+    let (algorithm, hex) = integrity.to_hex();
+    format!("{algorithm}/{hex}")
+}
+
+/// List every content file in the store along with its
+/// [`content_name`].
+///
+/// Files are sharded as `content-v2/<algorithm>/<hex[..2]>/<hex[2..4]>/<hex[4..]>`.
+async fn list_content(store_dir: &Path) -> anyhow::Result<Vec<(PathBuf, String)>> {
+    let root = store_dir.join("content-v2");
+    let mut result = Vec::new();
+    let mut stack = vec![root.clone()];
+    while let Some(dir) = stack.pop() {
+        let mut entries = match tokio::fs::read_dir(&dir).await {
+            Ok(entries) => entries,
+            // A store nothing was ever written to has no content yet.
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(err) => return Err(err.into()),
+        };
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if entry.file_type().await?.is_dir() {
+                stack.push(path);
+                continue;
+            }
+            let Ok(relative) = path.strip_prefix(&root) else {
+                continue;
+            };
+            let parts: Vec<_> = relative
+                .components()
+                .filter_map(|c| c.as_os_str().to_str())
+                .collect();
+            if let [algorithm, a, b, rest] = parts.as_slice() {
+                let name = format!("{algorithm}/{a}{b}{rest}");
+                result.push((path, name));
+            }
+        }
+    }
+    result.sort();
+    Ok(result)
+}
+
+/// List every layer row along with a printable reference for its image.
+fn list_layers(conn: &Connection) -> anyhow::Result<Vec<LayerRow>> {
+    let mut stmt = conn.prepare_cached(&format!(
+        "SELECT l.oci_manifest_id, r.registry, r.repository, m.digest, {},
+                l.digest, l.size_bytes
+         FROM oci_layer l
+         JOIN oci_manifest m ON m.id = l.oci_manifest_id
+         JOIN oci_repository r ON r.id = m.oci_repository_id
+         ORDER BY r.registry, r.repository, m.id, l.position",
+        latest_tag_sql("r.id", "m.digest")
+    ))?;
+    let rows = stmt.query_map([], |row| {
+        let registry: String = row.get(1)?;
+        let repository: String = row.get(2)?;
+        let manifest_digest: String = row.get(3)?;
+        let tag: Option<String> = row.get(4)?;
+        Ok(LayerRow {
+            manifest_id: row.get(0)?,
+            reference: image_reference(&registry, &repository, &manifest_digest, tag.as_deref()),
+            digest: row.get(5)?,
+            size_bytes: row.get(6)?,
+        })
+    })?;
+    let mut result = Vec::new();
+    for row in rows {
+        result.push(row?);
+    }
+    Ok(result)
+}
+
+/// List the distinct digests of every stored layer and config blob.
+fn list_blob_digests(conn: &Connection) -> anyhow::Result<HashSet<String>> {
+    let mut stmt = conn.prepare_cached(
+        "SELECT digest FROM oci_layer
+         UNION SELECT config_digest FROM oci_manifest WHERE config_digest IS NOT NULL",
+    )?;
+    let rows = stmt.query_map([], |row| row.get(0))?;
+    let mut result = HashSet::new();
+    for row in rows {
+        result.insert(row?);
+    }
+    Ok(result)
+}
+
+/// List WIT packages that were extracted from an image which has since been
+/// deleted.
+///
+/// Rows synced from a meta-registry never have WIT text or an image, so
+/// they are not reported.
+fn list_orphan_wit_packages(
+    conn: &Connection,
+) -> anyhow::Result<Vec<(i64, String, Option<String>)>> {
+    let mut stmt = conn.prepare_cached(
+        "SELECT id, package_name, version FROM wit_package
+         WHERE oci_manifest_id IS NULL AND wit_text IS NOT NULL
+         ORDER BY package_name, version",
+    )?;
+    let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?;
+    let mut result = Vec::new();
+    for row in rows {
+        result.push(row?);
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::oci::{OciLayer, OciRepository, OciTag};
+    use crate::storage::Migrations;
+    use crate::types::RawWitPackage;
+    use std::collections::HashMap;
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        Migrations::run_all(&conn).unwrap();
+        conn
+    }
+
+    /// Store a tagged image in `repository` with the given `(digest, size)` layers.
+    fn insert_image(conn: &Connection, repository: &str, layers: &[(&str, i64)]) -> i64 {
+        let repo_id = OciRepository::upsert(conn, "ghcr.io", repository).unwrap();
+        let digest = format!("sha256:{repository}");
+        let (mid, _) = OciManifest::upsert(
+            conn,
+            repo_id,
+            &digest,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            &HashMap::new(),
+        )
+        .unwrap();
+        OciTag::upsert(conn, repo_id, "1.0.0", &digest).unwrap();
+        for (position, (layer, size)) in (0i32..).zip(layers) {
+            OciLayer::insert(conn, mid, layer, None, Some(*size), position).unwrap();
+        }
+        mid
+    }
+
+    #[tokio::test]
+    async fn clean_store_has_no_issues() {
+        let conn = setup_test_db();
+        let dir = tempfile::tempdir().unwrap();
+        insert_image(&conn, "example/ok", &[("sha256:ok", 3)]);
+        cacache::write(dir.path(), "sha256:ok", b"abc")
+            .await
+            .unwrap();
+
+        let report = run(&conn, dir.path(), false).await.unwrap();
+        assert!(report.is_clean());
+        assert_eq!(report.layers_checked, 1);
+        assert_eq!(report.blobs_checked, 1);
+    }
+
+    #[tokio::test]
+    async fn empty_store_has_no_issues() {
+        let conn = setup_test_db();
+        let dir = tempfile::tempdir().unwrap();
+
+        let report = run(&conn, dir.path(), true).await.unwrap();
+        assert!(report.is_clean());
+        assert_eq!(report.blobs_checked, 0);
+    }
+
+    #[tokio::test]
+    async fn finds_and_prunes_every_kind_of_problem() {
+        let conn = setup_test_db();
+        let dir = tempfile::tempdir().unwrap();
+        insert_image(&conn, "example/ok", &[("sha256:ok", 3)]);
+        insert_image(&conn, "example/missing", &[("sha256:missing", 3)]);
+        insert_image(&conn, "example/short", &[("sha256:short", 10)]);
+        RawWitPackage::insert(
+            &conn,
+            "example:gone",
+            Some("1.0.0"),
+            None,
+            Some("package example:gone;"),
+            None,
+            None,
+        )
+        .unwrap();
+        cacache::write(dir.path(), "sha256:ok", b"abc")
+            .await
+            .unwrap();
+        cacache::write(dir.path(), "sha256:short", b"abc")
+            .await
+            .unwrap();
+        cacache::write(dir.path(), "sha256:stray", b"abc")
+            .await
+            .unwrap();
+
+        let report = run(&conn, dir.path(), false).await.unwrap();
+        let problems: Vec<_> = report.issues.iter().map(|i| i.problem.clone()).collect();
+        assert_eq!(
+            problems,
+            vec![
+                FsckProblem::MissingLayer {
+                    reference: "ghcr.io/example/missing:1.0.0".to_string(),
+                    digest: "sha256:missing".to_string(),
+                },
+                FsckProblem::SizeMismatch {
+                    reference: "ghcr.io/example/short:1.0.0".to_string(),
+                    digest: "sha256:short".to_string(),
+                    expected: 10,
+                    actual: 3,
+                },
+                FsckProblem::OrphanWitPackage {
+                    package: "example:gone".to_string(),
+                    version: Some("1.0.0".to_string()),
+                },
+                FsckProblem::OrphanBlob {
+                    digest: "sha256:stray".to_string(),
+                },
+            ]
+        );
+        assert!(!report.is_repaired());
+
+        let report = run(&conn, dir.path(), true).await.unwrap();
+        assert_eq!(report.issues.len(), 4);
+        assert!(report.is_repaired());
+
+        let report = run(&conn, dir.path(), false).await.unwrap();
+        assert!(report.is_clean(), "unexpected issues: {:?}", report.issues);
+        assert_eq!(report.layers_checked, 1);
+    }
+
+    #[tokio::test]
+    async fn prune_removes_content_no_entry_points_at() {
+        let conn = setup_test_db();
+        let dir = tempfile::tempdir().unwrap();
+        insert_image(&conn, "example/ok", &[("sha256:ok", 3)]);
+        cacache::write(dir.path(), "sha256:ok", b"abc")
+            .await
+            .unwrap();
+        let stray = cacache::write(dir.path(), "sha256:stray", b"stray")
+            .await
+            .unwrap();
+        // Removing only the index entry leaves the content behind.
+        let gone = cacache::write(dir.path(), "sha256:gone", b"gone")
+            .await
+            .unwrap();
+        cacache::remove(dir.path(), "sha256:gone").await.unwrap();
+
+        let report = run(&conn, dir.path(), false).await.unwrap();
+        let (algorithm, hex) = gone.to_hex();
+        let problems: Vec<_> = report.issues.iter().map(|i| i.problem.clone()).collect();
+        assert_eq!(
+            problems,
+            vec![
+                FsckProblem::OrphanBlob {
+                    digest: "sha256:stray".to_string(),
+                },
+                FsckProblem::OrphanContent {
+                    hash: format!("{algorithm}:{hex}"),
+                },
+            ]
+        );
+
+        let report = run(&conn, dir.path(), true).await.unwrap();
+        assert_eq!(report.issues.len(), 2);
+        assert!(!cacache::exists(dir.path(), &stray).await);
+        assert!(!cacache::exists(dir.path(), &gone).await);
+        assert_eq!(
+            cacache::read(dir.path(), "sha256:ok").await.unwrap(),
+            b"abc"
+        );
+
+        let report = run(&conn, dir.path(), false).await.unwrap();
+        assert!(report.is_clean(), "unexpected issues: {:?}", report.issues);
+    }
+
+    #[tokio::test]
+    async fn config_blobs_are_not_orphans() {
+        let conn = setup_test_db();
+        let dir = tempfile::tempdir().unwrap();
+        let repo_id = OciRepository::upsert(&conn, "ghcr.io", "example/app").unwrap();
+        OciManifest::upsert(
+            &conn,
+            repo_id,
+            "sha256:manifest",
+            None,
+            None,
+            None,
+            None,
+            Some("application/vnd.wasm.config.v0+json"),
+            Some("sha256:config"),
+            &HashMap::new(),
+        )
+        .unwrap();
+        cacache::write(dir.path(), "sha256:config", b"{}")
+            .await
+            .unwrap();
+
+        let report = run(&conn, dir.path(), false).await.unwrap();
+        assert!(report.is_clean(), "unexpected issues: {:?}", report.issues);
+        assert_eq!(report.blobs_checked, 1);
+    }
+}
@@ -0,0 +1,30 @@
+//! Printable references for stored images, shared by the queries that list
+//! them.
+
+/// A SQL subquery for the most recently updated tag that points at the
+/// manifest `digest` in the repository with id `repository_id`, or `NULL`
+/// if none does. Both arguments are column expressions, e.g. `r.id` and
+/// `m.digest`.
+pub(crate) fn latest_tag_sql(repository_id: &str, digest: &str) -> String {
+    format!(
+        "(SELECT t.tag FROM oci_tag t
+           WHERE t.oci_repository_id = {repository_id}
+             AND t.manifest_digest = {digest}
+           ORDER BY t.updated_at DESC, t.id DESC
+           LIMIT 1)"
+    )
+}
+
+/// The reference of a stored image: by `tag` when it has one, and by its
+/// manifest `digest` otherwise.
+pub(crate) fn image_reference(
+    registry: &str,
+    repository: &str,
+    digest: &str,
+    tag: Option<&str>,
+) -> String {
+    match tag {
+        Some(tag) => format!("{registry}/{repository}:{tag}"),
+        None => format!("{registry}/{repository}@{digest}"),
+    }
+}
@@ -44,6 +44,7 @@ impl From<RawKnownPackage> for KnownPackage {
             wit_namespace: pkg.wit_namespace,
             wit_name: pkg.wit_name,
             dependencies: vec![],
+            pinned: pkg.pinned,
         }
     }
 }
@@ -0,0 +1,111 @@
+//! Advisory cross-process lock around mutating store operations.
+//!
+//! SQLite already serializes database writes, but inserting and deleting an
+//! image also touches the content-addressable blob store. Two processes
+//! sharing a data directory (e.g. parallel CI jobs) could otherwise remove a
+//! blob that the other has just recorded.
+
+use std::fs::{File, OpenOptions};
+use std::io::{Seek, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::manager::ManagerError;
+
+/// How long to wait for another process to release the store lock.
+const LOCK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How often to retry while another process holds the lock.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// An exclusive advisory lock on the store, released when dropped.
+///
+/// The lock file holds the PID of the current owner so a waiting process can
+/// report who is blocking it. The operating system releases the lock when
+/// the owning process exits, so a crash never leaves the store locked.
+#[derive(Debug)]
+pub(crate) struct StoreLock {
+    file: File,
+}
+
+impl StoreLock {
+    /// Acquire the lock at `path`, waiting up to [`LOCK_TIMEOUT`] for another
+    /// process to release it.
+    pub(crate) async fn acquire(path: &Path) -> anyhow::Result<Self> {
+        Self::acquire_within(path, LOCK_TIMEOUT).await
+    }
+
+    async fn acquire_within(path: &Path, timeout: Duration) -> anyhow::Result<Self> {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+
+        let deadline = Instant::now() + timeout;
+        loop {
+            match file.try_lock() {
+                Ok(()) => break,
+                Err(std::fs::TryLockError::WouldBlock) if Instant::now() < deadline => {
+                    tokio::time::sleep(POLL_INTERVAL).await;
+                }
+                Err(std::fs::TryLockError::WouldBlock) => {
+                    return Err(ManagerError::StoreLocked {
+                        pid: read_pid(path),
+                    }
+                    .into());
+                }
+                Err(std::fs::TryLockError::Error(e)) => return Err(e.into()),
+            }
+        }
+
+        file.set_len(0)?;
+        file.rewind()?;
+        write!(file, "{}", std::process::id())?;
+        file.flush()?;
+        tracing::debug!(path = %path.display(), "acquired store lock");
+        Ok(Self { file })
+    }
+}
+
+impl Drop for StoreLock {
+    fn drop(&mut self) {
+        // Clear the PID so a stale value is never reported, then unlock.
+        // Both are best-effort: closing the file releases the lock anyway.
+        let _ = self.file.set_len(0);
+        let _ = self.file.unlock();
+    }
+}
+
+/// Read the owner's PID from a lock file held by another process.
+fn read_pid(path: &Path) -> Option<u32> {
+    std::fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn second_lock_reports_owner_pid() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("store.lock");
+
+        let held = StoreLock::acquire(&path).await.unwrap();
+        let err = StoreLock::acquire_within(&path, Duration::ZERO)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<ManagerError>(),
+            Some(&ManagerError::StoreLocked {
+                pid: Some(std::process::id()),
+            })
+        );
+
+        drop(held);
+        StoreLock::acquire_within(&path, Duration::ZERO)
+            .await
+            .unwrap();
+    }
+}
@@ -0,0 +1,128 @@
+use std::time::Duration;
+
+use rusqlite::Connection;
+
+/// Cumulative statistics about pulls from registries, for judging how well
+/// the local cache is working.
+///
+/// A pull is a cache hit when the image was already stored, so its layers
+/// did not have to be downloaded again.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use component_package_manager::storage::PullMetrics;
+///
+/// let metrics = PullMetrics {
+///     pulls: 4,
+///     cache_hits: 3,
+///     bytes_downloaded: 2048,
+///     total_pull_time: Duration::from_secs(2),
+/// };
+/// assert_eq!(metrics.cache_misses(), 1);
+/// assert_eq!(metrics.hit_ratio(), Some(0.75));
+/// assert_eq!(metrics.average_pull_time(), Some(Duration::from_millis(500)));
+/// assert_eq!(PullMetrics::default().hit_ratio(), None);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PullMetrics {
+    /// Number of completed pulls.
+    pub pulls: u64,
+    /// Number of pulls of images that were already stored.
+    pub cache_hits: u64,
+    /// Total layer bytes downloaded from registries.
+    pub bytes_downloaded: u64,
+    /// Total wall-clock time spent pulling.
+    pub total_pull_time: Duration,
+}
+
+impl PullMetrics {
+    /// Number of pulls that had to store a new image.
+    #[must_use]
+    pub fn cache_misses(&self) -> u64 {
+        self.pulls.saturating_sub(self.cache_hits)
+    }
+
+    /// Fraction of pulls that were cache hits, or `None` before the first pull.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn hit_ratio(&self) -> Option<f64> {
+        (self.pulls > 0).then(|| self.cache_hits as f64 / self.pulls as f64)
+    }
+
+    /// Mean time per pull, or `None` before the first pull.
+    #[must_use]
+    pub fn average_pull_time(&self) -> Option<Duration> {
+        let pulls = u32::try_from(self.pulls).unwrap_or(u32::MAX);
+        (pulls > 0).then(|| self.total_pull_time / pulls)
+    }
+
+    /// Read the current counters.
+    pub(crate) fn query(conn: &Connection) -> anyhow::Result<Self> {
+        let metrics = conn.query_row(
+            "SELECT pulls, cache_hits, bytes_downloaded, total_pull_ms
+             FROM pull_metrics WHERE id = 1",
+            [],
+            |row| {
+                let get = |i| row.get::<_, i64>(i).map(|v| u64::try_from(v).unwrap_or(0));
+                Ok(Self {
+                    pulls: get(0)?,
+                    cache_hits: get(1)?,
+                    bytes_downloaded: get(2)?,
+                    total_pull_time: Duration::from_millis(get(3)?),
+                })
+            },
+        )?;
+        Ok(metrics)
+    }
+
+    /// Add one completed pull to the counters.
+    pub(crate) fn record(
+        conn: &Connection,
+        cache_hit: bool,
+        bytes_downloaded: u64,
+        elapsed: Duration,
+    ) -> anyhow::Result<()> {
+        conn.execute(
+            "UPDATE pull_metrics
+             SET pulls = pulls + 1,
+                 cache_hits = cache_hits + ?1,
+                 bytes_downloaded = bytes_downloaded + ?2,
+                 total_pull_ms = total_pull_ms + ?3,
+                 updated_at = CURRENT_TIMESTAMP
+             WHERE id = 1",
+            rusqlite::params![
+                i64::from(cache_hit),
+                i64::try_from(bytes_downloaded).unwrap_or(i64::MAX),
+                i64::try_from(elapsed.as_millis()).unwrap_or(i64::MAX),
+            ],
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::Migrations;
+
+    #[test]
+    fn record_accumulates_counters() {
+        let conn = Connection::open_in_memory().unwrap();
+        Migrations::run_all(&conn).unwrap();
+        assert_eq!(PullMetrics::query(&conn).unwrap(), PullMetrics::default());
+
+        PullMetrics::record(&conn, false, 1000, Duration::from_millis(300)).unwrap();
+        PullMetrics::record(&conn, true, 0, Duration::from_millis(100)).unwrap();
+
+        let metrics = PullMetrics::query(&conn).unwrap();
+        assert_eq!(metrics.pulls, 2);
+        assert_eq!(metrics.cache_hits, 1);
+        assert_eq!(metrics.bytes_downloaded, 1000);
+        assert_eq!(
+            metrics.average_pull_time(),
+            Some(Duration::from_millis(200))
+        );
+    }
+}
@@ -0,0 +1,31 @@
+CREATE VIRTUAL TABLE wit_package_fts USING fts5(
+    wit_text,
+    content = 'wit_package',
+    content_rowid = 'id'
+);
+CREATE TRIGGER trg_wit_package_fts_insert
+    AFTER INSERT ON wit_package
+    FOR EACH ROW
+    BEGIN
+        INSERT INTO wit_package_fts(rowid, wit_text)
+        VALUES (NEW.id, NEW.wit_text);
+    END;
+CREATE TRIGGER trg_wit_package_fts_delete
+    AFTER DELETE ON wit_package
+    FOR EACH ROW
+    BEGIN
+        INSERT INTO wit_package_fts(wit_package_fts, rowid, wit_text)
+        VALUES ('delete', OLD.id, OLD.wit_text);
+    END;
+CREATE TRIGGER trg_wit_package_fts_update
+    AFTER UPDATE OF wit_text ON wit_package
+    FOR EACH ROW
+    BEGIN
+        INSERT INTO wit_package_fts(wit_package_fts, rowid, wit_text)
+        VALUES ('delete', OLD.id, OLD.wit_text);
+        INSERT INTO wit_package_fts(rowid, wit_text)
+        VALUES (NEW.id, NEW.wit_text);
+    END;
+
+-- Backfill: index the WIT text of every package stored so far.
+INSERT INTO wit_package_fts(wit_package_fts) VALUES ('rebuild');
@@ -0,0 +1,2 @@
+ALTER TABLE "oci_manifest" ADD COLUMN "last_accessed_at" text;
+UPDATE "oci_manifest" SET "last_accessed_at" = "created_at";
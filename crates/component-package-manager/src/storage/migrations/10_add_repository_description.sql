@@ -0,0 +1,9 @@
+ALTER TABLE "oci_repository" ADD COLUMN "description" text;
+UPDATE "oci_repository" SET "description" = (
+    SELECT COALESCE(m.oci_description, m.oci_title)
+    FROM oci_manifest m
+    WHERE m.oci_repository_id = oci_repository.id
+      AND COALESCE(m.oci_description, m.oci_title) IS NOT NULL
+    ORDER BY m.created_at DESC, m.id DESC
+    LIMIT 1
+);
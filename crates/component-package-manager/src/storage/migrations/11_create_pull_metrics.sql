@@ -0,0 +1,9 @@
+CREATE TABLE pull_metrics (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    pulls INTEGER NOT NULL DEFAULT 0,
+    cache_hits INTEGER NOT NULL DEFAULT 0,
+    bytes_downloaded INTEGER NOT NULL DEFAULT 0,
+    total_pull_ms INTEGER NOT NULL DEFAULT 0,
+    updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+INSERT INTO pull_metrics (id) VALUES (1);
@@ -0,0 +1 @@
+ALTER TABLE "oci_repository" ADD COLUMN "pinned" integer NOT NULL DEFAULT 0;
@@ -0,0 +1,3 @@
+CREATE INDEX idx_oci_layer_digest ON oci_layer(digest);
+CREATE INDEX idx_oci_manifest_config_digest ON oci_manifest(config_digest);
+CREATE INDEX idx_oci_tag_repository_digest ON oci_tag(oci_repository_id, manifest_digest);
@@ -0,0 +1,2 @@
+ALTER TABLE "oci_repository" DROP COLUMN "wit_name";
+ALTER TABLE "oci_repository" DROP COLUMN "wit_namespace";
@@ -0,0 +1 @@
+ALTER TABLE "wasm_component" DROP COLUMN "producers_json";
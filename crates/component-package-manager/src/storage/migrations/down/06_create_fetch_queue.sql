@@ -0,0 +1,2 @@
+-- Dropping the table also drops its trigger and indexes.
+DROP TABLE fetch_queue;
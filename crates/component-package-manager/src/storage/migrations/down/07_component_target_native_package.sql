@@ -0,0 +1 @@
+ALTER TABLE "component_target" DROP COLUMN "is_native_package";
@@ -0,0 +1,4 @@
+DROP TRIGGER trg_wit_package_fts_insert;
+DROP TRIGGER trg_wit_package_fts_delete;
+DROP TRIGGER trg_wit_package_fts_update;
+DROP TABLE wit_package_fts;
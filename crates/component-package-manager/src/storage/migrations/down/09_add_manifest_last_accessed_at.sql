@@ -0,0 +1 @@
+ALTER TABLE "oci_manifest" DROP COLUMN "last_accessed_at";
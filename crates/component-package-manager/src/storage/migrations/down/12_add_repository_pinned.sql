@@ -0,0 +1 @@
+ALTER TABLE "oci_repository" DROP COLUMN "pinned";
@@ -0,0 +1,3 @@
+DROP INDEX idx_oci_tag_repository_digest;
+DROP INDEX idx_oci_manifest_config_digest;
+DROP INDEX idx_oci_layer_digest;
@@ -1,13 +1,29 @@
 //! Cross-cutting persistence types and database storage.
 
+pub(crate) mod blob;
 mod config;
+mod dedup;
+mod fsck;
+pub(crate) mod image_ref;
 mod known_package;
+mod lock;
+mod metrics;
 mod models;
+mod prune;
+mod relocate;
+mod stats;
 mod store;
 
+pub use blob::ComponentReader;
 pub use component_meta_registry_types::PackageDependencyRef;
 pub use config::StateInfo;
+pub use dedup::{DedupStats, SpaceConsumer};
+pub use fsck::{FsckIssue, FsckMode, FsckProblem, FsckReport};
 pub use known_package::{KnownPackage, KnownPackageParams};
-pub use models::Migrations;
+pub use metrics::PullMetrics;
+pub use models::{AppliedMigration, Migrations};
+pub use prune::PrunePolicy;
+pub use relocate::RelocateProgress;
+pub use stats::{StoreStats, UnusedImage};
 pub(crate) use store::Store;
 pub use store::{FetchTask, FetchTaskKind};
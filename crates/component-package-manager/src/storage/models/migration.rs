@@ -1,3 +1,5 @@
+use std::path::{Path, PathBuf};
+
 use anyhow::Context;
 use rusqlite::Connection;
 
@@ -6,6 +8,8 @@ struct MigrationDef {
     version: u32,
     name: &'static str,
     sql: &'static str,
+    /// SQL that reverts `sql`, or `None` if the migration is irreversible.
+    down: Option<&'static str>,
 }
 
 /// All migrations in order. Each migration is run exactly once.
@@ -14,36 +18,91 @@ const MIGRATIONS: &[MigrationDef] = &[
         version: 1,
         name: "init",
         sql: include_str!("../migrations/01_init.sql"),
+        down: None,
     },
     MigrationDef {
         version: 2,
         name: "rename_wit_interface_to_wit_type",
         sql: include_str!("../migrations/02_rename_wit_interface_to_wit_type.sql"),
+        down: None,
     },
     MigrationDef {
         version: 3,
         name: "add_wit_namespace",
         sql: include_str!("../migrations/03_add_wit_namespace.sql"),
+        down: Some(include_str!("../migrations/down/03_add_wit_namespace.sql")),
     },
     MigrationDef {
         version: 4,
         name: "add_kind_to_oci_repository",
         sql: include_str!("../migrations/04_add_kind_to_oci_repository.sql"),
+        down: Some(include_str!(
+            "../migrations/down/04_add_kind_to_oci_repository.sql"
+        )),
     },
     MigrationDef {
         version: 5,
         name: "add_producers_json",
         sql: include_str!("../migrations/05_add_producers_json.sql"),
+        down: Some(include_str!("../migrations/down/05_add_producers_json.sql")),
     },
     MigrationDef {
         version: 6,
         name: "create_fetch_queue",
         sql: include_str!("../migrations/06_create_fetch_queue.sql"),
+        down: Some(include_str!("../migrations/down/06_create_fetch_queue.sql")),
     },
     MigrationDef {
         version: 7,
         name: "component_target_native_package",
         sql: include_str!("../migrations/07_component_target_native_package.sql"),
+        down: Some(include_str!(
+            "../migrations/down/07_component_target_native_package.sql"
+        )),
+    },
+    MigrationDef {
+        version: 8,
+        name: "wit_package_fts",
+        sql: include_str!("../migrations/08_wit_package_fts.sql"),
+        down: Some(include_str!("../migrations/down/08_wit_package_fts.sql")),
+    },
+    MigrationDef {
+        version: 9,
+        name: "add_manifest_last_accessed_at",
+        sql: include_str!("../migrations/09_add_manifest_last_accessed_at.sql"),
+        down: Some(include_str!(
+            "../migrations/down/09_add_manifest_last_accessed_at.sql"
+        )),
+    },
+    MigrationDef {
+        version: 10,
+        name: "add_repository_description",
+        sql: include_str!("../migrations/10_add_repository_description.sql"),
+        down: Some(include_str!(
+            "../migrations/down/10_add_repository_description.sql"
+        )),
+    },
+    MigrationDef {
+        version: 11,
+        name: "create_pull_metrics",
+        sql: include_str!("../migrations/11_create_pull_metrics.sql"),
+        down: Some(include_str!(
+            "../migrations/down/11_create_pull_metrics.sql"
+        )),
+    },
+    MigrationDef {
+        version: 12,
+        name: "add_repository_pinned",
+        sql: include_str!("../migrations/12_add_repository_pinned.sql"),
+        down: Some(include_str!(
+            "../migrations/down/12_add_repository_pinned.sql"
+        )),
+    },
+    MigrationDef {
+        version: 13,
+        name: "add_lookup_indexes",
+        sql: include_str!("../migrations/13_add_lookup_indexes.sql"),
+        down: Some(include_str!("../migrations/down/13_add_lookup_indexes.sql")),
     },
 ];
 
@@ -66,6 +125,30 @@ pub struct Migrations {
     pub total: u32,
 }
 
+/// A migration recorded as applied in the database.
+///
+/// # Example
+///
+/// ```
+/// use component_package_manager::storage::AppliedMigration;
+///
+/// let applied = AppliedMigration {
+///     version: 12,
+///     name: "add_repository_pinned".to_string(),
+///     applied_at: "2026-01-01 12:00:00".to_string(),
+/// };
+/// assert_eq!(applied.version, 12);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppliedMigration {
+    /// The migration version.
+    pub version: u32,
+    /// The migration name, or `"unknown"` if it was applied by a newer build.
+    pub name: String,
+    /// When the migration was applied, as an SQLite UTC timestamp.
+    pub applied_at: String,
+}
+
 impl Migrations {
     /// Initialize the migrations table and run all pending migrations.
     // r[impl db.migrations.create-tables]
@@ -74,48 +157,153 @@ impl Migrations {
         // Create the migrations table if it doesn't exist
         conn.execute_batch(include_str!("../migrations/00_migrations.sql"))?;
 
-        // Get the current migration version
-        let current_version: u32 = conn
-            .query_row(
-                "SELECT COALESCE(MAX(version), 0) FROM migrations",
-                [],
-                |row| row.get(0),
-            )
-            .unwrap_or(0);
-
-        // Run all migrations that haven't been applied yet
+        let current = Self::current_version(conn);
         for migration in MIGRATIONS {
-            if migration.version > current_version {
-                conn.execute_batch(migration.sql).with_context(|| {
-                    format!(
-                        "Failed to run migration {}: {}",
-                        migration.version, migration.name
-                    )
-                })?;
-
-                conn.execute(
-                    "INSERT INTO migrations (version) VALUES (?1)",
-                    [migration.version],
-                )?;
+            if migration.version > current {
+                Self::apply(conn, migration)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Migrate the database up or down to exactly `target`.
+    ///
+    /// Migrating down runs the down SQL of every applied migration newer than
+    /// `target`, newest first. Each migration runs in its own transaction, so
+    /// a failure leaves the database at the last version that succeeded.
+    // r[impl db.migrations.down]
+    pub(crate) fn migrate_to(conn: &Connection, target: u32) -> anyhow::Result<()> {
+        conn.execute_batch(include_str!("../migrations/00_migrations.sql"))?;
+
+        let current = Self::current_version(conn);
+        let total = MIGRATIONS.last().map_or(0, |m| m.version);
+        anyhow::ensure!(
+            target <= total,
+            "Cannot migrate to version {target}: the latest known migration is {total}"
+        );
+        anyhow::ensure!(
+            current <= total,
+            "Cannot migrate a database at version {current}: the latest known migration is {total}"
+        );
+
+        if target >= current {
+            for migration in MIGRATIONS {
+                if migration.version > current && migration.version <= target {
+                    Self::apply(conn, migration)?;
+                }
             }
+            return Ok(());
         }
 
+        for migration in MIGRATIONS.iter().rev() {
+            if migration.version > target && migration.version <= current {
+                Self::revert(conn, migration)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Snapshot the database into `dest`, replacing any existing file.
+    ///
+    /// The snapshot is a standalone, compacted copy that can replace
+    /// `metadata.db3` to undo a migration.
+    // r[impl db.migrations.backup]
+    pub(crate) fn backup(conn: &Connection, dest: &Path) -> anyhow::Result<()> {
+        match std::fs::remove_file(dest) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e).context("Failed to remove previous database backup"),
+        }
+        conn.execute("VACUUM INTO ?1", [dest.to_string_lossy()])
+            .with_context(|| format!("Failed to back up database to {}", dest.display()))?;
         Ok(())
     }
 
+    /// The path of the backup taken of `metadata_file` at `version`.
+    pub(crate) fn backup_path(metadata_file: &Path, version: u32) -> PathBuf {
+        let mut name = metadata_file.file_name().unwrap_or_default().to_owned();
+        name.push(format!(".v{version}.bak"));
+        metadata_file.with_file_name(name)
+    }
+
+    /// Returns every applied migration, oldest first.
+    // r[impl db.migrations.history]
+    pub(crate) fn history(conn: &Connection) -> anyhow::Result<Vec<AppliedMigration>> {
+        let mut stmt =
+            conn.prepare_cached("SELECT version, applied_at FROM migrations ORDER BY version ASC")?;
+        let rows = stmt.query_map([], |row| {
+            let version: u32 = row.get(0)?;
+            Ok(AppliedMigration {
+                version,
+                name: MIGRATIONS
+                    .iter()
+                    .find(|m| m.version == version)
+                    .map_or("unknown", |m| m.name)
+                    .to_string(),
+                applied_at: row.get(1)?,
+            })
+        })?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
     /// Returns information about the current migration state.
     // r[impl db.migrations.info]
     pub(crate) fn get(conn: &Connection) -> Self {
-        let current: u32 = conn
-            .query_row(
-                "SELECT COALESCE(MAX(version), 0) FROM migrations",
-                [],
-                |row| row.get(0),
-            )
-            .unwrap_or(0);
+        let current = Self::current_version(conn);
         let total = MIGRATIONS.last().map_or(0, |m| m.version);
         Self { current, total }
     }
+
+    /// Returns the newest applied migration version, or 0 if none are.
+    fn current_version(conn: &Connection) -> u32 {
+        conn.query_row(
+            "SELECT COALESCE(MAX(version), 0) FROM migrations",
+            [],
+            |row| row.get(0),
+        )
+        .unwrap_or(0)
+    }
+
+    /// Run a migration and record it, atomically.
+    fn apply(conn: &Connection, migration: &MigrationDef) -> anyhow::Result<()> {
+        let tx = conn.unchecked_transaction()?;
+        tx.execute_batch(migration.sql).with_context(|| {
+            format!(
+                "Failed to run migration {}: {}",
+                migration.version, migration.name
+            )
+        })?;
+        tx.execute(
+            "INSERT INTO migrations (version) VALUES (?1)",
+            [migration.version],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Revert a migration and remove its record, atomically.
+    fn revert(conn: &Connection, migration: &MigrationDef) -> anyhow::Result<()> {
+        let down = migration.down.with_context(|| {
+            format!(
+                "Migration {}: {} cannot be reverted",
+                migration.version, migration.name
+            )
+        })?;
+        let tx = conn.unchecked_transaction()?;
+        tx.execute_batch(down).with_context(|| {
+            format!(
+                "Failed to revert migration {}: {}",
+                migration.version, migration.name
+            )
+        })?;
+        tx.execute(
+            "DELETE FROM migrations WHERE version = ?1",
+            [migration.version],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -204,4 +392,94 @@ mod tests {
         let expected_total = MIGRATIONS.last().map(|m| m.version).unwrap_or(0);
         assert_eq!(info.total, expected_total);
     }
+
+    // r[verify db.migrations.down]
+    #[test]
+    fn test_migrations_migrate_down_and_up_again() {
+        let conn = Connection::open_in_memory().unwrap();
+        Migrations::run_all(&conn).unwrap();
+        let total = Migrations::get(&conn).total;
+
+        Migrations::migrate_to(&conn, 2).unwrap();
+        assert_eq!(Migrations::get(&conn).current, 2);
+        assert!(conn.execute("SELECT 1 FROM pull_metrics", []).is_err());
+        assert!(
+            conn.execute("SELECT wit_namespace FROM oci_repository", [])
+                .is_err()
+        );
+
+        Migrations::migrate_to(&conn, total).unwrap();
+        assert_eq!(Migrations::get(&conn).current, total);
+        conn.execute("SELECT pinned FROM oci_repository", [])
+            .unwrap();
+    }
+
+    #[test]
+    fn test_migrations_irreversible_migration_is_rejected() {
+        let conn = Connection::open_in_memory().unwrap();
+        Migrations::run_all(&conn).unwrap();
+
+        assert!(Migrations::migrate_to(&conn, 0).is_err());
+        // Every reversible migration above the irreversible one was reverted.
+        assert_eq!(Migrations::get(&conn).current, 2);
+    }
+
+    // r[verify db.migrations.history]
+    #[test]
+    fn test_migrations_history_records_timestamps() {
+        let conn = Connection::open_in_memory().unwrap();
+        Migrations::run_all(&conn).unwrap();
+
+        let history = Migrations::history(&conn).unwrap();
+        assert_eq!(history.len(), MIGRATIONS.len());
+        let first = history.first().unwrap();
+        assert_eq!(first.version, 1);
+        assert_eq!(first.name, "init");
+        assert!(history.iter().all(|m| !m.applied_at.is_empty()));
+    }
+
+    // r[verify db.migrations.backup]
+    #[test]
+    fn test_migrations_backup_snapshots_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let metadata_file = dir.path().join("metadata.db3");
+        let conn = Connection::open(&metadata_file).unwrap();
+        Migrations::run_all(&conn).unwrap();
+
+        let backup = Migrations::backup_path(&metadata_file, 12);
+        assert_eq!(backup, dir.path().join("metadata.db3.v12.bak"));
+        Migrations::backup(&conn, &backup).unwrap();
+        // A second backup replaces the first.
+        Migrations::backup(&conn, &backup).unwrap();
+
+        let restored = Connection::open(&backup).unwrap();
+        assert_eq!(
+            Migrations::get(&restored).current,
+            Migrations::get(&conn).current
+        );
+    }
+
+    #[test]
+    fn test_lookup_indexes_are_used() {
+        let conn = Connection::open_in_memory().unwrap();
+        Migrations::run_all(&conn).unwrap();
+
+        let plan = |sql: &str| -> String {
+            let mut stmt = conn.prepare(&format!("EXPLAIN QUERY PLAN {sql}")).unwrap();
+            let rows = stmt.query_map([], |row| row.get::<_, String>(3)).unwrap();
+            rows.map(Result::unwrap).collect::<Vec<_>>().join("\n")
+        };
+        assert!(
+            plan("SELECT 1 FROM oci_layer WHERE digest = 'sha256:a'")
+                .contains("idx_oci_layer_digest")
+        );
+        assert!(
+            plan("SELECT 1 FROM oci_manifest WHERE config_digest = 'sha256:a'")
+                .contains("idx_oci_manifest_config_digest")
+        );
+        assert!(
+            plan("SELECT tag FROM oci_tag WHERE oci_repository_id = 1 AND manifest_digest = 'sha256:a'")
+                .contains("idx_oci_tag_repository_digest")
+        );
+    }
 }
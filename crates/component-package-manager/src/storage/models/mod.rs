@@ -1,5 +1,5 @@
 mod migration;
 mod raw_known_package;
 
-pub use migration::Migrations;
+pub use migration::{AppliedMigration, Migrations};
 pub(crate) use raw_known_package::RawKnownPackage;
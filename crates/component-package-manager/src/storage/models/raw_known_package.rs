@@ -1,4 +1,6 @@
-use rusqlite::Connection;
+use std::collections::HashMap;
+
+use rusqlite::{Connection, OptionalExtension};
 use semver::Version;
 
 use component_meta_registry_types::PackageKind;
@@ -57,6 +59,9 @@ pub struct RawKnownPackage {
     /// Package kind: component or interface.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub kind: Option<PackageKind>,
+    /// Whether the user pinned this package.
+    #[serde(default)]
+    pub pinned: bool,
 }
 
 impl RawKnownPackage {
@@ -119,21 +124,10 @@ impl RawKnownPackage {
             kind_str.as_deref(),
         )?;
 
-        // Store description on the most recent manifest that doesn't have one.
-        // Uses a subquery instead of LIMIT in UPDATE (SQLITE_ENABLE_UPDATE_DELETE_LIMIT
-        // is not enabled in most SQLite builds).
+        // Store the description on the repository itself, so it is kept
+        // even when no manifest has been pulled yet.
         if let Some(desc) = params.description
-            && let Err(e) = conn.execute(
-                "UPDATE oci_manifest SET oci_description = ?1
-                 WHERE id = (
-                     SELECT id FROM oci_manifest
-                     WHERE oci_repository_id = ?2
-                       AND oci_description IS NULL
-                     ORDER BY created_at DESC
-                     LIMIT 1
-                 )",
-                rusqlite::params![desc, repo_id],
-            )
+            && let Err(e) = OciRepository::set_description(conn, repo_id, desc)
         {
             tracing::warn!("Failed to update description for repo {repo_id}: {e}");
         }
@@ -151,41 +145,105 @@ impl RawKnownPackage {
         Ok(())
     }
 
-    /// Fetch tags for a repository from `oci_tag`, sorted by semver descending.
+    /// Read a row of `oci_repository` columns into a package, leaving its
+    /// tags and description to [`Self::inflate`].
+    ///
+    /// The row holds `id, registry, repository, updated_at, created_at,
+    /// wit_namespace, wit_name, kind, pinned`, in that order.
+    fn from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<Self> {
+        let kind: Option<String> = row.get(7)?;
+        Ok(RawKnownPackage {
+            id: row.get(0)?,
+            registry: row.get(1)?,
+            repository: row.get(2)?,
+            description: None,
+            tags: Vec::new(),
+            signature_tags: Vec::new(),
+            attestation_tags: Vec::new(),
+            last_seen_at: row.get(3)?,
+            created_at: row.get(4)?,
+            wit_namespace: row.get(5)?,
+            wit_name: row.get(6)?,
+            kind: parse_kind(kind.as_deref()),
+            pinned: row.get(8)?,
+        })
+    }
+
+    /// Fill in the tags and description of every package, with one query
+    /// each for the whole batch rather than two per package.
+    fn inflate(conn: &Connection, mut packages: Vec<Self>) -> anyhow::Result<Vec<Self>> {
+        if packages.is_empty() {
+            return Ok(packages);
+        }
+        let ids: Vec<i64> = packages.iter().map(|p| p.id).collect();
+        let ids = serde_json::to_string(&ids)?;
+        let mut tags = Self::fetch_tags(conn, &ids)?;
+        let mut descriptions = Self::fetch_descriptions(conn, &ids)?;
+        for package in &mut packages {
+            package.tags = tags.remove(&package.id).unwrap_or_default();
+            package.description = descriptions.remove(&package.id);
+        }
+        Ok(packages)
+    }
+
+    /// Fetch the tags of the repositories whose ids are in the JSON array
+    /// `ids` from `oci_tag`, each sorted by semver descending.
     ///
     /// Only returns tags that are valid semver versions (with an optional `v`
     /// prefix). Hash-based tags (signatures, attestations) and non-version
     /// tags like `latest` are excluded.
-    fn fetch_tags(conn: &Connection, repo_id: i64) -> Vec<String> {
-        let Ok(mut stmt) = conn.prepare(
-            "SELECT t.tag FROM oci_tag t
-             WHERE t.oci_repository_id = ?1",
-        ) else {
-            return Vec::new();
-        };
-
-        let Ok(rows) = stmt.query_map([repo_id], |row| row.get::<_, String>(0)) else {
-            return Vec::new();
-        };
+    fn fetch_tags(conn: &Connection, ids: &str) -> anyhow::Result<HashMap<i64, Vec<String>>> {
+        let mut stmt = conn.prepare_cached(
+            "SELECT t.oci_repository_id, t.tag FROM oci_tag t
+             JOIN json_each(?1) ids ON ids.value = t.oci_repository_id",
+        )?;
+        let rows = stmt.query_map([ids], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })?;
 
-        let mut versioned: Vec<(Version, String)> = rows
-            .flatten()
-            .filter_map(|tag| parse_tag_as_semver(&tag).map(|v| (v, tag)))
-            .collect();
-        versioned.sort_by(|(a, _), (b, _)| b.cmp(a));
-        versioned.into_iter().map(|(_, tag)| tag).collect()
+        let mut versioned: HashMap<i64, Vec<(Version, String)>> = HashMap::new();
+        for row in rows {
+            let (id, tag) = row?;
+            if let Some(version) = parse_tag_as_semver(&tag) {
+                versioned.entry(id).or_default().push((version, tag));
+            }
+        }
+        Ok(versioned
+            .into_iter()
+            .map(|(id, mut tags)| {
+                tags.sort_by(|(a, _), (b, _)| b.cmp(a));
+                (id, tags.into_iter().map(|(_, tag)| tag).collect())
+            })
+            .collect())
     }
 
-    /// Fetch the description from the first manifest that has one.
-    fn fetch_description(conn: &Connection, repo_id: i64) -> Option<String> {
-        conn.query_row(
-            "SELECT m.oci_description FROM oci_manifest m
-             WHERE m.oci_repository_id = ?1 AND m.oci_description IS NOT NULL
-             LIMIT 1",
-            [repo_id],
-            |row| row.get(0),
-        )
-        .ok()
+    /// Fetch the description of the repositories whose ids are in the JSON
+    /// array `ids`, falling back to the description or title annotation of
+    /// each one's newest manifest that has one.
+    fn fetch_descriptions(conn: &Connection, ids: &str) -> anyhow::Result<HashMap<i64, String>> {
+        let mut stmt = conn.prepare_cached(
+            "SELECT r.id, COALESCE(
+                 r.description,
+                 (SELECT COALESCE(m.oci_description, m.oci_title) FROM oci_manifest m
+                  WHERE m.oci_repository_id = r.id
+                    AND COALESCE(m.oci_description, m.oci_title) IS NOT NULL
+                  ORDER BY m.created_at DESC, m.id DESC
+                  LIMIT 1)
+             )
+             FROM oci_repository r
+             JOIN json_each(?1) ids ON ids.value = r.id",
+        )?;
+        let rows = stmt.query_map([ids], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, Option<String>>(1)?))
+        })?;
+
+        let mut descriptions = HashMap::new();
+        for row in rows {
+            if let (id, Some(description)) = row? {
+                descriptions.insert(id, description);
+            }
+        }
+        Ok(descriptions)
     }
 
     /// Search for known packages by a query string.
@@ -197,52 +255,43 @@ impl RawKnownPackage {
         limit: u32,
     ) -> anyhow::Result<Vec<RawKnownPackage>> {
         let search_pattern = format!("%{query}%");
-        let mut stmt = conn.prepare(
+        let mut stmt = conn.prepare_cached(
             "SELECT id, registry, repository, updated_at, created_at,
-                    wit_namespace, wit_name, kind
+                    wit_namespace, wit_name, kind, pinned
              FROM oci_repository
              WHERE registry LIKE ?1
                 OR repository LIKE ?1
                 OR wit_namespace LIKE ?1
                 OR wit_name LIKE ?1
-             ORDER BY repository ASC, registry ASC
+             ORDER BY pinned DESC, repository ASC, registry ASC
              LIMIT ?2 OFFSET ?3",
         )?;
+        let packages = stmt
+            .query_map((&search_pattern, limit, offset), Self::from_row)?
+            .collect::<rusqlite::Result<_>>()?;
+        Self::inflate(conn, packages)
+    }
 
-        let rows = stmt.query_map((&search_pattern, limit, offset), |row| {
-            Ok((
-                row.get::<_, i64>(0)?,
-                row.get::<_, String>(1)?,
-                row.get::<_, String>(2)?,
-                row.get::<_, String>(3)?,
-                row.get::<_, String>(4)?,
-                row.get::<_, Option<String>>(5)?,
-                row.get::<_, Option<String>>(6)?,
-                row.get::<_, Option<String>>(7)?,
-            ))
-        })?;
+    /// Count the known packages matched by [`Self::search`].
+    pub(crate) fn count_search(conn: &Connection, query: &str) -> anyhow::Result<u64> {
+        let search_pattern = format!("%{query}%");
+        let count: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM oci_repository
+             WHERE registry LIKE ?1
+                OR repository LIKE ?1
+                OR wit_namespace LIKE ?1
+                OR wit_name LIKE ?1",
+            [&search_pattern],
+            |row| row.get(0),
+        )?;
+        Ok(u64::try_from(count).unwrap_or(0))
+    }
 
-        let mut packages = Vec::new();
-        for row in rows {
-            let (id, registry, repository, updated_at, created_at, wit_ns, wit_n, kind_str) = row?;
-            let tags = Self::fetch_tags(conn, id);
-            let description = Self::fetch_description(conn, id);
-            packages.push(RawKnownPackage {
-                id,
-                registry,
-                repository,
-                description,
-                tags,
-                signature_tags: Vec::new(),
-                attestation_tags: Vec::new(),
-                last_seen_at: updated_at,
-                created_at,
-                wit_namespace: wit_ns,
-                wit_name: wit_n,
-                kind: parse_kind(kind_str.as_deref()),
-            });
-        }
-        Ok(packages)
+    /// Count all known packages.
+    pub(crate) fn count(conn: &Connection) -> anyhow::Result<u64> {
+        let count: i64 =
+            conn.query_row("SELECT COUNT(*) FROM oci_repository", [], |row| row.get(0))?;
+        Ok(u64::try_from(count).unwrap_or(0))
     }
 
     /// Get all known packages, ordered alphabetically by repository.
@@ -251,48 +300,17 @@ impl RawKnownPackage {
         offset: u32,
         limit: u32,
     ) -> anyhow::Result<Vec<RawKnownPackage>> {
-        let mut stmt = conn.prepare(
+        let mut stmt = conn.prepare_cached(
             "SELECT id, registry, repository, updated_at, created_at,
-                    wit_namespace, wit_name, kind
+                    wit_namespace, wit_name, kind, pinned
              FROM oci_repository
-             ORDER BY repository ASC, registry ASC
+             ORDER BY pinned DESC, repository ASC, registry ASC
              LIMIT ?1 OFFSET ?2",
         )?;
-
-        let rows = stmt.query_map((limit, offset), |row| {
-            Ok((
-                row.get::<_, i64>(0)?,
-                row.get::<_, String>(1)?,
-                row.get::<_, String>(2)?,
-                row.get::<_, String>(3)?,
-                row.get::<_, String>(4)?,
-                row.get::<_, Option<String>>(5)?,
-                row.get::<_, Option<String>>(6)?,
-                row.get::<_, Option<String>>(7)?,
-            ))
-        })?;
-
-        let mut packages = Vec::new();
-        for row in rows {
-            let (id, registry, repository, updated_at, created_at, wit_ns, wit_n, kind_str) = row?;
-            let tags = Self::fetch_tags(conn, id);
-            let description = Self::fetch_description(conn, id);
-            packages.push(RawKnownPackage {
-                id,
-                registry,
-                repository,
-                description,
-                tags,
-                signature_tags: Vec::new(),
-                attestation_tags: Vec::new(),
-                last_seen_at: updated_at,
-                created_at,
-                wit_namespace: wit_ns,
-                wit_name: wit_n,
-                kind: parse_kind(kind_str.as_deref()),
-            });
-        }
-        Ok(packages)
+        let packages = stmt
+            .query_map((limit, offset), Self::from_row)?
+            .collect::<rusqlite::Result<_>>()?;
+        Self::inflate(conn, packages)
     }
 
     /// Get known packages ordered by most recent update timestamp.
@@ -301,48 +319,17 @@ impl RawKnownPackage {
         offset: u32,
         limit: u32,
     ) -> anyhow::Result<Vec<RawKnownPackage>> {
-        let mut stmt = conn.prepare(
+        let mut stmt = conn.prepare_cached(
             "SELECT id, registry, repository, updated_at, created_at,
-                    wit_namespace, wit_name, kind
+                    wit_namespace, wit_name, kind, pinned
              FROM oci_repository
              ORDER BY updated_at DESC
              LIMIT ?2 OFFSET ?1",
         )?;
-
-        let rows = stmt.query_map((offset, limit), |row| {
-            Ok((
-                row.get::<_, i64>(0)?,
-                row.get::<_, String>(1)?,
-                row.get::<_, String>(2)?,
-                row.get::<_, String>(3)?,
-                row.get::<_, String>(4)?,
-                row.get::<_, Option<String>>(5)?,
-                row.get::<_, Option<String>>(6)?,
-                row.get::<_, Option<String>>(7)?,
-            ))
-        })?;
-
-        let mut packages = Vec::new();
-        for row in rows {
-            let (id, registry, repository, updated_at, created_at, wit_ns, wit_n, kind_str) = row?;
-            let tags = Self::fetch_tags(conn, id);
-            let description = Self::fetch_description(conn, id);
-            packages.push(RawKnownPackage {
-                id,
-                registry,
-                repository,
-                description,
-                tags,
-                signature_tags: Vec::new(),
-                attestation_tags: Vec::new(),
-                last_seen_at: updated_at,
-                created_at,
-                wit_namespace: wit_ns,
-                wit_name: wit_n,
-                kind: parse_kind(kind_str.as_deref()),
-            });
-        }
-        Ok(packages)
+        let packages = stmt
+            .query_map((offset, limit), Self::from_row)?
+            .collect::<rusqlite::Result<_>>()?;
+        Self::inflate(conn, packages)
     }
 
     /// Get a known package by registry and repository.
@@ -351,48 +338,16 @@ impl RawKnownPackage {
         registry: &str,
         repository: &str,
     ) -> anyhow::Result<Option<RawKnownPackage>> {
-        let result = conn.query_row(
-            "SELECT id, registry, repository, updated_at, created_at,
-                    wit_namespace, wit_name, kind
-             FROM oci_repository
-             WHERE registry = ?1 AND repository = ?2",
-            [registry, repository],
-            |row| {
-                Ok((
-                    row.get::<_, i64>(0)?,
-                    row.get::<_, String>(1)?,
-                    row.get::<_, String>(2)?,
-                    row.get::<_, String>(3)?,
-                    row.get::<_, String>(4)?,
-                    row.get::<_, Option<String>>(5)?,
-                    row.get::<_, Option<String>>(6)?,
-                    row.get::<_, Option<String>>(7)?,
-                ))
-            },
-        );
-
-        match result {
-            Ok((id, reg, repo, updated_at, created_at, wit_ns, wit_n, kind_str)) => {
-                let tags = Self::fetch_tags(conn, id);
-                let description = Self::fetch_description(conn, id);
-                Ok(Some(RawKnownPackage {
-                    id,
-                    registry: reg,
-                    repository: repo,
-                    description,
-                    tags,
-                    signature_tags: Vec::new(),
-                    attestation_tags: Vec::new(),
-                    last_seen_at: updated_at,
-                    created_at,
-                    wit_namespace: wit_ns,
-                    wit_name: wit_n,
-                    kind: parse_kind(kind_str.as_deref()),
-                }))
-            }
-            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-            Err(e) => Err(e.into()),
-        }
+        let package = conn
+            .prepare_cached(
+                "SELECT id, registry, repository, updated_at, created_at,
+                        wit_namespace, wit_name, kind, pinned
+                 FROM oci_repository
+                 WHERE registry = ?1 AND repository = ?2",
+            )?
+            .query_row([registry, repository], Self::from_row)
+            .optional()?;
+        Ok(Self::inflate(conn, package.into_iter().collect())?.pop())
     }
 
     /// Search for known packages that import a given interface.
@@ -405,20 +360,22 @@ impl RawKnownPackage {
         offset: u32,
         limit: u32,
     ) -> anyhow::Result<Vec<RawKnownPackage>> {
-        let mut stmt = conn.prepare(
+        let mut stmt = conn.prepare_cached(
             "SELECT DISTINCT r.id, r.registry, r.repository, r.updated_at, r.created_at,
-                    r.wit_namespace, r.wit_name, r.kind
+                    r.wit_namespace, r.wit_name, r.kind, r.pinned
              FROM oci_repository r
              JOIN oci_manifest m ON m.oci_repository_id = r.id
              JOIN wit_package wp ON wp.oci_manifest_id = m.id
              JOIN wit_world ww ON ww.wit_package_id = wp.id
              JOIN wit_world_import wi ON wi.wit_world_id = ww.id
              WHERE wi.declared_package = ?1
-             ORDER BY r.repository ASC, r.registry ASC
+             ORDER BY r.pinned DESC, r.repository ASC, r.registry ASC
              LIMIT ?2 OFFSET ?3",
         )?;
-
-        Self::collect_repo_rows(conn, &mut stmt, (interface, limit, offset))
+        let packages = stmt
+            .query_map((interface, limit, offset), Self::from_row)?
+            .collect::<rusqlite::Result<_>>()?;
+        Self::inflate(conn, packages)
     }
 
     /// Search for known packages that export a given interface.
@@ -431,64 +388,22 @@ impl RawKnownPackage {
         offset: u32,
         limit: u32,
     ) -> anyhow::Result<Vec<RawKnownPackage>> {
-        let mut stmt = conn.prepare(
+        let mut stmt = conn.prepare_cached(
             "SELECT DISTINCT r.id, r.registry, r.repository, r.updated_at, r.created_at,
-                    r.wit_namespace, r.wit_name, r.kind
+                    r.wit_namespace, r.wit_name, r.kind, r.pinned
              FROM oci_repository r
              JOIN oci_manifest m ON m.oci_repository_id = r.id
              JOIN wit_package wp ON wp.oci_manifest_id = m.id
              JOIN wit_world ww ON ww.wit_package_id = wp.id
              JOIN wit_world_export we ON we.wit_world_id = ww.id
              WHERE we.declared_package = ?1
-             ORDER BY r.repository ASC, r.registry ASC
+             ORDER BY r.pinned DESC, r.repository ASC, r.registry ASC
              LIMIT ?2 OFFSET ?3",
         )?;
-
-        Self::collect_repo_rows(conn, &mut stmt, (interface, limit, offset))
-    }
-
-    /// Execute a prepared statement that returns `(id, registry, repository,
-    /// updated_at, created_at, wit_namespace, wit_name, kind)` rows and inflate each
-    /// into a full `RawKnownPackage` with tags and description.
-    fn collect_repo_rows(
-        conn: &Connection,
-        stmt: &mut rusqlite::Statement<'_>,
-        params: (&str, u32, u32),
-    ) -> anyhow::Result<Vec<RawKnownPackage>> {
-        let rows = stmt.query_map(params, |row| {
-            Ok((
-                row.get::<_, i64>(0)?,
-                row.get::<_, String>(1)?,
-                row.get::<_, String>(2)?,
-                row.get::<_, String>(3)?,
-                row.get::<_, String>(4)?,
-                row.get::<_, Option<String>>(5)?,
-                row.get::<_, Option<String>>(6)?,
-                row.get::<_, Option<String>>(7)?,
-            ))
-        })?;
-
-        let mut packages = Vec::new();
-        for row in rows {
-            let (id, registry, repository, updated_at, created_at, wit_ns, wit_n, kind_str) = row?;
-            let tags = Self::fetch_tags(conn, id);
-            let description = Self::fetch_description(conn, id);
-            packages.push(RawKnownPackage {
-                id,
-                registry,
-                repository,
-                description,
-                tags,
-                signature_tags: Vec::new(),
-                attestation_tags: Vec::new(),
-                last_seen_at: updated_at,
-                created_at,
-                wit_namespace: wit_ns,
-                wit_name: wit_n,
-                kind: parse_kind(kind_str.as_deref()),
-            });
-        }
-        Ok(packages)
+        let packages = stmt
+            .query_map((interface, limit, offset), Self::from_row)?
+            .collect::<rusqlite::Result<_>>()?;
+        Self::inflate(conn, packages)
     }
 
     /// Search for a known package by WIT package name.
@@ -520,50 +435,18 @@ impl RawKnownPackage {
         namespace: &str,
         name: &str,
     ) -> anyhow::Result<Option<RawKnownPackage>> {
-        let result = conn.query_row(
-            "SELECT id, registry, repository, updated_at, created_at,
-                    wit_namespace, wit_name, kind
-             FROM oci_repository
-             WHERE wit_namespace = ?1 AND wit_name = ?2
-             ORDER BY updated_at DESC
-             LIMIT 1",
-            [namespace, name],
-            |row| {
-                Ok((
-                    row.get::<_, i64>(0)?,
-                    row.get::<_, String>(1)?,
-                    row.get::<_, String>(2)?,
-                    row.get::<_, String>(3)?,
-                    row.get::<_, String>(4)?,
-                    row.get::<_, Option<String>>(5)?,
-                    row.get::<_, Option<String>>(6)?,
-                    row.get::<_, Option<String>>(7)?,
-                ))
-            },
-        );
-
-        match result {
-            Ok((id, registry, repository, updated_at, created_at, wit_ns, wit_n, kind_str)) => {
-                let tags = Self::fetch_tags(conn, id);
-                let description = Self::fetch_description(conn, id);
-                Ok(Some(RawKnownPackage {
-                    id,
-                    registry,
-                    repository,
-                    description,
-                    tags,
-                    signature_tags: Vec::new(),
-                    attestation_tags: Vec::new(),
-                    last_seen_at: updated_at,
-                    created_at,
-                    wit_namespace: wit_ns,
-                    wit_name: wit_n,
-                    kind: parse_kind(kind_str.as_deref()),
-                }))
-            }
-            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-            Err(e) => Err(e.into()),
-        }
+        let package = conn
+            .prepare_cached(
+                "SELECT id, registry, repository, updated_at, created_at,
+                        wit_namespace, wit_name, kind, pinned
+                 FROM oci_repository
+                 WHERE wit_namespace = ?1 AND wit_name = ?2
+                 ORDER BY updated_at DESC
+                 LIMIT 1",
+            )?
+            .query_row([namespace, name], Self::from_row)
+            .optional()?;
+        Ok(Self::inflate(conn, package.into_iter().collect())?.pop())
     }
 
     /// Fuzzy fallback: convert WIT name to a search pattern on `repository`.
@@ -574,50 +457,18 @@ impl RawKnownPackage {
         let search_pattern = wit_name.replace(':', "/");
         let like_pattern = format!("%{search_pattern}%");
 
-        let result = conn.query_row(
-            "SELECT id, registry, repository, updated_at, created_at,
-                    wit_namespace, wit_name, kind
-             FROM oci_repository
-             WHERE repository LIKE ?1
-             ORDER BY updated_at DESC
-             LIMIT 1",
-            [&like_pattern],
-            |row| {
-                Ok((
-                    row.get::<_, i64>(0)?,
-                    row.get::<_, String>(1)?,
-                    row.get::<_, String>(2)?,
-                    row.get::<_, String>(3)?,
-                    row.get::<_, String>(4)?,
-                    row.get::<_, Option<String>>(5)?,
-                    row.get::<_, Option<String>>(6)?,
-                    row.get::<_, Option<String>>(7)?,
-                ))
-            },
-        );
-
-        match result {
-            Ok((id, registry, repository, updated_at, created_at, wit_ns, wit_n, kind_str)) => {
-                let tags = Self::fetch_tags(conn, id);
-                let description = Self::fetch_description(conn, id);
-                Ok(Some(RawKnownPackage {
-                    id,
-                    registry,
-                    repository,
-                    description,
-                    tags,
-                    signature_tags: Vec::new(),
-                    attestation_tags: Vec::new(),
-                    last_seen_at: updated_at,
-                    created_at,
-                    wit_namespace: wit_ns,
-                    wit_name: wit_n,
-                    kind: parse_kind(kind_str.as_deref()),
-                }))
-            }
-            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-            Err(e) => Err(e.into()),
-        }
+        let package = conn
+            .prepare_cached(
+                "SELECT id, registry, repository, updated_at, created_at,
+                        wit_namespace, wit_name, kind, pinned
+                 FROM oci_repository
+                 WHERE repository LIKE ?1
+                 ORDER BY updated_at DESC
+                 LIMIT 1",
+            )?
+            .query_row([&like_pattern], Self::from_row)
+            .optional()?;
+        Ok(Self::inflate(conn, package.into_iter().collect())?.pop())
     }
 }
 
@@ -685,6 +536,60 @@ mod tests {
         assert_eq!(results.first().unwrap().repository, "bytecode/component");
     }
 
+    #[test]
+    fn test_known_package_pages_and_counts() {
+        let conn = setup_test_db();
+        for repo in ["user/a-nginx", "user/b-nginx", "user/c-nginx", "user/other"] {
+            RawKnownPackage::upsert(&conn, "ghcr.io", repo, None, None).unwrap();
+        }
+
+        assert_eq!(RawKnownPackage::count(&conn).unwrap(), 4);
+        assert_eq!(RawKnownPackage::count_search(&conn, "nginx").unwrap(), 3);
+
+        let page = RawKnownPackage::search(&conn, "nginx", 2, 2).unwrap();
+        let repos: Vec<_> = page.iter().map(|p| p.repository.as_str()).collect();
+        assert_eq!(repos, ["user/c-nginx"]);
+
+        let page = RawKnownPackage::get_all(&conn, 1, 2).unwrap();
+        let repos: Vec<_> = page.iter().map(|p| p.repository.as_str()).collect();
+        assert_eq!(repos, ["user/b-nginx", "user/c-nginx"]);
+    }
+
+    #[test]
+    fn test_known_package_pinned_sorts_first() {
+        use crate::oci::OciRepository;
+
+        let conn = setup_test_db();
+        for repo in ["user/a-nginx", "user/b-nginx", "user/c-nginx"] {
+            RawKnownPackage::upsert(&conn, "ghcr.io", repo, None, None).unwrap();
+        }
+        let repo_id = OciRepository::upsert(&conn, "ghcr.io", "user/c-nginx").unwrap();
+        OciRepository::set_pinned(&conn, repo_id, true).unwrap();
+
+        let all = RawKnownPackage::get_all(&conn, 0, 10).unwrap();
+        let repos: Vec<_> = all
+            .iter()
+            .map(|p| (p.repository.as_str(), p.pinned))
+            .collect();
+        assert_eq!(
+            repos,
+            [
+                ("user/c-nginx", true),
+                ("user/a-nginx", false),
+                ("user/b-nginx", false)
+            ]
+        );
+
+        let found = RawKnownPackage::search(&conn, "nginx", 0, 1).unwrap();
+        assert_eq!(found.first().unwrap().repository, "user/c-nginx");
+
+        OciRepository::set_pinned(&conn, repo_id, false).unwrap();
+        let pkg = RawKnownPackage::get(&conn, "ghcr.io", "user/c-nginx")
+            .unwrap()
+            .unwrap();
+        assert!(!pkg.pinned);
+    }
+
     // r[verify db.known-packages.search-empty]
     #[test]
     fn test_known_package_search_no_results() {
@@ -770,6 +675,67 @@ mod tests {
         assert!(pkg.tags[1..].contains(&"1.2.0".to_string()));
     }
 
+    #[test]
+    fn test_listing_batches_tags_and_descriptions_per_package() {
+        use crate::oci::{OciManifest, OciRepository as OciRepo, OciTag};
+        use std::collections::HashMap;
+
+        let conn = setup_test_db();
+        for (repo, tags) in [
+            ("user/a", ["1.0.0", "2.0.0"]),
+            ("user/b", ["0.1.0", "0.2.0"]),
+        ] {
+            let repo_id = OciRepo::upsert(&conn, "ghcr.io", repo).unwrap();
+            for tag in tags {
+                let digest = format!("sha256:{repo}-{tag}");
+                OciManifest::upsert(
+                    &conn,
+                    repo_id,
+                    &digest,
+                    None,
+                    Some("{}"),
+                    None,
+                    None,
+                    None,
+                    None,
+                    &HashMap::new(),
+                )
+                .unwrap();
+                OciTag::upsert(&conn, repo_id, tag, &digest).unwrap();
+            }
+        }
+        RawKnownPackage::upsert(&conn, "ghcr.io", "user/b", None, Some("B")).unwrap();
+        RawKnownPackage::upsert(&conn, "ghcr.io", "user/c", None, None).unwrap();
+
+        let packages = RawKnownPackage::get_all(&conn, 0, 10).unwrap();
+        let listed: Vec<_> = packages
+            .iter()
+            .map(|p| {
+                (
+                    p.repository.as_str(),
+                    p.tags.clone(),
+                    p.description.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            listed,
+            [
+                (
+                    "user/a",
+                    vec!["2.0.0".to_string(), "1.0.0".to_string()],
+                    None
+                ),
+                (
+                    "user/b",
+                    vec!["0.2.0".to_string(), "0.1.0".to_string()],
+                    Some("B")
+                ),
+                ("user/c", vec![], None),
+            ]
+        );
+    }
+
     // r[verify db.known-packages.search-by-wit-name]
     #[test]
     fn test_known_package_search_by_wit_name() {
@@ -1035,6 +1001,46 @@ mod tests {
         assert_eq!(packages[0].kind, None);
     }
 
+    #[test]
+    fn test_known_package_description_prefers_repository() {
+        use crate::oci::{OciManifest, OciRepository as OciRepo};
+        use std::collections::HashMap;
+
+        let conn = setup_test_db();
+        let repo_id = OciRepo::upsert(&conn, "ghcr.io", "example/app").unwrap();
+        let get = |conn: &Connection| {
+            RawKnownPackage::get(conn, "ghcr.io", "example/app")
+                .unwrap()
+                .unwrap()
+                .description
+        };
+        RawKnownPackage::upsert(&conn, "ghcr.io", "example/app", Some("1.0.0"), None).unwrap();
+        assert_eq!(get(&conn), None);
+
+        // Without a repository description, the newest manifest's title is used.
+        let annotations = HashMap::from([(
+            "org.opencontainers.image.title".to_string(),
+            "Example app".to_string(),
+        )]);
+        OciManifest::upsert(
+            &conn,
+            repo_id,
+            "sha256:1",
+            None,
+            Some("{}"),
+            None,
+            None,
+            None,
+            None,
+            &annotations,
+        )
+        .unwrap();
+        assert_eq!(get(&conn).as_deref(), Some("Example app"));
+
+        OciRepo::set_description(&conn, repo_id, "An example application").unwrap();
+        assert_eq!(get(&conn).as_deref(), Some("An example application"));
+    }
+
     /// Regression test: `upsert` must not clobber an existing tag→digest
     /// mapping with whichever manifest happens to be most recently inserted.
     ///
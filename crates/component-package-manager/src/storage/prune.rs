@@ -0,0 +1,305 @@
+use std::time::Duration;
+
+use rusqlite::Connection;
+
+use super::image_ref::{image_reference, latest_tag_sql};
+
+/// Which stored images [`Manager::prune`](crate::manager::Manager::prune)
+/// removes.
+///
+/// An image is removed when it matches any enabled rule. With every rule
+/// disabled nothing is removed. Images in pinned repositories (see
+/// [`Manager::pin`](crate::manager::Manager::pin)) are never removed.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use component_package_manager::storage::PrunePolicy;
+///
+/// // Remove images unused for 30 days, and all but the 3 newest per repository.
+/// let policy = PrunePolicy {
+///     unused_for: Some(Duration::from_hours(30 * 24)),
+///     keep_last: Some(3),
+///     ..PrunePolicy::default()
+/// };
+/// assert!(!policy.is_empty());
+/// assert!(PrunePolicy::default().is_empty());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PrunePolicy {
+    /// Remove images that have not been pulled, installed, or run for at
+    /// least this long.
+    pub unused_for: Option<Duration>,
+    /// Keep only the `N` most recently stored images in each repository.
+    pub keep_last: Option<u32>,
+    /// Remove the least recently used images until the rest add up to at
+    /// most this many bytes, going by each image's recorded size.
+    pub max_size: Option<u64>,
+    /// Report what would be removed without removing anything.
+    pub dry_run: bool,
+}
+
+impl PrunePolicy {
+    /// Returns `true` when no rule is enabled.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.unused_for.is_none() && self.keep_last.is_none() && self.max_size.is_none()
+    }
+
+    /// Find the images this policy removes, as `(manifest id, reference)`
+    /// pairs: those matching `unused_for` or `keep_last` ordered by
+    /// reference, then those evicted for `max_size`, least recently used
+    /// first.
+    pub(crate) fn candidates(&self, conn: &Connection) -> anyhow::Result<Vec<(i64, String)>> {
+        if self.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut result = self.rule_candidates(conn)?;
+        if let Some(max_size) = self.max_size {
+            let evicted = lru_candidates(conn, max_size, &result)?;
+            result.extend(evicted);
+        }
+        Ok(result)
+    }
+
+    /// The images matching `unused_for` or `keep_last`.
+    fn rule_candidates(&self, conn: &Connection) -> anyhow::Result<Vec<(i64, String)>> {
+        if self.unused_for.is_none() && self.keep_last.is_none() {
+            return Ok(Vec::new());
+        }
+        let cutoff = self
+            .unused_for
+            .map(|age| format!("-{} seconds", age.as_secs()));
+
+        let mut stmt = conn.prepare_cached(&format!(
+            "SELECT img.id, img.registry, img.repository, img.digest, {} AS tag
+             FROM (
+                 SELECT m.id, m.digest, r.id AS repo_id, r.registry, r.repository,
+                        COALESCE(m.last_accessed_at, m.created_at) AS accessed_at,
+                        ROW_NUMBER() OVER (
+                            PARTITION BY m.oci_repository_id
+                            ORDER BY m.created_at DESC, m.id DESC
+                        ) AS newest
+                 FROM oci_manifest m
+                 JOIN oci_repository r ON r.id = m.oci_repository_id
+                 WHERE m.raw_json IS NOT NULL AND r.pinned = 0
+             ) AS img
+             WHERE (?1 IS NOT NULL AND img.accessed_at < datetime('now', ?1))
+                OR (?2 IS NOT NULL AND img.newest > ?2)
+             ORDER BY img.registry ASC, img.repository ASC, img.newest ASC",
+            latest_tag_sql("img.repo_id", "img.digest")
+        ))?;
+        let rows = stmt.query_map(rusqlite::params![cutoff, self.keep_last], |row| {
+            let id: i64 = row.get(0)?;
+            let registry: String = row.get(1)?;
+            let repository: String = row.get(2)?;
+            let digest: String = row.get(3)?;
+            let tag: Option<String> = row.get(4)?;
+            let reference = image_reference(&registry, &repository, &digest, tag.as_deref());
+            Ok((id, reference))
+        })?;
+
+        let mut result = Vec::new();
+        for row in rows {
+            result.push(row?);
+        }
+        Ok(result)
+    }
+}
+
+/// Unpinned images to evict, least recently used first, until the images
+/// that remain add up to at most `max_size` bytes. Images in `removed` are
+/// already going and count as freed.
+fn lru_candidates(
+    conn: &Connection,
+    max_size: u64,
+    removed: &[(i64, String)],
+) -> anyhow::Result<Vec<(i64, String)>> {
+    let mut stmt = conn.prepare_cached(&format!(
+        "SELECT m.id, r.registry, r.repository, m.digest, COALESCE(m.size_bytes, 0), r.pinned,
+                {} AS tag
+         FROM oci_manifest m
+         JOIN oci_repository r ON r.id = m.oci_repository_id
+         WHERE m.raw_json IS NOT NULL
+         ORDER BY COALESCE(m.last_accessed_at, m.created_at) ASC, m.id ASC",
+        latest_tag_sql("r.id", "m.digest")
+    ))?;
+    let rows = stmt.query_map([], |row| {
+        let id: i64 = row.get(0)?;
+        let registry: String = row.get(1)?;
+        let repository: String = row.get(2)?;
+        let digest: String = row.get(3)?;
+        let size: i64 = row.get(4)?;
+        let pinned: bool = row.get(5)?;
+        let tag: Option<String> = row.get(6)?;
+        let reference = image_reference(&registry, &repository, &digest, tag.as_deref());
+        Ok((id, reference, u64::try_from(size).unwrap_or(0), pinned))
+    })?;
+
+    let mut images = Vec::new();
+    for row in rows {
+        let image = row?;
+        if !removed.iter().any(|(id, _)| *id == image.0) {
+            images.push(image);
+        }
+    }
+    let mut total: u64 = images.iter().map(|(_, _, size, _)| size).sum();
+    let mut evicted = Vec::new();
+    for (id, reference, size, pinned) in images {
+        if total <= max_size {
+            break;
+        }
+        if !pinned {
+            total = total.saturating_sub(size);
+            evicted.push((id, reference));
+        }
+    }
+    Ok(evicted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::oci::{OciManifest, OciRepository, OciTag};
+    use crate::storage::Migrations;
+    use std::collections::HashMap;
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        Migrations::run_all(&conn).unwrap();
+        conn
+    }
+
+    /// Store `repository:tag`, first recorded and last accessed at the given
+    /// SQLite timestamps.
+    fn insert_image(conn: &Connection, repository: &str, tag: &str, created: &str, accessed: &str) {
+        let repo_id = OciRepository::upsert(conn, "ghcr.io", repository).unwrap();
+        let digest = format!("sha256:{repository}-{tag}");
+        let (id, _) = OciManifest::upsert(
+            conn,
+            repo_id,
+            &digest,
+            None,
+            Some("{}"),
+            None,
+            None,
+            None,
+            None,
+            &HashMap::new(),
+        )
+        .unwrap();
+        OciTag::upsert(conn, repo_id, tag, &digest).unwrap();
+        conn.execute(
+            "UPDATE oci_manifest SET created_at = ?2, last_accessed_at = ?3 WHERE id = ?1",
+            rusqlite::params![id, created, accessed],
+        )
+        .unwrap();
+    }
+
+    fn references(policy: &PrunePolicy, conn: &Connection) -> Vec<String> {
+        policy
+            .candidates(conn)
+            .unwrap()
+            .into_iter()
+            .map(|(_, reference)| reference)
+            .collect()
+    }
+
+    #[test]
+    fn empty_policy_removes_nothing() {
+        let conn = setup_test_db();
+        insert_image(
+            &conn,
+            "example/a",
+            "1.0",
+            "2000-01-01 00:00:00",
+            "2000-01-01 00:00:00",
+        );
+        assert!(references(&PrunePolicy::default(), &conn).is_empty());
+    }
+
+    #[test]
+    fn unused_and_keep_last_rules_combine() {
+        let conn = setup_test_db();
+        let recent = "9999-01-01 00:00:00";
+        insert_image(&conn, "example/a", "1.0", "2020-01-01 00:00:00", recent);
+        insert_image(&conn, "example/a", "2.0", "2021-01-01 00:00:00", recent);
+        insert_image(&conn, "example/a", "3.0", "2022-01-01 00:00:00", recent);
+        insert_image(
+            &conn,
+            "example/b",
+            "1.0",
+            "2020-01-01 00:00:00",
+            "2020-01-01 00:00:00",
+        );
+
+        let unused = PrunePolicy {
+            unused_for: Some(Duration::from_hours(30 * 24)),
+            ..PrunePolicy::default()
+        };
+        assert_eq!(references(&unused, &conn), ["ghcr.io/example/b:1.0"]);
+
+        let keep_two = PrunePolicy {
+            keep_last: Some(2),
+            ..PrunePolicy::default()
+        };
+        assert_eq!(references(&keep_two, &conn), ["ghcr.io/example/a:1.0"]);
+
+        let both = PrunePolicy {
+            unused_for: unused.unused_for,
+            keep_last: Some(2),
+            ..PrunePolicy::default()
+        };
+        assert_eq!(
+            references(&both, &conn),
+            ["ghcr.io/example/a:1.0", "ghcr.io/example/b:1.0"]
+        );
+    }
+
+    #[test]
+    fn pinned_repositories_are_never_pruned() {
+        let conn = setup_test_db();
+        let old = "2000-01-01 00:00:00";
+        insert_image(&conn, "example/a", "1.0", old, old);
+        insert_image(&conn, "example/b", "1.0", old, old);
+        let repo_id = OciRepository::upsert(&conn, "ghcr.io", "example/a").unwrap();
+        OciRepository::set_pinned(&conn, repo_id, true).unwrap();
+
+        let unused = PrunePolicy {
+            unused_for: Some(Duration::from_hours(24)),
+            ..PrunePolicy::default()
+        };
+        assert_eq!(references(&unused, &conn), ["ghcr.io/example/b:1.0"]);
+    }
+
+    #[test]
+    fn max_size_evicts_least_recently_used_first() {
+        let conn = setup_test_db();
+        let created = "2020-01-01 00:00:00";
+        insert_image(&conn, "example/a", "1.0", created, "2024-01-03 00:00:00");
+        insert_image(&conn, "example/b", "1.0", created, "2024-01-01 00:00:00");
+        insert_image(&conn, "example/c", "1.0", created, "2024-01-02 00:00:00");
+        insert_image(&conn, "example/d", "1.0", created, "2024-01-04 00:00:00");
+        conn.execute("UPDATE oci_manifest SET size_bytes = 100", [])
+            .unwrap();
+        // The oldest image is pinned, so it stays and the next ones go.
+        let repo_id = OciRepository::upsert(&conn, "ghcr.io", "example/b").unwrap();
+        OciRepository::set_pinned(&conn, repo_id, true).unwrap();
+
+        let policy = PrunePolicy {
+            max_size: Some(250),
+            ..PrunePolicy::default()
+        };
+        assert_eq!(
+            references(&policy, &conn),
+            ["ghcr.io/example/c:1.0", "ghcr.io/example/a:1.0"]
+        );
+
+        let roomy = PrunePolicy {
+            max_size: Some(400),
+            ..PrunePolicy::default()
+        };
+        assert!(references(&roomy, &conn).is_empty());
+    }
+}
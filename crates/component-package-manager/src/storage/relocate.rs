@@ -0,0 +1,234 @@
+//! Moving the store to a different data directory.
+
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use tokio::sync::mpsc;
+
+/// File left in a data directory whose store moved elsewhere. It holds the
+/// path of the new data directory.
+pub(crate) const RELOCATED_FILE: &str = "relocated";
+
+/// The most relocation pointers followed when opening a store, so a cycle
+/// of pointers can't loop forever.
+const MAX_HOPS: usize = 16;
+
+/// Progress of a store relocation, sent by
+/// [`Manager::relocate_with_progress`](crate::manager::Manager::relocate_with_progress).
+///
+/// # Example
+///
+/// ```
+/// use component_package_manager::storage::RelocateProgress;
+///
+/// let progress = RelocateProgress {
+///     bytes_moved: 512,
+///     total_bytes: 1024,
+/// };
+/// assert!(progress.bytes_moved < progress.total_bytes);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RelocateProgress {
+    /// Bytes moved so far.
+    pub bytes_moved: u64,
+    /// Bytes to move in total.
+    pub total_bytes: u64,
+}
+
+/// Follow relocation pointers from `data_dir` to the directory the store
+/// lives in now.
+pub(crate) fn resolve(mut data_dir: PathBuf) -> PathBuf {
+    for _ in 0..MAX_HOPS {
+        if data_dir.join("db").exists() {
+            break;
+        }
+        let Ok(target) = std::fs::read_to_string(data_dir.join(RELOCATED_FILE)) else {
+            break;
+        };
+        data_dir = PathBuf::from(target);
+    }
+    data_dir
+}
+
+/// Check that the store in `from` can move to `to`.
+pub(crate) fn check_target(from: &Path, to: &Path) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        !to.starts_with(from.join("db")) && !to.starts_with(from.join("store")),
+        "Cannot move the store into itself ({})",
+        to.display()
+    );
+    anyhow::ensure!(
+        !to.join("db").exists() && !to.join("store").exists(),
+        "{} already contains a store",
+        to.display()
+    );
+    anyhow::ensure!(
+        to.to_str().is_some(),
+        "{} is not a valid UTF-8 path",
+        to.display()
+    );
+    Ok(())
+}
+
+/// Record in `from` that its store moved to `to`.
+pub(crate) fn write_pointer(from: &Path, to: &Path) -> anyhow::Result<()> {
+    // `to` may be a previous location of the store, pointing back at `from`.
+    match std::fs::remove_file(to.join(RELOCATED_FILE)) {
+        Ok(()) => {}
+        Err(e) if e.kind() == ErrorKind::NotFound => {}
+        Err(e) => return Err(e).context("Failed to remove the old relocation pointer"),
+    }
+    let to = to
+        .to_str()
+        .context("data directory is not a valid UTF-8 path")?;
+    std::fs::write(from.join(RELOCATED_FILE), to).with_context(|| {
+        format!(
+            "Failed to record the new store location in {}",
+            from.display()
+        )
+    })
+}
+
+/// Moves directories, reporting the bytes moved across all of them.
+pub(crate) struct Mover<'a> {
+    progress: RelocateProgress,
+    progress_tx: &'a mpsc::Sender<RelocateProgress>,
+}
+
+impl<'a> Mover<'a> {
+    pub(crate) fn new(total_bytes: u64, progress_tx: &'a mpsc::Sender<RelocateProgress>) -> Self {
+        Self {
+            progress: RelocateProgress {
+                bytes_moved: 0,
+                total_bytes,
+            },
+            progress_tx,
+        }
+    }
+
+    /// Move the directory `from`, holding `size` bytes, to `to`.
+    ///
+    /// The directory is renamed when both paths are on the same filesystem,
+    /// and copied then removed otherwise. A failed copy removes what it
+    /// copied, leaving `from` intact.
+    pub(crate) async fn move_dir(
+        &mut self,
+        from: &Path,
+        to: &Path,
+        size: u64,
+    ) -> anyhow::Result<()> {
+        match tokio::fs::rename(from, to).await {
+            Ok(()) => {
+                self.advance(size).await;
+                Ok(())
+            }
+            Err(e) if e.kind() == ErrorKind::CrossesDevices => {
+                if let Err(e) = self.copy_dir(from, to).await {
+                    let _ = tokio::fs::remove_dir_all(to).await;
+                    return Err(e);
+                }
+                if let Err(e) = tokio::fs::remove_dir_all(from).await {
+                    tracing::warn!("failed to remove {} after copying it: {e}", from.display());
+                }
+                Ok(())
+            }
+            Err(e) => Err(e)
+                .with_context(|| format!("Failed to move {} to {}", from.display(), to.display())),
+        }
+    }
+
+    /// Copy the directory `from` to `to`, file by file.
+    async fn copy_dir(&mut self, from: &Path, to: &Path) -> anyhow::Result<()> {
+        let mut stack = vec![(from.to_path_buf(), to.to_path_buf())];
+        while let Some((from, to)) = stack.pop() {
+            tokio::fs::create_dir_all(&to)
+                .await
+                .with_context(|| format!("Failed to create {}", to.display()))?;
+            let mut entries = tokio::fs::read_dir(&from)
+                .await
+                .with_context(|| format!("Failed to read {}", from.display()))?;
+            while let Some(entry) = entries.next_entry().await? {
+                let target = to.join(entry.file_name());
+                if entry.file_type().await?.is_dir() {
+                    stack.push((entry.path(), target));
+                } else {
+                    let copied = tokio::fs::copy(entry.path(), &target)
+                        .await
+                        .with_context(|| format!("Failed to copy {}", entry.path().display()))?;
+                    self.advance(copied).await;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn advance(&mut self, bytes: u64) {
+        self.progress.bytes_moved += bytes;
+        let _ = self.progress_tx.send(self.progress).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn copy_dir_copies_nested_files_and_reports_progress() {
+        let dir = tempfile::tempdir().unwrap();
+        let from = dir.path().join("from");
+        std::fs::create_dir_all(from.join("nested")).unwrap();
+        std::fs::write(from.join("a"), [1, 2, 3]).unwrap();
+        std::fs::write(from.join("nested").join("b"), [4, 5]).unwrap();
+
+        let (tx, mut rx) = mpsc::channel(8);
+        let to = dir.path().join("to");
+        Mover::new(5, &tx).copy_dir(&from, &to).await.unwrap();
+        drop(tx);
+
+        assert_eq!(std::fs::read(to.join("a")).unwrap(), [1, 2, 3]);
+        assert_eq!(std::fs::read(to.join("nested").join("b")).unwrap(), [4, 5]);
+        let mut last = None;
+        while let Some(progress) = rx.recv().await {
+            last = Some(progress);
+        }
+        assert_eq!(
+            last,
+            Some(RelocateProgress {
+                bytes_moved: 5,
+                total_bytes: 5
+            })
+        );
+    }
+
+    #[test]
+    fn resolve_follows_pointers_to_the_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let (a, b, c) = (
+            dir.path().join("a"),
+            dir.path().join("b"),
+            dir.path().join("c"),
+        );
+        for path in [&a, &b] {
+            std::fs::create_dir_all(path).unwrap();
+        }
+        std::fs::create_dir_all(c.join("db")).unwrap();
+        write_pointer(&a, &b).unwrap();
+        write_pointer(&b, &c).unwrap();
+
+        assert_eq!(resolve(a), c);
+        assert_eq!(resolve(c.clone()), c);
+    }
+
+    #[test]
+    fn check_target_rejects_existing_stores() {
+        let dir = tempfile::tempdir().unwrap();
+        let from = dir.path().join("from");
+        let to = dir.path().join("to");
+        std::fs::create_dir_all(to.join("store")).unwrap();
+
+        assert!(check_target(&from, &to).is_err());
+        assert!(check_target(&from, &from.join("store").join("x")).is_err());
+        assert!(check_target(&from, &dir.path().join("elsewhere")).is_ok());
+    }
+}
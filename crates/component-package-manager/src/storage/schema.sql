@@ -72,6 +72,23 @@ CREATE TABLE _sync_meta (
     `value` TEXT NOT NULL
 );
 
+-- Cumulative pull statistics, used to judge cache efficiency.
+-- Holds exactly one row, which every completed pull updates.
+CREATE TABLE pull_metrics (
+    -- Always 1; the table is a single row of counters.
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    -- Number of completed pulls.
+    pulls INTEGER NOT NULL DEFAULT 0,
+    -- Number of pulls of images that were already stored.
+    cache_hits INTEGER NOT NULL DEFAULT 0,
+    -- Total layer bytes downloaded from registries.
+    bytes_downloaded INTEGER NOT NULL DEFAULT 0,
+    -- Total wall-clock time spent pulling, in milliseconds.
+    total_pull_ms INTEGER NOT NULL DEFAULT 0,
+    -- ISO 8601 timestamp of the most recent update.
+    updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
 -- ============================================================
 -- OCI LAYER: Repositories, Manifests, Tags, Layers, Referrers
 -- ============================================================
@@ -103,6 +120,13 @@ CREATE TABLE oci_repository (
     -- "interface" for a WIT interface type package.  NULL when
     -- the kind has not been determined yet.
     kind TEXT,
+    -- Human-readable description, taken from the
+    -- org.opencontainers.image.description (or .title) annotation of
+    -- the most recently fetched manifest, or from the meta-registry.
+    description TEXT,
+    -- 1 when the user pinned this repository: its images are never
+    -- pruned and it sorts first in package listings.
+    pinned INTEGER NOT NULL DEFAULT 0,
     UNIQUE(registry, repository)
 );
 
@@ -145,6 +169,9 @@ CREATE TABLE oci_manifest (
     size_bytes INTEGER,
     -- ISO 8601 timestamp of when this manifest was first recorded.
     created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    -- ISO 8601 timestamp of when this image was last pulled, installed,
+    -- or run.  Used by prune policies; NULL falls back to created_at.
+    last_accessed_at TEXT,
 
     -- Top-level OCI manifest fields (not annotations) ────────
 
@@ -621,6 +648,47 @@ CREATE UNIQUE INDEX uq_component_target ON component_target(
     COALESCE(declared_version, '')
 );
 
+-- ============================================================
+-- WIT FULL-TEXT SEARCH
+-- ============================================================
+
+-- Full-text index over the WIT source of every stored package, so
+-- packages can be found by the function, type, or interface names
+-- that appear in their WIT.  External-content table: the text lives
+-- in wit_package and is only tokenized here.
+CREATE VIRTUAL TABLE wit_package_fts USING fts5(
+    wit_text,
+    content = 'wit_package',
+    content_rowid = 'id'
+);
+
+-- Keep wit_package_fts in sync with wit_package.
+CREATE TRIGGER trg_wit_package_fts_insert
+    AFTER INSERT ON wit_package
+    FOR EACH ROW
+    BEGIN
+        INSERT INTO wit_package_fts(rowid, wit_text)
+        VALUES (NEW.id, NEW.wit_text);
+    END;
+
+CREATE TRIGGER trg_wit_package_fts_delete
+    AFTER DELETE ON wit_package
+    FOR EACH ROW
+    BEGIN
+        INSERT INTO wit_package_fts(wit_package_fts, rowid, wit_text)
+        VALUES ('delete', OLD.id, OLD.wit_text);
+    END;
+
+CREATE TRIGGER trg_wit_package_fts_update
+    AFTER UPDATE OF wit_text ON wit_package
+    FOR EACH ROW
+    BEGIN
+        INSERT INTO wit_package_fts(wit_package_fts, rowid, wit_text)
+        VALUES ('delete', OLD.id, OLD.wit_text);
+        INSERT INTO wit_package_fts(rowid, wit_text)
+        VALUES (NEW.id, NEW.wit_text);
+    END;
+
 -- ============================================================
 -- FETCH QUEUE
 --
@@ -715,11 +783,20 @@ CREATE INDEX idx_oci_manifest_artifact_type ON oci_manifest(artifact_type);
 -- Reverse tag lookup: find all tags that point to a given digest,
 -- e.g. for displaying all names for a manifest.
 CREATE INDEX idx_oci_tag_digest ON oci_tag(manifest_digest);
+-- Latest tag of a manifest within its repository, looked up for every
+-- row when listing stored images.
+CREATE INDEX idx_oci_tag_repository_digest ON oci_tag(oci_repository_id, manifest_digest);
 -- Search manifest annotations by key across all manifests,
 -- e.g. "find all manifests with com.example.custom-key".
 CREATE INDEX idx_oci_manifest_annotation_key ON oci_manifest_annotation(`key`);
 -- Search layer annotations by key across all layers.
 CREATE INDEX idx_oci_layer_annotation_key ON oci_layer_annotation(`key`);
+-- Cross-manifest layer lookup: find the images sharing a layer blob,
+-- e.g. before removing it from the content store or when grouping
+-- layers by digest for deduplication statistics.
+CREATE INDEX idx_oci_layer_digest ON oci_layer(digest);
+-- Find the manifests using a config blob, which keep it alive.
+CREATE INDEX idx_oci_manifest_config_digest ON oci_manifest(config_digest);
 -- Promoted OCI annotation columns: enable direct filtering
 -- by version, vendor, or license without JSON parsing.
 CREATE INDEX idx_oci_manifest_version ON oci_manifest(oci_version);
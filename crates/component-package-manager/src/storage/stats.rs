@@ -0,0 +1,273 @@
+use std::path::Path;
+
+use rusqlite::Connection;
+
+use super::dedup::{DedupStats, SpaceConsumer};
+use super::image_ref::{image_reference, latest_tag_sql};
+
+/// How many of the least recently used images [`StoreStats`] reports.
+const OLDEST_UNUSED: u32 = 5;
+
+/// Store-wide statistics: how many images and interfaces are stored, how
+/// much space they take, and which images are the largest or have gone
+/// unused the longest.
+///
+/// # Example
+///
+/// ```
+/// use component_package_manager::storage::StoreStats;
+///
+/// let stats = StoreStats {
+///     images: 2,
+///     interfaces: 3,
+///     logical_size: 4096,
+///     physical_size: 3072,
+///     largest: vec![],
+///     oldest_unused: vec![],
+/// };
+/// assert_eq!(stats.saved_size(), 1024);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StoreStats {
+    /// Number of stored images.
+    pub images: u64,
+    /// Number of distinct interfaces imported or exported by stored worlds,
+    /// counting each version separately.
+    pub interfaces: u64,
+    /// Sum of layer sizes as referenced by each image, in bytes.
+    pub logical_size: u64,
+    /// Bytes the layers take up in the content store: each shared layer
+    /// counted once, at its compressed size if it is stored compressed.
+    pub physical_size: u64,
+    /// The images referencing the most layer bytes, largest first.
+    pub largest: Vec<SpaceConsumer>,
+    /// The images that have gone unused the longest, oldest first.
+    pub oldest_unused: Vec<UnusedImage>,
+}
+
+impl StoreStats {
+    /// Bytes saved by sharing layers across images and compressing them.
+    #[must_use]
+    pub fn saved_size(&self) -> u64 {
+        self.logical_size.saturating_sub(self.physical_size)
+    }
+
+    /// Compute store statistics from the metadata database, measuring the
+    /// physical size from the blobs indexed in the content store `cache`.
+    pub(crate) fn query(conn: &Connection, cache: &Path) -> anyhow::Result<Self> {
+        let dedup = DedupStats::query(conn)?;
+
+        let images: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM oci_manifest WHERE raw_json IS NOT NULL",
+            [],
+            |row| row.get(0),
+        )?;
+
+        let interfaces: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM (
+                 SELECT declared_package, declared_interface, declared_version
+                   FROM wit_world_import WHERE declared_interface IS NOT NULL
+                 UNION
+                 SELECT declared_package, declared_interface, declared_version
+                   FROM wit_world_export WHERE declared_interface IS NOT NULL
+             )",
+            [],
+            |row| row.get(0),
+        )?;
+
+        let mut stmt = conn.prepare_cached(&format!(
+            "SELECT r.registry, r.repository, m.digest,
+                    {} AS tag,
+                    COALESCE(m.last_accessed_at, m.created_at) AS last_used
+             FROM oci_manifest m
+             JOIN oci_repository r ON r.id = m.oci_repository_id
+             WHERE m.raw_json IS NOT NULL
+             ORDER BY last_used ASC, m.id ASC
+             LIMIT ?1",
+            latest_tag_sql("r.id", "m.digest")
+        ))?;
+        let rows = stmt.query_map([OLDEST_UNUSED], |row| {
+            let registry: String = row.get(0)?;
+            let repository: String = row.get(1)?;
+            let digest: String = row.get(2)?;
+            let tag: Option<String> = row.get(3)?;
+            let last_used: String = row.get(4)?;
+            Ok(UnusedImage {
+                reference: image_reference(&registry, &repository, &digest, tag.as_deref()),
+                last_used,
+            })
+        })?;
+        let mut oldest_unused = Vec::new();
+        for row in rows {
+            oldest_unused.push(row?);
+        }
+
+        Ok(Self {
+            images: u64::try_from(images).unwrap_or(0),
+            interfaces: u64::try_from(interfaces).unwrap_or(0),
+            logical_size: dedup.logical_size,
+            physical_size: stored_size(conn, cache)?,
+            largest: dedup.top_consumers,
+            oldest_unused,
+        })
+    }
+}
+
+/// The bytes the distinct layers take up in the content store `cache`, as
+/// recorded in their index entries. Layers missing from the store take up
+/// nothing.
+fn stored_size(conn: &Connection, cache: &Path) -> anyhow::Result<u64> {
+    let mut stmt = conn.prepare_cached("SELECT DISTINCT digest FROM oci_layer")?;
+    let digests = stmt.query_map([], |row| row.get::<_, String>(0))?;
+    let mut total = 0u64;
+    for digest in digests {
+        if let Some(entry) = cacache::metadata_sync(cache, digest?)? {
+            total = total.saturating_add(u64::try_from(entry.size).unwrap_or(0));
+        }
+    }
+    Ok(total)
+}
+
+/// A stored image and when it was last pulled, installed, or run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnusedImage {
+    /// The image reference, e.g. `ghcr.io/example/app:1.0.0`.
+    pub reference: String,
+    /// When the image was last used, or first stored if it never was, as a
+    /// SQLite timestamp in UTC, e.g. `2025-01-31 12:00:00`.
+    pub last_used: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::oci::{OciLayer, OciManifest, OciRepository, OciTag};
+    use crate::storage::Migrations;
+    use std::collections::HashMap;
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        Migrations::run_all(&conn).unwrap();
+        conn
+    }
+
+    /// Store `repository:1.0.0` with one layer of `size` bytes, last used at
+    /// the SQLite timestamp `used`.
+    fn insert_image(conn: &Connection, repository: &str, size: i64, used: &str) {
+        let repo_id = OciRepository::upsert(conn, "ghcr.io", repository).unwrap();
+        let digest = format!("sha256:{repository}");
+        let (id, _) = OciManifest::upsert(
+            conn,
+            repo_id,
+            &digest,
+            None,
+            Some("{}"),
+            None,
+            None,
+            None,
+            None,
+            &HashMap::new(),
+        )
+        .unwrap();
+        OciTag::upsert(conn, repo_id, "1.0.0", &digest).unwrap();
+        OciLayer::insert(
+            conn,
+            id,
+            &format!("sha256:{repository}-layer"),
+            None,
+            Some(size),
+            0,
+        )
+        .unwrap();
+        conn.execute(
+            "UPDATE oci_manifest SET created_at = ?2, last_accessed_at = ?2 WHERE id = ?1",
+            rusqlite::params![id, used],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn empty_store_has_no_images() {
+        let conn = setup_test_db();
+        let dir = tempfile::tempdir().unwrap();
+        assert_eq!(
+            StoreStats::query(&conn, dir.path()).unwrap(),
+            StoreStats::default()
+        );
+    }
+
+    #[test]
+    fn counts_images_interfaces_and_ages() {
+        let conn = setup_test_db();
+        insert_image(&conn, "example/new", 10, "2025-06-01 00:00:00");
+        insert_image(&conn, "example/old", 30, "2024-01-01 00:00:00");
+
+        conn.execute(
+            "INSERT INTO wit_package (id, package_name, version) VALUES (1, 'example:app', '1.0.0')",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO wit_world (id, wit_package_id, name) VALUES (1, 1, 'app')",
+            [],
+        )
+        .unwrap();
+        for (table, interface) in [
+            ("wit_world_import", "streams"),
+            ("wit_world_import", "error"),
+            ("wit_world_export", "streams"),
+        ] {
+            conn.execute(
+                &format!(
+                    "INSERT INTO {table} (wit_world_id, declared_package, declared_interface, declared_version)
+                     VALUES (1, 'wasi:io', ?1, '0.2.0')"
+                ),
+                [interface],
+            )
+            .unwrap();
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let stats = StoreStats::query(&conn, dir.path()).unwrap();
+        assert_eq!(stats.images, 2);
+        assert_eq!(stats.interfaces, 2);
+        assert_eq!(stats.logical_size, 40);
+        // Neither layer is in the content store.
+        assert_eq!(stats.physical_size, 0);
+        assert_eq!(
+            stats.largest.first().map(|c| c.reference.as_str()),
+            Some("ghcr.io/example/old:1.0.0")
+        );
+        assert_eq!(
+            stats.oldest_unused,
+            vec![
+                UnusedImage {
+                    reference: "ghcr.io/example/old:1.0.0".to_string(),
+                    last_used: "2024-01-01 00:00:00".to_string(),
+                },
+                UnusedImage {
+                    reference: "ghcr.io/example/new:1.0.0".to_string(),
+                    last_used: "2025-06-01 00:00:00".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn physical_size_is_what_the_layers_take_up_in_the_store() {
+        let conn = setup_test_db();
+        let dir = tempfile::tempdir().unwrap();
+        insert_image(&conn, "example/app", 4096, "2025-06-01 00:00:00");
+        let key = "sha256:example/app-layer";
+        crate::storage::blob::write(dir.path(), key, &[7u8; 4096])
+            .await
+            .unwrap();
+        let entry = cacache::metadata(dir.path(), key).await.unwrap().unwrap();
+
+        let stats = StoreStats::query(&conn, dir.path()).unwrap();
+        assert_eq!(stats.logical_size, 4096);
+        assert_eq!(stats.physical_size, entry.size as u64);
+        assert!(stats.physical_size < 4096);
+        assert_eq!(stats.saved_size(), 4096 - entry.size as u64);
+    }
+}
@@ -4,13 +4,22 @@ use std::collections::HashMap;
 use std::collections::HashSet;
 use std::path::Path;
 
+use super::blob;
 use super::config::StateInfo;
+use super::dedup::DedupStats;
+use super::fsck::{self, FsckReport};
 use super::known_package::KnownPackageParams;
+use super::lock::StoreLock;
+use super::metrics::PullMetrics;
 use super::models::{Migrations, RawKnownPackage};
-use crate::components::{ComponentTarget, WasmComponent};
+use super::prune::PrunePolicy;
+use super::relocate::{self, RelocateProgress};
+use crate::StoreEvent;
+use crate::components::{ComponentExporter, ComponentTarget, WasmComponent};
+use crate::events::EVENT_CAPACITY;
 use crate::oci::{
     InsertResult, OciLayer, OciLayerAnnotation, OciManifest, OciReferrer, OciRepository, OciTag,
-    RawImageEntry,
+    RawImageEntry, SortBy, SortDirection, glob_match, manifest_description, verify_manifest_digest,
 };
 use crate::types::{
     RawWitPackage, WitPackageDependency, WitWorld, WitWorldExport, WitWorldImport,
@@ -18,6 +27,8 @@ use crate::types::{
 };
 use futures_concurrency::prelude::*;
 use oci_client::{Reference, client::ImageData, manifest::OciImageManifest};
+use rusqlite::trace::{TraceEvent, TraceEventCodes};
+use tokio::sync::{broadcast, mpsc};
 
 /// Outcome of [`Store::try_extract_wit_package`].
 ///
@@ -40,6 +51,86 @@ enum WitExtractOutcome {
     Failed(anyhow::Error),
 }
 
+/// Blobs written to the content store by [`Store::insert`] but not yet
+/// indexed under their digest.
+///
+/// Content without an index entry can't be read by key, so a staged blob is
+/// invisible until [`promote`](Self::promote) indexes it. If the insert fails,
+/// [`discard`](Self::discard) removes whatever the insert added.
+#[derive(Debug, Default)]
+struct StagedBlobs {
+    blobs: Vec<StagedBlob>,
+}
+
+/// A single staged blob.
+#[derive(Debug)]
+struct StagedBlob {
+    key: String,
+    integrity: cacache::Integrity,
+    /// The size of the stored, possibly compressed, bytes.
+    size: usize,
+    /// The index metadata recording the blob's compression, if any.
+    metadata: Option<serde_json::Value>,
+    /// Whether the content was not in the store before staging.
+    new_content: bool,
+    /// Whether the key was not indexed before staging.
+    new_key: bool,
+}
+
+impl StagedBlobs {
+    /// Write `data`'s content to the store, compressed, without indexing it
+    /// under `key`.
+    async fn stage(&mut self, cache: &Path, key: &str, data: &[u8]) -> anyhow::Result<()> {
+        let encoded = blob::encode(data)?;
+        let new_key = cacache::metadata(cache, key).await?.is_none();
+        let new_content = !cacache::exists(cache, &cacache::Integrity::from(&encoded.data)).await;
+        let integrity = cacache::write_hash(cache, &encoded.data).await?;
+        self.blobs.push(StagedBlob {
+            key: key.to_string(),
+            integrity,
+            size: encoded.data.len(),
+            metadata: encoded.metadata,
+            new_content,
+            new_key,
+        });
+        Ok(())
+    }
+
+    /// Index every staged blob under its key.
+    ///
+    /// Runs once the transaction that records the blobs' rows has
+    /// committed, so the index never points at blobs for rows that don't
+    /// exist.
+    async fn promote(&self, cache: &Path) -> anyhow::Result<()> {
+        for blob in &self.blobs {
+            cacache::index::insert_async(
+                cache,
+                &blob.key,
+                blob::write_opts(blob.integrity.clone(), blob.size, blob.metadata.as_ref()),
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Remove the index entries and content that staging added, leaving
+    /// blobs that were already in the store untouched.
+    async fn discard(&self, cache: &Path) {
+        for blob in &self.blobs {
+            if blob.new_key
+                && let Err(e) = cacache::remove(cache, &blob.key).await
+            {
+                tracing::warn!("Failed to remove staged key {}: {e}", blob.key);
+            }
+            if blob.new_content
+                && let Err(e) = cacache::remove_hash(cache, &blob.integrity).await
+            {
+                tracing::warn!("Failed to remove staged blob {}: {e}", blob.key);
+            }
+        }
+    }
+}
+
 /// The kind of work a [`FetchTask`] represents.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum FetchTaskKind {
@@ -74,7 +165,46 @@ pub struct FetchTask {
     /// How many times this task has been attempted so far.
     pub attempts: i64,
 }
-use rusqlite::{Connection, OptionalExtension};
+use rusqlite::backup::Backup;
+use rusqlite::{Connection, OpenFlags, OptionalExtension, TransactionBehavior};
+
+/// How long a connection waits for another process to release the database
+/// write lock before failing with `database is locked`.
+const BUSY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// How many prepared statements a connection keeps for reuse.
+///
+/// Queries go through `prepare_cached`, so with enough room every statement
+/// the store runs is only compiled once per connection.
+const STATEMENT_CACHE_CAPACITY: usize = 128;
+
+/// How many pages a database backup or restore copies per step.
+const BACKUP_PAGES_PER_STEP: std::ffi::c_int = 256;
+
+/// Emit a `trace` event with the wall-clock time of each SQL statement.
+fn log_sql_profile(event: TraceEvent<'_>) {
+    if let TraceEvent::Profile(stmt, elapsed) = event {
+        tracing::trace!(sql = %stmt.sql(), elapsed_us = elapsed.as_micros(), "sql statement");
+    }
+}
+
+/// Open the metadata database at `metadata_file`.
+fn connect(metadata_file: &Path) -> anyhow::Result<Connection> {
+    let conn = Connection::open(metadata_file)?;
+
+    // Configure SQLite for better concurrency, data integrity, and performance.
+    // WAL lets readers proceed while another process writes, and the busy
+    // timeout makes concurrent writers queue instead of failing outright.
+    conn.busy_timeout(BUSY_TIMEOUT)?;
+    conn.set_prepared_statement_cache_capacity(STATEMENT_CACHE_CAPACITY);
+    conn.execute_batch(
+        "PRAGMA foreign_keys = ON;
+         PRAGMA journal_mode = WAL;
+         PRAGMA synchronous = NORMAL;",
+    )?;
+    conn.trace_v2(TraceEventCodes::SQLITE_TRACE_PROFILE, Some(log_sql_profile));
+    Ok(conn)
+}
 
 /// Calculate the total size of a directory recursively
 async fn dir_size(path: &Path) -> u64 {
@@ -103,6 +233,7 @@ async fn dir_size(path: &Path) -> u64 {
 pub(crate) struct Store {
     pub(crate) state_info: StateInfo,
     conn: Connection,
+    events: broadcast::Sender<StoreEvent>,
 }
 
 /// A raw row from the `oci_manifest` table, used as an intermediate
@@ -141,15 +272,13 @@ struct ManifestRow {
 }
 
 impl Store {
-    /// Open the store and run any pending migrations.
+    /// Open the store in the default data directory, and run any pending
+    /// migrations.
     pub(crate) async fn open() -> anyhow::Result<Self> {
-        let data_dir = dirs::data_local_dir()
-            .context("No local data dir known for the current OS")?
-            .join("wasm");
-        let config_file = crate::xdg_config_home()
-            .context("Could not determine config directory (set $XDG_CONFIG_HOME or $HOME)")?
-            .join("wasm")
-            .join("config.toml");
+        let data_dir =
+            StateInfo::default_data_dir().context("No local data dir known for the current OS")?;
+        let config_file = crate::Config::config_path()
+            .context("Could not determine config directory (set $XDG_CONFIG_HOME or $HOME)")?;
         Self::open_inner(data_dir, config_file).await
     }
 
@@ -165,6 +294,7 @@ impl Store {
         data_dir: std::path::PathBuf,
         config_file: std::path::PathBuf,
     ) -> anyhow::Result<Self> {
+        let data_dir = relocate::resolve(data_dir);
         let store_dir = data_dir.join("store");
         let db_dir = data_dir.join("db");
         let metadata_file = db_dir.join("metadata.db3");
@@ -177,16 +307,20 @@ impl Store {
             .await
             .context("Could not create config directories on disk")?;
 
-        let conn = Connection::open(&metadata_file)?;
-
-        // Configure SQLite for better concurrency, data integrity, and performance
-        conn.execute_batch(
-            "PRAGMA foreign_keys = ON;
-             PRAGMA journal_mode = WAL;
-             PRAGMA synchronous = NORMAL;
-             PRAGMA busy_timeout = 5000;",
-        )?;
-
+        let conn = connect(&metadata_file)?;
+
+        // Snapshot an existing database before upgrading it, so a failed or
+        // unwanted migration can be undone by restoring the backup.
+        let before = Migrations::get(&conn);
+        if before.current > 0 && before.current < before.total {
+            let backup = Migrations::backup_path(&metadata_file, before.current);
+            Migrations::backup(&conn, &backup)?;
+            tracing::info!(
+                "backed up database at migration {} to {}",
+                before.current,
+                backup.display()
+            );
+        }
         Migrations::run_all(&conn)?;
 
         let migration_info = Migrations::get(&conn);
@@ -202,7 +336,26 @@ impl Store {
             metadata_size,
         );
 
-        Ok(Self { state_info, conn })
+        let mut store = Self {
+            state_info,
+            conn,
+            events: broadcast::channel(EVENT_CAPACITY).0,
+        };
+        let dedup_stats = store.dedup_stats().unwrap_or_else(|e| {
+            tracing::warn!("failed to compute layer deduplication stats: {e:#}");
+            DedupStats::default()
+        });
+        let pull_metrics = store.pull_metrics().unwrap_or_else(|e| {
+            tracing::warn!("failed to read pull metrics: {e:#}");
+            PullMetrics::default()
+        });
+        let migration_history = Migrations::history(&store.conn)?;
+        store.state_info = store
+            .state_info
+            .with_dedup_stats(dedup_stats)
+            .with_pull_metrics(pull_metrics)
+            .with_migration_history(migration_history);
+        Ok(store)
     }
 
     /// Create a Store directly from an in-memory SQLite connection.
@@ -228,9 +381,195 @@ impl Store {
             0,
             0,
         );
-        Self { state_info, conn }
+        Self {
+            state_info,
+            conn,
+            events: broadcast::channel(EVENT_CAPACITY).0,
+        }
+    }
+
+    /// Subscribe to [`StoreEvent`]s emitted from now on.
+    pub(crate) fn subscribe(&self) -> broadcast::Receiver<StoreEvent> {
+        self.events.subscribe()
+    }
+
+    /// Send an event to current subscribers, if any.
+    pub(crate) fn emit(&self, event: StoreEvent) {
+        // Sending only fails when nobody is subscribed.
+        let _ = self.events.send(event);
+    }
+
+    /// Take the cross-process store lock for the duration of a mutation
+    /// that touches both the database and the blob store.
+    async fn lock(&self) -> anyhow::Result<StoreLock> {
+        StoreLock::acquire(&self.state_info.data_dir().join("store.lock")).await
+    }
+
+    /// Back up the database, then migrate it up or down to `version`.
+    ///
+    /// Returns the path of the backup, or `None` if the database was already
+    /// at `version`.
+    pub(crate) async fn migrate_to(
+        &self,
+        version: u32,
+    ) -> anyhow::Result<Option<std::path::PathBuf>> {
+        let _lock = self.lock().await?;
+        let Migrations { current, total } = Migrations::get(&self.conn);
+        if current == version {
+            return Ok(None);
+        }
+        anyhow::ensure!(
+            version <= total,
+            "Cannot migrate to version {version}: the latest known migration is {total}"
+        );
+        let backup = Migrations::backup_path(self.state_info.metadata_file(), current);
+        Migrations::backup(&self.conn, &backup)?;
+        Migrations::migrate_to(&self.conn, version)?;
+        Ok(Some(backup))
+    }
+
+    /// Snapshot the metadata database to `path` with SQLite's online backup
+    /// API.
+    ///
+    /// The snapshot is written next to `path` and only renamed into place
+    /// once it is complete.
+    pub(crate) async fn backup(&self, path: &Path) -> anyhow::Result<()> {
+        let _lock = self.lock().await?;
+        let mut part = path.as_os_str().to_owned();
+        part.push(".part");
+        let part = std::path::PathBuf::from(part);
+
+        let result = (|| {
+            let mut dest = Connection::open(&part)?;
+            Backup::new(&self.conn, &mut dest)?.run_to_completion(
+                BACKUP_PAGES_PER_STEP,
+                std::time::Duration::ZERO,
+                None,
+            )?;
+            dest.close().map_err(|(_, e)| e)?;
+            std::fs::rename(&part, path)?;
+            Ok::<_, anyhow::Error>(())
+        })();
+        if result.is_err() {
+            let _ = std::fs::remove_file(&part);
+        }
+        result.with_context(|| format!("Failed to back up the database to {}", path.display()))
+    }
+
+    /// Replace the metadata database with the snapshot at `path`, then check
+    /// that the blobs its layers point at are present.
+    ///
+    /// The snapshot must be a metadata database this build can migrate;
+    /// older snapshots are migrated after restoring. The current database is
+    /// backed up next to `metadata.db3` first.
+    pub(crate) async fn restore(&mut self, path: &Path) -> anyhow::Result<FsckReport> {
+        let source = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        let check: String = source
+            .query_row("PRAGMA quick_check", [], |row| row.get(0))
+            .with_context(|| format!("{} is not a SQLite database", path.display()))?;
+        anyhow::ensure!(check == "ok", "{} is corrupt: {check}", path.display());
+        let Migrations { current, total } = Migrations::get(&source);
+        anyhow::ensure!(current > 0, "{} is not a metadata database", path.display());
+        anyhow::ensure!(
+            current <= total,
+            "{} is at migration {current}, newer than the latest known migration {total}",
+            path.display()
+        );
+
+        let pre_restore = self
+            .state_info
+            .metadata_file()
+            .with_extension("db3.pre-restore.bak");
+        self.backup(&pre_restore).await?;
+
+        let _lock = self.lock().await?;
+        Backup::new(&source, &mut self.conn)?.run_to_completion(
+            BACKUP_PAGES_PER_STEP,
+            std::time::Duration::ZERO,
+            None,
+        )?;
+        Migrations::run_all(&self.conn)?;
+        fsck::run(&self.conn, self.state_info.store_dir(), false).await
     }
 
+    /// Move the metadata database and the content store to `new_dir`,
+    /// leaving a pointer in the old data directory so opening it finds the
+    /// new one.
+    ///
+    /// If a move fails, the store is put back where it was.
+    pub(crate) async fn relocate(
+        &mut self,
+        new_dir: &Path,
+        progress_tx: &mpsc::Sender<RelocateProgress>,
+    ) -> anyhow::Result<()> {
+        let _lock = self.lock().await?;
+        let old_dir = self.state_info.data_dir().to_path_buf();
+        let new_dir = std::path::absolute(new_dir)?;
+        relocate::check_target(&old_dir, &new_dir)?;
+        tokio::fs::create_dir_all(&new_dir)
+            .await
+            .with_context(|| format!("Could not create {}", new_dir.display()))?;
+
+        let (old_db, new_db) = (old_dir.join("db"), new_dir.join("db"));
+        let (old_store, new_store) = (old_dir.join("store"), new_dir.join("store"));
+        let db_size = dir_size(&old_db).await;
+        let store_size = dir_size(&old_store).await;
+        let mut mover = relocate::Mover::new(db_size + store_size, progress_tx);
+
+        // Fold the WAL into the database file and close it, so the files
+        // are complete while they move.
+        self.conn
+            .execute_batch("PRAGMA wal_checkpoint(TRUNCATE);")?;
+        let conn = std::mem::replace(&mut self.conn, Connection::open_in_memory()?);
+        if let Err((conn, e)) = conn.close() {
+            self.conn = conn;
+            return Err(e.into());
+        }
+
+        let result = async {
+            mover.move_dir(&old_db, &new_db, db_size).await?;
+            if let Err(e) = mover.move_dir(&old_store, &new_store, store_size).await {
+                mover.move_dir(&new_db, &old_db, db_size).await?;
+                return Err(e);
+            }
+            Ok::<_, anyhow::Error>(())
+        }
+        .await;
+        let data_dir = if result.is_ok() { &new_dir } else { &old_dir };
+        self.conn = connect(&data_dir.join("db").join("metadata.db3"))?;
+        result?;
+
+        relocate::write_pointer(&old_dir, &new_dir)?;
+        self.state_info = self.state_info.clone().with_data_dir(new_dir);
+        Ok(())
+    }
+
+    /// Run `f` inside a write transaction, committing if it returns `Ok`
+    /// and rolling back otherwise.
+    ///
+    /// The transaction is started with `BEGIN IMMEDIATE` so the write lock
+    /// is taken up front: a concurrent process then waits on the busy timeout
+    /// instead of failing with `database is locked` when a deferred read
+    /// transaction tries to upgrade. `f` must not `.await` or start another
+    /// transaction; savepoints are fine.
+    pub(crate) fn transaction<T>(
+        &self,
+        f: impl FnOnce() -> anyhow::Result<T>,
+    ) -> anyhow::Result<T> {
+        let tx = rusqlite::Transaction::new_unchecked(&self.conn, TransactionBehavior::Immediate)?;
+        let value = f()?;
+        tx.commit()?;
+        Ok(value)
+    }
+
+    /// Store a pulled image: its rows in the database and its layer and
+    /// config blobs in the content store.
+    ///
+    /// The insert is atomic. Blobs are staged before the rows are written
+    /// and only become readable once the transaction has committed; if
+    /// anything fails, the rows are rolled back (or deleted, if indexing the
+    /// blobs fails after the commit) and the staged blobs removed.
     pub(crate) async fn insert(
         &self,
         reference: &Reference,
@@ -241,6 +580,8 @@ impl Store {
         Option<OciImageManifest>,
         Option<i64>,
     )> {
+        verify_manifest_digest(reference.digest(), image.digest.as_deref())?;
+        let _lock = self.lock().await?;
         let digest = reference.digest().map(str::to_owned).or(image.digest);
         let manifest_str = serde_json::to_string(&image.manifest)?;
 
@@ -251,11 +592,7 @@ impl Store {
             .map(|l| u64::try_from(l.data.len()).unwrap_or(u64::MAX))
             .sum();
 
-        // 1. Upsert oci_repository
-        let repo_id =
-            OciRepository::upsert(&self.conn, reference.registry(), reference.repository())?;
-
-        // 2. Extract annotations from the manifest (convert BTreeMap → HashMap)
+        // 1. Extract annotations from the manifest (convert BTreeMap → HashMap)
         let annotations: HashMap<String, String> = image
             .manifest
             .as_ref()
@@ -264,93 +601,147 @@ impl Store {
             .into_iter()
             .collect();
 
-        // 3. Upsert manifest (atomic insert-or-find)
-        let (manifest_id, was_inserted) = OciManifest::upsert(
-            &self.conn,
-            repo_id,
-            digest.as_deref().unwrap_or("unknown"),
-            image
-                .manifest
-                .as_ref()
-                .and_then(|m| m.media_type.as_deref()),
-            Some(&manifest_str),
-            Some(i64::try_from(size_on_disk).unwrap_or(i64::MAX)),
-            image
-                .manifest
-                .as_ref()
-                .and_then(|m| m.artifact_type.as_deref()),
+        // Layers are stored when the manifest is new, or when it is a
+        // placeholder (e.g. from referrer discovery) that has no layers yet.
+        // The store lock keeps this answer valid until the commit below.
+        let needs_layers = image.manifest.is_some()
+            && self.needs_layers(reference, digest.as_deref().unwrap_or("unknown"))?;
+
+        // Stage the blobs first, outside of any transaction, so the database
+        // write lock is never held across bulk filesystem IO. Staged content
+        // can't be looked up by digest until it is promoted.
+        let cache = self.state_info.store_dir();
+        let fallback_key = reference.whole();
+        let layer_digest = |idx: usize| {
             image
                 .manifest
                 .as_ref()
-                .map(|m| m.config.media_type.as_str()),
-            image.manifest.as_ref().map(|m| m.config.digest.as_str()),
-            &annotations,
-        )?;
-
-        let result = if was_inserted {
-            InsertResult::Inserted
-        } else {
-            InsertResult::AlreadyExists
+                .and_then(|m| m.layers.get(idx))
+                .map_or(fallback_key.as_str(), |l| l.digest.as_str())
         };
-
-        // 4. Upsert tag if present
-        if let Some(tag) = reference.tag()
-            && let Some(ref d) = digest
-        {
-            OciTag::upsert(&self.conn, repo_id, tag, d)?;
+        let mut staged = StagedBlobs::default();
+        if needs_layers && let Some(ref manifest) = image.manifest {
+            let blobs = image
+                .layers
+                .iter()
+                .enumerate()
+                .map(|(idx, layer)| (layer_digest(idx), layer.data.as_ref()))
+                // Keep the config blob too, so the image can be served as-is.
+                .chain(std::iter::once((
+                    manifest.config.digest.as_str(),
+                    image.config.data.as_ref(),
+                )));
+            for (key, data) in blobs {
+                if let Err(e) = staged.stage(cache, key, data).await {
+                    staged.discard(cache).await;
+                    return Err(e);
+                }
+            }
         }
 
-        let manifest = image.manifest.clone();
+        // Record the repository, manifest, tag, and layers in one transaction
+        // and promote the staged blobs once it has committed, so a failure
+        // at any point leaves neither rows nor blobs behind.
+        let recorded = self.transaction(|| {
+            let repo_id =
+                OciRepository::upsert(&self.conn, reference.registry(), reference.repository())?;
 
-        // Store layers when the manifest is newly inserted, or when it was a
-        // placeholder (e.g. from referrer discovery) that has no layers yet.
-        let needs_layers = was_inserted || {
-            let layer_count: i64 = self.conn.query_row(
-                "SELECT COUNT(*) FROM oci_layer WHERE oci_manifest_id = ?1",
-                [manifest_id],
-                |row| row.get(0),
+            // Atomic insert-or-find
+            let (manifest_id, was_inserted) = OciManifest::upsert(
+                &self.conn,
+                repo_id,
+                digest.as_deref().unwrap_or("unknown"),
+                image
+                    .manifest
+                    .as_ref()
+                    .and_then(|m| m.media_type.as_deref()),
+                Some(&manifest_str),
+                Some(i64::try_from(size_on_disk).unwrap_or(i64::MAX)),
+                image
+                    .manifest
+                    .as_ref()
+                    .and_then(|m| m.artifact_type.as_deref()),
+                image
+                    .manifest
+                    .as_ref()
+                    .map(|m| m.config.media_type.as_str()),
+                image.manifest.as_ref().map(|m| m.config.digest.as_str()),
+                &annotations,
             )?;
-            layer_count == 0
-        };
+            OciManifest::touch(&self.conn, manifest_id)?;
+            if let Some(description) = image.manifest.as_ref().and_then(manifest_description) {
+                OciRepository::set_description(&self.conn, repo_id, description)?;
+            }
 
-        if needs_layers && let Some(ref manifest) = image.manifest {
-            for (idx, layer) in image.layers.iter().enumerate() {
-                let cache = self.state_info.store_dir();
-                let fallback_key = reference.whole().clone();
-                let layer_digest = manifest
-                    .layers
-                    .get(idx)
-                    .map_or(fallback_key.as_str(), |l| l.digest.as_str());
-                let layer_media_type = manifest.layers.get(idx).map(|l| l.media_type.as_str());
-                let layer_size = manifest.layers.get(idx).map(|l| l.size);
-                let data = &layer.data;
-                let _integrity = cacache::write(&cache, layer_digest, data).await?;
-
-                // Record the layer in oci_layer
-                let layer_id = OciLayer::insert(
-                    &self.conn,
-                    manifest_id,
-                    layer_digest,
-                    layer_media_type,
-                    layer_size.map(|s| s.max(0)),
-                    i32::try_from(idx).unwrap_or(i32::MAX),
-                )?;
+            if let Some(tag) = reference.tag()
+                && let Some(ref d) = digest
+            {
+                OciTag::upsert(&self.conn, repo_id, tag, d)?;
+            }
 
-                // Store layer-level annotations
-                if let Some(descriptor) = manifest.layers.get(idx)
-                    && let Some(ref annotations) = descriptor.annotations
-                {
-                    for (key, value) in annotations {
-                        if let Err(e) = OciLayerAnnotation::insert(&self.conn, layer_id, key, value)
-                        {
-                            tracing::warn!("Failed to insert layer annotation '{}': {}", key, e);
+            if needs_layers && let Some(ref manifest) = image.manifest {
+                for (idx, layer) in image.layers.iter().enumerate() {
+                    let layer_media_type = manifest.layers.get(idx).map(|l| l.media_type.as_str());
+                    let layer_size = manifest.layers.get(idx).map(|l| l.size);
+
+                    // Record the layer in oci_layer
+                    let layer_id = OciLayer::insert(
+                        &self.conn,
+                        manifest_id,
+                        layer_digest(idx),
+                        layer_media_type,
+                        layer_size.map(|s| s.max(0)),
+                        i32::try_from(idx).unwrap_or(i32::MAX),
+                    )?;
+
+                    // Store layer-level annotations
+                    if let Some(descriptor) = manifest.layers.get(idx)
+                        && let Some(ref annotations) = descriptor.annotations
+                    {
+                        for (key, value) in annotations {
+                            if let Err(e) =
+                                OciLayerAnnotation::insert(&self.conn, layer_id, key, value)
+                            {
+                                tracing::warn!(
+                                    "Failed to insert layer annotation '{}': {}",
+                                    key,
+                                    e
+                                );
+                            }
                         }
                     }
+
+                    self.try_extract_wit_package(manifest_id, Some(layer_id), &layer.data);
                 }
+            }
 
-                self.try_extract_wit_package(manifest_id, Some(layer_id), data);
+            Ok((manifest_id, was_inserted))
+        });
+        let (manifest_id, was_inserted) = match recorded {
+            Ok(recorded) => recorded,
+            Err(e) => {
+                staged.discard(cache).await;
+                return Err(e);
+            }
+        };
+        if let Err(e) = staged.promote(cache).await {
+            staged.discard(cache).await;
+            // The rows describe layers that can't be read; drop the image so
+            // it is pulled again cleanly.
+            if let Err(delete_err) =
+                self.transaction(|| OciManifest::delete(&self.conn, manifest_id))
+            {
+                tracing::warn!("Failed to remove image after failed blob indexing: {delete_err}");
             }
+            return Err(e);
         }
+
+        let result = if was_inserted {
+            InsertResult::Inserted
+        } else {
+            InsertResult::AlreadyExists
+        };
+        let manifest = image.manifest;
         let manifest_id_opt = if result == InsertResult::Inserted {
             Some(manifest_id)
         } else {
@@ -359,6 +750,20 @@ impl Store {
         Ok((result, digest, manifest, manifest_id_opt))
     }
 
+    /// Whether the manifest `digest` in `reference`'s repository is missing
+    /// or has no layers recorded.
+    fn needs_layers(&self, reference: &Reference, digest: &str) -> anyhow::Result<bool> {
+        let Some(repo) =
+            OciRepository::find(&self.conn, reference.registry(), reference.repository())?
+        else {
+            return Ok(true);
+        };
+        match OciManifest::find(&self.conn, repo.id(), digest)? {
+            Some(manifest) => Ok(OciLayer::list_by_manifest(&self.conn, manifest.id())?.is_empty()),
+            None => Ok(true),
+        }
+    }
+
     /// Insert only the metadata (SQLite entry) for an image, without storing layers.
     ///
     /// Returns the insert result and the optional manifest ID.
@@ -371,9 +776,6 @@ impl Store {
     ) -> anyhow::Result<(InsertResult, Option<i64>)> {
         let manifest_str = serde_json::to_string(manifest)?;
 
-        let repo_id =
-            OciRepository::upsert(&self.conn, reference.registry(), reference.repository())?;
-
         let annotations: HashMap<String, String> = manifest
             .annotations
             .clone()
@@ -381,38 +783,61 @@ impl Store {
             .into_iter()
             .collect();
 
-        // Atomic upsert — insert or find existing
-        let (manifest_id, was_inserted) = OciManifest::upsert(
-            &self.conn,
-            repo_id,
-            digest.unwrap_or("unknown"),
-            manifest.media_type.as_deref(),
-            Some(&manifest_str),
-            Some(i64::try_from(size_on_disk).unwrap_or(i64::MAX)),
-            manifest.artifact_type.as_deref(),
-            Some(manifest.config.media_type.as_str()),
-            Some(manifest.config.digest.as_str()),
-            &annotations,
-        )?;
+        self.transaction(|| {
+            let repo_id =
+                OciRepository::upsert(&self.conn, reference.registry(), reference.repository())?;
 
-        let result = if was_inserted {
-            InsertResult::Inserted
-        } else {
-            InsertResult::AlreadyExists
-        };
+            // Atomic upsert — insert or find existing
+            let (manifest_id, was_inserted) = OciManifest::upsert(
+                &self.conn,
+                repo_id,
+                digest.unwrap_or("unknown"),
+                manifest.media_type.as_deref(),
+                Some(&manifest_str),
+                Some(i64::try_from(size_on_disk).unwrap_or(i64::MAX)),
+                manifest.artifact_type.as_deref(),
+                Some(manifest.config.media_type.as_str()),
+                Some(manifest.config.digest.as_str()),
+                &annotations,
+            )?;
+            OciManifest::touch(&self.conn, manifest_id)?;
+            if let Some(description) = manifest_description(manifest) {
+                OciRepository::set_description(&self.conn, repo_id, description)?;
+            }
 
-        // Upsert tag if present
-        if let Some(tag) = reference.tag()
-            && let Some(d) = digest
-        {
-            OciTag::upsert(&self.conn, repo_id, tag, d)?;
-        }
+            let result = if was_inserted {
+                InsertResult::Inserted
+            } else {
+                InsertResult::AlreadyExists
+            };
 
-        if result == InsertResult::Inserted {
-            Ok((result, Some(manifest_id)))
-        } else {
-            Ok((result, None))
-        }
+            // Upsert tag if present
+            if let Some(tag) = reference.tag()
+                && let Some(d) = digest
+            {
+                OciTag::upsert(&self.conn, repo_id, tag, d)?;
+            }
+
+            if result == InsertResult::Inserted {
+                Ok((result, Some(manifest_id)))
+            } else {
+                Ok((result, None))
+            }
+        })
+    }
+
+    /// Insert an image's config blob into the content-addressable store.
+    ///
+    /// Config blobs have no row of their own; they are kept alive by the
+    /// `config_digest` of the manifests that use them.
+    pub(crate) async fn insert_config(
+        &self,
+        config_digest: &str,
+        data: &[u8],
+    ) -> anyhow::Result<()> {
+        let _lock = self.lock().await?;
+        blob::write(self.state_info.store_dir(), config_digest, data).await?;
+        Ok(())
     }
 
     /// Insert a single layer into the content-addressable store.
@@ -431,34 +856,36 @@ impl Store {
         position: i32,
         layer_annotations: Option<&BTreeMap<String, String>>,
     ) -> anyhow::Result<()> {
+        let _lock = self.lock().await?;
         let cache = self.state_info.store_dir();
-        let _integrity = cacache::write(&cache, layer_digest, data).await?;
+        blob::write(cache, layer_digest, data).await?;
 
         let Some(manifest_id) = manifest_id else {
             return Ok(());
         };
 
-        let layer_id = OciLayer::insert(
-            &self.conn,
-            manifest_id,
-            layer_digest,
-            media_type,
-            Some(i64::try_from(data.len()).unwrap_or(i64::MAX)),
-            position,
-        )?;
+        self.transaction(|| {
+            let layer_id = OciLayer::insert(
+                &self.conn,
+                manifest_id,
+                layer_digest,
+                media_type,
+                Some(i64::try_from(data.len()).unwrap_or(i64::MAX)),
+                position,
+            )?;
 
-        // Store layer-level annotations
-        if let Some(annotations) = layer_annotations {
-            for (key, value) in annotations {
-                if let Err(e) = OciLayerAnnotation::insert(&self.conn, layer_id, key, value) {
-                    tracing::warn!("Failed to insert layer annotation '{}': {}", key, e);
+            // Store layer-level annotations
+            if let Some(annotations) = layer_annotations {
+                for (key, value) in annotations {
+                    if let Err(e) = OciLayerAnnotation::insert(&self.conn, layer_id, key, value) {
+                        tracing::warn!("Failed to insert layer annotation '{}': {}", key, e);
+                    }
                 }
             }
-        }
-
-        self.try_extract_wit_package(manifest_id, Some(layer_id), data);
 
-        Ok(())
+            self.try_extract_wit_package(manifest_id, Some(layer_id), data);
+            Ok(())
+        })
     }
 
     /// Outcome of attempting to extract a WIT package from wasm bytes.
@@ -606,6 +1033,9 @@ impl Store {
 
         // Best-effort resolution of cross-package foreign keys
         self.try_resolve_foreign_keys(wit_package_id, manifest_id);
+        self.emit(StoreEvent::WitExtracted {
+            package: raw_name.to_owned(),
+        });
         WitExtractOutcome::Extracted
     }
 
@@ -646,30 +1076,32 @@ impl Store {
         referrer_digest: &str,
         artifact_type: &str,
     ) -> anyhow::Result<()> {
-        let repo_id = OciRepository::upsert(&self.conn, registry, repository)?;
+        self.transaction(|| {
+            let repo_id = OciRepository::upsert(&self.conn, registry, repository)?;
 
-        // Upsert a minimal manifest entry for the referrer
-        let (referrer_manifest_id, _) = OciManifest::upsert(
-            &self.conn,
-            repo_id,
-            referrer_digest,
-            None,
-            None,
-            None,
-            Some(artifact_type),
-            None,
-            None,
-            &HashMap::new(),
-        )?;
+            // Upsert a minimal manifest entry for the referrer
+            let (referrer_manifest_id, _) = OciManifest::upsert(
+                &self.conn,
+                repo_id,
+                referrer_digest,
+                None,
+                None,
+                None,
+                Some(artifact_type),
+                None,
+                None,
+                &HashMap::new(),
+            )?;
 
-        OciReferrer::insert(
-            &self.conn,
-            subject_manifest_id,
-            referrer_manifest_id,
-            artifact_type,
-        )?;
+            OciReferrer::insert(
+                &self.conn,
+                subject_manifest_id,
+                referrer_manifest_id,
+                artifact_type,
+            )?;
 
-        Ok(())
+            Ok(())
+        })
     }
 
     /// Re-extract WIT metadata for every package that has a cached OCI layer.
@@ -681,13 +1113,14 @@ impl Store {
     /// improvements to the extraction logic.
     ///
     /// Original OCI data (manifests, layers, blobs) is never modified.
+    #[tracing::instrument(level = "debug", skip_all)]
     pub(crate) async fn reindex_wit_packages(&self) -> anyhow::Result<u64> {
         // Collect (wit_package.id, oci_manifest_id, oci_layer_id, layer_digest)
         // tuples.  Two sources:
         //   1. wit_package rows that already have an oci_layer_id.
         //   2. wit_package rows with only an oci_manifest_id (legacy data) —
         //      we resolve the layer via the manifest's first wasm layer.
-        let mut stmt = self.conn.prepare(
+        let mut stmt = self.conn.prepare_cached(
             "SELECT wp.id, wp.oci_manifest_id, ol.id, ol.digest
              FROM wit_package wp
              JOIN oci_layer ol ON ol.id = wp.oci_layer_id
@@ -713,7 +1146,7 @@ impl Store {
 
         for (wit_id, manifest_id, layer_id, digest) in &rows {
             // Read the raw bytes from cacache.
-            let bytes = match cacache::read(&store_dir, digest).await {
+            let bytes = match blob::read(&store_dir, digest).await {
                 Ok(b) => b,
                 Err(e) => {
                     tracing::warn!("reindex: failed to read layer {digest} from cache: {e}");
@@ -774,98 +1207,345 @@ impl Store {
         Ok(reindexed)
     }
 
+    /// Re-extract WIT metadata from the first layer of every stored image.
+    ///
+    /// Unlike [`Self::reindex_wit_packages`], this also visits images that
+    /// have no `wit_package` row yet, such as images pulled before WIT
+    /// extraction existed. Each image is rescanned inside a savepoint: its
+    /// `wit_package` and `wasm_component` rows are only replaced when
+    /// extraction succeeds, so layers that aren't components keep whatever
+    /// rows they had.
+    ///
+    /// Returns the number of images a WIT package was extracted from.
+    #[tracing::instrument(level = "debug", skip_all)]
+    pub(crate) async fn rescan_wit_packages(&self) -> anyhow::Result<u64> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT m.id, ol.id, ol.digest
+             FROM oci_manifest m
+             JOIN oci_layer ol ON ol.oci_manifest_id = m.id
+             WHERE ol.position = (
+                 SELECT MIN(ol2.position) FROM oci_layer ol2
+                 WHERE ol2.oci_manifest_id = m.id
+             )
+             ORDER BY m.id",
+        )?;
+        let rows: Vec<(i64, i64, String)> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let store_dir = self.state_info.store_dir().to_path_buf();
+        let mut rescanned = 0u64;
+
+        for (manifest_id, layer_id, digest) in &rows {
+            let bytes = match blob::read(&store_dir, digest).await {
+                Ok(b) => b,
+                Err(e) => {
+                    tracing::warn!("rescan: failed to read layer {digest} from cache: {e}");
+                    continue;
+                }
+            };
+
+            self.conn.execute_batch("SAVEPOINT rescan_wit_package")?;
+
+            let outcome = self
+                .conn
+                .execute(
+                    "DELETE FROM wit_package WHERE oci_manifest_id = ?1",
+                    [manifest_id],
+                )
+                .and_then(|_| {
+                    self.conn.execute(
+                        "DELETE FROM wasm_component WHERE oci_manifest_id = ?1",
+                        [manifest_id],
+                    )
+                })
+                .map_err(anyhow::Error::from)
+                .map(|_| self.try_extract_wit_package(*manifest_id, Some(*layer_id), &bytes));
+
+            match outcome {
+                Ok(WitExtractOutcome::Extracted) => {
+                    self.conn
+                        .execute_batch("RELEASE SAVEPOINT rescan_wit_package")?;
+                    rescanned += 1;
+                }
+                Ok(WitExtractOutcome::NotApplicable) => {
+                    self.conn.execute_batch(
+                        "ROLLBACK TO SAVEPOINT rescan_wit_package; RELEASE SAVEPOINT rescan_wit_package",
+                    )?;
+                }
+                Ok(WitExtractOutcome::Failed(e)) | Err(e) => {
+                    tracing::warn!("rescan: failed to re-extract WIT from layer {digest}: {e}");
+                    self.conn.execute_batch(
+                        "ROLLBACK TO SAVEPOINT rescan_wit_package; RELEASE SAVEPOINT rescan_wit_package",
+                    )?;
+                }
+            }
+        }
+
+        Ok(rescanned)
+    }
+
     /// Returns all currently stored images and their metadata.
     pub(crate) fn list_all(&self) -> anyhow::Result<Vec<RawImageEntry>> {
         RawImageEntry::get_all(&self.conn)
     }
 
+    /// Returns stored images ordered by `sort` in `direction`, starting at
+    /// `offset`. A `limit` of `None` returns every remaining image.
+    pub(crate) fn list_sorted(
+        &self,
+        sort: SortBy,
+        direction: SortDirection,
+        offset: u32,
+        limit: Option<u32>,
+    ) -> anyhow::Result<Vec<RawImageEntry>> {
+        RawImageEntry::get_page(&self.conn, sort, direction, offset, limit)
+    }
+
+    /// Returns the stored images and known tagged references that point at
+    /// `digest`, as a manifest or a layer digest.
+    pub(crate) fn find_by_digest(
+        &self,
+        digest: &str,
+    ) -> anyhow::Result<(Vec<RawImageEntry>, Vec<String>)> {
+        let images = RawImageEntry::find_by_digest(&self.conn, digest)?;
+        let tags = OciTag::references_to_digest(&self.conn, digest)?;
+        Ok((images, tags))
+    }
+
+    /// Returns the manifests and tags stored for a repository, or `None` if
+    /// nothing has been stored for it.
+    pub(crate) fn repository_contents(
+        &self,
+        registry: &str,
+        repository: &str,
+    ) -> anyhow::Result<Option<(Vec<OciManifest>, Vec<OciTag>)>> {
+        let Some(repo) = OciRepository::find(&self.conn, registry, repository)? else {
+            return Ok(None);
+        };
+        let manifests = OciManifest::list_by_repository(&self.conn, repo.id())?;
+        let tags = OciTag::list_by_repository(&self.conn, repo.id())?;
+        Ok(Some((manifests, tags)))
+    }
+
+    /// Returns every repository, ordered by name.
+    pub(crate) fn list_repositories(&self) -> anyhow::Result<Vec<OciRepository>> {
+        OciRepository::list_all(&self.conn)
+    }
+
+    /// Returns the number of stored images.
+    pub(crate) fn count_images(&self) -> anyhow::Result<u64> {
+        RawImageEntry::count(&self.conn)
+    }
+
     /// Deletes an image by its reference.
     /// Only removes cached layers if no other images reference them.
+    #[tracing::instrument(level = "debug", skip_all, fields(reference = %reference))]
     pub(crate) async fn delete(&self, reference: &Reference) -> anyhow::Result<bool> {
-        // Find the repository
-        let repo = OciRepository::find(&self.conn, reference.registry(), reference.repository())?;
-        let Some(repo) = repo else {
-            return Ok(false);
-        };
+        let _lock = self.lock().await?;
+
+        // Resolve and delete the rows in one transaction, then remove the
+        // orphaned blobs once the rows that referenced them are gone.
+        let orphaned = self.transaction(|| {
+            // Find the repository
+            let repo =
+                OciRepository::find(&self.conn, reference.registry(), reference.repository())?;
+            let Some(repo) = repo else {
+                return Ok(None);
+            };
 
-        // Resolve the manifest(s) to delete
-        let repo_id = repo.id();
-        let manifests_to_delete = match (reference.tag(), reference.digest()) {
-            (Some(tag), Some(digest)) => {
-                // Both tag and digest specified — verify tag matches digest, then delete
-                if let Some(oci_tag) = OciTag::find_by_tag(&self.conn, repo_id, tag)? {
-                    if oci_tag.manifest_digest == digest {
-                        OciManifest::find(&self.conn, repo_id, digest)?
+            // Resolve the manifest(s) to delete
+            let repo_id = repo.id();
+            let manifests_to_delete = match (reference.tag(), reference.digest()) {
+                (Some(tag), Some(digest)) => {
+                    // Both tag and digest specified — verify tag matches digest, then delete
+                    if let Some(oci_tag) = OciTag::find_by_tag(&self.conn, repo_id, tag)? {
+                        if oci_tag.manifest_digest == digest {
+                            OciManifest::find(&self.conn, repo_id, digest)?
+                                .into_iter()
+                                .collect()
+                        } else {
+                            Vec::new()
+                        }
+                    } else {
+                        Vec::new()
+                    }
+                }
+                (Some(tag), None) => {
+                    // Resolve tag to digest
+                    if let Some(oci_tag) = OciTag::find_by_tag(&self.conn, repo_id, tag)? {
+                        OciManifest::find(&self.conn, repo_id, &oci_tag.manifest_digest)?
                             .into_iter()
                             .collect()
                     } else {
                         Vec::new()
                     }
-                } else {
-                    Vec::new()
                 }
-            }
-            (Some(tag), None) => {
-                // Resolve tag to digest
-                if let Some(oci_tag) = OciTag::find_by_tag(&self.conn, repo_id, tag)? {
-                    OciManifest::find(&self.conn, repo_id, &oci_tag.manifest_digest)?
-                        .into_iter()
-                        .collect()
-                } else {
-                    Vec::new()
+                (None, Some(digest)) => OciManifest::find(&self.conn, repo_id, digest)?
+                    .into_iter()
+                    .collect(),
+                (None, None) => {
+                    // Delete all manifests for this repo
+                    OciManifest::list_by_repository(&self.conn, repo_id)?
                 }
+            };
+
+            if manifests_to_delete.is_empty() {
+                return Ok(None);
             }
-            (None, Some(digest)) => OciManifest::find(&self.conn, repo_id, digest)?
-                .into_iter()
-                .collect(),
-            (None, None) => {
-                // Delete all manifests for this repo
-                OciManifest::list_by_repository(&self.conn, repo_id)?
-            }
+            self.delete_manifest_rows(&manifests_to_delete).map(Some)
+        })?;
+        let Some(orphaned) = orphaned else {
+            return Ok(false);
         };
+        self.remove_blobs(&orphaned).await;
+        Ok(true)
+    }
 
-        if manifests_to_delete.is_empty() {
-            return Ok(false);
-        }
+    /// Deletes every image in a repository, returning how many were removed.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub(crate) async fn delete_repository(
+        &self,
+        registry: &str,
+        repository: &str,
+    ) -> anyhow::Result<usize> {
+        let _lock = self.lock().await?;
+        let (count, orphaned) = self.transaction(|| {
+            let Some(repo) = OciRepository::find(&self.conn, registry, repository)? else {
+                return Ok((0, Vec::new()));
+            };
+            let manifests = OciManifest::list_by_repository(&self.conn, repo.id())?;
+            Ok((manifests.len(), self.delete_manifest_rows(&manifests)?))
+        })?;
+        self.remove_blobs(&orphaned).await;
+        Ok(count)
+    }
 
-        // Collect all layer digests from manifests being deleted
-        let mut layer_digests: HashSet<String> = HashSet::new();
-        let mut manifest_ids: Vec<i64> = Vec::new();
-        for manifest in &manifests_to_delete {
-            manifest_ids.push(manifest.id());
-            if let Ok(layers) = OciLayer::list_by_manifest(&self.conn, manifest.id()) {
-                for l in layers {
-                    layer_digests.insert(l.digest);
+    /// Deletes every image, in any repository, whose manifest digest starts
+    /// with `prefix`. Returns how many were removed.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub(crate) async fn delete_by_digest_prefix(&self, prefix: &str) -> anyhow::Result<usize> {
+        let _lock = self.lock().await?;
+        let (count, orphaned) = self.transaction(|| {
+            let manifests = OciManifest::list_by_digest_prefix(&self.conn, prefix)?;
+            Ok((manifests.len(), self.delete_manifest_rows(&manifests)?))
+        })?;
+        self.remove_blobs(&orphaned).await;
+        Ok(count)
+    }
+
+    /// Deletes every stored image whose `registry/repository`, tagged
+    /// reference, or digest reference matches the glob `pattern`, except
+    /// those in pinned repositories. Returns how many were removed.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub(crate) async fn delete_matching(&self, pattern: &str) -> anyhow::Result<usize> {
+        let _lock = self.lock().await?;
+        let (count, orphaned) = self.transaction(|| {
+            let mut manifests = Vec::new();
+            for repo in OciRepository::list_all(&self.conn)? {
+                if OciRepository::is_pinned(&self.conn, repo.id())? {
+                    continue;
+                }
+                let name = format!("{}/{}", repo.registry, repo.repository);
+                let tags = OciTag::list_by_repository(&self.conn, repo.id())?;
+                for manifest in OciManifest::list_by_repository(&self.conn, repo.id())? {
+                    let matches = glob_match(pattern, &name)
+                        || glob_match(pattern, &format!("{name}@{}", manifest.digest))
+                        || tags.iter().any(|tag| {
+                            tag.manifest_digest == manifest.digest
+                                && glob_match(pattern, &format!("{name}:{}", tag.tag))
+                        });
+                    if matches {
+                        manifests.push(manifest);
+                    }
                 }
             }
+            Ok((manifests.len(), self.delete_manifest_rows(&manifests)?))
+        })?;
+        self.remove_blobs(&orphaned).await;
+        Ok(count)
+    }
+
+    /// Removes the images selected by `policy`, returning their references.
+    ///
+    /// With [`PrunePolicy::dry_run`] set, nothing is removed and the
+    /// references that would be removed are returned.
+    #[tracing::instrument(level = "debug", skip(self))]
+    pub(crate) async fn prune(&self, policy: &PrunePolicy) -> anyhow::Result<Vec<String>> {
+        if policy.dry_run {
+            let candidates = policy.candidates(&self.conn)?;
+            return Ok(candidates.into_iter().map(|(_, r)| r).collect());
         }
 
-        // Find layers still needed by other manifests (ones NOT being deleted)
-        let all_manifests = OciManifest::list_by_repository(&self.conn, repo_id)?;
-        let mut retained_digests: HashSet<String> = HashSet::new();
-        for other in &all_manifests {
-            if manifest_ids.contains(&other.id()) {
-                continue;
-            }
-            if let Ok(other_layers) = OciLayer::list_by_manifest(&self.conn, other.id()) {
-                for l in other_layers {
-                    retained_digests.insert(l.digest);
+        let _lock = self.lock().await?;
+        let (removed, orphaned) = self.transaction(|| {
+            let mut manifests = Vec::new();
+            let mut removed = Vec::new();
+            for (id, reference) in policy.candidates(&self.conn)? {
+                if let Some(manifest) = OciManifest::get_by_id(&self.conn, id)? {
+                    manifests.push(manifest);
+                    removed.push(reference);
                 }
             }
+            Ok((removed, self.delete_manifest_rows(&manifests)?))
+        })?;
+        self.remove_blobs(&orphaned).await;
+        Ok(removed)
+    }
+
+    /// Records that a stored image was just used, for [`PrunePolicy::unused_for`].
+    pub(crate) fn record_access(
+        &self,
+        registry: &str,
+        repository: &str,
+        digest: &str,
+    ) -> anyhow::Result<()> {
+        if let Some(repo) = OciRepository::find(&self.conn, registry, repository)?
+            && let Some(manifest) = OciManifest::find(&self.conn, repo.id(), digest)?
+        {
+            OciManifest::touch(&self.conn, manifest.id())?;
         }
+        Ok(())
+    }
 
-        // Remove cached layers that are no longer needed
-        let orphaned = crate::oci::compute_orphaned_layers(&layer_digests, &retained_digests);
-        for layer_digest in &orphaned {
-            let _ = cacache::remove(self.state_info.store_dir(), layer_digest).await;
+    /// Deletes `manifests` and returns the digests of their layers that no
+    /// remaining manifest, in any repository, still references.
+    ///
+    /// Must be called inside [`Self::transaction`]; the returned blobs should
+    /// only be removed once it commits.
+    fn delete_manifest_rows(&self, manifests: &[OciManifest]) -> anyhow::Result<Vec<String>> {
+        let mut blob_digests: HashSet<String> = HashSet::new();
+        for manifest in manifests {
+            for layer in OciLayer::list_by_manifest(&self.conn, manifest.id())? {
+                blob_digests.insert(layer.digest);
+            }
+            blob_digests.extend(manifest.config_digest.clone());
         }
 
         // Delete the manifests (FK cascade handles layers, tags, etc.)
-        for manifest in &manifests_to_delete {
+        for manifest in manifests {
             OciManifest::delete(&self.conn, manifest.id())?;
         }
 
-        Ok(true)
+        let mut orphaned = Vec::new();
+        for digest in blob_digests {
+            if !OciLayer::is_referenced(&self.conn, &digest)?
+                && !OciManifest::is_config_referenced(&self.conn, &digest)?
+            {
+                orphaned.push(digest);
+            }
+        }
+        Ok(orphaned)
+    }
+
+    /// Removes layer blobs, content included, from the content store,
+    /// logging any failures.
+    async fn remove_blobs(&self, digests: &[String]) {
+        for layer_digest in digests {
+            if let Err(e) = blob::remove(self.state_info.store_dir(), layer_digest).await {
+                tracing::warn!(layer = %layer_digest, "Failed to remove orphaned layer from cache: {e}");
+            }
+        }
     }
 
     /// Search for known packages by query string.
@@ -878,6 +1558,11 @@ impl Store {
         RawKnownPackage::search(&self.conn, query, offset, limit)
     }
 
+    /// Count the known packages matching `query`.
+    pub(crate) fn count_search_known_packages(&self, query: &str) -> anyhow::Result<u64> {
+        RawKnownPackage::count_search(&self.conn, query)
+    }
+
     /// Search for known packages that import a given interface.
     pub(crate) fn search_known_packages_by_import(
         &self,
@@ -907,6 +1592,11 @@ impl Store {
         RawKnownPackage::get_all(&self.conn, offset, limit)
     }
 
+    /// Count all known packages.
+    pub(crate) fn count_known_packages(&self) -> anyhow::Result<u64> {
+        RawKnownPackage::count(&self.conn)
+    }
+
     /// Get recently updated known packages.
     pub(crate) fn list_recent_known_packages(
         &self,
@@ -925,6 +1615,29 @@ impl Store {
         RawKnownPackage::get(&self.conn, registry, repository)
     }
 
+    /// Record a repository's human-readable description.
+    pub(crate) fn set_repository_description(
+        &self,
+        registry: &str,
+        repository: &str,
+        description: &str,
+    ) -> anyhow::Result<()> {
+        let repo_id = OciRepository::upsert(&self.conn, registry, repository)?;
+        OciRepository::set_description(&self.conn, repo_id, description)
+    }
+
+    /// Pin or unpin a repository, recording it as a known package first if
+    /// needed.
+    pub(crate) fn set_repository_pinned(
+        &self,
+        registry: &str,
+        repository: &str,
+        pinned: bool,
+    ) -> anyhow::Result<()> {
+        let repo_id = OciRepository::upsert(&self.conn, registry, repository)?;
+        OciRepository::set_pinned(&self.conn, repo_id, pinned)
+    }
+
     /// Add or update a known package.
     pub(crate) fn add_known_package(
         &self,
@@ -981,6 +1694,7 @@ impl Store {
                 rusqlite::params![registry, repository, tag, max_age],
                 |row| row.get(0),
             )
+            .inspect_err(|e| tracing::warn!("Failed to check tag freshness: {e}"))
             .ok();
 
         fresh.unwrap_or(false)
@@ -1225,7 +1939,7 @@ impl Store {
 
         let mut stmt = self
             .conn
-            .prepare("SELECT status, COUNT(*) FROM fetch_queue GROUP BY status")?;
+            .prepare_cached("SELECT status, COUNT(*) FROM fetch_queue GROUP BY status")?;
         let rows = stmt.query_map([], |row| {
             Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
         })?;
@@ -1242,7 +1956,7 @@ impl Store {
         }
 
         // Active tasks: pending + in_progress, ordered by priority then age.
-        let mut active_stmt = self.conn.prepare(
+        let mut active_stmt = self.conn.prepare_cached(
             "SELECT registry, repository, tag, task, status,
                     priority, attempts, max_attempts, last_error,
                     created_at, updated_at
@@ -1272,7 +1986,7 @@ impl Store {
             .collect::<Result<Vec<_>, _>>()?;
 
         // History: completed + failed, most recent first, capped at 50.
-        let mut history_stmt = self.conn.prepare(
+        let mut history_stmt = self.conn.prepare_cached(
             "SELECT registry, repository, tag, task, status,
                     priority, attempts, max_attempts, last_error,
                     created_at, updated_at
@@ -1359,7 +2073,7 @@ impl Store {
 
         // Read bytes from cacache.
         let store_dir = self.state_info.store_dir().to_path_buf();
-        let bytes = cacache::read(&store_dir, &digest).await?;
+        let bytes = blob::read(&store_dir, &digest).await?;
 
         // Delete old WIT data, then re-extract from the cached layer bytes.
         // The DELETEs above clear any stale rows for this manifest; the
@@ -1427,6 +2141,71 @@ impl Store {
         RawWitPackage::get_all_with_images(&self.conn)
     }
 
+    /// Full-text search over stored WIT text.
+    pub(crate) fn search_wit_packages(
+        &self,
+        query: &str,
+        offset: u32,
+        limit: u32,
+    ) -> anyhow::Result<Vec<(RawWitPackage, String)>> {
+        RawWitPackage::search_wit_text(&self.conn, query, offset, limit)
+    }
+
+    /// The size of the blob content on disk right now, in bytes.
+    pub(crate) async fn content_size(&self) -> u64 {
+        dir_size(&self.state_info.store_dir().join("content-v2")).await
+    }
+
+    /// Check every layer row against the blob store, and look for orphaned
+    /// WIT rows and unreferenced blobs.
+    ///
+    /// When `repair` is set, broken images, orphaned rows, and stray blobs
+    /// are removed while holding the store lock.
+    pub(crate) async fn fsck(&self, repair: bool) -> anyhow::Result<FsckReport> {
+        let _lock = if repair {
+            Some(self.lock().await?)
+        } else {
+            None
+        };
+        fsck::run(&self.conn, self.state_info.store_dir(), repair).await
+    }
+
+    /// Read the cumulative pull statistics.
+    pub(crate) fn pull_metrics(&self) -> anyhow::Result<PullMetrics> {
+        PullMetrics::query(&self.conn)
+    }
+
+    /// Add one completed pull to the pull statistics.
+    pub(crate) fn record_pull(
+        &self,
+        cache_hit: bool,
+        bytes_downloaded: u64,
+        elapsed: std::time::Duration,
+    ) -> anyhow::Result<()> {
+        PullMetrics::record(&self.conn, cache_hit, bytes_downloaded, elapsed)
+    }
+
+    /// Compute layer deduplication statistics for the content store.
+    pub(crate) fn dedup_stats(&self) -> anyhow::Result<DedupStats> {
+        DedupStats::query(&self.conn)
+    }
+
+    /// Compute store-wide statistics.
+    pub(crate) fn store_stats(&self) -> anyhow::Result<super::StoreStats> {
+        super::StoreStats::query(&self.conn, self.state_info.store_dir())
+    }
+
+    /// Find stored components whose target world exports the given package,
+    /// optionally narrowed to one interface and version.
+    pub(crate) fn find_exporters(
+        &self,
+        package: &str,
+        interface: Option<&str>,
+        version: Option<&str>,
+    ) -> anyhow::Result<Vec<ComponentExporter>> {
+        ComponentExporter::find(&self.conn, package, interface, version)
+    }
+
     /// Find the OCI reference for a WIT package by name and optional version.
     pub(crate) fn find_oci_reference_by_wit_name(
         &self,
@@ -1449,7 +2228,7 @@ impl Store {
     pub(crate) fn get_sync_meta(&self, key: &str) -> anyhow::Result<Option<String>> {
         let mut stmt = self
             .conn
-            .prepare("SELECT value FROM _sync_meta WHERE key = ?1")?;
+            .prepare_cached("SELECT value FROM _sync_meta WHERE key = ?1")?;
         let mut rows = stmt.query_map([key], |row| row.get::<_, String>(0))?;
         match rows.next() {
             Some(row) => Ok(Some(row?)),
@@ -1488,7 +2267,7 @@ impl Store {
         registry: &str,
         repository: &str,
     ) -> anyhow::Result<Vec<component_meta_registry_types::PackageDependencyRef>> {
-        let mut stmt = self.conn.prepare(
+        let mut stmt = self.conn.prepare_cached(
             "SELECT DISTINCT wpd.declared_package, wpd.declared_version
              FROM wit_package_dependency wpd
              JOIN wit_package wp ON wpd.dependent_id = wp.id
@@ -1546,7 +2325,7 @@ impl Store {
         package_name: &str,
         version: Option<&str>,
     ) -> anyhow::Result<Vec<component_meta_registry_types::PackageDependencyRef>> {
-        let mut stmt = self.conn.prepare(
+        let mut stmt = self.conn.prepare_cached(
             "SELECT wpd.declared_package, wpd.declared_version
              FROM wit_package_dependency wpd
              WHERE wpd.dependent_id = (
@@ -1582,7 +2361,7 @@ impl Store {
         &self,
         package_name: &str,
     ) -> anyhow::Result<Vec<String>> {
-        let mut stmt = self.conn.prepare(
+        let mut stmt = self.conn.prepare_cached(
             "SELECT DISTINCT version
              FROM wit_package
              WHERE package_name = ?1
@@ -1704,7 +2483,7 @@ impl Store {
         };
 
         // Fetch all manifests for this repository, newest first.
-        let mut manifest_stmt = self.conn.prepare(
+        let mut manifest_stmt = self.conn.prepare_cached(
             "SELECT id, digest, size_bytes, created_at,
                     oci_created, oci_authors, oci_url, oci_documentation,
                     oci_source, oci_version, oci_revision, oci_vendor,
@@ -1959,7 +2738,7 @@ impl Store {
         // Find all wit_package rows for this manifest.
         let mut pkg_stmt = self
             .conn
-            .prepare("SELECT id FROM wit_package WHERE oci_manifest_id = ?1")?;
+            .prepare_cached("SELECT id FROM wit_package WHERE oci_manifest_id = ?1")?;
         let pkg_ids: Vec<i64> = pkg_stmt
             .query_map([manifest_id], |row| row.get(0))?
             .collect::<Result<Vec<_>, _>>()?;
@@ -1997,7 +2776,7 @@ impl Store {
         use component_meta_registry_types::{ComponentSummary, ComponentTargetRef};
         type ComponentRow = (i64, Option<String>, Option<String>, Option<String>);
 
-        let mut stmt = self.conn.prepare(
+        let mut stmt = self.conn.prepare_cached(
             "SELECT id, name, description, producers_json FROM wasm_component
              WHERE oci_manifest_id = ?1
              ORDER BY name ASC",
@@ -2082,7 +2861,7 @@ impl Store {
         &self,
         manifest_id: i64,
     ) -> anyhow::Result<Vec<component_meta_registry_types::PackageDependencyRef>> {
-        let mut stmt = self.conn.prepare(
+        let mut stmt = self.conn.prepare_cached(
             "SELECT DISTINCT wpd.declared_package, wpd.declared_version
              FROM wit_package_dependency wpd
              JOIN wit_package wp ON wpd.dependent_id = wp.id
@@ -2107,7 +2886,7 @@ impl Store {
         &self,
         manifest_id: i64,
     ) -> anyhow::Result<Vec<component_meta_registry_types::ReferrerSummary>> {
-        let mut stmt = self.conn.prepare(
+        let mut stmt = self.conn.prepare_cached(
             "SELECT r.artifact_type, m.digest
              FROM oci_referrer r
              JOIN oci_manifest m ON r.referrer_manifest_id = m.id
@@ -2301,7 +3080,7 @@ impl Store {
         &self,
         manifest_id: i64,
     ) -> anyhow::Result<Vec<component_meta_registry_types::AnnotationEntry>> {
-        let mut stmt = self.conn.prepare(
+        let mut stmt = self.conn.prepare_cached(
             "SELECT `key`, `value` FROM oci_manifest_annotation
              WHERE oci_manifest_id = ?1
              ORDER BY `key` ASC",
@@ -2326,7 +3105,7 @@ impl Store {
         &self,
         world_id: i64,
     ) -> anyhow::Result<Vec<component_meta_registry_types::WitInterfaceRef>> {
-        let mut stmt = self.conn.prepare(
+        let mut stmt = self.conn.prepare_cached(
             "SELECT declared_package, declared_interface, declared_version
              FROM wit_world_import
              WHERE wit_world_id = ?1
@@ -2355,7 +3134,7 @@ impl Store {
         &self,
         world_id: i64,
     ) -> anyhow::Result<Vec<component_meta_registry_types::WitInterfaceRef>> {
-        let mut stmt = self.conn.prepare(
+        let mut stmt = self.conn.prepare_cached(
             "SELECT declared_package, declared_interface, declared_version
              FROM wit_world_export
              WHERE wit_world_id = ?1
@@ -4087,4 +4866,340 @@ mod tests {
         let store = Store::from_conn(conn);
         assert!(!store.is_tag_fresh("ghcr.io", "test/pkg", "0.1.0", 3600));
     }
+
+    #[test]
+    fn transaction_rolls_back_on_error() {
+        let store = Store::from_conn(setup_test_db());
+
+        let result: anyhow::Result<()> = store.transaction(|| {
+            store.set_sync_meta("key", "value")?;
+            anyhow::bail!("abort");
+        });
+        assert!(result.is_err());
+        assert_eq!(store.get_sync_meta("key").unwrap(), None);
+
+        store
+            .transaction(|| store.set_sync_meta("key", "value"))
+            .unwrap();
+        assert_eq!(
+            store.get_sync_meta("key").unwrap().as_deref(),
+            Some("value")
+        );
+    }
+
+    #[tokio::test]
+    async fn concurrent_writers_wait_for_the_lock() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = Store::open_at(dir.path()).await.unwrap();
+        let second = Store::open_at(dir.path()).await.unwrap();
+
+        // Hold the write lock in one store while the other tries to write.
+        let (locked_tx, locked_rx) = std::sync::mpsc::channel();
+        let holder = std::thread::spawn(move || {
+            first
+                .transaction(|| {
+                    first.set_sync_meta("writer", "first")?;
+                    locked_tx.send(()).unwrap();
+                    std::thread::sleep(std::time::Duration::from_millis(200));
+                    Ok(())
+                })
+                .unwrap();
+        });
+        locked_rx.recv().unwrap();
+
+        second.set_sync_meta("writer", "second").unwrap();
+        holder.join().unwrap();
+        assert_eq!(
+            second.get_sync_meta("writer").unwrap().as_deref(),
+            Some("second")
+        );
+    }
+
+    /// Store a tagged image `registry/repository:tag` with the given layers.
+    fn insert_tagged_image(conn: &Connection, repository: &str, tag: &str, layers: &[&str]) {
+        let repo_id = OciRepository::upsert(conn, "ghcr.io", repository).unwrap();
+        let digest = format!("sha256:{}-{tag}", repository.replace('/', "-"));
+        let (manifest_id, _) = OciManifest::upsert(
+            conn,
+            repo_id,
+            &digest,
+            None,
+            Some("{}"),
+            None,
+            None,
+            None,
+            None,
+            &HashMap::new(),
+        )
+        .unwrap();
+        OciTag::upsert(conn, repo_id, tag, &digest).unwrap();
+        for (position, layer) in (0i32..).zip(layers) {
+            OciLayer::insert(conn, manifest_id, layer, None, None, position).unwrap();
+        }
+    }
+
+    #[test]
+    fn delete_manifest_rows_keeps_layers_shared_across_repositories() {
+        let store = Store::from_conn(setup_test_db());
+        insert_tagged_image(
+            &store.conn,
+            "example/a",
+            "1.0",
+            &["sha256:shared", "sha256:a"],
+        );
+        insert_tagged_image(&store.conn, "example/b", "1.0", &["sha256:shared"]);
+
+        let repo = OciRepository::find(&store.conn, "ghcr.io", "example/a")
+            .unwrap()
+            .unwrap();
+        let manifests = OciManifest::list_by_repository(&store.conn, repo.id()).unwrap();
+        let orphaned = store
+            .transaction(|| store.delete_manifest_rows(&manifests))
+            .unwrap();
+        assert_eq!(orphaned, vec!["sha256:a".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn bulk_deletes_return_counts() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::open_at(dir.path()).await.unwrap();
+        insert_tagged_image(&store.conn, "example/a", "1.0", &["sha256:a1"]);
+        insert_tagged_image(&store.conn, "example/a", "2.0", &["sha256:a2"]);
+        insert_tagged_image(&store.conn, "example/b", "1.0", &["sha256:b1"]);
+        insert_tagged_image(&store.conn, "other/c", "1.0", &["sha256:c1"]);
+
+        assert_eq!(
+            store
+                .delete_matching("ghcr.io/example/*:1.*")
+                .await
+                .unwrap(),
+            2
+        );
+        assert_eq!(store.delete_matching("docker.io/*").await.unwrap(), 0);
+
+        store
+            .set_repository_pinned("ghcr.io", "other/c", true)
+            .unwrap();
+        assert_eq!(store.delete_matching("ghcr.io/other/*").await.unwrap(), 0);
+
+        assert_eq!(
+            store
+                .delete_by_digest_prefix("sha256:example-a")
+                .await
+                .unwrap(),
+            1
+        );
+
+        assert_eq!(
+            store.delete_repository("ghcr.io", "other/c").await.unwrap(),
+            1
+        );
+        assert!(store.list_all().unwrap().is_empty());
+    }
+
+    #[test]
+    fn wit_extraction_emits_event() {
+        // Skipped when the sample component hasn't been built.
+        let Some(bytes) = read_sample_component() else {
+            return;
+        };
+
+        let conn = setup_test_db();
+        let manifest_id = insert_test_manifest(&conn);
+        let store = Store::from_conn(conn);
+        let mut events = store.subscribe();
+
+        assert!(matches!(
+            store.try_extract_wit_package(manifest_id, None, &bytes),
+            WitExtractOutcome::Extracted
+        ));
+        assert!(matches!(
+            events.try_recv(),
+            Ok(StoreEvent::WitExtracted { package }) if !package.is_empty()
+        ));
+    }
+
+    #[tokio::test]
+    async fn rescan_extracts_wit_for_unindexed_images() {
+        let conn = setup_test_db();
+        let manifest_id = insert_test_manifest(&conn);
+        let store = Store::from_conn(conn);
+
+        // A layer that isn't a component is skipped without error.
+        let store_dir = store.state_info.store_dir().to_path_buf();
+        OciLayer::insert(&store.conn, manifest_id, "sha256:not-wasm", None, None, 0).unwrap();
+        cacache::write(&store_dir, "sha256:not-wasm", b"not wasm")
+            .await
+            .unwrap();
+        assert_eq!(store.rescan_wit_packages().await.unwrap(), 0);
+
+        // Skipped when the sample component hasn't been built.
+        let Some(bytes) = read_sample_component() else {
+            return;
+        };
+        store.conn.execute("DELETE FROM oci_layer", []).unwrap();
+        OciLayer::insert(&store.conn, manifest_id, "sha256:sample", None, None, 0).unwrap();
+        cacache::write(&store_dir, "sha256:sample", &bytes)
+            .await
+            .unwrap();
+        assert!(store.list_wit_packages().unwrap().is_empty());
+        assert_eq!(store.rescan_wit_packages().await.unwrap(), 1);
+        assert_eq!(store.list_wit_packages().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn insert_rejects_mismatched_pinned_digest() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::open_at(dir.path()).await.unwrap();
+        let reference = Reference::with_digest(
+            "ghcr.io".to_owned(),
+            "example/a".to_owned(),
+            "sha256:aaa".to_owned(),
+        );
+        let image = ImageData {
+            layers: Vec::new(),
+            digest: Some("sha256:bbb".to_owned()),
+            config: oci_client::client::Config::oci_v1(Vec::new(), None),
+            manifest: None,
+        };
+
+        let err = store.insert(&reference, image).await.unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<crate::oci::OciLayerError>(),
+            Some(crate::oci::OciLayerError::DigestMismatch { .. })
+        ));
+        assert!(store.list_all().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn failed_insert_leaves_no_rows_or_blobs() {
+        use oci_client::client::{Config, ImageLayer};
+
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::open_at(dir.path()).await.unwrap();
+        store
+            .conn
+            .execute_batch(
+                "CREATE TEMP TRIGGER fail_layer BEFORE INSERT ON oci_layer
+                 BEGIN SELECT RAISE(ABORT, 'disk full'); END;",
+            )
+            .unwrap();
+
+        let layer = ImageLayer::new(
+            b"\0asm\x0d\0\x01\0".to_vec(),
+            "application/wasm".to_owned(),
+            None,
+        );
+        let config = Config::oci_v1(b"{}".to_vec(), None);
+        let manifest = OciImageManifest::build(std::slice::from_ref(&layer), &config, None);
+        let layer_digest = manifest.layers.first().unwrap().digest.clone();
+        let reference: Reference = "ghcr.io/example/a:1.0".parse().unwrap();
+        let image = || ImageData {
+            layers: vec![layer.clone()],
+            digest: Some("sha256:aaa".to_owned()),
+            config: config.clone(),
+            manifest: Some(manifest.clone()),
+        };
+
+        assert!(store.insert(&reference, image()).await.is_err());
+        assert!(store.list_all().unwrap().is_empty());
+        let cache = store.state_info.store_dir();
+        assert!(
+            cacache::metadata(&cache, &layer_digest)
+                .await
+                .unwrap()
+                .is_none()
+        );
+        assert!(!cacache::exists(&cache, &cacache::Integrity::from(layer.data.as_ref())).await);
+
+        store.conn.execute_batch("DROP TRIGGER fail_layer").unwrap();
+        store.insert(&reference, image()).await.unwrap();
+        assert_eq!(store.list_all().unwrap().len(), 1);
+        assert_eq!(
+            blob::read(cache, &layer_digest).await.unwrap(),
+            layer.data.to_vec()
+        );
+    }
+
+    #[tokio::test]
+    async fn deletes_remove_blob_content_from_disk() {
+        use oci_client::client::{Config, ImageLayer};
+
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::open_at(dir.path()).await.unwrap();
+        let config = Config::oci_v1(b"{}".to_vec(), None);
+        let image = |data: &[u8]| {
+            let layer = ImageLayer::new(data.to_vec(), "application/wasm".to_owned(), None);
+            let manifest = OciImageManifest::build(std::slice::from_ref(&layer), &config, None);
+            ImageData {
+                layers: vec![layer],
+                digest: Some(format!("sha256:{}", data.len())),
+                config: config.clone(),
+                manifest: Some(manifest),
+            }
+        };
+        let a: Reference = "ghcr.io/example/a:1.0".parse().unwrap();
+        let b: Reference = "ghcr.io/example/b:1.0".parse().unwrap();
+        store
+            .insert(&a, image(b"\0asm\x0d\0\x01\0a"))
+            .await
+            .unwrap();
+        store
+            .insert(&b, image(b"\0asm\x0d\0\x01\0bb"))
+            .await
+            .unwrap();
+        let full = store.content_size().await;
+        assert!(full > 0);
+
+        let policy = PrunePolicy {
+            max_size: Some(0),
+            ..PrunePolicy::default()
+        };
+        store
+            .set_repository_pinned("ghcr.io", "example/b", true)
+            .unwrap();
+        assert_eq!(store.prune(&policy).await.unwrap().len(), 1);
+        let pruned = store.content_size().await;
+        assert!(pruned < full, "{pruned} >= {full}");
+
+        store
+            .set_repository_pinned("ghcr.io", "example/b", false)
+            .unwrap();
+        assert_eq!(store.delete_matching("*").await.unwrap(), 1);
+        assert_eq!(store.content_size().await, 0);
+    }
+
+    #[tokio::test]
+    async fn insert_compresses_layers() {
+        use oci_client::client::{Config, ImageLayer};
+
+        let dir = tempfile::tempdir().unwrap();
+        let store = Store::open_at(dir.path()).await.unwrap();
+
+        let mut wasm = b"\0asm\x0d\0\x01\0".to_vec();
+        wasm.resize(64 * 1024, 0);
+        let layer = ImageLayer::new(wasm.clone(), "application/wasm".to_owned(), None);
+        let config = Config::oci_v1(b"{}".to_vec(), None);
+        let manifest = OciImageManifest::build(std::slice::from_ref(&layer), &config, None);
+        let layer_digest = manifest.layers.first().unwrap().digest.clone();
+        let reference: Reference = "ghcr.io/example/a:1.0".parse().unwrap();
+        let image = ImageData {
+            layers: vec![layer],
+            digest: None,
+            config,
+            manifest: Some(manifest),
+        };
+        store.insert(&reference, image).await.unwrap();
+
+        let cache = store.state_info.store_dir();
+        let entry = cacache::metadata(cache, &layer_digest)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(blob::is_compressed(&entry));
+        assert!(entry.size < wasm.len());
+        assert_eq!(blob::logical_size(&entry), wasm.len());
+        assert_eq!(blob::read(cache, &layer_digest).await.unwrap(), wasm);
+        assert!(store.fsck(false).await.unwrap().is_clean());
+    }
 }
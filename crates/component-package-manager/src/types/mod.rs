@@ -7,13 +7,16 @@
 mod detect;
 mod parser;
 mod raw;
+mod surface;
 mod wit_package;
 mod worlds;
 
 pub use detect::is_wit_package;
-pub use parser::DependencyItem;
-pub(crate) use parser::extract_wit_metadata;
-pub use parser::extract_wit_text;
+pub use parser::{
+    DependencyItem, ImportExportItem, WitFile, WitMetadata, WorldMetadata, extract_wit_files,
+    extract_wit_metadata, extract_wit_text,
+};
 pub(crate) use raw::RawWitPackage;
+pub use surface::{FunctionSignature, SurfaceItem, WorldSurface, extract_world_surface};
 pub use wit_package::WitPackage;
 pub use worlds::{WitPackageDependency, WitWorld, WitWorldExport, WitWorldImport};
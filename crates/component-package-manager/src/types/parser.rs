@@ -1,9 +1,11 @@
+use std::path::{Path, PathBuf};
+
 use wit_component::WitPrinter;
 use wit_parser::decoding::{DecodedWasm, decode};
 
 /// An import or export declaration inside a WIT world.
-#[derive(Debug, Clone)]
-pub(crate) struct ImportExportItem {
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ImportExportItem {
     /// The declared package name (e.g. "wasi:http").
     pub package: String,
     /// The declared interface name within the package, if any.
@@ -13,8 +15,8 @@ pub(crate) struct ImportExportItem {
 }
 
 /// Metadata about a single WIT world.
-#[derive(Debug, Clone)]
-pub(crate) struct WorldMetadata {
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct WorldMetadata {
     /// The world name (e.g. "proxy", "command").
     pub name: String,
     /// Import declarations in this world.
@@ -37,7 +39,7 @@ pub(crate) struct WorldMetadata {
 /// assert_eq!(dep.package, "wasi:io");
 /// assert_eq!(dep.version.as_deref(), Some("0.2.0"));
 /// ```
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct DependencyItem {
     /// The declared package name (e.g. "wasi:io").
     pub package: String,
@@ -46,7 +48,11 @@ pub struct DependencyItem {
 }
 
 /// Metadata extracted from a WIT component.
-pub(crate) struct WitMetadata {
+///
+/// This is what the store records about the components and WIT packages it
+/// holds; [`extract_wit_metadata`] produces it for any Wasm bytes.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct WitMetadata {
     /// The WIT package name (e.g. "wasi:http").
     pub package_name: Option<String>,
     /// All worlds declared in this package or component.
@@ -61,7 +67,17 @@ pub(crate) struct WitMetadata {
 
 /// Attempt to extract WIT metadata from wasm component bytes.
 /// Returns `None` if the bytes are not a valid wasm component.
-pub(crate) fn extract_wit_metadata(wasm_bytes: &[u8]) -> Option<WitMetadata> {
+///
+/// # Example
+///
+/// ```
+/// use component_package_manager::types::extract_wit_metadata;
+///
+/// // Invalid bytes produce None.
+/// assert!(extract_wit_metadata(b"not wasm").is_none());
+/// ```
+#[must_use]
+pub fn extract_wit_metadata(wasm_bytes: &[u8]) -> Option<WitMetadata> {
     // Try to decode the wasm bytes as a component
     let decoded = decode(wasm_bytes).ok()?;
 
@@ -270,18 +286,23 @@ fn extract_dependencies(
     deps
 }
 
+/// The primary package of a decoded WIT package or component.
+///
+/// For components this is the package of the component's world.
+fn primary_package(decoded: &DecodedWasm) -> Option<wit_parser::PackageId> {
+    match decoded {
+        DecodedWasm::WitPackage(_, package_id) => Some(*package_id),
+        DecodedWasm::Component(resolve, world_id) => resolve.worlds.get(*world_id)?.package,
+    }
+}
+
 /// Produce well-formed WIT text via `WitPrinter`.
 ///
 /// Works for WIT packages and for components: in both cases the decoded
 /// `Resolve` contains a primary package we can hand to the printer.
 fn wit_printer_text(decoded: &DecodedWasm) -> Option<String> {
-    let (resolve, package_id) = match decoded {
-        DecodedWasm::WitPackage(resolve, package_id) => (resolve, *package_id),
-        DecodedWasm::Component(resolve, world_id) => {
-            let world = resolve.worlds.get(*world_id)?;
-            (resolve, world.package?)
-        }
-    };
+    let resolve = decoded.resolve();
+    let package_id = primary_package(decoded)?;
     let nested: Vec<_> = resolve
         .packages
         .iter()
@@ -293,6 +314,61 @@ fn wit_printer_text(decoded: &DecodedWasm) -> Option<String> {
     Some(printer.output.to_string())
 }
 
+/// A WIT file produced by [`extract_wit_files`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WitFile {
+    /// The file's path, relative to the WIT directory.
+    pub path: PathBuf,
+    /// The file's WIT text.
+    pub contents: String,
+}
+
+/// Decode a binary WIT package or WebAssembly component (`.wasm`) into a WIT
+/// directory, with one file per package.
+///
+/// The primary package is written to `<name>.wit`, and every package it
+/// depends on to `deps/<namespace>-<name>[@<version>].wit`: the layout
+/// `wit-parser` reads a WIT directory in. Each file is printed by
+/// `wit-component`'s [`WitPrinter`].
+///
+/// Returns `None` if the bytes are not a valid WIT package or component.
+///
+/// # Example
+///
+/// ```
+/// use component_package_manager::types::extract_wit_files;
+///
+/// // Invalid bytes produce None.
+/// assert!(extract_wit_files(b"not wasm").is_none());
+/// ```
+#[must_use]
+pub fn extract_wit_files(wasm_bytes: &[u8]) -> Option<Vec<WitFile>> {
+    let decoded = decode(wasm_bytes).ok()?;
+    let resolve = decoded.resolve();
+    let primary = primary_package(&decoded)?;
+
+    let mut files = Vec::new();
+    for (id, package) in &resolve.packages {
+        let mut printer = WitPrinter::default();
+        printer.print(resolve, id, &[]).ok()?;
+        let name = &package.name;
+        let path = if id == primary {
+            PathBuf::from(format!("{}.wit", name.name))
+        } else {
+            let file = match &name.version {
+                Some(version) => format!("{}-{}@{version}.wit", name.namespace, name.name),
+                None => format!("{}-{}.wit", name.namespace, name.name),
+            };
+            Path::new("deps").join(file)
+        };
+        files.push(WitFile {
+            path,
+            contents: printer.output.to_string(),
+        });
+    }
+    Some(files)
+}
+
 /// Generate WIT text representation from decoded component.
 fn generate_wit_text(decoded: &DecodedWasm) -> String {
     use std::fmt::Write as _;
@@ -987,6 +1063,48 @@ mod tests {
         );
     }
 
+    #[test]
+    fn extract_wit_files_writes_dependencies_to_deps() {
+        use wit_parser::Resolve;
+
+        let mut resolve = Resolve::default();
+        resolve
+            .push_str(
+                "dep.wit",
+                "package test:dep@1.0.0;\ninterface greeter { hello: func(); }",
+            )
+            .expect("dependency should parse");
+        let package_id = resolve
+            .push_str(
+                "app.wit",
+                "package test:app;\nworld app { import test:dep/greeter@1.0.0; }",
+            )
+            .expect("package should parse");
+        let wasm_bytes =
+            wit_component::encode(&resolve, package_id).expect("encoding should succeed");
+
+        let files = extract_wit_files(&wasm_bytes).expect("should produce WIT files");
+        let paths: Vec<_> = files.iter().map(|f| f.path.clone()).collect();
+        assert_eq!(
+            paths,
+            [
+                Path::new("deps").join("test-dep@1.0.0.wit"),
+                PathBuf::from("app.wit")
+            ]
+        );
+
+        // The files read back as a WIT directory.
+        let dir = tempfile::tempdir().unwrap();
+        for file in &files {
+            let path = dir.path().join(&file.path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, &file.contents).unwrap();
+        }
+        Resolve::default()
+            .push_dir(dir.path())
+            .expect("produced WIT directory must be valid");
+    }
+
     #[test]
     fn extract_wit_text_returns_none_for_garbage() {
         assert!(extract_wit_text(b"not wasm").is_none());
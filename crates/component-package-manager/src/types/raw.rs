@@ -108,7 +108,7 @@ impl RawWitPackage {
         limit: i64,
     ) -> anyhow::Result<Vec<Self>> {
         let pattern = format!("%{query}%");
-        let mut stmt = conn.prepare(
+        let mut stmt = conn.prepare_cached(
             "SELECT id, package_name, version, description, wit_text,
                     oci_manifest_id, oci_layer_id, created_at
              FROM wit_package
@@ -128,7 +128,7 @@ impl RawWitPackage {
 
     /// Return every WIT package, ordered by name then version.
     pub(crate) fn get_all(conn: &Connection) -> anyhow::Result<Vec<Self>> {
-        let mut stmt = conn.prepare(
+        let mut stmt = conn.prepare_cached(
             "SELECT id, package_name, version, description, wit_text,
                     oci_manifest_id, oci_layer_id, created_at
              FROM wit_package
@@ -148,7 +148,7 @@ impl RawWitPackage {
     ///
     /// Joins through `oci_manifest` → `oci_repository` to build the reference.
     pub(crate) fn get_all_with_images(conn: &Connection) -> anyhow::Result<Vec<(Self, String)>> {
-        let mut stmt = conn.prepare(
+        let mut stmt = conn.prepare_cached(
             "SELECT w.id, w.package_name, w.version, w.description, w.wit_text,
                     w.oci_manifest_id, w.oci_layer_id, w.created_at,
                     r.registry || '/' || r.repository AS reference
@@ -181,6 +181,45 @@ impl RawWitPackage {
         Ok(result)
     }
 
+    /// Full-text search over the stored WIT text of every package that was
+    /// extracted from an OCI artifact.
+    ///
+    /// Each whitespace-separated term in `query` is matched as a prefix, and
+    /// all terms must appear. Results are ordered by relevance and paired
+    /// with the `registry/repository` they were pulled from.
+    pub(crate) fn search_wit_text(
+        conn: &Connection,
+        query: &str,
+        offset: u32,
+        limit: u32,
+    ) -> anyhow::Result<Vec<(Self, String)>> {
+        let Some(fts_query) = fts5_query(query) else {
+            return Ok(Vec::new());
+        };
+        let mut stmt = conn.prepare_cached(
+            "SELECT w.id, w.package_name, w.version, w.description, w.wit_text,
+                    w.oci_manifest_id, w.oci_layer_id, w.created_at,
+                    r.registry || '/' || r.repository AS reference
+             FROM wit_package_fts f
+             JOIN wit_package w ON w.id = f.rowid
+             JOIN oci_manifest m ON w.oci_manifest_id = m.id
+             JOIN oci_repository r ON m.oci_repository_id = r.id
+             WHERE wit_package_fts MATCH ?1
+             ORDER BY f.rank, w.package_name ASC, w.version ASC
+             LIMIT ?2 OFFSET ?3",
+        )?;
+
+        let rows = stmt.query_map(rusqlite::params![fts_query, limit, offset], |row| {
+            Ok((Self::from_row(row)?, row.get::<_, String>(8)?))
+        })?;
+
+        let mut result = Vec::new();
+        for row in rows {
+            result.push(row?);
+        }
+        Ok(result)
+    }
+
     /// Map a `rusqlite::Row` to `Self`.
     fn from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<Self> {
         Ok(Self {
@@ -224,6 +263,23 @@ impl RawWitPackage {
     }
 }
 
+/// Turn free-form user input into an FTS5 query.
+///
+/// Every term is quoted so that WIT punctuation (`:`, `-`, `/`, `@`) is
+/// never interpreted as FTS5 query syntax, and marked as a prefix match.
+/// Returns `None` when the input contains no terms.
+fn fts5_query(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .map(|term| format!("\"{}\"*", term.replace('"', "\"\"")))
+        .collect();
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -492,4 +548,119 @@ mod tests {
         assert_eq!(results[0].0.package_name, "wasi:http");
         assert_eq!(results[0].1, "ghcr.io/webassembly/wasi/http");
     }
+
+    // r[verify db.wit-package.search-wit-text]
+    #[test]
+    fn search_wit_text_matches_identifiers_in_wit() {
+        let conn = setup_test_db();
+
+        let manifest_id = insert_test_manifest(&conn, "ghcr.io", "webassembly/wasi/http");
+        RawWitPackage::insert(
+            &conn,
+            "wasi:http",
+            Some("0.2.0"),
+            None,
+            Some("package wasi:http@0.2.0;\ninterface types {\n  resource incoming-request;\n}"),
+            Some(manifest_id),
+            None,
+        )
+        .unwrap();
+        let manifest_id = insert_test_manifest(&conn, "ghcr.io", "webassembly/wasi/clocks");
+        RawWitPackage::insert(
+            &conn,
+            "wasi:clocks",
+            Some("0.2.0"),
+            None,
+            Some(
+                "package wasi:clocks@0.2.0;\ninterface wall-clock {\n  now: func() -> datetime;\n}",
+            ),
+            Some(manifest_id),
+            None,
+        )
+        .unwrap();
+
+        let results = RawWitPackage::search_wit_text(&conn, "incoming-request", 0, 10).unwrap();
+        let [(pkg, reference)] = results.as_slice() else {
+            panic!("expected exactly one match, got {}", results.len());
+        };
+        assert_eq!(pkg.package_name, "wasi:http");
+        assert_eq!(reference, "ghcr.io/webassembly/wasi/http");
+
+        // Terms are prefix-matched and punctuation is not query syntax.
+        let results = RawWitPackage::search_wit_text(&conn, "wall-cl datetime", 0, 10).unwrap();
+        let [(pkg, _)] = results.as_slice() else {
+            panic!("expected exactly one match, got {}", results.len());
+        };
+        assert_eq!(pkg.package_name, "wasi:clocks");
+
+        let results = RawWitPackage::search_wit_text(&conn, "wasi:", 0, 10).unwrap();
+        assert_eq!(results.len(), 2);
+
+        assert!(
+            RawWitPackage::search_wit_text(&conn, "   ", 0, 10)
+                .unwrap()
+                .is_empty()
+        );
+        assert!(
+            RawWitPackage::search_wit_text(&conn, "nonexistent", 0, 10)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn search_wit_text_tracks_updates_and_deletes() {
+        let conn = setup_test_db();
+
+        let manifest_id = insert_test_manifest(&conn, "ghcr.io", "example/pkg");
+        let id = RawWitPackage::insert(
+            &conn,
+            "example:pkg",
+            None,
+            None,
+            Some("interface old-name {}"),
+            Some(manifest_id),
+            None,
+        )
+        .unwrap();
+
+        conn.execute(
+            "UPDATE wit_package SET wit_text = 'interface new-name {}' WHERE id = ?1",
+            [id],
+        )
+        .unwrap();
+        assert!(
+            RawWitPackage::search_wit_text(&conn, "old", 0, 10)
+                .unwrap()
+                .is_empty()
+        );
+        assert_eq!(
+            RawWitPackage::search_wit_text(&conn, "new", 0, 10)
+                .unwrap()
+                .len(),
+            1
+        );
+
+        conn.execute("DELETE FROM wit_package WHERE id = ?1", [id])
+            .unwrap();
+        assert!(
+            RawWitPackage::search_wit_text(&conn, "new", 0, 10)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn fts5_query_quotes_and_prefixes_terms() {
+        assert_eq!(fts5_query("wasi:http"), Some("\"wasi:http\"*".to_string()));
+        assert_eq!(
+            fts5_query("get  user"),
+            Some("\"get\"* \"user\"*".to_string())
+        );
+        assert_eq!(
+            fts5_query("say \"hi\""),
+            Some("\"say\"* \"\"\"hi\"\"\"*".to_string())
+        );
+        assert_eq!(fts5_query("  "), None);
+    }
 }
@@ -0,0 +1,252 @@
+//! The import and export surface of WIT worlds, with function signatures.
+
+use wit_parser::decoding::{DecodedWasm, decode};
+use wit_parser::{Function, Handle, Resolve, Type, TypeDefKind, World, WorldItem, WorldKey};
+
+/// The imports and exports of a single WIT world.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct WorldSurface {
+    /// The world name (e.g. "proxy", "command").
+    pub world: String,
+    /// What the world imports, in declaration order.
+    pub imports: Vec<SurfaceItem>,
+    /// What the world exports, in declaration order.
+    pub exports: Vec<SurfaceItem>,
+}
+
+/// An interface or freestanding function imported or exported by a world.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SurfaceItem {
+    /// The import or export name, e.g. `wasi:http/handler@0.2.0` for an
+    /// interface or `run` for a freestanding function.
+    pub name: String,
+    /// The functions the item provides.
+    pub functions: Vec<FunctionSignature>,
+}
+
+/// A function and its WIT signature.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct FunctionSignature {
+    /// The function name; resource methods keep their `[method]` prefix.
+    pub name: String,
+    /// The WIT signature, e.g. `func(a: u32) -> string`.
+    pub signature: String,
+}
+
+/// Extract the import and export surface of every world in a component or
+/// binary WIT package.
+///
+/// Components have a single world; WIT packages list each world they declare.
+/// Types a world exports are left out, as they provide no functions.
+///
+/// Returns `None` if the bytes are not a valid WIT package or component.
+///
+/// # Example
+///
+/// ```
+/// use component_package_manager::types::extract_world_surface;
+///
+/// // Invalid bytes produce None.
+/// assert!(extract_world_surface(b"not wasm").is_none());
+/// ```
+#[must_use]
+pub fn extract_world_surface(wasm_bytes: &[u8]) -> Option<Vec<WorldSurface>> {
+    let decoded = decode(wasm_bytes).ok()?;
+    let resolve = decoded.resolve();
+    let worlds: Vec<&World> = match &decoded {
+        DecodedWasm::WitPackage(_, package_id) => resolve
+            .packages
+            .get(*package_id)?
+            .worlds
+            .values()
+            .filter_map(|id| resolve.worlds.get(*id))
+            .collect(),
+        DecodedWasm::Component(_, world_id) => vec![resolve.worlds.get(*world_id)?],
+    };
+    Some(
+        worlds
+            .into_iter()
+            .map(|world| WorldSurface {
+                world: world.name.clone(),
+                imports: surface_items(resolve, &world.imports),
+                exports: surface_items(resolve, &world.exports),
+            })
+            .collect(),
+    )
+}
+
+/// Describe the interfaces and functions among a world's imports or exports.
+fn surface_items<'a>(
+    resolve: &Resolve,
+    items: impl IntoIterator<Item = (&'a WorldKey, &'a WorldItem)>,
+) -> Vec<SurfaceItem> {
+    items
+        .into_iter()
+        .filter_map(|(key, item)| {
+            let functions = match item {
+                WorldItem::Interface { id, .. } => resolve
+                    .interfaces
+                    .get(*id)?
+                    .functions
+                    .values()
+                    .map(|func| signature(resolve, func))
+                    .collect(),
+                WorldItem::Function(func) => vec![signature(resolve, func)],
+                WorldItem::Type { .. } => return None,
+            };
+            Some(SurfaceItem {
+                name: resolve.name_world_key(key),
+                functions,
+            })
+        })
+        .collect()
+}
+
+/// The WIT signature of `func`.
+fn signature(resolve: &Resolve, func: &Function) -> FunctionSignature {
+    let params: Vec<String> = func
+        .params
+        .iter()
+        .map(|param| format!("{}: {}", param.name, type_name(resolve, &param.ty)))
+        .collect();
+    let mut signature = format!("func({})", params.join(", "));
+    if let Some(result) = &func.result {
+        signature = format!("{signature} -> {}", type_name(resolve, result));
+    }
+    FunctionSignature {
+        name: func.name.clone(),
+        signature,
+    }
+}
+
+/// The name of a type as it is written in WIT.
+fn type_name(resolve: &Resolve, ty: &Type) -> String {
+    let id = match ty {
+        Type::Bool => return "bool".into(),
+        Type::U8 => return "u8".into(),
+        Type::U16 => return "u16".into(),
+        Type::U32 => return "u32".into(),
+        Type::U64 => return "u64".into(),
+        Type::S8 => return "s8".into(),
+        Type::S16 => return "s16".into(),
+        Type::S32 => return "s32".into(),
+        Type::S64 => return "s64".into(),
+        Type::F32 => return "f32".into(),
+        Type::F64 => return "f64".into(),
+        Type::Char => return "char".into(),
+        Type::String => return "string".into(),
+        Type::ErrorContext => return "error-context".into(),
+        Type::Id(id) => *id,
+    };
+    let Some(def) = resolve.types.get(id) else {
+        return "_".into();
+    };
+    if let Some(name) = &def.name {
+        return name.clone();
+    }
+    let name = |ty: &Type| type_name(resolve, ty);
+    let optional = |ty: &Option<Type>| ty.as_ref().map_or_else(|| "_".into(), name);
+    match &def.kind {
+        TypeDefKind::Option(ty) => format!("option<{}>", name(ty)),
+        TypeDefKind::Result(result) => match (&result.ok, &result.err) {
+            (None, None) => "result".into(),
+            (ok, None) => format!("result<{}>", optional(ok)),
+            (ok, err) => format!("result<{}, {}>", optional(ok), optional(err)),
+        },
+        TypeDefKind::List(ty) => format!("list<{}>", name(ty)),
+        TypeDefKind::FixedLengthList(ty, len) => format!("list<{}, {len}>", name(ty)),
+        TypeDefKind::Map(key, value) => format!("map<{}, {}>", name(key), name(value)),
+        TypeDefKind::Tuple(tuple) => {
+            let types: Vec<String> = tuple.types.iter().map(name).collect();
+            format!("tuple<{}>", types.join(", "))
+        }
+        TypeDefKind::Future(ty) => match ty {
+            Some(ty) => format!("future<{}>", name(ty)),
+            None => "future".into(),
+        },
+        TypeDefKind::Stream(ty) => match ty {
+            Some(ty) => format!("stream<{}>", name(ty)),
+            None => "stream".into(),
+        },
+        TypeDefKind::Handle(Handle::Own(id)) => name(&Type::Id(*id)),
+        TypeDefKind::Handle(Handle::Borrow(id)) => format!("borrow<{}>", name(&Type::Id(*id))),
+        TypeDefKind::Type(ty) => name(ty),
+        TypeDefKind::Record(_)
+        | TypeDefKind::Resource
+        | TypeDefKind::Flags(_)
+        | TypeDefKind::Variant(_)
+        | TypeDefKind::Enum(_)
+        | TypeDefKind::Unknown => "_".into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encode(wit: &str) -> Vec<u8> {
+        let mut resolve = Resolve::default();
+        resolve
+            .push_str(
+                "dep.wit",
+                "package test:dep@1.0.0;\n\
+                 interface greeter {\n\
+                     resource name { text: func() -> string; }\n\
+                     hello: func(who: borrow<name>, times: option<u32>) -> result<list<string>, string>;\n\
+                 }",
+            )
+            .unwrap();
+        let package = resolve.push_str("app.wit", wit).unwrap();
+        wit_component::encode(&resolve, package).unwrap()
+    }
+
+    #[test]
+    fn lists_interfaces_with_function_signatures() {
+        let bytes = encode(
+            "package test:app;\n\
+             world app {\n\
+                 import test:dep/greeter@1.0.0;\n\
+                 export run: func() -> bool;\n\
+             }",
+        );
+        let worlds = extract_world_surface(&bytes).unwrap();
+        let [world] = worlds.as_slice() else {
+            panic!("expected one world, got {worlds:?}");
+        };
+        assert_eq!(world.world, "app");
+
+        let [import] = world.imports.as_slice() else {
+            panic!("expected one import, got {:?}", world.imports);
+        };
+        assert_eq!(import.name, "test:dep/greeter@1.0.0");
+        assert!(
+            import.functions.contains(&FunctionSignature {
+                name: "hello".into(),
+                signature:
+                    "func(who: borrow<name>, times: option<u32>) -> result<list<string>, string>"
+                        .into(),
+            })
+        );
+        assert!(import.functions.contains(&FunctionSignature {
+            name: "[method]name.text".into(),
+            signature: "func(self: borrow<name>) -> string".into(),
+        }));
+
+        let [export] = world.exports.as_slice() else {
+            panic!("expected one export, got {:?}", world.exports);
+        };
+        assert_eq!(export.name, "run");
+        assert_eq!(
+            export.functions,
+            [FunctionSignature {
+                name: "run".into(),
+                signature: "func() -> bool".into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn returns_none_for_invalid_bytes() {
+        assert!(extract_world_surface(b"not wasm").is_none());
+    }
+}
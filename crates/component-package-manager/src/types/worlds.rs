@@ -56,7 +56,7 @@ impl WitWorld {
         conn: &Connection,
         wit_package_id: i64,
     ) -> anyhow::Result<Vec<Self>> {
-        let mut stmt = conn.prepare(
+        let mut stmt = conn.prepare_cached(
             "SELECT id, wit_package_id, name, description, created_at
              FROM wit_world
              WHERE wit_package_id = ?1
@@ -430,6 +430,8 @@ fn generate_migration_rs_content(root: &Path) -> Result<String> {
     let mut buf = String::new();
 
     // Header
+    buf.push_str("use std::path::{Path, PathBuf};\n");
+    buf.push('\n');
     buf.push_str("use anyhow::Context;\n");
     buf.push_str("use rusqlite::Connection;\n");
     buf.push('\n');
@@ -438,6 +440,8 @@ fn generate_migration_rs_content(root: &Path) -> Result<String> {
     buf.push_str("    version: u32,\n");
     buf.push_str("    name: &'static str,\n");
     buf.push_str("    sql: &'static str,\n");
+    buf.push_str("    /// SQL that reverts `sql`, or `None` if the migration is irreversible.\n");
+    buf.push_str("    down: Option<&'static str>,\n");
     buf.push_str("}\n");
     buf.push('\n');
     buf.push_str("/// All migrations in order. Each migration is run exactly once.\n");
@@ -452,6 +456,20 @@ fn generate_migration_rs_content(root: &Path) -> Result<String> {
             "        sql: include_str!(\"../migrations/{num:02}_{name}.sql\"),"
         )
         .expect("write to String");
+        // Down migrations are optional and hand-written in `migrations/down/`.
+        if migrations_dir
+            .join("down")
+            .join(format!("{num:02}_{name}.sql"))
+            .exists()
+        {
+            writeln!(
+                buf,
+                "        down: Some(include_str!(\"../migrations/down/{num:02}_{name}.sql\")),"
+            )
+            .expect("write to String");
+        } else {
+            writeln!(buf, "        down: None,").expect("write to String");
+        }
         writeln!(buf, "    }},").expect("write to String");
     }
 